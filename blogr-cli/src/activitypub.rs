@@ -0,0 +1,402 @@
+//! ActivityPub delivery
+//!
+//! The actor, WebFinger and outbox documents are static files written by
+//! [`crate::generator::activitypub`]. This module holds what can't be static:
+//! the actor's signing key, the list of followers and the signed requests that
+//! deliver new posts to their inboxes. Everything lives in `.blogr/activitypub/`,
+//! which is never deployed.
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, EncodePublicKey, LineEnding};
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// Directory holding the key, followers and announcement log
+pub fn state_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".blogr").join("activitypub")
+}
+
+/// RSA key the actor signs its requests with
+pub struct ActorKey {
+    key: RsaPrivateKey,
+}
+
+impl ActorKey {
+    /// Load the key from `private.pem`, generating it on first use. The public
+    /// half is published in the actor document, so the file must be kept.
+    pub fn load_or_create(project_root: &Path) -> Result<Self> {
+        let path = state_dir(project_root).join("private.pem");
+        if path.exists() {
+            let pem = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let key = RsaPrivateKey::from_pkcs8_pem(&pem)
+                .with_context(|| format!("Invalid key in {}", path.display()))?;
+            return Ok(Self { key });
+        }
+
+        crate::status!("🔑 Generating ActivityPub key");
+        let key = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048)?;
+        let pem = key.to_pkcs8_pem(LineEnding::LF)?;
+
+        fs::create_dir_all(state_dir(project_root))?;
+        fs::write(&path, pem.as_bytes())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(Self { key })
+    }
+
+    pub fn public_key_pem(&self) -> Result<String> {
+        Ok(RsaPublicKey::from(&self.key).to_public_key_pem(LineEnding::LF)?)
+    }
+
+    fn sign(&self, data: &str) -> String {
+        let signing_key = SigningKey::<Sha256>::new(self.key.clone());
+        BASE64.encode(signing_key.sign(data.as_bytes()).to_bytes())
+    }
+}
+
+/// An account that receives new posts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Follower {
+    /// Actor URL
+    pub actor: String,
+    /// `@user@host`, when added by handle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    /// Where activities are delivered, preferring the server's shared inbox
+    pub inbox: String,
+    pub added: DateTime<Utc>,
+}
+
+/// Followers stored in `followers.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Followers {
+    followers: Vec<Follower>,
+}
+
+impl Followers {
+    pub fn path(project_root: &Path) -> PathBuf {
+        state_dir(project_root).join("followers.json")
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self> {
+        load_json(&Self::path(project_root))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        save_json(&Self::path(project_root), self)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Follower> {
+        self.followers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.followers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.followers.is_empty()
+    }
+
+    /// Add a follower, returning `false` if the actor is already listed
+    pub fn add(&mut self, follower: Follower) -> bool {
+        if self.followers.iter().any(|f| f.actor == follower.actor) {
+            return false;
+        }
+        self.followers.push(follower);
+        true
+    }
+
+    /// Remove a follower by actor URL or handle
+    pub fn remove(&mut self, account: &str) -> Option<Follower> {
+        let account = account.trim_start_matches('@');
+        let index = self.followers.iter().position(|f| {
+            f.actor == account
+                || f.handle.as_deref().map(|h| h.trim_start_matches('@')) == Some(account)
+        })?;
+        Some(self.followers.remove(index))
+    }
+
+    /// Distinct inboxes, so a server's shared inbox gets each activity once
+    pub fn inboxes(&self) -> Vec<&str> {
+        let mut inboxes: Vec<&str> = self.followers.iter().map(|f| f.inbox.as_str()).collect();
+        inboxes.sort_unstable();
+        inboxes.dedup();
+        inboxes
+    }
+}
+
+/// Posts already sent to followers, by slug
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AnnouncedLog {
+    posts: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl AnnouncedLog {
+    pub fn path(project_root: &Path) -> PathBuf {
+        state_dir(project_root).join("announced.json")
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self> {
+        load_json(&Self::path(project_root))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        save_json(&Self::path(project_root), self)
+    }
+
+    pub fn contains(&self, slug: &str) -> bool {
+        self.posts.contains_key(slug)
+    }
+
+    pub fn record(&mut self, slug: &str) {
+        self.posts.insert(slug.to_string(), Utc::now());
+    }
+}
+
+fn load_json<T: Default + for<'de> Deserialize<'de>>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+}
+
+fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(value)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Looks up remote accounts and delivers signed activities
+pub struct ActivityPubClient {
+    client: reqwest::Client,
+}
+
+impl Default for ActivityPubClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActivityPubClient {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent(concat!(
+                "blogr/",
+                env!("CARGO_PKG_VERSION"),
+                " (activitypub)"
+            ))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Resolve `@user@host` (via WebFinger) or an actor URL to a follower
+    pub async fn resolve(&self, account: &str) -> Result<Follower> {
+        let (actor_url, handle) =
+            if account.starts_with("http://") || account.starts_with("https://") {
+                (account.to_string(), None)
+            } else {
+                let handle = account.trim_start_matches('@');
+                let (_, host) = handle.split_once('@').ok_or_else(|| {
+                    anyhow!("Expected @user@host or an actor URL, got '{}'", account)
+                })?;
+                let mut webfinger = Url::parse(&format!("https://{}/.well-known/webfinger", host))?;
+                webfinger
+                    .query_pairs_mut()
+                    .append_pair("resource", &format!("acct:{}", handle));
+
+                let jrd: Value = self
+                    .get_json(webfinger.as_str(), "application/jrd+json")
+                    .await?;
+                let actor = jrd["links"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .find(|link| {
+                        link["rel"] == "self"
+                            && link["type"].as_str().is_some_and(|t| {
+                                t.contains("activity+json") || t.contains("ld+json")
+                            })
+                    })
+                    .and_then(|link| link["href"].as_str())
+                    .ok_or_else(|| anyhow!("{} has no ActivityPub actor", handle))?;
+                (actor.to_string(), Some(format!("@{}", handle)))
+            };
+
+        let actor: Value = self.get_json(&actor_url, ACTIVITY_JSON).await?;
+        let inbox = actor["endpoints"]["sharedInbox"]
+            .as_str()
+            .or_else(|| actor["inbox"].as_str())
+            .ok_or_else(|| anyhow!("{} has no inbox", actor_url))?;
+
+        Ok(Follower {
+            actor: actor["id"].as_str().unwrap_or(&actor_url).to_string(),
+            handle,
+            inbox: inbox.to_string(),
+            added: Utc::now(),
+        })
+    }
+
+    async fn get_json(&self, url: &str, accept: &str) -> Result<Value> {
+        let response = self
+            .client
+            .get(url)
+            .header(reqwest::header::ACCEPT, accept)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("{} returned {}", url, response.status()));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// POST an activity to an inbox, signed with the actor's key
+    pub async fn deliver(
+        &self,
+        inbox: &str,
+        activity: &Value,
+        key: &ActorKey,
+        key_id: &str,
+    ) -> Result<()> {
+        let url = Url::parse(inbox)?;
+        let body = serde_json::to_vec(activity)?;
+        let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let digest = digest_header(&body);
+        let host = host_header(&url);
+        let signature = key.sign(&signing_string(&url, &host, &date, &digest));
+
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, ACTIVITY_JSON)
+            .header(reqwest::header::HOST, &host)
+            .header(reqwest::header::DATE, &date)
+            .header("Digest", &digest)
+            .header(
+                "Signature",
+                format!(
+                    r#"keyId="{}",algorithm="rsa-sha256",headers="(request-target) host date digest",signature="{}""#,
+                    key_id, signature
+                ),
+            )
+            .body(body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(anyhow!("inbox returned {}", response.status()))
+        }
+    }
+}
+
+/// `Digest` header value for a request body
+fn digest_header(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64.encode(Sha256::digest(body)))
+}
+
+fn host_header(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// The text covered by an HTTP signature of a POST request
+fn signing_string(url: &Url, host: &str, date: &str, digest: &str) -> String {
+    let mut target = url.path().to_string();
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+    format!(
+        "(request-target): post {}\nhost: {}\ndate: {}\ndigest: {}",
+        target, host, date, digest
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_string() {
+        let url = Url::parse("https://mastodon.example:8443/inbox?x=1").unwrap();
+        let digest = digest_header(b"{}");
+        assert_eq!(
+            digest,
+            "SHA-256=RBNvo1WzZ4oRRq0W9+hknpT7T8If536DEMBg9hyq/4o="
+        );
+        assert_eq!(
+            signing_string(
+                &url,
+                &host_header(&url),
+                "Tue, 07 May 2024 10:00:00 GMT",
+                &digest
+            ),
+            "(request-target): post /inbox?x=1\nhost: mastodon.example:8443\ndate: Tue, 07 May 2024 10:00:00 GMT\ndigest: SHA-256=RBNvo1WzZ4oRRq0W9+hknpT7T8If536DEMBg9hyq/4o="
+        );
+    }
+
+    #[test]
+    fn test_followers() {
+        let follower = |actor: &str, handle: Option<&str>, inbox: &str| Follower {
+            actor: actor.to_string(),
+            handle: handle.map(str::to_string),
+            inbox: inbox.to_string(),
+            added: Utc::now(),
+        };
+
+        let mut followers = Followers::default();
+        assert!(followers.add(follower(
+            "https://a.example/users/alice",
+            Some("@alice@a.example"),
+            "https://a.example/inbox"
+        )));
+        assert!(followers.add(follower(
+            "https://a.example/users/bob",
+            None,
+            "https://a.example/inbox"
+        )));
+        assert!(!followers.add(follower(
+            "https://a.example/users/bob",
+            None,
+            "https://a.example/users/bob/inbox"
+        )));
+        assert_eq!(followers.inboxes(), vec!["https://a.example/inbox"]);
+
+        assert!(followers.remove("alice@a.example").is_some());
+        assert!(followers.remove("https://a.example/users/bob").is_some());
+        assert!(followers.is_empty());
+    }
+}
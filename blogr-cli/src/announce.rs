@@ -0,0 +1,564 @@
+//! Social announcements
+//!
+//! Composes a short post for each network from a Tera template and publishes
+//! it through the network's API. Mastodon and X build their link preview from
+//! the page's Open Graph tags; Bluesky doesn't, so its card (with the post's
+//! first image) is attached to the record. Announcements are logged in
+//! `.blogr/announcements.json` so a post isn't announced twice.
+
+use crate::config::Config;
+use crate::content::Post;
+use anyhow::{anyhow, Context as _, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use tera::{Context, Tera};
+use url::Url;
+
+/// Largest image attached to a Bluesky card
+const MAX_THUMB_BYTES: usize = 1_000_000;
+
+/// Length X counts for any link
+const X_LINK_LENGTH: usize = 23;
+
+/// A network announcements can be posted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mastodon,
+    Bluesky,
+    X,
+}
+
+impl Network {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Network::Mastodon => "mastodon",
+            Network::Bluesky => "bluesky",
+            Network::X => "x",
+        }
+    }
+
+    /// Environment variable holding the access token or app password
+    pub fn token_var(&self) -> &'static str {
+        match self {
+            Network::Mastodon => "MASTODON_TOKEN",
+            Network::Bluesky => "BLUESKY_APP_PASSWORD",
+            Network::X => "X_ACCESS_TOKEN",
+        }
+    }
+
+    /// Longest post the network accepts
+    pub fn limit(&self) -> usize {
+        match self {
+            Network::Mastodon => 500,
+            Network::Bluesky => 300,
+            Network::X => 280,
+        }
+    }
+
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            Network::Mastodon => "{{ title }}\n\n{{ summary }}\n\n{{ url }}\n\n{{ hashtags }}",
+            Network::Bluesky => "{{ title }}\n\n{{ summary }}\n\n{{ url }}",
+            Network::X => "{{ title }}\n\n{{ summary }}\n\n{{ url }}",
+        }
+    }
+
+    fn template<'a>(&self, config: &'a Config) -> &'a str {
+        let template = match self {
+            Network::Mastodon => &config.announce.mastodon.template,
+            Network::Bluesky => &config.announce.bluesky.template,
+            Network::X => &config.announce.x.template,
+        };
+        template.as_deref().unwrap_or(self.default_template())
+    }
+
+    /// Length of a post as the network counts it
+    fn length(&self, text: &str, url: &str) -> usize {
+        match self {
+            Network::X => {
+                text.replace(url, "").chars().count() + text.matches(url).count() * X_LINK_LENGTH
+            }
+            _ => text.chars().count(),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Network::Mastodon => "Mastodon",
+            Network::Bluesky => "Bluesky",
+            Network::X => "X",
+        })
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "mastodon" => Ok(Network::Mastodon),
+            "bluesky" | "bsky" => Ok(Network::Bluesky),
+            "x" | "twitter" => Ok(Network::X),
+            other => Err(anyhow!(
+                "Unknown network '{}'. Use mastodon, bluesky or x",
+                other
+            )),
+        }
+    }
+}
+
+/// What an announcement is about
+pub struct Announcement {
+    pub title: String,
+    pub summary: String,
+    pub url: String,
+    pub tags: Vec<String>,
+    /// Absolute URL of the post's first image
+    pub image: Option<String>,
+}
+
+impl Announcement {
+    pub fn from_post(config: &Config, post: &Post) -> Self {
+        let url = config.page_url(&format!("posts/{}.html", post.metadata.slug));
+        Self {
+            title: post.metadata.title.clone(),
+            summary: post.metadata.description.clone(),
+            image: first_image(&post.content, &url),
+            tags: post.metadata.tags.clone(),
+            url,
+        }
+    }
+
+    /// Render a network's template, shortening the summary to fit its limit
+    pub fn compose(&self, config: &Config, network: Network) -> Result<String> {
+        let template = network.template(config);
+        let text = self.render(config, template, &self.summary)?;
+        let length = network.length(&text, &self.url);
+        if length <= network.limit() {
+            return Ok(text);
+        }
+
+        let overflow = length - network.limit();
+        let summary = self.summary.trim();
+        let summary_length = summary.chars().count();
+        if summary_length > overflow + 1 {
+            let summary: String = summary
+                .chars()
+                .take(summary_length - overflow - 1)
+                .collect();
+            let text = self.render(config, template, &format!("{}…", summary.trim_end()))?;
+            if network.length(&text, &self.url) <= network.limit() {
+                return Ok(text);
+            }
+        }
+
+        Err(anyhow!(
+            "The {} announcement is {} characters; the limit is {}. Shorten the announce.{}.template",
+            network,
+            length,
+            network.limit(),
+            network.key()
+        ))
+    }
+
+    fn render(&self, config: &Config, template: &str, summary: &str) -> Result<String> {
+        let mut context = Context::new();
+        context.insert("title", &self.title);
+        context.insert("summary", summary);
+        context.insert("url", &self.url);
+        context.insert("tags", &self.tags);
+        context.insert("hashtags", &hashtags(&self.tags));
+        context.insert("blog", &config.blog.title);
+        context.insert("author", &config.blog.author);
+
+        let text = Tera::one_off(template, &context, false).context("Invalid announce template")?;
+        // Empty placeholders (like a missing summary) leave blank paragraphs behind
+        let paragraphs: Vec<&str> = text
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect();
+        Ok(paragraphs.join("\n\n"))
+    }
+}
+
+fn hashtags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| {
+            let tag: String = tag
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect();
+            format!("#{}", tag)
+        })
+        .filter(|tag| tag.len() > 1)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The first markdown image in a post, resolved against its URL
+fn first_image(markdown: &str, page_url: &str) -> Option<String> {
+    let start = markdown.find("![")?;
+    let rest = &markdown[start..];
+    let target = &rest[rest.find("](")? + 2..];
+    let target = &target[..target.find([')', ' '])?];
+    Url::parse(page_url)
+        .ok()?
+        .join(target)
+        .ok()
+        .map(String::from)
+}
+
+/// Where an announcement was posted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Posted {
+    pub at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Announcements made so far, by post slug and then network
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AnnouncementLog {
+    posts: BTreeMap<String, BTreeMap<String, Posted>>,
+}
+
+impl AnnouncementLog {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".blogr").join("announcements.json")
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, slug: &str, network: Network) -> Option<&Posted> {
+        self.posts.get(slug)?.get(network.key())
+    }
+
+    pub fn contains_post(&self, slug: &str) -> bool {
+        self.posts.contains_key(slug)
+    }
+
+    pub fn record(&mut self, slug: &str, network: Option<Network>, url: Option<String>) {
+        let networks = self.posts.entry(slug.to_string()).or_default();
+        if let Some(network) = network {
+            networks.insert(
+                network.key().to_string(),
+                Posted {
+                    at: Utc::now(),
+                    url,
+                },
+            );
+        }
+    }
+}
+
+/// Posts announcements through each network's API
+pub struct AnnounceClient {
+    client: reqwest::Client,
+}
+
+impl Default for AnnounceClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnnounceClient {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("blogr/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Post `text`, returning the URL of the new post when the network gives one
+    pub async fn post(
+        &self,
+        config: &Config,
+        network: Network,
+        token: &str,
+        text: &str,
+        announcement: &Announcement,
+        idempotency_key: &str,
+    ) -> Result<Option<String>> {
+        match network {
+            Network::Mastodon => {
+                self.post_mastodon(config, token, text, idempotency_key)
+                    .await
+            }
+            Network::Bluesky => self.post_bluesky(config, token, text, announcement).await,
+            Network::X => self.post_x(token, text).await,
+        }
+    }
+
+    async fn post_mastodon(
+        &self,
+        config: &Config,
+        token: &str,
+        text: &str,
+        idempotency_key: &str,
+    ) -> Result<Option<String>> {
+        let instance = config
+            .announce
+            .mastodon
+            .instance
+            .as_deref()
+            .ok_or_else(|| anyhow!("Set announce.mastodon.instance in blogr.toml"))?;
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/api/v1/statuses",
+                instance.trim_end_matches('/')
+            ))
+            .bearer_auth(token)
+            // Mastodon drops a repeated request with the same key for an hour
+            .header("Idempotency-Key", idempotency_key)
+            .json(&json!({
+                "status": text,
+                "visibility": config.announce.mastodon.visibility,
+            }))
+            .send()
+            .await?;
+        let status = json_response(response).await?;
+        Ok(status["url"].as_str().map(str::to_string))
+    }
+
+    async fn post_bluesky(
+        &self,
+        config: &Config,
+        password: &str,
+        text: &str,
+        announcement: &Announcement,
+    ) -> Result<Option<String>> {
+        let bluesky = &config.announce.bluesky;
+        let handle = bluesky
+            .handle
+            .as_deref()
+            .ok_or_else(|| anyhow!("Set announce.bluesky.handle in blogr.toml"))?;
+        let pds = bluesky.pds.trim_end_matches('/');
+
+        let session = self
+            .client
+            .post(format!("{}/xrpc/com.atproto.server.createSession", pds))
+            .json(&json!({ "identifier": handle, "password": password }))
+            .send()
+            .await?;
+        let session = json_response(session).await?;
+        let jwt = session["accessJwt"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Bluesky didn't return a session"))?;
+        let did = session["did"].as_str().unwrap_or(handle);
+
+        let mut external = json!({
+            "uri": announcement.url,
+            "title": announcement.title,
+            "description": announcement.summary,
+        });
+        if let Some(image) = &announcement.image {
+            // The card is still useful without its image
+            if let Ok(Some(blob)) = self.upload_thumb(pds, jwt, image).await {
+                external["thumb"] = blob;
+            }
+        }
+
+        let record = json!({
+            "$type": "app.bsky.feed.post",
+            "text": text,
+            "createdAt": Utc::now().to_rfc3339(),
+            "facets": bluesky_facets(text, &announcement.url),
+            "embed": { "$type": "app.bsky.embed.external", "external": external },
+        });
+        let response = self
+            .client
+            .post(format!("{}/xrpc/com.atproto.repo.createRecord", pds))
+            .bearer_auth(jwt)
+            .json(&json!({
+                "repo": did,
+                "collection": "app.bsky.feed.post",
+                "record": record,
+            }))
+            .send()
+            .await?;
+        let created = json_response(response).await?;
+
+        Ok(created["uri"]
+            .as_str()
+            .and_then(|uri| uri.rsplit('/').next())
+            .map(|id| format!("https://bsky.app/profile/{}/post/{}", handle, id)))
+    }
+
+    async fn upload_thumb(&self, pds: &str, jwt: &str, image: &str) -> Result<Option<Value>> {
+        let response = self.client.get(image).send().await?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if !response.status().is_success() || !content_type.starts_with("image/") {
+            return Ok(None);
+        }
+        let bytes = response.bytes().await?;
+        if bytes.len() > MAX_THUMB_BYTES {
+            return Ok(None);
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/xrpc/com.atproto.repo.uploadBlob", pds))
+            .bearer_auth(jwt)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(bytes)
+            .send()
+            .await?;
+        Ok(Some(json_response(response).await?["blob"].clone()))
+    }
+
+    async fn post_x(&self, token: &str, text: &str) -> Result<Option<String>> {
+        let response = self
+            .client
+            .post("https://api.x.com/2/tweets")
+            .bearer_auth(token)
+            .json(&json!({ "text": text }))
+            .send()
+            .await?;
+        let tweet = json_response(response).await?;
+        Ok(tweet["data"]["id"]
+            .as_str()
+            .map(|id| format!("https://x.com/i/status/{}", id)))
+    }
+}
+
+async fn json_response(response: reqwest::Response) -> Result<Value> {
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        let detail = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|body| {
+                ["error", "message", "detail"]
+                    .iter()
+                    .find_map(|key| body[*key].as_str().map(str::to_string))
+            })
+            .unwrap_or(text);
+        return Err(anyhow!("{}: {}", status, detail));
+    }
+    serde_json::from_str(&text).map_err(|e| anyhow!("Unexpected response: {}", e))
+}
+
+/// Bluesky doesn't detect links or hashtags itself; they are marked up with
+/// facets using byte offsets into the text
+fn bluesky_facets(text: &str, url: &str) -> Vec<Value> {
+    let mut facets = Vec::new();
+
+    if let Some(start) = text.find(url) {
+        facets.push(json!({
+            "index": { "byteStart": start, "byteEnd": start + url.len() },
+            "features": [{ "$type": "app.bsky.richtext.facet#link", "uri": url }],
+        }));
+    }
+
+    let mut offset = 0;
+    for word in text.split_inclusive(char::is_whitespace) {
+        let trimmed = word.trim_end();
+        if let Some(tag) = trimmed.strip_prefix('#') {
+            if !tag.is_empty() && tag.chars().all(char::is_alphanumeric) {
+                facets.push(json!({
+                    "index": { "byteStart": offset, "byteEnd": offset + trimmed.len() },
+                    "features": [{ "$type": "app.bsky.richtext.facet#tag", "tag": tag }],
+                }));
+            }
+        }
+        offset += word.len();
+    }
+
+    facets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcement(summary: &str) -> Announcement {
+        Announcement {
+            title: "Hello".to_string(),
+            summary: summary.to_string(),
+            url: "https://blog.example.com/posts/hello.html".to_string(),
+            tags: vec!["rust".to_string(), "open-source".to_string()],
+            image: None,
+        }
+    }
+
+    #[test]
+    fn test_compose() {
+        let config = Config::default();
+
+        assert_eq!(
+            announcement("")
+                .compose(&config, Network::Mastodon)
+                .unwrap(),
+            "Hello\n\nhttps://blog.example.com/posts/hello.html\n\n#rust #opensource"
+        );
+
+        // X counts the link as 23 characters and the summary is cut to fit
+        let text = announcement(&"word ".repeat(100))
+            .compose(&config, Network::X)
+            .unwrap();
+        assert!(Network::X.length(&text, "https://blog.example.com/posts/hello.html") <= 280);
+        assert!(text.contains("…\n\nhttps://"));
+    }
+
+    #[test]
+    fn test_first_image() {
+        assert_eq!(
+            first_image(
+                "Intro\n\n![A diagram](../static/d.png \"Title\")",
+                "https://blog.example.com/posts/hello.html"
+            ),
+            Some("https://blog.example.com/static/d.png".to_string())
+        );
+        assert_eq!(first_image("No images", "https://blog.example.com/"), None);
+    }
+
+    #[test]
+    fn test_bluesky_facets() {
+        let text = "Héllo\n\nhttps://b.example/x\n\n#rust #go";
+        let facets = bluesky_facets(text, "https://b.example/x");
+        assert_eq!(facets[0]["index"]["byteStart"], 8);
+        assert_eq!(facets[0]["index"]["byteEnd"], 27);
+        assert_eq!(facets[1]["index"]["byteStart"], 29);
+        assert_eq!(facets[1]["features"][0]["tag"], "rust");
+        assert_eq!(facets[2]["index"]["byteStart"], 35);
+        assert_eq!(facets[2]["index"]["byteEnd"], 38);
+    }
+}
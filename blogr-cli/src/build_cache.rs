@@ -0,0 +1,241 @@
+//! Content-addressed cache for build artifacts
+//!
+//! Artifacts are stored as `<dir>/<kind>/<first two hex digits>/<hash>`, where
+//! the hash covers the blogr version and everything the artifact depends on.
+//! Entries never go stale, so one cache directory can be shared between
+//! projects, machines and CI runs.
+
+use crate::config::CacheConfig;
+use crate::utils::{Console, Utils};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the cache directory
+pub const CACHE_DIR_VAR: &str = "BLOGR_CACHE_DIR";
+
+/// Entries and bytes stored for one kind of artifact
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct KindStats {
+    pub entries: u64,
+    pub bytes: u64,
+}
+
+pub struct BuildCache {
+    /// `None` when caching is disabled
+    dir: Option<PathBuf>,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+}
+
+impl BuildCache {
+    pub fn new(config: &CacheConfig) -> Self {
+        let dir = config.enabled.then(|| cache_dir(config)).flatten();
+        Self::at(dir)
+    }
+
+    pub fn at(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Hash the inputs an artifact is derived from
+    pub fn key(inputs: &[&[u8]]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        for input in inputs {
+            // Length prefixes keep ("ab", "c") and ("a", "bc") apart
+            hasher.update((input.len() as u64).to_le_bytes());
+            hasher.update(input);
+        }
+        Utils::hex(&hasher.finalize())
+    }
+
+    fn entry_path(&self, kind: &str, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(kind).join(&key[..2]).join(key))
+    }
+
+    /// Return the cached artifact, or create and store it. Failing to read or
+    /// write the cache never fails the build.
+    pub fn get_or_insert_with(
+        &self,
+        kind: &str,
+        key: &str,
+        create: impl FnOnce() -> Result<String>,
+    ) -> Result<String> {
+        let Some(path) = self.entry_path(kind, key) else {
+            return create();
+        };
+
+        if let Ok(cached) = fs::read_to_string(&path) {
+            self.hits.set(self.hits.get() + 1);
+            return Ok(cached);
+        }
+        self.misses.set(self.misses.get() + 1);
+
+        let value = create()?;
+        if let Err(e) = write_atomically(&path, &value) {
+            Console::warn(&format!("Could not write to the build cache: {}", e));
+        }
+        Ok(value)
+    }
+
+    /// Like `get_or_insert_with`, for binary artifacts such as thumbnails
+    pub fn get_or_insert_bytes(
+        &self,
+        kind: &str,
+        key: &str,
+        create: impl FnOnce() -> Result<Vec<u8>>,
+    ) -> Result<Vec<u8>> {
+        let Some(path) = self.entry_path(kind, key) else {
+            return create();
+        };
+
+        if let Ok(cached) = fs::read(&path) {
+            self.hits.set(self.hits.get() + 1);
+            return Ok(cached);
+        }
+        self.misses.set(self.misses.get() + 1);
+
+        let value = create()?;
+        if let Err(e) = write_atomically(&path, &value) {
+            Console::warn(&format!("Could not write to the build cache: {}", e));
+        }
+        Ok(value)
+    }
+
+    /// Hits and misses since the cache was opened
+    pub fn usage(&self) -> (usize, usize) {
+        (self.hits.get(), self.misses.get())
+    }
+
+    /// Entries and size per kind of artifact
+    pub fn stats(&self) -> Result<BTreeMap<String, KindStats>> {
+        let mut stats = BTreeMap::new();
+        let Some(dir) = self.dir.as_ref().filter(|dir| dir.exists()) else {
+            return Ok(stats);
+        };
+
+        for kind in fs::read_dir(dir)? {
+            let kind = kind?;
+            if !kind.file_type()?.is_dir() {
+                continue;
+            }
+            let mut kind_stats = KindStats::default();
+            for entry in walkdir::WalkDir::new(kind.path())
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+            {
+                kind_stats.entries += 1;
+                kind_stats.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+            stats.insert(kind.file_name().to_string_lossy().into_owned(), kind_stats);
+        }
+        Ok(stats)
+    }
+
+    /// Remove cached artifacts, of one kind or all of them. Returns the
+    /// number of entries removed.
+    pub fn clear(&self, kind: Option<&str>) -> Result<u64> {
+        let stats = self.stats()?;
+        let Some(dir) = &self.dir else {
+            return Ok(0);
+        };
+
+        let mut removed = 0;
+        for (name, kind_stats) in stats {
+            if kind.is_some_and(|kind| kind != name) {
+                continue;
+            }
+            let path = dir.join(&name);
+            fs::remove_dir_all(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            removed += kind_stats.entries;
+        }
+        Ok(removed)
+    }
+}
+
+/// Where the cache lives: `BLOGR_CACHE_DIR`, then `build.cache.dir`, then the
+/// user cache directory
+pub fn cache_dir(config: &CacheConfig) -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(CACHE_DIR_VAR).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    if let Some(dir) = &config.dir {
+        return Some(match (dir.strip_prefix("~/"), &home) {
+            (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+            _ => PathBuf::from(dir),
+        });
+    }
+    if let Some(dir) = std::env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join("blogr"));
+    }
+    if let Some(dir) = std::env::var_os("LOCALAPPDATA") {
+        return Some(PathBuf::from(dir).join("blogr").join("cache"));
+    }
+    home.map(|home| PathBuf::from(home).join(".cache").join("blogr"))
+}
+
+/// Write through a temporary file so concurrent builds never read half an entry
+fn write_atomically(path: &Path, value: impl AsRef<[u8]>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&temp, value)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = BuildCache::at(Some(temp_dir.path().to_path_buf()));
+        let key = BuildCache::key(&[b"# Hello"]);
+
+        let first = cache
+            .get_or_insert_with("markdown", &key, || Ok("<h1>Hello</h1>".to_string()))
+            .unwrap();
+        let second = cache
+            .get_or_insert_with("markdown", &key, || panic!("should be cached"))
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(cache.usage(), (1, 1));
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats["markdown"].entries, 1);
+        assert_eq!(stats["markdown"].bytes, 14);
+
+        assert_eq!(cache.clear(Some("markdown")).unwrap(), 1);
+        assert!(cache.stats().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_key() {
+        assert_ne!(
+            BuildCache::key(&[b"ab", b"c"]),
+            BuildCache::key(&[b"a", b"bc"])
+        );
+        assert_eq!(BuildCache::key(&[b"x"]).len(), 64);
+    }
+}
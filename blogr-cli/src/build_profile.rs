@@ -0,0 +1,338 @@
+//! `blogr build --profile-build`
+//!
+//! While profiling, a tracing layer records every span the build opens:
+//! `build` around the whole build, `phase` for each timed step, `page` for
+//! each post, and `markdown`, `templates`, `assets`, `search index` and
+//! `feeds` for kinds of work. The report adds up time per kind (excluding
+//! time spent in nested kinds), lists the phases and the slowest pages, and
+//! writes every span as a Chrome trace for Perfetto, speedscope or
+//! chrome://tracing.
+
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+use serde_json::json;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Spans counted as kinds of work, in report order
+pub const KINDS: [&str; 5] = ["markdown", "templates", "assets", "search index", "feeds"];
+
+/// How many of the slowest pages are listed
+const SLOWEST_PAGES: usize = 10;
+
+/// Where the trace is written, relative to the project root
+pub const TRACE_FILE: &str = ".blogr/build-profile.json";
+
+static SPANS: Mutex<Vec<SpanRecord>> = Mutex::new(Vec::new());
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Small ids for threads, in the order they first open a span
+fn thread_id() -> u64 {
+    THREAD.with(|id| {
+        if id.get() == 0 {
+            id.set(NEXT_THREAD.fetch_add(1, Ordering::Relaxed));
+        }
+        id.get()
+    })
+}
+
+#[derive(Debug, Clone)]
+struct SpanRecord {
+    name: &'static str,
+    /// The span's first field, e.g. the page path
+    label: Option<String>,
+    start: Duration,
+    duration: Duration,
+    /// Time spent in child spans
+    children: Duration,
+    thread: u64,
+}
+
+/// Stored on open spans until they close
+struct Timing {
+    label: Option<String>,
+    started: Instant,
+    children: Duration,
+    thread: u64,
+}
+
+#[derive(Default)]
+struct FirstField(Option<String>);
+
+impl Visit for FirstField {
+    fn record_str(&mut self, _field: &Field, value: &str) {
+        self.0.get_or_insert_with(|| value.to_string());
+    }
+
+    fn record_debug(&mut self, _field: &Field, value: &dyn Debug) {
+        self.0.get_or_insert_with(|| format!("{:?}", value));
+    }
+}
+
+/// Records the spans of a build; added by `logging::init` when profiling
+pub struct ProfileLayer {
+    started: Instant,
+}
+
+impl ProfileLayer {
+    pub fn new() -> Self {
+        SPANS.lock().unwrap().clear();
+        Self {
+            started: Instant::now(),
+        }
+    }
+}
+
+impl<S> Layer<S> for ProfileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut label = FirstField::default();
+        attrs.record(&mut label);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Timing {
+                label: label.0,
+                started: Instant::now(),
+                children: Duration::ZERO,
+                thread: thread_id(),
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(timing) = span.extensions_mut().remove::<Timing>() else {
+            return;
+        };
+        let duration = timing.started.elapsed();
+        if let Some(parent) = span.parent() {
+            if let Some(parent) = parent.extensions_mut().get_mut::<Timing>() {
+                parent.children += duration;
+            }
+        }
+        SPANS.lock().unwrap().push(SpanRecord {
+            name: span.name(),
+            label: timing.label,
+            start: timing.started.duration_since(self.started),
+            duration,
+            children: timing.children,
+            thread: timing.thread,
+        });
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Timed {
+    pub name: String,
+    pub ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BuildProfile {
+    pub total_ms: f64,
+    /// Time per kind of work, then `other`
+    pub kinds: Vec<Timed>,
+    pub phases: Vec<Timed>,
+    pub slowest_pages: Vec<Timed>,
+    pub trace: PathBuf,
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl BuildProfile {
+    /// Summarise the recorded spans and write the trace under `project_root`
+    pub fn finish(project_root: &Path) -> Result<Self> {
+        let spans = std::mem::take(&mut *SPANS.lock().unwrap());
+        let mut profile = summarize(&spans);
+
+        let trace = project_root.join(TRACE_FILE);
+        if let Some(parent) = trace.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&trace, serde_json::to_string(&chrome_trace(&spans))?)
+            .with_context(|| format!("Failed to write {}", trace.display()))?;
+        profile.trace = trace;
+        Ok(profile)
+    }
+
+    pub fn print(&self) {
+        let share = |ms: f64| {
+            if self.total_ms > 0.0 {
+                ms / self.total_ms * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        println!();
+        println!("⏱️  Build profile ({:.0}ms)", self.total_ms);
+        for kind in &self.kinds {
+            println!(
+                "   {:<14} {:>8.1}ms {:>5.1}%",
+                kind.name,
+                kind.ms,
+                share(kind.ms)
+            );
+        }
+
+        println!();
+        println!("📋 Phases:");
+        for phase in &self.phases {
+            println!("   {:>8.1}ms  {}", phase.ms, phase.name);
+        }
+
+        if !self.slowest_pages.is_empty() {
+            println!();
+            println!("🐢 Slowest pages:");
+            for page in &self.slowest_pages {
+                println!("   {:>8.1}ms  {}", page.ms, page.name);
+            }
+        }
+
+        println!();
+        println!("🔥 Trace written to {}", self.trace.display());
+        println!("💡 Open it in https://ui.perfetto.dev or https://www.speedscope.app");
+    }
+}
+
+fn summarize(spans: &[SpanRecord]) -> BuildProfile {
+    let total = spans
+        .iter()
+        .filter(|span| span.name == "build")
+        .map(|span| span.duration)
+        .sum::<Duration>();
+
+    let mut by_kind: BTreeMap<&str, Duration> = BTreeMap::new();
+    for span in spans.iter().filter(|span| KINDS.contains(&span.name)) {
+        *by_kind.entry(span.name).or_default() += span.duration.saturating_sub(span.children);
+    }
+    let counted: Duration = by_kind.values().sum();
+    let mut kinds: Vec<Timed> = KINDS
+        .iter()
+        .map(|kind| Timed {
+            name: kind.to_string(),
+            ms: ms(by_kind.get(kind).copied().unwrap_or_default()),
+        })
+        .collect();
+    kinds.push(Timed {
+        name: "other".to_string(),
+        ms: ms(total.saturating_sub(counted)),
+    });
+
+    let mut phases: Vec<&SpanRecord> = spans.iter().filter(|span| span.name == "phase").collect();
+    phases.sort_by_key(|span| span.start);
+
+    let mut pages: Vec<&SpanRecord> = spans.iter().filter(|span| span.name == "page").collect();
+    pages.sort_by_key(|span| std::cmp::Reverse(span.duration));
+    pages.truncate(SLOWEST_PAGES);
+
+    let timed = |span: &&SpanRecord| Timed {
+        name: span.label.clone().unwrap_or_default(),
+        ms: ms(span.duration),
+    };
+    BuildProfile {
+        total_ms: ms(total),
+        kinds,
+        phases: phases.iter().map(timed).collect(),
+        slowest_pages: pages.iter().map(timed).collect(),
+        trace: PathBuf::new(),
+    }
+}
+
+/// Spans as complete events in the Chrome trace format
+fn chrome_trace(spans: &[SpanRecord]) -> serde_json::Value {
+    let events: Vec<serde_json::Value> = spans
+        .iter()
+        .map(|span| {
+            json!({
+                "name": span.label.as_deref().unwrap_or(span.name),
+                "cat": span.name,
+                "ph": "X",
+                "ts": span.start.as_micros() as u64,
+                "dur": span.duration.as_micros() as u64,
+                "pid": 1,
+                "tid": span.thread,
+            })
+        })
+        .collect();
+    json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(
+        name: &'static str,
+        label: &str,
+        start: u64,
+        duration: u64,
+        children: u64,
+    ) -> SpanRecord {
+        SpanRecord {
+            name,
+            label: (!label.is_empty()).then(|| label.to_string()),
+            start: Duration::from_millis(start),
+            duration: Duration::from_millis(duration),
+            children: Duration::from_millis(children),
+            thread: 1,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let spans = [
+            span("markdown", "", 1, 10, 0),
+            span("templates", "post.html", 11, 5, 0),
+            span("page", "posts/a.html", 1, 16, 15),
+            span("markdown", "", 20, 30, 4),
+            span("templates", "", 22, 4, 0),
+            span("page", "posts/b.html", 20, 35, 30),
+            span("phase", "rendered 2 posts", 2, 58, 51),
+            span("phase", "loaded posts", 0, 2, 0),
+            span("build", "minimal-retro", 0, 100, 62),
+        ];
+        let profile = summarize(&spans);
+
+        assert_eq!(profile.total_ms, 100.0);
+        let kind = |name: &str| profile.kinds.iter().find(|k| k.name == name).unwrap().ms;
+        // Nested templates don't count twice
+        assert_eq!(kind("markdown"), 36.0);
+        assert_eq!(kind("templates"), 9.0);
+        assert_eq!(kind("feeds"), 0.0);
+        assert_eq!(kind("other"), 55.0);
+
+        let names = |list: &[Timed]| list.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&profile.phases), ["loaded posts", "rendered 2 posts"]);
+        assert_eq!(
+            names(&profile.slowest_pages),
+            ["posts/b.html", "posts/a.html"]
+        );
+
+        let trace = chrome_trace(&spans);
+        assert_eq!(trace["traceEvents"].as_array().unwrap().len(), spans.len());
+        assert_eq!(trace["traceEvents"][1]["name"], "post.html");
+        assert_eq!(trace["traceEvents"][1]["ts"], 11000);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::activitypub::{ActivityPubClient, ActorKey, AnnouncedLog, Followers};
+use crate::config::Config;
+use crate::content::PostManager;
+use crate::generator::activitypub::{create_activity, handle, key_id};
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+
+fn load_project() -> Result<(Project, Config)> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+    if !config.activitypub.enabled {
+        return Err(anyhow!(
+            "ActivityPub is not enabled. Set activitypub.enabled = true in blogr.toml"
+        ));
+    }
+    Ok((project, config))
+}
+
+pub async fn handle_followers() -> Result<()> {
+    let (project, config) = load_project()?;
+    let followers = Followers::load(&project.root)?;
+
+    println!("🐘 {}", handle(&config));
+    if followers.is_empty() {
+        println!("No followers yet. Add one with 'blogr activitypub add-follower @user@host'.");
+        return Ok(());
+    }
+
+    for follower in followers.iter() {
+        println!(
+            "  {} ({})",
+            follower.handle.as_deref().unwrap_or(&follower.actor),
+            follower.inbox
+        );
+    }
+    println!("{} follower(s)", followers.len());
+    Ok(())
+}
+
+pub async fn handle_add_follower(account: String) -> Result<()> {
+    let (project, _) = load_project()?;
+    let mut followers = Followers::load(&project.root)?;
+
+    let follower = ActivityPubClient::new().resolve(&account).await?;
+    let name = follower
+        .handle
+        .clone()
+        .unwrap_or_else(|| follower.actor.clone());
+    if !followers.add(follower) {
+        Console::info(&format!("{} is already a follower", name));
+        return Ok(());
+    }
+
+    followers.save(&project.root)?;
+    Console::success(&format!("Added {}", name));
+    Ok(())
+}
+
+pub async fn handle_remove_follower(account: String) -> Result<()> {
+    let (project, _) = load_project()?;
+    let mut followers = Followers::load(&project.root)?;
+
+    let removed = followers
+        .remove(&account)
+        .ok_or_else(|| anyhow!("'{}' is not a follower", account))?;
+    followers.save(&project.root)?;
+    Console::success(&format!(
+        "Removed {}",
+        removed.handle.unwrap_or(removed.actor)
+    ));
+    Ok(())
+}
+
+pub async fn handle_announce(slug: Option<String>, dry_run: bool) -> Result<()> {
+    let (project, config) = load_project()?;
+    announce_posts(&project, &config, slug.as_deref(), dry_run).await
+}
+
+/// Send published posts that haven't been announced yet to every follower
+pub async fn announce_posts(
+    project: &Project,
+    config: &Config,
+    slug: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let first_run = !AnnouncedLog::path(&project.root).exists();
+    let mut log = AnnouncedLog::load(&project.root)?;
+    let followers = Followers::load(&project.root)?;
+
+    let now = Utc::now();
+    let mut posts = PostManager::new(project.posts_dir()).load_all_posts()?;
+    posts.retain(|post| post.is_live(now) && slug.is_none_or(|slug| post.metadata.slug == slug));
+    if let (Some(slug), true) = (slug, posts.is_empty()) {
+        return Err(anyhow!("No published post with slug '{}'", slug));
+    }
+    if slug.is_none() {
+        posts.retain(|post| !log.contains(&post.metadata.slug));
+    }
+    posts.sort_by_key(|post| post.metadata.date);
+
+    if posts.is_empty() {
+        println!("No new posts to announce");
+        return Ok(());
+    }
+
+    // Followers shouldn't get the whole back catalogue the first time
+    if (first_run && slug.is_none()) || followers.is_empty() {
+        if dry_run {
+            println!(
+                "{} post(s) would be marked as announced without sending",
+                posts.len()
+            );
+            return Ok(());
+        }
+        for post in &posts {
+            log.record(&post.metadata.slug);
+        }
+        log.save(&project.root)?;
+        if followers.is_empty() {
+            println!(
+                "No followers yet; marked {} post(s) as announced",
+                posts.len()
+            );
+        } else {
+            println!(
+                "Marked {} existing post(s) as announced; posts published from now on will be sent",
+                posts.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let inboxes = followers.inboxes();
+    if dry_run {
+        for post in &posts {
+            println!("📝 {}", post.metadata.title);
+        }
+        println!(
+            "{} post(s) would be sent to {} inbox(es)",
+            posts.len(),
+            inboxes.len()
+        );
+        return Ok(());
+    }
+
+    let key = ActorKey::load_or_create(&project.root)?;
+    let key_id = key_id(config);
+    let client = ActivityPubClient::new();
+    let (mut sent, mut failed) = (0, 0);
+
+    for post in &posts {
+        let activity = create_activity(config, post);
+        let mut delivered = true;
+        for inbox in &inboxes {
+            if let Err(e) = client.deliver(inbox, &activity, &key, &key_id).await {
+                Console::warn(&format!("Delivery to {} failed: {}", inbox, e));
+                delivered = false;
+            }
+        }
+
+        // Activities have stable IDs, so retrying a partly delivered post is safe
+        if delivered {
+            println!("🐘 {}", post.metadata.title);
+            log.record(&post.metadata.slug);
+            sent += 1;
+        } else {
+            failed += 1;
+        }
+    }
+
+    log.save(&project.root)?;
+    Console::success(&format!(
+        "Announced {} post(s) to {} follower(s)",
+        sent,
+        followers.len()
+    ));
+    if failed > 0 {
+        println!("{} post(s) will be retried next time", failed);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use crate::announce::{AnnounceClient, Announcement, AnnouncementLog, Network};
+use crate::config::Config;
+use crate::content::{Post, PostManager};
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+
+pub async fn handle_announce(
+    slug: String,
+    to: Option<String>,
+    dry_run: bool,
+    force: bool,
+) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+
+    let networks = parse_networks(to.as_deref(), &config)?;
+    if !dry_run {
+        check_tokens(&networks)?;
+    }
+
+    let post = PostManager::new(project.posts_dir())
+        .find_by_slug(&slug)?
+        .ok_or_else(|| anyhow!("Post with slug '{}' not found", slug))?;
+    if !post.is_live(Utc::now()) {
+        return Err(anyhow!(
+            "Post '{}' isn't live yet (status: {}); publish it before announcing it",
+            slug,
+            post.metadata.status
+        ));
+    }
+
+    let mut log = AnnouncementLog::load(&project.root)?;
+    let failed = announce_post(
+        &project, &config, &post, &networks, &mut log, dry_run, force,
+    )
+    .await?;
+
+    if failed > 0 {
+        return Err(anyhow!("Announcing failed on {} network(s)", failed));
+    }
+    Ok(())
+}
+
+/// Announce published posts that haven't been announced yet on the configured
+/// networks. The first run only records the existing posts.
+pub async fn announce_new_posts(project: &Project, config: &Config) -> Result<()> {
+    let networks = parse_networks(None, config)?;
+    check_tokens(&networks)?;
+
+    let first_run = !AnnouncementLog::path(&project.root).exists();
+    let mut log = AnnouncementLog::load(&project.root)?;
+
+    let now = Utc::now();
+    let mut posts = PostManager::new(project.posts_dir()).load_all_posts()?;
+    posts.retain(|post| post.is_live(now) && !log.contains_post(&post.metadata.slug));
+    posts.sort_by_key(|post| post.metadata.date);
+
+    if first_run {
+        for post in &posts {
+            log.record(&post.metadata.slug, None, None);
+        }
+        log.save(&project.root)?;
+        println!(
+            "Marked {} existing post(s) as announced; posts published from now on will be announced",
+            posts.len()
+        );
+        return Ok(());
+    }
+
+    if posts.is_empty() {
+        println!("No new posts to announce");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for post in &posts {
+        failed += announce_post(project, config, post, &networks, &mut log, false, false).await?;
+    }
+    if failed > 0 {
+        return Err(anyhow!("{} announcement(s) failed", failed));
+    }
+    Ok(())
+}
+
+/// Post one announcement per network, returning how many failed
+async fn announce_post(
+    project: &Project,
+    config: &Config,
+    post: &Post,
+    networks: &[Network],
+    log: &mut AnnouncementLog,
+    dry_run: bool,
+    force: bool,
+) -> Result<usize> {
+    let slug = &post.metadata.slug;
+    let announcement = Announcement::from_post(config, post);
+    let client = AnnounceClient::new();
+    let mut failed = 0;
+
+    for &network in networks {
+        if let (Some(posted), false) = (log.get(slug, network), force) {
+            Console::info(&format!(
+                "'{}' was already announced on {}{} (use --force to post again)",
+                slug,
+                network,
+                posted
+                    .url
+                    .as_deref()
+                    .map(|url| format!(": {}", url))
+                    .unwrap_or_default()
+            ));
+            continue;
+        }
+
+        let text = match announcement.compose(config, network) {
+            Ok(text) => text,
+            Err(e) => {
+                Console::error(&format!("{}: {:#}", network, e));
+                failed += 1;
+                continue;
+            }
+        };
+
+        if dry_run {
+            println!("── {} ({} characters) ──", network, text.chars().count());
+            println!("{}\n", text);
+            continue;
+        }
+
+        let token = std::env::var(network.token_var())?;
+        let idempotency_key = format!("blogr-{}-{}", slug, network.key());
+        match client
+            .post(
+                config,
+                network,
+                &token,
+                &text,
+                &announcement,
+                &idempotency_key,
+            )
+            .await
+        {
+            Ok(url) => {
+                Console::success(&format!(
+                    "Announced '{}' on {}{}",
+                    post.metadata.title,
+                    network,
+                    url.as_deref()
+                        .map(|url| format!(": {}", url))
+                        .unwrap_or_default()
+                ));
+                log.record(slug, Some(network), url);
+                log.save(&project.root)?;
+            }
+            Err(e) => {
+                Console::error(&format!("{} failed: {:#}", network, e));
+                failed += 1;
+            }
+        }
+    }
+
+    Ok(failed)
+}
+
+fn parse_networks(to: Option<&str>, config: &Config) -> Result<Vec<Network>> {
+    let names: Vec<&str> = match to {
+        Some(to) => to.split(',').collect(),
+        None => config
+            .announce
+            .networks
+            .iter()
+            .map(String::as_str)
+            .collect(),
+    };
+
+    let mut networks = Vec::new();
+    for name in names.into_iter().filter(|name| !name.trim().is_empty()) {
+        let network: Network = name.parse()?;
+        if !networks.contains(&network) {
+            networks.push(network);
+        }
+    }
+    if networks.is_empty() {
+        return Err(anyhow!(
+            "No networks given. Use --to mastodon,bluesky,x or set announce.networks in blogr.toml"
+        ));
+    }
+    Ok(networks)
+}
+
+fn check_tokens(networks: &[Network]) -> Result<()> {
+    let missing: Vec<&str> = networks
+        .iter()
+        .map(Network::token_var)
+        .filter(|var| std::env::var(var).is_err())
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Missing credentials: set {} (or add it to a .env file in the project)",
+            missing.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,345 @@
+//! `blogr bench`: build timings on a synthetic site
+//!
+//! Generates a blog with a given number of posts and times a cold and a warm
+//! full build, an incremental rebuild after editing one post, the search index
+//! and the feeds. The `--json` report can be kept and passed back with
+//! `--baseline` to see how a later version compares.
+
+use crate::build_cache::CACHE_DIR_VAR;
+use crate::content::{Post, PostStatus};
+use crate::generator::SearchIndexer;
+use crate::generator::SiteBuilder;
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Duration as Days, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const TAGS: [&str; 24] = [
+    "rust",
+    "web",
+    "design",
+    "notes",
+    "travel",
+    "books",
+    "music",
+    "linux",
+    "security",
+    "cooking",
+    "photography",
+    "career",
+    "testing",
+    "databases",
+    "css",
+    "javascript",
+    "productivity",
+    "writing",
+    "hardware",
+    "networking",
+    "science",
+    "history",
+    "games",
+    "open-source",
+];
+
+const WORDS: [&str; 40] = [
+    "the",
+    "build",
+    "static",
+    "site",
+    "quickly",
+    "renders",
+    "every",
+    "page",
+    "with",
+    "a",
+    "theme",
+    "while",
+    "posts",
+    "stay",
+    "in",
+    "plain",
+    "markdown",
+    "files",
+    "that",
+    "are",
+    "easy",
+    "to",
+    "edit",
+    "and",
+    "review",
+    "before",
+    "publishing",
+    "them",
+    "on",
+    "any",
+    "host",
+    "which",
+    "serves",
+    "html",
+    "fast",
+    "cache",
+    "feeds",
+    "search",
+    "index",
+    "readers",
+];
+
+/// Timings of one benchmark, in milliseconds
+#[derive(Debug, Serialize, Deserialize)]
+struct BenchResult {
+    name: String,
+    median_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+}
+
+impl BenchResult {
+    fn new(name: &str, mut times: Vec<Duration>) -> Self {
+        times.sort();
+        let ms = |duration: &Duration| duration.as_secs_f64() * 1000.0;
+        Self {
+            name: name.to_string(),
+            median_ms: ms(&times[times.len() / 2]),
+            min_ms: ms(&times[0]),
+            max_ms: ms(&times[times.len() - 1]),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BenchReport {
+    version: String,
+    posts: usize,
+    runs: usize,
+    results: Vec<BenchResult>,
+}
+
+pub async fn handle_bench(
+    posts: usize,
+    runs: usize,
+    fixture: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+) -> Result<()> {
+    if posts == 0 || runs == 0 {
+        bail!("--posts and --runs must be at least 1");
+    }
+    let baseline = baseline
+        .map(|path| -> Result<BenchReport> {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&text)
+                .with_context(|| format!("{} is not a `blogr bench --json` report", path.display()))
+        })
+        .transpose()?;
+
+    let temp = std::env::temp_dir().join(format!("blogr-bench-{}", uuid::Uuid::new_v4()));
+    let parent = fixture.clone().unwrap_or_else(|| temp.clone());
+    fs::create_dir_all(&parent)?;
+    let result = run(&parent, &temp, posts, runs);
+    // The site itself stays when it was asked for
+    let _ = fs::remove_dir_all(&temp);
+    let results = result?;
+
+    let report = BenchReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        posts,
+        runs,
+        results,
+    };
+    if Console::is_json() {
+        return Console::report("bench", serde_json::to_value(&report)?);
+    }
+
+    println!(
+        "⏱️  blogr {} with {} posts, median of {} run(s)",
+        report.version, posts, runs
+    );
+    for result in &report.results {
+        let change = baseline
+            .as_ref()
+            .and_then(|baseline| baseline.results.iter().find(|old| old.name == result.name))
+            .map(|old| {
+                format!(
+                    "  {:+.1}%",
+                    (result.median_ms - old.median_ms) / old.median_ms * 100.0
+                )
+            })
+            .unwrap_or_default();
+        println!(
+            "  {:<28} {:>10.1} ms  (min {:.1}, max {:.1}){}",
+            result.name, result.median_ms, result.min_ms, result.max_ms, change
+        );
+    }
+    if let Some(baseline) = &baseline {
+        if baseline.posts != posts {
+            Console::warn(&format!(
+                "The baseline was measured with {} posts",
+                baseline.posts
+            ));
+        }
+    }
+    if let Some(fixture) = &fixture {
+        println!("📁 Site kept in {}", fixture.join("bench").display());
+    }
+    Ok(())
+}
+
+fn run(parent: &Path, temp: &Path, posts: usize, runs: usize) -> Result<Vec<BenchResult>> {
+    let project = create_site(parent, posts)?;
+    let config = project.load_config()?;
+    let output_dir = config.output_dir(&project.root);
+
+    // A cache of its own, so cold builds are cold and the user's stays untouched
+    let cache_dir = temp.join("cache");
+    std::env::set_var(CACHE_DIR_VAR, &cache_dir);
+    let builder = || SiteBuilder::new(project.clone(), Some(output_dir.clone()), false, false);
+
+    let mut results = Vec::new();
+    let cold = time(runs, || {
+        let _ = fs::remove_dir_all(&cache_dir);
+        builder()?.build().map(|_| ())
+    })?;
+    results.push(BenchResult::new("full build (cold cache)", cold));
+    let warm = time(runs, || builder()?.build().map(|_| ()))?;
+    results.push(BenchResult::new("full build (warm cache)", warm));
+
+    let full = builder()?;
+    full.build()?;
+    let mut dependencies = full
+        .take_dependencies()
+        .ok_or_else(|| anyhow!("The build recorded no dependencies"))?;
+    let edited = project.posts_dir().join(format!("{}.md", post_slug(0)));
+    let mut edit = 0;
+    let incremental = time(runs, || {
+        edit += 1;
+        let mut post = Post::from_file(&edited)?;
+        post.content
+            .push_str(&format!("\n\nEdit number {}.\n", edit));
+        post.save_to_file(&edited)?;
+        let builder = builder()?;
+        builder
+            .rebuild_posts(&dependencies, std::slice::from_ref(&edited))?
+            .ok_or_else(|| anyhow!("The edit needed a full build"))?;
+        dependencies = builder
+            .take_dependencies()
+            .ok_or_else(|| anyhow!("The rebuild recorded no dependencies"))?;
+        Ok(())
+    })?;
+    results.push(BenchResult::new("incremental build (1 post)", incremental));
+
+    let site = builder()?;
+    let published = site.load_posts()?;
+    let indexer = SearchIndexer::new(config.search.clone()).with_url_config(config.urls.clone());
+    results.push(BenchResult::new(
+        "search index",
+        time(runs, || indexer.generate_index(&published, &output_dir))?,
+    ));
+    results.push(BenchResult::new(
+        "feeds",
+        time(runs, || site.generate_rss_feed(&published))?,
+    ));
+    Ok(results)
+}
+
+/// Time `runs` runs of `f`
+fn time(runs: usize, mut f: impl FnMut() -> Result<()>) -> Result<Vec<Duration>> {
+    (0..runs)
+        .map(|_| {
+            let started = Instant::now();
+            f()?;
+            Ok(started.elapsed())
+        })
+        .collect()
+}
+
+/// A blog with `count` generated posts, in `parent/bench`
+fn create_site(parent: &Path, count: usize) -> Result<Project> {
+    let project = Project::init(
+        parent,
+        "bench".to_string(),
+        "Bench Author".to_string(),
+        "A synthetic site for blogr bench".to_string(),
+        None,
+        None,
+    )?;
+    let posts_dir = project.posts_dir();
+    fs::remove_dir_all(&posts_dir)?;
+    fs::create_dir_all(&posts_dir)?;
+
+    let mut random = Lcg(count as u64);
+    let start = Utc.with_ymd_and_hms(2020, 1, 1, 9, 0, 0).unwrap();
+    for index in 0..count {
+        let mut tags: Vec<String> = (0..1 + random.below(3))
+            .map(|_| TAGS[random.below(TAGS.len())].to_string())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        let mut post = Post::new(
+            format!("Post number {}", index + 1),
+            "Bench Author".to_string(),
+            Some(sentence(&mut random, 12)),
+            tags,
+            Some(post_slug(index)),
+            PostStatus::Published,
+        );
+        post.metadata.date = start + Days::hours(index as i64 * 19);
+        post.content = post_body(&mut random, index);
+        post.save_to_file(posts_dir.join(format!("{}.md", post_slug(index))))?;
+    }
+    Ok(project)
+}
+
+fn post_slug(index: usize) -> String {
+    format!("post-{:05}", index + 1)
+}
+
+/// Markdown with the things real posts have: headings, lists, links, quotes
+/// and highlighted code
+fn post_body(random: &mut Lcg, index: usize) -> String {
+    let mut body = String::new();
+    for section in 0..3 {
+        body.push_str(&format!("## Section {}\n\n", section + 1));
+        for _ in 0..3 {
+            let words = 40 + random.below(40);
+            body.push_str(&sentence(random, words));
+            body.push_str("\n\n");
+        }
+        body.push_str(&format!(
+            "- {}\n- [an earlier post](/posts/{}.html)\n- `inline code`\n\n",
+            sentence(random, 6),
+            post_slug(random.below(index + 1))
+        ));
+    }
+    body.push_str(&format!("> {}\n\n", sentence(random, 20)));
+    body.push_str(&format!(
+        "```rust\nfn post_{}() -> usize {{\n    let words = vec![\"{}\"];\n    words.len()\n}}\n```\n",
+        index,
+        WORDS[random.below(WORDS.len())]
+    ));
+    body
+}
+
+fn sentence(random: &mut Lcg, words: usize) -> String {
+    let text: Vec<&str> = (0..words.max(1))
+        .map(|_| WORDS[random.below(WORDS.len())])
+        .collect();
+    let text = text.join(" ");
+    format!("{}{}.", text[..1].to_uppercase(), &text[1..])
+}
+
+/// Small deterministic generator, so runs with the same `--posts` build the same site
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % bound as u64) as usize
+    }
+}
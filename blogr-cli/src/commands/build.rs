@@ -1,35 +1,72 @@
+use crate::build_profile::BuildProfile;
+use crate::fonts;
 use crate::generator::SiteBuilder;
+use crate::github_projects;
 use crate::project::Project;
 use crate::utils::Console;
 use anyhow::{anyhow, Result};
 use std::path::PathBuf;
 
-pub async fn handle_build(output: Option<PathBuf>, drafts: bool, future: bool) -> Result<()> {
+pub async fn handle_build(
+    output: Option<PathBuf>,
+    drafts: bool,
+    future: bool,
+    profile: Option<String>,
+    profile_build: bool,
+) -> Result<()> {
     Console::info("Building static site...");
 
     // Check if we're in a blogr project
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
+    let config = project.load_config()?;
+    github_projects::refresh(&project.root, &config.github_projects).await;
+    fonts::refresh(&project, &config).await;
+
+    let project_root = project.root.clone();
+
     // Create site builder
-    let site_builder = SiteBuilder::new(project, output, drafts, future)?;
+    let site_builder =
+        SiteBuilder::new(project, output, drafts, future)?.with_profile(profile.clone());
 
     // Build the site
-    site_builder.build()?;
+    let summary = site_builder.build()?;
+    let build_profile = if profile_build {
+        Some(BuildProfile::finish(&project_root)?)
+    } else {
+        None
+    };
+
+    if Console::is_json() {
+        let mut report = serde_json::to_value(&summary)?;
+        report["output_dir"] = site_builder.output_dir().display().to_string().into();
+        report["drafts"] = drafts.into();
+        report["future"] = future.into();
+        report["profile"] = profile.into();
+        if let Some(build_profile) = &build_profile {
+            report["build_profile"] = serde_json::to_value(build_profile)?;
+        }
+        return Console::report("build", report);
+    }
 
     Console::success("Site built successfully!");
-    println!(
+    crate::status!(
         "📦 Built site saved to: {}",
         site_builder.output_dir().display()
     );
-    println!("🌐 Ready for deployment");
+    crate::status!("🌐 Ready for deployment");
 
     // Show what was included
     if drafts {
-        println!("📝 Draft posts included in build");
+        crate::status!("📝 Draft posts included in build");
     }
     if future {
-        println!("🔮 Future-dated posts included in build");
+        crate::status!("🔮 Future-dated posts included in build");
+    }
+
+    if let Some(build_profile) = build_profile {
+        build_profile.print();
     }
 
     Ok(())
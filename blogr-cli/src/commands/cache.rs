@@ -0,0 +1,82 @@
+use crate::build_cache::{cache_dir, BuildCache};
+use crate::config::CacheConfig;
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+
+/// Cache settings of the current project, or the defaults outside one
+fn cache_config() -> Result<CacheConfig> {
+    Ok(match Project::find_project()? {
+        Some(project) => project.load_config()?.build.cache,
+        None => CacheConfig::default(),
+    })
+}
+
+fn open_cache(config: &CacheConfig) -> Result<BuildCache> {
+    let dir = cache_dir(config).ok_or_else(|| {
+        anyhow!("Could not determine the cache directory. Set BLOGR_CACHE_DIR or build.cache.dir")
+    })?;
+    Ok(BuildCache::at(Some(dir)))
+}
+
+pub async fn handle_stats() -> Result<()> {
+    let config = cache_config()?;
+    let cache = open_cache(&config)?;
+    if let Some(dir) = cache.dir() {
+        println!("📦 Build cache: {}", dir.display());
+    }
+    if !config.enabled {
+        Console::warn("Caching is disabled for this project (build.cache.enabled = false)");
+    }
+
+    let stats = cache.stats()?;
+    if stats.is_empty() {
+        println!("The cache is empty");
+        return Ok(());
+    }
+
+    let (mut entries, mut bytes) = (0, 0);
+    for (kind, kind_stats) in &stats {
+        println!(
+            "  {:<12} {:>8} entries  {:>10}",
+            kind,
+            kind_stats.entries,
+            format_size(kind_stats.bytes)
+        );
+        entries += kind_stats.entries;
+        bytes += kind_stats.bytes;
+    }
+    println!(
+        "  {:<12} {:>8} entries  {:>10}",
+        "total",
+        entries,
+        format_size(bytes)
+    );
+    Ok(())
+}
+
+pub async fn handle_clear(kind: Option<String>) -> Result<()> {
+    let cache = open_cache(&cache_config()?)?;
+    let removed = cache.clear(kind.as_deref())?;
+
+    match kind {
+        Some(kind) => Console::success(&format!("Removed {} cached '{}' entries", removed, kind)),
+        None => Console::success(&format!("Removed {} cached entries", removed)),
+    }
+    Ok(())
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
@@ -0,0 +1,261 @@
+use crate::config::VerifyConfig;
+use crate::content::PostManager;
+use crate::generator::manifest::{self, Manifest};
+use crate::project::Project;
+use crate::prose::{self, ProseRule};
+use crate::seo::{self, SeoIssueKind, SeoPage};
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use walkdir::WalkDir;
+
+pub async fn handle_prose(slug: Option<String>, no_spelling: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+    let prose_config = &config.prose;
+
+    let post_manager = PostManager::new(project.posts_dir());
+    let posts = match &slug {
+        Some(slug) => vec![post_manager
+            .find_by_slug(slug)?
+            .ok_or_else(|| anyhow!("Post with slug '{}' not found", slug))?],
+        None => post_manager.load_all_posts()?,
+    };
+    if posts.is_empty() {
+        Console::info("No posts to check");
+        return Ok(());
+    }
+
+    let mut files = Vec::new();
+    for post in &posts {
+        let path = post_manager.post_path(post);
+        let raw = fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        files.push((path, raw));
+    }
+
+    let spelling_disabled = no_spelling
+        || prose_config
+            .disable
+            .iter()
+            .any(|rule| rule.parse::<ProseRule>().ok() == Some(ProseRule::Spelling));
+    let misspelled = if spelling_disabled {
+        HashSet::new()
+    } else {
+        match prose::spell_checker(prose_config) {
+            Some((program, args)) => {
+                let dictionary =
+                    prose::load_dictionary(&project.root.join(&prose_config.dictionary))?;
+                let words: BTreeSet<String> = files
+                    .iter()
+                    .flat_map(|(_, raw)| prose::words(raw))
+                    .filter(|word| !dictionary.contains(&word.to_lowercase()))
+                    .collect();
+                Console::info(&format!(
+                    "Spell-checking {} distinct words with {}",
+                    words.len(),
+                    program
+                ));
+                prose::misspelled_words(&program, &args, &words)?
+            }
+            None => {
+                Console::warn(
+                    "No spell checker found; install aspell or hunspell, or set [prose] spell_checker. Skipping spelling.",
+                );
+                HashSet::new()
+            }
+        }
+    };
+
+    let mut total = 0;
+    let mut reported = Vec::new();
+    for (path, raw) in &files {
+        let findings = prose::check(raw, prose_config, &misspelled);
+        let display = path.strip_prefix(&project.root).unwrap_or(path);
+        total += findings.len();
+        if Console::is_json() {
+            reported.extend(findings.iter().map(|finding| {
+                serde_json::json!({
+                    "file": display.display().to_string(),
+                    "line": finding.line,
+                    "rule": finding.rule.to_string(),
+                    "message": finding.message,
+                })
+            }));
+            continue;
+        }
+        for finding in &findings {
+            println!(
+                "{}:{}: {} ({})",
+                display.display(),
+                finding.line,
+                finding.message,
+                finding.rule
+            );
+        }
+    }
+
+    if Console::is_json() {
+        Console::report(
+            "check prose",
+            serde_json::json!({
+                "status": if total == 0 { "ok" } else { "failed" },
+                "posts": files.len(),
+                "findings": reported,
+            }),
+        )?;
+    }
+
+    if total == 0 {
+        Console::success(&format!(
+            "Checked {} post(s): no problems found",
+            files.len()
+        ));
+        return Ok(());
+    }
+
+    crate::status!();
+    crate::status!(
+        "💡 Add words to {} or silence a line with <!-- prose-ignore -->",
+        prose_config.dictionary
+    );
+    Err(anyhow!(
+        "Prose check found {} problem(s) in {} post(s)",
+        total,
+        files.len()
+    ))
+}
+
+pub async fn handle_seo(min_score: Option<u32>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+    let output_dir = config.output_dir(&project.root);
+    if !output_dir.exists() {
+        return Err(anyhow!(
+            "{} does not exist. Run 'blogr build' first.",
+            output_dir.display()
+        ));
+    }
+
+    let mut pages = Vec::new();
+    for entry in WalkDir::new(&output_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "html"))
+    {
+        let html = fs::read_to_string(entry.path())
+            .map_err(|e| anyhow!("Failed to read {}: {}", entry.path().display(), e))?;
+        let file = entry
+            .path()
+            .strip_prefix(&output_dir)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        pages.push(SeoPage::parse(&file, &html));
+    }
+
+    Console::info(&format!(
+        "Auditing {} page(s) in {}",
+        pages.len(),
+        output_dir.display()
+    ));
+    let report = seo::audit(&pages, &config.blog.base_url);
+
+    let mut by_file: BTreeMap<&str, Vec<&seo::SeoIssue>> = BTreeMap::new();
+    for issue in &report.issues {
+        by_file.entry(issue.file.as_str()).or_default().push(issue);
+    }
+
+    if Console::is_json() {
+        let below_minimum = min_score.is_some_and(|min_score| report.score < min_score);
+        let issues: Vec<serde_json::Value> = report
+            .issues
+            .iter()
+            .map(|issue| {
+                serde_json::json!({
+                    "file": issue.file,
+                    "kind": issue.kind.to_string(),
+                    "message": issue.message,
+                })
+            })
+            .collect();
+        Console::report(
+            "check seo",
+            serde_json::json!({
+                "status": if below_minimum { "failed" } else { "ok" },
+                "score": report.score,
+                "min_score": min_score,
+                "pages": report.pages,
+                "issues": issues,
+            }),
+        )?;
+        if below_minimum {
+            return Err(anyhow!(
+                "SEO score {} is below the minimum of {}",
+                report.score,
+                min_score.unwrap_or_default()
+            ));
+        }
+        return Ok(());
+    }
+    for (file, issues) in &by_file {
+        println!("{}", file);
+        for issue in issues {
+            println!("  ⚠️  {}: {}", issue.kind, issue.message);
+        }
+    }
+
+    if !report.issues.is_empty() {
+        let mut counts: BTreeMap<SeoIssueKind, usize> = BTreeMap::new();
+        for issue in &report.issues {
+            *counts.entry(issue.kind).or_default() += 1;
+        }
+        println!();
+        for (kind, count) in counts {
+            println!("  {:<24} {}", kind.to_string(), count);
+        }
+        println!();
+    }
+
+    let summary = format!(
+        "SEO score: {}/100 ({} issue(s) on {} of {} audited page(s))",
+        report.score,
+        report.issues.len(),
+        by_file.len(),
+        report.pages
+    );
+    if report.issues.is_empty() {
+        Console::success(&summary);
+    } else {
+        Console::warn(&summary);
+    }
+
+    if let Some(min_score) = min_score {
+        if report.score < min_score {
+            return Err(anyhow!(
+                "SEO score {} is below the minimum of {}",
+                report.score,
+                min_score
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_deploy(sample: Option<usize>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+    let manifest = Manifest::load(&config.output_dir(&project.root))?;
+
+    // The site is expected to be live already, so there is nothing to wait for
+    let verify = VerifyConfig {
+        sample: sample.unwrap_or(config.deploy.verify.sample),
+        attempts: 1,
+        ..config.deploy.verify.clone()
+    };
+    manifest::verify(&manifest, &config.get_effective_base_url(), &verify).await
+}
@@ -0,0 +1,48 @@
+use crate::utils::Console;
+use anyhow::{anyhow, Context, Result};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub fn handle_completions(shell: Shell) -> Result<()> {
+    let mut command = crate::Cli::command();
+    clap_complete::generate(shell, &mut command, "blogr", &mut io::stdout());
+    Ok(())
+}
+
+pub fn handle_man(subcommand: Vec<String>, out_dir: Option<PathBuf>) -> Result<()> {
+    let root = crate::Cli::command().disable_help_subcommand(true);
+
+    if let Some(out_dir) = out_dir {
+        fs::create_dir_all(&out_dir)
+            .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+        clap_mangen::generate_to(root, &out_dir)
+            .with_context(|| format!("Failed to write man pages to {}", out_dir.display()))?;
+        Console::success(&format!("Man pages written to {}", out_dir.display()));
+        crate::status!(
+            "💡 Read one with: man {}",
+            out_dir.join("blogr.1").display()
+        );
+        return Ok(());
+    }
+
+    // Building names subcommand pages like `blogr-newsletter-plugin-run`,
+    // as generate_to does for the whole tree
+    let mut command = root;
+    command.build();
+    let mut path = String::from("blogr");
+    for part in &subcommand {
+        command = command
+            .find_subcommand(part)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown command '{} {}'", path, part))?;
+        path = format!("{} {}", path, part);
+    }
+
+    clap_mangen::Man::new(command)
+        .render(&mut io::stdout())
+        .context("Failed to render the man page")?;
+    Ok(())
+}
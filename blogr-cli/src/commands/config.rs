@@ -1,7 +1,15 @@
+use crate::config::Config;
+use crate::config_path::{self, ConfigPath, Segment};
+use crate::domain_check::{self, Finding, HttpsStatus, Level};
 use crate::project::Project;
 use crate::utils::Console;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How often `--verify --wait` checks for the HTTPS certificate
+const HTTPS_POLL_INTERVAL: Duration = Duration::from_secs(15);
 
 /// Handle domain configuration commands
 pub async fn handle_domain(action: DomainAction) -> Result<()> {
@@ -11,7 +19,15 @@ pub async fn handle_domain(action: DomainAction) -> Result<()> {
             subdomain,
             enforce_https,
             github_pages,
-        } => handle_domain_set(domain, subdomain, enforce_https, github_pages).await,
+            verify,
+            wait,
+        } => {
+            handle_domain_set(domain, subdomain, enforce_https, github_pages).await?;
+            if verify {
+                verify_domains(wait).await?;
+            }
+            Ok(())
+        }
         DomainAction::List => handle_domain_list().await,
         DomainAction::Clear => handle_domain_clear().await,
         DomainAction::AddAlias { alias } => handle_domain_add_alias(alias).await,
@@ -26,6 +42,10 @@ pub enum DomainAction {
         subdomain: Option<String>,
         enforce_https: bool,
         github_pages: bool,
+        /// Check DNS and HTTPS once the domain is saved
+        verify: bool,
+        /// Seconds to wait for the HTTPS certificate
+        wait: u64,
     },
     List,
     Clear,
@@ -146,10 +166,19 @@ async fn handle_domain_list() -> Result<()> {
             );
         }
 
-        if !domains.aliases.is_empty() {
-            println!("🔗 Domain Aliases:");
-            for alias in &domains.aliases {
-                println!("  • {}", alias);
+        let aliases = config.alias_domains();
+        if !aliases.is_empty() {
+            println!("🔗 Domain Aliases (redirected to the main domain):");
+            for alias in &aliases {
+                if domains
+                    .aliases
+                    .iter()
+                    .any(|a| a.eq_ignore_ascii_case(alias))
+                {
+                    println!("  • {}", alias);
+                } else {
+                    println!("  • {} (www redirect)", alias);
+                }
             }
         }
 
@@ -266,20 +295,20 @@ fn create_cname_file(project: &Project, config: &crate::config::Config) -> Resul
 }
 
 fn display_domain_summary(config: &crate::config::Config) -> Result<()> {
-    println!();
-    println!("📊 Domain Configuration Summary:");
-    println!("{}", "-".repeat(40));
+    crate::status!();
+    crate::status!("📊 Domain Configuration Summary:");
+    crate::status!("{}", "-".repeat(40));
 
     if let Some(domains) = &config.blog.domains {
         let all_domains = config.get_all_domains();
         if !all_domains.is_empty() {
-            println!("🌐 Configured domains:");
+            crate::status!("🌐 Configured domains:");
             for domain in all_domains {
-                println!("  • {}", domain);
+                crate::status!("  • {}", domain);
             }
         }
 
-        println!(
+        crate::status!(
             "🔒 HTTPS: {}",
             if domains.enforce_https {
                 "Enforced"
@@ -287,55 +316,181 @@ fn display_domain_summary(config: &crate::config::Config) -> Result<()> {
                 "Optional"
             }
         );
-        println!("📍 Effective URL: {}", config.get_effective_base_url());
+        crate::status!("📍 Effective URL: {}", config.get_effective_base_url());
     }
 
-    println!();
+    crate::status!();
     Ok(())
 }
 
-/// Handle general configuration commands
-pub async fn handle_get(key: String) -> Result<()> {
-    Console::info(&format!("Getting configuration value: {}", key));
-
-    // Check if we're in a blogr project
+/// Check the DNS records of every configured domain, then whether the main
+/// one is served over HTTPS, polling for up to `wait` seconds
+async fn verify_domains(wait: u64) -> Result<()> {
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
-
     let config = project.load_config()?;
+    let domains = config.get_all_domains();
+    let Some(main_domain) = domains.first().cloned() else {
+        anyhow::bail!("No domain is configured");
+    };
+    let pages_host = config
+        .github
+        .as_ref()
+        .map(|github| format!("{}.github.io", github.username.to_lowercase()));
+
+    Console::info("Verifying domain setup for GitHub Pages...");
+    crate::status!();
+    let client = domain_check::client()?;
+    let mut findings = Vec::new();
+    for domain in &domains {
+        let records = domain_check::lookup(&client, domain).await?;
+        findings.extend(domain_check::check_records(
+            domain,
+            &records,
+            pages_host.as_deref(),
+        ));
+    }
+    let dns_ok = !findings.iter().any(|f| f.level == Level::Problem);
 
-    // Parse the key path (e.g., "blog.title", "theme.name", "domains.primary")
-    let parts: Vec<&str> = key.split('.').collect();
+    if dns_ok {
+        findings.extend(check_https(&client, &main_domain, wait, &config).await);
+    } else {
+        findings.push(Finding {
+            level: Level::Warning,
+            message: "HTTPS not checked".to_string(),
+            fix: Some(
+                "GitHub issues a certificate once DNS is right; run this again after fixing the records"
+                    .to_string(),
+            ),
+        });
+    }
 
-    match parts.as_slice() {
-        ["blog", "title"] => println!("{}", config.blog.title),
-        ["blog", "author"] => println!("{}", config.blog.author),
-        ["blog", "description"] => println!("{}", config.blog.description),
-        ["blog", "base_url"] => println!("{}", config.blog.base_url),
-        ["blog", "language"] => {
-            println!("{}", config.blog.language.as_deref().unwrap_or("Not set"))
+    let mut next_steps = Vec::new();
+    for finding in &findings {
+        let icon = match finding.level {
+            Level::Ok => "✅",
+            Level::Warning => "⚠️ ",
+            Level::Problem => "❌",
+        };
+        crate::status!("{} {}", icon, finding.message);
+        if let Some(fix) = &finding.fix {
+            crate::status!("   💡 {}", fix);
+            if finding.level == Level::Problem {
+                next_steps.push(fix);
+            }
         }
-        ["blog", "timezone"] => {
-            println!("{}", config.blog.timezone.as_deref().unwrap_or("Not set"))
+    }
+    crate::status!();
+
+    if next_steps.is_empty() {
+        Console::success(&format!("{} is set up for GitHub Pages", main_domain));
+        return Ok(());
+    }
+    crate::status!("📋 Next steps:");
+    for (number, step) in next_steps.iter().enumerate() {
+        crate::status!("   {}. {}", number + 1, step);
+    }
+    crate::status!(
+        "   Then run 'blogr config domain set {} --verify' again.",
+        main_domain
+    );
+    anyhow::bail!("Domain verification found {} problem(s)", next_steps.len())
+}
+
+/// Poll until `domain` is served over HTTPS or `wait` seconds pass
+async fn check_https(
+    client: &reqwest::Client,
+    domain: &str,
+    wait: u64,
+    config: &Config,
+) -> Vec<Finding> {
+    let deadline = Instant::now() + Duration::from_secs(wait);
+    let mut announced = false;
+    let status = loop {
+        let status = domain_check::https_status(client, domain).await;
+        if !matches!(status, HttpsStatus::CertificatePending(_)) || Instant::now() >= deadline {
+            break status;
         }
-        ["theme", "name"] => println!("{}", config.theme.name),
-        ["domains", "primary"] => {
-            if let Some(domains) = &config.blog.domains {
-                println!("{}", domains.primary.as_deref().unwrap_or("Not set"));
-            } else {
-                println!("Not set");
-            }
+        if !announced {
+            Console::info(&format!(
+                "Waiting up to {}s for GitHub to issue a certificate for {}...",
+                wait, domain
+            ));
+            announced = true;
         }
-        ["domains", "enforce_https"] => {
-            if let Some(domains) = &config.blog.domains {
-                println!("{}", domains.enforce_https);
-            } else {
-                println!("true (default)");
+        tokio::time::sleep(HTTPS_POLL_INTERVAL).await;
+    };
+
+    let finding = |level, message: String, fix: Option<String>| Finding {
+        level,
+        message,
+        fix,
+    };
+    match status {
+        HttpsStatus::Ready => {
+            let mut findings = vec![finding(
+                Level::Ok,
+                format!("https://{} has a valid certificate", domain),
+                None,
+            )];
+            let enforced = config
+                .blog
+                .domains
+                .as_ref()
+                .is_some_and(|domains| domains.enforce_https);
+            if enforced && !domain_check::redirects_to_https(domain).await.unwrap_or(true) {
+                findings.push(finding(
+                    Level::Warning,
+                    format!("http://{} isn't redirected to HTTPS", domain),
+                    Some(
+                        "Tick 'Enforce HTTPS' in the repository's Settings → Pages".to_string(),
+                    ),
+                ));
             }
+            findings
         }
-        _ => {
-            anyhow::bail!("Unknown configuration key: {}", key);
-        }
+        HttpsStatus::CertificatePending(reason) => vec![finding(
+            Level::Warning,
+            format!("No certificate for {} yet ({})", domain, reason),
+            Some(
+                "GitHub usually issues one within an hour of DNS being right; use --wait 900 to keep checking"
+                    .to_string(),
+            ),
+        )],
+        HttpsStatus::NoSite => vec![finding(
+            Level::Problem,
+            format!("GitHub Pages has no site for {}", domain),
+            Some(format!(
+                "Set the custom domain to {} in the repository's Settings → Pages, or deploy with the CNAME file ('--github-pages')",
+                domain
+            )),
+        )],
+        HttpsStatus::Unreachable(reason) => vec![finding(
+            Level::Problem,
+            format!("{} doesn't answer ({})", domain, reason),
+            Some("Check that the site is deployed and DNS changes have spread".to_string()),
+        )],
+    }
+}
+
+/// Handle general configuration commands
+pub async fn handle_get(key: String) -> Result<()> {
+    Console::info(&format!("Getting configuration value: {}", key));
+
+    // Check if we're in a blogr project
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project.load_config()?;
+    let effective = toml::Value::try_from(&config)?;
+
+    // Parse the key path (e.g., "blog.title", "theme.config.show_avatar", "menu[2].url")
+    let path = config_path(&key)?;
+    match path.get(&effective) {
+        Some(value) => println!("{}", config_path::display_value(value)),
+        // Optional settings are left out of the effective config when unset
+        None if is_unset(&path, &effective) => println!("Not set"),
+        None => anyhow::bail!("Unknown configuration key: {}", key),
     }
 
     Ok(())
@@ -348,31 +503,171 @@ pub async fn handle_set(key: String, value: String) -> Result<()> {
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
-    let mut config = project.load_config()?;
-
-    // Parse the key path and set the value
-    let parts: Vec<&str> = key.split('.').collect();
-
-    match parts.as_slice() {
-        ["blog", "title"] => config.blog.title = value.clone(),
-        ["blog", "author"] => config.blog.author = value.clone(),
-        ["blog", "description"] => config.blog.description = value.clone(),
-        ["blog", "base_url"] => config.blog.base_url = value.clone(),
-        ["blog", "language"] => config.blog.language = Some(value.clone()),
-        ["blog", "timezone"] => config.blog.timezone = Some(value.clone()),
-        ["theme", "name"] => config.theme.name = value.clone(),
-        _ => {
-            anyhow::bail!("Unknown or unsupported configuration key: {}", key);
+    let config_path = project.root.join("blogr.toml");
+    let mut file = read_config_file(&config_path)?;
+    let effective = toml::Value::try_from(project.load_config()?)?;
+
+    // Parse the key path and set the value, typed like the current one
+    let path = self::config_path(&key)?;
+    let new_value = config_path::parse_value(&value, path.get(&effective));
+    let typed = !new_value.is_str();
+    path.set(&mut file, new_value)?;
+
+    // Validate the configuration, and that the key is one blogr reads. An
+    // unset optional setting has no type to go by, so `2024` may be text.
+    let config = match parse_config(&file) {
+        Err(_) if typed => {
+            path.set(&mut file, toml::Value::String(value.clone()))?;
+            parse_config(&file)?
         }
+        result => result?,
+    };
+    if path.get(&toml::Value::try_from(&config)?).is_none() {
+        anyhow::bail!("Unknown or unsupported configuration key: {}", key);
     }
-
-    // Validate the configuration
     config.validate()?;
 
     // Save configuration
-    let config_path = project.root.join("blogr.toml");
-    config.save_to_file(&config_path)?;
+    write_config_file(&config_path, &file)?;
 
     Console::success(&format!("Configuration updated: {} = {}", key, value));
     Ok(())
 }
+
+/// Remove a setting from blogr.toml, so its default applies again
+pub async fn handle_unset(key: String) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config_path = project.root.join("blogr.toml");
+    let mut file = read_config_file(&config_path)?;
+    let path = self::config_path(&key)?;
+
+    if path.remove(&mut file).is_none() {
+        let effective = toml::Value::try_from(project.load_config()?)?;
+        if path.get(&effective).is_none() && !is_unset(&path, &effective) {
+            anyhow::bail!("Unknown configuration key: {}", key);
+        }
+        Console::info(&format!("{} is not set in blogr.toml", key));
+        return Ok(());
+    }
+
+    let config =
+        parse_config(&file).with_context(|| format!("{} is required and can't be unset", key))?;
+    config.validate()?;
+    write_config_file(&config_path, &file)?;
+
+    let effective = toml::Value::try_from(&config)?;
+    match path.get(&effective) {
+        Some(value) => Console::success(&format!(
+            "Unset {}; the default is {}",
+            key,
+            config_path::display_value(value)
+        )),
+        None => Console::success(&format!("Unset {}", key)),
+    }
+    Ok(())
+}
+
+/// Print every setting, or those under `prefix`, with where its value comes from
+pub async fn handle_list(prefix: Option<String>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let file = read_config_file(&project.root.join("blogr.toml"))?;
+    let effective = toml::Value::try_from(project.load_config()?)?;
+    let prefix = prefix.as_deref().map(config_path).transpose()?;
+    let under_prefix = |path: &ConfigPath| {
+        prefix
+            .as_ref()
+            .is_none_or(|prefix| path.0.starts_with(&prefix.0))
+    };
+
+    let mut entries: Vec<(String, String, &str)> = Vec::new();
+    for (path, value) in config_path::leaves(&effective) {
+        if under_prefix(&path) {
+            let source = if path.get(&file).is_some() {
+                "blogr.toml"
+            } else {
+                "default"
+            };
+            entries.push((path.to_string(), value.to_string(), source));
+        }
+    }
+    // Keys blogr doesn't read, e.g. misspelled or from an older version
+    for (path, value) in config_path::leaves(&file) {
+        if under_prefix(&path) && path.get(&effective).is_none() {
+            entries.push((path.to_string(), value.to_string(), "blogr.toml, unused"));
+        }
+    }
+    entries.sort();
+
+    if Console::is_json() {
+        let entries: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|(key, value, source)| {
+                serde_json::json!({ "key": key, "value": value, "source": source })
+            })
+            .collect();
+        return Console::report("config list", serde_json::json!({ "entries": entries }));
+    }
+
+    if entries.is_empty() {
+        Console::info("No matching settings");
+        return Ok(());
+    }
+    let width = entries
+        .iter()
+        .map(|(key, value, _)| key.chars().count() + value.chars().count() + 3)
+        .max()
+        .unwrap_or(0)
+        .min(72);
+    for (key, value, source) in &entries {
+        let line = format!("{} = {}", key, value);
+        println!("{:<width$}  # {}", line, source, width = width);
+    }
+    Ok(())
+}
+
+/// Parse a key, accepting the older `domains.*` spelling for `blog.domains.*`
+fn config_path(key: &str) -> Result<ConfigPath> {
+    match key.strip_prefix("domains.") {
+        Some(rest) => ConfigPath::parse(&format!("blog.domains.{}", rest)),
+        None => ConfigPath::parse(key),
+    }
+}
+
+/// Whether a key missing from the effective config is an unset optional
+/// setting rather than an unknown key: the nearest setting above it must be
+/// a table, and must not be the top level, except for the optional `[github]`
+fn is_unset(path: &ConfigPath, effective: &toml::Value) -> bool {
+    let ancestor = (1..path.0.len())
+        .rev()
+        .map(|len| ConfigPath(path.0[..len].to_vec()))
+        .find_map(|prefix| prefix.get(effective));
+    match ancestor {
+        Some(value) => value.is_table(),
+        None => path.0.first() == Some(&Segment::Key("github".to_string())),
+    }
+}
+
+/// blogr.toml as written, without defaults filled in
+fn read_config_file(path: &Path) -> Result<toml::Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    toml::from_str(&content).with_context(|| "Failed to parse configuration file")
+}
+
+fn parse_config(file: &toml::Value) -> Result<Config> {
+    file.clone()
+        .try_into()
+        .map_err(|e| anyhow!("Invalid configuration: {}", e))
+}
+
+/// Write blogr.toml back, keeping only the settings it had
+fn write_config_file(path: &Path, file: &toml::Value) -> Result<()> {
+    let content =
+        toml::to_string_pretty(file).with_context(|| "Failed to serialize configuration")?;
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))
+}
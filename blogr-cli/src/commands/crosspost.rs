@@ -0,0 +1,100 @@
+use crate::content::PostManager;
+use crate::crosspost::{Article, CrosspostClient, Platform};
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+
+pub async fn handle_crosspost(slug: String, to: String, draft: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+
+    let mut platforms: Vec<Platform> = Vec::new();
+    for name in to.split(',').filter(|name| !name.trim().is_empty()) {
+        let platform: Platform = name.parse()?;
+        if !platforms.contains(&platform) {
+            platforms.push(platform);
+        }
+    }
+    if platforms.is_empty() {
+        return Err(anyhow!(
+            "No platforms given. Use --to devto,hashnode,medium"
+        ));
+    }
+
+    // Check every token before publishing anywhere
+    let missing: Vec<&str> = platforms
+        .iter()
+        .map(Platform::token_var)
+        .filter(|var| std::env::var(var).is_err())
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "Missing API token: set {} (or add it to a .env file in the project)",
+            missing.join(", ")
+        ));
+    }
+
+    let post_manager = PostManager::new(project.posts_dir());
+    let mut post = post_manager
+        .find_by_slug(&slug)?
+        .ok_or_else(|| anyhow!("Post with slug '{}' not found", slug))?;
+    if !post.is_live(Utc::now()) {
+        return Err(anyhow!(
+            "Post '{}' isn't live yet (status: {}); publish it before cross-posting",
+            slug,
+            post.metadata.status
+        ));
+    }
+
+    let canonical_url = config.page_url(&format!("posts/{}.html", slug));
+    let article = Article::from_post(&post, canonical_url, draft);
+    let client = CrosspostClient::new();
+    let mut failed = 0;
+
+    for platform in platforms {
+        let token = std::env::var(platform.token_var())?;
+        let existing = post.metadata.crosspost.get(platform.key()).cloned();
+
+        match client
+            .publish(
+                platform,
+                &token,
+                &article,
+                existing.as_ref(),
+                config.crosspost.hashnode_publication_id.as_deref(),
+            )
+            .await
+        {
+            Ok(copy) => {
+                let action = if existing.is_some() {
+                    "Updated"
+                } else {
+                    "Published"
+                };
+                Console::success(&format!(
+                    "{} on {}: {}",
+                    action,
+                    platform,
+                    copy.url.as_deref().unwrap_or(&copy.id)
+                ));
+
+                // Save after each platform so an ID is never lost to a later failure
+                post.metadata
+                    .crosspost
+                    .insert(platform.key().to_string(), copy);
+                post.save_to_file(&post.file_path)?;
+            }
+            Err(e) => {
+                Console::error(&format!("{} failed: {:#}", platform, e));
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("Cross-posting failed on {} platform(s)", failed));
+    }
+    Ok(())
+}
@@ -1,20 +1,29 @@
 use crate::content::PostManager;
 use crate::project::Project;
+use crate::trash::Trash;
 use crate::utils::Console;
 use anyhow::{anyhow, Result};
 use std::io::{self, Write};
 
-pub async fn handle_delete(slug: String, force: bool) -> Result<()> {
+pub async fn handle_delete(slug: Option<String>, force: bool, purge: bool) -> Result<()> {
     // Check if we're in a blogr project
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
+    let trash = Trash::new(&project.root);
+    let Some(slug) = slug else {
+        return empty_trash(&trash, force);
+    };
+
     let post_manager = PostManager::new(project.posts_dir());
 
     // Find the post by slug to get details
-    let post = post_manager
-        .find_by_slug(&slug)?
-        .ok_or_else(|| anyhow!("Post with slug '{}' not found", slug))?;
+    let Some(post) = post_manager.find_by_slug(&slug)? else {
+        if purge && trash.find(&slug)?.is_some() {
+            return purge_trashed(&trash, &slug, force);
+        }
+        return Err(anyhow!("Post with slug '{}' not found", slug));
+    };
 
     // Show post details
     Console::info(&format!("Post to delete: '{}'", post.metadata.title));
@@ -24,33 +33,43 @@ pub async fn handle_delete(slug: String, force: bool) -> Result<()> {
         post.metadata.date.format("%Y-%m-%d %H:%M UTC")
     );
     println!("👤 Author: {}", post.metadata.author);
-    println!("📊 Status: {:?}", post.metadata.status);
+    println!("📊 Status: {}", post.metadata.status);
     if !post.metadata.tags.is_empty() {
         println!("🏷️  Tags: {}", post.metadata.tags.join(", "));
     }
-    println!(
-        "📄 File: {}",
-        project.posts_dir().join(post.filename()).display()
-    );
+    println!("📄 File: {}", post.file_path.display());
 
     // Confirmation (unless forced)
     if !force {
         println!();
-        print!(
-            "🗑️  Are you sure you want to delete this post? This action cannot be undone. (y/N): "
-        );
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let input = input.trim().to_lowercase();
-
-        if input != "y" && input != "yes" {
+        let question = if purge {
+            "🗑️  Are you sure you want to permanently delete this post? This action cannot be undone. (y/N): "
+        } else {
+            "🗑️  Move this post to the trash? (y/N): "
+        };
+        if !confirm(question)? {
             Console::info("Delete operation cancelled");
             return Ok(());
         }
     }
 
+    if !purge {
+        trash.trash(&post)?;
+        Console::success(&format!(
+            "Moved post '{}' to the trash",
+            post.metadata.title
+        ));
+        println!();
+        println!("💡 Next steps:");
+        println!("  • Undo: blogr restore {}", post.metadata.slug);
+        println!(
+            "  • Delete it permanently: blogr delete {} --purge",
+            post.metadata.slug
+        );
+        println!("  • Rebuild site: blogr build");
+        return Ok(());
+    }
+
     // Delete the post
     match post_manager.delete_post(&slug)? {
         true => {
@@ -60,7 +79,7 @@ pub async fn handle_delete(slug: String, force: bool) -> Result<()> {
             ));
             println!(
                 "🗑️  File removed: {}",
-                project.posts_dir().join(post.filename()).display()
+                post_manager.post_path(&post).display()
             );
             println!();
             println!("💡 Next steps:");
@@ -76,3 +95,69 @@ pub async fn handle_delete(slug: String, force: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// Permanently delete the trashed copies of a post
+fn purge_trashed(trash: &Trash, slug: &str, force: bool) -> Result<()> {
+    let entries: Vec<_> = trash
+        .entries()?
+        .into_iter()
+        .filter(|entry| entry.post.slug == slug)
+        .collect();
+
+    let question = format!(
+        "🗑️  Permanently delete {} trashed copy(ies) of '{}'? This action cannot be undone. (y/N): ",
+        entries.len(),
+        slug
+    );
+    if !force && !confirm(&question)? {
+        Console::info("Delete operation cancelled");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        trash.purge(entry)?;
+    }
+    Console::success(&format!("Removed '{}' from the trash", slug));
+    Ok(())
+}
+
+fn empty_trash(trash: &Trash, force: bool) -> Result<()> {
+    let entries = trash.entries()?;
+    if entries.is_empty() {
+        Console::info("The trash is empty");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        println!(
+            "  {} ({}, deleted {})",
+            entry.post.title,
+            entry.post.slug,
+            entry.post.deleted_at.format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+    let question = format!(
+        "🗑️  Permanently delete these {} post(s)? This action cannot be undone. (y/N): ",
+        entries.len()
+    );
+    if !force && !confirm(&question)? {
+        Console::info("Delete operation cancelled");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        trash.purge(entry)?;
+    }
+    Console::success(&format!("Emptied the trash ({} post(s))", entries.len()));
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{}", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
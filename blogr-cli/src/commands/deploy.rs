@@ -1,28 +1,35 @@
-use crate::config::{DeploymentType, EnvConfig};
-use crate::generator::SiteBuilder;
+use crate::commands::activitypub::announce_posts;
+use crate::commands::announce::announce_new_posts;
+use crate::commands::webmention::send_webmentions;
+use crate::config::{
+    Config, DeployTarget, DeployTargetKind, DeploymentType, EnvConfig, PREVIEW_PROFILE,
+};
+use crate::fonts;
+use crate::generator::manifest::{self, Manifest};
+use crate::generator::{hooks, HookRunner, HookStage, SiteBuilder};
+use crate::github_projects;
+use crate::progress::Progress;
 use crate::project::Project;
+use crate::s3;
 use crate::utils::Console;
 use anyhow::{anyhow, Context, Result};
-use git2::{BranchType, Repository, Signature};
+use git2::{BranchType, Oid, Repository, Signature};
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
 use uuid::Uuid;
 use walkdir::WalkDir;
 
-pub async fn handle_deploy(branch: String, message: Option<String>) -> Result<()> {
-    Console::info(&format!("Deploying to GitHub Pages (branch: {branch})"));
-
-    // Validate GitHub token early
-    Console::step(1, 7, "Validating GitHub token...");
-    let github_token = EnvConfig::github_token()
-        .ok_or_else(|| anyhow!("GitHub token not found. Set GITHUB_TOKEN environment variable."))?;
-
-    if github_token.is_empty() {
-        anyhow::bail!(
-            "GitHub token is empty. Please set a valid GITHUB_TOKEN environment variable."
-        );
-    }
-
+/// Deploy the site to a branch. A preview deploy includes drafts and future
+/// posts, is hidden from search engines and goes to `deploy.preview.branch`.
+/// With `all`, the site goes to every `[[deploy.targets]]` entry instead.
+pub async fn handle_deploy(
+    branch: Option<String>,
+    message: Option<String>,
+    preview: bool,
+    all: bool,
+) -> Result<()> {
     // Check if we're in a blogr project
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
@@ -30,29 +37,309 @@ pub async fn handle_deploy(branch: String, message: Option<String>) -> Result<()
     // Load configuration BEFORE any git operations to preserve current settings
     let mut config = project.load_config()?;
 
+    if all {
+        return deploy_all(project, config, message).await;
+    }
+
+    let branch = branch.unwrap_or_else(|| {
+        if preview {
+            config.deploy.preview.branch.clone()
+        } else {
+            "gh-pages".to_string()
+        }
+    });
+    if preview {
+        Console::info(&format!("Deploying a preview (branch: {branch})"));
+    } else {
+        Console::info(&format!("Deploying to GitHub Pages (branch: {branch})"));
+    }
+
+    // Validate GitHub token early
+    Console::step(1, 7, "Validating GitHub token...");
+    validate_github_token()?;
+
     // Ensure URL configuration consistency
-    config.sync_base_url_with_domains();
+    if preview {
+        config = config.preview();
+    } else {
+        config.sync_base_url_with_domains();
+    }
 
     let github_config = config.github.as_ref()
         .ok_or_else(|| anyhow!("GitHub configuration not found. Initialize with GitHub integration or configure manually."))?;
 
-    // For personal mode, read content.md BEFORE stashing to preserve uncommitted changes
-    let content_md = if config.site.site_type == "personal" {
+    let content_md = read_content_md(&project, &config)?;
+
+    Console::step(2, 7, "Preparing git repository...");
+
+    let stashed = stash_changes(&project)?;
+
+    Console::step(3, 7, "Building site...");
+
+    let temp_output = build_for_deploy(&project, &config, content_md, preview).await?;
+    let site = temp_output.path();
+
+    if preview {
+        Console::warn(
+            "blogr doesn't password-protect previews: anyone with the URL can read it unless the host serving the branch restricts access",
+        );
+    }
+
+    let deploy_message = message.unwrap_or_else(default_message);
+    let commit_id = publish_branch(
+        &project.root,
+        &config,
+        site,
+        &branch,
+        &deploy_message,
+        !preview,
+        true,
+    )?;
+
+    if stashed {
+        restore_stash(&project);
+    }
+
+    // The site is live, so a failing hook is reported without failing the deploy
+    if !preview {
+        let hooks = HookRunner::new(&project, &config, site)
+            .with("branch", branch.clone())
+            .with("commit", commit_id.to_string());
+        if let Err(e) = hooks.run(HookStage::PostDeploy) {
+            Console::warn(&format!("Post-deploy hook failed: {:#}", e));
+        }
+    }
+
+    let manifest = verified_manifest(&config, site, preview)?;
+
+    // Clean up the temporary build
+    temp_output.close()?;
+
+    // Previews are for review: nothing is checked, announced or sent
+    if preview {
+        crate::status!();
+        Console::success("🔍 Preview deployed!");
+        match &config.deploy.preview.base_url {
+            Some(_) => crate::status!("🌐 Preview URL: {}", config.blog.base_url),
+            None => crate::status!(
+                "🌐 Serve the '{}' branch from a separate site and set deploy.preview.base_url",
+                branch
+            ),
+        }
+        crate::status!("📦 Commit: {}", commit_id);
+        return Ok(());
+    }
+
+    crate::status!();
+    Console::success("🚀 Site deployed to GitHub Pages!");
+    crate::status!("🌐 Your site will be available at:");
+
+    crate::status!("   {}", config.blog.base_url);
+
+    crate::status!("📝 Deployment branch: {}", branch);
+    crate::status!("📦 Commit: {}", commit_id);
+    crate::status!();
+
+    // Check GitHub Pages deployment status
+    Console::info("Checking GitHub Pages deployment status...");
+    match check_github_pages_status(&github_config.username, &github_config.repository).await {
+        Ok(status) => match status.as_str() {
+            "built" => Console::success("GitHub Pages is enabled and working"),
+            "building" => Console::info("GitHub Pages is currently building"),
+            "errored" => Console::warn("GitHub Pages deployment has errors"),
+            _ => Console::info(&format!("GitHub Pages status: {}", status)),
+        },
+        Err(e) => Console::warn(&format!("Could not check GitHub Pages status: {}", e)),
+    }
+
+    crate::status!();
+    crate::status!("ℹ️  Note: It may take a few minutes for changes to appear on GitHub Pages");
+
+    // Nothing is announced for a site that isn't live as built
+    if let Some(manifest) = &manifest {
+        crate::status!();
+        manifest::verify(manifest, &config.blog.base_url, &config.deploy.verify).await?;
+    }
+
+    announce_after_deploy(&project, &config).await;
+
+    Ok(())
+}
+
+/// What every deploy target gets: the built site and where it came from.
+/// The site is removed once the last target is done with it.
+struct TargetJob {
+    project: Project,
+    config: Config,
+    site: TempDir,
+    message: String,
+}
+
+/// Build once and publish to every deploy target, one after another or all
+/// at once with `deploy.parallel`, then report the status of each
+async fn deploy_all(project: Project, mut config: Config, message: Option<String>) -> Result<()> {
+    config.validate()?;
+    let targets = config.deploy.targets.clone();
+    if targets.is_empty() {
+        anyhow::bail!(
+            "No deploy targets configured. Add [[deploy.targets]] to blogr.toml, or run 'blogr deploy' without --all."
+        );
+    }
+    config.sync_base_url_with_domains();
+    Console::info(&format!("Deploying to {} targets", targets.len()));
+
+    let content_md = read_content_md(&project, &config)?;
+    let stashed = stash_changes(&project)?;
+    let built = build_for_deploy(&project, &config, content_md, false).await;
+    // Targets only read the built site, so local changes can come back now
+    if stashed {
+        restore_stash(&project);
+    }
+    let job = Arc::new(TargetJob {
+        project,
+        config,
+        site: built?,
+        message: message.unwrap_or_else(default_message),
+    });
+
+    let mut results = Vec::new();
+    if job.config.deploy.parallel {
+        let handles: Vec<_> = targets
+            .iter()
+            .map(|target| {
+                Console::info(&format!("Deploying to {}...", target.name));
+                tokio::spawn(deploy_target(target.clone(), Arc::clone(&job)))
+            })
+            .collect();
+        for handle in handles {
+            results.push(
+                handle
+                    .await
+                    .unwrap_or_else(|e| Err(anyhow!("Deploy task failed: {}", e))),
+            );
+        }
+    } else {
+        for target in &targets {
+            Console::info(&format!("Deploying to {}...", target.name));
+            results.push(deploy_target(target.clone(), Arc::clone(&job)).await);
+        }
+    }
+
+    crate::status!();
+    let mut deployed = Vec::new();
+    for (target, result) in targets.iter().zip(&results) {
+        let label = format!("{} [{}]", target.name, target.kind.describe());
+        match result {
+            Ok(status) => {
+                Console::success(&format!("{}: {}", label, status));
+                deployed.push(target.name.clone());
+            }
+            Err(e) => Console::error(&format!("{}: {:#}", label, e)),
+        }
+    }
+
+    let failed = targets.len() - deployed.len();
+    if failed == 0 {
+        // The site is live, so a failing hook is reported without failing the deploy
+        let hooks = HookRunner::new(&job.project, &job.config, job.site.path())
+            .with("targets", deployed.join(","));
+        if let Err(e) = hooks.run(HookStage::PostDeploy) {
+            Console::warn(&format!("Post-deploy hook failed: {:#}", e));
+        }
+    }
+
+    let manifest = verified_manifest(&job.config, job.site.path(), false)?;
+    if failed > 0 {
+        anyhow::bail!("{} of {} deploy targets failed", failed, targets.len());
+    }
+
+    if let Some(manifest) = &manifest {
+        crate::status!();
+        manifest::verify(
+            manifest,
+            &job.config.blog.base_url,
+            &job.config.deploy.verify,
+        )
+        .await?;
+    }
+    announce_after_deploy(&job.project, &job.config).await;
+    Ok(())
+}
+
+/// Publish the built site to one target, returning a short status
+async fn deploy_target(target: DeployTarget, job: Arc<TargetJob>) -> Result<String> {
+    match target.kind {
+        DeployTargetKind::Github { branch } => {
+            let commit = tokio::task::spawn_blocking(move || {
+                publish_branch(
+                    &job.project.root,
+                    &job.config,
+                    job.site.path(),
+                    &branch,
+                    &job.message,
+                    true,
+                    false,
+                )
+            })
+            .await??;
+            Ok(format!("pushed commit {}", commit))
+        }
+        DeployTargetKind::S3 {
+            bucket,
+            region,
+            endpoint,
+            prefix,
+        } => {
+            let bucket = s3::Bucket::new(
+                &bucket,
+                &region,
+                endpoint.as_deref(),
+                s3::Credentials::from_env()?,
+            );
+            let count = bucket
+                .upload_dir(job.site.path(), prefix.as_deref().unwrap_or(""))
+                .await?;
+            Ok(format!("uploaded {} files", count))
+        }
+        DeployTargetKind::Command { command } => {
+            tokio::task::spawn_blocking(move || {
+                let status = hooks::shell(&command)
+                    .current_dir(&job.project.root)
+                    .env("BLOGR_OUTPUT_DIR", job.site.path())
+                    .env("BLOGR_BASE_URL", &job.config.blog.base_url)
+                    .env("BLOGR_TARGET", &target.name)
+                    .status()
+                    .with_context(|| format!("Failed to run {}", command))?;
+                if !status.success() {
+                    anyhow::bail!("{} ({})", command, status);
+                }
+                Ok(format!("ran {}", command))
+            })
+            .await?
+        }
+    }
+}
+
+/// For personal and hybrid sites, read content.md BEFORE stashing to preserve uncommitted changes
+fn read_content_md(project: &Project, config: &Config) -> Result<Option<String>> {
+    if matches!(config.site.site_type.as_str(), "personal" | "hybrid") {
         let content_md_path = project.root.join("content.md");
         if content_md_path.exists() {
-            Some(
+            Ok(Some(
                 fs::read_to_string(&content_md_path)
                     .with_context(|| "Failed to read content.md")?,
-            )
+            ))
         } else {
-            None
+            Ok(None)
         }
     } else {
-        None
-    };
-
-    Console::step(2, 7, "Preparing git repository...");
+        Ok(None)
+    }
+}
 
+/// Stash uncommitted changes so the build sees the committed state, returning
+/// whether anything was stashed
+fn stash_changes(project: &Project) -> Result<bool> {
     // Open the git repository
     let mut repo = Repository::open(&project.root)
         .with_context(|| "Failed to open git repository. Ensure this is a git repository.")?;
@@ -77,34 +364,69 @@ pub async fn handle_deploy(branch: String, message: Option<String>) -> Result<()
         })
     };
 
-    let mut stash_id = None;
     if has_uncommitted_changes {
         Console::info("Working directory has uncommitted changes. Auto-stashing for deployment...");
 
         // Auto-stash uncommitted changes (they will be restored after deployment)
         let signature = get_git_signature()?;
         let id = repo.stash_save(&signature, "Auto-stash before deployment", None)?;
-        stash_id = Some(id);
         Console::info(&format!("Changes stashed with ID: {}", id));
     }
 
-    Console::step(3, 7, "Building site...");
+    Ok(has_uncommitted_changes)
+}
 
+async fn build_for_deploy(
+    project: &Project,
+    config: &Config,
+    content_md: Option<String>,
+    preview: bool,
+) -> Result<TempDir> {
     // Build the site AFTER handling git state to ensure build directory exists
-    // Use a temporary directory outside the project to avoid conflicts
-    let temp_output = std::env::temp_dir().join(format!("blogr-deploy-{}", Uuid::new_v4()));
+    // Use a temporary directory outside the project to avoid conflicts. It is
+    // removed when dropped, so a failed deploy doesn't leave it behind.
+    let temp_output = tempfile::Builder::new()
+        .prefix("blogr-deploy-")
+        .tempdir()
+        .context("Failed to create a temporary build directory")?;
     // Use the pre-loaded config and content.md to avoid issues with git stashing
+    github_projects::refresh(&project.root, &config.github_projects).await;
+    fonts::refresh(project, config).await;
     let site_builder = SiteBuilder::new_with_config_and_content(
         project.clone(),
         config.clone(),
         content_md,
-        Some(temp_output.clone()),
-        false,
-        false,
-    )?;
+        Some(temp_output.path().to_path_buf()),
+        preview,
+        preview,
+    )?
+    .with_profile(preview.then(|| PREVIEW_PROFILE.to_string()));
     site_builder.build()?;
+    tracing::debug!("Built site into {}", temp_output.path().display());
+
+    Ok(temp_output)
+}
+
+/// Commit the built `site` to `branch` in a temporary worktree and push it to
+/// `origin`. Writes `CNAME` for the configured domain when `cname` is set.
+fn publish_branch(
+    project_root: &Path,
+    config: &Config,
+    site: &Path,
+    branch: &str,
+    message: &str,
+    cname: bool,
+    steps: bool,
+) -> Result<Oid> {
+    let step = |n, text: &str| {
+        if steps {
+            Console::step(n, 7, text);
+        }
+    };
+    let repo = Repository::open(project_root)
+        .with_context(|| "Failed to open git repository. Ensure this is a git repository.")?;
 
-    Console::step(4, 7, &format!("Preparing {} branch...", branch));
+    step(4, &format!("Preparing {} branch...", branch));
 
     // Create a unique temporary directory for deployment worktree
     let mut temp_deploy_dir;
@@ -133,18 +455,18 @@ pub async fn handle_deploy(branch: String, message: Option<String>) -> Result<()
     }
 
     // Ensure deployment branch exists
-    let deploy_branch_exists = repo.find_branch(&branch, BranchType::Local).is_ok();
+    let deploy_branch_exists = repo.find_branch(branch, BranchType::Local).is_ok();
 
     if !deploy_branch_exists {
         Console::info(&format!("Creating new deployment branch '{}'...", branch));
         // Create orphan branch for GitHub Pages
-        create_orphan_branch(&repo, &branch)?;
+        create_orphan_branch(&repo, branch)?;
     } else {
         Console::info(&format!("Using existing deployment branch '{}'...", branch));
     }
 
     // Verify the branch was created/exists
-    if repo.find_branch(&branch, BranchType::Local).is_err() {
+    if repo.find_branch(branch, BranchType::Local).is_err() {
         anyhow::bail!("Failed to create or find deployment branch '{}'", branch);
     }
 
@@ -214,64 +536,23 @@ pub async fn handle_deploy(branch: String, message: Option<String>) -> Result<()
     })?;
 
     let deploy_repo = Repository::open(&temp_deploy_dir)?;
+    tracing::debug!(
+        "Checked out {} in worktree {}",
+        branch,
+        temp_deploy_dir.display()
+    );
 
-    Console::step(5, 7, "Copying built files...");
+    step(5, "Copying built files...");
 
     // Clear the deployment worktree and copy built files
     clear_deployment_branch(&temp_deploy_dir)?;
-    copy_site_files(&temp_output, &temp_deploy_dir)?;
+    copy_site_files(site, &temp_deploy_dir)?;
 
-    // Smart CNAME file creation based on deployment type
-    let deployment_type = config.get_deployment_type();
-
-    // Create CNAME file for custom domains
-    let cname_created = match deployment_type {
-        DeploymentType::CustomDomain => {
-            let effective_url = config.get_effective_base_url();
-            if let Ok(url) = url::Url::parse(&effective_url) {
-                if let Some(host) = url.host_str() {
-                    let cname_path = temp_deploy_dir.join("CNAME");
-                    fs::write(cname_path, format!("{}\n", host))?;
-                    Console::info(&format!("Created CNAME file for custom domain: {}", host));
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        }
-        DeploymentType::GitHubPagesRoot => {
-            Console::info("Deploying to GitHub Pages root domain - no CNAME file needed");
-            false
-        }
-        DeploymentType::GitHubPagesSubpath => {
-            Console::info("Deploying to GitHub Pages subpath - no CNAME file needed");
-            false
-        }
-        DeploymentType::Unknown => {
-            Console::warn(
-                "Could not determine deployment type - checking for explicit GitHub Pages domain",
-            );
-            false
-        }
-    };
-
-    // Fallback: if no CNAME was created but we have a github_pages_domain configured, use it
-    if !cname_created {
-        if let Some(domains) = &config.blog.domains {
-            if let Some(github_domain) = &domains.github_pages_domain {
-                let cname_path = temp_deploy_dir.join("CNAME");
-                fs::write(cname_path, format!("{}\n", github_domain))?;
-                Console::info(&format!(
-                    "Created CNAME file from github_pages_domain: {}",
-                    github_domain
-                ));
-            }
-        }
+    if cname {
+        write_cname(config, &temp_deploy_dir)?;
     }
 
-    Console::step(6, 7, "Committing changes...");
+    step(6, "Committing changes...");
 
     // Stage all files in the deploy repository
     let mut index = deploy_repo.index()?;
@@ -279,13 +560,6 @@ pub async fn handle_deploy(branch: String, message: Option<String>) -> Result<()
     index.write()?;
 
     // Create commit in the deploy repository
-    let deploy_message = message.unwrap_or_else(|| {
-        format!(
-            "Deploy site - {}",
-            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-        )
-    });
-
     let signature = get_git_signature()?;
     let tree_id = index.write_tree()?;
     let tree = deploy_repo.find_tree(tree_id)?;
@@ -302,78 +576,101 @@ pub async fn handle_deploy(branch: String, message: Option<String>) -> Result<()
             Some("HEAD"),
             &signature,
             &signature,
-            &deploy_message,
+            message,
             &tree,
             &[&parent],
         )?
     } else {
-        deploy_repo.commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            &deploy_message,
-            &tree,
-            &[],
-        )?
+        deploy_repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[])?
     };
 
-    Console::step(7, 7, "Pushing to GitHub...");
+    tracing::debug!("Committed {} on {}", commit_id, branch);
+    step(7, "Pushing to GitHub...");
 
     // Push to GitHub from the deploy repository
-    push_to_github(&deploy_repo, &branch, github_config)?;
-
-    // Restore stashed changes if any were stashed
-    if let Some(_stash_id) = stash_id {
-        Console::info("Restoring stashed changes...");
-        // Create a new repo handle to avoid borrowing conflicts
-        let mut restore_repo = Repository::open(&project.root)?;
-        match restore_repo.stash_pop(0, None) {
-            Ok(_) => Console::info("Successfully restored stashed changes"),
-            Err(e) => Console::warn(&format!(
-                "Warning: Could not restore stashed changes: {}",
-                e
-            )),
-        }
-    }
-
-    // Clean up worktree and temporary directories
-    // Note: The worktree will be automatically cleaned up when we remove temp_deploy_dir
+    crate::logging::timed(&format!("pushed {} to origin", branch), || {
+        push_to_github(&deploy_repo, branch)
+    })?;
 
-    if temp_output.exists() {
-        fs::remove_dir_all(&temp_output)?;
-    }
+    // The worktree is cleaned up with its directory
     if temp_deploy_dir.exists() {
         fs::remove_dir_all(&temp_deploy_dir)?;
     }
 
-    println!();
-    Console::success("🚀 Site deployed to GitHub Pages!");
-    println!("🌐 Your site will be available at:");
+    Ok(commit_id)
+}
 
-    println!("   {}", config.blog.base_url);
+/// Put back changes stashed by `stash_changes`
+fn restore_stash(project: &Project) {
+    Console::info("Restoring stashed changes...");
+    let restored = Repository::open(&project.root).and_then(|mut repo| repo.stash_pop(0, None));
+    match restored {
+        Ok(_) => Console::info("Successfully restored stashed changes"),
+        Err(e) => Console::warn(&format!(
+            "Warning: Could not restore stashed changes: {}",
+            e
+        )),
+    }
+}
 
-    println!("📝 Deployment branch: {}", branch);
-    println!("📦 Commit: {}", commit_id);
-    println!();
+/// Webmentions and announcements configured to follow a deploy. Failures are
+/// reported without failing the deploy.
+async fn announce_after_deploy(project: &Project, config: &Config) {
+    // Posts that aren't live yet are skipped and picked up by the next send
+    if config.indieweb.send_on_deploy {
+        crate::status!();
+        Console::info("Sending webmentions...");
+        if let Err(e) = send_webmentions(project, config, None, false).await {
+            Console::warn(&format!("Could not send webmentions: {:#}", e));
+        }
+    }
 
-    // Check GitHub Pages deployment status
-    Console::info("Checking GitHub Pages deployment status...");
-    match check_github_pages_status(&github_config.username, &github_config.repository).await {
-        Ok(status) => match status.as_str() {
-            "built" => Console::success("GitHub Pages is enabled and working"),
-            "building" => Console::info("GitHub Pages is currently building"),
-            "errored" => Console::warn("GitHub Pages deployment has errors"),
-            _ => Console::info(&format!("GitHub Pages status: {}", status)),
-        },
-        Err(e) => Console::warn(&format!("Could not check GitHub Pages status: {}", e)),
+    if config.activitypub.enabled && config.activitypub.announce_on_deploy {
+        crate::status!();
+        Console::info("Announcing new posts to followers...");
+        if let Err(e) = announce_posts(project, config, None, false).await {
+            Console::warn(&format!("Could not announce posts: {:#}", e));
+        }
     }
 
-    println!();
-    println!("ℹ️  Note: It may take a few minutes for changes to appear on GitHub Pages");
+    if config.announce.after_deploy {
+        crate::status!();
+        Console::info("Announcing new posts on social networks...");
+        if let Err(e) = announce_new_posts(project, config).await {
+            Console::warn(&format!("Could not announce posts: {:#}", e));
+        }
+    }
+}
 
+/// The manifest of the built site, when the live site should be checked against it
+fn verified_manifest(config: &Config, site: &Path, preview: bool) -> Result<Option<Manifest>> {
+    if config.deploy.verify.enabled && !preview {
+        Manifest::load(site).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Fail early when there is no GitHub token to push with
+fn validate_github_token() -> Result<()> {
+    let github_token = EnvConfig::github_token()
+        .ok_or_else(|| anyhow!("GitHub token not found. Set GITHUB_TOKEN environment variable."))?;
+
+    if github_token.is_empty() {
+        anyhow::bail!(
+            "GitHub token is empty. Please set a valid GITHUB_TOKEN environment variable."
+        );
+    }
     Ok(())
 }
 
+fn default_message() -> String {
+    format!(
+        "Deploy site - {}",
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+    )
+}
+
 async fn check_github_pages_status(username: &str, repository: &str) -> Result<String> {
     let github_token = EnvConfig::github_token()
         .ok_or_else(|| anyhow!("GitHub token not found. Set GITHUB_TOKEN environment variable."))?;
@@ -417,6 +714,61 @@ async fn check_github_pages_status(username: &str, repository: &str) -> Result<S
     Ok(status)
 }
 
+/// Write `CNAME` for a custom domain, or for `github_pages_domain`
+fn write_cname(config: &Config, temp_deploy_dir: &Path) -> Result<()> {
+    // Smart CNAME file creation based on deployment type
+    let deployment_type = config.get_deployment_type();
+
+    // Create CNAME file for custom domains
+    let cname_created = match deployment_type {
+        DeploymentType::CustomDomain => {
+            let effective_url = config.get_effective_base_url();
+            if let Ok(url) = url::Url::parse(&effective_url) {
+                if let Some(host) = url.host_str() {
+                    let cname_path = temp_deploy_dir.join("CNAME");
+                    fs::write(cname_path, format!("{}\n", host))?;
+                    Console::info(&format!("Created CNAME file for custom domain: {}", host));
+                    true
+                } else {
+                    false
+                }
+            } else {
+                false
+            }
+        }
+        DeploymentType::GitHubPagesRoot => {
+            Console::info("Deploying to GitHub Pages root domain - no CNAME file needed");
+            false
+        }
+        DeploymentType::GitHubPagesSubpath => {
+            Console::info("Deploying to GitHub Pages subpath - no CNAME file needed");
+            false
+        }
+        DeploymentType::Unknown => {
+            Console::warn(
+                "Could not determine deployment type - checking for explicit GitHub Pages domain",
+            );
+            false
+        }
+    };
+
+    // Fallback: if no CNAME was created but we have a github_pages_domain configured, use it
+    if !cname_created {
+        if let Some(domains) = &config.blog.domains {
+            if let Some(github_domain) = &domains.github_pages_domain {
+                let cname_path = temp_deploy_dir.join("CNAME");
+                fs::write(cname_path, format!("{}\n", github_domain))?;
+                Console::info(&format!(
+                    "Created CNAME file from github_pages_domain: {}",
+                    github_domain
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn create_orphan_branch(repo: &Repository, branch_name: &str) -> Result<()> {
     // Create a new orphan branch by creating an empty commit
     let signature = get_git_signature()?;
@@ -428,8 +780,9 @@ fn create_orphan_branch(repo: &Repository, branch_name: &str) -> Result<()> {
     };
     let tree = repo.find_tree(tree_id)?;
 
-    // Create the initial commit on the new branch
-    let commit_id = repo.commit(
+    // Create the initial commit on the new branch. It is checked out in a
+    // worktree later, so the project's own checkout is left alone.
+    repo.commit(
         Some(&format!("refs/heads/{}", branch_name)),
         &signature,
         &signature,
@@ -438,11 +791,6 @@ fn create_orphan_branch(repo: &Repository, branch_name: &str) -> Result<()> {
         &[],
     )?;
 
-    // Checkout the new branch
-    let commit = repo.find_commit(commit_id)?;
-    repo.checkout_tree(commit.as_object(), None)?;
-    repo.set_head(&format!("refs/heads/{}", branch_name))?;
-
     Ok(())
 }
 
@@ -491,18 +839,18 @@ fn copy_site_files(source: &Path, dest: &Path) -> Result<()> {
     Ok(())
 }
 
-fn get_git_signature() -> Result<Signature<'static>> {
-    let name = EnvConfig::git_author_name().unwrap_or_else(|| "Blogr".to_string());
-    let email = EnvConfig::git_author_email().unwrap_or_else(|| "blogr@example.com".to_string());
+pub(crate) fn get_git_signature() -> Result<Signature<'static>> {
+    let name = EnvConfig::git_author_name()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Blogr".to_string());
+    let email = EnvConfig::git_author_email()
+        .filter(|email| !email.is_empty())
+        .unwrap_or_else(|| "blogr@example.com".to_string());
 
     Signature::now(&name, &email).with_context(|| "Failed to create git signature")
 }
 
-fn push_to_github(
-    repo: &Repository,
-    branch: &str,
-    _github_config: &crate::config::GitHubConfig,
-) -> Result<()> {
+fn push_to_github(repo: &Repository, branch: &str) -> Result<()> {
     // Get the existing remote
     let mut remote = repo
         .find_remote("origin")
@@ -513,12 +861,37 @@ fn push_to_github(
     // Force push the branch to avoid conflicts
     let refspec = format!("+refs/heads/{}:refs/heads/{}", branch, branch);
 
+    if remote_url.starts_with("https://") {
+        Console::info("Using HTTPS token authentication for git push...");
+    } else if remote_url.starts_with("git@") || remote_url.starts_with("ssh://") {
+        Console::info("Using SSH authentication for git push...");
+    }
+    let mut callbacks = remote_callbacks(remote_url)?;
+    // The total is only known once the pack is ready
+    let mut progress: Option<Progress> = None;
+    callbacks.push_transfer_progress(move |current, total, _bytes| {
+        progress
+            .get_or_insert_with(|| Progress::new(total as u64, "Pushing objects"))
+            .set_position(current as u64);
+    });
+
+    let mut push_options = git2::PushOptions::new();
+    push_options.remote_callbacks(callbacks);
+    tracing::debug!("Pushing {}", refspec);
+
+    remote.push(&[&refspec], Some(&mut push_options))?;
+
+    Ok(())
+}
+
+/// Credentials for `origin`: the SSH agent or `~/.ssh/id_rsa` for SSH remotes,
+/// the GitHub token for HTTPS ones and nothing for local paths
+pub(crate) fn remote_callbacks(remote_url: &str) -> Result<git2::RemoteCallbacks<'static>> {
     let mut callbacks = git2::RemoteCallbacks::new();
 
     // Handle authentication based on remote URL type
     if remote_url.starts_with("git@") || remote_url.starts_with("ssh://") {
         // SSH authentication - use SSH agent or SSH keys
-        Console::info("Using SSH authentication for git push...");
         callbacks.credentials(|_url, username_from_url, allowed_types| {
             if allowed_types.contains(git2::CredentialType::SSH_KEY) {
                 // Try SSH agent first
@@ -549,19 +922,14 @@ fn push_to_github(
         let github_token = EnvConfig::github_token()
             .ok_or_else(|| anyhow!("GitHub token not found. Set GITHUB_TOKEN environment variable for HTTPS authentication."))?;
 
-        Console::info("Using HTTPS token authentication for git push...");
         let token_clone = github_token.clone();
         callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
             git2::Cred::userpass_plaintext(&token_clone, "")
         });
-    } else {
+    } else if !remote_url.starts_with("file://") && !Path::new(remote_url).exists() {
+        // Local remotes need no credentials
         anyhow::bail!("Unsupported remote URL format: {}", remote_url);
     }
 
-    let mut push_options = git2::PushOptions::new();
-    push_options.remote_callbacks(callbacks);
-
-    remote.push(&[&refspec], Some(&mut push_options))?;
-
-    Ok(())
+    Ok(callbacks)
 }
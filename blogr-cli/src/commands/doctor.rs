@@ -0,0 +1,391 @@
+use crate::config::{Config, EnvConfig};
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+use blogr_themes::SiteType;
+use std::time::Duration;
+
+/// Timeout for network checks (GitHub API, SMTP/IMAP servers)
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+/// Outcome of a single diagnostic check
+struct CheckResult {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+    fix: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skip,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn print(&self) {
+        let icon = match self.status {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+            CheckStatus::Skip => "⏭️ ",
+        };
+        println!("{} {}: {}", icon, self.name, self.detail);
+        if let Some(fix) = &self.fix {
+            println!("   💡 {}", fix);
+        }
+    }
+}
+
+pub async fn handle_doctor(offline: bool) -> Result<()> {
+    Console::info("Running environment diagnostics...");
+    println!();
+
+    let mut results = vec![check_git()];
+
+    let project = Project::find_project().ok().flatten();
+    let config = match &project {
+        Some(project) => {
+            results.push(CheckResult::pass(
+                "Project",
+                format!("found at {}", project.root.display()),
+            ));
+            match project.load_config() {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    results.push(CheckResult::fail(
+                        "Config",
+                        format!("blogr.toml could not be parsed: {}", e),
+                        "Fix the syntax error in blogr.toml, or compare it with a fresh 'blogr init' project",
+                    ));
+                    None
+                }
+            }
+        }
+        None => {
+            results.push(CheckResult::fail(
+                "Project",
+                "no blogr.toml found in this directory or its parents",
+                "Run 'blogr init' to create a project, or cd into an existing one",
+            ));
+            None
+        }
+    };
+
+    if let (Some(project), Some(config)) = (&project, &config) {
+        results.push(check_config(project));
+        results.push(check_theme(project, config));
+        results.push(check_port(config.dev.port));
+
+        if offline {
+            results.push(CheckResult::skip("GitHub token", "skipped (--offline)"));
+        } else {
+            results.push(check_github_token(config).await);
+        }
+
+        results.extend(check_newsletter(config, offline).await);
+    } else {
+        results.push(check_port(crate::config::DevConfig::default().port));
+        if !offline {
+            results.push(check_github_token(&Config::default()).await);
+        }
+    }
+
+    for result in &results {
+        result.print();
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let (passed, warnings, failures) = (
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Fail),
+    );
+
+    println!();
+    println!(
+        "📋 {} passed, {} warnings, {} failed",
+        passed, warnings, failures
+    );
+
+    if failures > 0 {
+        return Err(anyhow!("{} diagnostic check(s) failed", failures));
+    }
+
+    if warnings == 0 {
+        Console::success("Everything looks good!");
+    }
+    Ok(())
+}
+
+fn check_git() -> CheckResult {
+    match std::process::Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => CheckResult::pass(
+            "Git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => CheckResult::fail(
+            "Git",
+            "git is not installed or not on PATH",
+            "Install git from https://git-scm.com/downloads (needed for 'blogr deploy')",
+        ),
+    }
+}
+
+fn check_config(project: &Project) -> CheckResult {
+    match project.validate() {
+        Ok(issues) if issues.is_empty() => CheckResult::pass("Config", "blogr.toml is valid"),
+        Ok(issues) => CheckResult::fail(
+            "Config",
+            issues.join("; "),
+            "Run 'blogr project check' for details, or fix the values with 'blogr config set'",
+        ),
+        Err(e) => CheckResult::fail(
+            "Config",
+            format!("validation failed: {}", e),
+            "Run 'blogr project check' for details",
+        ),
+    }
+}
+
+fn check_theme(project: &Project, config: &Config) -> CheckResult {
+    let theme = match project.load_theme(&config.theme.name) {
+        Ok(Some(theme)) => theme,
+        Ok(None) => {
+            return CheckResult::fail(
+                "Theme",
+                format!("theme '{}' does not exist", config.theme.name),
+                "Run 'blogr theme list' and pick one with 'blogr theme set <name>'",
+            )
+        }
+        Err(e) => {
+            return CheckResult::fail(
+                "Theme",
+                format!("installed theme '{}' is broken: {}", config.theme.name, e),
+                "Reinstall it with 'blogr theme update'",
+            )
+        }
+    };
+
+    let info = theme.info();
+    match config.site.site_type.parse::<SiteType>() {
+        Ok(site_type) if !info.supports(&site_type) => CheckResult::warn(
+            "Theme",
+            format!(
+                "'{}' is not designed for {} sites",
+                info.name, config.site.site_type
+            ),
+            "Run 'blogr theme list' to see themes for this site type",
+        ),
+        Err(e) => CheckResult::fail("Theme", e, "Set [site] site_type in blogr.toml"),
+        _ => CheckResult::pass("Theme", format!("{} v{}", info.name, info.version)),
+    }
+}
+
+fn check_port(port: u16) -> CheckResult {
+    match std::net::TcpListener::bind(("127.0.0.1", port)) {
+        Ok(_) => CheckResult::pass("Dev server port", format!("{} is available", port)),
+        Err(e) => CheckResult::warn(
+            "Dev server port",
+            format!("{} is not available ({})", port, e),
+            format!(
+                "Stop the process using port {}, or run 'blogr serve --port <other>'",
+                port
+            ),
+        ),
+    }
+}
+
+async fn check_github_token(config: &Config) -> CheckResult {
+    let Some(token) = EnvConfig::github_token() else {
+        let detail = "GITHUB_TOKEN / GH_TOKEN is not set";
+        let fix = "Create a token at https://github.com/settings/tokens with 'repo' scope and export GITHUB_TOKEN";
+        return if config.github.is_some() {
+            CheckResult::fail("GitHub token", detail, fix)
+        } else {
+            CheckResult::warn("GitHub token", detail, fix)
+        };
+    };
+
+    let client = match reqwest::Client::builder().timeout(NETWORK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            return CheckResult::warn(
+                "GitHub token",
+                format!("could not create HTTP client: {}", e),
+                "Check your TLS setup",
+            )
+        }
+    };
+
+    let response = client
+        .get("https://api.github.com/user")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Accept", "application/vnd.github.v3+json")
+        .header("User-Agent", "blogr-cli")
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => {
+            let login = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|user| user.get("login").and_then(|l| l.as_str()).map(String::from))
+                .unwrap_or_else(|| "unknown user".to_string());
+            CheckResult::pass(
+                "GitHub token",
+                format!("valid (authenticated as {})", login),
+            )
+        }
+        Ok(response) if response.status() == 401 => CheckResult::fail(
+            "GitHub token",
+            "token was rejected by GitHub (invalid or expired)",
+            "Generate a new token at https://github.com/settings/tokens and update GITHUB_TOKEN",
+        ),
+        Ok(response) => CheckResult::warn(
+            "GitHub token",
+            format!("GitHub API returned {}", response.status()),
+            "Try again later, or check https://www.githubstatus.com",
+        ),
+        Err(e) => CheckResult::warn(
+            "GitHub token",
+            format!("could not reach GitHub: {}", e),
+            "Check your network connection, or use --offline to skip network checks",
+        ),
+    }
+}
+
+async fn check_newsletter(config: &Config, offline: bool) -> Vec<CheckResult> {
+    if !config.newsletter.enabled {
+        return vec![CheckResult::skip("Newsletter", "disabled in blogr.toml")];
+    }
+
+    let mut results = Vec::new();
+
+    match &config.newsletter.smtp {
+        Some(smtp) => {
+            results.push(check_mail_server("SMTP server", &smtp.server, smtp.port, offline).await);
+            results.push(match &smtp.oauth {
+                Some(provider) => check_oauth_sign_in("SMTP sign-in", provider, &smtp.username),
+                None => check_password_env("SMTP password", "NEWSLETTER_SMTP_PASSWORD"),
+            });
+        }
+        None => results.push(CheckResult::warn(
+            "SMTP server",
+            "not configured, newsletters cannot be sent",
+            "Add a [newsletter.smtp] section with server, port and username",
+        )),
+    }
+
+    match &config.newsletter.imap {
+        Some(imap) => {
+            results.push(check_mail_server("IMAP server", &imap.server, imap.port, offline).await);
+            results.push(match &imap.oauth {
+                Some(provider) => check_oauth_sign_in("IMAP sign-in", provider, &imap.username),
+                None => check_password_env("IMAP password", "NEWSLETTER_IMAP_PASSWORD"),
+            });
+        }
+        None => results.push(CheckResult::warn(
+            "IMAP server",
+            "not configured, subscribers cannot be fetched by email",
+            "Add a [newsletter.imap] section, or import subscribers with 'blogr newsletter import'",
+        )),
+    }
+
+    results
+}
+
+async fn check_mail_server(
+    name: &'static str,
+    server: &str,
+    port: u16,
+    offline: bool,
+) -> CheckResult {
+    if offline {
+        return CheckResult::skip(name, "skipped (--offline)");
+    }
+
+    let address = format!("{}:{}", server, port);
+    match tokio::time::timeout(NETWORK_TIMEOUT, tokio::net::TcpStream::connect(&address)).await
+    {
+        Ok(Ok(_)) => CheckResult::pass(name, format!("{} is reachable", address)),
+        Ok(Err(e)) => CheckResult::fail(
+            name,
+            format!("could not connect to {}: {}", address, e),
+            "Check the server name and port, and that your network allows outgoing mail connections",
+        ),
+        Err(_) => CheckResult::fail(
+            name,
+            format!("timed out connecting to {}", address),
+            "Check the server name and port; some networks block mail ports",
+        ),
+    }
+}
+
+fn check_oauth_sign_in(name: &'static str, provider: &str, username: &str) -> CheckResult {
+    match crate::newsletter::oauth::stored_token(provider, username) {
+        Ok(Some(_)) => CheckResult::pass(name, format!("signed in with {}", provider)),
+        Ok(None) => CheckResult::fail(
+            name,
+            format!("{} is not signed in with {}", username, provider),
+            "Run 'blogr newsletter login'",
+        ),
+        Err(e) => CheckResult::warn(
+            name,
+            e.to_string(),
+            "Check that the OS keychain is available, then run 'blogr newsletter login'",
+        ),
+    }
+}
+
+fn check_password_env(name: &'static str, var: &str) -> CheckResult {
+    if std::env::var(var).is_ok() {
+        CheckResult::pass(name, format!("{} is set", var))
+    } else {
+        CheckResult::fail(
+            name,
+            format!("{} is not set", var),
+            format!("Export {} (or add it to a .env file in the project)", var),
+        )
+    }
+}
@@ -0,0 +1,185 @@
+use crate::content::{PostManager, PostStatus};
+use crate::draft_sync::{conflict_path, content_hash, plan, Action, DraftStore, Drafts, SyncState};
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path};
+
+pub async fn handle_sync(pull_only: bool, push_only: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let mut config = project.load_config()?;
+    let (pull, push) = (!push_only, !pull_only);
+
+    let store = match config.drafts.sync.as_str() {
+        "gist" => DraftStore::Gist {
+            id: config.drafts.gist_id.clone(),
+        },
+        _ => DraftStore::Branch {
+            repo_root: project.root.clone(),
+            branch: config.drafts.branch.clone(),
+        },
+    };
+    Console::info(&format!("Syncing drafts with {}...", store.describe()));
+
+    let posts_dir = project.posts_dir();
+    let mut local = Drafts::new();
+    let mut published = BTreeSet::new();
+    for post in PostManager::new(&posts_dir).load_all_posts()? {
+        let name = draft_name(&posts_dir, &post.file_path)?;
+        if post.metadata.status == PostStatus::Draft {
+            let content = fs::read_to_string(&post.file_path)
+                .with_context(|| format!("Failed to read {}", post.file_path.display()))?;
+            local.insert(name, content);
+        } else {
+            published.insert(name);
+        }
+    }
+
+    let remote = store.fetch().await?;
+    let mut state = SyncState::load(&project.root)?;
+    let resolved: BTreeSet<String> = state
+        .conflicts
+        .iter()
+        .filter(|name| !conflict_path(&project.root, name).exists())
+        .cloned()
+        .collect();
+    let actions = plan(&local, &remote, &published, &state, &resolved);
+
+    // Push first, so nothing changes locally if the remote rejects it
+    let mut changes = BTreeMap::new();
+    if push {
+        for (name, action) in &actions {
+            match action {
+                Action::Push(content) => {
+                    changes.insert(name.clone(), Some(content.clone()));
+                }
+                Action::RemoveRemote => {
+                    changes.insert(name.clone(), None);
+                }
+                _ => {}
+            }
+        }
+    }
+    if !changes.is_empty() {
+        if let Some(gist_id) = store.store(&remote, &changes).await? {
+            config.drafts.gist_id = Some(gist_id.clone());
+            config.save_to_file(project.root.join("blogr.toml"))?;
+            Console::info(&format!("Created private gist {}", gist_id));
+        }
+    }
+
+    let (mut pulled, mut pushed, mut conflicts) = (0, 0, 0);
+    for (name, action) in actions {
+        if !matches!(action, Action::Unresolved | Action::Conflict(_)) {
+            state.conflicts.remove(&name);
+        }
+
+        match action {
+            Action::InSync => match local.get(&name) {
+                Some(content) => {
+                    state.synced.insert(name, content_hash(content));
+                }
+                None => {
+                    state.synced.remove(&name);
+                }
+            },
+            Action::Pull(content) if pull => {
+                let path = posts_dir.join(checked_name(&name)?);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, &content)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                println!("  ⬇ {}", name);
+                state.synced.insert(name, content_hash(&content));
+                pulled += 1;
+            }
+            Action::RemoveLocal if pull => {
+                let path = posts_dir.join(checked_name(&name)?);
+                if path.exists() {
+                    fs::remove_file(&path)
+                        .with_context(|| format!("Failed to remove {}", path.display()))?;
+                }
+                println!("  ✗ {} (removed or published on another machine)", name);
+                state.synced.remove(&name);
+                pulled += 1;
+            }
+            Action::Push(content) if push => {
+                println!("  ⬆ {}", name);
+                state.synced.insert(name, content_hash(&content));
+                pushed += 1;
+            }
+            Action::RemoveRemote if push => {
+                println!("  ✗ {} (removed from the remote copy)", name);
+                state.synced.remove(&name);
+                pushed += 1;
+            }
+            Action::Conflict(content) => {
+                let path = conflict_path(&project.root, checked_name(&name)?);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, content)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                Console::warn(&format!(
+                    "Conflict: {} changed here and remotely; the remote version is in {}",
+                    name,
+                    path.display()
+                ));
+                state.conflicts.insert(name);
+                conflicts += 1;
+            }
+            Action::Unresolved => {
+                Console::warn(&format!(
+                    "Skipping {}: merge and delete {} to resolve the conflict",
+                    name,
+                    conflict_path(&project.root, &name).display()
+                ));
+                conflicts += 1;
+            }
+            // Skipped by --pull or --push
+            _ => {}
+        }
+    }
+    state.save(&project.root)?;
+
+    if conflicts > 0 {
+        return Err(anyhow!(
+            "{} draft(s) have conflicts. Merge the copies in .blogr/drafts-conflicts, delete them and sync again",
+            conflicts
+        ));
+    }
+    Console::success(&format!(
+        "Drafts in sync ({} pulled, {} pushed)",
+        pulled, pushed
+    ));
+    Ok(())
+}
+
+/// Path of a post relative to the posts directory, with `/` separators
+fn draft_name(posts_dir: &Path, path: &Path) -> Result<String> {
+    let relative = path
+        .strip_prefix(posts_dir)
+        .with_context(|| format!("{} is outside the posts directory", path.display()))?;
+    Ok(relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// Refuse remote names that would write outside the posts directory
+fn checked_name(name: &str) -> Result<&str> {
+    let path = Path::new(name);
+    if !name.ends_with(".md")
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(anyhow!("Refusing to sync unexpected file '{}'", name));
+    }
+    Ok(name)
+}
@@ -1,4 +1,5 @@
 use crate::content::PostManager;
+use crate::history::PostHistory;
 use crate::project::Project;
 use crate::utils::Console;
 use anyhow::{anyhow, Result};
@@ -11,7 +12,7 @@ pub async fn handle_edit(slug: String, use_tui: bool) -> Result<()> {
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
-    let post_manager = PostManager::new(project.posts_dir());
+    let post_manager = PostManager::new(project.posts_dir()).with_history(&project.root);
 
     // Find the post by slug
     let post = post_manager
@@ -27,7 +28,6 @@ pub async fn handle_edit(slug: String, use_tui: bool) -> Result<()> {
         let edited_post = tui_launcher::launch_editor(post, &project).await?;
 
         // Save the edited post
-        let post_manager = PostManager::new(project.posts_dir());
         let final_file_path = post_manager.save_post(&edited_post)?;
 
         Console::success("Post edited and saved!");
@@ -37,7 +37,7 @@ pub async fn handle_edit(slug: String, use_tui: bool) -> Result<()> {
         println!("  • List all posts: blogr list");
     } else {
         // Use external editor
-        let file_path = project.posts_dir().join(post.filename());
+        let file_path = post_manager.post_path(&post);
 
         // Try to determine the best editor to use
         let editor = std::env::var("EDITOR")
@@ -58,6 +58,10 @@ pub async fn handle_edit(slug: String, use_tui: bool) -> Result<()> {
         Console::info(&format!("Opening with editor: {}", editor));
         println!("📝 File: {}", file_path.display());
 
+        // Keep the text from before editing in the post history
+        let history = PostHistory::new(&project.root);
+        history.snapshot_file(&slug, &file_path)?;
+
         // Open the file in the editor
         let status = Command::new(&editor)
             .arg(&file_path)
@@ -65,6 +69,7 @@ pub async fn handle_edit(slug: String, use_tui: bool) -> Result<()> {
             .map_err(|e| anyhow!("Failed to open editor '{}': {}", editor, e))?;
 
         if status.success() {
+            history.snapshot_file(&slug, &file_path)?;
             Console::success("Post editing completed");
             println!("💡 Next steps:");
             println!("  • Preview changes: blogr serve");
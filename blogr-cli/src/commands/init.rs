@@ -1,11 +1,15 @@
 use anyhow::{Context, Result};
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 
 use crate::config::EnvConfig;
 use crate::project::Project;
+use crate::starter::StarterKit;
+use crate::tui::init_wizard::WizardResult;
+use crate::tui_launcher;
 use crate::utils::{Console, Utils};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_init(
     name: Option<String>,
     path: Option<PathBuf>,
@@ -13,14 +17,9 @@ pub async fn handle_init(
     github_repo: Option<String>,
     no_github: bool,
     personal: bool,
+    hybrid: bool,
+    template: Option<StarterKit>,
 ) -> Result<()> {
-    if personal {
-        Console::info("Initializing new personal website...");
-    } else {
-        Console::info("Initializing new Blogr project...");
-    }
-    println!();
-
     // Determine project path
     let project_path = path.unwrap_or_else(|| std::env::current_dir().unwrap());
 
@@ -29,6 +28,33 @@ pub async fn handle_init(
         anyhow::bail!("Already inside a Blogr project. Cannot initialize a new project here.");
     }
 
+    // Walk through the setup wizard when run without arguments in a terminal
+    let no_arguments = name.is_none()
+        && github_username.is_none()
+        && github_repo.is_none()
+        && !no_github
+        && !personal
+        && !hybrid
+        && template.is_none();
+    if no_arguments && io::stdin().is_terminal() && io::stdout().is_terminal() {
+        return handle_init_wizard(project_path).await;
+    }
+
+    let personal = personal || template.is_some_and(StarterKit::is_personal);
+    if let Some(template) = template {
+        Console::info(&format!(
+            "Initializing new project from the {} starter...",
+            template.name()
+        ));
+    } else if personal {
+        Console::info("Initializing new personal website...");
+    } else if hybrid {
+        Console::info("Initializing new personal website with a blog...");
+    } else {
+        Console::info("Initializing new Blogr project...");
+    }
+    crate::status!();
+
     // Interactive mode if no name provided
     let project_name = if let Some(name) = name {
         name
@@ -61,7 +87,7 @@ pub async fn handle_init(
 
     // Get description
     let description = {
-        if personal {
+        if personal || hybrid {
             print!("Website description: ");
         } else {
             print!("Blog description: ");
@@ -71,7 +97,7 @@ pub async fn handle_init(
         io::stdin().read_line(&mut input)?;
         let input = input.trim();
         if input.is_empty() {
-            if personal {
+            if personal || hybrid {
                 format!("Personal website of {author}")
             } else {
                 format!("A blog by {author}")
@@ -156,7 +182,18 @@ pub async fn handle_init(
     Console::step(1, 5, "Creating project structure...");
 
     // Initialize the project
-    let project = if personal {
+    let project = if let Some(template) = template {
+        Project::init_from_starter(
+            &project_path,
+            project_name.clone(),
+            author.clone(),
+            description.clone(),
+            final_github_username.clone(),
+            final_github_repo.clone(),
+            template,
+        )
+        .with_context(|| "Failed to initialize project")?
+    } else if personal {
         Project::init_personal(
             &project_path,
             project_name.clone(),
@@ -166,6 +203,16 @@ pub async fn handle_init(
             final_github_repo.clone(),
         )
         .with_context(|| "Failed to initialize personal website")?
+    } else if hybrid {
+        Project::init_hybrid(
+            &project_path,
+            project_name.clone(),
+            author.clone(),
+            description.clone(),
+            final_github_username.clone(),
+            final_github_repo.clone(),
+        )
+        .with_context(|| "Failed to initialize hybrid site")?
     } else {
         Project::init(
             &project_path,
@@ -178,6 +225,74 @@ pub async fn handle_init(
         .with_context(|| "Failed to initialize project")?
     };
 
+    let theme_message = if let Some(template) = template {
+        format!(
+            "{} starter configured with the {} theme",
+            template.name(),
+            template.theme()
+        )
+    } else if personal {
+        "Dark Minimal theme configured".to_string()
+    } else {
+        "Minimal Retro theme configured".to_string()
+    };
+
+    finish_init(
+        &project,
+        &project_name,
+        &description,
+        final_github_username,
+        final_github_repo,
+        &theme_message,
+    )
+    .await
+}
+
+async fn handle_init_wizard(project_path: PathBuf) -> Result<()> {
+    let config = match tui_launcher::launch_init_wizard().await? {
+        WizardResult::Create(config) => *config,
+        WizardResult::Cancelled => {
+            Console::info("Setup cancelled");
+            return Ok(());
+        }
+    };
+
+    Console::step(1, 5, "Creating project structure...");
+    let project_name = config.blog.title.clone();
+    let description = config.blog.description.clone();
+    let (github_username, github_repo) = match &config.github {
+        Some(github) => (
+            Some(github.username.clone()),
+            Some(github.repository.clone()),
+        ),
+        None => (None, None),
+    };
+    let theme_message = format!("{} theme configured", config.theme.name);
+    let project = Project::init_with_config(&project_path, config)
+        .with_context(|| "Failed to initialize project")?;
+
+    finish_init(
+        &project,
+        &project_name,
+        &description,
+        github_username,
+        github_repo,
+        &theme_message,
+    )
+    .await
+}
+
+/// Git setup, GitHub repository creation and the closing summary
+async fn finish_init(
+    project: &Project,
+    project_name: &str,
+    description: &str,
+    final_github_username: Option<String>,
+    final_github_repo: Option<String>,
+    theme_message: &str,
+) -> Result<()> {
+    let personal = project.config.site.site_type == "personal";
+
     Console::step(2, 5, "Initializing Git repository...");
 
     // Initialize git repository
@@ -200,18 +315,13 @@ pub async fn handle_init(
 
     Console::step(3, 5, "Setting up theme...");
 
-    // Theme is already set up in project initialization
-    if personal {
-        Console::success("Dark Minimal theme configured");
-    } else {
-        Console::success("Minimal Retro theme configured");
-    }
+    Console::success(theme_message);
 
     // GitHub repository creation
     if let (Some(username), Some(repo)) = (&final_github_username, &final_github_repo) {
         Console::step(4, 5, "Creating GitHub repository...");
 
-        match create_github_repository(username, repo, &description).await {
+        match create_github_repository(username, repo, description).await {
             Ok(()) => {
                 Console::success(&format!("GitHub repository created: {}/{}", username, repo));
 
@@ -248,13 +358,13 @@ pub async fn handle_init(
     if !issues.is_empty() {
         Console::warn("Project validation found some issues:");
         for issue in issues {
-            println!("  - {}", issue);
+            crate::status!("  - {}", issue);
         }
     } else {
         Console::success("Project validation passed");
     }
 
-    println!();
+    crate::status!();
     if personal {
         Console::success(&format!(
             "🎉 Successfully initialized personal website '{project_name}'!"
@@ -262,34 +372,39 @@ pub async fn handle_init(
     } else {
         Console::success(&format!("🎉 Successfully initialized '{project_name}'!"));
     }
-    println!();
+    crate::status!();
 
     // Show next steps
-    println!("📁 Project created in: {}", project.root.display());
-    println!();
-    println!("🚀 Next steps:");
-    println!("  1. cd {}", project.root.display());
-    println!("  2. blogr serve          # Start development server");
+    crate::status!("📁 Project created in: {}", project.root.display());
+    crate::status!();
+    crate::status!("🚀 Next steps:");
+    crate::status!("  1. cd {}", project.root.display());
+    crate::status!("  2. blogr serve          # Start development server");
     if !personal {
-        println!("  3. blogr new \"My Post\" # Create your first post");
-        println!("  4. blogr build          # Build the static site");
+        crate::status!("  3. blogr new \"My Post\" # Create your first post");
+        crate::status!("  4. blogr build          # Build the static site");
     } else {
-        println!("  3. Edit blogr.toml      # Customize your website");
-        println!("  4. blogr build          # Build the static site");
+        crate::status!("  3. Edit blogr.toml      # Customize your website");
+        crate::status!("  4. blogr build          # Build the static site");
     }
-    println!();
+    crate::status!();
 
     if let (Some(username), Some(repo)) = (&final_github_username, &final_github_repo) {
-        println!("🌐 Your blog will be available at:");
-        println!("   https://{}.github.io/{}", username, repo);
-        println!();
+        crate::status!("🌐 Your blog will be available at:");
+        crate::status!("   https://{}.github.io/{}", username, repo);
+        crate::status!();
     }
 
-    println!("📚 Learn more:");
-    println!("  - Run 'blogr --help' for available commands");
-    println!("  - Edit 'blogr.toml' to customize your blog");
-    println!("  - Add posts to the 'posts/' directory");
-    println!();
+    crate::status!("📚 Learn more:");
+    crate::status!("  - Run 'blogr --help' for available commands");
+    crate::status!("  - Edit 'blogr.toml' to customize your blog");
+    crate::status!("  - Add posts to the 'posts/' directory");
+    if project.config.site.site_type == "hybrid" {
+        crate::status!(
+            "  - Edit 'content.md' for the landing page; the blog is published under /blog/"
+        );
+    }
+    crate::status!();
 
     Ok(())
 }
@@ -7,6 +7,7 @@ use chrono::{DateTime, Local};
 pub async fn handle_list(
     drafts_only: bool,
     published_only: bool,
+    status_filter: Option<PostStatus>,
     tag_filter: Option<String>,
     sort_order: String,
 ) -> Result<()> {
@@ -14,6 +15,7 @@ pub async fn handle_list(
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
+    let config = project.load_config()?;
     let post_manager = PostManager::new(project.posts_dir());
     let mut posts = post_manager.load_all_posts()?;
 
@@ -22,6 +24,8 @@ pub async fn handle_list(
         posts.retain(|p| p.metadata.status == PostStatus::Draft);
     } else if published_only {
         posts.retain(|p| p.metadata.status == PostStatus::Published);
+    } else if let Some(status) = status_filter {
+        posts.retain(|p| p.metadata.status == status);
     }
 
     if let Some(tag) = &tag_filter {
@@ -42,6 +46,26 @@ pub async fn handle_list(
         }
     }
 
+    if Console::is_json() {
+        let posts: Vec<serde_json::Value> = posts
+            .iter()
+            .map(|post| {
+                serde_json::json!({
+                    "slug": post.metadata.slug,
+                    "title": post.metadata.title,
+                    "status": post.metadata.status.to_string(),
+                    "date": post.metadata.date.to_rfc3339(),
+                    "author": post.metadata.author,
+                    "tags": post.metadata.tags,
+                    "description": post.metadata.description,
+                    "featured": post.metadata.featured,
+                    "reading_time_minutes": post.reading_time(&config).minutes,
+                })
+            })
+            .collect();
+        return Console::report("list", serde_json::json!({ "posts": posts }));
+    }
+
     if posts.is_empty() {
         let filter_msg = if let Some(tag) = tag_filter {
             format!(" with tag '{}'", tag)
@@ -49,6 +73,8 @@ pub async fn handle_list(
             " (drafts only)".to_string()
         } else if published_only {
             " (published only)".to_string()
+        } else if let Some(status) = status_filter {
+            format!(" with status '{}'", status)
         } else {
             String::new()
         };
@@ -65,6 +91,8 @@ pub async fn handle_list(
         " (drafts)".to_string()
     } else if published_only {
         " (published)".to_string()
+    } else if let Some(status) = status_filter {
+        format!(" ({})", status)
     } else {
         String::new()
     };
@@ -74,10 +102,7 @@ pub async fn handle_list(
 
     // Display posts
     for (i, post) in posts.iter().enumerate() {
-        let status_icon = match post.metadata.status {
-            PostStatus::Published => "✅",
-            PostStatus::Draft => "📝",
-        };
+        let status_icon = post.metadata.status.icon();
 
         let featured_icon = if post.metadata.featured { "⭐" } else { "  " };
 
@@ -93,11 +118,17 @@ pub async fn handle_list(
             post.metadata.title
         );
 
+        let date_label = if post.metadata.status == PostStatus::Scheduled {
+            "goes live "
+        } else {
+            ""
+        };
         println!(
-            "      📅 {} | 👤 {} | ⏱️  {} min read",
+            "      📅 {}{} | 👤 {} | ⏱️  {} min read",
+            date_label,
             date_str,
             post.metadata.author,
-            post.reading_time()
+            post.reading_time(&config).minutes
         );
 
         if !post.metadata.tags.is_empty() {
@@ -119,6 +150,7 @@ pub async fn handle_list(
     println!("💡 Commands:");
     println!("  • Edit a post: blogr edit <slug>");
     println!("  • Delete a post: blogr delete <slug>");
+    println!("  • Publish or schedule a post: blogr publish <slug> [--at <time>]");
     println!("  • Create new post: blogr new \"Title\"");
     if posts.iter().any(|p| p.metadata.status == PostStatus::Draft) {
         println!("  • List only drafts: blogr list --drafts");
@@ -129,6 +161,13 @@ pub async fn handle_list(
     {
         println!("  • List only published: blogr list --published");
     }
+    if let Some(status) = posts
+        .iter()
+        .map(|p| p.metadata.status)
+        .find(|status| !matches!(status, PostStatus::Draft | PostStatus::Published))
+    {
+        println!("  • List by status: blogr list --status {}", status);
+    }
 
     Ok(())
 }
@@ -1,15 +1,28 @@
+pub mod activitypub;
+pub mod announce;
+pub mod bench;
 pub mod build;
+pub mod cache;
+pub mod check;
+pub mod completions;
 pub mod config;
+pub mod crosspost;
 pub mod delete;
 pub mod deploy;
+pub mod doctor;
+pub mod drafts;
 pub mod edit;
 pub mod init;
 pub mod list;
 pub mod new;
 pub mod newsletter;
+pub mod post;
 pub mod project;
+pub mod publish;
+pub mod restore;
 pub mod serve;
 pub mod theme;
+pub mod webmention;
 pub use project as project_cmd;
 
 // Specific exports when needed for command handlers
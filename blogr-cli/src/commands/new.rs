@@ -1,7 +1,11 @@
-use crate::content::{Post, PostManager, PostStatus};
+use crate::content::{Post, PostManager, PostStatus, BUNDLE_INDEX};
+use crate::generator::pages::load_pages;
+use crate::generator::slugs::check_collisions;
 use crate::project::Project;
 use crate::utils::Console;
 use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::path::Path;
 
 pub async fn handle_new(
     title: String,
@@ -9,6 +13,7 @@ pub async fn handle_new(
     draft: bool,
     slug: Option<String>,
     tags: Option<String>,
+    bundle: bool,
     use_tui: bool,
 ) -> Result<()> {
     Console::info(&format!("Creating new post: '{}'", title));
@@ -31,24 +36,64 @@ pub async fn handle_new(
         PostStatus::Published
     };
 
+    let date = Utc::now();
+    let slug = slug.unwrap_or_else(|| Post::slug_from_title(&title, date, &config.slugs));
+    if slug.is_empty() {
+        return Err(anyhow!(
+            "Can't make a slug from '{}'; choose one with --slug",
+            title
+        ));
+    }
+
     // Create new post
-    let post = Post::new(
+    let mut post = Post::new(
         title.clone(),
         config.blog.author.clone(),
         None, // Will use default description
         tags,
-        slug,
+        Some(slug),
         status,
     );
+    post.metadata.date = date;
+    if bundle {
+        post.file_path = Path::new(&post.metadata.slug).join(BUNDLE_INDEX);
+    }
+
+    // Refuse to overwrite another post's file or output
+    let post_manager = PostManager::new(project.posts_dir()).with_history(&project.root);
+    let mut posts = post_manager.load_all_posts()?;
+    if let Some(existing) = posts
+        .iter()
+        .find(|p| p.metadata.slug.eq_ignore_ascii_case(&post.metadata.slug))
+    {
+        return Err(anyhow!(
+            "Post '{}' already uses the slug '{}' ({}); choose another with --slug",
+            existing.metadata.title,
+            existing.metadata.slug,
+            existing.file_path.display()
+        ));
+    }
+    let file_path = post_manager.post_path(&post);
+    if file_path.exists() {
+        return Err(anyhow!(
+            "{} already exists; choose another slug with --slug",
+            file_path.display()
+        ));
+    }
+    let pages = match config.site.site_type.as_str() {
+        "personal" | "hybrid" => load_pages(&project.root.join("content"))?,
+        _ => Vec::new(),
+    };
+    posts.push(post.clone());
+    check_collisions(&config, &posts, &pages)?;
 
     // Save the post
-    let post_manager = PostManager::new(project.posts_dir());
     let file_path = post_manager.save_post(&post)?;
 
     Console::success(&format!("Created new post: '{}'", title));
     println!("📝 Post saved to: {}", file_path.display());
     println!("🏷️  Slug: {}", post.metadata.slug);
-    println!("📊 Status: {:?}", post.metadata.status);
+    println!("📊 Status: {}", post.metadata.status);
 
     if !post.metadata.tags.is_empty() {
         println!("🏷️  Tags: {}", post.metadata.tags.join(", "));
@@ -75,7 +120,10 @@ pub async fn handle_new(
         println!("  • Edit externally: blogr edit {}", post.metadata.slug);
         println!("  • Start dev server: blogr serve");
         if post.metadata.status == PostStatus::Draft {
-            println!("  • Publish when ready: change status to 'published' in frontmatter");
+            println!(
+                "  • Publish when ready: blogr publish {}",
+                post.metadata.slug
+            );
         }
     }
 
@@ -1,17 +1,31 @@
 //! Newsletter command handlers
 
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::io::{self, Write};
 
+use crate::newsletter::auto::{AutoLock, AutoState};
+use crate::newsletter::growth::{self, GrowthPeriod};
+use crate::newsletter::hygiene::{self, Issue};
+use crate::newsletter::oauth::{self, OAuthProvider};
 use crate::newsletter::{
-    ApiConfig, MigrationConfig, MigrationManager, MigrationSource, ModernApprovalApp,
-    NewsletterApiServer, NewsletterManager, PluginManager, SubscriberStatus,
+    check_plugin_config, AbTestOptions, ApiConfig, ComposeApp, ComposeResult, MigrationConfig,
+    MigrationManager, MigrationSource, ModernApprovalApp, NewsletterApiServer, NewsletterComposer,
+    NewsletterManager, PluginConfig, PluginManager, Segment, SubscriberStatus, Variant,
 };
 use crate::project::Project;
 use crate::tui;
+use crate::utils::{Console, Utils};
 
 /// Handle the fetch-subscribers command
-pub async fn handle_fetch_subscribers(interactive: bool) -> Result<()> {
+pub async fn handle_fetch_subscribers(interactive: bool, since: Option<String>) -> Result<()> {
+    let since = since
+        .map(|date| {
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid --since date '{}'. Use YYYY-MM-DD.", date))
+        })
+        .transpose()?;
+
     // Find the current project
     let project = Project::find_project()?
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
@@ -27,22 +41,22 @@ pub async fn handle_fetch_subscribers(interactive: bool) -> Result<()> {
 
     // Check if newsletter is enabled
     if !newsletter_manager.is_enabled() {
-        println!("Newsletter functionality is not enabled.");
-        println!("To enable it, add the following to your blogr.toml:");
-        println!();
-        println!("[newsletter]");
-        println!("enabled = true");
-        println!("subscribe_email = \"subscribe@yourdomain.com\"");
-        println!("sender_name = \"Your Blog Name\"");
-        println!();
-        println!("You'll also need to configure IMAP settings:");
-        println!();
-        println!("[newsletter.imap]");
-        println!("server = \"imap.gmail.com\"");
-        println!("port = 993");
-        println!("username = \"subscribe@yourdomain.com\"");
-        println!();
-        println!("And set the NEWSLETTER_IMAP_PASSWORD environment variable.");
+        crate::status!("Newsletter functionality is not enabled.");
+        crate::status!("To enable it, add the following to your blogr.toml:");
+        crate::status!();
+        crate::status!("[newsletter]");
+        crate::status!("enabled = true");
+        crate::status!("subscribe_email = \"subscribe@yourdomain.com\"");
+        crate::status!("sender_name = \"Your Blog Name\"");
+        crate::status!();
+        crate::status!("You'll also need to configure IMAP settings:");
+        crate::status!();
+        crate::status!("[newsletter.imap]");
+        crate::status!("server = \"imap.gmail.com\"");
+        crate::status!("port = 993");
+        crate::status!("username = \"subscribe@yourdomain.com\"");
+        crate::status!();
+        crate::status!("And set the NEWSLETTER_IMAP_PASSWORD environment variable.");
         return Ok(());
     }
 
@@ -51,26 +65,26 @@ pub async fn handle_fetch_subscribers(interactive: bool) -> Result<()> {
         .print_status()
         .context("Failed to print newsletter status")?;
 
-    println!();
+    crate::status!();
 
     // If interactive mode, offer to set up configuration
     if interactive {
         match newsletter_manager.get_imap_config()? {
             Some(_) => {
-                println!("IMAP configuration found. Proceeding with email fetch...");
+                crate::status!("IMAP configuration found. Proceeding with email fetch...");
             }
             None => {
-                println!("No IMAP configuration found.");
+                crate::status!("No IMAP configuration found.");
                 if prompt_yes_no("Would you like to set up IMAP configuration now?")? {
                     let imap_config = crate::newsletter::config::setup_imap_config()?;
-                    println!("IMAP configuration created. Please add it to your blogr.toml:");
-                    println!();
-                    println!("[newsletter.imap]");
-                    println!("server = \"{}\"", imap_config.server);
-                    println!("port = {}", imap_config.port);
-                    println!("username = \"{}\"", imap_config.username);
-                    println!();
-                    println!("Also set the NEWSLETTER_IMAP_PASSWORD environment variable and run the command again.");
+                    crate::status!("IMAP configuration created. Please add it to your blogr.toml:");
+                    crate::status!();
+                    crate::status!("[newsletter.imap]");
+                    crate::status!("server = \"{}\"", imap_config.server);
+                    crate::status!("port = {}", imap_config.port);
+                    crate::status!("username = \"{}\"", imap_config.username);
+                    crate::status!();
+                    crate::status!("Also set the NEWSLETTER_IMAP_PASSWORD environment variable and run the command again.");
                     return Ok(());
                 }
             }
@@ -78,12 +92,12 @@ pub async fn handle_fetch_subscribers(interactive: bool) -> Result<()> {
     }
 
     // Attempt to fetch subscribers
-    match newsletter_manager.fetch_subscribers(interactive) {
+    match newsletter_manager.fetch_subscribers(interactive, since) {
         Ok(()) => {
-            println!("✓ Newsletter subscriber fetch completed successfully.");
+            crate::status!("✓ Newsletter subscriber fetch completed successfully.");
 
             // Show updated statistics
-            println!("\nUpdated subscriber statistics:");
+            crate::status!("\nUpdated subscriber statistics:");
             let total = newsletter_manager.database().get_subscriber_count(None)?;
             let pending = newsletter_manager.database().get_subscriber_count(Some(
                 crate::newsletter::database::SubscriberStatus::Pending,
@@ -92,26 +106,26 @@ pub async fn handle_fetch_subscribers(interactive: bool) -> Result<()> {
                 crate::newsletter::database::SubscriberStatus::Approved,
             ))?;
 
-            println!("  Total: {}", total);
-            println!("  Pending: {}", pending);
-            println!("  Approved: {}", approved);
+            crate::status!("  Total: {}", total);
+            crate::status!("  Pending: {}", pending);
+            crate::status!("  Approved: {}", approved);
 
             if pending > 0 {
-                println!("\nNext steps:");
-                println!("  1. Run 'blogr newsletter approve' to review pending subscribers");
-                println!("  2. Run 'blogr newsletter list' to see all subscribers");
+                crate::status!("\nNext steps:");
+                crate::status!("  1. Run 'blogr newsletter approve' to review pending subscribers");
+                crate::status!("  2. Run 'blogr newsletter list' to see all subscribers");
             }
         }
         Err(e) => {
             eprintln!("Failed to fetch subscribers: {}", e);
 
             // Provide helpful troubleshooting information
-            println!("\nTroubleshooting tips:");
-            println!("1. Verify your IMAP credentials are correct");
-            println!("2. Check that NEWSLETTER_IMAP_PASSWORD environment variable is set");
-            println!("3. Ensure your email provider allows IMAP access");
-            println!("4. For Gmail, you may need to use an App Password instead of your regular password");
-            println!("5. Check your firewall and network connectivity");
+            crate::status!("\nTroubleshooting tips:");
+            crate::status!("1. Verify your IMAP credentials are correct");
+            crate::status!("2. Check that NEWSLETTER_IMAP_PASSWORD environment variable is set");
+            crate::status!("3. Ensure your email provider allows IMAP access");
+            crate::status!("4. For Gmail, you may need to use an App Password instead of your regular password");
+            crate::status!("5. Check your firewall and network connectivity");
 
             return Err(e);
         }
@@ -120,6 +134,73 @@ pub async fn handle_fetch_subscribers(interactive: bool) -> Result<()> {
     Ok(())
 }
 
+/// IMAP and SMTP accounts that sign in with OAuth2, as (provider, username)
+fn oauth_accounts(config: &crate::config::Config) -> Vec<(String, String)> {
+    let newsletter = &config.newsletter;
+    let imap = newsletter
+        .imap
+        .as_ref()
+        .and_then(|imap| Some((imap.oauth.clone()?, imap.username.clone())));
+    let smtp = newsletter
+        .smtp
+        .as_ref()
+        .and_then(|smtp| Some((smtp.oauth.clone()?, smtp.username.clone())));
+
+    let mut accounts: Vec<(String, String)> = imap.into_iter().chain(smtp).collect();
+    accounts.dedup();
+    accounts
+}
+
+/// Handle the login command - signs OAuth2 accounts in with the device flow
+pub async fn handle_login() -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+
+    let accounts = oauth_accounts(&config);
+    if accounts.is_empty() {
+        crate::status!("No newsletter account signs in with OAuth2.");
+        crate::status!("To use it, add to your blogr.toml:");
+        crate::status!();
+        crate::status!("[newsletter.imap]");
+        crate::status!("oauth = \"google\"");
+        crate::status!();
+        crate::status!("[newsletter.oauth.google]");
+        crate::status!("client_id = \"<your OAuth client ID>\"");
+        return Ok(());
+    }
+
+    for (name, username) in accounts {
+        let provider_config = config.newsletter.oauth.get(&name).ok_or_else(|| {
+            anyhow::anyhow!("No [newsletter.oauth.{}] section in blogr.toml", name)
+        })?;
+        let provider = OAuthProvider::from_config(&name, provider_config)?;
+        oauth::login(&provider, &username).await?;
+        crate::status!("✓ Signed in {} with {}", username, name);
+    }
+    Ok(())
+}
+
+/// Handle the logout command - removes stored OAuth2 tokens
+pub fn handle_logout() -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+
+    let accounts = oauth_accounts(&config);
+    if accounts.is_empty() {
+        crate::status!("No newsletter account signs in with OAuth2.");
+    }
+    for (name, username) in accounts {
+        if oauth::delete_token(&name, &username)? {
+            crate::status!("✓ Signed {} out of {}", username, name);
+        } else {
+            crate::status!("{} was not signed in with {}", username, name);
+        }
+    }
+    Ok(())
+}
+
 /// Handle the approve command - launches the modern TUI approval interface
 pub fn handle_approve() -> Result<()> {
     // Find the current project
@@ -137,13 +218,13 @@ pub fn handle_approve() -> Result<()> {
 
     // Check if newsletter is enabled
     if !newsletter_manager.is_enabled() {
-        println!("Newsletter functionality is not enabled.");
-        println!("To enable it, add the following to your blogr.toml:");
-        println!();
-        println!("[newsletter]");
-        println!("enabled = true");
-        println!("subscribe_email = \"subscribe@yourdomain.com\"");
-        println!("sender_name = \"Your Blog Name\"");
+        crate::status!("Newsletter functionality is not enabled.");
+        crate::status!("To enable it, add the following to your blogr.toml:");
+        crate::status!();
+        crate::status!("[newsletter]");
+        crate::status!("enabled = true");
+        crate::status!("subscribe_email = \"subscribe@yourdomain.com\"");
+        crate::status!("sender_name = \"Your Blog Name\"");
         return Ok(());
     }
 
@@ -153,8 +234,8 @@ pub fn handle_approve() -> Result<()> {
     // Check if there are any subscribers
     let total_count = database.get_subscriber_count(None)?;
     if total_count == 0 {
-        println!("No subscribers found.");
-        println!(
+        crate::status!("No subscribers found.");
+        crate::status!(
             "Run 'blogr newsletter fetch-subscribers' first to import subscribers from email."
         );
         return Ok(());
@@ -215,7 +296,99 @@ pub fn handle_approve() -> Result<()> {
     }
 
     tui.exit()?;
-    println!("✨ Newsletter approval session completed!");
+    crate::status!("✨ Newsletter approval session completed!");
+    Ok(())
+}
+
+/// Handle the compose command: write, preview and send a newsletter in a TUI
+pub fn handle_compose(template: Option<String>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
+
+    if !newsletter_manager.is_enabled() {
+        crate::status!("❌ Newsletter functionality is not enabled.");
+        return Ok(());
+    }
+
+    let theme = project
+        .load_theme(&config.theme.name)?
+        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+    let composer = newsletter_manager.create_composer(theme, template.as_deref())?;
+    let subscribers = newsletter_manager.database().get_subscribers(None)?;
+
+    let mut tui = tui::init()?;
+    tui.init()?;
+
+    let mut app = ComposeApp::new(composer, subscribers);
+    let submitted = loop {
+        if app.update() || app.needs_redraw() {
+            tui.draw_compose(&mut app)?;
+            app.mark_redrawn();
+        }
+
+        match tui.events.next()? {
+            crate::tui::Event::Key(key_event) => match app.handle_key_event(key_event)? {
+                ComposeResult::Continue => {}
+                ComposeResult::Quit => break false,
+                ComposeResult::TestSend(email) => {
+                    // The sender prints progress, so repaint the whole screen afterwards
+                    let result = app.newsletter().and_then(|newsletter| {
+                        newsletter_manager.send_test_newsletter(&newsletter, &email, false)
+                    });
+                    tui.clear()?;
+                    match result {
+                        Ok(()) => {
+                            app.set_status_message(&format!("✅ Test email sent to {}", email))
+                        }
+                        Err(e) => app.set_status_message(&format!("❌ Test email failed: {:#}", e)),
+                    }
+                }
+                ComposeResult::Submit => {
+                    let schedule = app.draft().schedule;
+                    match schedule.as_deref().map(Utils::parse_local_time).transpose() {
+                        Ok(Some(at)) if at <= chrono::Utc::now() => {
+                            app.set_status_message("The scheduled time is in the past")
+                        }
+                        Ok(_) => break true,
+                        Err(e) => app.set_status_message(&e.to_string()),
+                    }
+                }
+            },
+            crate::tui::Event::Resize(_, _) | crate::tui::Event::Redraw => app.mark_for_redraw(),
+            crate::tui::Event::Tick | crate::tui::Event::Mouse(_) => {}
+        }
+
+        if !app.running {
+            break false;
+        }
+    };
+
+    tui.exit()?;
+
+    if !submitted {
+        crate::status!("Newsletter composition cancelled.");
+        return Ok(());
+    }
+
+    let draft = app.draft();
+    let newsletter = app.newsletter()?;
+    let segments = Segment::parse_all(&draft.segments)?;
+
+    if let Some(at) = draft.schedule {
+        return schedule_newsletter(&newsletter_manager, &newsletter, &at, &segments, false);
+    }
+
+    crate::status!("📤 Sending newsletter '{}'...", newsletter.subject);
+    let report = newsletter_manager.send_newsletter(&newsletter, false, &segments)?;
+
+    crate::status!("✅ Newsletter sending completed!");
+    crate::status!("📊 Success rate: {:.1}%", report.success_rate() * 100.0);
+
     Ok(())
 }
 
@@ -236,7 +409,7 @@ pub fn handle_list(status_filter: Option<String>) -> Result<()> {
 
     // Check if newsletter is enabled
     if !newsletter_manager.is_enabled() {
-        println!("Newsletter functionality is not enabled.");
+        crate::status!("Newsletter functionality is not enabled.");
         return Ok(());
     }
 
@@ -252,12 +425,36 @@ pub fn handle_list(status_filter: Option<String>) -> Result<()> {
     // Get subscribers
     let subscribers = database.get_subscribers(status)?;
 
+    if Console::is_json() {
+        let mut counts = serde_json::Map::new();
+        counts.insert(
+            "total".to_string(),
+            database.get_subscriber_count(None)?.into(),
+        );
+        for status in [
+            SubscriberStatus::Pending,
+            SubscriberStatus::Approved,
+            SubscriberStatus::Declined,
+        ] {
+            counts.insert(
+                status.to_string(),
+                database.get_subscriber_count(Some(status))?.into(),
+            );
+        }
+        return Console::report(
+            "newsletter list",
+            serde_json::json!({ "subscribers": subscribers, "counts": counts }),
+        );
+    }
+
     if subscribers.is_empty() {
-        println!("No subscribers found.");
+        crate::status!("No subscribers found.");
         if status_filter.is_some() {
-            println!("Try running without a status filter to see all subscribers.");
+            crate::status!("Try running without a status filter to see all subscribers.");
         } else {
-            println!("Run 'blogr newsletter fetch-subscribers' to import subscribers from email.");
+            crate::status!(
+                "Run 'blogr newsletter fetch-subscribers' to import subscribers from email."
+            );
         }
         return Ok(());
     }
@@ -265,10 +462,10 @@ pub fn handle_list(status_filter: Option<String>) -> Result<()> {
     // Print header
     println!();
     println!(
-        "{:<5} {:<30} {:<10} {:<20} {:<15}",
+        "{:<5} {:<30} {:<10} {:<20} {:<16} Tags",
         "ID", "Email", "Status", "Subscribed", "Approved"
     );
-    println!("{}", "-".repeat(85));
+    println!("{}", "-".repeat(100));
 
     // Print subscribers
     for subscriber in &subscribers {
@@ -278,12 +475,13 @@ pub fn handle_list(status_filter: Option<String>) -> Result<()> {
             .unwrap_or_else(|| "-".to_string());
 
         println!(
-            "{:<5} {:<30} {:<10} {:<20} {:<15}",
+            "{:<5} {:<30} {:<10} {:<20} {:<16} {}",
             subscriber.id.unwrap_or(0),
             subscriber.email,
-            subscriber.status,
+            subscriber.status.to_string(),
             subscriber.subscribed_at.format("%Y-%m-%d %H:%M"),
-            approved_str
+            approved_str,
+            subscriber.tags.join(", ")
         );
     }
 
@@ -323,7 +521,7 @@ pub fn handle_remove(email: &str, force: bool) -> Result<()> {
 
     // Check if newsletter is enabled
     if !newsletter_manager.is_enabled() {
-        println!("Newsletter functionality is not enabled.");
+        crate::status!("Newsletter functionality is not enabled.");
         return Ok(());
     }
 
@@ -342,19 +540,169 @@ pub fn handle_remove(email: &str, force: bool) -> Result<()> {
             );
 
             if !force && !prompt_yes_no(&format!("Remove subscriber '{}'?", email))? {
-                println!("Operation cancelled.");
+                crate::status!("Operation cancelled.");
                 return Ok(());
             }
 
             if database.remove_subscriber(email)? {
-                println!("✓ Subscriber '{}' has been removed.", email);
+                crate::status!("✓ Subscriber '{}' has been removed.", email);
             } else {
-                println!("Failed to remove subscriber '{}'.", email);
+                crate::status!("Failed to remove subscriber '{}'.", email);
             }
         }
         None => {
-            println!("Subscriber '{}' not found.", email);
+            crate::status!("Subscriber '{}' not found.", email);
+        }
+    }
+
+    Ok(())
+}
+
+/// Report duplicate, invalid and role addresses, optionally fixing them
+pub fn handle_clean(gmail_aliases: bool, fix: bool, yes: bool) -> Result<()> {
+    // Find the current project
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    // Load project configuration
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+
+    // Create newsletter manager
+    let newsletter_manager = NewsletterManager::new(config, &project.root)
+        .context("Failed to initialize newsletter manager")?;
+
+    // Check if newsletter is enabled
+    if !newsletter_manager.is_enabled() {
+        crate::status!("Newsletter functionality is not enabled.");
+        return Ok(());
+    }
+
+    let database = newsletter_manager.database();
+    let subscribers = database.get_subscribers(None)?;
+    let mut findings = hygiene::analyze(&subscribers, gmail_aliases);
+
+    if findings.is_empty() {
+        crate::status!("✓ No issues found in {} subscribers.", subscribers.len());
+        return Ok(());
+    }
+
+    println!();
+    println!("{:<35} {:<10} Issue", "Email", "Status");
+    println!("{}", "-".repeat(80));
+    for finding in &findings {
+        println!(
+            "{:<35} {:<10} {}",
+            finding.subscriber.email,
+            finding.subscriber.status.to_string(),
+            finding.issue
+        );
+    }
+    println!();
+    println!(
+        "Found {} issues in {} subscribers.",
+        findings.len(),
+        subscribers.len()
+    );
+
+    if !fix {
+        crate::status!("Run 'blogr newsletter clean --fix' to fix them.");
+        return Ok(());
+    }
+
+    // Removals first, so renamed addresses don't collide with their duplicates
+    findings.sort_by_key(|finding| match finding.issue {
+        Issue::Duplicate { .. } | Issue::Invalid => 0,
+        Issue::Unnormalized { .. } => 1,
+        Issue::RoleAccount => 2,
+    });
+
+    crate::status!();
+    let mut fixed = 0;
+    for finding in &findings {
+        let email = &finding.subscriber.email;
+        let question = match &finding.issue {
+            Issue::Duplicate { of } => format!("Remove {} (duplicate of {})?", email, of),
+            Issue::Invalid => format!("Remove invalid address {}?", email),
+            Issue::Unnormalized { normalized } => format!("Rename {} to {}?", email, normalized),
+            Issue::RoleAccount if finding.subscriber.status == SubscriberStatus::Declined => {
+                continue;
+            }
+            Issue::RoleAccount => format!("Decline role account {}?", email),
+        };
+        if !yes && !prompt_yes_no(&question)? {
+            continue;
+        }
+
+        match &finding.issue {
+            Issue::Duplicate { of } => {
+                // Keep any tags that were only on the duplicate
+                if let Some(kept) = database.get_subscriber_by_email(of)? {
+                    let mut tags = kept.tags.clone();
+                    tags.extend(finding.subscriber.tags.iter().cloned());
+                    database.set_subscriber_tags(of, &tags)?;
+                }
+                database.remove_subscriber(email)?;
+                crate::status!("✓ Removed {}", email);
+            }
+            Issue::Invalid => {
+                database.remove_subscriber(email)?;
+                crate::status!("✓ Removed {}", email);
+            }
+            Issue::Unnormalized { normalized } => {
+                database.rename_subscriber(email, normalized)?;
+                crate::status!("✓ Renamed {} to {}", email, normalized);
+            }
+            Issue::RoleAccount => {
+                if let Some(id) = finding.subscriber.id {
+                    database.update_subscriber_status(id, SubscriberStatus::Declined)?;
+                    crate::status!("✓ Declined {}", email);
+                }
+            }
         }
+        fixed += 1;
+    }
+
+    crate::status!();
+    crate::status!("Fixed {} of {} issues.", fixed, findings.len());
+
+    Ok(())
+}
+
+/// Handle the tag and untag commands - add or remove subscriber tags
+pub fn handle_tag(email: &str, tags: &[String], remove: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)
+        .context("Failed to initialize newsletter manager")?;
+    let database = newsletter_manager.database();
+
+    let subscriber = database
+        .get_subscriber_by_email(email)?
+        .ok_or_else(|| anyhow::anyhow!("Subscriber '{}' not found", email))?;
+
+    let changes = crate::newsletter::database::normalize_tags(tags);
+    let mut updated = subscriber.tags.clone();
+    if remove {
+        updated.retain(|tag| !changes.contains(tag));
+    } else {
+        updated.extend(changes);
+    }
+    database.set_subscriber_tags(email, &updated)?;
+
+    let stored = database
+        .get_subscriber_by_email(email)?
+        .map(|s| s.tags)
+        .unwrap_or_default();
+    if stored.is_empty() {
+        crate::status!("✓ '{}' has no tags", email);
+    } else {
+        crate::status!("✓ '{}' tags: {}", email, stored.join(", "));
     }
 
     Ok(())
@@ -365,7 +713,10 @@ pub fn handle_export(
     format: &str,
     output_file: Option<&str>,
     status_filter: Option<String>,
+    segment: Vec<String>,
 ) -> Result<()> {
+    let segments = Segment::parse_all(&segment)?;
+
     // Find the current project
     let project = Project::find_project()?
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
@@ -381,7 +732,7 @@ pub fn handle_export(
 
     // Check if newsletter is enabled
     if !newsletter_manager.is_enabled() {
-        println!("Newsletter functionality is not enabled.");
+        crate::status!("Newsletter functionality is not enabled.");
         return Ok(());
     }
 
@@ -395,10 +746,10 @@ pub fn handle_export(
     };
 
     // Get subscribers
-    let subscribers = database.get_subscribers(status)?;
+    let subscribers = Segment::filter(&segments, database.get_subscribers(status)?);
 
     if subscribers.is_empty() {
-        println!("No subscribers found to export.");
+        crate::status!("No subscribers found to export.");
         return Ok(());
     }
 
@@ -418,7 +769,7 @@ pub fn handle_export(
     match output_file {
         Some(file_path) => {
             std::fs::write(file_path, output)?;
-            println!(
+            crate::status!(
                 "✓ Exported {} subscribers to '{}'",
                 subscribers.len(),
                 file_path
@@ -437,7 +788,7 @@ fn export_csv(subscribers: &[crate::newsletter::Subscriber]) -> Result<String> {
     let mut output = String::new();
 
     // Header
-    output.push_str("id,email,status,subscribed_at,approved_at,source_email_id,notes\n");
+    output.push_str("id,email,status,subscribed_at,approved_at,source_email_id,notes,tags\n");
 
     // Data
     for subscriber in subscribers {
@@ -450,14 +801,15 @@ fn export_csv(subscribers: &[crate::newsletter::Subscriber]) -> Result<String> {
         let notes = subscriber.notes.as_deref().unwrap_or("");
 
         output.push_str(&format!(
-            "{},{},{},{},{},{},{}\n",
+            "{},{},{},{},{},{},{},{}\n",
             subscriber.id.unwrap_or(0),
             subscriber.email,
             subscriber.status,
             subscriber.subscribed_at.format("%Y-%m-%d %H:%M:%S"),
             approved_at,
             source_email_id,
-            notes
+            notes,
+            subscriber.tags.join(";")
         ));
     }
 
@@ -471,7 +823,14 @@ fn export_json(subscribers: &[crate::newsletter::Subscriber]) -> Result<String>
 }
 
 /// Handle the send latest post command
-pub async fn handle_send_latest(interactive: bool) -> Result<()> {
+pub async fn handle_send_latest(
+    interactive: bool,
+    at: Option<String>,
+    segment: Vec<String>,
+    template: Option<String>,
+    ab: Option<AbTestOptions>,
+) -> Result<()> {
+    let segments = Segment::parse_all(&segment)?;
     let project = Project::find_project()?
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
@@ -481,55 +840,91 @@ pub async fn handle_send_latest(interactive: bool) -> Result<()> {
     let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
 
     if !newsletter_manager.is_enabled() {
-        println!("❌ Newsletter functionality is not enabled.");
+        crate::status!("❌ Newsletter functionality is not enabled.");
         return Ok(());
     }
 
     // Load posts
     let post_manager = crate::content::PostManager::new(project.posts_dir());
     let mut posts = post_manager.load_all_posts()?;
-    posts.retain(|p| p.metadata.status == crate::content::PostStatus::Published);
+    let now = chrono::Utc::now();
+    posts.retain(|p| p.is_mailable(now));
     posts.sort_by(|a, b| b.metadata.date.cmp(&a.metadata.date));
 
     if posts.is_empty() {
-        println!("❌ No published posts found");
+        crate::status!("❌ No published posts found");
         return Ok(());
     }
 
     // Load theme
-    let theme = blogr_themes::get_theme(&config.theme.name)
+    let theme = project
+        .load_theme(&config.theme.name)?
         .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
 
     // Compose newsletter from latest post
-    println!(
+    crate::status!(
         "📝 Composing newsletter from latest post: '{}'",
         posts[0].metadata.title
     );
-    let newsletter = newsletter_manager.compose_from_latest_post(theme, &posts)?;
+    let newsletter =
+        newsletter_manager.compose_from_latest_post(theme, &posts, template.as_deref())?;
 
     // Preview
-    let composer =
-        newsletter_manager.create_composer(blogr_themes::get_theme(&config.theme.name).unwrap())?;
+    let composer = newsletter_manager.create_composer(
+        project.load_theme(&config.theme.name)?.unwrap(),
+        template.as_deref(),
+    )?;
     composer.preview_in_terminal(&newsletter)?;
 
+    if let Some(ab) = ab {
+        if at.is_some() {
+            return Err(anyhow::anyhow!("--subject-b cannot be combined with --at"));
+        }
+        return start_ab_test(
+            &newsletter_manager,
+            &newsletter,
+            &ab,
+            &segments,
+            interactive,
+        );
+    }
+
+    if let Some(at) = at {
+        return schedule_newsletter(
+            &newsletter_manager,
+            &newsletter,
+            &at,
+            &segments,
+            interactive,
+        );
+    }
+
     // Confirm sending
     if interactive && !prompt_yes_no("Send this newsletter to all approved subscribers?")? {
-        println!("Newsletter sending cancelled.");
+        crate::status!("Newsletter sending cancelled.");
         return Ok(());
     }
 
     // Send newsletter
-    println!("📤 Sending newsletter...");
-    let report = newsletter_manager.send_newsletter(&newsletter, interactive)?;
+    crate::status!("📤 Sending newsletter...");
+    let report = newsletter_manager.send_newsletter(&newsletter, interactive, &segments)?;
 
-    println!("✅ Newsletter sending completed!");
-    println!("📊 Success rate: {:.1}%", report.success_rate() * 100.0);
+    crate::status!("✅ Newsletter sending completed!");
+    crate::status!("📊 Success rate: {:.1}%", report.success_rate() * 100.0);
 
     Ok(())
 }
 
-/// Handle the send custom newsletter command
-pub async fn handle_send_custom(subject: String, content: String, interactive: bool) -> Result<()> {
+/// Handle the send digest command: every post published after `since`
+pub async fn handle_send_digest(
+    since: &str,
+    subject: Option<String>,
+    interactive: bool,
+    at: Option<String>,
+    segment: Vec<String>,
+    template: Option<String>,
+) -> Result<()> {
+    let segments = Segment::parse_all(&segment)?;
     let project = Project::find_project()?
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
@@ -539,40 +934,85 @@ pub async fn handle_send_custom(subject: String, content: String, interactive: b
     let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
 
     if !newsletter_manager.is_enabled() {
-        println!("❌ Newsletter functionality is not enabled.");
+        crate::status!("❌ Newsletter functionality is not enabled.");
         return Ok(());
     }
 
-    // Load theme and create composer
-    let theme = blogr_themes::get_theme(&config.theme.name)
+    let since = if since == "last-send" {
+        newsletter_manager
+            .database()
+            .last_send_at()?
+            .ok_or_else(|| {
+                anyhow::anyhow!("No newsletter has been sent yet. Pass --since YYYY-MM-DD instead.")
+            })?
+    } else {
+        Utils::parse_local_time(since)?
+    };
+
+    // Load the posts published since then, newest first
+    let post_manager = crate::content::PostManager::new(project.posts_dir());
+    let mut posts = post_manager.load_all_posts()?;
+    let now = chrono::Utc::now();
+    posts.retain(|p| p.is_mailable(now) && p.metadata.date > since);
+    posts.sort_by_key(|p| std::cmp::Reverse(p.metadata.date));
+
+    let since_local = since.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+    if posts.is_empty() {
+        crate::status!("📭 No posts published since {}", since_local);
+        return Ok(());
+    }
+
+    let theme = project
+        .load_theme(&config.theme.name)?
         .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
-    let composer = newsletter_manager.create_composer(theme)?;
+    let composer = newsletter_manager.create_digest_composer(theme, template.as_deref())?;
 
-    // Compose custom newsletter
-    println!("📝 Composing custom newsletter: '{}'", subject);
-    let newsletter = composer.compose_custom(subject, content)?;
+    crate::status!(
+        "📝 Composing digest of {} posts published since {}",
+        posts.len(),
+        since_local
+    );
+    let newsletter = composer.compose_digest(&posts, Some(since), subject)?;
 
-    // Preview
     composer.preview_in_terminal(&newsletter)?;
 
+    if let Some(at) = at {
+        return schedule_newsletter(
+            &newsletter_manager,
+            &newsletter,
+            &at,
+            &segments,
+            interactive,
+        );
+    }
+
     // Confirm sending
     if interactive && !prompt_yes_no("Send this newsletter to all approved subscribers?")? {
-        println!("Newsletter sending cancelled.");
+        crate::status!("Newsletter sending cancelled.");
         return Ok(());
     }
 
     // Send newsletter
-    println!("📤 Sending newsletter...");
-    let report = newsletter_manager.send_newsletter(&newsletter, interactive)?;
+    crate::status!("📤 Sending newsletter...");
+    let report = newsletter_manager.send_newsletter(&newsletter, interactive, &segments)?;
 
-    println!("✅ Newsletter sending completed!");
-    println!("📊 Success rate: {:.1}%", report.success_rate() * 100.0);
+    crate::status!("✅ Newsletter sending completed!");
+    crate::status!("📊 Success rate: {:.1}%", report.success_rate() * 100.0);
 
     Ok(())
 }
 
-/// Handle the draft newsletter command (preview only)
-pub async fn handle_draft_latest() -> Result<()> {
+/// Handle the send custom newsletter command
+pub async fn handle_send_custom(
+    subject: String,
+    content: String,
+    interactive: bool,
+    at: Option<String>,
+    segment: Vec<String>,
+    template: Option<String>,
+    ab: Option<AbTestOptions>,
+) -> Result<()> {
+    let segments = Segment::parse_all(&segment)?;
     let project = Project::find_project()?
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
@@ -582,63 +1022,148 @@ pub async fn handle_draft_latest() -> Result<()> {
     let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
 
     if !newsletter_manager.is_enabled() {
-        println!("❌ Newsletter functionality is not enabled.");
+        crate::status!("❌ Newsletter functionality is not enabled.");
         return Ok(());
     }
 
-    // Load posts
-    let post_manager = crate::content::PostManager::new(project.posts_dir());
-    let mut posts = post_manager.load_all_posts()?;
-    posts.retain(|p| p.metadata.status == crate::content::PostStatus::Published);
-    posts.sort_by(|a, b| b.metadata.date.cmp(&a.metadata.date));
+    // Load theme and create composer
+    let theme = project
+        .load_theme(&config.theme.name)?
+        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+    let composer = newsletter_manager.create_composer(theme, template.as_deref())?;
 
-    if posts.is_empty() {
-        println!("❌ No published posts found");
+    // Compose custom newsletter
+    crate::status!("📝 Composing custom newsletter: '{}'", subject);
+    let newsletter = composer.compose_custom(subject, content)?;
+
+    // Preview
+    composer.preview_in_terminal(&newsletter)?;
+
+    if let Some(ab) = ab {
+        if at.is_some() {
+            return Err(anyhow::anyhow!("--subject-b cannot be combined with --at"));
+        }
+        return start_ab_test(
+            &newsletter_manager,
+            &newsletter,
+            &ab,
+            &segments,
+            interactive,
+        );
+    }
+
+    if let Some(at) = at {
+        return schedule_newsletter(
+            &newsletter_manager,
+            &newsletter,
+            &at,
+            &segments,
+            interactive,
+        );
+    }
+
+    // Confirm sending
+    if interactive && !prompt_yes_no("Send this newsletter to all approved subscribers?")? {
+        crate::status!("Newsletter sending cancelled.");
         return Ok(());
     }
 
-    // Load theme
-    let theme = blogr_themes::get_theme(&config.theme.name)
-        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+    // Send newsletter
+    crate::status!("📤 Sending newsletter...");
+    let report = newsletter_manager.send_newsletter(&newsletter, interactive, &segments)?;
 
-    // Compose and preview newsletter
-    let newsletter = newsletter_manager.compose_from_latest_post(theme, &posts)?;
-    let composer =
-        newsletter_manager.create_composer(blogr_themes::get_theme(&config.theme.name).unwrap())?;
-    composer.preview_in_terminal(&newsletter)?;
+    crate::status!("✅ Newsletter sending completed!");
+    crate::status!("📊 Success rate: {:.1}%", report.success_rate() * 100.0);
 
     Ok(())
 }
 
-/// Handle the draft custom newsletter command
-pub async fn handle_draft_custom(subject: String, content: String) -> Result<()> {
-    let project = Project::find_project()?
-        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+/// Build A/B test options from the send command flags
+pub fn ab_test_options(
+    subject_b: Option<String>,
+    test_percent: Option<u8>,
+    wait_hours: Option<u64>,
+) -> Option<AbTestOptions> {
+    subject_b.map(|subject_b| AbTestOptions {
+        subject_b,
+        test_percent,
+        wait_hours,
+    })
+}
 
-    let config = project
-        .load_config()
-        .context("Failed to load project configuration")?;
-    let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
+/// Start a subject line A/B test for a composed newsletter
+fn start_ab_test(
+    newsletter_manager: &NewsletterManager,
+    newsletter: &crate::newsletter::Newsletter,
+    ab: &AbTestOptions,
+    segments: &[Segment],
+    interactive: bool,
+) -> Result<()> {
+    println!("🅰️  Subject A: {}", newsletter.subject);
+    println!("🅱️  Subject B: {}", ab.subject_b);
 
-    if !newsletter_manager.is_enabled() {
-        println!("❌ Newsletter functionality is not enabled.");
+    if interactive && !prompt_yes_no("Start this A/B test?")? {
+        crate::status!("A/B test cancelled.");
         return Ok(());
     }
 
-    // Load theme and create composer
-    let theme = blogr_themes::get_theme(&config.theme.name)
-        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
-    let composer = newsletter_manager.create_composer(theme)?;
+    newsletter_manager.start_ab_test(newsletter, ab, segments, interactive)?;
+    crate::status!("✅ A/B test started!");
 
-    // Compose and preview custom newsletter
-    let newsletter = composer.compose_custom(subject, content)?;
-    composer.preview_in_terminal(&newsletter)?;
+    Ok(())
+}
+
+/// Store a composed newsletter in the send queue for `run-queue` to deliver
+fn schedule_newsletter(
+    newsletter_manager: &NewsletterManager,
+    newsletter: &crate::newsletter::Newsletter,
+    at: &str,
+    segments: &[Segment],
+    interactive: bool,
+) -> Result<()> {
+    let scheduled_at = Utils::parse_local_time(at)?;
+    if scheduled_at <= chrono::Utc::now() {
+        return Err(anyhow::anyhow!(
+            "Scheduled time {} is in the past",
+            scheduled_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+        ));
+    }
+
+    let local = scheduled_at.with_timezone(&chrono::Local);
+    if interactive
+        && !prompt_yes_no(&format!(
+            "Schedule this newsletter for {}?",
+            local.format("%Y-%m-%d %H:%M")
+        ))?
+    {
+        crate::status!("Newsletter scheduling cancelled.");
+        return Ok(());
+    }
+
+    let id = newsletter_manager.database().enqueue_newsletter(
+        &newsletter.subject,
+        &newsletter.html_content,
+        &newsletter.text_content,
+        scheduled_at,
+        &segments.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+    )?;
+
+    crate::status!(
+        "🗓️  Newsletter #{} scheduled for {}",
+        id,
+        local.format("%Y-%m-%d %H:%M %Z")
+    );
+    crate::status!(
+        "💡 Run 'blogr newsletter run-queue' from cron to deliver scheduled newsletters"
+    );
 
     Ok(())
 }
 
-/// Handle the test email command
-pub async fn handle_test_email(test_email: String, interactive: bool) -> Result<()> {
+/// Handle the auto command: send posts published since the last run
+pub async fn handle_auto(dry_run: bool) -> Result<()> {
     let project = Project::find_project()?
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
@@ -648,27 +1173,418 @@ pub async fn handle_test_email(test_email: String, interactive: bool) -> Result<
     let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
 
     if !newsletter_manager.is_enabled() {
-        println!("❌ Newsletter functionality is not enabled.");
+        crate::status!("❌ Newsletter functionality is not enabled.");
         return Ok(());
     }
 
-    // Load posts for test content
+    let _lock = AutoLock::acquire(&project.root)?;
+
     let post_manager = crate::content::PostManager::new(project.posts_dir());
     let mut posts = post_manager.load_all_posts()?;
-    posts.retain(|p| p.metadata.status == crate::content::PostStatus::Published);
-    posts.sort_by(|a, b| b.metadata.date.cmp(&a.metadata.date));
+    let now = chrono::Utc::now();
+    posts.retain(|p| p.is_live(now));
+    posts.sort_by_key(|p| std::cmp::Reverse(p.metadata.date));
+
+    let Some(mut state) = AutoState::load(&project.root)? else {
+        crate::status!(
+            "👀 First run: {} published posts are treated as sent; posts published from now on will be mailed",
+            posts.len()
+        );
+        if !dry_run {
+            AutoState::starting_now(&posts).save(&project.root)?;
+        }
+        return Ok(());
+    };
 
-    if posts.is_empty() {
-        println!("❌ No published posts found for test email");
+    let private = state.skip_private(&posts);
+    for post in &private {
+        crate::status!(
+            "🔒 Not mailing private post '{}' ({})",
+            post.metadata.title,
+            post.metadata.slug
+        );
+    }
+    if !private.is_empty() && !dry_run {
+        state.save(&project.root)?;
+    }
+
+    let new_posts: Vec<crate::content::Post> =
+        state.new_posts(&posts).into_iter().cloned().collect();
+    if new_posts.is_empty() {
+        crate::status!("📭 No new posts to send");
         return Ok(());
     }
 
-    // Load theme and compose test newsletter
-    let theme = blogr_themes::get_theme(&config.theme.name)
+    let theme = project
+        .load_theme(&config.theme.name)?
         .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+    let newsletter = if let [post] = new_posts.as_slice() {
+        newsletter_manager
+            .create_composer(theme, None)?
+            .compose_from_post(post)?
+    } else {
+        newsletter_manager
+            .create_digest_composer(theme, None)?
+            .compose_digest(&new_posts, state.since, None)?
+    };
+
+    if dry_run {
+        println!("Would send '{}' for:", newsletter.subject);
+        for post in &new_posts {
+            println!("  - {} ({})", post.metadata.title, post.metadata.slug);
+        }
+        return Ok(());
+    }
+
+    crate::status!("📤 Sending '{}'", newsletter.subject);
+    let report = newsletter_manager.send_newsletter(&newsletter, false, &[])?;
+
+    // A partly failed send still reached most subscribers, so the posts are
+    // marked either way and the next run doesn't mail everyone again
+    state.mark_sent(&new_posts);
+    state.since = Some(now);
+    state.save(&project.root)?;
+
+    crate::status!(
+        "✅ Newsletter sent ({:.1}% success rate)",
+        report.success_rate() * 100.0
+    );
+    if report.failed_sends > 0 {
+        return Err(anyhow::anyhow!(
+            "{} deliveries failed; see 'blogr newsletter history'",
+            report.failed_sends
+        ));
+    }
+
+    Ok(())
+}
+
+/// Handle the run-queue command: send every scheduled newsletter that is due
+pub async fn handle_run_queue(dry_run: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)?;
+
+    if !newsletter_manager.is_enabled() {
+        crate::status!("❌ Newsletter functionality is not enabled.");
+        return Ok(());
+    }
+
+    let due = newsletter_manager
+        .database()
+        .get_due_newsletters(chrono::Utc::now())?;
+
+    if due.is_empty() {
+        crate::status!("📭 No scheduled newsletters are due");
+    }
+
+    let mut failures = 0;
+    for queued in due {
+        if dry_run {
+            println!(
+                "Would send #{} '{}' (scheduled {})",
+                queued.id,
+                queued.subject,
+                queued
+                    .scheduled_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+            );
+            continue;
+        }
+
+        // Skip items another run has already picked up
+        if !newsletter_manager
+            .database()
+            .claim_queued_newsletter(queued.id)?
+        {
+            continue;
+        }
+
+        crate::status!(
+            "📤 Sending scheduled newsletter #{}: '{}'",
+            queued.id,
+            queued.subject
+        );
+        let newsletter = crate::newsletter::Newsletter::new(
+            queued.subject.clone(),
+            queued.html_content.clone(),
+            queued.text_content.clone(),
+        );
+
+        let result = Segment::parse_all(&queued.segments)
+            .and_then(|segments| newsletter_manager.send_newsletter(&newsletter, false, &segments));
+        match result {
+            Ok(report) => {
+                newsletter_manager.database().update_queue_status(
+                    queued.id,
+                    crate::newsletter::QueueStatus::Sent,
+                    None,
+                )?;
+                crate::status!(
+                    "✅ Newsletter #{} sent ({:.1}% success rate)",
+                    queued.id,
+                    report.success_rate() * 100.0
+                );
+            }
+            Err(e) => {
+                failures += 1;
+                newsletter_manager.database().update_queue_status(
+                    queued.id,
+                    crate::newsletter::QueueStatus::Failed,
+                    Some(&e.to_string()),
+                )?;
+                tracing::error!("Newsletter #{} failed: {:#}", queued.id, e);
+            }
+        }
+    }
+
+    let ab_tests = newsletter_manager
+        .database()
+        .get_due_ab_tests(chrono::Utc::now())?;
+    let tracking = newsletter_manager.config().newsletter.tracking.enabled;
+    for test in ab_tests {
+        if !tracking {
+            crate::status!(
+                "🧪 A/B test #{} is ready: pick a winner with 'blogr newsletter ab-finish {} --winner a|b'",
+                test.id, test.id
+            );
+            continue;
+        }
+        if dry_run {
+            println!("Would finish A/B test #{}", test.id);
+            continue;
+        }
+
+        match newsletter_manager.finish_ab_test(test.id, None, false) {
+            Ok(report) => crate::status!(
+                "✅ A/B test #{} completed ({:.1}% success rate)",
+                test.id,
+                report.success_rate() * 100.0
+            ),
+            Err(e) => {
+                failures += 1;
+                tracing::error!("A/B test #{} failed: {:#}", test.id, e);
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(anyhow::anyhow!(
+            "{} scheduled newsletter(s) failed to send",
+            failures
+        ));
+    }
+
+    Ok(())
+}
+
+/// Handle the queue command: list scheduled newsletters or cancel one
+pub fn handle_queue(cancel: Option<i64>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)?;
+    let database = newsletter_manager.database();
+
+    if let Some(id) = cancel {
+        if database.cancel_queued_newsletter(id)? {
+            crate::status!("✅ Cancelled scheduled newsletter #{}", id);
+            return Ok(());
+        }
+        return Err(anyhow::anyhow!(
+            "Newsletter #{} is not pending in the send queue",
+            id
+        ));
+    }
+
+    let queued = database.get_queued_newsletters(None)?;
+    if queued.is_empty() {
+        crate::status!("📭 The send queue is empty");
+        crate::status!(
+            "💡 Schedule a send with 'blogr newsletter send-latest --at \"YYYY-MM-DD HH:MM\"'"
+        );
+        return Ok(());
+    }
+
+    println!("{:<5} {:<10} {:<17} Subject", "ID", "Status", "Scheduled");
+    println!("{}", "-".repeat(60));
+    for item in &queued {
+        println!(
+            "{:<5} {:<10} {:<17} {}",
+            item.id,
+            item.status.to_string(),
+            item.scheduled_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            item.subject
+        );
+        if let Some(error) = &item.error {
+            println!("      ↳ {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the draft newsletter command (preview only)
+pub async fn handle_draft_latest(template: Option<String>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
+
+    if !newsletter_manager.is_enabled() {
+        crate::status!("❌ Newsletter functionality is not enabled.");
+        return Ok(());
+    }
+
+    // Load posts
+    let post_manager = crate::content::PostManager::new(project.posts_dir());
+    let mut posts = post_manager.load_all_posts()?;
+    let now = chrono::Utc::now();
+    posts.retain(|p| p.is_mailable(now));
+    posts.sort_by(|a, b| b.metadata.date.cmp(&a.metadata.date));
+
+    if posts.is_empty() {
+        crate::status!("❌ No published posts found");
+        return Ok(());
+    }
+
+    // Load theme
+    let theme = project
+        .load_theme(&config.theme.name)?
+        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+
+    // Compose and preview newsletter
+    let newsletter =
+        newsletter_manager.compose_from_latest_post(theme, &posts, template.as_deref())?;
+    let composer = newsletter_manager.create_composer(
+        project.load_theme(&config.theme.name)?.unwrap(),
+        template.as_deref(),
+    )?;
+    composer.preview_in_terminal(&newsletter)?;
+
+    Ok(())
+}
+
+/// Handle the draft custom newsletter command
+pub async fn handle_draft_custom(
+    subject: String,
+    content: String,
+    template: Option<String>,
+) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
+
+    if !newsletter_manager.is_enabled() {
+        crate::status!("❌ Newsletter functionality is not enabled.");
+        return Ok(());
+    }
+
+    // Load theme and create composer
+    let theme = project
+        .load_theme(&config.theme.name)?
+        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+    let composer = newsletter_manager.create_composer(theme, template.as_deref())?;
+
+    // Compose and preview custom newsletter
+    let newsletter = composer.compose_custom(subject, content)?;
+    composer.preview_in_terminal(&newsletter)?;
+
+    Ok(())
+}
+
+/// Handle the templates command
+pub fn handle_templates() -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
+
+    let theme = project
+        .load_theme(&config.theme.name)?
+        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+    let composer = newsletter_manager.create_composer(theme, Some("default"))?;
+    let default = config.newsletter.template.as_deref().unwrap_or("default");
+
+    println!("📧 Newsletter templates:");
+    for name in composer.available_templates() {
+        let description = NewsletterComposer::builtin_templates()
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, description)| description.to_string())
+            .unwrap_or_else(|| format!("newsletter/templates/{}.html", name));
+        let marker = if name == default { " (default)" } else { "" };
+        println!("  {:<12} {}{}", name, description, marker);
+    }
+
+    crate::status!();
+    crate::status!(
+        "Add your own templates to {}",
+        newsletter_manager.templates_dir().display()
+    );
+
+    Ok(())
+}
+
+/// Handle the test email command
+pub async fn handle_test_email(
+    test_email: String,
+    interactive: bool,
+    template: Option<String>,
+) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
+
+    if !newsletter_manager.is_enabled() {
+        crate::status!("❌ Newsletter functionality is not enabled.");
+        return Ok(());
+    }
+
+    // Load posts for test content
+    let post_manager = crate::content::PostManager::new(project.posts_dir());
+    let mut posts = post_manager.load_all_posts()?;
+    let now = chrono::Utc::now();
+    posts.retain(|p| p.is_mailable(now));
+    posts.sort_by(|a, b| b.metadata.date.cmp(&a.metadata.date));
+
+    if posts.is_empty() {
+        crate::status!("❌ No published posts found for test email");
+        return Ok(());
+    }
+
+    // Load theme and compose test newsletter
+    let theme = project
+        .load_theme(&config.theme.name)?
+        .ok_or_else(|| anyhow::anyhow!("Theme '{}' not found", config.theme.name))?;
+
+    let newsletter =
+        newsletter_manager.compose_from_latest_post(theme, &posts, template.as_deref())?;
 
-    let newsletter = newsletter_manager.compose_from_latest_post(theme, &posts)?;
-
     // Send test email
     newsletter_manager.send_test_newsletter(&newsletter, &test_email, interactive)?;
 
@@ -718,8 +1634,8 @@ pub async fn handle_import(
 
     // Check if newsletter is enabled
     if !newsletter_manager.is_enabled() {
-        println!("Newsletter functionality is not enabled.");
-        println!("Enable it first with newsletter configuration in blogr.toml");
+        crate::status!("Newsletter functionality is not enabled.");
+        crate::status!("Enable it first with newsletter configuration in blogr.toml");
         return Ok(());
     }
 
@@ -747,10 +1663,10 @@ pub async fn handle_import(
 
     if preview {
         // Preview mode
-        println!("Previewing import from {} source...", source);
-        println!("File: {}", file);
-        println!("Preview limit: {}", preview_limit);
-        println!();
+        crate::status!("Previewing import from {} source...", source);
+        crate::status!("File: {}", file);
+        crate::status!("Preview limit: {}", preview_limit);
+        crate::status!();
 
         match migration_manager.preview_migration(&migration_config, Some(preview_limit)) {
             Ok(preview_data) => {
@@ -775,7 +1691,9 @@ pub async fn handle_import(
                 }
 
                 println!("{:-<80}", "");
-                println!("To import these subscribers, run the same command without --preview");
+                crate::status!(
+                    "To import these subscribers, run the same command without --preview"
+                );
             }
             Err(e) => {
                 eprintln!("Failed to preview migration: {}", e);
@@ -794,30 +1712,30 @@ pub async fn handle_import(
         io::stdin().read_line(&mut input)?;
 
         if !input.trim().to_lowercase().starts_with('y') {
-            println!("Import cancelled.");
+            crate::status!("Import cancelled.");
             return Ok(());
         }
 
         match migration_manager.import_from_file(&migration_config) {
             Ok(result) => {
-                println!("\nImport completed successfully!");
-                println!("Total processed: {}", result.total_processed);
-                println!("Successfully imported: {}", result.successfully_imported);
-                println!("Skipped duplicates: {}", result.skipped_duplicates);
+                crate::status!("\nImport completed successfully!");
+                crate::status!("Total processed: {}", result.total_processed);
+                crate::status!("Successfully imported: {}", result.successfully_imported);
+                crate::status!("Skipped duplicates: {}", result.skipped_duplicates);
 
                 if !result.errors.is_empty() {
-                    println!("Errors encountered: {}", result.errors.len());
+                    crate::status!("Errors encountered: {}", result.errors.len());
                     for error in &result.errors {
                         eprintln!("  - {}", error);
                     }
                 }
 
                 if result.successfully_imported > 0 {
-                    println!("\nNext steps:");
-                    println!(
+                    crate::status!("\nNext steps:");
+                    crate::status!(
                         "1. Run 'blogr newsletter approve' to review and approve new subscribers"
                     );
-                    println!("2. Use 'blogr newsletter list --status pending' to see pending subscribers");
+                    crate::status!("2. Use 'blogr newsletter list --status pending' to see pending subscribers");
                 }
             }
             Err(e) => {
@@ -837,26 +1755,29 @@ pub async fn handle_plugin_list() -> Result<()> {
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
     // Load project configuration
-    let _config = project
+    let config = project
         .load_config()
         .context("Failed to load project configuration")?;
 
     // Create plugin manager
-    let plugin_manager = PluginManager::new(project.root.clone());
+    let plugin_manager = PluginManager::for_project(&config, &project.root)?;
 
     let plugins = plugin_manager.list_plugins();
 
     if plugins.is_empty() {
-        println!("No plugins are currently loaded.");
-        println!("\nTo add plugins, implement the NewsletterPlugin trait and register them in your code.");
+        crate::status!("No plugins are currently loaded.");
+        crate::status!(
+            "\nTo add a plugin, copy its .wasm file into the plugins/ directory of your project."
+        );
         return Ok(());
     }
 
     println!("Loaded Newsletter Plugins:");
     println!("{:-<80}", "");
 
-    for plugin in plugins {
+    for (plugin, enabled) in plugins {
         println!("Name: {}", plugin.name);
+        println!("Status: {}", if enabled { "enabled" } else { "disabled" });
         println!("Version: {}", plugin.version);
         println!("Author: {}", plugin.author);
         println!("Description: {}", plugin.description);
@@ -881,8 +1802,13 @@ pub async fn handle_plugin_info(name: &str) -> Result<()> {
     let project = Project::find_project()?
         .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
+    // Load project configuration
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+
     // Create plugin manager
-    let plugin_manager = PluginManager::new(project.root.clone());
+    let plugin_manager = PluginManager::for_project(&config, &project.root)?;
 
     if let Some(plugin) = plugin_manager.get_plugin(name) {
         let metadata = plugin.metadata();
@@ -934,8 +1860,8 @@ pub async fn handle_plugin_info(name: &str) -> Result<()> {
             }
         }
     } else {
-        println!("Plugin '{}' not found.", name);
-        println!("Use 'blogr newsletter plugin list' to see available plugins.");
+        crate::status!("Plugin '{}' not found.", name);
+        crate::status!("Use 'blogr newsletter plugin list' to see available plugins.");
     }
 
     Ok(())
@@ -943,30 +1869,76 @@ pub async fn handle_plugin_info(name: &str) -> Result<()> {
 
 /// Handle the plugin enable command
 pub async fn handle_plugin_enable(name: &str) -> Result<()> {
-    println!("Plugin enable/disable functionality requires configuration management.");
-    println!(
-        "This feature is not yet implemented - plugins are currently managed through blogr.toml"
-    );
-    println!("\nTo enable a plugin, add it to your blogr.toml:");
-    println!();
-    println!("[newsletter.plugins.{}]", name);
-    println!("enabled = true");
-    println!("# plugin-specific configuration here");
-
-    Ok(())
+    set_plugin_enabled(name, true)
 }
 
 /// Handle the plugin disable command
 pub async fn handle_plugin_disable(name: &str) -> Result<()> {
-    println!("Plugin enable/disable functionality requires configuration management.");
-    println!(
-        "This feature is not yet implemented - plugins are currently managed through blogr.toml"
-    );
-    println!("\nTo disable a plugin, set enabled = false in your blogr.toml:");
-    println!();
-    println!("[newsletter.plugins.{}]", name);
-    println!("enabled = false");
+    set_plugin_enabled(name, false)
+}
+
+/// Write `[newsletter.plugins.<name>] enabled` to blogr.toml. Enabling checks that
+/// the plugin is installed and that its configuration matches its schema.
+fn set_plugin_enabled(name: &str, enabled: bool) -> Result<()> {
+    // Find the current project
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    // Load project configuration
+    let mut config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+
+    let current = config
+        .newsletter
+        .plugins
+        .as_ref()
+        .and_then(|plugins| plugins.get(name))
+        .cloned()
+        .unwrap_or(PluginConfig {
+            enabled: false,
+            config: HashMap::new(),
+        });
+
+    if current.enabled == enabled {
+        crate::status!(
+            "Plugin '{}' is already {}.",
+            name,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        return Ok(());
+    }
+
+    if enabled {
+        let plugin_manager = PluginManager::for_project(&config, &project.root)?;
+        let plugin = plugin_manager.get_plugin(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Plugin '{}' not found. Use 'blogr newsletter plugin list' to see available plugins.",
+                name
+            )
+        })?;
+        check_plugin_config(plugin, &current).with_context(|| {
+            format!(
+                "Set its options under [newsletter.plugins.{}] config in blogr.toml, then try again",
+                name
+            )
+        })?;
+    }
 
+    config
+        .newsletter
+        .plugins
+        .get_or_insert_with(HashMap::new)
+        .insert(name.to_string(), PluginConfig { enabled, ..current });
+    config
+        .save_to_file(project.root.join("blogr.toml"))
+        .context("Failed to save project configuration")?;
+
+    crate::status!(
+        "✅ Plugin '{}' {}.",
+        name,
+        if enabled { "enabled" } else { "disabled" }
+    );
     Ok(())
 }
 
@@ -986,7 +1958,7 @@ pub async fn handle_plugin_run(command: &str, args: &[String]) -> Result<()> {
         .context("Failed to initialize newsletter manager")?;
 
     // Create plugin manager
-    let plugin_manager = PluginManager::new(project.root.clone());
+    let plugin_manager = PluginManager::for_project(&config, &project.root)?;
 
     // Create plugin context
     use crate::newsletter::{create_plugin_context, PluginHook};
@@ -995,7 +1967,7 @@ pub async fn handle_plugin_run(command: &str, args: &[String]) -> Result<()> {
 
     let context = create_plugin_context(
         Arc::new(config),
-        Arc::new(newsletter_manager.take_database()),
+        Some(Arc::new(newsletter_manager.take_database())),
         project.root.clone(),
         PluginHook::CustomCommand,
         HashMap::new(),
@@ -1007,7 +1979,7 @@ pub async fn handle_plugin_run(command: &str, args: &[String]) -> Result<()> {
                 if let Some(message) = result.message {
                     println!("{}", message);
                 }
-                println!("Plugin command '{}' executed successfully.", command);
+                crate::status!("Plugin command '{}' executed successfully.", command);
             } else {
                 if let Some(message) = result.message {
                     eprintln!("Error: {}", message);
@@ -1024,6 +1996,429 @@ pub async fn handle_plugin_run(command: &str, args: &[String]) -> Result<()> {
     Ok(())
 }
 
+/// Handle the ab-status command
+pub fn handle_ab_status(id: Option<i64>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)?;
+    let database = newsletter_manager.database();
+
+    let Some(id) = id else {
+        let tests = database.get_ab_tests()?;
+        if tests.is_empty() {
+            crate::status!("No A/B tests yet. Start one with 'blogr newsletter send-latest --subject-b \"...\"'");
+            return Ok(());
+        }
+
+        println!("🧪 A/B tests:");
+        println!();
+        println!(
+            "{:<5} {:<10} {:<6} {:<17} Subjects",
+            "ID", "Status", "Winner", "Decide after"
+        );
+        println!("{}", "─".repeat(80));
+        for test in tests {
+            println!(
+                "{:<5} {:<10} {:<6} {:<17} A: {}",
+                test.id,
+                test.status.to_string(),
+                test.winner.as_deref().unwrap_or("-").to_uppercase(),
+                test.decide_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                test.subject_a
+            );
+            println!("{:<41} B: {}", "", test.subject_b);
+        }
+        return Ok(());
+    };
+
+    let test = database
+        .get_ab_test(id)?
+        .ok_or_else(|| anyhow::anyhow!("No A/B test with ID {}", id))?;
+    let (stats_a, stats_b) = newsletter_manager.ab_test_stats(&test)?;
+
+    println!("🧪 A/B test #{} ({})", test.id, test.status);
+    println!(
+        "   Test group: {}% of subscribers{}",
+        test.test_percent,
+        if test.segments.is_empty() {
+            String::new()
+        } else {
+            format!(" in {}", test.segments.join(" + "))
+        }
+    );
+    println!(
+        "   Decide after: {}",
+        test.decide_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+    );
+    println!();
+
+    for (variant, subject, run, stats) in [
+        ("A", &test.subject_a, test.run_a, stats_a),
+        ("B", &test.subject_b, test.run_b, stats_b),
+    ] {
+        let won = test.winner.as_deref() == Some(&variant.to_lowercase());
+        println!("{} {}: {}", if won { "🏆" } else { "  " }, variant, subject);
+        match (run, stats) {
+            (Some(run), Some(stats)) if stats.tracked > 0 => {
+                let rate = |count: usize| count as f64 / stats.tracked as f64 * 100.0;
+                println!(
+                    "     Send #{}: {} delivered, {:.1}% opened, {:.1}% clicked",
+                    run,
+                    stats.delivered,
+                    rate(stats.unique_opens),
+                    rate(stats.unique_clicks)
+                );
+            }
+            (Some(run), Some(stats)) => println!(
+                "     Send #{}: {} delivered (not tracked)",
+                run, stats.delivered
+            ),
+            _ => println!("     Not sent"),
+        }
+    }
+
+    if let Some(run) = test.remainder_run {
+        println!();
+        println!("Winner sent to the remaining subscribers as send #{}", run);
+    }
+    if let Some(error) = &test.error {
+        println!();
+        println!("❌ Sending the winner failed: {}", error);
+        println!("   Retry with 'blogr newsletter ab-finish {}'", test.id);
+    }
+
+    Ok(())
+}
+
+/// Handle the ab-finish command
+pub fn handle_ab_finish(id: i64, winner: Option<String>, interactive: bool) -> Result<()> {
+    let winner = winner.map(|w| w.parse::<Variant>()).transpose()?;
+
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)?;
+
+    if !newsletter_manager.is_enabled() {
+        crate::status!("❌ Newsletter functionality is not enabled.");
+        return Ok(());
+    }
+
+    let report = newsletter_manager.finish_ab_test(id, winner, interactive)?;
+    crate::status!("✅ A/B test #{} completed!", id);
+    crate::status!("📊 Success rate: {:.1}%", report.success_rate() * 100.0);
+
+    Ok(())
+}
+
+/// Handle the history command
+pub fn handle_history(limit: usize) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)?;
+
+    let runs = newsletter_manager.database().get_send_runs(limit)?;
+    if runs.is_empty() {
+        crate::status!("No newsletters have been sent yet.");
+        return Ok(());
+    }
+
+    println!("📬 Newsletter send history:");
+    println!();
+    println!(
+        "{:<5} {:<17} {:>6} {:>6} {:>10}  {:<11} Subject",
+        "ID", "Started (UTC)", "Sent", "Failed", "Recipients", "Status"
+    );
+    println!("{}", "─".repeat(80));
+
+    for run in &runs {
+        println!(
+            "{:<5} {:<17} {:>6} {:>6} {:>10}  {:<11} {}",
+            run.id,
+            run.started_at.format("%Y-%m-%d %H:%M"),
+            run.sent,
+            run.failed,
+            run.recipients,
+            send_run_status(run),
+            run.subject
+        );
+    }
+
+    crate::status!();
+    crate::status!("Run 'blogr newsletter report <id>' for the details of a send.");
+
+    Ok(())
+}
+
+/// Handle the report command
+pub fn handle_report(send_id: i64, all: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)?;
+    let database = newsletter_manager.database();
+
+    let run = database
+        .get_send_run(send_id)?
+        .ok_or_else(|| anyhow::anyhow!("No send with ID {}", send_id))?;
+    let deliveries = database.get_deliveries(send_id)?;
+
+    println!("📊 Send #{}: {}", run.id, run.subject);
+    println!("══════════════════════════════════════");
+    println!(
+        "Started:    {}",
+        run.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    match run.completed_at {
+        Some(completed_at) => println!(
+            "Completed:  {} ({} seconds)",
+            completed_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            (completed_at - run.started_at).num_seconds()
+        ),
+        None => println!("Completed:  no ({})", send_run_status(&run)),
+    }
+    println!("Recipients: {}", run.recipients);
+    println!("✅ Sent:    {}", run.sent);
+    println!("❌ Failed:  {}", run.failed);
+
+    let not_attempted = run.recipients.saturating_sub(run.sent + run.failed);
+    if not_attempted > 0 {
+        println!("⏸️  Not attempted: {}", not_attempted);
+    }
+
+    let attempted = run.sent + run.failed;
+    if attempted > 0 {
+        println!(
+            "📈 Success rate: {:.1}%",
+            run.sent as f64 / attempted as f64 * 100.0
+        );
+    }
+
+    let failures: Vec<_> = deliveries.iter().filter(|d| !d.sent).collect();
+    if !failures.is_empty() {
+        // Group failures by error so a single SMTP problem doesn't fill the screen
+        let mut by_error: std::collections::BTreeMap<&str, Vec<&str>> =
+            std::collections::BTreeMap::new();
+        for delivery in &failures {
+            by_error
+                .entry(delivery.error.as_deref().unwrap_or("unknown error"))
+                .or_default()
+                .push(&delivery.email);
+        }
+
+        println!();
+        println!("❌ Failures:");
+        for (error, emails) in by_error {
+            println!("  {} ({})", error, emails.len());
+            for email in emails {
+                println!("    • {}", email);
+            }
+        }
+        crate::status!();
+        crate::status!("💡 Send the same newsletter again to retry failed recipients");
+    }
+
+    if all {
+        println!();
+        println!("✅ Delivered:");
+        for delivery in deliveries.iter().filter(|d| d.sent) {
+            println!(
+                "  {}  {}",
+                delivery.attempted_at.format("%Y-%m-%d %H:%M:%S"),
+                delivery.email
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Short status label for a send run
+fn send_run_status(run: &crate::newsletter::database::SendRun) -> &'static str {
+    if run.completed_at.is_some() {
+        "complete"
+    } else if run.failed > 0 {
+        "failures"
+    } else {
+        "incomplete"
+    }
+}
+
+/// Handle the stats command
+pub fn handle_stats(send_id: i64) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config.clone(), &project.root)?;
+
+    let stats = newsletter_manager
+        .database()
+        .get_tracking_stats(send_id)?
+        .ok_or_else(|| anyhow::anyhow!("No send with ID {}", send_id))?;
+
+    let percent = |count: usize| {
+        if stats.tracked == 0 {
+            0.0
+        } else {
+            count as f64 / stats.tracked as f64 * 100.0
+        }
+    };
+
+    println!("📊 Send #{}: {}", stats.run_id, stats.subject);
+    println!("   Started: {} UTC", stats.started_at);
+    println!("   Delivered: {}", stats.delivered);
+
+    if stats.tracked == 0 {
+        println!();
+        println!("Tracking was not enabled for this send.");
+        if !config.newsletter.tracking.enabled {
+            println!(
+                "Enable it with [newsletter.tracking] in blogr.toml (see docs/NEWSLETTER.md)."
+            );
+        }
+        return Ok(());
+    }
+
+    println!(
+        "   Opens: {} unique ({:.1}%), {} total",
+        stats.unique_opens,
+        percent(stats.unique_opens),
+        stats.total_opens
+    );
+    println!(
+        "   Clicks: {} unique ({:.1}%), {} total",
+        stats.unique_clicks,
+        percent(stats.unique_clicks),
+        stats.total_clicks
+    );
+
+    if !stats.links.is_empty() {
+        println!();
+        println!("{:>7} {:>7}  Link", "Unique", "Total");
+        for link in &stats.links {
+            println!(
+                "{:>7} {:>7}  {}",
+                link.unique_clicks, link.total_clicks, link.url
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "Opens are approximate: some mail clients block images, others load them automatically."
+    );
+
+    Ok(())
+}
+
+pub fn handle_growth(monthly: bool, periods: usize, json: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow::anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let config = project
+        .load_config()
+        .context("Failed to load project configuration")?;
+    let newsletter_manager = NewsletterManager::new(config, &project.root)?;
+    let database = newsletter_manager.database();
+
+    let subscribers = database.get_subscribers(None)?;
+    let removals = database.get_removal_times()?;
+    let period = if monthly {
+        GrowthPeriod::Month
+    } else {
+        GrowthPeriod::Week
+    };
+    let rows = growth::growth(
+        &subscribers,
+        &removals,
+        period,
+        periods,
+        chrono::Utc::now().date_naive(),
+    );
+    let count = |status: SubscriberStatus| {
+        subscribers
+            .iter()
+            .filter(|subscriber| subscriber.status == status)
+            .count()
+    };
+
+    if json {
+        let report = serde_json::json!({
+            "period": period,
+            "rows": rows,
+            "subscribers": {
+                "total": subscribers.len(),
+                "approved": count(SubscriberStatus::Approved),
+                "pending": count(SubscriberStatus::Pending),
+                "declined": count(SubscriberStatus::Declined),
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "📈 Subscriber growth by {} (last {} {}s)",
+        period,
+        rows.len(),
+        period
+    );
+    println!();
+    let heading = match period {
+        GrowthPeriod::Week => "Week of",
+        GrowthPeriod::Month => "Month",
+    };
+    println!(
+        "{:<12} {:>6} {:>9} {:>8} {:>7}",
+        heading, "New", "Approved", "Removed", "Total"
+    );
+    for row in &rows {
+        println!(
+            "{:<12} {:>6} {:>9} {:>8} {:>7}",
+            row.period, row.new, row.approved, row.removed, row.total
+        );
+    }
+
+    let new: Vec<usize> = rows.iter().map(|row| row.new).collect();
+    let total: Vec<usize> = rows.iter().map(|row| row.total).collect();
+    println!();
+    println!("New:   {}", growth::sparkline(&new));
+    println!("Total: {}", growth::sparkline(&total));
+    println!();
+    println!(
+        "Subscribers: {} ({} approved, {} pending, {} declined)",
+        subscribers.len(),
+        count(SubscriberStatus::Approved),
+        count(SubscriberStatus::Pending),
+        count(SubscriberStatus::Declined)
+    );
+    crate::status!("💡 Use --monthly to group by month, or --json for machine-readable output");
+
+    Ok(())
+}
+
 /// Handle the API server command
 pub async fn handle_api_server(
     host: &str,
@@ -1046,8 +2441,8 @@ pub async fn handle_api_server(
 
     // Check if newsletter is enabled
     if !newsletter_manager.is_enabled() {
-        println!("Newsletter functionality is not enabled.");
-        println!("Enable it first with newsletter configuration in blogr.toml");
+        crate::status!("Newsletter functionality is not enabled.");
+        crate::status!("Enable it first with newsletter configuration in blogr.toml");
         return Ok(());
     }
 
@@ -1063,25 +2458,33 @@ pub async fn handle_api_server(
     // Create and start the API server
     let api_server = NewsletterApiServer::new(newsletter_manager, config, api_config);
 
-    println!("Newsletter API Documentation:");
-    println!("  GET  /health              - Health check");
-    println!("  GET  /subscribers         - List subscribers");
-    println!("  POST /subscribers         - Create subscriber");
-    println!("  GET  /subscribers/:email  - Get subscriber");
-    println!("  PUT  /subscribers/:email  - Update subscriber");
-    println!("  DEL  /subscribers/:email  - Delete subscriber");
-    println!("  GET  /stats               - Get statistics");
-    println!("  GET  /export              - Export subscribers");
-    println!("  POST /import              - Import subscribers");
-    println!();
+    crate::status!("Newsletter API Documentation:");
+    crate::status!("  GET  /health              - Health check");
+    crate::status!("  GET  /subscribers         - List subscribers");
+    crate::status!("  POST /subscribers         - Create subscriber");
+    crate::status!("  GET  /subscribers/{{email}} - Get subscriber");
+    crate::status!("  PUT  /subscribers/{{email}} - Update subscriber");
+    crate::status!("  DEL  /subscribers/{{email}} - Delete subscriber");
+    crate::status!("  GET  /stats               - Get statistics");
+    crate::status!("  GET  /export              - Export subscribers");
+    crate::status!("  POST /import              - Import subscribers");
+    crate::status!("  GET  /t/o/{{token}}         - Open tracking pixel");
+    crate::status!("  GET  /t/c/{{token}}/{{link}}  - Tracked link redirect");
+    crate::status!();
 
     if let Some(key) = api_key {
-        println!("API Key authentication is enabled.");
-        println!(
+        crate::status!("API Key authentication is enabled.");
+        crate::status!(
             "Include 'Authorization: Bearer {}' header in requests.",
             key
         );
-        println!();
+        crate::status!();
+    } else {
+        crate::status!(
+            "No API key given: only the public signup, contact and tracking routes will answer."
+        );
+        crate::status!("Pass --api-key to use the management endpoints.");
+        crate::status!();
     }
 
     api_server.start().await
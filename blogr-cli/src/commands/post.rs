@@ -0,0 +1,180 @@
+use crate::content::PostManager;
+use crate::history::{diff_lines, DiffLine, PostHistory};
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{anyhow, Context, Result};
+use crossterm::style::{Color, Stylize};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+
+pub async fn handle_history(slug: String, version: Option<u32>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let history = PostHistory::new(&project.root);
+    let versions = history.versions(&slug)?;
+    if versions.is_empty() {
+        println!(
+            "No saved versions of '{}'. Versions are kept when a post is saved from 'blogr new' or 'blogr edit'.",
+            slug
+        );
+        return Ok(());
+    }
+
+    let current = fs::read_to_string(post_path(&project, &slug)?).ok();
+
+    if let Some(number) = version {
+        let version = history.version(&slug, number)?;
+        let content = version.read()?;
+        match &current {
+            Some(current) => {
+                println!("Changes from version {} to the current post:\n", number);
+                print_diff(&content, current);
+            }
+            None => print!("{}", content),
+        }
+        return Ok(());
+    }
+
+    println!("📜 History of '{}'", slug);
+    let mut previous = String::new();
+    for version in &versions {
+        let content = version.read()?;
+        let (added, removed) = diff_lines(&previous, &content).iter().fold(
+            (0, 0),
+            |(added, removed), line| match line {
+                DiffLine::Added(_) => (added + 1, removed),
+                DiffLine::Removed(_) => (added, removed + 1),
+                DiffLine::Same(_) => (added, removed),
+            },
+        );
+        println!(
+            "  {:>3}  {}  {} {}{}",
+            version.number,
+            version
+                .saved_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            paint(format!("{:>5}", format!("+{}", added)), Color::Green),
+            paint(format!("{:>5}", format!("-{}", removed)), Color::Red),
+            if current.as_deref() == Some(content.as_str()) {
+                "  (current)"
+            } else {
+                ""
+            }
+        );
+        previous = content;
+    }
+    println!();
+    println!("💡 Show changes: blogr post history {} --version N", slug);
+    println!("💡 Restore:      blogr post restore {} --version N", slug);
+    Ok(())
+}
+
+pub async fn handle_restore(slug: String, version: u32, force: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let history = PostHistory::new(&project.root);
+    let content = history.version(&slug, version)?.read()?;
+    let path = post_path(&project, &slug)?;
+
+    let current = fs::read_to_string(&path).ok();
+    match &current {
+        Some(current) if *current == content => {
+            Console::info(&format!("'{}' already matches version {}", slug, version));
+            return Ok(());
+        }
+        Some(current) => {
+            println!("Restoring version {} changes the post:\n", version);
+            print_diff(current, &content);
+        }
+        None => Console::info(&format!(
+            "'{}' no longer exists; it will be recreated",
+            slug
+        )),
+    }
+
+    if !force {
+        println!();
+        print!("♻️  Restore version {}? (y/N): ", version);
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+
+        if input != "y" && input != "yes" {
+            Console::info("Restore cancelled");
+            return Ok(());
+        }
+    }
+
+    // Keep the version being replaced so the restore can be undone
+    history.snapshot_file(&slug, &path)?;
+    let replaced = match current {
+        Some(_) => history.versions(&slug)?.last().map(|latest| latest.number),
+        None => None,
+    };
+    fs::write(&path, &content).with_context(|| format!("Failed to write {}", path.display()))?;
+    history.snapshot(&slug, &content)?;
+
+    Console::success(&format!("Restored '{}' to version {}", slug, version));
+    if let Some(replaced) = replaced {
+        println!(
+            "💡 The replaced text was saved as version {}; undo with: blogr post restore {} --version {}",
+            replaced, slug, replaced
+        );
+    }
+    Ok(())
+}
+
+/// File of the post, or where it would be if it was deleted
+fn post_path(project: &Project, slug: &str) -> Result<PathBuf> {
+    let post_manager = PostManager::new(project.posts_dir());
+    Ok(match post_manager.find_by_slug(slug)? {
+        Some(post) => post.file_path,
+        None => project.posts_dir().join(format!("{}.md", slug)),
+    })
+}
+
+/// Color text when writing to a terminal
+fn paint(text: String, color: Color) -> String {
+    if io::stdout().is_terminal() {
+        text.with(color).to_string()
+    } else {
+        text
+    }
+}
+
+/// Print changed lines with a little surrounding context
+fn print_diff(old: &str, new: &str) {
+    const CONTEXT: usize = 2;
+
+    let diff = diff_lines(old, new);
+    let changed: Vec<usize> = diff
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut last_shown = None;
+    for (index, line) in diff.iter().enumerate() {
+        let near_change = changed
+            .iter()
+            .any(|&change| index.abs_diff(change) <= CONTEXT);
+        if !near_change {
+            continue;
+        }
+        if last_shown.is_some_and(|last| last + 1 < index) {
+            println!("{}", paint("  ...".to_string(), Color::DarkGrey));
+        }
+        last_shown = Some(index);
+
+        match line {
+            DiffLine::Same(line) => println!("  {}", line),
+            DiffLine::Removed(line) => println!("{}", paint(format!("- {}", line), Color::Red)),
+            DiffLine::Added(line) => println!("{}", paint(format!("+ {}", line), Color::Green)),
+        }
+    }
+}
@@ -1,8 +1,9 @@
+use crate::config::Config;
 use crate::content::{PostManager, PostStatus};
-use crate::project::Project;
+use crate::project::{ContentStats, Project};
 use crate::utils::Console;
+use crate::workspace::{self, Workspace};
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
 use walkdir::WalkDir;
 
 pub async fn handle_info() -> Result<()> {
@@ -16,14 +17,16 @@ pub async fn handle_info() -> Result<()> {
     let post_manager = PostManager::new(project.posts_dir());
     let posts = post_manager.load_all_posts()?;
 
-    let published_count = posts
+    let status_counts: Vec<String> = PostStatus::ALL
         .iter()
-        .filter(|p| p.metadata.status == PostStatus::Published)
-        .count();
-    let draft_count = posts
-        .iter()
-        .filter(|p| p.metadata.status == PostStatus::Draft)
-        .count();
+        .filter_map(|status| {
+            let count = posts
+                .iter()
+                .filter(|p| p.metadata.status == *status)
+                .count();
+            (count > 0).then(|| format!("{} {}", count, status))
+        })
+        .collect();
 
     let github_status = if let Some(github) = &config.github {
         format!("{}/{}", github.username, github.repository)
@@ -36,15 +39,14 @@ pub async fn handle_info() -> Result<()> {
     println!("  👤 Author: {}", config.blog.author);
     println!("  📄 Description: {}", config.blog.description);
     println!("  🎨 Theme: {}", config.theme.name);
-    println!(
-        "  📊 Posts: {} ({} published, {} draft)",
-        posts.len(),
-        published_count,
-        draft_count
-    );
+    if status_counts.is_empty() {
+        println!("  📊 Posts: 0");
+    } else {
+        println!("  📊 Posts: {} ({})", posts.len(), status_counts.join(", "));
+    }
     println!("  🌐 GitHub: {}", github_status);
-    println!();
-    println!("💡 Edit blogr.toml to update project settings");
+    crate::status!();
+    crate::status!("💡 Edit blogr.toml to update project settings");
 
     Ok(())
 }
@@ -75,13 +77,8 @@ pub async fn handle_check() -> Result<()> {
         Ok(posts) => {
             Console::info(&format!("Found {} posts", posts.len()));
             for post in &posts {
-                // Basic validation - posts are already parsed successfully if we get here
-                if post.metadata.title.is_empty() {
-                    post_issues.push(format!(
-                        "Post '{}' has empty title",
-                        post.file_path.display()
-                    ));
-                }
+                // Basic validation - posts are already parsed successfully if we get here.
+                // Titles are optional, for short posts on link blogs.
                 if post.metadata.author.is_empty() {
                     post_issues.push(format!(
                         "Post '{}' has empty author",
@@ -128,8 +125,8 @@ pub async fn handle_check() -> Result<()> {
         println!("✅ Configuration file is valid");
         println!("✅ Posts are properly formatted");
         println!("✅ Theme is available and configured");
-        println!();
-        println!("🎉 Your project is ready for building and deployment!");
+        crate::status!();
+        crate::status!("🎉 Your project is ready for building and deployment!");
     } else {
         if !post_issues.is_empty() {
             Console::warn("Post validation issues:");
@@ -140,7 +137,7 @@ pub async fn handle_check() -> Result<()> {
         }
 
         Console::warn(&format!("Found {} validation issue(s)", total_issues));
-        println!("💡 Fix these issues before building your site");
+        crate::status!("💡 Fix these issues before building your site");
     }
 
     Ok(())
@@ -190,22 +187,22 @@ pub async fn handle_clean() -> Result<()> {
 
     if cleaned_items.is_empty() {
         Console::info("No build artifacts found to clean");
-        println!("✨ Project is already clean");
+        crate::status!("✨ Project is already clean");
     } else {
         Console::success("Project cleaned successfully!");
         for item in cleaned_items {
-            println!("🧹 Removed {}", item);
+            crate::status!("🧹 Removed {}", item);
         }
 
         let freed_mb = freed_bytes as f64 / 1_048_576.0;
         if freed_mb > 0.1 {
-            println!("📦 Freed up space: {:.1} MB", freed_mb);
+            crate::status!("📦 Freed up space: {:.1} MB", freed_mb);
         } else {
-            println!("📦 Freed up space: {} bytes", freed_bytes);
+            crate::status!("📦 Freed up space: {} bytes", freed_bytes);
         }
     }
 
-    println!("💡 Run 'blogr build' to regenerate site");
+    crate::status!("💡 Run 'blogr build' to regenerate site");
 
     Ok(())
 }
@@ -256,46 +253,14 @@ fn find_temp_files(root: &std::path::Path) -> Result<Vec<std::path::PathBuf>> {
     Ok(temp_files)
 }
 
-pub async fn handle_stats() -> Result<()> {
-    Console::info("Generating project statistics...");
-
+pub async fn handle_stats(json: bool) -> Result<()> {
     // Check if we're in a blogr project
     let project = Project::find_project()?
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
     let post_manager = PostManager::new(project.posts_dir());
     let posts = post_manager.load_all_posts()?;
-
-    let published_count = posts
-        .iter()
-        .filter(|p| p.metadata.status == PostStatus::Published)
-        .count();
-    let draft_count = posts
-        .iter()
-        .filter(|p| p.metadata.status == PostStatus::Draft)
-        .count();
-
-    // Calculate word count and reading time
-    let total_words: usize = posts
-        .iter()
-        .map(|p| p.content.split_whitespace().count())
-        .sum();
-    let average_words = if posts.is_empty() {
-        0
-    } else {
-        total_words / posts.len()
-    };
-    let total_reading_time: usize = posts.iter().map(|p| p.reading_time()).sum();
-
-    // Get all tags and count usage
-    let mut tag_counts = HashMap::new();
-    for post in &posts {
-        for tag in &post.metadata.tags {
-            *tag_counts.entry(tag.clone()).or_insert(0) += 1;
-        }
-    }
-    let mut sorted_tags: Vec<_> = tag_counts.into_iter().collect();
-    sorted_tags.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by count, descending
+    let stats = ContentStats::from_posts(&posts, &project.load_config()?);
 
     // Count static files
     let static_dir = project.static_dir();
@@ -318,29 +283,89 @@ pub async fn handle_stats() -> Result<()> {
         }
     }
 
+    if json {
+        let mut report = serde_json::to_value(&stats)?;
+        report["files"] = serde_json::json!({
+            "static": static_count,
+            "images": image_count,
+            "css": css_count,
+        });
+        return Console::report("project stats", report);
+    }
+
+    Console::info("Generating project statistics...");
+
     println!("📊 Project Statistics:");
     println!();
     println!("📝 Content:");
-    println!("  - Total posts: {}", posts.len());
-    println!("  - Published: {}", published_count);
-    println!("  - Drafts: {}", draft_count);
-    println!("  - Total words: ~{}", total_words);
+    println!("  - Total posts: {}", stats.total_posts);
+    println!("  - Published: {}", stats.published_posts);
+    println!("  - Drafts: {}", stats.draft_posts);
+    println!("  - Total words: ~{}", stats.total_words);
     if !posts.is_empty() {
-        println!("  - Average words per post: {}", average_words);
+        println!("  - Average words per post: {}", stats.average_words);
     }
     println!(
         "  - Estimated reading time: {} minutes total",
-        total_reading_time
+        stats.total_reading_time
     );
+    if let Some(drought) = stats.longest_drought.as_ref().filter(|d| d.days > 0) {
+        println!(
+            "  - Longest gap between posts: {} days ({} → {})",
+            drought.days,
+            drought.start.format("%Y-%m-%d"),
+            drought.end.format("%Y-%m-%d")
+        );
+    }
     println!();
 
-    if !sorted_tags.is_empty() {
-        println!("🏷️ Tags:");
-        for (tag, count) in sorted_tags.iter().take(10) {
-            println!("  - {} ({})", tag, count);
+    if !stats.posts.is_empty() {
+        println!("📄 Posts:");
+        let title_width = stats
+            .posts
+            .iter()
+            .map(|p| p.title.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(40);
+        for post in &stats.posts {
+            let title: String = post.title.chars().take(title_width).collect();
+            let draft = if post.status == PostStatus::Published {
+                String::new()
+            } else {
+                format!(" ({})", post.status)
+            };
+            println!(
+                "  {}  {:<width$}  {:>6} words  {:>3} min{}",
+                post.date.format("%Y-%m-%d"),
+                title,
+                post.words,
+                post.reading_time,
+                draft,
+                width = title_width
+            );
         }
-        if sorted_tags.len() > 10 {
-            println!("  - ... and {} more", sorted_tags.len() - 10);
+        println!();
+    }
+
+    if !stats.posts_per_month.is_empty() {
+        println!("📅 Posts per month:");
+        let counts: Vec<(&String, &usize)> = stats.posts_per_month.iter().collect();
+        print_histogram(&counts);
+        println!();
+    }
+
+    if !stats.tags.is_empty() {
+        println!("🏷️ Tags:");
+        let counts: Vec<(&String, &usize)> = stats
+            .tags
+            .iter()
+            .take(10)
+            .map(|t| (&t.tag, &t.count))
+            .collect();
+        print_histogram(&counts);
+        if stats.tags.len() > 10 {
+            println!("  ... and {} more", stats.tags.len() - 10);
         }
         println!();
     }
@@ -352,6 +377,111 @@ pub async fn handle_stats() -> Result<()> {
     println!();
     println!("🚀 Last build: Never");
     println!("📤 Last deploy: Never");
+    crate::status!();
+    crate::status!("💡 Use 'blogr project stats --json' for machine-readable output");
 
     Ok(())
 }
+
+pub fn handle_list_projects(json: bool) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let root = workspace::search_root(&cwd)?;
+    let workspace = Workspace::find(&cwd)?;
+    let projects = match &workspace {
+        Some(workspace) => workspace.projects(),
+        None => workspace::discover_projects(&root),
+    };
+    let current = Project::find_project()?.map(|project| project.root);
+
+    let rows: Vec<serde_json::Value> = projects
+        .iter()
+        .map(|dir| {
+            let path = dir.strip_prefix(&root).unwrap_or(dir);
+            let path = if path.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                path.display().to_string()
+            };
+            let mut row = serde_json::json!({
+                "name": dir.file_name().map(|name| name.to_string_lossy().to_string()),
+                "path": path,
+                "current": current.as_ref() == Some(dir),
+            });
+            match Config::load_from_file(dir.join("blogr.toml")) {
+                Ok(config) => {
+                    row["title"] = config.blog.title.into();
+                    row["site_type"] = config.site.site_type.into();
+                    row["theme"] = config.theme.name.into();
+                }
+                Err(e) => row["error"] = format!("{:#}", e).into(),
+            }
+            row
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        Console::info(&format!("No blogr projects under {}", root.display()));
+        return Ok(());
+    }
+    match &workspace {
+        Some(workspace) => println!(
+            "📚 Projects in workspace {}:",
+            workspace.root.join(workspace::WORKSPACE_FILE).display()
+        ),
+        None => println!("📚 Projects under {}:", root.display()),
+    }
+    for row in &rows {
+        let marker = if row["current"] == true { "→" } else { " " };
+        match row["error"].as_str() {
+            Some(error) => println!(
+                "{} {}  ❌ {}",
+                marker,
+                row["path"].as_str().unwrap_or(""),
+                error
+            ),
+            None => println!(
+                "{} {}  {} ({}, theme {})",
+                marker,
+                row["path"].as_str().unwrap_or(""),
+                row["title"].as_str().unwrap_or(""),
+                row["site_type"].as_str().unwrap_or(""),
+                row["theme"].as_str().unwrap_or("")
+            ),
+        }
+    }
+    crate::status!();
+    crate::status!("💡 Run a command in one with 'blogr --project <name> <command>'");
+    Ok(())
+}
+
+/// Print labelled counts as a horizontal bar chart
+fn print_histogram(counts: &[(&String, &usize)]) {
+    const MAX_BAR: usize = 30;
+
+    let label_width = counts
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let max_count = counts.iter().map(|(_, count)| **count).max().unwrap_or(0);
+
+    for (label, count) in counts {
+        let bar_len = if max_count == 0 {
+            0
+        } else {
+            (**count * MAX_BAR).div_ceil(max_count)
+        };
+        println!(
+            "  {:<width$}  {} {}",
+            label,
+            "█".repeat(bar_len),
+            count,
+            width = label_width
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use crate::content::{PostManager, PostStatus};
+use crate::project::Project;
+use crate::utils::{Console, Utils};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, Utc};
+
+pub async fn handle_publish(slug: String, at: Option<String>, keep_date: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+
+    let post_manager = PostManager::new(project.posts_dir()).with_history(&project.root);
+    let mut post = post_manager
+        .find_by_slug(&slug)?
+        .ok_or_else(|| anyhow!("Post with slug '{}' not found", slug))?;
+
+    let now = Utc::now();
+    let date = match at {
+        Some(at) => Utils::parse_local_time(&at)?,
+        None if keep_date => post.metadata.date,
+        None => now,
+    };
+    // A date in the future schedules the post instead
+    let status = if date > now {
+        PostStatus::Scheduled
+    } else {
+        PostStatus::Published
+    };
+
+    if post.metadata.status == status && post.metadata.date == date {
+        Console::info(&format!("Post '{}' is already {}", slug, status));
+        return Ok(());
+    }
+
+    let previous = post.metadata.status;
+    post.metadata.status = status;
+    post.metadata.date = date;
+    post_manager.save_post(&post)?;
+
+    let local_date: DateTime<Local> = date.into();
+    let date_str = local_date.format("%Y-%m-%d %H:%M");
+    if status == PostStatus::Scheduled {
+        Console::success(&format!(
+            "Scheduled '{}' for {} (was {})",
+            post.metadata.title, date_str, previous
+        ));
+        println!("💡 It goes live on the first build after that time");
+    } else {
+        Console::success(&format!(
+            "Published '{}' on {} (was {})",
+            post.metadata.title, date_str, previous
+        ));
+        println!("💡 Rebuild or deploy the site to put it online: blogr deploy");
+    }
+
+    Ok(())
+}
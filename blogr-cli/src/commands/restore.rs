@@ -0,0 +1,40 @@
+use crate::project::Project;
+use crate::trash::Trash;
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+
+pub async fn handle_restore(slug: Option<String>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let trash = Trash::new(&project.root);
+
+    let Some(slug) = slug else {
+        let entries = trash.entries()?;
+        if entries.is_empty() {
+            println!("🗑️  The trash is empty");
+            return Ok(());
+        }
+        println!("🗑️  Trash:");
+        for entry in entries.iter().rev() {
+            println!(
+                "  {} - {} (deleted {})",
+                entry.post.slug,
+                entry.post.title,
+                entry.post.deleted_at.format("%Y-%m-%d %H:%M UTC")
+            );
+        }
+        println!();
+        println!("💡 Restore a post: blogr restore <slug>");
+        println!("💡 Empty the trash: blogr delete --purge");
+        return Ok(());
+    };
+
+    let entry = trash
+        .find(&slug)?
+        .ok_or_else(|| anyhow!("No post with slug '{}' in the trash", slug))?;
+    let path = trash.restore(&entry)?;
+
+    Console::success(&format!("Restored post '{}'", entry.post.title));
+    println!("📝 File: {}", path.display());
+    Ok(())
+}
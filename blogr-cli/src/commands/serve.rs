@@ -1,19 +1,31 @@
-use crate::content::PostManager;
+use crate::config::Config;
+use crate::content::{Post, PostManager, PostStatus};
+use crate::fonts;
 use crate::generator::assets::get_mime_type;
+use crate::generator::dependencies::DependencyGraph;
+use crate::generator::output;
+use crate::generator::pages::load_pages;
+use crate::generator::template_errors::TemplateError;
 use crate::generator::SiteBuilder;
+use crate::github_projects;
 use crate::project::Project;
+use crate::query;
 use crate::utils::Console;
+use crate::watcher::Watcher;
 use anyhow::{anyhow, Result};
 use axum::{
     body::Body,
-    extract::{Path as AxumPath, Query},
-    http::{header, StatusCode},
+    extract::{Path as AxumPath, Query, Request},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tokio::fs;
 use tower::ServiceBuilder;
 
@@ -22,14 +34,46 @@ struct AppState {
     output_dir: PathBuf,
     project: Project,
     include_drafts: bool,
+    /// Bumped after every rebuild, for pages to notice and reload
+    version: Arc<AtomicU64>,
+    live_reload: bool,
 }
 
+/// Polls the server and reloads the page after a rebuild
+const LIVE_RELOAD_SCRIPT: &str = r#"<script>
+(function () {
+    let version = null;
+    setInterval(async function () {
+        try {
+            const current = await (await fetch('/__blogr/version', { cache: 'no-store' })).text();
+            if (version !== null && current !== version) {
+                location.reload();
+            }
+            version = current;
+        } catch (error) {}
+    }, 1000);
+})();
+</script>"#;
+
 #[derive(Deserialize)]
 struct PostsQuery {
     page: Option<usize>,
     limit: Option<usize>,
 }
 
+#[derive(Deserialize)]
+struct QueryParams {
+    /// The query, for GET requests
+    q: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RenderQuery {
+    /// Return only the post body instead of the full themed page
+    #[serde(default)]
+    fragment: bool,
+}
+
 #[derive(Serialize)]
 struct PostsResponse {
     posts: Vec<serde_json::Value>,
@@ -39,7 +83,15 @@ struct PostsResponse {
     limit: usize,
 }
 
-pub async fn handle_serve(port: u16, host: String, drafts: bool, open: bool) -> Result<()> {
+pub async fn handle_serve(
+    port: u16,
+    host: String,
+    drafts: bool,
+    open: bool,
+    editor_api: bool,
+    query_api: bool,
+    in_memory: bool,
+) -> Result<()> {
     Console::info(&format!("Starting development server on {}:{}", host, port));
 
     // Check if we're in a blogr project
@@ -60,32 +112,100 @@ pub async fn handle_serve(port: u16, host: String, drafts: bool, open: bool) ->
         .as_ref()
         .map(|p| project.root.join(p))
         .unwrap_or_else(|| project.root.join("_site"));
+    // Builds write to RAM, and the output directory on disk is left alone
+    let memory = in_memory.then(|| output::mount(&output_dir));
 
+    github_projects::refresh(&project.root, &config.github_projects).await;
+    fonts::refresh(&project, &config).await;
     let site_builder = SiteBuilder::new(project.clone(), Some(output_dir.clone()), drafts, false)?;
     site_builder.build()?;
+    let dependencies = site_builder.take_dependencies();
+
+    let version = Arc::new(AtomicU64::new(0));
+    let watcher = Watcher::new(&project, &config)?;
+    {
+        let project = project.clone();
+        let output_dir = output_dir.clone();
+        let version = version.clone();
+        std::thread::spawn(move || {
+            watch(watcher, dependencies, project, output_dir, drafts, version)
+        });
+    }
 
     // Create router
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/", get(serve_index))
         .route("/api/posts", get(serve_posts_api))
-        .route("/*path", get(serve_file))
+        .route("/__blogr/version", get(serve_version));
+    let mut api = Router::new();
+    if editor_api {
+        api = api
+            .route("/api/editor", get(serve_editor_info))
+            .route("/api/editor/render", post(serve_editor_render));
+    }
+    if query_api {
+        api = api.route("/api/query", get(serve_query).post(serve_query));
+    }
+    if editor_api || query_api {
+        app = app.merge(api.route_layer(middleware::from_fn(local_origins_only)));
+    }
+    let app = app
+        .route("/{*path}", get(serve_file))
         .with_state(AppState {
             output_dir: output_dir.clone(),
             project: project.clone(),
             include_drafts: drafts,
+            version,
+            live_reload: config.dev.auto_reload,
         })
+        .layer(middleware::from_fn_with_state(
+            Arc::<str>::from(host.as_str()),
+            local_hosts_only,
+        ))
         .layer(ServiceBuilder::new());
 
     Console::success(&format!(
         "Development server running at http://{}:{}",
         host, port
     ));
-    println!("📝 Site built and ready");
-    println!("🌐 Server serving from: {}", output_dir.display());
+    crate::status!("📝 Site built and ready");
+    match &memory {
+        Some(memory) => {
+            let (files, bytes) = memory.usage();
+            crate::status!(
+                "🧠 Serving {} files ({:.1} MB) from memory",
+                files,
+                bytes as f64 / 1_000_000.0
+            );
+        }
+        None => crate::status!("🌐 Server serving from: {}", output_dir.display()),
+    }
     if drafts {
-        println!("📝 Including draft posts");
+        crate::status!("📝 Including draft posts");
     }
-    println!("Press Ctrl+C to stop");
+    crate::status!(
+        "👀 Watching for changes{}",
+        if config.dev.auto_reload {
+            ", pages reload after rebuilds"
+        } else {
+            ""
+        }
+    );
+    if editor_api {
+        crate::status!(
+            "✏️  Editor API: POST a post's markdown to http://{}:{}/api/editor/render",
+            host,
+            port
+        );
+    }
+    if query_api {
+        crate::status!(
+            "🔎 Query API: POST a JSON query to http://{}:{}/api/query",
+            host,
+            port
+        );
+    }
+    crate::status!("Press Ctrl+C to stop");
 
     // Open browser if requested
     if open {
@@ -103,10 +223,90 @@ pub async fn handle_serve(port: u16, host: String, drafts: bool, open: bool) ->
     Ok(())
 }
 
+/// Rebuild the site whenever the project changes. When only posts changed,
+/// just the pages they appear on are written again. Failed builds are
+/// reported and the previous output keeps being served.
+fn watch(
+    mut watcher: Watcher,
+    mut dependencies: Option<DependencyGraph>,
+    project: Project,
+    output_dir: PathBuf,
+    drafts: bool,
+    version: Arc<AtomicU64>,
+) {
+    loop {
+        let changed = watcher.wait();
+        let names: Vec<String> = changed.iter().map(|path| watcher.display(path)).collect();
+        Console::info(&match names.len() {
+            1 => format!("Changed: {}", names[0]),
+            n if n <= 5 => format!("Changed: {}", names.join(", ")),
+            n => format!("Changed: {} and {} more", names[..3].join(", "), n - 3),
+        });
+
+        let config = match project.load_config() {
+            Ok(config) => config,
+            Err(e) => {
+                Console::error(&format!("Not rebuilding, blogr.toml is invalid: {:#}", e));
+                continue;
+            }
+        };
+        // Pick up changes to the watch settings themselves
+        if names.iter().any(|name| name == "blogr.toml") {
+            match Watcher::new(&project, &config) {
+                Ok(new_watcher) => watcher = new_watcher,
+                Err(e) => Console::warn(&format!("Keeping the previous watch settings: {:#}", e)),
+            }
+        }
+
+        let started = std::time::Instant::now();
+        let result = SiteBuilder::new_with_config(
+            project.clone(),
+            config,
+            Some(output_dir.clone()),
+            drafts,
+            false,
+        )
+        .and_then(|builder| {
+            let partial = match &dependencies {
+                Some(previous) => builder.rebuild_posts(previous, &changed)?,
+                None => None,
+            };
+            if partial.is_none() {
+                builder.build()?;
+            }
+            Ok(builder.take_dependencies())
+        });
+        // Files written by build hooks, such as bundled CSS in static/, don't
+        // start another rebuild
+        watcher.changes();
+        match result {
+            Ok(graph) => {
+                dependencies = graph;
+                version.fetch_add(1, Ordering::SeqCst);
+                Console::success(&format!(
+                    "Rebuilt in {:.2}s",
+                    started.elapsed().as_secs_f64()
+                ));
+            }
+            Err(e) => {
+                // The output may be half written, so the next rebuild is a full one
+                dependencies = None;
+                Console::error(&format!("Rebuild failed: {:#}", e));
+            }
+        }
+    }
+}
+
+async fn serve_version(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> impl IntoResponse {
+    state.version.load(Ordering::SeqCst).to_string()
+}
+
 async fn serve_index(
     axum::extract::State(state): axum::extract::State<AppState>,
 ) -> impl IntoResponse {
-    serve_file_from_path(state.output_dir.join("index.html")).await
+    serve_file_from_path(&state, state.output_dir.join("index.html")).await
 }
 
 async fn serve_file(
@@ -116,47 +316,65 @@ async fn serve_file(
     let file_path = state.output_dir.join(&path);
 
     // If it's a directory, try to serve index.html
-    if file_path.is_dir() {
+    if output::is_dir(&file_path) {
         let index_path = file_path.join("index.html");
-        if index_path.exists() {
-            return serve_file_from_path(index_path).await;
+        if output::is_file(&index_path) {
+            return serve_file_from_path(&state, index_path).await;
         }
     }
 
     // Try to serve the file directly
-    if file_path.exists() && file_path.is_file() {
-        return serve_file_from_path(file_path).await;
+    if output::is_file(&file_path) {
+        return serve_file_from_path(&state, file_path).await;
     }
 
     // If it's an HTML request without .html extension, try adding it
     if !path.ends_with(".html") && !path.contains('.') {
         let html_path = state.output_dir.join(format!("{}.html", path));
-        if html_path.exists() {
-            return serve_file_from_path(html_path).await;
+        if output::is_file(&html_path) {
+            return serve_file_from_path(&state, html_path).await;
         }
     }
 
-    // File not found
-    Response::builder()
-        .status(StatusCode::NOT_FOUND)
-        .body(Body::from("File not found"))
-        .unwrap()
+    // File not found: show the site's 404 page, as static hosts do
+    error_page(&state.output_dir, StatusCode::NOT_FOUND, "File not found").await
+}
+
+/// Read a file of the built site, from memory with `--in-memory`
+async fn read_output(path: &Path) -> std::io::Result<Vec<u8>> {
+    if output::is_mounted(path) {
+        return output::read(path);
+    }
+    fs::read(path).await
+}
+
+/// The site's `<status>.html` error page, or plain text when it has none
+async fn error_page(output_dir: &Path, status: StatusCode, fallback: &'static str) -> Response {
+    let page = output_dir.join(format!("{}.html", status.as_u16()));
+    match read_output(&page).await {
+        Ok(content) => Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, get_mime_type(&page))
+            .body(Body::from(content))
+            .unwrap(),
+        Err(_) => Response::builder()
+            .status(status)
+            .body(Body::from(fallback))
+            .unwrap(),
+    }
 }
 
 async fn serve_posts_api(
     Query(params): Query<PostsQuery>,
     axum::extract::State(state): axum::extract::State<AppState>,
 ) -> impl IntoResponse {
-    let page = params.page.unwrap_or(1);
-    let limit = params.limit.unwrap_or(10);
-
     // Load all posts
     let post_manager = PostManager::new(state.project.posts_dir());
     let posts = match post_manager.load_all_posts() {
         Ok(mut posts) => {
             // Filter drafts if not including them
             if !state.include_drafts {
-                posts.retain(|p| p.metadata.status == crate::content::PostStatus::Published);
+                posts.retain(|p| p.metadata.status.is_public());
             }
             posts
         }
@@ -172,55 +390,288 @@ async fn serve_posts_api(
         }
     };
 
+    let config = state.project.load_config().unwrap_or_default();
+    Json(posts_page(&posts, &config, &params)).into_response()
+}
+
+/// One page of posts with rendered content, `pagination.posts_per_page` long
+/// unless the request sets `limit`
+fn posts_page(posts: &[Post], config: &Config, params: &PostsQuery) -> PostsResponse {
+    let page = params.page.unwrap_or(1);
+    let limit = params
+        .limit
+        .unwrap_or_else(|| config.build.pagination.per_page());
+
     let total = posts.len();
     let start = (page.saturating_sub(1)) * limit;
     let end = (start + limit).min(total);
 
-    let page_posts: Vec<_> = posts.iter().skip(start).take(limit).collect();
     let mut posts_with_content = Vec::new();
 
-    for post in &page_posts {
+    for post in posts.iter().skip(start).take(limit) {
         // Convert markdown to HTML for each post
         let html_content = match crate::generator::markdown::render_markdown(&post.content) {
             Ok(content) => content,
             Err(e) => {
-                eprintln!(
-                    "Warning: Failed to render markdown for post {}: {}",
-                    post.metadata.slug, e
+                tracing::warn!(
+                    "Failed to render markdown for post {}: {}",
+                    post.metadata.slug,
+                    e
                 );
                 post.content.clone() // Fallback to raw content
             }
         };
 
-        // Calculate reading time (average 200 words per minute)
-        let word_count = post.content.split_whitespace().count();
-        let reading_time = (word_count / 200).max(1);
-
         // Create a struct that includes both post data and rendered content
         let post_data = serde_json::json!({
             "metadata": post.metadata,
             "content": html_content,
-            "reading_time": reading_time
+            "reading_time": post.reading_time(config).minutes,
+            "url": config.urls.page_path(&format!("posts/{}.html", post.metadata.slug))
         });
 
         posts_with_content.push(post_data);
     }
 
-    let response = PostsResponse {
+    PostsResponse {
         posts: posts_with_content,
         has_more: end < total,
         total,
         page,
         limit,
+    }
+}
+
+/// Answer a query about posts, tags and pages, sent as the body or as `?q=`
+async fn serve_query(
+    Query(params): Query<QueryParams>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+    body: String,
+) -> Response {
+    let text = params.q.unwrap_or(body);
+    let result =
+        serde_json::from_str::<query::Query>(if text.trim().is_empty() { "{}" } else { &text })
+            .map_err(|e| anyhow!("Invalid query: {}", e))
+            .and_then(|query| {
+                let config = state.project.load_config()?;
+                let posts = PostManager::new(state.project.posts_dir()).load_all_posts()?;
+                let pages = load_pages(&state.project.root.join("content"))?;
+                query::run(&query, &posts, &pages, &config, &state.project.root)
+            });
+
+    let (status, body) = match result {
+        Ok(data) => (StatusCode::OK, serde_json::json!({ "data": data })),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            serde_json::json!({ "errors": [format!("{:#}", e)] }),
+        ),
     };
+    (status, Json(body)).into_response()
+}
+
+/// Keep the editor and query APIs to pages served from this machine and to
+/// editor webviews. Requests from other sites open in the same browser are
+/// refused, so they can't read the project's content; requests without an
+/// `Origin`, such as from curl or an editor process, are let through.
+async fn local_origins_only(request: Request, next: Next) -> Response {
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    let Some(origin) = origin else {
+        return next.run(request).await;
+    };
+    if !origin.to_str().is_ok_and(is_local_origin) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(header::VARY, header::ORIGIN.into());
+    response
+}
+
+fn is_local_origin(origin: &str) -> bool {
+    if origin.starts_with("vscode-webview://") {
+        return true;
+    }
+    let Ok(url) = url::Url::parse(origin) else {
+        return false;
+    };
+    matches!(url.scheme(), "http" | "https")
+        && match url.host() {
+            Some(url::Host::Domain(domain)) => domain == "localhost",
+            Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        }
+}
+
+/// Answer only requests addressed to this machine. Another site could point
+/// one of its domains at 127.0.0.1 and read the server as its own origin
+/// (DNS rebinding); its requests still name that domain in `Host`. `host` is
+/// the address the server was started on, which is also allowed.
+async fn local_hosts_only(
+    axum::extract::State(host): axum::extract::State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let allowed = match request.headers().get(header::HOST) {
+        Some(value) => value
+            .to_str()
+            .is_ok_and(|value| is_local_host(value, &host)),
+        None => true,
+    };
+    if !allowed {
+        return (StatusCode::FORBIDDEN, "Host not allowed").into_response();
+    }
+    next.run(request).await
+}
+
+/// Whether a `Host` header names this machine: `localhost`, an IP address or
+/// the host the server was started on. Addresses can't be rebound, so
+/// they're fine even when the server listens on the network.
+fn is_local_host(value: &str, server_host: &str) -> bool {
+    let Ok(url) = url::Url::parse(&format!("http://{}", value)) else {
+        return false;
+    };
+    if url.path() != "/" || !url.username().is_empty() {
+        return false;
+    }
+    match url.host() {
+        Some(url::Host::Domain(domain)) => {
+            domain == "localhost"
+                || domain.ends_with(".localhost")
+                || domain.eq_ignore_ascii_case(server_host)
+        }
+        Some(url::Host::Ipv4(_)) | Some(url::Host::Ipv6(_)) => true,
+        None => false,
+    }
+}
+
+/// Describe the editor API so plugins can check what the server supports
+async fn serve_editor_info(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> impl IntoResponse {
+    let theme = state
+        .project
+        .load_config()
+        .map(|config| config.theme.name)
+        .unwrap_or_default();
+
+    Json(serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "theme": theme,
+        "render": {
+            "method": "POST",
+            "path": "/api/editor/render",
+            "body": "markdown, with or without frontmatter",
+            "query": { "fragment": "true for the post body only" },
+        },
+    }))
+}
+
+/// Render an unsaved editor buffer with the active theme
+async fn serve_editor_render(
+    Query(params): Query<RenderQuery>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    buffer: String,
+) -> Response {
+    let result = editor_post(&state.project, &buffer).and_then(|post| {
+        if params.fragment {
+            return crate::generator::markdown::render_markdown(&post.content);
+        }
+        let builder = SiteBuilder::new(state.project.clone(), None, true, true)?;
+        let html = builder.render_post(&post)?;
+
+        // Let the page load the theme's assets from this server wherever it's shown
+        let host = headers
+            .get(header::HOST)
+            .and_then(|host| host.to_str().ok())
+            .unwrap_or("localhost");
+        Ok(insert_base_tag(&html, &format!("http://{}/", host)))
+    });
+
+    match result {
+        Ok(html) => Response::builder()
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .body(Body::from(html))
+            .unwrap(),
+        // Template errors as JSON, so plugins can jump to the line
+        Err(e) => match e.downcast_ref::<TemplateError>() {
+            Some(error) => {
+                let location = error.location.as_ref();
+                let body = serde_json::json!({
+                    "error": error.message,
+                    "template": error.template,
+                    "line": location.map(|l| l.line),
+                    "column": location.map(|l| l.column),
+                    "subject": error.subject,
+                    "rendering": error.rendering,
+                    "report": error.to_string(),
+                });
+                Response::builder()
+                    .status(StatusCode::UNPROCESSABLE_ENTITY)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap()
+            }
+            None => Response::builder()
+                .status(StatusCode::UNPROCESSABLE_ENTITY)
+                .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(Body::from(format!("{:#}", e)))
+                .unwrap(),
+        },
+    }
+}
+
+/// Build a post from an editor buffer. Buffers without frontmatter get
+/// placeholder metadata, titled after their first heading.
+fn editor_post(project: &Project, buffer: &str) -> Result<Post> {
+    let buffer = buffer.replace("\r\n", "\n");
+    if buffer.starts_with("---\n") {
+        return Post::parse(&buffer, Path::new("preview.md"));
+    }
+
+    let title = buffer
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .unwrap_or("Untitled")
+        .trim()
+        .to_string();
+    let author = project
+        .load_config()
+        .map(|config| config.blog.author)
+        .unwrap_or_default();
+    let mut post = Post::new(title, author, None, vec![], None, PostStatus::Draft);
+    post.content = buffer;
+    Ok(post)
+}
+
+fn insert_base_tag(html: &str, base: &str) -> String {
+    let tag = format!("<base href=\"{}\">", base);
+    match html
+        .find("<head")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1))
+    {
+        Some(index) => format!("{}{}{}", &html[..index], tag, &html[index..]),
+        None => format!("{}{}", tag, html),
+    }
+}
 
-    Json(response).into_response()
+fn insert_live_reload(html: &str) -> String {
+    match html.rfind("</body>") {
+        Some(index) => format!("{}{}{}", &html[..index], LIVE_RELOAD_SCRIPT, &html[index..]),
+        None => format!("{}{}", html, LIVE_RELOAD_SCRIPT),
+    }
 }
 
-async fn serve_file_from_path(path: PathBuf) -> Response {
-    match fs::read(&path).await {
-        Ok(content) => {
+async fn serve_file_from_path(state: &AppState, path: PathBuf) -> Response {
+    match read_output(&path).await {
+        Ok(mut content) => {
             let mime_type = get_mime_type(&path);
+            if state.live_reload && mime_type.starts_with("text/html") {
+                content = insert_live_reload(&String::from_utf8_lossy(&content)).into_bytes();
+            }
             Response::builder()
                 .header(header::CONTENT_TYPE, mime_type)
                 .body(Body::from(content))
@@ -232,3 +683,163 @@ async fn serve_file_from_path(path: PathBuf) -> Response {
             .unwrap(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_posts_per_page() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = Project::init(
+            temp_dir.path(),
+            "Test Blog".to_string(),
+            "Test Author".to_string(),
+            "A test blog".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let mut config = project.load_config().unwrap();
+        config.build.pagination.posts_per_page = 2;
+        config.reading_time.words_per_minute = 1;
+
+        let posts: Vec<Post> = (1..=5)
+            .map(|n| editor_post(&project, &format!("# Post {n}\n\nBody")).unwrap())
+            .collect();
+        let query = |page, limit| PostsQuery { page, limit };
+
+        let first = posts_page(&posts, &config, &query(None, None));
+        assert_eq!(
+            (first.posts.len(), first.limit, first.has_more),
+            (2, 2, true)
+        );
+        let last = posts_page(&posts, &config, &query(Some(3), None));
+        assert_eq!((last.posts.len(), last.has_more), (1, false));
+        let explicit = posts_page(&posts, &config, &query(None, Some(4)));
+        assert_eq!(explicit.posts.len(), 4);
+        let minutes = posts[0].reading_time(&config).minutes;
+        assert!(minutes > 1);
+        assert_eq!(first.posts[0]["reading_time"], minutes);
+
+        // The theme asks the dev server for pages of the same size
+        let output = temp_dir.path().join("out");
+        SiteBuilder::new_with_config(project, config, Some(output.clone()), false, false)
+            .unwrap()
+            .build()
+            .unwrap();
+        let index = std::fs::read_to_string(output.join("index.html")).unwrap();
+        assert!(index.contains("const pageSize = 2;"), "{index}");
+        assert!(index.contains("limit=${pageSize}"));
+    }
+
+    #[test]
+    fn test_editor_post() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = Project::init(
+            temp_dir.path(),
+            "Test Blog".to_string(),
+            "Test Author".to_string(),
+            "A test blog".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let post = editor_post(&project, "Intro\r\n\r\n# Draft title\r\n\r\nBody").unwrap();
+        assert_eq!(post.metadata.title, "Draft title");
+        assert_eq!(post.metadata.author, "Test Author");
+        assert_eq!(post.metadata.status, PostStatus::Draft);
+        assert_eq!(post.content, "Intro\n\n# Draft title\n\nBody");
+        assert_eq!(
+            editor_post(&project, "No heading").unwrap().metadata.title,
+            "Untitled"
+        );
+
+        let buffer = "---\ntitle: \"From frontmatter\"\ndate: 2024-03-02\nauthor: Ann\n\
+            description: \"\"\ntags: [rust]\nstatus: published\n---\n# Heading\n";
+        let post = editor_post(&project, buffer).unwrap();
+        assert_eq!(post.metadata.title, "From frontmatter");
+        assert_eq!(post.metadata.author, "Ann");
+        assert_eq!(post.metadata.tags, ["rust"]);
+        assert!(editor_post(&project, "---\ntitle: [\n---\n").is_err());
+    }
+
+    #[test]
+    fn test_insert_base_tag() {
+        let base = "http://127.0.0.1:3000/";
+        assert_eq!(
+            insert_base_tag("<html><head lang=\"en\"><title>T</title></head></html>", base),
+            "<html><head lang=\"en\"><base href=\"http://127.0.0.1:3000/\"><title>T</title></head></html>"
+        );
+        assert_eq!(
+            insert_base_tag("<p>No head</p>", base),
+            "<base href=\"http://127.0.0.1:3000/\"><p>No head</p>"
+        );
+    }
+
+    #[test]
+    fn test_is_local_origin() {
+        assert!(is_local_origin("http://localhost:3000"));
+        assert!(is_local_origin("http://127.0.0.1:8080"));
+        assert!(is_local_origin("http://[::1]:3000"));
+        assert!(is_local_origin(
+            "vscode-webview://1a2b3c4d5e6f7g8h9i0j1k2l3m4n5o6p7q8r9s0t1u2v3w4x5y6z"
+        ));
+        assert!(!is_local_origin("https://evil.example"));
+        assert!(!is_local_origin("http://localhost.evil.example"));
+        assert!(!is_local_origin("null"));
+    }
+
+    #[test]
+    fn test_is_local_host() {
+        assert!(is_local_host("localhost:3000", "127.0.0.1"));
+        assert!(is_local_host("LOCALHOST", "127.0.0.1"));
+        assert!(is_local_host("blog.localhost:3000", "127.0.0.1"));
+        assert!(is_local_host("127.0.0.1:3000", "127.0.0.1"));
+        assert!(is_local_host("192.168.1.20:3000", "0.0.0.0"));
+        assert!(is_local_host("[::1]:3000", "::1"));
+        assert!(is_local_host("devbox.lan:3000", "devbox.lan"));
+        assert!(!is_local_host("evil.example:3000", "127.0.0.1"));
+        assert!(!is_local_host("localhost.evil.example", "127.0.0.1"));
+        assert!(!is_local_host("devbox.lan:3000", "0.0.0.0"));
+        assert!(!is_local_host("localhost/evil", "127.0.0.1"));
+        assert!(!is_local_host("", "127.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_rebound_hosts_are_refused() {
+        let app = Router::new()
+            .route("/__blogr/version", get(|| async { "1" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::<str>::from("127.0.0.1"),
+                local_hosts_only,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let url = format!("http://{}/__blogr/version", addr);
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let response = client
+            .get(&url)
+            .header(reqwest::header::HOST, format!("localhost:{}", addr.port()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let response = client
+            .get(&url)
+            .header(
+                reqwest::header::HOST,
+                format!("evil.example:{}", addr.port()),
+            )
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    }
+}
@@ -1,9 +1,32 @@
+use crate::generator::theme_lint::{lint_theme, LintIssue};
 use crate::project::Project;
+use crate::theme_package;
 use crate::utils::Console;
 use anyhow::{anyhow, Result};
-use blogr_themes::{get_all_themes, get_theme, SiteType, ThemeInfo};
+use blogr_themes::{get_all_themes, get_theme, SiteType, Theme, ThemeInfo};
 use std::collections::hash_map::Entry;
 
+/// Look up a theme, including those installed in the current project
+fn find_theme(name: &str) -> Result<Option<Box<dyn Theme>>> {
+    match Project::find_project()? {
+        Some(project) => project.load_theme(name),
+        None => Ok(get_theme(name)),
+    }
+}
+
+/// A theme in `theme list --json`
+fn theme_json(info: &ThemeInfo, source: &str, current: &Option<String>) -> serde_json::Value {
+    serde_json::json!({
+        "name": info.name,
+        "version": info.version,
+        "author": info.author,
+        "description": info.description,
+        "site_type": info.site_type.to_string(),
+        "source": source,
+        "current": current.as_deref() == Some(info.name.as_str()),
+    })
+}
+
 pub async fn handle_list() -> Result<()> {
     // Load all available themes from blogr-themes crate
     let all_themes = get_all_themes();
@@ -13,14 +36,39 @@ pub async fn handle_list() -> Result<()> {
         return Ok(());
     }
 
-    let current_theme = if let Ok(Some(project)) = Project::find_project() {
-        match project.load_config() {
-            Ok(config) => Some(config.theme.name),
-            Err(_) => None,
+    let project = Project::find_project().ok().flatten();
+    let current_theme = project
+        .as_ref()
+        .and_then(|project| project.load_config().ok())
+        .map(|config| config.theme.name);
+
+    if Console::is_json() {
+        let mut themes: Vec<serde_json::Value> = all_themes
+            .iter()
+            .map(|theme| theme_json(&theme.info(), "built-in", &current_theme))
+            .collect();
+        if let Some(project) = &project {
+            let dirs = [
+                ("installed", Some(project.themes_dir())),
+                ("shared", project.shared_themes_dir()),
+            ];
+            for (source, dir) in dirs {
+                for (_, theme) in dir
+                    .map(|dir| theme_package::installed(&dir))
+                    .unwrap_or_default()
+                {
+                    match theme {
+                        Ok(theme) => themes.push(theme_json(&theme.info(), source, &current_theme)),
+                        Err(e) => Console::warn(&e.to_string()),
+                    }
+                }
+            }
         }
-    } else {
-        None
-    };
+        return Console::report(
+            "theme list",
+            serde_json::json!({ "current": current_theme, "themes": themes }),
+        );
+    }
 
     println!("📋 Available themes:");
     // Separate themes by type
@@ -30,7 +78,7 @@ pub async fn handle_list() -> Result<()> {
     for theme in all_themes {
         let info = theme.info();
         match info.site_type {
-            SiteType::Blog => blog_themes.push(info),
+            SiteType::Blog | SiteType::Hybrid => blog_themes.push(info),
             SiteType::Personal => personal_themes.push(info),
         }
     }
@@ -51,8 +99,35 @@ pub async fn handle_list() -> Result<()> {
             .for_each(|theme| print_theme_info(&current_theme, theme));
     }
 
-    println!();
-    println!("💡 Use 'blogr theme info <name>' for detailed information");
+    // Display themes installed into the project
+    if let Some(project) = &project {
+        let installed = theme_package::installed(&project.themes_dir());
+        if !installed.is_empty() {
+            println!("\n🧩 Installed Themes (in themes/):");
+        }
+        for (dir, theme) in installed {
+            match theme {
+                Ok(theme) => print_theme_info(&current_theme, &theme.info()),
+                Err(e) => println!("  ❌ {} - {}\n", dir.display(), e),
+            }
+        }
+
+        if let Some(shared) = project.shared_themes_dir() {
+            let installed = theme_package::installed(&shared);
+            if !installed.is_empty() {
+                println!("\n🧩 Shared Themes (in {}):", shared.display());
+            }
+            for (dir, theme) in installed {
+                match theme {
+                    Ok(theme) => print_theme_info(&current_theme, &theme.info()),
+                    Err(e) => println!("  ❌ {} - {}\n", dir.display(), e),
+                }
+            }
+        }
+    }
+
+    crate::status!();
+    crate::status!("💡 Use 'blogr theme info <name>' for detailed information");
 
     Ok(())
 }
@@ -71,6 +146,9 @@ fn print_theme_info(current_theme: &Option<String>, theme: &ThemeInfo) {
         "      👤 Author: {} | 📦 Version: {}",
         theme.author, theme.version
     );
+    if theme.site_type == SiteType::Hybrid {
+        println!("      🏡 Also builds hybrid sites (a personal landing page with a blog)");
+    }
     println!();
 }
 
@@ -78,13 +156,14 @@ pub async fn handle_info(name: String) -> Result<()> {
     Console::info(&format!("Theme information: {}", name));
 
     // Load theme by name
-    if let Some(theme) = get_theme(&name) {
+    if let Some(theme) = find_theme(&name)? {
         let info = theme.info();
 
         println!("🎨 Theme: {}", info.name);
         println!("📝 Description: {}", info.description);
         println!("👤 Author: {}", info.author);
         println!("📦 Version: {}", info.version);
+        println!("🧭 Site type: {}", info.site_type);
         println!();
 
         if !info.config_schema.is_empty() {
@@ -106,14 +185,14 @@ pub async fn handle_info(name: String) -> Result<()> {
                     println!();
                     println!("✅ This theme is currently active");
                 } else {
-                    println!();
-                    println!("💡 Use 'blogr theme set {}' to activate this theme", name);
+                    crate::status!();
+                    crate::status!("💡 Use 'blogr theme set {}' to activate this theme", name);
                 }
             }
         }
     } else {
         Console::warn(&format!("Theme '{}' not found", name));
-        println!("💡 Run 'blogr theme list' to see available themes");
+        crate::status!("💡 Run 'blogr theme list' to see available themes");
     }
 
     Ok(())
@@ -127,7 +206,7 @@ pub async fn handle_set(name: String) -> Result<()> {
         .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
 
     // Validate theme exists
-    let theme = get_theme(&name).ok_or_else(|| {
+    let theme = project.load_theme(&name)?.ok_or_else(|| {
         anyhow!(
             "Theme '{}' not found. Run 'blogr theme list' to see available themes.",
             name
@@ -139,37 +218,28 @@ pub async fn handle_set(name: String) -> Result<()> {
 
     // Validate theme compatibility with site type
     let theme_info = theme.info();
-    let config_site_type = match config.site.site_type.as_str() {
-        "blog" => SiteType::Blog,
-        "personal" => SiteType::Personal,
-        other => {
-            return Err(anyhow!(
-                "❌ Unknown site type '{}' in configuration. Expected 'blog' or 'personal'.",
-                other
-            ))
-        }
-    };
+    let config_site_type: SiteType = config
+        .site
+        .site_type
+        .parse()
+        .map_err(|e| anyhow!("❌ {} in configuration", e))?;
 
-    if theme_info.site_type != config_site_type {
+    if !theme_info.supports(&config_site_type) {
         // Dynamically build theme lists by site type
-        let mut blog_theme_names = Vec::new();
-        let mut personal_theme_names = Vec::new();
-
-        for theme in get_all_themes() {
-            let info = theme.info();
-            if info.site_type == SiteType::Blog {
-                blog_theme_names.push(info.name);
-            } else {
-                personal_theme_names.push(info.name);
-            }
-        }
-
-        let blog_themes = blog_theme_names.join(", ");
-        let personal_themes = personal_theme_names.join(", ");
+        let names_for = |site_type: SiteType| {
+            get_all_themes()
+                .iter()
+                .map(|theme| theme.info())
+                .filter(|info| info.supports(&site_type))
+                .map(|info| info.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
 
         return Err(anyhow!(
             "❌ Theme '{}' is a {} theme, but your site is configured as a {} site.\n\n\
             {} themes: {}\n\
+            {} themes: {}\n\
             {} themes: {}\n\n\
             💡 To use this theme, either:\n\
             1. Choose a compatible {} theme from the list above\n\
@@ -178,9 +248,11 @@ pub async fn handle_set(name: String) -> Result<()> {
             theme_info.site_type,
             config_site_type,
             "Blog",
-            blog_themes,
+            names_for(SiteType::Blog),
             "Personal",
-            personal_themes,
+            names_for(SiteType::Personal),
+            "Hybrid",
+            names_for(SiteType::Hybrid),
             config_site_type,
             theme_info.site_type
         ));
@@ -203,13 +275,13 @@ pub async fn handle_set(name: String) -> Result<()> {
     config.save_to_file(&config_path)?;
 
     Console::success(&format!("Theme set to: {}", name));
-    println!("🎨 Theme changed successfully");
-    println!("📝 Configuration updated in blogr.toml");
-    println!("🔄 Run 'blogr build' or 'blogr serve' to see changes");
+    crate::status!("🎨 Theme changed successfully");
+    crate::status!("📝 Configuration updated in blogr.toml");
+    crate::status!("🔄 Run 'blogr build' or 'blogr serve' to see changes");
 
     if !theme_info.config_schema.is_empty() {
-        println!();
-        println!(
+        crate::status!();
+        crate::status!(
             "💡 Use 'blogr theme info {}' to see available configuration options",
             name
         );
@@ -222,7 +294,7 @@ pub async fn handle_preview(name: String) -> Result<()> {
     Console::info(&format!("Previewing theme: {}", name));
 
     // Load theme by name
-    let theme = get_theme(&name).ok_or_else(|| {
+    let theme = find_theme(&name)?.ok_or_else(|| {
         anyhow!(
             "Theme '{}' not found. Run 'blogr theme list' to see available themes.",
             name
@@ -291,20 +363,183 @@ pub async fn handle_preview(name: String) -> Result<()> {
     if let Ok(Some(project)) = Project::find_project() {
         if let Ok(config) = project.load_config() {
             if config.theme.name != name {
-                println!();
-                println!(
+                crate::status!();
+                crate::status!(
                     "💡 Like this theme? Use 'blogr theme set {}' to activate it",
                     name
                 );
             } else {
-                println!();
-                println!("✅ This theme is currently active in your project");
+                crate::status!();
+                crate::status!("✅ This theme is currently active in your project");
             }
         }
     } else {
-        println!();
-        println!("💡 Create a new project with 'blogr init' to use this theme");
+        crate::status!();
+        crate::status!("💡 Create a new project with 'blogr init' to use this theme");
+    }
+
+    Ok(())
+}
+
+pub async fn handle_lint(name: Option<String>) -> Result<()> {
+    let name = match name {
+        Some(name) => Some(name),
+        None => match Project::find_project()? {
+            Some(project) => Some(project.load_config()?.theme.name),
+            None => None,
+        },
+    };
+
+    let themes = match &name {
+        Some(name) => vec![find_theme(name)?.ok_or_else(|| {
+            anyhow!(
+                "Theme '{}' not found. Run 'blogr theme list' to see available themes.",
+                name
+            )
+        })?],
+        None => get_all_themes(),
+    };
+
+    let mut total = 0;
+    for theme in themes {
+        let info = theme.info();
+        Console::info(&format!("Linting theme '{}'", info.name));
+
+        let issues = lint_theme(theme.as_ref());
+        if issues.is_empty() {
+            Console::success(&format!("{}: no problems found", info.name));
+            continue;
+        }
+
+        print_issues(&issues);
+        Console::warn(&format!("{}: {} problem(s)", info.name, issues.len()));
+        total += issues.len();
+    }
+
+    if total > 0 {
+        return Err(anyhow!("Theme lint found {} problem(s)", total));
+    }
+    Ok(())
+}
+
+pub async fn handle_install(source: String, rev: Option<String>, shared: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let mut config = project.load_config()?;
+
+    let themes_dir = if shared {
+        project.shared_themes_dir().ok_or_else(|| {
+            anyhow!("--shared needs a blogr-workspace.toml above the project with themes_dir set")
+        })?
+    } else {
+        project.themes_dir()
+    };
+
+    Console::info(&format!("Installing theme from {}", source));
+    let (slug, package) = theme_package::install(&themes_dir, &source, rev.as_deref())?;
+
+    Console::success(&format!(
+        "Installed {} v{} into {}",
+        slug,
+        package.version,
+        themes_dir.join(&slug).display()
+    ));
+    if let Some(commit) = &package.commit {
+        crate::status!("📌 Pinned to commit {}", &commit[..commit.len().min(12)]);
+    }
+    config.theme.packages.insert(slug.clone(), package);
+    config.save_to_file(project.root.join("blogr.toml"))?;
+
+    report_lint(&project, &slug)?;
+    if config.theme.name != slug {
+        crate::status!("💡 Use 'blogr theme set {}' to activate it", slug);
+    }
+    Ok(())
+}
+
+pub async fn handle_update(name: Option<String>, rev: Option<String>) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let mut config = project.load_config()?;
+
+    let names: Vec<String> = match &name {
+        Some(name) => {
+            if !config.theme.packages.contains_key(name) {
+                return Err(anyhow!(
+                    "Theme '{}' was not installed with 'blogr theme install'",
+                    name
+                ));
+            }
+            vec![name.clone()]
+        }
+        None => config.theme.packages.keys().cloned().collect(),
+    };
+    if names.is_empty() {
+        Console::info("No installed themes to update");
+        return Ok(());
+    }
+
+    for name in names {
+        let old = config.theme.packages[&name].clone();
+        let rev = rev.clone().or_else(|| old.rev.clone());
+
+        // Shared themes are updated where they are, for every project
+        let themes_dir = match project.shared_themes_dir() {
+            Some(shared)
+                if theme_package::find_installed(&project.themes_dir(), &name)?.is_none()
+                    && theme_package::find_installed(&shared, &name)?.is_some() =>
+            {
+                shared
+            }
+            _ => project.themes_dir(),
+        };
+
+        Console::info(&format!("Updating {} from {}", name, old.source));
+        let (slug, package) = theme_package::install(&themes_dir, &old.source, rev.as_deref())?;
+        if slug != name {
+            config.theme.packages.remove(&name);
+            Console::warn(&format!(
+                "{} was renamed to {} upstream; themes/{} can be deleted",
+                name, slug, name
+            ));
+        }
+
+        if package.commit.is_some() && package.commit == old.commit {
+            Console::success(&format!("{} is up to date (v{})", slug, package.version));
+        } else {
+            Console::success(&format!(
+                "Updated {}: v{} -> v{}",
+                slug, old.version, package.version
+            ));
+        }
+        config.theme.packages.insert(slug, package);
+    }
+
+    config.save_to_file(project.root.join("blogr.toml"))?;
+    Ok(())
+}
+
+/// Lint a freshly installed theme and warn about what it finds
+fn report_lint(project: &Project, name: &str) -> Result<()> {
+    let Some(theme) = project.load_theme(name)? else {
+        return Ok(());
+    };
+    let issues = lint_theme(theme.as_ref());
+    if issues.is_empty() {
+        return Ok(());
     }
 
+    Console::warn(&format!(
+        "{} has {} problem(s) that may break builds:",
+        name,
+        issues.len()
+    ));
+    print_issues(&issues);
     Ok(())
 }
+
+fn print_issues(issues: &[LintIssue]) {
+    for issue in issues {
+        println!("  ⚠️  {} ({}): {}", issue.file, issue.kind, issue.message);
+    }
+}
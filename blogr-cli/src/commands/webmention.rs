@@ -0,0 +1,121 @@
+use crate::config::Config;
+use crate::content::PostManager;
+use crate::generator::markdown::render_markdown;
+use crate::project::Project;
+use crate::utils::Console;
+use crate::webmention::{external_links, SentLog, WebmentionClient};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+
+pub async fn handle_send(slug: Option<String>, dry_run: bool) -> Result<()> {
+    let project = Project::find_project()?
+        .ok_or_else(|| anyhow!("Not in a blogr project. Run 'blogr init' first."))?;
+    let config = project.load_config()?;
+
+    send_webmentions(&project, &config, slug.as_deref(), dry_run).await
+}
+
+/// Send webmentions for links in published posts that haven't been processed yet
+pub async fn send_webmentions(
+    project: &Project,
+    config: &Config,
+    slug: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let log_path = project.root.join(".blogr").join("webmentions.json");
+    let mut log = SentLog::load(&log_path)?;
+    let base_url = config.get_effective_base_url();
+
+    let now = Utc::now();
+    let mut posts = PostManager::new(project.posts_dir()).load_all_posts()?;
+    posts.retain(|post| post.is_live(now) && slug.is_none_or(|slug| post.metadata.slug == slug));
+    if let (Some(slug), true) = (slug, posts.is_empty()) {
+        return Err(anyhow!("No published post with slug '{}'", slug));
+    }
+
+    let client = WebmentionClient::new();
+    let (mut sent, mut skipped, mut failed) = (0, 0, 0);
+
+    for post in &posts {
+        let source = config.page_url(&format!("posts/{}.html", post.metadata.slug));
+        let html = render_markdown(&post.content)?;
+        let targets: Vec<String> = external_links(&html, &base_url)
+            .into_iter()
+            .filter(|target| !log.contains(&source, target))
+            .collect();
+        if targets.is_empty() {
+            continue;
+        }
+
+        if dry_run {
+            println!("📝 {}", source);
+            for target in &targets {
+                println!("   → {}", target);
+            }
+            continue;
+        }
+
+        // Receivers fetch the source to check the link, so wait until it's live
+        match client.source_links_to(&source, &targets).await {
+            Ok(true) => {}
+            Ok(false) => {
+                Console::warn(&format!(
+                    "Skipping {}: the published page doesn't link to its targets yet",
+                    source
+                ));
+                skipped += targets.len();
+                continue;
+            }
+            Err(e) => {
+                Console::warn(&format!("Skipping {}: {}", source, e));
+                skipped += targets.len();
+                continue;
+            }
+        }
+
+        for target in &targets {
+            let endpoint = match client.discover(target).await {
+                Ok(endpoint) => endpoint,
+                Err(e) => {
+                    Console::warn(&format!("Could not fetch {}: {}", target, e));
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            let Some(endpoint) = endpoint else {
+                log.record(&source, target, None);
+                continue;
+            };
+
+            match client.send(&endpoint, &source, target).await {
+                Ok(()) => {
+                    println!("📨 {} → {}", post.metadata.slug, target);
+                    log.record(&source, target, Some(&endpoint));
+                    sent += 1;
+                }
+                Err(e) => {
+                    Console::warn(&format!("Webmention to {} failed: {}", target, e));
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    log.save(&log_path)?;
+    Console::success(&format!("Sent {} webmention(s)", sent));
+    if skipped + failed > 0 {
+        println!(
+            "{} link(s) will be retried next time ({} not live yet, {} failed)",
+            skipped + failed,
+            skipped,
+            failed
+        );
+    }
+
+    Ok(())
+}
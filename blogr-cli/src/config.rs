@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
+use blogr_themes::SiteType;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -31,15 +32,990 @@ pub struct Config {
     pub newsletter: NewsletterConfig,
     #[serde(default)]
     pub site: SiteConfig,
+    #[serde(default)]
+    pub urls: UrlConfig,
+    /// Extra redirects, mapping an old path to its new location
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub redirects: HashMap<String, String>,
+    /// Custom error pages, mapping a status code to a markdown file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub error_pages: BTreeMap<String, String>,
+    /// Shell commands run around builds and deploys
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Webmention and microformats settings
+    #[serde(default)]
+    pub indieweb: IndieWebConfig,
+    /// Fediverse actor published with the site
+    #[serde(default)]
+    pub activitypub: ActivityPubConfig,
+    /// Settings for `blogr crosspost`
+    #[serde(default)]
+    pub crosspost: CrosspostConfig,
+    /// Settings for `blogr announce`
+    #[serde(default)]
+    pub announce: AnnounceConfig,
+    /// Settings for `blogr drafts sync`
+    #[serde(default)]
+    pub drafts: DraftsConfig,
+    /// Resume page built from `resume.yaml`
+    #[serde(default)]
+    pub resume: ResumeConfig,
+    /// Contact form for personal sites
+    #[serde(default)]
+    pub contact: ContactConfig,
+    /// GitHub repositories shown by portfolio themes
+    #[serde(default)]
+    pub github_projects: GitHubProjectsConfig,
+    /// How markdown content is rendered
+    #[serde(default)]
+    pub markdown: MarkdownConfig,
+    /// How reading time is estimated
+    #[serde(default)]
+    pub reading_time: ReadingTimeConfig,
+    /// How `blogr new` turns titles into slugs
+    #[serde(default)]
+    pub slugs: SlugConfig,
+    /// Spelling and style checks run by `blogr check prose`
+    #[serde(default)]
+    pub prose: ProseConfig,
+    /// `robots.txt` and crawler rules per build profile
+    #[serde(default)]
+    pub robots: RobotsConfig,
+    /// schema.org JSON-LD added to pages
+    #[serde(default)]
+    pub structured_data: StructuredDataConfig,
+    /// Headers files with a Content-Security-Policy for static hosts
+    #[serde(default)]
+    pub security: SecurityConfig,
+    /// Print stylesheet and plain copies of posts
+    #[serde(default)]
+    pub reader: ReaderConfig,
+    /// Extra renderings of every post, like plain text or Gemini
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_formats: Vec<OutputFormatConfig>,
+    /// Encryption of `private: true` posts
+    #[serde(default)]
+    pub private: PrivateConfig,
+    /// Keeping readers' requests away from third parties
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    /// Settings for `blogr deploy`
+    #[serde(default)]
+    pub deploy: DeployConfig,
+    /// Navigation links shown by themes that support a menu
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub menu: Vec<MenuItem>,
+    /// Sidebar navigation and versions for documentation themes
+    #[serde(default)]
+    pub docs: DocsConfig,
+}
+
+/// A `[[menu]]` entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuItem {
+    pub name: String,
+    /// Site path such as "archive" or "posts/about.html", or a full URL
+    pub url: String,
+}
+
+/// Documentation settings from `[docs]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocsConfig {
+    /// The sidebar tree, in reading order. Without it, pages from
+    /// `content/` are listed by their `order`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nav: Vec<DocsNavItem>,
+    /// The version being built, e.g. "v2"
+    pub version: Option<String>,
+    /// Other published versions, for the version selector
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<DocsVersion>,
+}
+
+/// An entry in `[[docs.nav]]`: a page from `content/`, a link, or a group
+/// heading with `children`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocsNavItem {
+    /// Shown in the sidebar; defaults to the page's title
+    pub title: Option<String>,
+    /// Slug of a page in `content/`, e.g. "guides/deploy"
+    pub page: Option<String>,
+    /// A site path or full URL, for entries that aren't pages
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DocsNavItem>,
+}
+
+/// A `[[docs.versions]]` entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsVersion {
+    pub name: String,
+    /// Where that version's docs are published
+    pub url: String,
+}
+
+/// Contact form settings from `[contact]`. Messages go to a form service or
+/// to `blogr newsletter api-server`, never through the static site itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// "formspree", "staticforms" or "newsletter"
+    #[serde(default = "default_contact_backend")]
+    pub backend: String,
+    /// Formspree form ID, the part after `/f/` in the form's URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form_id: Option<String>,
+    /// StaticForms access key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_key: Option<String>,
+    /// Public URL of `blogr newsletter api-server`, for the newsletter backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Subject of the messages the backend passes on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+}
+
+fn default_contact_backend() -> String {
+    "formspree".to_string()
+}
+
+impl Default for ContactConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: default_contact_backend(),
+            form_id: None,
+            access_key: None,
+            endpoint: None,
+            subject: None,
+        }
+    }
+}
+
+/// Resume settings from `[resume]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResumeConfig {
+    /// Also publish the resume as `resume.pdf`, printed with a headless browser
+    #[serde(default)]
+    pub pdf: bool,
+    /// Chromium-based browser used for the PDF; found on the PATH if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser: Option<String>,
+}
+
+/// Projects showcase settings from `[github_projects]`. The token is read from
+/// `GITHUB_TOKEN` or `GH_TOKEN`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubProjectsConfig {
+    /// GitHub user whose repositories are shown; the showcase is off when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// "pinned" (needs a token) or "starred"
+    #[serde(default = "default_github_projects_source")]
+    pub source: String,
+    /// Maximum number of repositories to show
+    #[serde(default = "default_github_projects_limit")]
+    pub limit: usize,
+    /// How long fetched repositories are reused before asking GitHub again
+    #[serde(default = "default_github_projects_cache_hours")]
+    pub cache_hours: u64,
+}
+
+fn default_github_projects_source() -> String {
+    "pinned".to_string()
+}
+
+fn default_github_projects_limit() -> usize {
+    6
+}
+
+fn default_github_projects_cache_hours() -> u64 {
+    24
+}
+
+impl Default for GitHubProjectsConfig {
+    fn default() -> Self {
+        Self {
+            user: None,
+            source: default_github_projects_source(),
+            limit: default_github_projects_limit(),
+            cache_hours: default_github_projects_cache_hours(),
+        }
+    }
+}
+
+/// Reading time settings from `[reading_time]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingTimeConfig {
+    /// Reading speed for languages written with spaces between words
+    #[serde(default = "default_words_per_minute")]
+    pub words_per_minute: usize,
+    /// Reading speed for Chinese and Japanese, which are counted by character
+    #[serde(default = "default_characters_per_minute")]
+    pub characters_per_minute: usize,
+    /// Seconds spent on the first image; each following image takes a second
+    /// less, down to 3 seconds
+    #[serde(default = "default_seconds_per_image")]
+    pub seconds_per_image: usize,
+    /// Words per minute by language code, used for the blog's `language`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub languages: HashMap<String, usize>,
+}
+
+fn default_words_per_minute() -> usize {
+    200
+}
+
+fn default_characters_per_minute() -> usize {
+    500
+}
+
+fn default_seconds_per_image() -> usize {
+    12
+}
+
+impl Default for ReadingTimeConfig {
+    fn default() -> Self {
+        Self {
+            words_per_minute: default_words_per_minute(),
+            characters_per_minute: default_characters_per_minute(),
+            seconds_per_image: default_seconds_per_image(),
+            languages: HashMap::new(),
+        }
+    }
+}
+
+impl ReadingTimeConfig {
+    /// Words per minute for a language such as "de" or "pt-BR", falling back
+    /// from the full code to the primary language and then the default
+    pub fn words_per_minute_for(&self, language: Option<&str>) -> usize {
+        let Some(language) = language else {
+            return self.words_per_minute;
+        };
+        let primary = language.split(['-', '_']).next().unwrap_or(language);
+        [language, primary]
+            .iter()
+            .find_map(|code| {
+                self.languages
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(code))
+                    .map(|(_, wpm)| *wpm)
+            })
+            .unwrap_or(self.words_per_minute)
+    }
+}
+
+/// Slug rules from `[slugs]`, used for posts created without `--slug`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlugConfig {
+    /// Spell accented and non-Latin letters with ASCII ("Crème brûlée" → "creme-brulee")
+    #[serde(default)]
+    pub transliterate: bool,
+    /// Longest slug in characters, cut at a word; 0 for no limit
+    #[serde(default)]
+    pub max_length: usize,
+    /// Start slugs with the post's date, e.g. `2024-05-01-hello-world`
+    #[serde(default)]
+    pub date_prefix: bool,
+}
+
+/// JSON-LD settings from `[structured_data]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredDataConfig {
+    /// Add `BlogPosting`, `BreadcrumbList` and `Person` data to pages; turn
+    /// off for themes that write their own
+    #[serde(default = "default_structured_data_enabled")]
+    pub enabled: bool,
+}
+
+fn default_structured_data_enabled() -> bool {
+    true
+}
+
+impl Default for StructuredDataConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_structured_data_enabled(),
+        }
+    }
+}
+
+/// Print and reading options for posts from `[reader]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReaderConfig {
+    /// Add a print stylesheet to post pages that hides the theme's navigation
+    /// and other chrome
+    #[serde(default)]
+    pub print_css: bool,
+    /// Write a copy of each post without the theme to `posts/<slug>/plain.html`
+    #[serde(default)]
+    pub plain: bool,
+}
+
+/// Privacy settings from `[privacy]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    #[serde(default)]
+    pub self_host_fonts: SelfHostFontsConfig,
+    #[serde(default)]
+    pub embeds: EmbedsConfig,
+}
+
+/// `[privacy.self_host_fonts]`: serve the theme's Google Fonts from `fonts/`
+/// instead of Google's servers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelfHostFontsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// `[privacy.embeds]`: privacy mode for YouTube, Vimeo and Twitter embeds
+/// in posts, whether they come from shortcodes or pasted HTML
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbedsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub youtube: EmbedMode,
+    #[serde(default)]
+    pub vimeo: EmbedMode,
+    #[serde(default)]
+    pub twitter: EmbedMode,
+}
+
+/// How privacy mode treats one provider's embeds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmbedMode {
+    /// A placeholder that loads the embed when clicked
+    #[default]
+    ClickToLoad,
+    /// youtube-nocookie.com, Vimeo's do-not-track flag, and tweets without
+    /// Twitter's script
+    PrivacyEnhanced,
+    /// Left as written
+    Off,
+}
+
+/// An `[[output_formats]]` entry: a second rendering of every post, made
+/// with the template `formats/<name>.<extension>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputFormatConfig {
+    /// `text`, `gemini` and `lite` are built in; other names need a template
+    pub name: String,
+    /// File extension, which built-in formats have by default
+    #[serde(default)]
+    pub extension: Option<String>,
+    /// MIME type for the `<link rel="alternate">` on post pages
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// What the template gets as `content`: `html`, `text` or `gemtext`
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Encryption of private posts from `[private]`. Passwords are never stored
+/// here: posts set their own, or `BLOGR_PRIVATE_PASSWORD` holds the site's.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateConfig {
+    /// PBKDF2 rounds for deriving keys from passwords; more is slower to
+    /// guess, and slower to unlock
+    #[serde(default = "default_private_iterations")]
+    pub iterations: u32,
+}
+
+fn default_private_iterations() -> u32 {
+    100_000
+}
+
+impl Default for PrivateConfig {
+    fn default() -> Self {
+        Self {
+            iterations: default_private_iterations(),
+        }
+    }
+}
+
+/// Deployment settings from `[deploy]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeployConfig {
+    /// Where `blogr deploy --preview` publishes, from `[deploy.preview]`
+    #[serde(default)]
+    pub preview: PreviewDeployConfig,
+    /// Destinations for `blogr deploy --all`, from `[[deploy.targets]]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<DeployTarget>,
+    /// Deploy to all targets at once instead of one after another
+    #[serde(default)]
+    pub parallel: bool,
+    /// Checking the live site against the build, from `[deploy.verify]`
+    #[serde(default)]
+    pub verify: VerifyConfig,
+}
+
+/// Integrity manifest and post-deploy check, from `[deploy.verify]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyConfig {
+    /// Write `blogr-manifest.json` with every build and check it after deploys
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many files to fetch from the live site
+    #[serde(default = "default_verify_sample")]
+    pub sample: usize,
+    /// How often to fetch files that don't match yet, while the host publishes
+    #[serde(default = "default_verify_attempts")]
+    pub attempts: u32,
+    /// Seconds to wait between attempts
+    #[serde(default = "default_verify_wait")]
+    pub wait: u64,
+}
+
+fn default_verify_sample() -> usize {
+    20
+}
+
+fn default_verify_attempts() -> u32 {
+    5
+}
+
+fn default_verify_wait() -> u64 {
+    30
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample: default_verify_sample(),
+            attempts: default_verify_attempts(),
+            wait: default_verify_wait(),
+        }
+    }
+}
+
+/// One destination of `blogr deploy --all`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployTarget {
+    /// Name shown in the status of each deploy
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: DeployTargetKind,
+}
+
+/// Where a deploy target publishes, chosen by its `type`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DeployTargetKind {
+    /// A branch of the `origin` remote, as `blogr deploy` does
+    Github {
+        #[serde(default = "default_deploy_branch")]
+        branch: String,
+    },
+    /// An S3 bucket, or a bucket of an S3-compatible store with `endpoint`.
+    /// Credentials come from `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`.
+    S3 {
+        bucket: String,
+        #[serde(default = "default_s3_region")]
+        region: String,
+        /// e.g. `https://<account>.r2.cloudflarestorage.com`; buckets are then
+        /// addressed by path
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        endpoint: Option<String>,
+        /// Key prefix to upload under, e.g. `blog/`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prefix: Option<String>,
+    },
+    /// A shell command run from the project root, with the built site in
+    /// `BLOGR_OUTPUT_DIR`
+    Command { command: String },
+}
+
+fn default_deploy_branch() -> String {
+    "gh-pages".to_string()
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+impl DeployTargetKind {
+    /// Short description for status output, e.g. `github (gh-pages)`
+    pub fn describe(&self) -> String {
+        match self {
+            DeployTargetKind::Github { branch } => format!("github ({})", branch),
+            DeployTargetKind::S3 { bucket, .. } => format!("s3 ({})", bucket),
+            DeployTargetKind::Command { .. } => "command".to_string(),
+        }
+    }
+}
+
+/// A review copy of the site with drafts, from `[deploy.preview]`. blogr doesn't
+/// protect it; the preview branch is in the repository, so no credentials go in it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewDeployConfig {
+    /// Branch the preview is pushed to
+    #[serde(default = "default_preview_branch")]
+    pub branch: String,
+    /// URL the preview branch is served at; links use the site's base URL without it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+fn default_preview_branch() -> String {
+    "gh-pages-preview".to_string()
+}
+
+impl Default for PreviewDeployConfig {
+    fn default() -> Self {
+        Self {
+            branch: default_preview_branch(),
+            base_url: None,
+        }
+    }
+}
+
+/// Security headers from `[security]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// Write headers files into the output
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hosts to write files for: `netlify` and `cloudflare` (`_headers`) or
+    /// `vercel` (`vercel.json`)
+    #[serde(default = "default_security_hosts")]
+    pub hosts: Vec<String>,
+    /// Include a Content-Security-Policy computed from the built site
+    #[serde(default = "default_security_csp")]
+    pub csp: bool,
+    /// Extra sources per CSP directive, e.g. an analytics script's origin
+    /// under `script-src`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, Vec<String>>,
+    /// Extra headers, or replacements for the built-in ones; an empty value
+    /// removes a header
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+fn default_security_hosts() -> Vec<String> {
+    vec!["netlify".to_string(), "vercel".to_string()]
+}
+
+fn default_security_csp() -> bool {
+    true
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hosts: default_security_hosts(),
+            csp: default_security_csp(),
+            sources: BTreeMap::new(),
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+/// `robots.txt` settings from `[robots]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobotsConfig {
+    /// Write `robots.txt` at the site root
+    #[serde(default = "default_robots_enabled")]
+    pub enabled: bool,
+    /// Paths every crawler may visit inside disallowed ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Paths every crawler should stay out of
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disallow: Vec<String>,
+    /// Rules for particular crawlers, from `[[robots.agents]]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<RobotsAgent>,
+    /// Overrides for builds with `--profile <name>`, from `[robots.profiles.<name>]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, RobotsProfile>,
+}
+
+fn default_robots_enabled() -> bool {
+    true
+}
+
+impl Default for RobotsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_robots_enabled(),
+            allow: Vec::new(),
+            disallow: Vec::new(),
+            agents: Vec::new(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl RobotsConfig {
+    /// Whether a build with this profile should keep every crawler out. The
+    /// `preview` profile does unless configured otherwise.
+    pub fn disallows_all(&self, profile: Option<&str>) -> bool {
+        let Some(profile) = profile else {
+            return false;
+        };
+        match self.profiles.get(profile) {
+            Some(rules) => rules.disallow_all,
+            None => profile == PREVIEW_PROFILE,
+        }
+    }
+}
+
+/// Build profile for review copies of a site, hidden from search engines
+pub const PREVIEW_PROFILE: &str = "preview";
+
+/// A `User-agent` group of `robots.txt`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobotsAgent {
+    pub user_agent: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disallow: Vec<String>,
+}
+
+/// Crawler rules for one build profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RobotsProfile {
+    /// Disallow everything in `robots.txt` and mark every page `noindex`
+    #[serde(default)]
+    pub disallow_all: bool,
+}
+
+/// `blogr check prose` settings from `[prose]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProseConfig {
+    /// Spell checker that lists misspelled words, `aspell` or `hunspell`;
+    /// whichever is installed if unset
+    #[serde(default)]
+    pub spell_checker: Option<String>,
+    /// Dictionary the spell checker uses
+    #[serde(default = "default_prose_language")]
+    pub language: String,
+    /// Project word list, one word per line, relative to the project root
+    #[serde(default = "default_prose_dictionary")]
+    pub dictionary: String,
+    /// Sentences with more words than this are reported
+    #[serde(default = "default_max_sentence_words")]
+    pub max_sentence_words: usize,
+    /// Rules to turn off: `spelling`, `passive-voice`, `long-sentence`, `repeated-word`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disable: Vec<String>,
+}
+
+fn default_prose_language() -> String {
+    "en_US".to_string()
+}
+
+fn default_prose_dictionary() -> String {
+    "dictionary.txt".to_string()
+}
+
+fn default_max_sentence_words() -> usize {
+    35
+}
+
+impl Default for ProseConfig {
+    fn default() -> Self {
+        Self {
+            spell_checker: None,
+            language: default_prose_language(),
+            dictionary: default_prose_dictionary(),
+            max_sentence_words: default_max_sentence_words(),
+            disable: Vec::new(),
+        }
+    }
+}
+
+/// Markdown rendering settings from `[markdown]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkdownConfig {
+    /// Length of automatic post summaries, for posts without a `<!--more-->`
+    /// marker or a description
+    #[serde(default = "default_summary_words")]
+    pub summary_words: usize,
+    /// Replace `:emoji:` shortcodes such as `:tada:` with the emoji
+    #[serde(default)]
+    pub emoji: bool,
+    /// Curly quotes, dashes from `--` and `---`, and an ellipsis from `...`
+    #[serde(default)]
+    pub smart_punctuation: bool,
+    /// Video shortcodes and the `video` frontmatter field
+    #[serde(default)]
+    pub video: VideoConfig,
+    /// Give links to other sites `rel="external noopener"` and an `external`
+    /// class, for themes that mark them
+    #[serde(default)]
+    pub external_links: bool,
+}
+
+fn default_summary_words() -> usize {
+    50
+}
+
+impl Default for MarkdownConfig {
+    fn default() -> Self {
+        Self {
+            summary_words: default_summary_words(),
+            emoji: false,
+            smart_punctuation: false,
+            video: VideoConfig::default(),
+            external_links: false,
+        }
+    }
+}
+
+/// Video embedding settings from `[markdown.video]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoConfig {
+    /// Embed YouTube from youtube-nocookie.com and ask Vimeo not to track viewers
+    #[serde(default = "default_video_privacy_enhanced")]
+    pub privacy_enhanced: bool,
+    /// Show a placeholder and only load the player when it is clicked
+    #[serde(default)]
+    pub click_to_load: bool,
+    /// Make poster images for local videos with ffmpeg
+    #[serde(default = "default_video_posters")]
+    pub posters: bool,
+    /// ffmpeg executable used for posters; found on the PATH if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ffmpeg: Option<String>,
+    /// `preload` attribute of local videos: "none", "metadata" or "auto"
+    #[serde(default = "default_video_preload")]
+    pub preload: String,
+}
+
+fn default_video_privacy_enhanced() -> bool {
+    true
+}
+
+fn default_video_posters() -> bool {
+    true
+}
+
+fn default_video_preload() -> String {
+    "metadata".to_string()
+}
+
+impl Default for VideoConfig {
+    fn default() -> Self {
+        Self {
+            privacy_enhanced: default_video_privacy_enhanced(),
+            click_to_load: false,
+            posters: default_video_posters(),
+            ffmpeg: None,
+            preload: default_video_preload(),
+        }
+    }
+}
+
+/// Draft syncing settings from `[drafts]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftsConfig {
+    /// Where drafts are synced: "branch" (a branch on `origin`) or "gist"
+    #[serde(default = "default_drafts_sync")]
+    pub sync: String,
+    /// Branch used when `sync = "branch"`
+    #[serde(default = "default_drafts_branch")]
+    pub branch: String,
+    /// Private gist used when `sync = "gist"`; created on the first sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gist_id: Option<String>,
+}
+
+fn default_drafts_sync() -> String {
+    "branch".to_string()
+}
+
+fn default_drafts_branch() -> String {
+    "drafts".to_string()
+}
+
+impl Default for DraftsConfig {
+    fn default() -> Self {
+        Self {
+            sync: default_drafts_sync(),
+            branch: default_drafts_branch(),
+            gist_id: None,
+        }
+    }
+}
+
+/// Social announcement settings from `[announce]`. Credentials are read from the
+/// `MASTODON_TOKEN`, `BLUESKY_APP_PASSWORD` and `X_ACCESS_TOKEN` environment variables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnnounceConfig {
+    /// Networks to post to when `--to` isn't given: "mastodon", "bluesky", "x"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<String>,
+    /// Announce new posts after `blogr deploy`
+    #[serde(default)]
+    pub after_deploy: bool,
+    #[serde(default)]
+    pub mastodon: MastodonAnnounceConfig,
+    #[serde(default)]
+    pub bluesky: BlueskyAnnounceConfig,
+    #[serde(default)]
+    pub x: XAnnounceConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MastodonAnnounceConfig {
+    /// Server the account is on, e.g. `https://mastodon.social`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// "public", "unlisted" or "private"
+    #[serde(default = "default_mastodon_visibility")]
+    pub visibility: String,
+    /// Tera template with `title`, `summary`, `url`, `tags`, `hashtags`, `blog` and `author`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+fn default_mastodon_visibility() -> String {
+    "public".to_string()
+}
+
+impl Default for MastodonAnnounceConfig {
+    fn default() -> Self {
+        Self {
+            instance: None,
+            visibility: default_mastodon_visibility(),
+            template: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlueskyAnnounceConfig {
+    /// Account handle, e.g. `me.bsky.social`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+    /// Server hosting the account
+    #[serde(default = "default_bluesky_pds")]
+    pub pds: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+fn default_bluesky_pds() -> String {
+    "https://bsky.social".to_string()
+}
+
+impl Default for BlueskyAnnounceConfig {
+    fn default() -> Self {
+        Self {
+            handle: None,
+            pds: default_bluesky_pds(),
+            template: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct XAnnounceConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+/// Cross-posting settings from `[crosspost]`. API tokens are read from the
+/// `DEVTO_API_KEY`, `HASHNODE_TOKEN` and `MEDIUM_TOKEN` environment variables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrosspostConfig {
+    /// Hashnode blog to publish to, from the blog's dashboard URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hashnode_publication_id: Option<String>,
+}
+
+/// ActivityPub settings from `[activitypub]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityPubConfig {
+    /// Publish an actor, WebFinger and outbox so the blog can be followed from Mastodon
+    #[serde(default)]
+    pub enabled: bool,
+    /// Account name, making the handle `@<username>@<domain>`
+    #[serde(default = "default_activitypub_username")]
+    pub username: String,
+    /// Profile picture URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    /// Inbox that receives Follow requests. Static hosts can't accept POSTs,
+    /// so this is a separate service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbox_url: Option<String>,
+    /// Send new posts to followers after `blogr deploy`
+    #[serde(default)]
+    pub announce_on_deploy: bool,
+}
+
+fn default_activitypub_username() -> String {
+    "blog".to_string()
+}
+
+impl Default for ActivityPubConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            username: default_activitypub_username(),
+            avatar: None,
+            inbox_url: None,
+            announce_on_deploy: false,
+        }
+    }
+}
+
+/// IndieWeb settings from `[indieweb]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndieWebConfig {
+    /// Where other sites send webmentions for this one, e.g.
+    /// `https://webmention.io/example.com/webmention`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webmention_endpoint: Option<String>,
+    /// Pingback endpoint advertised next to the webmention endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pingback_endpoint: Option<String>,
+    /// Send webmentions for links in posts after `blogr deploy`
+    #[serde(default)]
+    pub send_on_deploy: bool,
+    /// Photo URL for the author h-card
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_photo: Option<String>,
+}
+
+/// Commands from `[hooks]`, run from the project root with `sh -c` (`cmd /C` on Windows)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Before the site is generated, e.g. to compile CSS into `static/`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_build: Vec<String>,
+    /// After the site is written to the output directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_build: Vec<String>,
+    /// After `blogr deploy` has pushed the site
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_deploy: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SiteConfig {
-    /// Type of site: "blog" or "personal"
+    /// Type of site: "blog", "personal" or "hybrid" (a personal landing page
+    /// with the blog under `/blog/`)
     #[serde(default = "default_site_type")]
     pub site_type: String,
 }
 
+/// Directory the blog of a hybrid site is published under
+pub const BLOG_SECTION: &str = "blog";
+
 fn default_site_type() -> String {
     "blog".to_string()
 }
@@ -52,6 +1028,116 @@ impl Default for SiteConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlConfig {
+    /// Write pages as `posts/<slug>/index.html` instead of `posts/<slug>.html`
+    #[serde(default)]
+    pub pretty: bool,
+    /// Whether directory-style URLs end with a trailing slash
+    #[serde(default = "default_trailing_slash")]
+    pub trailing_slash: bool,
+    /// Whether to emit `<link rel="canonical">` tags
+    #[serde(default = "default_canonical")]
+    pub canonical: bool,
+    /// Directory the pages are published under, such as the blog of a hybrid site
+    #[serde(skip)]
+    pub section: Option<String>,
+}
+
+fn default_trailing_slash() -> bool {
+    true
+}
+
+fn default_canonical() -> bool {
+    true
+}
+
+impl Default for UrlConfig {
+    fn default() -> Self {
+        Self {
+            pretty: false,
+            trailing_slash: default_trailing_slash(),
+            canonical: default_canonical(),
+            section: None,
+        }
+    }
+}
+
+impl UrlConfig {
+    /// Map a page path such as `posts/hello.html` to the path it is linked at
+    pub fn page_path(&self, path: &str) -> String {
+        let path = self.styled_path(path);
+        self.in_section(path)
+    }
+
+    /// Map an asset path to the path it is linked at
+    pub fn asset_path(&self, path: &str) -> String {
+        self.in_section(path.trim_start_matches('/').to_string())
+    }
+
+    /// Prefix a path with the section; `static/` is shared by the whole site
+    fn in_section(&self, path: String) -> String {
+        match &self.section {
+            Some(section) if !path.starts_with("static/") => {
+                if path.is_empty() {
+                    self.with_slash_style(section)
+                } else {
+                    format!("{}/{}", section, path)
+                }
+            }
+            _ => path,
+        }
+    }
+
+    fn styled_path(&self, path: &str) -> String {
+        let path = path.trim_start_matches('/');
+        if path.is_empty() {
+            return String::new();
+        }
+
+        if let Some(stem) = path.strip_suffix(".html") {
+            if !self.pretty {
+                return path.to_string();
+            }
+            let dir = if stem == "index" {
+                ""
+            } else {
+                stem.strip_suffix("/index").unwrap_or(stem)
+            };
+            if dir.is_empty() {
+                return String::new();
+            }
+            return self.with_slash_style(dir);
+        }
+
+        // Extensionless paths are directories; anything else (css, xml, ...) is left alone
+        if Path::new(path).extension().is_none() {
+            return self.with_slash_style(path.trim_end_matches('/'));
+        }
+
+        path.to_string()
+    }
+
+    /// Map a page path such as `posts/hello.html` to the file it is written to
+    pub fn output_path(&self, path: &str) -> PathBuf {
+        let path = path.trim_start_matches('/');
+        match path.strip_suffix(".html") {
+            Some(stem) if self.pretty && stem != "index" && !stem.ends_with("/index") => {
+                PathBuf::from(stem).join("index.html")
+            }
+            _ => PathBuf::from(path),
+        }
+    }
+
+    fn with_slash_style(&self, dir: &str) -> String {
+        if self.trailing_slash {
+            format!("{}/", dir)
+        } else {
+            dir.to_string()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlogConfig {
     pub title: String,
@@ -77,6 +1163,10 @@ pub struct DomainConfig {
     pub enforce_https: bool,
     /// Custom domain for GitHub Pages (CNAME file content)
     pub github_pages_domain: Option<String>,
+    /// Also redirect the `www.` form of an apex domain, or the apex form of
+    /// a `www.` domain, to the main domain
+    #[serde(default = "default_www_redirect")]
+    pub www_redirect: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,31 +1177,137 @@ pub struct SubdomainConfig {
     pub base_domain: String,
 }
 
-fn default_enforce_https() -> bool {
-    true
+fn default_enforce_https() -> bool {
+    true
+}
+
+fn default_www_redirect() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    pub name: String,
+    #[serde(default)]
+    pub config: HashMap<String, toml::Value>,
+    /// Themes installed with `blogr theme install`, by directory name under `themes/`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub packages: BTreeMap<String, ThemePackage>,
+}
+
+/// Where an installed theme came from, from `[theme.packages.<name>]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemePackage {
+    /// Git URL or local path the theme was installed from
+    pub source: String,
+    /// Tag, branch or commit asked for; `blogr theme update` follows it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    /// Commit that is installed, for git sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Version from the theme's manifest
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubConfig {
+    pub username: String,
+    pub repository: String,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildConfig {
+    pub output_dir: Option<String>,
+    #[serde(default)]
+    pub drafts: bool,
+    #[serde(default)]
+    pub future_posts: bool,
+    /// Derive `updated_at` and contributors for posts from git history
+    #[serde(default)]
+    pub git_metadata: bool,
+    /// Cache for build artifacts, from `[build.cache]`
+    #[serde(default)]
+    pub cache: CacheConfig,
+    /// Index page size and layout, from `[build.pagination]`
+    #[serde(default)]
+    pub pagination: PaginationConfig,
+    /// Static JSON API, from `[build.api]`
+    #[serde(default)]
+    pub api: ApiConfig,
+}
+
+/// The JSON files under `api/` describing the whole blog
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiConfig {
+    /// Write `api/site.json`, `api/posts.json`, `api/posts/<slug>.json` and `api/tags.json`
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// How the home page and its `api/posts-page-N.json` files list posts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationConfig {
+    /// Posts on the home page and in each JSON page
+    #[serde(default = "default_posts_per_page")]
+    pub posts_per_page: usize,
+    /// Whether listings show each post's summary or its full content; themes
+    /// choose when it's unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_content: Option<IndexContent>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexContent {
+    Summary,
+    Full,
+}
+
+fn default_posts_per_page() -> usize {
+    10
+}
+
+impl PaginationConfig {
+    /// `posts_per_page`, treating 0 as 1 for configs that skipped validation
+    pub fn per_page(&self) -> usize {
+        self.posts_per_page.max(1)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ThemeConfig {
-    pub name: String,
-    #[serde(default)]
-    pub config: HashMap<String, toml::Value>,
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self {
+            posts_per_page: default_posts_per_page(),
+            index_content: None,
+        }
+    }
 }
 
+/// Build cache settings. The cache is content-addressed, so it can be shared
+/// between projects and kept between CI runs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitHubConfig {
-    pub username: String,
-    pub repository: String,
-    pub branch: Option<String>,
+pub struct CacheConfig {
+    #[serde(default = "default_cache_enabled")]
+    pub enabled: bool,
+    /// Cache directory; `~/` is expanded. Defaults to the user cache
+    /// directory (`~/.cache/blogr`). `BLOGR_CACHE_DIR` takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BuildConfig {
-    pub output_dir: Option<String>,
-    #[serde(default)]
-    pub drafts: bool,
-    #[serde(default)]
-    pub future_posts: bool,
+fn default_cache_enabled() -> bool {
+    true
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_cache_enabled(),
+            dir: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,17 +1316,29 @@ pub struct DevConfig {
     pub port: u16,
     #[serde(default)]
     pub auto_reload: bool,
+    /// How long the project must be quiet after a change before `serve` rebuilds
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    /// Extra glob patterns, relative to the project root, whose changes `serve` ignores
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
 }
 
 fn default_port() -> u16 {
     3000
 }
 
+fn default_debounce_ms() -> u64 {
+    300
+}
+
 impl Default for DevConfig {
     fn default() -> Self {
         Self {
             port: default_port(),
             auto_reload: true,
+            debounce_ms: default_debounce_ms(),
+            ignore: Vec::new(),
         }
     }
 }
@@ -161,11 +1369,35 @@ pub struct SearchConfig {
     /// Whether to remove common stopwords from search
     #[serde(default = "default_remove_stopwords")]
     pub remove_stopwords: bool,
+    /// Search language for stop words and stemming (defaults to blog.language)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Extra stop words to ignore on top of the language's built-in list
+    #[serde(default)]
+    pub stop_words: Vec<String>,
+    /// Whether to reduce words to a common stem (e.g. "posts" matches "post")
+    #[serde(default)]
+    pub stemming: bool,
+    /// Whether to match words by prefix while typing
+    #[serde(default = "default_prefix_search")]
+    pub prefix: bool,
     /// Field boost weights for search scoring
     #[serde(default = "default_field_boosts")]
     pub field_boosts: std::collections::HashMap<String, f32>,
+    /// Document fields left out of the index file to keep it small
+    #[serde(default)]
+    pub exclude_fields: Vec<String>,
+    /// Split the index into files of this many documents (0 keeps one file)
+    #[serde(default)]
+    pub chunk_size: usize,
+    /// Also write compressed copies of the index (`gzip`)
+    #[serde(default)]
+    pub precompress: Vec<String>,
 }
 
+/// Index fields that `exclude_fields` may drop; id, url and title are always kept
+pub const SEARCH_OPTIONAL_FIELDS: [&str; 5] = ["tags", "date", "description", "content", "excerpt"];
+
 fn default_search_enabled() -> bool {
     true
 }
@@ -202,6 +1434,10 @@ fn default_remove_stopwords() -> bool {
     false
 }
 
+fn default_prefix_search() -> bool {
+    true
+}
+
 fn default_field_boosts() -> std::collections::HashMap<String, f32> {
     let mut boosts = std::collections::HashMap::new();
     boosts.insert("title".to_string(), 5.0);
@@ -221,7 +1457,14 @@ impl Default for SearchConfig {
             minify: default_minify(),
             lazy_load: default_lazy_load(),
             remove_stopwords: default_remove_stopwords(),
+            language: None,
+            stop_words: Vec::new(),
+            stemming: false,
+            prefix: default_prefix_search(),
             field_boosts: default_field_boosts(),
+            exclude_fields: Vec::new(),
+            chunk_size: 0,
+            precompress: Vec::new(),
         }
     }
 }
@@ -233,6 +1476,10 @@ pub struct NewsletterConfig {
     pub enabled: bool,
     /// Email address for newsletter subscriptions
     pub subscribe_email: Option<String>,
+    /// Public URL of the API server's `/subscribe` endpoint. When set, the site's
+    /// signup form posts there instead of opening the reader's mail client.
+    #[serde(default)]
+    pub subscribe_endpoint: Option<String>,
     /// Name to display in newsletter emails
     pub sender_name: Option<String>,
     /// Subject line for confirmation emails
@@ -244,6 +1491,234 @@ pub struct NewsletterConfig {
     /// Plugin configurations
     #[serde(default)]
     pub plugins: Option<std::collections::HashMap<String, crate::newsletter::PluginConfig>>,
+    /// Throttling and retry settings for sends
+    #[serde(default)]
+    pub sending: SendingConfig,
+    /// Default email template: "default", "table", or a file name from newsletter/templates
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Email template for `send-digest`; the built-in digest layout when unset
+    #[serde(default)]
+    pub digest_template: Option<String>,
+    /// Stands in for `{{ subscriber.name }}` when a subscriber has no name
+    #[serde(default = "default_fallback_name")]
+    pub fallback_name: String,
+    /// Accent color used by email templates (e.g. "#2c3e50")
+    #[serde(default)]
+    pub brand_color: Option<String>,
+    /// Logo image URL shown in the email header
+    #[serde(default)]
+    pub logo_url: Option<String>,
+    /// Open and click tracking (off by default)
+    #[serde(default)]
+    pub tracking: TrackingConfig,
+    /// Defaults for subject line A/B tests
+    #[serde(default)]
+    pub ab_test: AbTestConfig,
+    /// Endpoints notified when the API server creates, approves or removes subscribers
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Which emails `fetch-subscribers` reads and how it sorts them
+    #[serde(default)]
+    pub fetch: FetchRulesConfig,
+    /// OAuth2 providers that `imap.oauth` and `smtp.oauth` refer to
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub oauth: BTreeMap<String, OAuthProviderConfig>,
+}
+
+/// Rules for `blogr newsletter fetch-subscribers` from `[newsletter.fetch]`.
+/// Patterns are case-insensitive and match anywhere in the text; `*` matches
+/// any run of characters, e.g. `*@example.com`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchRulesConfig {
+    /// Mailbox to read
+    #[serde(default = "default_fetch_folder")]
+    pub folder: String,
+    /// Only read messages not marked as seen
+    #[serde(default = "default_true")]
+    pub unseen_only: bool,
+    /// Subject (and body, with `match_body`) patterns of subscription requests
+    #[serde(default = "default_subscribe_patterns")]
+    pub subscribe: Vec<String>,
+    /// Patterns of unsubscribe requests, which remove the sender; these win
+    /// over `subscribe`
+    #[serde(default = "default_unsubscribe_patterns")]
+    pub unsubscribe: Vec<String>,
+    /// Look for the patterns in the body as well as the subject
+    #[serde(default = "default_true")]
+    pub match_body: bool,
+    /// Only accept requests from these senders; any sender when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub senders: Vec<String>,
+    /// Never treat messages from these senders as requests
+    #[serde(default = "default_ignore_senders")]
+    pub ignore_senders: Vec<String>,
+    /// Move processed requests to this mailbox
+    #[serde(default)]
+    pub archive_folder: Option<String>,
+}
+
+fn default_fetch_folder() -> String {
+    "INBOX".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_subscribe_patterns() -> Vec<String> {
+    [
+        "subscribe",
+        "subscription",
+        "newsletter",
+        "sign up",
+        "signup",
+        "join",
+        "mailing list",
+        "updates",
+        "notifications",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_unsubscribe_patterns() -> Vec<String> {
+    ["unsubscribe", "remove", "stop", "opt out", "opt-out"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_ignore_senders() -> Vec<String> {
+    ["mailer-daemon@*", "postmaster@*", "*noreply*", "*no-reply*"]
+        .map(String::from)
+        .to_vec()
+}
+
+impl Default for FetchRulesConfig {
+    fn default() -> Self {
+        Self {
+            folder: default_fetch_folder(),
+            unseen_only: true,
+            subscribe: default_subscribe_patterns(),
+            unsubscribe: default_unsubscribe_patterns(),
+            match_body: true,
+            senders: Vec::new(),
+            ignore_senders: default_ignore_senders(),
+            archive_folder: None,
+        }
+    }
+}
+
+/// An outbound webhook called by the newsletter API server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// URL receiving the JSON POST
+    pub url: String,
+    /// Shared secret for the `X-Blogr-Signature` HMAC-SHA256 header
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Events to deliver (e.g. "subscriber.created"); empty means all events
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// Defaults for subject line A/B tests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbTestConfig {
+    /// Percentage of the audience in the test, split evenly between the two subjects
+    #[serde(default = "default_ab_test_percent")]
+    pub test_percent: u8,
+    /// Hours to wait before sending the winning subject to everyone else
+    #[serde(default = "default_ab_wait_hours")]
+    pub wait_hours: u64,
+}
+
+fn default_ab_test_percent() -> u8 {
+    20
+}
+
+fn default_ab_wait_hours() -> u64 {
+    4
+}
+
+impl Default for AbTestConfig {
+    fn default() -> Self {
+        Self {
+            test_percent: default_ab_test_percent(),
+            wait_hours: default_ab_wait_hours(),
+        }
+    }
+}
+
+/// Open and click tracking for newsletters.
+///
+/// Tracking adds a pixel and rewritten links to every email, so it reveals when
+/// subscribers read and click. Tell them in your privacy policy before enabling it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrackingConfig {
+    /// Add a tracking pixel and wrap links in sent newsletters
+    #[serde(default)]
+    pub enabled: bool,
+    /// Public URL of the newsletter API server that records opens and clicks
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// Throttling and retry settings for newsletter delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendingConfig {
+    /// Maximum emails sent per minute
+    #[serde(default = "default_emails_per_minute")]
+    pub emails_per_minute: u32,
+    /// Number of emails to send before pausing
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// Seconds to pause between batches
+    #[serde(default = "default_batch_pause_secs")]
+    pub batch_pause_secs: u64,
+    /// Retries for transient SMTP failures (4xx replies, timeouts, dropped
+    /// connections), at most `MAX_SEND_RETRIES`
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Initial retry delay in seconds, doubled after each attempt
+    #[serde(default = "default_retry_backoff_secs")]
+    pub retry_backoff_secs: u64,
+}
+
+fn default_emails_per_minute() -> u32 {
+    10
+}
+
+fn default_batch_size() -> usize {
+    50
+}
+
+fn default_batch_pause_secs() -> u64 {
+    30
+}
+
+/// Upper limit of `newsletter.sending.max_retries`; the delay doubles with
+/// each retry, so more would wait for days
+pub const MAX_SEND_RETRIES: u32 = 10;
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_backoff_secs() -> u64 {
+    5
+}
+
+impl Default for SendingConfig {
+    fn default() -> Self {
+        Self {
+            emails_per_minute: default_emails_per_minute(),
+            batch_size: default_batch_size(),
+            batch_pause_secs: default_batch_pause_secs(),
+            max_retries: default_max_retries(),
+            retry_backoff_secs: default_retry_backoff_secs(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -253,6 +1728,9 @@ pub struct ImapConfig {
     pub username: String,
     /// Password is not stored in config - set via environment variable NEWSLETTER_IMAP_PASSWORD
     pub use_tls: Option<bool>,
+    /// Sign in with this `[newsletter.oauth]` provider instead of a password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -262,22 +1740,61 @@ pub struct SmtpConfig {
     pub username: String,
     /// Password is not stored in config - set via environment variable NEWSLETTER_SMTP_PASSWORD
     pub use_tls: Option<bool>,
+    /// Sign in with this `[newsletter.oauth]` provider instead of a password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<String>,
+}
+
+/// An OAuth2 app used to sign in to IMAP and SMTP, from
+/// `[newsletter.oauth.<name>]`. The endpoints and scopes of `google` and
+/// `microsoft` are built in; other providers need `token_url`, `scopes` and
+/// either `device_url` or, to sign in through the browser, `auth_url`. A client secret,
+/// if the provider wants one, is read from `NEWSLETTER_OAUTH_<NAME>_CLIENT_SECRET`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthProviderConfig {
+    pub client_id: String,
+    /// Device authorization endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_url: Option<String>,
+    /// Authorization endpoint for signing in through the browser instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
 }
 
 fn default_newsletter_enabled() -> bool {
     false
 }
 
+fn default_fallback_name() -> String {
+    "there".to_string()
+}
+
 impl Default for NewsletterConfig {
     fn default() -> Self {
         Self {
             enabled: default_newsletter_enabled(),
             subscribe_email: None,
+            subscribe_endpoint: None,
             sender_name: None,
             confirmation_subject: None,
             imap: None,
             smtp: None,
             plugins: None,
+            sending: SendingConfig::default(),
+            template: None,
+            digest_template: None,
+            fallback_name: default_fallback_name(),
+            brand_color: None,
+            logo_url: None,
+            tracking: TrackingConfig::default(),
+            ab_test: AbTestConfig::default(),
+            webhooks: Vec::new(),
+            fetch: FetchRulesConfig::default(),
+            oauth: BTreeMap::new(),
         }
     }
 }
@@ -297,17 +1814,48 @@ impl Default for Config {
             theme: ThemeConfig {
                 name: "minimal-retro".to_string(),
                 config: HashMap::new(),
+                packages: BTreeMap::new(),
             },
             github: None,
             build: BuildConfig {
                 output_dir: Some("dist".to_string()),
                 drafts: false,
                 future_posts: false,
+                git_metadata: false,
+                cache: CacheConfig::default(),
+                pagination: PaginationConfig::default(),
+                api: ApiConfig::default(),
             },
             dev: DevConfig::default(),
             search: SearchConfig::default(),
             newsletter: NewsletterConfig::default(),
             site: SiteConfig::default(),
+            urls: UrlConfig::default(),
+            redirects: HashMap::new(),
+            error_pages: BTreeMap::new(),
+            hooks: HooksConfig::default(),
+            indieweb: IndieWebConfig::default(),
+            activitypub: ActivityPubConfig::default(),
+            crosspost: CrosspostConfig::default(),
+            announce: AnnounceConfig::default(),
+            drafts: DraftsConfig::default(),
+            resume: ResumeConfig::default(),
+            contact: ContactConfig::default(),
+            github_projects: GitHubProjectsConfig::default(),
+            markdown: MarkdownConfig::default(),
+            reading_time: ReadingTimeConfig::default(),
+            slugs: SlugConfig::default(),
+            prose: ProseConfig::default(),
+            robots: RobotsConfig::default(),
+            structured_data: StructuredDataConfig::default(),
+            security: SecurityConfig::default(),
+            reader: ReaderConfig::default(),
+            output_formats: Vec::new(),
+            private: PrivateConfig::default(),
+            privacy: PrivacyConfig::default(),
+            deploy: DeployConfig::default(),
+            menu: Vec::new(),
+            docs: DocsConfig::default(),
         }
     }
 }
@@ -377,6 +1925,40 @@ impl Config {
         config
     }
 
+    /// Create a hybrid site: a personal landing page with the blog under `/blog/`
+    pub fn new_hybrid(
+        title: String,
+        author: String,
+        description: String,
+        github_username: Option<String>,
+        github_repo: Option<String>,
+    ) -> Self {
+        let mut config =
+            Self::new_with_defaults(title, author, description, github_username, github_repo);
+        config.site.site_type = "hybrid".to_string();
+        config
+    }
+
+    /// Configuration for building the blog of a hybrid site, whose pages are
+    /// published under `/blog/`
+    pub fn blog_section(&self) -> Self {
+        let mut config = self.clone();
+        config.urls.section = Some(BLOG_SECTION.to_string());
+        config
+    }
+
+    /// Configuration for a preview deploy: served from `deploy.preview.base_url`
+    /// when set, and without the production domains
+    pub fn preview(&self) -> Self {
+        let mut config = self.clone();
+        config.sync_base_url_with_domains();
+        config.blog.domains = None;
+        if let Some(base_url) = &self.deploy.preview.base_url {
+            config.blog.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        config
+    }
+
     /// Get the project root directory (where blogr.toml is located)
     pub fn find_project_root() -> Result<Option<PathBuf>> {
         let mut current =
@@ -418,7 +2000,6 @@ impl Config {
     }
 
     /// Get the themes directory path
-    #[allow(dead_code)]
     pub fn themes_dir(&self, project_root: &Path) -> PathBuf {
         project_root.join("themes")
     }
@@ -453,6 +2034,214 @@ impl Config {
             anyhow::bail!("Development server port must be greater than 0");
         }
 
+        if self.private.iterations == 0 {
+            anyhow::bail!("private.iterations must be greater than 0");
+        }
+
+        let mut target_names = std::collections::HashSet::new();
+        for target in &self.deploy.targets {
+            if !target_names.insert(target.name.as_str()) {
+                anyhow::bail!("Deploy target '{}' is listed twice", target.name);
+            }
+        }
+
+        if let Some(url) = &self.deploy.preview.base_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                anyhow::bail!("deploy.preview.base_url must start with http:// or https://");
+            }
+        }
+
+        for field in &self.search.exclude_fields {
+            if !SEARCH_OPTIONAL_FIELDS.contains(&field.as_str()) {
+                anyhow::bail!(
+                    "search.exclude_fields can't contain '{}' (use {})",
+                    field,
+                    SEARCH_OPTIONAL_FIELDS.join(", ")
+                );
+            }
+        }
+        for encoding in &self.search.precompress {
+            match encoding.as_str() {
+                "gzip" => {}
+                "brotli" => anyhow::bail!(
+                    "search.precompress: brotli isn't available in this build of blogr, use gzip"
+                ),
+                other => anyhow::bail!("Unknown encoding '{}' in search.precompress", other),
+            }
+        }
+
+        crate::generator::formats::OutputFormat::resolve(&self.output_formats)?;
+
+        if self.build.pagination.posts_per_page == 0 {
+            anyhow::bail!("build.pagination.posts_per_page must be greater than 0");
+        }
+
+        self.site
+            .site_type
+            .parse::<SiteType>()
+            .map_err(anyhow::Error::msg)?;
+
+        let endpoints = [
+            ("webmention_endpoint", &self.indieweb.webmention_endpoint),
+            ("pingback_endpoint", &self.indieweb.pingback_endpoint),
+        ];
+        for (name, endpoint) in endpoints {
+            if let Some(endpoint) = endpoint {
+                if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                    anyhow::bail!("indieweb.{} must start with http:// or https://", name);
+                }
+            }
+        }
+
+        if self.activitypub.enabled {
+            let username = &self.activitypub.username;
+            if username.is_empty()
+                || !username
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                anyhow::bail!("activitypub.username may only contain letters, digits and '_'");
+            }
+            if let Some(inbox) = &self.activitypub.inbox_url {
+                if !inbox.starts_with("http://") && !inbox.starts_with("https://") {
+                    anyhow::bail!("activitypub.inbox_url must start with http:// or https://");
+                }
+            }
+        }
+
+        for network in &self.announce.networks {
+            if !matches!(network.as_str(), "mastodon" | "bluesky" | "x") {
+                anyhow::bail!(
+                    "Unknown network '{}' in announce.networks (use mastodon, bluesky or x)",
+                    network
+                );
+            }
+        }
+        if !matches!(
+            self.announce.mastodon.visibility.as_str(),
+            "public" | "unlisted" | "private"
+        ) {
+            anyhow::bail!("announce.mastodon.visibility must be public, unlisted or private");
+        }
+
+        for item in &self.menu {
+            if item.name.trim().is_empty() || item.url.trim().is_empty() {
+                anyhow::bail!("Menu entries need both a name and a url");
+            }
+        }
+
+        if self.contact.enabled {
+            crate::generator::contact::ContactForm::new(&self.contact, "/")?;
+        }
+        if !matches!(self.github_projects.source.as_str(), "pinned" | "starred") {
+            anyhow::bail!("github_projects.source must be \"pinned\" or \"starred\"");
+        }
+        if let Some(user) = &self.github_projects.user {
+            if !crate::utils::Utils::is_valid_github_username(user) {
+                anyhow::bail!(
+                    "github_projects.user '{}' is not a valid GitHub username",
+                    user
+                );
+            }
+        }
+
+        if self.reading_time.words_per_minute == 0
+            || self.reading_time.characters_per_minute == 0
+            || self.reading_time.languages.values().any(|wpm| *wpm == 0)
+        {
+            anyhow::bail!("reading_time speeds must be greater than zero");
+        }
+
+        if !matches!(
+            self.markdown.video.preload.as_str(),
+            "none" | "metadata" | "auto"
+        ) {
+            anyhow::bail!("markdown.video.preload must be \"none\", \"metadata\" or \"auto\"");
+        }
+
+        for agent in &self.robots.agents {
+            if agent.user_agent.trim().is_empty() {
+                anyhow::bail!("robots.agents entries need a user_agent");
+            }
+        }
+        let robots_paths = self.robots.allow.iter().chain(&self.robots.disallow).chain(
+            self.robots
+                .agents
+                .iter()
+                .flat_map(|agent| agent.allow.iter().chain(&agent.disallow)),
+        );
+        for path in robots_paths {
+            if !path.starts_with(['/', '*']) {
+                anyhow::bail!("robots paths must start with / or *, got \"{}\"", path);
+            }
+        }
+
+        for status in self.error_pages.keys() {
+            if !status
+                .parse::<u16>()
+                .is_ok_and(|status| (400..=599).contains(&status))
+            {
+                anyhow::bail!(
+                    "error_pages: \"{}\" is not an HTTP error status (400-599)",
+                    status
+                );
+            }
+        }
+
+        for host in &self.security.hosts {
+            if !crate::generator::security::HOSTS.contains(&host.as_str()) {
+                anyhow::bail!(
+                    "security.hosts: unknown host \"{}\" (expected {})",
+                    host,
+                    crate::generator::security::HOSTS.join(", ")
+                );
+            }
+        }
+        for directive in self.security.sources.keys() {
+            let valid = directive.ends_with("-src")
+                || matches!(
+                    directive.as_str(),
+                    "form-action" | "base-uri" | "frame-ancestors"
+                );
+            if !valid {
+                anyhow::bail!("security.sources: \"{}\" is not a CSP directive", directive);
+            }
+        }
+
+        for rule in &self.prose.disable {
+            rule.parse::<crate::prose::ProseRule>()
+                .map_err(|e| anyhow::anyhow!("prose.disable: {}", e))?;
+        }
+        if self.prose.max_sentence_words == 0 {
+            anyhow::bail!("prose.max_sentence_words must be greater than zero");
+        }
+
+        if !matches!(self.drafts.sync.as_str(), "branch" | "gist") {
+            anyhow::bail!("drafts.sync must be \"branch\" or \"gist\"");
+        }
+        if self.drafts.branch.trim().is_empty() {
+            anyhow::bail!("drafts.branch cannot be empty");
+        }
+        let deploy_branch = self
+            .github
+            .as_ref()
+            .and_then(|github| github.branch.as_deref())
+            .unwrap_or("gh-pages");
+        if self.drafts.sync == "branch" && self.drafts.branch == deploy_branch {
+            anyhow::bail!("drafts.branch must differ from the deploy branch");
+        }
+
+        let hooks = [
+            ("pre_build", &self.hooks.pre_build),
+            ("post_build", &self.hooks.post_build),
+            ("post_deploy", &self.hooks.post_deploy),
+        ];
+        for (stage, commands) in hooks {
+            if commands.iter().any(|command| command.trim().is_empty()) {
+                anyhow::bail!("Empty command in hooks.{}", stage);
+            }
+        }
+
         // Validate newsletter configuration
         if self.newsletter.enabled {
             if self.newsletter.subscribe_email.is_none() {
@@ -464,6 +2253,81 @@ impl Config {
                     anyhow::bail!("Invalid newsletter subscribe_email format");
                 }
             }
+
+            if let Some(endpoint) = &self.newsletter.subscribe_endpoint {
+                if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                    anyhow::bail!("Newsletter subscribe_endpoint must be an http(s) URL");
+                }
+            }
+
+            if self.newsletter.sending.max_retries > MAX_SEND_RETRIES {
+                anyhow::bail!(
+                    "newsletter.sending.max_retries cannot be more than {}",
+                    MAX_SEND_RETRIES
+                );
+            }
+
+            let fetch = &self.newsletter.fetch;
+            if fetch.folder.trim().is_empty() {
+                anyhow::bail!("newsletter.fetch.folder cannot be empty");
+            }
+            if fetch
+                .subscribe
+                .iter()
+                .all(|pattern| pattern.trim().is_empty())
+            {
+                anyhow::bail!("newsletter.fetch.subscribe needs at least one pattern");
+            }
+            if fetch.archive_folder.as_deref().map(str::trim) == Some(fetch.folder.trim()) {
+                anyhow::bail!(
+                    "newsletter.fetch.archive_folder must differ from the folder it reads"
+                );
+            }
+
+            let accounts = [
+                (
+                    "imap",
+                    self.newsletter
+                        .imap
+                        .as_ref()
+                        .and_then(|imap| imap.oauth.as_ref()),
+                ),
+                (
+                    "smtp",
+                    self.newsletter
+                        .smtp
+                        .as_ref()
+                        .and_then(|smtp| smtp.oauth.as_ref()),
+                ),
+            ];
+            for (section, provider) in accounts {
+                if let Some(provider) = provider {
+                    if !self.newsletter.oauth.contains_key(provider) {
+                        anyhow::bail!(
+                            "newsletter.{}.oauth refers to '{}', which has no [newsletter.oauth.{}] section",
+                            section,
+                            provider,
+                            provider
+                        );
+                    }
+                }
+            }
+            for (name, provider) in &self.newsletter.oauth {
+                if provider.client_id.trim().is_empty() {
+                    anyhow::bail!("newsletter.oauth.{}.client_id cannot be empty", name);
+                }
+                let built_in = matches!(name.as_str(), "google" | "microsoft");
+                if !built_in
+                    && ((provider.device_url.is_none() && provider.auth_url.is_none())
+                        || provider.token_url.is_none()
+                        || provider.scopes.is_empty())
+                {
+                    anyhow::bail!(
+                        "newsletter.oauth.{} needs device_url or auth_url, token_url and scopes (only google and microsoft are built in)",
+                        name
+                    );
+                }
+            }
         }
 
         Ok(())
@@ -521,6 +2385,15 @@ impl Config {
         self.blog.base_url.trim_end_matches('/').to_string()
     }
 
+    /// Get the absolute URL of a page path, honouring the `[urls]` style
+    pub fn page_url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.get_effective_base_url(),
+            self.urls.page_path(path)
+        )
+    }
+
     /// Detect the deployment type based on the effective base URL
     pub fn get_deployment_type(&self) -> DeploymentType {
         let base_url = self.get_effective_base_url();
@@ -557,6 +2430,7 @@ impl Config {
                 subdomain: None,
                 enforce_https,
                 github_pages_domain: Some(domain.clone()),
+                www_redirect: true,
             });
         } else if let Some(domains) = &mut self.blog.domains {
             domains.primary = Some(domain.clone());
@@ -579,6 +2453,7 @@ impl Config {
                 }),
                 enforce_https,
                 github_pages_domain: Some(full_domain),
+                www_redirect: true,
             });
         } else if let Some(domains) = &mut self.blog.domains {
             domains.subdomain = Some(SubdomainConfig {
@@ -599,6 +2474,7 @@ impl Config {
                 subdomain: None,
                 enforce_https: true,
                 github_pages_domain: None,
+                www_redirect: true,
             });
         } else if let Some(domains) = &mut self.blog.domains {
             if !domains.aliases.contains(&alias) {
@@ -619,7 +2495,7 @@ impl Config {
         self.blog.domains = None;
     }
 
-    /// Get all configured domains (primary + aliases + subdomain)
+    /// Get all configured domains (primary + subdomain + aliases)
     pub fn get_all_domains(&self) -> Vec<String> {
         let mut domains = Vec::new();
 
@@ -631,12 +2507,57 @@ impl Config {
             if let Some(subdomain) = &domain_config.subdomain {
                 domains.push(format!("{}.{}", subdomain.prefix, subdomain.base_domain));
             }
-
-            domains.extend(domain_config.aliases.clone());
         }
 
+        for alias in self.alias_domains() {
+            if !domains.contains(&alias) {
+                domains.push(alias);
+            }
+        }
         domains
     }
+
+    /// The custom domain the site is served from, if there is one
+    pub fn main_domain(&self) -> Option<String> {
+        let domains = self.blog.domains.as_ref()?;
+        domains.primary.clone().or_else(|| {
+            domains
+                .subdomain
+                .as_ref()
+                .map(|subdomain| format!("{}.{}", subdomain.prefix, subdomain.base_domain))
+        })
+    }
+
+    /// Domains that redirect to the main one: the configured aliases, and
+    /// the `www.` or apex form of the main domain unless `www_redirect` is off
+    pub fn alias_domains(&self) -> Vec<String> {
+        let Some(domains) = &self.blog.domains else {
+            return Vec::new();
+        };
+        let main = self.main_domain();
+        let mut aliases: Vec<String> = Vec::new();
+        let www = main
+            .as_deref()
+            .filter(|_| domains.www_redirect)
+            .and_then(www_counterpart);
+        for alias in domains.aliases.iter().cloned().chain(www) {
+            let alias = alias.trim().trim_end_matches('.').to_lowercase();
+            if !alias.is_empty() && Some(&alias) != main.as_ref() && !aliases.contains(&alias) {
+                aliases.push(alias);
+            }
+        }
+        aliases
+    }
+}
+
+/// `www.example.com` for `example.com` and the other way round; other
+/// subdomains have no counterpart
+pub fn www_counterpart(domain: &str) -> Option<String> {
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    if let Some(apex) = domain.strip_prefix("www.") {
+        return apex.contains('.').then(|| apex.to_string());
+    }
+    (domain.split('.').count() == 2).then(|| format!("www.{}", domain))
 }
 
 /// Environment variable utilities for configuration
@@ -716,6 +2637,54 @@ mod tests {
 
         config.blog.title = "".to_string();
         assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        config.build.pagination.posts_per_page = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_deploy_targets() {
+        let deploy: DeployConfig = toml::from_str(
+            r#"
+parallel = true
+
+[[targets]]
+name = "pages"
+type = "github"
+
+[[targets]]
+name = "mirror"
+type = "s3"
+bucket = "blog-mirror"
+region = "eu-west-1"
+
+[[targets]]
+name = "server"
+type = "command"
+command = "rsync -a $BLOGR_OUTPUT_DIR/ host:/srv/blog/"
+"#,
+        )
+        .unwrap();
+
+        assert!(deploy.parallel);
+        let kinds: Vec<String> = deploy
+            .targets
+            .iter()
+            .map(|target| target.kind.describe())
+            .collect();
+        assert_eq!(kinds, ["github (gh-pages)", "s3 (blog-mirror)", "command"]);
+        assert!(matches!(
+            &deploy.targets[1].kind,
+            DeployTargetKind::S3 { region, endpoint: None, .. } if region == "eu-west-1"
+        ));
+
+        let mut config = Config {
+            deploy,
+            ..Config::default()
+        };
+        config.deploy.targets[2].name = "pages".to_string();
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -754,6 +2723,51 @@ output_dir = "dist"
         assert!(config.newsletter.sender_name.is_none());
     }
 
+    #[test]
+    fn test_url_config_paths() {
+        let plain = UrlConfig::default();
+        assert_eq!(plain.page_path("posts/hello.html"), "posts/hello.html");
+        assert_eq!(plain.page_path("tags"), "tags/");
+        assert_eq!(
+            plain.output_path("posts/hello.html"),
+            PathBuf::from("posts/hello.html")
+        );
+
+        let pretty = UrlConfig {
+            pretty: true,
+            ..UrlConfig::default()
+        };
+        assert_eq!(pretty.page_path("posts/hello.html"), "posts/hello/");
+        assert_eq!(pretty.page_path("tags/index.html"), "tags/");
+        assert_eq!(pretty.page_path("index.html"), "");
+        assert_eq!(pretty.page_path("js/search.js"), "js/search.js");
+        assert_eq!(
+            pretty.output_path("posts/hello.html"),
+            PathBuf::from("posts/hello/index.html")
+        );
+        assert_eq!(
+            pretty.output_path("tags/index.html"),
+            PathBuf::from("tags/index.html")
+        );
+
+        let no_slash = UrlConfig {
+            pretty: true,
+            trailing_slash: false,
+            ..UrlConfig::default()
+        };
+        assert_eq!(no_slash.page_path("posts/hello.html"), "posts/hello");
+        assert_eq!(no_slash.page_path("tags/"), "tags");
+
+        let section = Config::default().blog_section().urls;
+        assert_eq!(
+            section.page_path("posts/hello.html"),
+            "blog/posts/hello.html"
+        );
+        assert_eq!(section.page_path(""), "blog/");
+        assert_eq!(section.asset_path("/css/style.css"), "blog/css/style.css");
+        assert_eq!(section.asset_path("static/me.png"), "static/me.png");
+    }
+
     #[test]
     fn test_newsletter_validation() {
         let mut config = Config::default();
@@ -772,5 +2786,33 @@ output_dir = "dist"
         // Valid email should pass
         config.newsletter.subscribe_email = Some("newsletter@example.com".to_string());
         assert!(config.validate().is_ok());
+
+        config.newsletter.sending.max_retries = 64;
+        assert!(config.validate().is_err());
+        config.newsletter.sending.max_retries = MAX_SEND_RETRIES;
+        assert!(config.validate().is_ok());
+    }
+    #[test]
+    fn test_alias_domains() {
+        let mut config = Config::default();
+        config.set_primary_domain("example.com".to_string(), true);
+        config.add_domain_alias("Old-Blog.net".to_string());
+        config.add_domain_alias("example.com".to_string());
+        assert_eq!(config.alias_domains(), ["old-blog.net", "www.example.com"]);
+        assert_eq!(
+            config.get_all_domains(),
+            ["example.com", "old-blog.net", "www.example.com"]
+        );
+
+        config.set_primary_domain("www.example.com".to_string(), true);
+        assert_eq!(config.alias_domains(), ["old-blog.net", "example.com"]);
+
+        // Listed aliases stay without the automatic one
+        config.blog.domains.as_mut().unwrap().www_redirect = false;
+        config.remove_domain_alias("example.com");
+        assert_eq!(config.alias_domains(), ["old-blog.net"]);
+
+        assert_eq!(www_counterpart("blog.example.com"), None);
+        assert_eq!(www_counterpart("www.com"), None);
     }
 }
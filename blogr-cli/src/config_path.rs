@@ -0,0 +1,318 @@
+//! Dot paths into `blogr.toml`, for `blogr config get/set/unset/list`
+//!
+//! A path is a list of keys separated by dots, with `[n]` to index arrays:
+//! `theme.config.show_avatar`, `menu[2].url`. Keys that aren't plain names
+//! are quoted, as in TOML: `redirects."/old.html"`.
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use std::fmt;
+use toml::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A parsed dot path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigPath(pub Vec<Segment>);
+
+impl ConfigPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut chars = path.chars().peekable();
+        // Whether the next thing must be a key, as at the start and after a dot
+        let mut want_key = true;
+        while let Some(&c) = chars.peek() {
+            match c {
+                '[' => {
+                    chars.next();
+                    let index: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                    let index = index
+                        .trim()
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid array index '[{}]' in '{}'", index, path))?;
+                    segments.push(Segment::Index(index));
+                    want_key = false;
+                }
+                '.' if !want_key => {
+                    chars.next();
+                    want_key = true;
+                }
+                '"' if want_key => {
+                    chars.next();
+                    let mut key = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => key.extend(chars.next()),
+                            Some(c) => key.push(c),
+                            None => bail!("Unclosed quote in '{}'", path),
+                        }
+                    }
+                    segments.push(Segment::Key(key));
+                    want_key = false;
+                }
+                _ if want_key => {
+                    let mut key = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        key.push(c);
+                        chars.next();
+                    }
+                    if key.is_empty() {
+                        bail!("Empty key in '{}'", path);
+                    }
+                    segments.push(Segment::Key(key));
+                    want_key = false;
+                }
+                _ => bail!(
+                    "Expected '.' or '[' after '{}' in '{}'",
+                    segments_text(&segments),
+                    path
+                ),
+            }
+        }
+        if want_key {
+            bail!("'{}' is not a configuration key", path);
+        }
+        Ok(Self(segments))
+    }
+
+    /// The value at this path
+    pub fn get<'a>(&self, root: &'a Value) -> Option<&'a Value> {
+        self.0
+            .iter()
+            .try_fold(root, |value, segment| match segment {
+                Segment::Key(key) => value.as_table()?.get(key),
+                Segment::Index(index) => value.as_array()?.get(*index),
+            })
+    }
+
+    /// Set the value at this path, creating missing tables. An index can
+    /// replace an element or append one just past the end.
+    pub fn set(&self, root: &mut Value, value: Value) -> Result<()> {
+        let mut current = root;
+        for (i, segment) in self.0.iter().enumerate() {
+            // What to create if this step is missing
+            let empty = || match self.0.get(i + 1) {
+                Some(Segment::Index(_)) => Value::Array(Vec::new()),
+                _ => Value::Table(toml::Table::new()),
+            };
+            let parent = || segments_text(&self.0[..i]);
+            current = match segment {
+                Segment::Key(key) => {
+                    let table = current
+                        .as_table_mut()
+                        .ok_or_else(|| anyhow!("'{}' is not a table", parent()))?;
+                    table.entry(key.clone()).or_insert_with(empty)
+                }
+                Segment::Index(index) => {
+                    let prefix = parent();
+                    let array = current
+                        .as_array_mut()
+                        .ok_or_else(|| anyhow!("'{}' is not an array", prefix))?;
+                    if *index == array.len() {
+                        array.push(empty());
+                    }
+                    let len = array.len();
+                    array.get_mut(*index).ok_or_else(|| {
+                        anyhow!(
+                            "'{}' has {} entries; [{}] is out of range",
+                            prefix,
+                            len,
+                            index
+                        )
+                    })?
+                }
+            };
+        }
+        *current = value;
+        Ok(())
+    }
+
+    /// Remove the value at this path, returning it if it was there
+    pub fn remove(&self, root: &mut Value) -> Option<Value> {
+        let (last, parents) = self.0.split_last()?;
+        let parent = parents
+            .iter()
+            .try_fold(root, |value, segment| match segment {
+                Segment::Key(key) => value.as_table_mut()?.get_mut(key),
+                Segment::Index(index) => value.as_array_mut()?.get_mut(*index),
+            })?;
+        match last {
+            Segment::Key(key) => parent.as_table_mut()?.remove(key),
+            Segment::Index(index) => {
+                let array = parent.as_array_mut()?;
+                (*index < array.len()).then(|| array.remove(*index))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConfigPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&segments_text(&self.0))
+    }
+}
+
+fn segments_text(segments: &[Segment]) -> String {
+    let mut text = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Key(key) => {
+                if !text.is_empty() {
+                    text.push('.');
+                }
+                let bare = !key.is_empty()
+                    && key
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+                if bare {
+                    text.push_str(key);
+                } else {
+                    text.push_str(&format!("{:?}", key));
+                }
+            }
+            Segment::Index(index) => text.push_str(&format!("[{}]", index)),
+        }
+    }
+    text
+}
+
+/// Every value under `root` with its path: tables are walked, and so are
+/// arrays of tables, like `[[menu]]`; other arrays are single values
+pub fn leaves(root: &Value) -> Vec<(ConfigPath, &Value)> {
+    fn walk<'a>(value: &'a Value, path: &mut Vec<Segment>, out: &mut Vec<(ConfigPath, &'a Value)>) {
+        match value {
+            Value::Table(table) => {
+                for (key, value) in table {
+                    path.push(Segment::Key(key.clone()));
+                    walk(value, path, out);
+                    path.pop();
+                }
+            }
+            Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_table) => {
+                for (index, value) in items.iter().enumerate() {
+                    path.push(Segment::Index(index));
+                    walk(value, path, out);
+                    path.pop();
+                }
+            }
+            _ => out.push((ConfigPath(path.clone()), value)),
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(root, &mut Vec::new(), &mut out);
+    out
+}
+
+/// Parse a value typed on the command line. If the current value is a
+/// string the input is kept as text; otherwise it is read as TOML (`true`,
+/// `42`, `["a", "b"]`), falling back to text.
+pub fn parse_value(input: &str, current: Option<&Value>) -> Value {
+    if matches!(current, Some(Value::String(_))) {
+        return Value::String(input.to_string());
+    }
+    toml::de::ValueDeserializer::parse(input)
+        .ok()
+        .and_then(|value| Value::deserialize(value).ok())
+        .unwrap_or_else(|| Value::String(input.to_string()))
+}
+
+/// A value as `config get` prints it: strings without quotes, tables as TOML
+pub fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Table(table) => toml::to_string_pretty(table)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_paths() {
+        let path = ConfigPath::parse("menu[2].url").unwrap();
+        assert_eq!(
+            path.0,
+            [
+                Segment::Key("menu".into()),
+                Segment::Index(2),
+                Segment::Key("url".into())
+            ]
+        );
+        let path = ConfigPath::parse("redirects.\"/old.html\"").unwrap();
+        assert_eq!(path.0[1], Segment::Key("/old.html".into()));
+        assert_eq!(path.to_string(), "redirects.\"/old.html\"");
+
+        for bad in ["", "blog.", ".blog", "menu[x]", "blog..title", "menu[0]url"] {
+            assert!(ConfigPath::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_set_get_remove() {
+        let mut root: Value =
+            toml::from_str("[blog]\ntitle = \"Old\"\n[[menu]]\nlabel = \"Home\"\n").unwrap();
+
+        let path = |p: &str| ConfigPath::parse(p).unwrap();
+        path("blog.title").set(&mut root, "New".into()).unwrap();
+        path("theme.config.show_avatar")
+            .set(&mut root, true.into())
+            .unwrap();
+        path("menu[1].url").set(&mut root, "/about".into()).unwrap();
+        assert!(path("menu[5].url").set(&mut root, "/x".into()).is_err());
+        assert!(path("blog.title.x").set(&mut root, "x".into()).is_err());
+
+        assert_eq!(path("blog.title").get(&root), Some(&Value::from("New")));
+        assert_eq!(
+            path("theme.config.show_avatar").get(&root),
+            Some(&Value::from(true))
+        );
+        assert_eq!(path("menu[1].url").get(&root), Some(&Value::from("/about")));
+
+        let keys: Vec<String> = leaves(&root).iter().map(|(p, _)| p.to_string()).collect();
+        assert_eq!(
+            keys,
+            [
+                "blog.title",
+                "menu[0].label",
+                "menu[1].url",
+                "theme.config.show_avatar"
+            ]
+        );
+
+        assert_eq!(
+            path("menu[0]")
+                .remove(&mut root)
+                .and_then(|v| v.get("label").cloned()),
+            Some("Home".into())
+        );
+        assert_eq!(path("menu[0].url").get(&root), Some(&Value::from("/about")));
+        assert_eq!(path("blog.missing").remove(&mut root), None);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let text = Value::from("x");
+        assert_eq!(parse_value("true", Some(&text)), Value::from("true"));
+        assert_eq!(parse_value("true", None), Value::from(true));
+        assert_eq!(parse_value("42", Some(&Value::from(1))), Value::from(42));
+        assert_eq!(
+            parse_value("[\"a\", \"b\"]", None),
+            Value::Array(vec!["a".into(), "b".into()])
+        );
+        assert_eq!(parse_value("hello world", None), Value::from("hello world"));
+    }
+}
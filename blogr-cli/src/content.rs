@@ -1,12 +1,17 @@
+use crate::config::{Config, ReadingTimeConfig, SlugConfig};
+use crate::history::PostHistory;
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostMetadata {
+    /// Empty for short posts without a title; see `Post::display_title`
+    #[serde(default)]
     pub title: String,
     #[serde(deserialize_with = "deserialize_date")]
     pub date: DateTime<Utc>,
@@ -14,9 +19,53 @@ pub struct PostMetadata {
     pub description: String,
     pub tags: Vec<String>,
     pub status: PostStatus,
+    /// Taken from the file name when the frontmatter has none
+    #[serde(default)]
     pub slug: String,
     #[serde(default)]
     pub featured: bool,
+    /// Old URL paths that should redirect to this post (e.g. `/old-url/`)
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// When the post was last modified (filled from git history when enabled)
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// People who edited the post (filled from git history when enabled)
+    #[serde(default)]
+    pub contributors: Vec<String>,
+    /// Copies on other platforms, by platform name (filled by `blogr crosspost`)
+    #[serde(default)]
+    pub crosspost: BTreeMap<String, CrossPost>,
+    /// YouTube or Vimeo link, or a video file in the project, shown above the post
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video: Option<String>,
+    /// The page a link post is about, for link blogs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// Cover image: a site path such as `static/images/harbor.jpg`, or a full URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover: Option<String>,
+    /// Alt text for the cover image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_alt: Option<String>,
+    /// Keep search engines from indexing the post and leave it out of the sitemap
+    #[serde(default)]
+    pub noindex: bool,
+    /// Encrypt the post's text so it can only be read with a password
+    #[serde(default)]
+    pub private: bool,
+    /// Password of a private post, instead of the site's. Never passed to templates.
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+}
+
+/// A copy of a post published on another platform
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CrossPost {
+    /// The platform's ID for the post, used to update it
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 fn deserialize_date<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
@@ -25,35 +74,127 @@ where
 {
     use serde::Deserialize;
     let s = String::deserialize(deserializer)?;
+    parse_date(&s).ok_or_else(|| serde::de::Error::custom(format!("Unable to parse date: {}", s)))
+}
 
+/// Parse a frontmatter date: RFC 3339, `YYYY-MM-DD` or `YYYY-MM-DD HH:MM:SS` (UTC)
+fn parse_date(s: &str) -> Option<DateTime<Utc>> {
     // Try parsing as RFC3339 first
-    if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
-        return Ok(dt.with_timezone(&Utc));
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
     }
 
     // Try parsing as date only (YYYY-MM-DD)
-    if let Ok(naive_date) = chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
+    if let Ok(naive_date) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
         let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
-        return Ok(DateTime::from_naive_utc_and_offset(naive_datetime, Utc));
+        return Some(DateTime::from_naive_utc_and_offset(naive_datetime, Utc));
     }
 
     // Try parsing as datetime without timezone
-    if let Ok(naive_datetime) = chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S") {
-        return Ok(DateTime::from_naive_utc_and_offset(naive_datetime, Utc));
-    }
+    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|naive_datetime| DateTime::from_naive_utc_and_offset(naive_datetime, Utc))
+}
 
-    Err(serde::de::Error::custom(format!(
-        "Unable to parse date: {}",
-        s
-    )))
+fn deserialize_optional_date<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(deserialize_with = "deserialize_date")] DateTime<Utc>);
+
+    Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(date)| date))
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+/// File name of a post stored as a page bundle, `posts/my-post/index.md`
+pub const BUNDLE_INDEX: &str = "index.md";
+
+/// Markers that end a post's summary, with and without spaces
+const MORE_MARKERS: &[&str] = &["<!--more-->", "<!-- more -->"];
+
+/// Words from an untitled post used as its title
+const DISPLAY_TITLE_WORDS: usize = 8;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum PostStatus {
     #[default]
     Draft,
+    /// Finished and waiting for a review
+    #[serde(rename = "in-review", alias = "review")]
+    InReview,
+    /// Goes live on the first build after its date
+    Scheduled,
     Published,
+    /// Kept in the project but taken off the site
+    Archived,
+}
+
+impl PostStatus {
+    pub const ALL: [PostStatus; 5] = [
+        PostStatus::Draft,
+        PostStatus::InReview,
+        PostStatus::Scheduled,
+        PostStatus::Published,
+        PostStatus::Archived,
+    ];
+
+    /// The name used in frontmatter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostStatus::Draft => "draft",
+            PostStatus::InReview => "in-review",
+            PostStatus::Scheduled => "scheduled",
+            PostStatus::Published => "published",
+            PostStatus::Archived => "archived",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            PostStatus::Draft => "📝",
+            PostStatus::InReview => "👀",
+            PostStatus::Scheduled => "⏰",
+            PostStatus::Published => "✅",
+            PostStatus::Archived => "📦",
+        }
+    }
+
+    /// Whether posts with this status belong on the site once their date has passed
+    pub fn is_public(&self) -> bool {
+        matches!(self, PostStatus::Scheduled | PostStatus::Published)
+    }
+
+    /// Whether the post is still being written, shown only in builds with drafts
+    pub fn is_unfinished(&self) -> bool {
+        matches!(self, PostStatus::Draft | PostStatus::InReview)
+    }
+}
+
+impl std::fmt::Display for PostStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PostStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase().replace('_', "-");
+        if s == "review" {
+            return Ok(PostStatus::InReview);
+        }
+        PostStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown post status '{}' (expected draft, in-review, scheduled, published or archived)",
+                    s
+                )
+            })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +227,17 @@ impl Post {
             status,
             slug: slug.clone(),
             featured: false,
+            aliases: Vec::new(),
+            updated_at: None,
+            contributors: Vec::new(),
+            crosspost: Default::default(),
+            video: None,
+            link: None,
+            cover: None,
+            cover_alt: None,
+            noindex: false,
+            private: false,
+            password: None,
         };
 
         Self {
@@ -99,14 +251,33 @@ impl Post {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref();
         let content = fs::read_to_string(path)?;
+        Self::parse(&content, path)
+    }
 
+    /// Parse a post from the text of a markdown file
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
         // Split frontmatter and content
-        let (frontmatter, body) = Self::parse_frontmatter(&content)?;
+        let (frontmatter, body) = Self::parse_frontmatter(content)?;
 
         // Parse metadata from frontmatter
-        let metadata: PostMetadata = serde_yaml::from_str(&frontmatter)
+        let mut metadata: PostMetadata = serde_yaml::from_str(&frontmatter)
             .map_err(|e| anyhow!("Failed to parse frontmatter: {}", e))?;
 
+        // Short posts can leave out the slug: `posts/<slug>.md` or `posts/<slug>/index.md`
+        if metadata.slug.trim().is_empty() {
+            let name = if path.file_name().is_some_and(|name| name == BUNDLE_INDEX) {
+                path.parent().and_then(Path::file_name)
+            } else {
+                path.file_stem()
+            };
+            metadata.slug = name
+                .map(|name| Self::generate_slug(&name.to_string_lossy()))
+                .unwrap_or_default();
+            if metadata.slug.is_empty() {
+                return Err(anyhow!("Post has no slug and its file name makes none"));
+            }
+        }
+
         Ok(Self {
             metadata,
             content: body,
@@ -121,6 +292,7 @@ impl Post {
         // Create a serializable version with date as string
         #[derive(Serialize)]
         struct SerializableMetadata {
+            #[serde(skip_serializing_if = "String::is_empty")]
             title: String,
             date: String,
             author: String,
@@ -129,17 +301,60 @@ impl Post {
             status: PostStatus,
             slug: String,
             featured: bool,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            aliases: Vec<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            updated_at: Option<String>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            contributors: Vec<String>,
+            #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+            crosspost: BTreeMap<String, CrossPost>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            video: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            link: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            cover: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            cover_alt: Option<String>,
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            noindex: bool,
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            private: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            password: Option<String>,
         }
 
         let serializable = SerializableMetadata {
             title: self.metadata.title.clone(),
-            date: self.metadata.date.format("%Y-%m-%d").to_string(),
+            // Keep the time when there is one, so scheduled posts go live on time
+            date: if self.metadata.date.time() == chrono::NaiveTime::MIN {
+                self.metadata.date.format("%Y-%m-%d").to_string()
+            } else {
+                self.metadata
+                    .date
+                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            },
             author: self.metadata.author.clone(),
             description: self.metadata.description.clone(),
             tags: self.metadata.tags.clone(),
-            status: self.metadata.status.clone(),
+            status: self.metadata.status,
             slug: self.metadata.slug.clone(),
             featured: self.metadata.featured,
+            aliases: self.metadata.aliases.clone(),
+            updated_at: self
+                .metadata
+                .updated_at
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+            contributors: self.metadata.contributors.clone(),
+            crosspost: self.metadata.crosspost.clone(),
+            video: self.metadata.video.clone(),
+            link: self.metadata.link.clone(),
+            cover: self.metadata.cover.clone(),
+            cover_alt: self.metadata.cover_alt.clone(),
+            noindex: self.metadata.noindex,
+            private: self.metadata.private,
+            password: self.metadata.password.clone(),
         };
 
         // Create frontmatter
@@ -171,6 +386,38 @@ impl Post {
             .join("-")
     }
 
+    /// Slug for a new post following the project's `[slugs]` rules
+    pub fn slug_from_title(title: &str, date: DateTime<Utc>, rules: &SlugConfig) -> String {
+        let mut slug = if rules.transliterate {
+            Self::generate_slug(&deunicode::deunicode(title))
+        } else {
+            Self::generate_slug(title)
+        };
+
+        let prefix = if rules.date_prefix {
+            date.format("%Y-%m-%d-").to_string()
+        } else {
+            String::new()
+        };
+
+        if rules.max_length > 0 {
+            let limit = rules.max_length.saturating_sub(prefix.len());
+            if slug.chars().count() > limit {
+                let cut: String = slug.chars().take(limit + 1).collect();
+                // Cut at the last whole word, or mid-word if the first word is too long
+                slug = match cut.rfind('-') {
+                    Some(end) if end > 0 => cut[..end].to_string(),
+                    _ => cut.chars().take(limit).collect(),
+                };
+            }
+        }
+
+        if slug.is_empty() {
+            return slug;
+        }
+        prefix + &slug
+    }
+
     /// Parse frontmatter and content from markdown
     fn parse_frontmatter(content: &str) -> Result<(String, String)> {
         if !content.starts_with("---\n") {
@@ -188,11 +435,53 @@ impl Post {
         }
     }
 
+    /// The title, or for an untitled post the start of its text, for feeds,
+    /// search results and `<title>`. Untitled private posts use their date.
+    pub fn display_title(&self) -> String {
+        if !self.metadata.title.trim().is_empty() {
+            return self.metadata.title.clone();
+        }
+        let text = if self.metadata.private {
+            String::new()
+        } else {
+            crate::generator::markdown::markdown_to_text(&self.content)
+        };
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.is_empty() {
+            return self.metadata.date.format("%B %-d, %Y").to_string();
+        }
+        let mut title = words[..words.len().min(DISPLAY_TITLE_WORDS)].join(" ");
+        if words.len() > DISPLAY_TITLE_WORDS {
+            title.push('…');
+        }
+        title
+    }
+
     /// Get the post's filename based on its slug
     pub fn filename(&self) -> String {
         format!("{}.md", self.metadata.slug)
     }
 
+    /// Folder of a page bundle (`posts/my-post/index.md`), whose other files
+    /// are published next to the post
+    pub fn bundle_dir(&self) -> Option<&Path> {
+        if self.file_path.file_name()? == BUNDLE_INDEX {
+            self.file_path.parent()
+        } else {
+            None
+        }
+    }
+
+    /// Whether the post is on the site: public and with a date that has passed
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.metadata.status.is_public() && self.metadata.date <= now
+    }
+
+    /// Whether the post can go out in a newsletter: live and not private
+    pub fn is_mailable(&self, now: DateTime<Utc>) -> bool {
+        self.is_live(now) && !self.metadata.private
+    }
+
     /// Check if post matches the given tag
     pub fn has_tag(&self, tag: &str) -> bool {
         self.metadata
@@ -201,26 +490,114 @@ impl Post {
             .any(|t| t.eq_ignore_ascii_case(tag))
     }
 
-    /// Get estimated reading time in minutes
-    pub fn reading_time(&self) -> usize {
-        const WORDS_PER_MINUTE: usize = 200;
-        let word_count = self.content.split_whitespace().count();
-        (word_count / WORDS_PER_MINUTE).max(1)
+    /// The markdown before the post's `<!--more-->` marker, if it has one
+    pub fn summary_markdown(&self) -> Option<&str> {
+        MORE_MARKERS
+            .iter()
+            .filter_map(|marker| self.content.find(marker))
+            .min()
+            .map(|end| &self.content[..end])
+    }
+
+    /// Word count and estimated reading time of the post body
+    pub fn reading_time(&self, config: &Config) -> ReadingTime {
+        ReadingTime::estimate(
+            &self.content,
+            &config.reading_time,
+            config.blog.language.as_deref(),
+        )
     }
 }
 
+/// How long a text takes to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReadingTime {
+    /// Words, with each Chinese or Japanese character counted as one
+    pub words: usize,
+    pub images: usize,
+    /// Whole minutes, at least 1
+    pub minutes: usize,
+}
+
+impl ReadingTime {
+    /// Estimate for markdown or HTML in `language`. HTML tags and markdown
+    /// punctuation aren't counted; images add time as on Medium, 12 seconds
+    /// for the first (by default) and one less for each following, down to 3.
+    pub fn estimate(text: &str, config: &ReadingTimeConfig, language: Option<&str>) -> Self {
+        let images = text.matches("![").count() + text.to_lowercase().matches("<img").count();
+
+        let mut plain = String::with_capacity(text.len());
+        let mut characters = 0;
+        let mut in_tag = false;
+        for c in text.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' if in_tag => {
+                    in_tag = false;
+                    plain.push(' ');
+                }
+                _ if in_tag => {}
+                c if is_counted_by_character(c) => {
+                    characters += 1;
+                    plain.push(' ');
+                }
+                c => plain.push(c),
+            }
+        }
+        let words = plain
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .count();
+
+        let words_per_minute = config.words_per_minute_for(language).max(1) as f64;
+        let characters_per_minute = config.characters_per_minute.max(1) as f64;
+        let image_seconds: usize = (0..images)
+            .map(|i| config.seconds_per_image.saturating_sub(i).max(3))
+            .sum();
+        let seconds = words as f64 / words_per_minute * 60.0
+            + characters as f64 / characters_per_minute * 60.0
+            + image_seconds as f64;
+
+        Self {
+            words: words + characters,
+            images,
+            minutes: ((seconds / 60.0).round() as usize).max(1),
+        }
+    }
+}
+
+/// Chinese characters and Japanese kana, which are written without spaces
+/// between words. Korean uses spaces, so Hangul is counted by word.
+fn is_counted_by_character(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana and Katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}'   // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2FA1F}' // CJK Extensions B and later
+    )
+}
+
 /// Manager for blog posts
 pub struct PostManager {
     posts_dir: PathBuf,
+    history: Option<PostHistory>,
 }
 
 impl PostManager {
     pub fn new<P: AsRef<Path>>(posts_dir: P) -> Self {
         Self {
             posts_dir: posts_dir.as_ref().to_path_buf(),
+            history: None,
         }
     }
 
+    /// Keep a copy of each saved version in the project's post history
+    pub fn with_history(mut self, project_root: &Path) -> Self {
+        self.history = Some(PostHistory::new(project_root));
+        self
+    }
+
     /// Load all posts from the posts directory
     pub fn load_all_posts(&self) -> Result<Vec<Post>> {
         let mut posts = Vec::new();
@@ -238,7 +615,7 @@ impl PostManager {
             if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
                 match Post::from_file(path) {
                     Ok(post) => posts.push(post),
-                    Err(e) => eprintln!("Warning: Failed to load post {}: {}", path.display(), e),
+                    Err(e) => tracing::warn!("Failed to load post {}: {}", path.display(), e),
                 }
             }
         }
@@ -272,18 +649,39 @@ impl PostManager {
             .collect())
     }
 
+    /// File a post is saved to: `<slug>.md`, or the `index.md` of its page bundle
+    pub fn post_path(&self, post: &Post) -> PathBuf {
+        match post.bundle_dir() {
+            Some(_) => self.posts_dir.join(&post.file_path),
+            None => self.posts_dir.join(post.filename()),
+        }
+    }
+
     /// Save a post to the posts directory
     pub fn save_post(&self, post: &Post) -> Result<PathBuf> {
-        let file_path = self.posts_dir.join(post.filename());
+        let file_path = self.post_path(post);
+        let slug = &post.metadata.slug;
+
+        // The file may have been changed outside the editor since the last save
+        if let Some(history) = &self.history {
+            history.snapshot_file(slug, &file_path)?;
+        }
         post.save_to_file(&file_path)?;
+        if let Some(history) = &self.history {
+            history.snapshot_file(slug, &file_path)?;
+        }
+
         Ok(file_path)
     }
 
     /// Delete a post by slug
     pub fn delete_post(&self, slug: &str) -> Result<bool> {
         if let Some(post) = self.find_by_slug(slug)? {
-            let file_path = self.posts_dir.join(post.filename());
-            fs::remove_file(file_path)?;
+            match post.bundle_dir() {
+                // The bundle's images and files go with it
+                Some(dir) if dir != self.posts_dir => fs::remove_dir_all(dir)?,
+                _ => fs::remove_file(self.post_path(&post))?,
+            }
             Ok(true)
         } else {
             Ok(false)
@@ -358,6 +756,36 @@ mod tests {
         assert_eq!(Post::generate_slug("Multiple---Dashes"), "multiple-dashes");
     }
 
+    #[test]
+    fn test_slug_rules() {
+        use chrono::TimeZone;
+
+        let date = Utc.with_ymd_and_hms(2024, 5, 1, 9, 30, 0).unwrap();
+        let rules = SlugConfig {
+            transliterate: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Post::slug_from_title("Crème Brûlée für alle", date, &rules),
+            "creme-brulee-fur-alle"
+        );
+        assert_eq!(
+            Post::slug_from_title("Crème", date, &SlugConfig::default()),
+            "crème"
+        );
+
+        let rules = SlugConfig {
+            max_length: 24,
+            date_prefix: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Post::slug_from_title("Writing a static site generator", date, &rules),
+            "2024-05-01-writing-a"
+        );
+        assert_eq!(Post::slug_from_title("!!!", date, &rules), "");
+    }
+
     #[test]
     fn test_frontmatter_parsing() {
         let content = r#"---
@@ -380,6 +808,55 @@ This is the body of the post."#;
         assert!(body.trim_start().starts_with("# Test Content"));
     }
 
+    #[test]
+    fn test_untitled_link_post() {
+        let content = "---\ndate: 2024-03-02\nauthor: Ann\ndescription: \"\"\ntags: []\n\
+            status: published\nlink: https://example.org/essay\n---\n\
+            A *good* essay on writing small tools that do one thing and do it well.\n";
+        let post = Post::parse(content, Path::new("posts/2024-03-02 Small tools.md")).unwrap();
+        assert_eq!(post.metadata.title, "");
+        assert_eq!(post.metadata.slug, "2024-03-02-small-tools");
+        assert_eq!(
+            post.metadata.link.as_deref(),
+            Some("https://example.org/essay")
+        );
+        assert_eq!(
+            post.display_title(),
+            "A good essay on writing small tools that…"
+        );
+
+        let bundle = Post::parse(content, Path::new("posts/notes/index.md")).unwrap();
+        assert_eq!(bundle.metadata.slug, "notes");
+    }
+
+    #[test]
+    fn test_workflow_status() {
+        let status: PostStatus = serde_yaml::from_str("review").unwrap();
+        assert_eq!(status, PostStatus::InReview);
+        assert_eq!(serde_yaml::to_string(&status).unwrap().trim(), "in-review");
+        assert_eq!(
+            "Scheduled".parse::<PostStatus>().unwrap(),
+            PostStatus::Scheduled
+        );
+        assert!("pending".parse::<PostStatus>().is_err());
+
+        let now = Utc::now();
+        let mut post = Post::new(
+            "Later".to_string(),
+            "Test Author".to_string(),
+            None,
+            vec![],
+            None,
+            PostStatus::Scheduled,
+        );
+        post.metadata.date = now + chrono::Duration::days(1);
+        assert!(!post.is_live(now));
+        post.metadata.date = now - chrono::Duration::days(1);
+        assert!(post.is_live(now));
+        post.metadata.status = PostStatus::Archived;
+        assert!(!post.is_live(now));
+    }
+
     #[test]
     fn test_post_creation_and_save() {
         let temp_dir = TempDir::new().unwrap();
@@ -400,4 +877,55 @@ This is the body of the post."#;
         assert_eq!(loaded_post.metadata.slug, "test-post");
         assert_eq!(loaded_post.metadata.tags, vec!["test", "example"]);
     }
+
+    #[test]
+    fn test_reading_time() {
+        let config = ReadingTimeConfig::default();
+        let estimate = |text: &str| ReadingTime::estimate(text, &config, None);
+
+        assert_eq!(estimate("<p>Hello <em>big</em> world</p>").words, 3);
+        assert_eq!(estimate("# Title\n\n- one\n- two --- three").words, 4);
+        assert_eq!(estimate("short").minutes, 1);
+        assert_eq!(estimate(&"word ".repeat(1000)).minutes, 5);
+
+        // Each character of Chinese or Japanese counts on its own
+        let japanese = estimate("これは日本語の文章です。Rust");
+        assert_eq!(japanese.words, 12);
+        assert_eq!(estimate(&"漢".repeat(1500)).minutes, 3);
+
+        // 12 + 11 + ... + 3 seconds, then 3 seconds for each further image
+        let images = estimate(&"![photo](a.jpg) ".repeat(12));
+        assert_eq!(images.images, 12);
+        assert_eq!(images.minutes, 1);
+        assert_eq!(estimate(&"<img src=\"a.jpg\">".repeat(40)).minutes, 3);
+    }
+
+    #[test]
+    fn test_reading_speed_by_language() {
+        let mut config = ReadingTimeConfig::default();
+        config.languages.insert("de".to_string(), 100);
+        let text = "Wort ".repeat(600);
+
+        assert_eq!(ReadingTime::estimate(&text, &config, Some("en")).minutes, 3);
+        assert_eq!(
+            ReadingTime::estimate(&text, &config, Some("de-AT")).minutes,
+            6
+        );
+    }
+
+    #[test]
+    fn test_summary_markdown() {
+        let mut post = Post::new(
+            "Summary".to_string(),
+            "Ann".to_string(),
+            None,
+            Vec::new(),
+            None,
+            PostStatus::Draft,
+        );
+        assert_eq!(post.summary_markdown(), None);
+
+        post.content = "First *part*.\n\n<!-- more -->\n\nThe rest <!--more-->".to_string();
+        assert_eq!(post.summary_markdown(), Some("First *part*.\n\n"));
+    }
 }
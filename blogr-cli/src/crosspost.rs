@@ -0,0 +1,401 @@
+//! Cross-posting to dev.to, Hashnode and Medium
+//!
+//! Copies are published with a canonical URL pointing back to the blog, so
+//! search engines credit the original. The ID each platform returns is stored
+//! in the post's frontmatter and used to update the copy on the next run.
+
+use crate::content::{CrossPost, Post};
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+
+/// A platform posts can be copied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    DevTo,
+    Hashnode,
+    Medium,
+}
+
+impl Platform {
+    /// Key used in the `crosspost` frontmatter
+    pub fn key(&self) -> &'static str {
+        match self {
+            Platform::DevTo => "devto",
+            Platform::Hashnode => "hashnode",
+            Platform::Medium => "medium",
+        }
+    }
+
+    /// Environment variable holding the API token
+    pub fn token_var(&self) -> &'static str {
+        match self {
+            Platform::DevTo => "DEVTO_API_KEY",
+            Platform::Hashnode => "HASHNODE_TOKEN",
+            Platform::Medium => "MEDIUM_TOKEN",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Platform::DevTo => "dev.to",
+            Platform::Hashnode => "Hashnode",
+            Platform::Medium => "Medium",
+        })
+    }
+}
+
+impl FromStr for Platform {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "devto" | "dev.to" => Ok(Platform::DevTo),
+            "hashnode" => Ok(Platform::Hashnode),
+            "medium" => Ok(Platform::Medium),
+            other => Err(anyhow!(
+                "Unknown platform '{}'. Use devto, hashnode or medium",
+                other
+            )),
+        }
+    }
+}
+
+/// What is sent to a platform
+pub struct Article {
+    pub title: String,
+    pub description: String,
+    pub markdown: String,
+    pub tags: Vec<String>,
+    pub canonical_url: String,
+    pub draft: bool,
+}
+
+impl Article {
+    /// Prepare a post for publishing elsewhere: links and images are made
+    /// absolute and a leading `# Title` heading is dropped, since every
+    /// platform shows the title itself
+    pub fn from_post(post: &Post, canonical_url: String, draft: bool) -> Self {
+        let mut body = post.content.trim_start();
+        if let Some(rest) = body.strip_prefix("# ") {
+            let (heading, rest) = rest.split_once('\n').unwrap_or((rest, ""));
+            if heading.trim() == post.metadata.title.trim() {
+                body = rest.trim_start();
+            }
+        }
+
+        Self {
+            title: post.metadata.title.clone(),
+            description: post.metadata.description.clone(),
+            markdown: absolutize_links(body, &canonical_url),
+            tags: post.metadata.tags.clone(),
+            canonical_url,
+            draft,
+        }
+    }
+}
+
+/// Publishes and updates copies through the platforms' APIs
+pub struct CrosspostClient {
+    client: reqwest::Client,
+}
+
+impl Default for CrosspostClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrosspostClient {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("blogr/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Create a copy, or update `existing` when the platform allows it
+    pub async fn publish(
+        &self,
+        platform: Platform,
+        token: &str,
+        article: &Article,
+        existing: Option<&CrossPost>,
+        hashnode_publication_id: Option<&str>,
+    ) -> Result<CrossPost> {
+        match platform {
+            Platform::DevTo => self.publish_devto(token, article, existing).await,
+            Platform::Hashnode => {
+                let publication_id = hashnode_publication_id.ok_or_else(|| {
+                    anyhow!("Set crosspost.hashnode_publication_id in blogr.toml")
+                })?;
+                self.publish_hashnode(token, publication_id, article, existing)
+                    .await
+            }
+            Platform::Medium => match existing {
+                Some(_) => Err(anyhow!(
+                    "Medium's API can't update posts; edit the copy on medium.com instead"
+                )),
+                None => self.publish_medium(token, article).await,
+            },
+        }
+    }
+
+    async fn publish_devto(
+        &self,
+        token: &str,
+        article: &Article,
+        existing: Option<&CrossPost>,
+    ) -> Result<CrossPost> {
+        // dev.to allows four tags made of letters and digits
+        let tags: Vec<String> = article
+            .tags
+            .iter()
+            .map(|tag| {
+                tag.chars()
+                    .filter(char::is_ascii_alphanumeric)
+                    .collect::<String>()
+                    .to_lowercase()
+            })
+            .filter(|tag| !tag.is_empty())
+            .take(4)
+            .collect();
+        let body = json!({
+            "article": {
+                "title": article.title,
+                "body_markdown": article.markdown,
+                "description": article.description,
+                "tags": tags,
+                "canonical_url": article.canonical_url,
+                "published": !article.draft,
+            }
+        });
+
+        let request = match existing {
+            Some(existing) => self
+                .client
+                .put(format!("https://dev.to/api/articles/{}", existing.id)),
+            None => self.client.post("https://dev.to/api/articles"),
+        };
+        let response = request
+            .header("api-key", token)
+            .header(reqwest::header::ACCEPT, "application/vnd.forem.api-v1+json")
+            .json(&body)
+            .send()
+            .await?;
+        let response = json_response(response).await?;
+
+        Ok(CrossPost {
+            id: id_string(&response["id"])?,
+            url: response["url"].as_str().map(str::to_string),
+        })
+    }
+
+    async fn publish_hashnode(
+        &self,
+        token: &str,
+        publication_id: &str,
+        article: &Article,
+        existing: Option<&CrossPost>,
+    ) -> Result<CrossPost> {
+        if article.draft {
+            return Err(anyhow!("Hashnode copies can't be created as drafts"));
+        }
+
+        let tags: Vec<Value> = article
+            .tags
+            .iter()
+            .map(|tag| json!({ "slug": tag.to_lowercase().replace(' ', "-"), "name": tag }))
+            .collect();
+        let mut input = json!({
+            "title": article.title,
+            "subtitle": article.description,
+            "contentMarkdown": article.markdown,
+            "originalArticleURL": article.canonical_url,
+            "tags": tags,
+        });
+
+        let (mutation, field) = match existing {
+            Some(existing) => {
+                input["id"] = json!(existing.id);
+                (
+                    "mutation UpdatePost($input: UpdatePostInput!) { updatePost(input: $input) { post { id url } } }",
+                    "updatePost",
+                )
+            }
+            None => {
+                input["publicationId"] = json!(publication_id);
+                (
+                    "mutation PublishPost($input: PublishPostInput!) { publishPost(input: $input) { post { id url } } }",
+                    "publishPost",
+                )
+            }
+        };
+
+        let response = self
+            .client
+            .post("https://gql.hashnode.com")
+            .header(reqwest::header::AUTHORIZATION, token)
+            .json(&json!({ "query": mutation, "variables": { "input": input } }))
+            .send()
+            .await?;
+        let response = json_response(response).await?;
+        if let Some(error) = response["errors"][0]["message"].as_str() {
+            return Err(anyhow!("Hashnode: {}", error));
+        }
+
+        let post = &response["data"][field]["post"];
+        Ok(CrossPost {
+            id: id_string(&post["id"])?,
+            url: post["url"].as_str().map(str::to_string),
+        })
+    }
+
+    async fn publish_medium(&self, token: &str, article: &Article) -> Result<CrossPost> {
+        let me = self
+            .client
+            .get("https://api.medium.com/v1/me")
+            .bearer_auth(token)
+            .send()
+            .await?;
+        let me = json_response(me).await?;
+        let user_id = me["data"]["id"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Medium didn't return a user ID"))?;
+
+        let response = self
+            .client
+            .post(format!("https://api.medium.com/v1/users/{}/posts", user_id))
+            .bearer_auth(token)
+            .json(&json!({
+                "title": article.title,
+                "contentFormat": "markdown",
+                "content": format!("# {}\n\n{}", article.title, article.markdown),
+                "tags": article.tags.iter().take(5).collect::<Vec<_>>(),
+                "canonicalUrl": article.canonical_url,
+                "publishStatus": if article.draft { "draft" } else { "public" },
+            }))
+            .send()
+            .await?;
+        let response = json_response(response).await?;
+
+        let post = &response["data"];
+        Ok(CrossPost {
+            id: id_string(&post["id"])?,
+            url: post["url"].as_str().map(str::to_string),
+        })
+    }
+}
+
+async fn json_response(response: reqwest::Response) -> Result<Value> {
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        let detail = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|body| {
+                ["error", "message", "errors"]
+                    .iter()
+                    .find_map(|key| body.get(*key).map(|value| value.to_string()))
+            })
+            .unwrap_or(text);
+        return Err(anyhow!("{}: {}", status, detail));
+    }
+    serde_json::from_str(&text).map_err(|e| anyhow!("Unexpected response: {}", e))
+}
+
+fn id_string(id: &Value) -> Result<String> {
+    match id {
+        Value::String(id) => Ok(id.clone()),
+        Value::Number(id) => Ok(id.to_string()),
+        _ => Err(anyhow!("Response didn't include the post ID")),
+    }
+}
+
+/// Resolve relative markdown links, images and HTML `src`/`href` attributes
+/// against the post's URL
+pub fn absolutize_links(markdown: &str, page_url: &str) -> String {
+    let Ok(base) = Url::parse(page_url) else {
+        return markdown.to_string();
+    };
+    let resolve = |target: &str| -> Option<String> {
+        if target.is_empty()
+            || target.starts_with('#')
+            || target.contains("://")
+            || target.starts_with("mailto:")
+        {
+            return None;
+        }
+        base.join(target).ok().map(String::from)
+    };
+
+    let mut output = String::with_capacity(markdown.len());
+    let mut in_code_block = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        if in_code_block {
+            output.push_str(line);
+            continue;
+        }
+
+        let mut rest = line;
+        while let Some((index, marker)) = ["](", "src=\"", "href=\""]
+            .iter()
+            .filter_map(|marker| rest.find(marker).map(|index| (index, *marker)))
+            .min_by_key(|(index, _)| *index)
+        {
+            let start = index + marker.len();
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let end = rest
+                .find(|c: char| c == ')' || c == '"' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            match resolve(&rest[..end]) {
+                Some(url) => output.push_str(&url),
+                None => output.push_str(&rest[..end]),
+            }
+            rest = &rest[end..];
+        }
+        output.push_str(rest);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolutize_links() {
+        let markdown = "See [the docs](/docs/) and [above](#intro).\n\
+            ![Diagram](../static/images/d.png \"Title\")\n\
+            <img src=\"/static/a.png\"> [x](https://other.example/)\n\
+            ```\n[not](/a-link)\n```\n";
+
+        assert_eq!(
+            absolutize_links(markdown, "https://blog.example.com/posts/hello.html"),
+            "See [the docs](https://blog.example.com/docs/) and [above](#intro).\n\
+            ![Diagram](https://blog.example.com/static/images/d.png \"Title\")\n\
+            <img src=\"https://blog.example.com/static/a.png\"> [x](https://other.example/)\n\
+            ```\n[not](/a-link)\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_platform_from_str() {
+        assert_eq!("devto".parse::<Platform>().unwrap(), Platform::DevTo);
+        assert_eq!(" Dev.to".parse::<Platform>().unwrap(), Platform::DevTo);
+        assert_eq!("hashnode".parse::<Platform>().unwrap(), Platform::Hashnode);
+        assert!("substack".parse::<Platform>().is_err());
+    }
+}
@@ -0,0 +1,480 @@
+//! Checks that a custom domain is set up for GitHub Pages
+//!
+//! DNS records are looked up over HTTPS (Google's JSON resolver), so the
+//! answer doesn't depend on the local resolver's cache. An apex domain
+//! (`example.com`) should have A records, and ideally AAAA records, for
+//! GitHub's Pages servers; a subdomain (`blog.example.com`) should be a
+//! CNAME for `<username>.github.io`. Once DNS is right, GitHub issues a
+//! certificate, which can take a while, so the HTTPS check can poll.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// GitHub Pages' IPv4 addresses, for apex domains
+pub const PAGES_IPV4: [&str; 4] = [
+    "185.199.108.153",
+    "185.199.109.153",
+    "185.199.110.153",
+    "185.199.111.153",
+];
+
+/// GitHub Pages' IPv6 addresses, for apex domains
+pub const PAGES_IPV6: [&str; 4] = [
+    "2606:50c0:8000::153",
+    "2606:50c0:8001::153",
+    "2606:50c0:8002::153",
+    "2606:50c0:8003::153",
+];
+
+/// Addresses GitHub Pages used before 2018, still found in old setups
+const RETIRED_IPV4: [&str; 2] = ["192.30.252.153", "192.30.252.154"];
+
+const RESOLVER: &str = "https://dns.google/resolve";
+
+/// Timeout for each DNS or HTTPS request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What GitHub serves for a domain it has no site for
+const NO_SITE_HERE: &str = "There isn't a GitHub Pages site here";
+
+const TYPE_A: u16 = 1;
+const TYPE_CNAME: u16 = 5;
+const TYPE_AAAA: u16 = 28;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Ok,
+    Warning,
+    Problem,
+}
+
+/// One result of a check, with what to do about it
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub level: Level,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    fn ok(message: impl Into<String>) -> Self {
+        Self {
+            level: Level::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            level: Level::Warning,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn problem(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            level: Level::Problem,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// The DNS records that matter for a domain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Records {
+    /// Where the name is an alias for, without the trailing dot
+    pub cname: Option<String>,
+    pub a: Vec<String>,
+    pub aaaa: Vec<String>,
+    /// Whether the name doesn't exist at all
+    pub missing: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct DnsResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DnsAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DnsAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+pub fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("blogr-cli")
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// Look up the A, AAAA and CNAME records for `domain`
+pub async fn lookup(client: &reqwest::Client, domain: &str) -> Result<Records> {
+    let ipv4 = query(client, domain, "A").await?;
+    let ipv6 = query(client, domain, "AAAA").await?;
+    let mut records = Records {
+        missing: ipv4.status == 3,
+        ..Records::default()
+    };
+    for answer in ipv4.answer.iter().chain(&ipv6.answer) {
+        let data = answer.data.trim_end_matches('.').to_string();
+        match answer.record_type {
+            // Only the first alias in a chain is the domain's own record
+            TYPE_CNAME if records.cname.is_none() => records.cname = Some(data),
+            TYPE_A if !records.a.contains(&data) => records.a.push(data),
+            TYPE_AAAA if !records.aaaa.contains(&data) => records.aaaa.push(data),
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+async fn query(client: &reqwest::Client, domain: &str, record_type: &str) -> Result<DnsResponse> {
+    let response = client
+        .get(RESOLVER)
+        .query(&[("name", domain), ("type", record_type)])
+        .send()
+        .await
+        .with_context(|| format!("Failed to look up {} records for {}", record_type, domain))?;
+    if !response.status().is_success() {
+        bail!("DNS lookup for {} failed: {}", domain, response.status());
+    }
+    response
+        .json()
+        .await
+        .with_context(|| format!("Unreadable DNS answer for {}", domain))
+}
+
+/// Whether `domain` is an apex domain, which can't have a CNAME record
+pub fn is_apex(domain: &str) -> bool {
+    domain.trim_end_matches('.').split('.').count() <= 2
+}
+
+/// Check `records` for `domain`. `pages_host` is the `<username>.github.io`
+/// a subdomain should point at, when the GitHub username is known.
+pub fn check_records(domain: &str, records: &Records, pages_host: Option<&str>) -> Vec<Finding> {
+    if records.missing {
+        return vec![Finding::problem(
+            format!("{} does not exist in DNS", domain),
+            format!(
+                "Add the records for {} at your DNS provider, then allow up to an hour for them to spread",
+                domain
+            ),
+        )];
+    }
+
+    let mut findings = Vec::new();
+    let retired: Vec<&String> = records
+        .a
+        .iter()
+        .filter(|ip| RETIRED_IPV4.contains(&ip.as_str()))
+        .collect();
+    if !retired.is_empty() {
+        findings.push(Finding::problem(
+            format!(
+                "{} points at GitHub's old addresses ({})",
+                domain,
+                join(&retired)
+            ),
+            format!("Replace them with {}", PAGES_IPV4.join(", ")),
+        ));
+    }
+
+    if is_apex(domain) {
+        check_apex(domain, records, &mut findings);
+    } else {
+        check_subdomain(domain, records, pages_host, &mut findings);
+    }
+    findings
+}
+
+fn check_apex(domain: &str, records: &Records, findings: &mut Vec<Finding>) {
+    if let Some(target) = &records.cname {
+        findings.push(Finding::problem(
+            format!("{} is a CNAME for {}", domain, target),
+            format!(
+                "An apex domain can't be a CNAME: replace it with A records for {}, or use your provider's ALIAS/ANAME record",
+                PAGES_IPV4.join(", ")
+            ),
+        ));
+        return;
+    }
+
+    let (pages, other) = split_records(&records.a, &PAGES_IPV4);
+    if !other.is_empty() {
+        findings.push(Finding::problem(
+            format!(
+                "{} has A records that aren't GitHub Pages: {}",
+                domain,
+                join(&other)
+            ),
+            "Remove them; visitors sent to these addresses won't see your blog",
+        ));
+    }
+    if pages.is_empty() {
+        findings.push(Finding::problem(
+            format!("{} has no A records for GitHub Pages", domain),
+            format!("Add A records for {}", PAGES_IPV4.join(", ")),
+        ));
+    } else if pages.len() < PAGES_IPV4.len() {
+        findings.push(Finding::warning(
+            format!(
+                "{} points at {} of GitHub's {} addresses",
+                domain,
+                pages.len(),
+                PAGES_IPV4.len()
+            ),
+            format!("Add A records for all of {}", PAGES_IPV4.join(", ")),
+        ));
+    } else if other.is_empty() {
+        findings.push(Finding::ok(format!(
+            "{} has A records for GitHub Pages",
+            domain
+        )));
+    }
+
+    let (pages, other) = split_records(&records.aaaa, &PAGES_IPV6);
+    if !other.is_empty() {
+        findings.push(Finding::problem(
+            format!(
+                "{} has AAAA records that aren't GitHub Pages: {}",
+                domain,
+                join(&other)
+            ),
+            "Remove them; visitors on IPv6 won't see your blog",
+        ));
+    } else if pages.is_empty() {
+        findings.push(Finding::warning(
+            format!("{} has no AAAA records", domain),
+            format!(
+                "Optionally add AAAA records for {} to serve visitors over IPv6",
+                PAGES_IPV6.join(", ")
+            ),
+        ));
+    }
+}
+
+fn check_subdomain(
+    domain: &str,
+    records: &Records,
+    pages_host: Option<&str>,
+    findings: &mut Vec<Finding>,
+) {
+    let expected = pages_host.unwrap_or("<username>.github.io");
+    let Some(target) = &records.cname else {
+        let (pages, _) = split_records(&records.a, &PAGES_IPV4);
+        if !pages.is_empty() {
+            findings.push(Finding::warning(
+                format!("{} uses A records instead of a CNAME", domain),
+                format!(
+                    "It works, but a CNAME for {} follows GitHub if its addresses change",
+                    expected
+                ),
+            ));
+        } else {
+            findings.push(Finding::problem(
+                format!("{} has no CNAME record", domain),
+                format!("Add a CNAME record for {} pointing to {}", domain, expected),
+            ));
+        }
+        return;
+    };
+
+    let target = target.to_lowercase();
+    let on_pages = target.ends_with(".github.io") && target.split('.').count() == 3;
+    match pages_host {
+        _ if !on_pages => findings.push(Finding::problem(
+            format!("{} is a CNAME for {}, not GitHub Pages", domain, target),
+            format!("Point the CNAME at {}", expected),
+        )),
+        Some(host) if !target.eq_ignore_ascii_case(host) => findings.push(Finding::problem(
+            format!("{} is a CNAME for {}, not {}", domain, target, host),
+            format!("Point the CNAME at {}", host),
+        )),
+        _ => findings.push(Finding::ok(format!("{} is a CNAME for {}", domain, target))),
+    }
+}
+
+/// Records in `expected`, and the others
+fn split_records<'a>(
+    records: &'a [String],
+    expected: &[&str],
+) -> (Vec<&'a String>, Vec<&'a String>) {
+    records
+        .iter()
+        .partition(|record| expected.contains(&record.as_str()))
+}
+
+fn join(items: &[&String]) -> String {
+    items
+        .iter()
+        .map(|item| item.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpsStatus {
+    /// The site answers over HTTPS with a valid certificate
+    Ready,
+    /// GitHub answers, but hasn't issued a certificate for the domain yet
+    CertificatePending(String),
+    /// GitHub doesn't have a site for the domain
+    NoSite,
+    /// The domain doesn't answer at all
+    Unreachable(String),
+}
+
+/// Whether `domain` is served over HTTPS yet
+pub async fn https_status(client: &reqwest::Client, domain: &str) -> HttpsStatus {
+    match client.get(format!("https://{}/", domain)).send().await {
+        Ok(response) => {
+            let body = response.text().await.unwrap_or_default();
+            if body.contains(NO_SITE_HERE) {
+                HttpsStatus::NoSite
+            } else {
+                HttpsStatus::Ready
+            }
+        }
+        Err(https_error) => {
+            // Before the certificate is issued, GitHub serves the site over
+            // plain HTTP with its own certificate for HTTPS
+            let reason = error_chain(&https_error);
+            match client.get(format!("http://{}/", domain)).send().await {
+                Ok(response) => {
+                    let body = response.text().await.unwrap_or_default();
+                    if body.contains(NO_SITE_HERE) {
+                        HttpsStatus::NoSite
+                    } else {
+                        HttpsStatus::CertificatePending(reason)
+                    }
+                }
+                Err(_) => HttpsStatus::Unreachable(reason),
+            }
+        }
+    }
+}
+
+/// Whether plain HTTP requests for `domain` are redirected to HTTPS
+pub async fn redirects_to_https(domain: &str) -> Result<bool> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let response = client.get(format!("http://{}/", domain)).send().await?;
+    Ok(response.status().is_redirection()
+        && response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .is_some_and(|location| location.starts_with("https://")))
+}
+
+/// An error with its causes, where the TLS detail usually is
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(cname: Option<&str>, a: &[&str], aaaa: &[&str]) -> Records {
+        Records {
+            cname: cname.map(String::from),
+            a: a.iter().map(|s| s.to_string()).collect(),
+            aaaa: aaaa.iter().map(|s| s.to_string()).collect(),
+            missing: false,
+        }
+    }
+
+    fn levels(findings: &[Finding]) -> Vec<Level> {
+        findings.iter().map(|f| f.level).collect()
+    }
+
+    #[test]
+    fn test_apex_records() {
+        let good = records(None, &PAGES_IPV4, &PAGES_IPV6);
+        assert_eq!(
+            levels(&check_records("example.com", &good, None)),
+            [Level::Ok]
+        );
+
+        let partial = records(None, &PAGES_IPV4[..2], &[]);
+        assert_eq!(
+            levels(&check_records("example.com", &partial, None)),
+            [Level::Warning, Level::Warning]
+        );
+
+        let mixed = records(
+            None,
+            &["185.199.108.153", "192.30.252.153", "203.0.113.7"],
+            &[],
+        );
+        let findings = check_records("example.com", &mixed, None);
+        assert_eq!(findings[0].level, Level::Problem);
+        assert!(findings[0].message.contains("old addresses"));
+        assert!(findings[1].message.contains("203.0.113.7"));
+
+        let cname = records(Some("ann.github.io"), &PAGES_IPV4, &[]);
+        let findings = check_records("example.com", &cname, None);
+        assert_eq!(levels(&findings), [Level::Problem]);
+        assert!(findings[0].fix.as_ref().unwrap().contains("ALIAS"));
+    }
+
+    #[test]
+    fn test_subdomain_records() {
+        let check = |records: &Records, host: Option<&str>| {
+            check_records("blog.example.com", records, host)
+        };
+
+        let good = records(Some("ann.github.io"), &PAGES_IPV4, &[]);
+        assert_eq!(levels(&check(&good, Some("ann.github.io"))), [Level::Ok]);
+        assert_eq!(levels(&check(&good, None)), [Level::Ok]);
+        assert_eq!(
+            levels(&check(&good, Some("bob.github.io"))),
+            [Level::Problem]
+        );
+
+        let elsewhere = records(Some("example.netlify.app"), &["203.0.113.7"], &[]);
+        assert_eq!(levels(&check(&elsewhere, None)), [Level::Problem]);
+
+        let a_records = records(None, &PAGES_IPV4, &[]);
+        assert_eq!(levels(&check(&a_records, None)), [Level::Warning]);
+
+        let missing = Records {
+            missing: true,
+            ..Records::default()
+        };
+        let findings = check(&missing, None);
+        assert!(findings[0].message.contains("does not exist"));
+    }
+
+    #[test]
+    fn test_parse_dns_answer() {
+        let response: DnsResponse = serde_json::from_str(
+            r#"{"Status":0,"Answer":[{"name":"blog.example.com.","type":5,"TTL":300,"data":"ann.github.io."},{"name":"ann.github.io.","type":1,"TTL":300,"data":"185.199.108.153"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.status, 0);
+        assert_eq!(response.answer[0].record_type, TYPE_CNAME);
+        assert!(is_apex("example.com"));
+        assert!(!is_apex("blog.example.com"));
+    }
+}
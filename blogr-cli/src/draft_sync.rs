@@ -0,0 +1,519 @@
+//! Draft syncing between machines
+//!
+//! Draft posts are copied to a `drafts` branch on `origin` or to a private
+//! GitHub gist. Each sync compares the local and remote copy of every draft
+//! with the version recorded at the last sync (`.blogr/drafts-sync.json`), so
+//! it can tell which side changed. When both did, the remote version is saved
+//! to `.blogr/drafts-conflicts/` and the draft is left alone until that copy is
+//! deleted.
+
+use crate::commands::deploy::{get_git_signature, remote_callbacks};
+use crate::config::EnvConfig;
+use crate::utils::Utils;
+use anyhow::{anyhow, Context, Result};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Ref holding the last commit made by `blogr drafts sync`
+const LOCAL_REF: &str = "refs/blogr/drafts";
+
+/// Gist file names can't contain `/`, so nested drafts are flattened with this
+const GIST_SEPARATOR: &str = "__";
+
+/// Draft files by path relative to the posts directory
+pub type Drafts = BTreeMap<String, String>;
+
+pub fn content_hash(content: &str) -> String {
+    Utils::hex(&Sha256::digest(content.as_bytes()))
+}
+
+/// What was synced last time, from `.blogr/drafts-sync.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    /// Hash of each draft as of the last sync
+    #[serde(default)]
+    pub synced: BTreeMap<String, String>,
+    /// Drafts with an unresolved conflict
+    #[serde(default)]
+    pub conflicts: BTreeSet<String>,
+}
+
+impl SyncState {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".blogr").join("drafts-sync.json")
+    }
+
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Where the conflicting remote version of a draft is saved
+pub fn conflict_path(project_root: &Path, name: &str) -> PathBuf {
+    project_root
+        .join(".blogr")
+        .join("drafts-conflicts")
+        .join(name)
+}
+
+/// One step of a sync
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    /// Both sides already match
+    InSync,
+    /// Take the remote version
+    Pull(String),
+    /// The draft was removed remotely and is unchanged here
+    RemoveLocal,
+    /// Send the local version
+    Push(String),
+    /// The draft was removed, or published, here
+    RemoveRemote,
+    /// Both sides changed; the remote version is set aside
+    Conflict(String),
+    /// A conflict copy is still waiting to be merged
+    Unresolved,
+}
+
+/// Decide what to do with every draft.
+///
+/// `published` are local posts that are no longer drafts. Conflicts whose copy
+/// has been deleted count as resolved in favour of the local version.
+pub fn plan(
+    local: &Drafts,
+    remote: &Drafts,
+    published: &BTreeSet<String>,
+    state: &SyncState,
+    resolved: &BTreeSet<String>,
+) -> Vec<(String, Action)> {
+    let names: BTreeSet<&String> = local
+        .keys()
+        .chain(remote.keys())
+        .chain(state.synced.keys())
+        .collect();
+
+    let mut actions = Vec::new();
+    for name in names {
+        if state.conflicts.contains(name) && !resolved.contains(name) {
+            actions.push((name.clone(), Action::Unresolved));
+            continue;
+        }
+
+        let ours = local.get(name).map(|content| content_hash(content));
+        let theirs = remote.get(name).map(|content| content_hash(content));
+        let base = if resolved.contains(name) {
+            theirs.as_ref()
+        } else {
+            state.synced.get(name)
+        };
+
+        let action = if ours == theirs {
+            Action::InSync
+        } else if published.contains(name) {
+            // Published here: the draft copy goes, unless it was edited elsewhere
+            match (&theirs, base) {
+                (Some(theirs), Some(base)) if theirs != base => {
+                    Action::Conflict(remote[name].clone())
+                }
+                (Some(_), _) => Action::RemoveRemote,
+                (None, _) => Action::InSync,
+            }
+        } else if ours.as_ref() == base {
+            match remote.get(name) {
+                Some(content) => Action::Pull(content.clone()),
+                None => Action::RemoveLocal,
+            }
+        } else if theirs.as_ref() == base {
+            match local.get(name) {
+                Some(content) => Action::Push(content.clone()),
+                None => Action::RemoveRemote,
+            }
+        } else {
+            match (local.get(name), remote.get(name)) {
+                // Edits win over deletions
+                (None, Some(content)) => Action::Pull(content.clone()),
+                (Some(content), None) => Action::Push(content.clone()),
+                (Some(_), Some(content)) => Action::Conflict(content.clone()),
+                (None, None) => Action::InSync,
+            }
+        };
+        actions.push((name.clone(), action));
+    }
+    actions
+}
+
+/// Remote copy of the drafts
+pub enum DraftStore {
+    /// A branch on `origin`
+    Branch { repo_root: PathBuf, branch: String },
+    /// A private GitHub gist; `None` until the first push creates it
+    Gist { id: Option<String> },
+}
+
+impl DraftStore {
+    pub fn describe(&self) -> String {
+        match self {
+            DraftStore::Branch { branch, .. } => format!("branch '{}' on origin", branch),
+            DraftStore::Gist { id: Some(id) } => format!("gist {}", id),
+            DraftStore::Gist { id: None } => "a new private gist".to_string(),
+        }
+    }
+
+    /// Download every remote draft
+    pub async fn fetch(&self) -> Result<Drafts> {
+        match self {
+            DraftStore::Branch { repo_root, branch } => fetch_branch(repo_root, branch),
+            DraftStore::Gist { id: Some(id) } => fetch_gist(id).await,
+            DraftStore::Gist { id: None } => Ok(Drafts::new()),
+        }
+    }
+
+    /// Apply changes (`None` removes a draft). Returns the new gist ID when
+    /// one was created.
+    pub async fn store(
+        &self,
+        remote: &Drafts,
+        changes: &BTreeMap<String, Option<String>>,
+    ) -> Result<Option<String>> {
+        match self {
+            DraftStore::Branch { repo_root, branch } => {
+                let mut files = remote.clone();
+                for (name, content) in changes {
+                    match content {
+                        Some(content) => files.insert(name.clone(), content.clone()),
+                        None => files.remove(name),
+                    };
+                }
+                push_branch(repo_root, branch, &files)?;
+                Ok(None)
+            }
+            DraftStore::Gist { id } => store_gist(id.as_deref(), changes).await,
+        }
+    }
+}
+
+fn fetch_branch(repo_root: &Path, branch: &str) -> Result<Drafts> {
+    let repo = Repository::discover(repo_root).context("The project is not a git repository")?;
+    let mut remote = repo
+        .find_remote("origin")
+        .context("No 'origin' remote found. Please add a remote first.")?;
+    let remote_url = remote.url().unwrap_or_default().to_string();
+    let remote_ref = format!("refs/heads/{}", branch);
+
+    // Start from a clean slate so a deleted remote branch reads as empty
+    if let Ok(mut reference) = repo.find_reference(LOCAL_REF) {
+        reference.delete()?;
+    }
+    let mut options = git2::FetchOptions::new();
+    options.remote_callbacks(remote_callbacks(&remote_url)?);
+    remote.fetch(
+        &[format!("+{}:{}", remote_ref, LOCAL_REF)],
+        Some(&mut options),
+        None,
+    )?;
+
+    let Ok(reference) = repo.find_reference(LOCAL_REF) else {
+        return Ok(Drafts::new());
+    };
+    let tree = reference.peel_to_tree()?;
+    let mut drafts = Drafts::new();
+    let mut error = None;
+    tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() != Some(git2::ObjectType::Blob) {
+            return git2::TreeWalkResult::Ok;
+        }
+        let name = format!("{}{}", dir, entry.name().unwrap_or_default());
+        match entry
+            .to_object(&repo)
+            .and_then(|object| object.peel_to_blob())
+        {
+            Ok(blob) => {
+                drafts.insert(name, String::from_utf8_lossy(blob.content()).into_owned());
+                git2::TreeWalkResult::Ok
+            }
+            Err(e) => {
+                error = Some(e);
+                git2::TreeWalkResult::Abort
+            }
+        }
+    })?;
+    if let Some(e) = error {
+        return Err(e.into());
+    }
+    Ok(drafts)
+}
+
+fn push_branch(repo_root: &Path, branch: &str, files: &Drafts) -> Result<()> {
+    let repo = Repository::discover(repo_root)?;
+
+    let mut index = git2::Index::new()?;
+    for (name, content) in files {
+        let entry = git2::IndexEntry {
+            ctime: git2::IndexTime::new(0, 0),
+            mtime: git2::IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100644,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: repo.blob(content.as_bytes())?,
+            flags: name.len().min(0xfff) as u16,
+            flags_extended: 0,
+            path: name.as_bytes().to_vec(),
+        };
+        index.add(&entry)?;
+    }
+    let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+
+    // The previous sync's commit, fetched just before, is the parent
+    let parent = repo
+        .find_reference(LOCAL_REF)
+        .ok()
+        .and_then(|reference| reference.peel_to_commit().ok());
+    let signature = get_git_signature()?;
+    let commit = repo.commit(
+        None,
+        &signature,
+        &signature,
+        "Sync drafts",
+        &tree,
+        &parent.iter().collect::<Vec<_>>(),
+    )?;
+    repo.reference(LOCAL_REF, commit, true, "blogr drafts sync")?;
+
+    let mut remote = repo.find_remote("origin")?;
+    let remote_url = remote.url().unwrap_or_default().to_string();
+    let mut callbacks = remote_callbacks(&remote_url)?;
+    let rejected = std::rc::Rc::new(std::cell::RefCell::new(None));
+    let rejected_in_callback = rejected.clone();
+    callbacks.push_update_reference(move |_refname, status| {
+        if let Some(status) = status {
+            *rejected_in_callback.borrow_mut() = Some(status.to_string());
+        }
+        Ok(())
+    });
+    let mut options = git2::PushOptions::new();
+    options.remote_callbacks(callbacks);
+
+    // Not forced: if another machine pushed since the fetch, this fails
+    remote.push(
+        &[format!("{}:refs/heads/{}", LOCAL_REF, branch)],
+        Some(&mut options),
+    )?;
+    if let Some(status) = rejected.borrow_mut().take() {
+        return Err(anyhow!(
+            "origin rejected the drafts branch ({}). Run 'blogr drafts sync' again",
+            status
+        ));
+    }
+    Ok(())
+}
+
+fn github_client() -> Result<(reqwest::Client, String)> {
+    let token = EnvConfig::github_token().ok_or_else(|| {
+        anyhow!("GitHub token not found. Set GITHUB_TOKEN (with the 'gist' scope)")
+    })?;
+    let client = reqwest::Client::builder().user_agent("blogr-cli").build()?;
+    Ok((client, token))
+}
+
+fn gist_file_name(name: &str) -> String {
+    name.replace('/', GIST_SEPARATOR)
+}
+
+async fn fetch_gist(id: &str) -> Result<Drafts> {
+    let (client, token) = github_client()?;
+    let response = client
+        .get(format!("https://api.github.com/gists/{}", id))
+        .bearer_auth(&token)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("GitHub API error ({status}): {body}");
+    }
+    let gist: Value = response.json().await?;
+
+    let mut drafts = Drafts::new();
+    for (file_name, file) in gist["files"].as_object().into_iter().flatten() {
+        let content = if file["truncated"].as_bool() == Some(true) {
+            let raw_url = file["raw_url"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Gist file {} has no raw URL", file_name))?;
+            client
+                .get(raw_url)
+                .bearer_auth(&token)
+                .send()
+                .await?
+                .text()
+                .await?
+        } else {
+            file["content"].as_str().unwrap_or_default().to_string()
+        };
+        drafts.insert(file_name.replace(GIST_SEPARATOR, "/"), content);
+    }
+    Ok(drafts)
+}
+
+async fn store_gist(
+    id: Option<&str>,
+    changes: &BTreeMap<String, Option<String>>,
+) -> Result<Option<String>> {
+    let (client, token) = github_client()?;
+
+    let mut files = Map::new();
+    for (name, content) in changes {
+        let file = match content {
+            Some(content) => json!({ "content": content }),
+            None => Value::Null,
+        };
+        files.insert(gist_file_name(name), file);
+    }
+
+    let request = match id {
+        Some(id) => client
+            .patch(format!("https://api.github.com/gists/{}", id))
+            .json(&json!({ "files": files })),
+        None => client.post("https://api.github.com/gists").json(&json!({
+            "description": "blogr drafts",
+            "public": false,
+            "files": files,
+        })),
+    };
+    let response = request
+        .bearer_auth(&token)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("GitHub API error ({status}): {body}");
+    }
+
+    if id.is_some() {
+        return Ok(None);
+    }
+    let gist: Value = response.json().await?;
+    Ok(gist["id"].as_str().map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drafts(entries: &[(&str, &str)]) -> Drafts {
+        entries
+            .iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut state = SyncState::default();
+        for name in ["same", "ours", "theirs", "both", "gone-here", "gone-there"] {
+            state
+                .synced
+                .insert(format!("{}.md", name), content_hash("base"));
+        }
+
+        let local = drafts(&[
+            ("same.md", "base"),
+            ("ours.md", "edited here"),
+            ("theirs.md", "base"),
+            ("both.md", "edited here"),
+            ("gone-there.md", "base"),
+            ("new-here.md", "new"),
+        ]);
+        let remote = drafts(&[
+            ("same.md", "base"),
+            ("ours.md", "base"),
+            ("theirs.md", "edited there"),
+            ("both.md", "edited there"),
+            ("gone-here.md", "base"),
+            ("new-there.md", "new"),
+        ]);
+
+        let actions: BTreeMap<String, Action> =
+            plan(&local, &remote, &BTreeSet::new(), &state, &BTreeSet::new())
+                .into_iter()
+                .collect();
+
+        assert_eq!(actions["same.md"], Action::InSync);
+        assert_eq!(actions["ours.md"], Action::Push("edited here".to_string()));
+        assert_eq!(
+            actions["theirs.md"],
+            Action::Pull("edited there".to_string())
+        );
+        assert_eq!(
+            actions["both.md"],
+            Action::Conflict("edited there".to_string())
+        );
+        assert_eq!(actions["gone-here.md"], Action::RemoveRemote);
+        assert_eq!(actions["gone-there.md"], Action::RemoveLocal);
+        assert_eq!(actions["new-here.md"], Action::Push("new".to_string()));
+        assert_eq!(actions["new-there.md"], Action::Pull("new".to_string()));
+    }
+
+    #[test]
+    fn test_plan_conflict_resolution() {
+        let mut state = SyncState::default();
+        state
+            .synced
+            .insert("post.md".to_string(), content_hash("base"));
+        state.conflicts.insert("post.md".to_string());
+        let local = drafts(&[("post.md", "merged")]);
+        let remote = drafts(&[("post.md", "edited there")]);
+
+        // The conflict copy still exists
+        assert_eq!(
+            plan(&local, &remote, &BTreeSet::new(), &state, &BTreeSet::new()),
+            vec![("post.md".to_string(), Action::Unresolved)]
+        );
+
+        // The conflict copy was deleted after merging
+        let resolved = BTreeSet::from(["post.md".to_string()]);
+        assert_eq!(
+            plan(&local, &remote, &BTreeSet::new(), &state, &resolved),
+            vec![("post.md".to_string(), Action::Push("merged".to_string()))]
+        );
+
+        // Published here in the meantime
+        state.conflicts.clear();
+        let published = BTreeSet::from(["post.md".to_string()]);
+        assert_eq!(
+            plan(
+                &Drafts::new(),
+                &drafts(&[("post.md", "base")]),
+                &published,
+                &state,
+                &BTreeSet::new()
+            ),
+            vec![("post.md".to_string(), Action::RemoveRemote)]
+        );
+    }
+}
@@ -0,0 +1,358 @@
+//! Self-hosted web fonts from `[privacy.self_host_fonts]`
+//!
+//! Builds download the Google Fonts stylesheets the theme's templates link to,
+//! along with the font files they use, into `.blogr/fonts/`. Stylesheets are
+//! saved with their `url()`s pointing at the downloaded files, and font URLs
+//! are versioned, so a download is kept for good. The site builder then
+//! copies everything to `fonts/`, points the `<link>` tags at the local
+//! copies and drops the preconnect hints, so readers' browsers never contact
+//! Google.
+
+use crate::config::Config;
+use crate::generator::output;
+use crate::project::Project;
+use crate::utils::{Console, Utils};
+use crate::webmention::{html_tags, parse_attributes};
+use anyhow::{anyhow, bail, Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STYLESHEET_HOST: &str = "fonts.googleapis.com";
+const FONT_HOST: &str = "fonts.gstatic.com";
+
+/// Google serves WOFF2 only to browsers it knows support it
+const BROWSER_USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+
+const INDEX_FILE: &str = "index.json";
+
+/// The downloaded stylesheets, ready to be published
+#[derive(Debug, Default)]
+pub struct SelfHostedFonts {
+    dir: PathBuf,
+    /// Local stylesheet file by the URL it replaces
+    stylesheets: BTreeMap<String, String>,
+}
+
+impl SelfHostedFonts {
+    fn dir(project_root: &Path) -> PathBuf {
+        project_root.join(".blogr").join("fonts")
+    }
+
+    /// The fonts downloaded for this project, or none when self-hosting is off
+    pub fn load(project_root: &Path, config: &Config) -> Self {
+        if !config.privacy.self_host_fonts.enabled {
+            return Self::default();
+        }
+        let dir = Self::dir(project_root);
+        let stylesheets = fs::read_to_string(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|index| serde_json::from_str(&index).ok())
+            .unwrap_or_default();
+        Self { dir, stylesheets }
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(INDEX_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&self.stylesheets)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Copy the stylesheets and font files to `fonts/` in the output
+    pub fn copy_to(&self, output_dir: &Path) -> Result<()> {
+        if self.stylesheets.is_empty() {
+            return Ok(());
+        }
+        let target = output_dir.join("fonts");
+        output::create_dir_all(&target)?;
+        let mut files = 0;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            if name == INDEX_FILE || !path.is_file() {
+                continue;
+            }
+            output::copy(&path, target.join(name))
+                .map_err(|e| anyhow!("Failed to copy font {}: {}", path.display(), e))?;
+            if path.extension().is_none_or(|ext| ext != "css") {
+                files += 1;
+            }
+        }
+        crate::status!("🔤 Self-hosting {} font file(s)", files);
+        Ok(())
+    }
+
+    /// Point the Google Fonts `<link>` tags in a page at the local copies and
+    /// remove the preconnect hints for Google's hosts. `url` turns a path
+    /// under `fonts/` into a link.
+    pub fn rewrite(&self, html: &str, url: impl Fn(&str) -> String) -> String {
+        if self.stylesheets.is_empty() {
+            return html.to_string();
+        }
+        let mut result = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find("<link") {
+            let (attributes, after) = parse_attributes(&rest[start + 5..]);
+            let tag = &rest[start..rest.len() - after.len()];
+            let href = attributes
+                .get("href")
+                .map(String::as_str)
+                .unwrap_or_default();
+            let rel = attributes
+                .get("rel")
+                .map(String::as_str)
+                .unwrap_or_default();
+            let before = &rest[..start];
+
+            if is_google_fonts(href)
+                && rel
+                    .split_whitespace()
+                    .any(|rel| matches!(rel, "preconnect" | "dns-prefetch"))
+            {
+                // Drop the tag along with its line
+                let trimmed = before.trim_end_matches([' ', '\t']);
+                if trimmed.is_empty() || trimmed.ends_with('\n') {
+                    result.push_str(trimmed);
+                    rest = after.strip_prefix('\n').unwrap_or(after);
+                } else {
+                    result.push_str(before);
+                    rest = after;
+                }
+                continue;
+            }
+
+            result.push_str(before);
+            match self.stylesheets.get(href) {
+                Some(local) => {
+                    let local = url(&format!("fonts/{}", local));
+                    let escaped = href.replace('&', "&amp;");
+                    result.push_str(&tag.replace(&escaped, &local).replace(href, &local));
+                }
+                None => result.push_str(tag),
+            }
+            rest = after;
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+fn host(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .or_else(|| url.strip_prefix("//"))?;
+    rest.split(['/', '?']).next()
+}
+
+fn is_google_fonts(url: &str) -> bool {
+    matches!(host(url), Some(STYLESHEET_HOST | FONT_HOST))
+}
+
+/// The Google Fonts stylesheets linked from templates
+fn stylesheet_urls<'a>(templates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut urls = Vec::new();
+    for template in templates {
+        for tag in html_tags(template, &["link"]) {
+            let stylesheet = tag
+                .attributes
+                .get("rel")
+                .is_some_and(|rel| rel.split_whitespace().any(|rel| rel == "stylesheet"));
+            if let Some(href) = tag.attributes.get("href").filter(|_| stylesheet) {
+                if host(href) == Some(STYLESHEET_HOST) && !urls.contains(href) {
+                    urls.push(href.clone());
+                }
+            }
+        }
+    }
+    urls
+}
+
+/// The `url()`s in a stylesheet
+fn css_urls(css: &str) -> Vec<&str> {
+    let mut urls = Vec::new();
+    let mut rest = css;
+    while let Some(start) = rest.find("url(") {
+        rest = &rest[start + 4..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let url = rest[..end].trim().trim_matches(['"', '\'']);
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+        rest = &rest[end..];
+    }
+    urls
+}
+
+/// File name for a downloaded font: the family directory Google uses, then
+/// the file's own name, e.g. `inter-UcC73FwrK3iLTeHuS_nVMrMxCp50SjIa1ZL7.woff2`
+fn font_file_name(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let mut segments = path.trim_end_matches('/').rsplit('/');
+    let file = segments.next().filter(|file| file.contains('.'))?;
+    let family = path
+        .split("/s/")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .filter(|family| *family != file);
+    let name = match family {
+        Some(family) => format!("{}-{}", family, file),
+        None => file.to_string(),
+    };
+    let safe = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    safe.then_some(name)
+}
+
+/// Local name for a stylesheet, from a hash of its URL
+fn stylesheet_file_name(url: &str) -> String {
+    let hash = Utils::hex(&Sha256::digest(url.as_bytes())[..6]);
+    format!("{}.css", hash)
+}
+
+/// Download the fonts the theme uses unless they already are. A failed
+/// download is reported but never fails a build; pages then keep linking to
+/// Google.
+pub async fn refresh(project: &Project, config: &Config) {
+    if let Err(e) = try_refresh(project, config).await {
+        Console::warn(&format!("Could not download fonts to self-host: {:#}", e));
+    }
+}
+
+async fn try_refresh(project: &Project, config: &Config) -> Result<()> {
+    let mut fonts = SelfHostedFonts::load(&project.root, config);
+    if !config.privacy.self_host_fonts.enabled {
+        return Ok(());
+    }
+    let theme = project
+        .load_theme(&config.theme.name)?
+        .ok_or_else(|| anyhow!("Theme '{}' not found", config.theme.name))?;
+    let templates: Vec<_> = theme.templates().into_iter().collect();
+    let missing: Vec<String> = stylesheet_urls(templates.iter().map(|(_, source)| source.as_ref()))
+        .into_iter()
+        .filter(|url| !fonts.stylesheets.contains_key(url))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent(BROWSER_USER_AGENT)
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+    fs::create_dir_all(&fonts.dir)?;
+    let mut downloaded = 0;
+    for url in missing {
+        let mut css = fetch(&client, &url)
+            .await
+            .and_then(|bytes| String::from_utf8(bytes).context("The stylesheet isn't UTF-8"))
+            .with_context(|| format!("Failed to download {}", url))?;
+
+        for font_url in css_urls(&css.clone()) {
+            if host(font_url) != Some(FONT_HOST) {
+                continue;
+            }
+            let name = font_file_name(font_url)
+                .ok_or_else(|| anyhow!("Unexpected font URL {}", font_url))?;
+            let path = fonts.dir.join(&name);
+            if !path.exists() {
+                let bytes = fetch(&client, font_url)
+                    .await
+                    .with_context(|| format!("Failed to download {}", font_url))?;
+                fs::write(&path, bytes)?;
+                downloaded += 1;
+            }
+            // The stylesheet is published next to the fonts
+            css = css.replace(font_url, &name);
+        }
+
+        let file = stylesheet_file_name(&url);
+        fs::write(fonts.dir.join(&file), css)?;
+        fonts.stylesheets.insert(url, file);
+        fonts.save()?;
+    }
+    crate::status!("🔤 Downloaded {} font file(s) to self-host", downloaded);
+    Ok(())
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let url = if url.starts_with("//") {
+        format!("https:{}", url)
+    } else {
+        url.to_string()
+    };
+    let response = client.get(&url).send().await?;
+    if !response.status().is_success() {
+        bail!("HTTP {}", response.status());
+    }
+    Ok(response.bytes().await?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLESHEET: &str =
+        "https://fonts.googleapis.com/css2?family=Inter:wght@400;700&display=swap";
+
+    #[test]
+    fn test_stylesheet_and_font_urls() {
+        let template = format!(
+            r#"<link rel="preconnect" href="https://fonts.googleapis.com">
+<link href="{}" rel="stylesheet">
+<link rel="stylesheet" href="{{{{ base_url }}}}/css/style.css">"#,
+            STYLESHEET.replace('&', "&amp;")
+        );
+        assert_eq!(stylesheet_urls([template.as_str()]), [STYLESHEET]);
+
+        let css = "@font-face { src: url(https://fonts.gstatic.com/s/inter/v20/abc-1.woff2) format('woff2'); }\n\
+                   @font-face { src: url('https://fonts.gstatic.com/s/inter/v20/def.woff2'); }";
+        let urls = css_urls(css);
+        assert_eq!(
+            urls,
+            [
+                "https://fonts.gstatic.com/s/inter/v20/abc-1.woff2",
+                "https://fonts.gstatic.com/s/inter/v20/def.woff2"
+            ]
+        );
+        assert_eq!(
+            font_file_name(urls[0]).as_deref(),
+            Some("inter-abc-1.woff2")
+        );
+        assert_eq!(font_file_name("https://fonts.gstatic.com/s/"), None);
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let fonts = SelfHostedFonts {
+            dir: PathBuf::new(),
+            stylesheets: BTreeMap::from([(STYLESHEET.to_string(), "0a1b2c.css".to_string())]),
+        };
+        let html = format!(
+            "<head>\n    <link rel=\"preconnect\" href=\"https://fonts.googleapis.com\">\n    \
+             <link rel=\"preconnect\" href=\"https://fonts.gstatic.com\" crossorigin>\n    \
+             <link href=\"{}\" rel=\"stylesheet\">\n    \
+             <link rel=\"stylesheet\" href=\"/css/style.css\">\n</head>",
+            STYLESHEET
+        );
+        assert_eq!(
+            fonts.rewrite(&html, |path| format!("/{}", path)),
+            "<head>\n    <link href=\"/fonts/0a1b2c.css\" rel=\"stylesheet\">\n    \
+             <link rel=\"stylesheet\" href=\"/css/style.css\">\n</head>"
+        );
+
+        // Without downloads pages are left alone
+        assert_eq!(
+            SelfHostedFonts::default().rewrite(&html, |path| path.to_string()),
+            html
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Static ActivityPub documents
+//!
+//! Writes the WebFinger response, actor, outbox and one Note per post so the
+//! blog can be looked up and followed as `@<username>@<domain>`. Delivering
+//! new posts to followers is done by `blogr activitypub announce`.
+
+use crate::activitypub::{ActorKey, Followers};
+use crate::config::{Config, DeploymentType};
+use crate::content::Post;
+use crate::generator::markdown::html_escape;
+use crate::generator::output;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+
+const CONTEXT: [&str; 2] = [
+    "https://www.w3.org/ns/activitystreams",
+    "https://w3id.org/security/v1",
+];
+const PUBLIC: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+/// Posts listed in the outbox
+const OUTBOX_LIMIT: usize = 20;
+
+/// Content types for hosts that read a `_headers` file (Netlify, Cloudflare Pages)
+const HEADERS: &str = "/.well-known/webfinger
+  Content-Type: application/jrd+json
+  Access-Control-Allow-Origin: *
+/activitypub/*
+  Content-Type: application/activity+json
+  Access-Control-Allow-Origin: *
+";
+
+pub fn actor_id(config: &Config) -> String {
+    format!("{}/activitypub/actor.json", config.get_effective_base_url())
+}
+
+/// ID of the key requests are signed with
+pub fn key_id(config: &Config) -> String {
+    format!("{}#main-key", actor_id(config))
+}
+
+/// The `@username@domain` people search for
+pub fn handle(config: &Config) -> String {
+    let base_url = config.get_effective_base_url();
+    let host = url::Url::parse(&base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or(base_url);
+    format!("@{}@{}", config.activitypub.username, host)
+}
+
+fn note_id(config: &Config, slug: &str) -> String {
+    format!(
+        "{}/activitypub/posts/{}.json",
+        config.get_effective_base_url(),
+        slug
+    )
+}
+
+/// A post as a Note linking to the full article
+pub fn note(config: &Config, post: &Post) -> Value {
+    let metadata = &post.metadata;
+    let url = config.page_url(&format!("posts/{}.html", metadata.slug));
+
+    let mut content = format!(
+        r#"<p><a href="{}">{}</a></p>"#,
+        html_escape(&url),
+        html_escape(&post.display_title())
+    );
+    if !metadata.description.trim().is_empty() {
+        content.push_str(&format!("<p>{}</p>", html_escape(&metadata.description)));
+    }
+
+    let tags: Vec<Value> = metadata
+        .tags
+        .iter()
+        .map(|tag| {
+            json!({
+                "type": "Hashtag",
+                "href": config.page_url(&format!("tags/{}.html", tag)),
+                "name": format!("#{}", tag.replace(['-', ' '], "_")),
+            })
+        })
+        .collect();
+    if !tags.is_empty() {
+        let hashtags: Vec<String> = tags
+            .iter()
+            .filter_map(|tag| {
+                Some(format!(
+                    r#"<a href="{}" class="mention hashtag" rel="tag">{}</a>"#,
+                    html_escape(tag["href"].as_str()?),
+                    html_escape(tag["name"].as_str()?)
+                ))
+            })
+            .collect();
+        content.push_str(&format!("<p>{}</p>", hashtags.join(" ")));
+    }
+
+    let mut note = json!({
+        "id": note_id(config, &metadata.slug),
+        "type": "Note",
+        "attributedTo": actor_id(config),
+        "url": url,
+        "published": metadata.date.to_rfc3339(),
+        "to": [PUBLIC],
+        "cc": [format!("{}/activitypub/followers.json", config.get_effective_base_url())],
+        "content": content,
+        "tag": tags,
+    });
+    if let Some(updated) = metadata.updated_at {
+        note["updated"] = json!(updated.to_rfc3339());
+    }
+    note
+}
+
+/// The Create activity announcing a post
+pub fn create_activity(config: &Config, post: &Post) -> Value {
+    let mut note = note(config, post);
+    json!({
+        "@context": CONTEXT,
+        "id": format!("{}#create", note["id"].as_str().unwrap_or_default()),
+        "type": "Create",
+        "actor": actor_id(config),
+        "published": note["published"].clone(),
+        "to": note["to"].clone(),
+        "cc": note["cc"].clone(),
+        "object": note.take(),
+    })
+}
+
+fn actor(config: &Config, public_key_pem: &str) -> Value {
+    let base_url = config.get_effective_base_url();
+    let id = actor_id(config);
+    let inbox = config
+        .activitypub
+        .inbox_url
+        .clone()
+        .unwrap_or_else(|| format!("{}/activitypub/inbox", base_url));
+
+    let mut actor = json!({
+        "@context": CONTEXT,
+        "id": id,
+        "type": "Person",
+        "preferredUsername": config.activitypub.username,
+        "name": config.blog.title,
+        "summary": format!("<p>{}</p>", html_escape(&config.blog.description)),
+        "url": format!("{}/", base_url),
+        "inbox": inbox,
+        "outbox": format!("{}/activitypub/outbox.json", base_url),
+        "followers": format!("{}/activitypub/followers.json", base_url),
+        "manuallyApprovesFollowers": false,
+        "discoverable": true,
+        "publicKey": {
+            "id": key_id(config),
+            "owner": id,
+            "publicKeyPem": public_key_pem,
+        },
+    });
+    if let Some(avatar) = &config.activitypub.avatar {
+        actor["icon"] = json!({ "type": "Image", "url": avatar });
+    }
+    actor
+}
+
+fn webfinger(config: &Config) -> Value {
+    let handle = handle(config);
+    json!({
+        "subject": format!("acct:{}", handle.trim_start_matches('@')),
+        "aliases": [actor_id(config)],
+        "links": [
+            {
+                "rel": "self",
+                "type": "application/activity+json",
+                "href": actor_id(config),
+            },
+            {
+                "rel": "http://webfinger.net/rel/profile-page",
+                "type": "text/html",
+                "href": format!("{}/", config.get_effective_base_url()),
+            },
+        ],
+    })
+}
+
+/// Write the ActivityPub documents for a blog build
+pub fn generate(
+    project_root: &Path,
+    config: &Config,
+    posts: &[Post],
+    output_dir: &Path,
+) -> Result<()> {
+    if config.get_deployment_type() == DeploymentType::GitHubPagesSubpath {
+        crate::utils::Console::warn(
+            "ActivityPub needs the site at the root of its domain; WebFinger lookups for a subpath site will fail",
+        );
+    }
+
+    let key = ActorKey::load_or_create(project_root)?;
+    let base_url = config.get_effective_base_url();
+    let ap_dir = output_dir.join("activitypub");
+    output::create_dir_all(ap_dir.join("posts"))?;
+    output::create_dir_all(output_dir.join(".well-known"))?;
+
+    let write = |path: &Path, value: &Value| -> Result<()> {
+        output::write(path, serde_json::to_string_pretty(value)?)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+    };
+
+    write(
+        &output_dir.join(".well-known/webfinger"),
+        &webfinger(config),
+    )?;
+    write(
+        &ap_dir.join("actor.json"),
+        &actor(config, &key.public_key_pem()?),
+    )?;
+
+    for post in posts {
+        let mut note = note(config, post);
+        note["@context"] = json!(CONTEXT);
+        write(
+            &ap_dir
+                .join("posts")
+                .join(format!("{}.json", post.metadata.slug)),
+            &note,
+        )?;
+    }
+
+    let items: Vec<Value> = posts
+        .iter()
+        .take(OUTBOX_LIMIT)
+        .map(|post| {
+            let mut activity = create_activity(config, post);
+            activity.as_object_mut().map(|a| a.remove("@context"));
+            activity
+        })
+        .collect();
+    write(
+        &ap_dir.join("outbox.json"),
+        &json!({
+            "@context": CONTEXT[0],
+            "id": format!("{}/activitypub/outbox.json", base_url),
+            "type": "OrderedCollection",
+            "totalItems": posts.len(),
+            "orderedItems": items,
+        }),
+    )?;
+
+    // Followers are kept private; only the count is published
+    write(
+        &ap_dir.join("followers.json"),
+        &json!({
+            "@context": CONTEXT[0],
+            "id": format!("{}/activitypub/followers.json", base_url),
+            "type": "OrderedCollection",
+            "totalItems": Followers::load(project_root)?.len(),
+        }),
+    )?;
+
+    // GitHub Pages runs Jekyll unless told not to, and Jekyll drops `.well-known`
+    output::write(output_dir.join(".nojekyll"), "")?;
+
+    let headers_path = output_dir.join("_headers");
+    let mut headers = output::read_to_string(&headers_path).unwrap_or_default();
+    if !headers.is_empty() && !headers.ends_with('\n') {
+        headers.push('\n');
+    }
+    headers.push_str(HEADERS);
+    output::write(&headers_path, headers)?;
+
+    crate::status!("🐘 Generated ActivityPub actor {}", handle(config));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{PostMetadata, PostStatus};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_create_activity() {
+        let mut config = Config::default();
+        config.blog.base_url = "https://blog.example.com".to_string();
+
+        let post = Post {
+            metadata: PostMetadata {
+                title: "Hello & welcome".to_string(),
+                date: Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap(),
+                author: "Me".to_string(),
+                description: String::new(),
+                tags: vec!["open-source".to_string()],
+                status: PostStatus::Published,
+                slug: "hello".to_string(),
+                featured: false,
+                aliases: Vec::new(),
+                updated_at: None,
+                contributors: Vec::new(),
+                crosspost: Default::default(),
+                video: None,
+                link: None,
+                cover: None,
+                cover_alt: None,
+                noindex: false,
+                private: false,
+                password: None,
+            },
+            content: String::new(),
+            file_path: std::path::PathBuf::new(),
+        };
+
+        assert_eq!(handle(&config), "@blog@blog.example.com");
+
+        let activity = create_activity(&config, &post);
+        assert_eq!(
+            activity["id"],
+            "https://blog.example.com/activitypub/posts/hello.json#create"
+        );
+        assert_eq!(
+            activity["actor"],
+            "https://blog.example.com/activitypub/actor.json"
+        );
+        assert_eq!(
+            activity["object"]["content"],
+            concat!(
+                r#"<p><a href="https://blog.example.com/posts/hello.html">Hello &amp; welcome</a></p>"#,
+                r#"<p><a href="https://blog.example.com/tags/open-source.html" class="mention hashtag" rel="tag">#open_source</a></p>"#
+            )
+        );
+        assert_eq!(activity["object"]["tag"][0]["name"], "#open_source");
+    }
+}
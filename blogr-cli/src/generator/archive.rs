@@ -0,0 +1,169 @@
+//! Archive grouping
+//!
+//! Posts are grouped by year and month, newest first, for the archive page
+//! and the year (`/archive/2024/`) and month (`/archive/2024/05/`) archives.
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// The posts of one year
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveYear<T> {
+    pub year: i32,
+    /// Page path of the year's archive, e.g. `archive/2024.html`
+    pub path: String,
+    /// Posts of the year, newest first
+    pub posts: Vec<T>,
+    /// Months with posts, newest first
+    pub months: Vec<ArchiveMonth<T>>,
+}
+
+/// The posts of one month
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveMonth<T> {
+    pub year: i32,
+    /// 1 to 12
+    pub month: u32,
+    /// English month name, e.g. "May"
+    pub name: String,
+    /// Page path of the month's archive, e.g. `archive/2024/05.html`
+    pub path: String,
+    pub posts: Vec<T>,
+}
+
+/// The year or month a sub-archive page covers
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivePeriod {
+    pub year: i32,
+    pub month: Option<u32>,
+    /// "2024" or "May 2024"
+    pub title: String,
+}
+
+impl<T: Clone> ArchiveYear<T> {
+    /// The year with only one of its months
+    pub fn only_month(&self, month: &ArchiveMonth<T>) -> Self {
+        Self {
+            year: self.year,
+            path: self.path.clone(),
+            posts: month.posts.clone(),
+            months: vec![month.clone()],
+        }
+    }
+}
+
+impl<T> ArchiveYear<T> {
+    pub fn period(&self) -> ArchivePeriod {
+        ArchivePeriod {
+            year: self.year,
+            month: None,
+            title: self.year.to_string(),
+        }
+    }
+}
+
+impl<T> ArchiveMonth<T> {
+    pub fn period(&self) -> ArchivePeriod {
+        ArchivePeriod {
+            year: self.year,
+            month: Some(self.month),
+            title: format!("{} {}", self.name, self.year),
+        }
+    }
+}
+
+pub fn year_path(year: i32) -> String {
+    format!("archive/{}.html", year)
+}
+
+pub fn month_path(year: i32, month: u32) -> String {
+    format!("archive/{}/{:02}.html", year, month)
+}
+
+/// Group items by the year and month of their date, newest first.
+/// Items with the same date keep their order.
+pub fn group_by_month<T: Clone>(items: &[(DateTime<Utc>, T)]) -> Vec<ArchiveYear<T>> {
+    let mut sorted: Vec<&(DateTime<Utc>, T)> = items.iter().collect();
+    sorted.sort_by_key(|(date, _)| Reverse(*date));
+
+    let mut years: Vec<ArchiveYear<T>> = Vec::new();
+    for (date, item) in sorted {
+        let (year, month) = (date.year(), date.month());
+        if years.last().is_none_or(|last| last.year != year) {
+            years.push(ArchiveYear {
+                year,
+                path: year_path(year),
+                posts: Vec::new(),
+                months: Vec::new(),
+            });
+        }
+        let current = years.last_mut().expect("a year was just added");
+        current.posts.push(item.clone());
+
+        if current.months.last().is_none_or(|last| last.month != month) {
+            current.months.push(ArchiveMonth {
+                year,
+                month,
+                name: MONTH_NAMES[month as usize - 1].to_string(),
+                path: month_path(year, month),
+                posts: Vec::new(),
+            });
+        }
+        current
+            .months
+            .last_mut()
+            .expect("a month was just added")
+            .posts
+            .push(item.clone());
+    }
+    years
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_group_by_month() {
+        let date = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap();
+        let items = [
+            (date(2023, 12, 31), "eve"),
+            (date(2024, 5, 2), "may-2"),
+            (date(2024, 1, 9), "jan"),
+            (date(2024, 5, 20), "may-20"),
+        ];
+
+        let years = group_by_month(&items);
+        assert_eq!(
+            years.iter().map(|y| y.year).collect::<Vec<_>>(),
+            [2024, 2023]
+        );
+        assert_eq!(years[0].posts, ["may-20", "may-2", "jan"]);
+        assert_eq!(years[0].path, "archive/2024.html");
+
+        let may = &years[0].months[0];
+        assert_eq!((may.month, may.name.as_str()), (5, "May"));
+        assert_eq!(may.path, "archive/2024/05.html");
+        assert_eq!(may.posts, ["may-20", "may-2"]);
+        assert_eq!(may.period().title, "May 2024");
+        assert_eq!(years[0].months[1].posts, ["jan"]);
+        assert_eq!(years[0].only_month(may).posts.len(), 2);
+    }
+}
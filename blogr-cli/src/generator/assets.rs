@@ -1,11 +1,16 @@
+use crate::generator::output;
 use anyhow::Result;
-use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 
 /// Copy a directory recursively
 pub fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
-    fs::create_dir_all(dst)?;
+    copy_dir_filtered(src, dst, |_| true)
+}
+
+/// Copy a directory recursively, leaving out files for which `keep` is false
+pub fn copy_dir_filtered(src: &Path, dst: &Path, keep: impl Fn(&Path) -> bool) -> Result<()> {
+    output::create_dir_all(dst)?;
 
     for entry in WalkDir::new(src) {
         let entry = entry?;
@@ -19,12 +24,12 @@ pub fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
         let dst_path = dst.join(relative_path);
 
         if entry.file_type().is_dir() {
-            fs::create_dir_all(&dst_path)?;
-        } else {
+            output::create_dir_all(&dst_path)?;
+        } else if keep(path) {
             if let Some(parent) = dst_path.parent() {
-                fs::create_dir_all(parent)?;
+                output::create_dir_all(parent)?;
             }
-            fs::copy(path, &dst_path)?;
+            output::copy(path, &dst_path)?;
         }
     }
 
@@ -65,9 +70,11 @@ pub fn get_mime_type(path: &Path) -> &'static str {
         Some("css") => "text/css",
         Some("js") => "application/javascript",
         Some("json") => "application/json",
+        Some("xml") => "application/xml",
         Some("png") => "image/png",
         Some("jpg") | Some("jpeg") => "image/jpeg",
         Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
         Some("svg") => "image/svg+xml",
         Some("ico") => "image/x-icon",
         Some("woff") => "font/woff",
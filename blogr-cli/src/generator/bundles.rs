@@ -0,0 +1,104 @@
+//! Page bundles
+//!
+//! A post can be a folder, `posts/my-post/index.md`, with its images and other
+//! files next to it. Those files are published at `posts/<slug>/` and relative
+//! links to them are pointed there, so they work on the post page as well as in
+//! listings and feeds.
+
+use crate::generator::assets::copy_dir_filtered;
+use anyhow::{anyhow, Result};
+use std::path::{Component, Path};
+use walkdir::WalkDir;
+
+/// Folder of a bundle's files in the output, relative to the site root
+pub fn bundle_output_dir(slug: &str) -> String {
+    format!("posts/{}", slug)
+}
+
+/// Paths of a bundle's files other than markdown, relative to its folder and sorted
+pub fn bundle_files(dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| entry.path().extension().is_none_or(|ext| ext != "md"))
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(dir).ok()?;
+            Some(relative.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Copy a bundle's files, except markdown, to `posts/<slug>/` in `output_dir`
+pub fn copy_bundle(dir: &Path, output_dir: &Path, slug: &str) -> Result<()> {
+    copy_dir_filtered(dir, &output_dir.join(bundle_output_dir(slug)), |path| {
+        path.extension().is_none_or(|ext| ext != "md")
+    })
+    .map_err(|e| anyhow!("Failed to copy the files of post '{}': {}", slug, e))
+}
+
+/// Site path of the bundle file a relative link points at, e.g.
+/// `posts/my-post/photo.jpg` for `photo.jpg`; None for any other link
+pub fn bundle_link(dir: &Path, slug: &str, dest: &str) -> Option<String> {
+    if dest.is_empty() || dest.starts_with(['/', '#', '?']) || url::Url::parse(dest).is_ok() {
+        return None;
+    }
+
+    let split = dest.find(['?', '#']).unwrap_or(dest.len());
+    let (path, suffix) = dest.split_at(split);
+    let path = path.strip_prefix("./").unwrap_or(path);
+    let file = urlencoding::decode(path).ok()?;
+    let file = Path::new(file.as_ref());
+    let inside = file
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    let is_markdown = file.extension().is_some_and(|ext| ext == "md");
+    if !inside || is_markdown || !dir.join(file).is_file() {
+        return None;
+    }
+
+    Some(format!("{}/{}{}", bundle_output_dir(slug), path, suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bundle_link() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("images")).unwrap();
+        fs::write(dir.path().join("index.md"), "---\n---\n").unwrap();
+        fs::write(dir.path().join("my photo.jpg"), "jpeg").unwrap();
+        fs::write(dir.path().join("images/chart.png"), "png").unwrap();
+
+        let link = |dest| bundle_link(dir.path(), "trip", dest);
+        assert_eq!(
+            link("my%20photo.jpg").as_deref(),
+            Some("posts/trip/my%20photo.jpg")
+        );
+        assert_eq!(
+            link("./images/chart.png#big").as_deref(),
+            Some("posts/trip/images/chart.png#big")
+        );
+        assert_eq!(link("missing.png"), None);
+        assert_eq!(link("index.md"), None);
+        assert_eq!(link("../trip/images/chart.png"), None);
+        assert_eq!(link("/images/chart.png"), None);
+        assert_eq!(link("https://example.com/chart.png"), None);
+
+        assert_eq!(
+            bundle_files(dir.path()),
+            ["images/chart.png", "my photo.jpg"]
+        );
+
+        let output = TempDir::new().unwrap();
+        copy_bundle(dir.path(), output.path(), "trip").unwrap();
+        assert!(output.path().join("posts/trip/images/chart.png").exists());
+        assert!(!output.path().join("posts/trip/index.md").exists());
+    }
+}
@@ -0,0 +1,153 @@
+//! Contact form from `[contact]`
+//!
+//! Static sites can't receive messages, so the form posts to a backend:
+//! Formspree, StaticForms, or the `/contact` endpoint of
+//! `blogr newsletter api-server`. Each backend names its honeypot and its
+//! redirect field differently; after sending, readers land on
+//! `contact/thanks.html`.
+
+use crate::config::ContactConfig;
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+
+/// The page readers are sent to after a message goes through
+pub const THANKS_PAGE: &str = "contact/thanks.html";
+
+pub const BACKENDS: &[&str] = &["formspree", "staticforms", "newsletter"];
+
+/// What `contact_form.html` needs to post to the configured backend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContactForm {
+    pub backend: String,
+    /// URL the form posts to
+    pub action: String,
+    /// Name of the field people never see; bots fill it in
+    pub honeypot: &'static str,
+    /// Settings the backend reads from hidden fields
+    pub hidden: Vec<HiddenField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HiddenField {
+    pub name: &'static str,
+    pub value: String,
+}
+
+impl ContactForm {
+    /// The form for `config`, redirecting to `thanks_url`
+    pub fn new(config: &ContactConfig, thanks_url: &str) -> Result<Self> {
+        let required = |value: &Option<String>, name: &str| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "contact.{} is needed for the {} backend",
+                        name,
+                        config.backend
+                    )
+                })
+        };
+        let field = |name: &'static str, value: &str| HiddenField {
+            name,
+            value: value.to_string(),
+        };
+        let subject = config
+            .subject
+            .as_deref()
+            .unwrap_or("New message from your site");
+
+        let (action, honeypot, hidden) = match config.backend.as_str() {
+            "formspree" => {
+                let form_id = required(&config.form_id, "form_id")?;
+                if !form_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    bail!("contact.form_id '{}' is not a Formspree form ID", form_id);
+                }
+                (
+                    format!("https://formspree.io/f/{}", form_id),
+                    "_gotcha",
+                    vec![field("_subject", subject), field("_next", thanks_url)],
+                )
+            }
+            "staticforms" => (
+                "https://api.staticforms.xyz/submit".to_string(),
+                "honeypot",
+                vec![
+                    field("accessKey", &required(&config.access_key, "access_key")?),
+                    field("subject", subject),
+                    field("redirectTo", thanks_url),
+                ],
+            ),
+            "newsletter" => {
+                let endpoint = required(&config.endpoint, "endpoint")?;
+                if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+                    bail!("contact.endpoint must be an http(s) URL");
+                }
+                (
+                    format!("{}/contact", endpoint.trim_end_matches('/')),
+                    "website",
+                    vec![field("subject", subject), field("redirect", thanks_url)],
+                )
+            }
+            other => bail!(
+                "Unknown contact.backend '{}' (expected {})",
+                other,
+                BACKENDS.join(", ")
+            ),
+        };
+        Ok(Self {
+            backend: config.backend.clone(),
+            action,
+            honeypot,
+            hidden,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(backend: &str) -> ContactConfig {
+        ContactConfig {
+            enabled: true,
+            backend: backend.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backends() {
+        let thanks = "https://example.com/contact/thanks.html";
+
+        let mut formspree = config("formspree");
+        assert!(ContactForm::new(&formspree, thanks)
+            .unwrap_err()
+            .to_string()
+            .contains("contact.form_id"));
+        formspree.form_id = Some("xyzabcd".to_string());
+        let form = ContactForm::new(&formspree, thanks).unwrap();
+        assert_eq!(form.action, "https://formspree.io/f/xyzabcd");
+        assert_eq!(form.honeypot, "_gotcha");
+        assert!(form.hidden.contains(&HiddenField {
+            name: "_next",
+            value: thanks.to_string()
+        }));
+
+        let mut staticforms = config("staticforms");
+        staticforms.access_key = Some("key-123".to_string());
+        let form = ContactForm::new(&staticforms, thanks).unwrap();
+        assert_eq!(form.hidden[0].value, "key-123");
+        assert_eq!(form.hidden[2].name, "redirectTo");
+
+        let mut newsletter = config("newsletter");
+        newsletter.endpoint = Some("https://api.example.com/".to_string());
+        let form = ContactForm::new(&newsletter, thanks).unwrap();
+        assert_eq!(form.action, "https://api.example.com/contact");
+        assert_eq!(form.honeypot, "website");
+
+        assert!(ContactForm::new(&config("mailchimp"), thanks).is_err());
+    }
+}
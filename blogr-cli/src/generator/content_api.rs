@@ -0,0 +1,235 @@
+//! Static JSON API from `[build.api]`
+//!
+//! Next to the `api/posts-page-N.json` pages the home page scrolls through,
+//! builds can write the whole blog as JSON for apps that show it themselves:
+//! `api/site.json`, `api/posts.json` with every post, `api/posts/<slug>.json`
+//! with a post's rendered content, and `api/tags.json`. Every file carries
+//! `schema_version`; fields are only added within a version, never renamed or
+//! removed.
+
+use crate::generator::output;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Version of the schemas below
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Path of a post's document, relative to the output directory
+pub fn post_path(slug: &str) -> String {
+    format!("api/posts/{}.json", slug)
+}
+
+/// `api/site.json`
+#[derive(Debug, Serialize)]
+pub struct SiteDocument {
+    pub schema_version: u32,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub language: String,
+    pub base_url: String,
+    pub post_count: usize,
+    pub tag_count: usize,
+    pub feeds: Feeds,
+    pub endpoints: Endpoints,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Feeds {
+    pub rss: String,
+    pub atom: String,
+}
+
+/// URLs of the other documents; `post` has `{slug}` in place of the slug
+#[derive(Debug, Serialize)]
+pub struct Endpoints {
+    pub posts: String,
+    pub post: String,
+    pub tags: String,
+}
+
+/// A post as listed in `api/posts.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PostSummary {
+    pub slug: String,
+    pub title: String,
+    /// RFC 3339
+    pub date: String,
+    pub updated: Option<String>,
+    pub author: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub featured: bool,
+    pub reading_time: usize,
+    pub word_count: usize,
+    pub cover: Option<String>,
+    /// The post's page
+    pub url: String,
+    /// The post's document
+    pub json_url: String,
+}
+
+/// `api/posts/<slug>.json`
+#[derive(Debug, Serialize)]
+pub struct PostDocument {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub post: PostSummary,
+    pub summary_html: String,
+    pub content_html: String,
+    /// Slug of the next newer post
+    pub newer: Option<String>,
+    /// Slug of the next older post
+    pub older: Option<String>,
+}
+
+/// A tag in `api/tags.json`
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Tag {
+    pub name: String,
+    pub count: usize,
+    pub url: String,
+    /// Slugs of the tag's posts, newest first
+    pub posts: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PostsDocument<'a> {
+    schema_version: u32,
+    posts: Vec<&'a PostSummary>,
+}
+
+#[derive(Serialize)]
+struct TagsDocument<'a> {
+    schema_version: u32,
+    tags: &'a [Tag],
+}
+
+/// Tags of `posts`, sorted by name, each with the URL of its page
+pub fn tags(posts: &[PostSummary], url: impl Fn(&str) -> String) -> Vec<Tag> {
+    let mut slugs: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for post in posts {
+        for tag in &post.tags {
+            slugs.entry(tag).or_default().push(post.slug.clone());
+        }
+    }
+    slugs
+        .into_iter()
+        .map(|(name, posts)| Tag {
+            name: name.to_string(),
+            count: posts.len(),
+            url: url(name),
+            posts,
+        })
+        .collect()
+}
+
+/// Write the documents under `output_dir/api`
+pub fn write(
+    output_dir: &Path,
+    site: &SiteDocument,
+    posts: &[PostDocument],
+    tags: &[Tag],
+) -> Result<()> {
+    let posts_dir = output_dir.join("api/posts");
+    output::create_dir_all(&posts_dir)?;
+
+    write_json(&output_dir.join("api/site.json"), site)?;
+    write_json(
+        &output_dir.join("api/posts.json"),
+        &PostsDocument {
+            schema_version: SCHEMA_VERSION,
+            posts: posts.iter().map(|document| &document.post).collect(),
+        },
+    )?;
+    for document in posts {
+        write_json(&output_dir.join(post_path(&document.post.slug)), document)?;
+    }
+    write_json(
+        &output_dir.join("api/tags.json"),
+        &TagsDocument {
+            schema_version: SCHEMA_VERSION,
+            tags,
+        },
+    )?;
+
+    crate::status!(
+        "🧩 Generated JSON API for {} posts and {} tags",
+        posts.len(),
+        tags.len()
+    );
+    Ok(())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)?;
+    output::write(path, json).map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(slug: &str, tags: &[&str]) -> PostSummary {
+        PostSummary {
+            slug: slug.to_string(),
+            title: slug.to_string(),
+            date: "2025-01-01T00:00:00+00:00".to_string(),
+            updated: None,
+            author: "Ann".to_string(),
+            description: String::new(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            featured: false,
+            reading_time: 1,
+            word_count: 10,
+            cover: None,
+            url: format!("https://example.com/posts/{}.html", slug),
+            json_url: format!("https://example.com/{}", post_path(slug)),
+        }
+    }
+
+    #[test]
+    fn test_tags() {
+        let posts = [
+            summary("newest", &["rust", "web"]),
+            summary("older", &["rust"]),
+        ];
+        let tags = tags(&posts, |tag| format!("/tags/{}.html", tag));
+        assert_eq!(
+            tags,
+            [
+                Tag {
+                    name: "rust".to_string(),
+                    count: 2,
+                    url: "/tags/rust.html".to_string(),
+                    posts: vec!["newest".to_string(), "older".to_string()],
+                },
+                Tag {
+                    name: "web".to_string(),
+                    count: 1,
+                    url: "/tags/web.html".to_string(),
+                    posts: vec!["newest".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_post_document_is_flat() {
+        let document = PostDocument {
+            schema_version: SCHEMA_VERSION,
+            post: summary("hello", &[]),
+            summary_html: String::new(),
+            content_html: "<p>Hi</p>".to_string(),
+            newer: None,
+            older: Some("earlier".to_string()),
+        };
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(json["slug"], "hello");
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["older"], "earlier");
+        assert!(json["newer"].is_null());
+    }
+}
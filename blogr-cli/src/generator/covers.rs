@@ -0,0 +1,320 @@
+//! Cover images
+//!
+//! A post's `cover` is a file in the project such as `static/images/harbor.jpg`,
+//! a file in its bundle such as `harbor.jpg`, or a full URL. Local covers are
+//! published with JPEG copies at each of `COVER_WIDTHS` narrower than the
+//! original, so themes can offer a `srcset`. Templates get the result as
+//! `cover`, feeds carry it as Media RSS, and post pages get Open Graph and
+//! Twitter image tags when their theme doesn't add its own.
+
+use crate::content::Post;
+use crate::generator::bundles::bundle_output_dir;
+use crate::generator::gallery::image_size;
+use crate::generator::markdown::html_escape;
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+
+/// Output folder of the resized copies
+pub const COVERS_DIR: &str = "covers";
+
+/// Widths of the resized copies, in pixels
+pub const COVER_WIDTHS: &[u32] = &[480, 960, 1600];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Cover {
+    /// Site path of the image, or its URL when it's remote
+    pub src: String,
+    pub alt: String,
+    /// Size of a local image as displayed, after EXIF rotation
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Resized copies, narrowest first
+    pub sizes: Vec<CoverSize>,
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverSize {
+    /// Site path of the copy, e.g. `covers/my-post-480.jpg`
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Cover {
+    /// The cover from a post's `cover` and `cover_alt`, if it has one
+    pub fn resolve(post: &Post, project_root: &Path) -> Result<Option<Self>> {
+        let metadata = &post.metadata;
+        let Some(cover) = metadata
+            .cover
+            .as_deref()
+            .map(str::trim)
+            .filter(|cover| !cover.is_empty())
+        else {
+            return Ok(None);
+        };
+        let alt = metadata.cover_alt.clone().unwrap_or_default();
+
+        if cover.starts_with("//") || url::Url::parse(cover).is_ok() {
+            return Ok(Some(Self {
+                src: cover.to_string(),
+                alt,
+                width: None,
+                height: None,
+                sizes: Vec::new(),
+                source: None,
+            }));
+        }
+
+        let relative = cover.strip_prefix("./").unwrap_or(cover);
+        let in_bundle = post
+            .bundle_dir()
+            .map(|dir| dir.join(relative))
+            .filter(|file| !cover.starts_with('/') && file.is_file());
+        let (src, source) = match in_bundle {
+            Some(file) => (
+                format!("{}/{}", bundle_output_dir(&metadata.slug), relative),
+                file,
+            ),
+            None => {
+                let path = cover.trim_start_matches('/');
+                let inside = Path::new(path)
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)));
+                let file = project_root.join(path);
+                if !inside || !file.is_file() {
+                    bail!(
+                        "The cover of post '{}' was not found: {}",
+                        metadata.slug,
+                        cover
+                    );
+                }
+                (path.to_string(), file)
+            }
+        };
+
+        let (width, height) = image_size(&source)?;
+        let sizes = COVER_WIDTHS
+            .iter()
+            .filter(|size| **size < width)
+            .map(|size| CoverSize {
+                path: format!("{}/{}-{}.jpg", COVERS_DIR, metadata.slug, size),
+                width: *size,
+                height: ((u64::from(height) * u64::from(*size) + u64::from(width) / 2)
+                    / u64::from(width))
+                .max(1) as u32,
+            })
+            .collect();
+
+        Ok(Some(Self {
+            src,
+            alt,
+            width: Some(width),
+            height: Some(height),
+            sizes,
+            source: Some(source),
+        }))
+    }
+
+    /// Whether the cover is an image on another site
+    pub fn is_remote(&self) -> bool {
+        self.source.is_none()
+    }
+
+    /// Site path of the narrowest copy, or of the original if there's none
+    pub fn thumbnail(&self) -> &str {
+        self.sizes.first().map_or(&self.src, |size| &size.path)
+    }
+
+    /// Link to one of the cover's paths, with `url` for local ones
+    fn link(&self, path: &str, url: &impl Fn(&str) -> String) -> String {
+        if self.is_remote() {
+            path.to_string()
+        } else {
+            url(path)
+        }
+    }
+
+    /// The `cover` template variable, with links made by `url`
+    pub fn context(&self, url: impl Fn(&str) -> String) -> Value {
+        let mut srcset: Vec<String> = self
+            .sizes
+            .iter()
+            .map(|size| format!("{} {}w", url(&size.path), size.width))
+            .collect();
+        if let Some(width) = self.width.filter(|_| !srcset.is_empty()) {
+            srcset.push(format!("{} {}w", self.link(&self.src, &url), width));
+        }
+
+        json!({
+            "src": self.src,
+            "url": self.link(&self.src, &url),
+            "alt": self.alt,
+            "width": self.width,
+            "height": self.height,
+            "sizes": self.sizes,
+            "srcset": srcset.join(", "),
+            "thumbnail": self.link(self.thumbnail(), &url),
+        })
+    }
+
+    /// `<media:content>` and `<media:thumbnail>` for an RSS item. `url`
+    /// must make absolute links.
+    pub fn media_rss(&self, url: impl Fn(&str) -> String) -> String {
+        let size = |width: Option<u32>, height: Option<u32>| match (width, height) {
+            (Some(width), Some(height)) => format!(r#" width="{}" height="{}""#, width, height),
+            _ => String::new(),
+        };
+        let description = if self.alt.trim().is_empty() {
+            String::new()
+        } else {
+            format!(
+                "\n        <media:description type=\"plain\">{}</media:description>",
+                html_escape(self.alt.trim())
+            )
+        };
+        let thumbnail = match self.sizes.first() {
+            Some(copy) => (url(&copy.path), size(Some(copy.width), Some(copy.height))),
+            None => (self.link(&self.src, &url), size(self.width, self.height)),
+        };
+        format!(
+            "\n      <media:content url=\"{}\" medium=\"image\"{}>{}\n      </media:content>\n      <media:thumbnail url=\"{}\"{}/>",
+            html_escape(&self.link(&self.src, &url)),
+            size(self.width, self.height),
+            description,
+            html_escape(&thumbnail.0),
+            thumbnail.1,
+        )
+    }
+
+    /// Open Graph and Twitter card tags for a post page. `url` must make
+    /// absolute links.
+    pub fn meta_tags(&self, url: impl Fn(&str) -> String) -> String {
+        let image = html_escape(&self.link(&self.src, &url));
+        let mut tags = vec![format!(r#"<meta property="og:image" content="{}">"#, image)];
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            tags.push(format!(
+                r#"<meta property="og:image:width" content="{}">"#,
+                width
+            ));
+            tags.push(format!(
+                r#"<meta property="og:image:height" content="{}">"#,
+                height
+            ));
+        }
+        if !self.alt.trim().is_empty() {
+            tags.push(format!(
+                r#"<meta property="og:image:alt" content="{}">"#,
+                html_escape(self.alt.trim())
+            ));
+        }
+        tags.push(r#"<meta name="twitter:card" content="summary_large_image">"#.to_string());
+        tags.push(format!(
+            r#"<meta name="twitter:image" content="{}">"#,
+            image
+        ));
+        tags.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::PostStatus;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn post(cover: &str, file_path: PathBuf) -> Post {
+        let mut post = Post::new(
+            "Harbor".to_string(),
+            "Author".to_string(),
+            None,
+            Vec::new(),
+            Some("harbor".to_string()),
+            PostStatus::Published,
+        );
+        post.metadata.cover = Some(cover.to_string());
+        post.metadata.cover_alt = Some("Boats at dusk".to_string());
+        post.file_path = file_path;
+        post
+    }
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        RgbImage::from_pixel(width, height, Rgb([20, 60, 120]))
+            .save_with_format(path, ImageFormat::Png)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_resolve_covers() {
+        let root = TempDir::new().unwrap();
+        write_png(&root.path().join("static/images/wide.png"), 1000, 500);
+        write_png(&root.path().join("posts/harbor/cover.png"), 300, 200);
+        let url = |path: &str| format!("https://example.com/{}", path);
+
+        let cover = Cover::resolve(
+            &post(
+                "/static/images/wide.png",
+                root.path().join("posts/harbor.md"),
+            ),
+            root.path(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(cover.src, "static/images/wide.png");
+        assert_eq!((cover.width, cover.height), (Some(1000), Some(500)));
+        let widths: Vec<(u32, u32)> = cover.sizes.iter().map(|s| (s.width, s.height)).collect();
+        assert_eq!(widths, vec![(480, 240), (960, 480)]);
+        assert_eq!(cover.thumbnail(), "covers/harbor-480.jpg");
+        let context = cover.context(url);
+        assert_eq!(
+            context["srcset"],
+            "https://example.com/covers/harbor-480.jpg 480w, \
+             https://example.com/covers/harbor-960.jpg 960w, \
+             https://example.com/static/images/wide.png 1000w"
+        );
+        let media = cover.media_rss(url);
+        assert!(media.contains(r#"<media:content url="https://example.com/static/images/wide.png" medium="image" width="1000" height="500">"#), "{}", media);
+        assert!(
+            media.contains("<media:description type=\"plain\">Boats at dusk</media:description>")
+        );
+        assert!(cover
+            .meta_tags(url)
+            .contains(r#"<meta property="og:image:alt" content="Boats at dusk">"#));
+
+        let bundled = Cover::resolve(
+            &post("cover.png", root.path().join("posts/harbor/index.md")),
+            root.path(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(bundled.src, "posts/harbor/cover.png");
+        assert!(bundled.sizes.is_empty());
+        assert_eq!(bundled.context(url)["srcset"], "");
+
+        let remote = Cover::resolve(
+            &post(
+                "https://cdn.example.net/a.jpg",
+                root.path().join("posts/harbor.md"),
+            ),
+            root.path(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(remote.is_remote());
+        assert_eq!(remote.context(url)["url"], "https://cdn.example.net/a.jpg");
+
+        let error = Cover::resolve(
+            &post("../secret.png", root.path().join("posts/harbor.md")),
+            root.path(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("post 'harbor' was not found"), "{}", error);
+    }
+}
@@ -0,0 +1,167 @@
+//! Which pages of a blog each post appears on
+//!
+//! A blog build records, for every post file, the slug, tags and archive
+//! month it was built with. `blogr serve` keeps the graph of the last build,
+//! and when only posts change it renders just their pages and the listings
+//! they appear on, before or after the change: their tags and archive months.
+//! The home page, archive, feeds, sitemap and search index list every post
+//! and are always written again.
+
+use crate::content::Post;
+use chrono::Datelike;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+/// What each post file contributed to a build
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    posts: HashMap<PathBuf, PostNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PostNode {
+    slug: String,
+    tags: Vec<String>,
+    /// Year and month of the post's archive page
+    month: (i32, u32),
+}
+
+/// The pages to write again after some posts changed
+#[derive(Debug, Default, PartialEq)]
+pub struct Rebuild {
+    /// Slugs of the posts to render
+    pub posts: BTreeSet<String>,
+    /// Slugs of posts that are gone, or were renamed
+    pub removed: BTreeSet<String>,
+    /// Tags whose pages list a changed post
+    pub tags: BTreeSet<String>,
+    /// Archive months, as (year, month), that list a changed post
+    pub months: BTreeSet<(i32, u32)>,
+}
+
+impl DependencyGraph {
+    /// The graph of a build of `posts`
+    pub fn new(posts: &[Post]) -> Self {
+        let posts = posts
+            .iter()
+            .map(|post| {
+                let date = post.metadata.date;
+                let node = PostNode {
+                    slug: post.metadata.slug.clone(),
+                    tags: post.metadata.tags.clone(),
+                    month: (date.year(), date.month()),
+                };
+                (post.file_path.clone(), node)
+            })
+            .collect();
+        Self { posts }
+    }
+
+    /// What to rebuild to go from this build to `next`, after the post files
+    /// in `changed` were edited, added or removed
+    pub fn plan(&self, next: &DependencyGraph, changed: &[PathBuf]) -> Rebuild {
+        let mut rebuild = Rebuild::default();
+        for path in changed {
+            let before = self.posts.get(path);
+            let after = next.posts.get(path);
+            for node in before.iter().chain(after.iter()) {
+                rebuild.tags.extend(node.tags.iter().cloned());
+                rebuild.months.insert(node.month);
+            }
+            if let Some(after) = after {
+                rebuild.posts.insert(after.slug.clone());
+            }
+            if let Some(before) = before {
+                if after.is_none_or(|after| after.slug != before.slug) {
+                    rebuild.removed.insert(before.slug.clone());
+                }
+            }
+        }
+        // Another post may have taken over the slug
+        rebuild
+            .removed
+            .retain(|slug| !next.posts.values().any(|node| &node.slug == slug));
+        rebuild
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.posts
+            .values()
+            .any(|node| node.tags.iter().any(|t| t == tag))
+    }
+
+    pub fn has_month(&self, year: i32, month: u32) -> bool {
+        self.posts.values().any(|node| node.month == (year, month))
+    }
+
+    pub fn has_year(&self, year: i32) -> bool {
+        self.posts.values().any(|node| node.month.0 == year)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::PostStatus;
+    use chrono::{TimeZone, Utc};
+
+    fn post(file: &str, slug: &str, tags: &[&str], month: u32) -> Post {
+        let mut post = Post::new(
+            slug.to_string(),
+            "Author".to_string(),
+            None,
+            tags.iter().map(|tag| tag.to_string()).collect(),
+            Some(slug.to_string()),
+            PostStatus::Published,
+        );
+        post.metadata.date = Utc.with_ymd_and_hms(2025, month, 1, 0, 0, 0).unwrap();
+        post.file_path = PathBuf::from(file);
+        post
+    }
+
+    #[test]
+    fn test_plan() {
+        let before = DependencyGraph::new(&[
+            post("posts/a.md", "a", &["rust"], 1),
+            post("posts/b.md", "b", &["rust", "web"], 2),
+            post("posts/c.md", "c", &["life"], 3),
+        ]);
+
+        // b is retagged and moved to March, c is renamed, d is new
+        let after = DependencyGraph::new(&[
+            post("posts/a.md", "a", &["rust"], 1),
+            post("posts/b.md", "b", &["web", "css"], 3),
+            post("posts/c.md", "c-renamed", &["life"], 3),
+            post("posts/d.md", "d", &["news"], 4),
+        ]);
+        let changed = [
+            PathBuf::from("posts/b.md"),
+            PathBuf::from("posts/c.md"),
+            PathBuf::from("posts/d.md"),
+        ];
+        let plan = before.plan(&after, &changed);
+        assert_eq!(
+            plan.posts.into_iter().collect::<Vec<_>>(),
+            ["b", "c-renamed", "d"]
+        );
+        assert_eq!(plan.removed.into_iter().collect::<Vec<_>>(), ["c"]);
+        assert_eq!(
+            plan.tags.into_iter().collect::<Vec<_>>(),
+            ["css", "life", "news", "rust", "web"]
+        );
+        assert_eq!(
+            plan.months.into_iter().collect::<Vec<_>>(),
+            [(2025, 2), (2025, 3), (2025, 4)]
+        );
+        assert!(!after.has_month(2025, 2));
+        assert!(after.has_tag("css"));
+
+        // Deleting a post removes its page and updates its listings
+        let gone = DependencyGraph::new(&[post("posts/b.md", "b", &["web", "css"], 3)]);
+        let plan = after.plan(&gone, &[PathBuf::from("posts/a.md")]);
+        assert!(plan.posts.is_empty());
+        assert_eq!(plan.removed.into_iter().collect::<Vec<_>>(), ["a"]);
+        assert!(!gone.has_tag("rust") && !gone.has_month(2025, 1));
+        assert!(gone.has_year(2025));
+    }
+}
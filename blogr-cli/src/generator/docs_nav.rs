@@ -0,0 +1,262 @@
+//! Sidebar navigation for documentation themes
+//!
+//! The tree comes from `[[docs.nav]]`, or from the pages in `content/` in
+//! their `order` when it isn't configured. Each page gets the tree marked
+//! with where it is (`active` on its entry, `open` on the groups above it),
+//! the previous and next pages in reading order, its breadcrumbs, and the
+//! versions for the version selector, as `docs` in the template context.
+
+use crate::config::{DocsConfig, DocsNavItem};
+use crate::generator::pages::Page;
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// An entry in the sidebar. `path` is a page's output path or a link, for
+/// the `url()` template function; groups without a page have none.
+#[derive(Debug, Clone, Serialize)]
+pub struct NavNode {
+    pub title: String,
+    pub path: Option<String>,
+    /// Slug of the page this entry is for, if any
+    pub slug: Option<String>,
+    pub children: Vec<NavNode>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Link {
+    title: String,
+    path: String,
+}
+
+/// The resolved sidebar for a site
+#[derive(Debug, Clone)]
+pub struct DocsNav {
+    nodes: Vec<NavNode>,
+    /// Pages in reading order, for previous and next links
+    reading_order: Vec<Link>,
+    slugs: Vec<String>,
+    config: DocsConfig,
+}
+
+impl DocsNav {
+    pub fn new(config: &DocsConfig, pages: &[Page]) -> Result<Self> {
+        let nodes: Vec<NavNode> = if config.nav.is_empty() {
+            pages
+                .iter()
+                .filter(|page| page.nav)
+                .map(|page| NavNode {
+                    title: page.title.clone(),
+                    path: Some(page.output_path()),
+                    slug: Some(page.slug.clone()),
+                    children: Vec::new(),
+                })
+                .collect()
+        } else {
+            config
+                .nav
+                .iter()
+                .map(|item| resolve(item, pages))
+                .collect::<Result<_>>()?
+        };
+
+        let mut reading_order = Vec::new();
+        let mut slugs = Vec::new();
+        walk(&nodes, &mut |node| {
+            if let (Some(slug), Some(path)) = (&node.slug, &node.path) {
+                if !slugs.contains(slug) {
+                    slugs.push(slug.clone());
+                    reading_order.push(Link {
+                        title: node.title.clone(),
+                        path: path.clone(),
+                    });
+                }
+            }
+        });
+
+        Ok(Self {
+            nodes,
+            reading_order,
+            slugs,
+            config: config.clone(),
+        })
+    }
+
+    /// The `docs` context for the page with this slug; `""` is the home page
+    pub fn context(&self, current: &str) -> Value {
+        let position = self.slugs.iter().position(|slug| slug == current);
+        let (prev, next) = match position {
+            Some(index) => (
+                index.checked_sub(1).map(|i| &self.reading_order[i]),
+                self.reading_order.get(index + 1),
+            ),
+            // The home page comes before everything in the tree
+            None if current.is_empty() => (None, self.reading_order.first()),
+            None => (None, None),
+        };
+
+        let mut breadcrumbs = Vec::new();
+        let nav: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|node| mark(node, current, &mut breadcrumbs).0)
+            .collect();
+
+        json!({
+            "nav": nav,
+            "prev": prev,
+            "next": next,
+            "breadcrumbs": breadcrumbs,
+            "version": self.config.version,
+            "versions": self.config.versions,
+        })
+    }
+}
+
+fn resolve(item: &DocsNavItem, pages: &[Page]) -> Result<NavNode> {
+    let children = item
+        .children
+        .iter()
+        .map(|child| resolve(child, pages))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(slug) = &item.page {
+        let slug = slug.trim_matches('/');
+        let Some(page) = pages.iter().find(|page| page.slug == slug) else {
+            bail!(
+                "[[docs.nav]] lists page '{}', but there is no content/{}.md",
+                slug,
+                slug
+            );
+        };
+        return Ok(NavNode {
+            title: item.title.clone().unwrap_or_else(|| page.title.clone()),
+            path: Some(page.output_path()),
+            slug: Some(page.slug.clone()),
+            children,
+        });
+    }
+
+    let Some(title) = item.title.clone() else {
+        bail!("Every [[docs.nav]] entry needs a `page` or a `title`");
+    };
+    if item.url.is_none() && children.is_empty() {
+        bail!(
+            "[[docs.nav]] entry '{}' needs a `page`, a `url` or `children`",
+            title
+        );
+    }
+    Ok(NavNode {
+        title,
+        path: item.url.clone(),
+        slug: None,
+        children,
+    })
+}
+
+fn walk(nodes: &[NavNode], visit: &mut impl FnMut(&NavNode)) {
+    for node in nodes {
+        visit(node);
+        walk(&node.children, visit);
+    }
+}
+
+/// The node as JSON with `active` and `open` set, and whether it contains
+/// the current page. Breadcrumbs collect from the current page up.
+fn mark(node: &NavNode, current: &str, breadcrumbs: &mut Vec<Value>) -> (Value, bool) {
+    let active = !current.is_empty() && node.slug.as_deref() == Some(current);
+    let mut open = false;
+    let children: Vec<Value> = node
+        .children
+        .iter()
+        .map(|child| {
+            let (child, contains) = mark(child, current, breadcrumbs);
+            open |= contains;
+            child
+        })
+        .collect();
+
+    if active || open {
+        breadcrumbs.insert(0, json!({ "title": node.title, "path": node.path }));
+    }
+    let value = json!({
+        "title": node.title,
+        "path": node.path,
+        "slug": node.slug,
+        "active": active,
+        "open": open,
+        "children": children,
+    });
+    (value, active || open)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn page(slug: &str, order: i64) -> Page {
+        let source = format!("---\ntitle: \"{} page\"\norder: {}\n---\nBody", slug, order);
+        Page::parse(&source, Path::new(&format!("{}.md", slug))).unwrap()
+    }
+
+    fn item(page: &str) -> DocsNavItem {
+        DocsNavItem {
+            page: Some(page.to_string()),
+            ..DocsNavItem::default()
+        }
+    }
+
+    #[test]
+    fn test_configured_tree() {
+        let pages = [page("install", 1), page("deploy", 2), page("faq", 3)];
+        let config = DocsConfig {
+            nav: vec![
+                item("install"),
+                DocsNavItem {
+                    title: Some("Guides".to_string()),
+                    children: vec![item("deploy")],
+                    ..DocsNavItem::default()
+                },
+                DocsNavItem {
+                    title: Some("GitHub".to_string()),
+                    url: Some("https://github.com/example/project".to_string()),
+                    ..DocsNavItem::default()
+                },
+            ],
+            version: Some("v2".to_string()),
+            ..DocsConfig::default()
+        };
+        let nav = DocsNav::new(&config, &pages).unwrap();
+
+        let docs = nav.context("deploy");
+        assert_eq!(docs["prev"]["path"], "install/index.html");
+        assert!(docs["next"].is_null());
+        assert_eq!(docs["nav"][1]["open"], true);
+        assert_eq!(docs["nav"][1]["children"][0]["active"], true);
+        assert_eq!(docs["nav"][0]["active"], false);
+        assert_eq!(docs["breadcrumbs"][0]["title"], "Guides");
+        assert_eq!(docs["breadcrumbs"][1]["title"], "deploy page");
+        assert_eq!(docs["version"], "v2");
+
+        let home = nav.context("");
+        assert_eq!(home["next"]["title"], "install page");
+
+        let missing = DocsConfig {
+            nav: vec![item("nope")],
+            ..DocsConfig::default()
+        };
+        let error = DocsNav::new(&missing, &pages).unwrap_err().to_string();
+        assert!(error.contains("content/nope.md"), "{}", error);
+    }
+
+    #[test]
+    fn test_tree_from_pages() {
+        let pages = [page("install", 1), page("deploy", 2)];
+        let nav = DocsNav::new(&DocsConfig::default(), &pages).unwrap();
+        let docs = nav.context("install");
+        assert_eq!(docs["nav"].as_array().unwrap().len(), 2);
+        assert_eq!(docs["next"]["title"], "deploy page");
+        assert!(docs["prev"].is_null());
+    }
+}
@@ -0,0 +1,94 @@
+//! Alias domains
+//!
+//! A site with a custom domain can answer on other domains too: the `www.`
+//! form of an apex domain (or the apex form of a `www.` domain) and any
+//! `aliases` in `[blog.domains]`. All of them redirect to the main domain,
+//! so canonical URLs, the sitemap and links between pages only ever use it.
+
+use url::Url;
+
+/// Netlify `_redirects` rules sending every path on each alias to the
+/// same path on `base_url`. The `!` applies them even where a file exists.
+pub fn redirect_rules(aliases: &[String], base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let mut rules = String::new();
+    for alias in aliases {
+        for scheme in ["https", "http"] {
+            rules.push_str(&format!(
+                "{}://{}/* {}/:splat 301!\n",
+                scheme, alias, base_url
+            ));
+        }
+    }
+    rules
+}
+
+/// `dest` on the main domain, if it's a link to one of the aliases
+pub fn canonical_link(dest: &str, aliases: &[String], base_url: &str) -> Option<String> {
+    if aliases.is_empty() {
+        return None;
+    }
+    let absolute = match dest.strip_prefix("//") {
+        Some(rest) => format!("https://{}", rest),
+        None => dest.to_string(),
+    };
+    let url = Url::parse(&absolute).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?.to_lowercase();
+    if !aliases.contains(&host) {
+        return None;
+    }
+
+    let mut link = format!("{}{}", base_url.trim_end_matches('/'), url.path());
+    if let Some(query) = url.query() {
+        link.push('?');
+        link.push_str(query);
+    }
+    if let Some(fragment) = url.fragment() {
+        link.push('#');
+        link.push_str(fragment);
+    }
+    Some(link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alias_links_and_rules() {
+        let aliases = vec!["www.example.com".to_string(), "old-blog.net".to_string()];
+        let base = "https://example.com";
+
+        assert_eq!(
+            canonical_link("https://www.example.com/posts/a.html#top", &aliases, base),
+            Some("https://example.com/posts/a.html#top".to_string())
+        );
+        assert_eq!(
+            canonical_link("http://OLD-BLOG.net/?page=2", &aliases, base),
+            Some("https://example.com/?page=2".to_string())
+        );
+        assert_eq!(
+            canonical_link("//www.example.com/feed.xml", &aliases, base),
+            Some("https://example.com/feed.xml".to_string())
+        );
+        assert_eq!(
+            canonical_link("https://example.com/a", &aliases, base),
+            None
+        );
+        assert_eq!(canonical_link("/posts/a.html", &aliases, base), None);
+        assert_eq!(
+            canonical_link("mailto:me@old-blog.net", &aliases, base),
+            None
+        );
+
+        let rules = redirect_rules(&aliases[..1], "https://example.com/");
+        assert_eq!(
+            rules,
+            "https://www.example.com/* https://example.com/:splat 301!\n\
+             http://www.example.com/* https://example.com/:splat 301!\n"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Privacy mode for third-party embeds, from `[privacy.embeds]`
+//!
+//! Each provider is set to `click-to-load`, `privacy-enhanced` or `off`.
+//! Video shortcodes follow these settings instead of `[markdown.video]`, and
+//! rendered posts are scanned for embeds pasted as HTML: YouTube and Vimeo
+//! iframes are replaced with blogr's video embeds, and tweets lose Twitter's
+//! `widgets.js`, so they stay plain quotes until a reader asks to load them.
+
+use crate::config::{EmbedMode, EmbedsConfig, VideoConfig};
+use crate::generator::video::{self, Video, VideoOptions, VIDEO_SCRIPT};
+use crate::webmention::parse_attributes;
+use std::collections::HashMap;
+
+const TWITTER_SCRIPT_HOST: &str = "platform.twitter.com";
+
+/// Class of tweets waiting for a click; Twitter's script only renders
+/// `twitter-tweet`
+const PENDING_TWEET_CLASS: &str = "blogr-twitter-tweet";
+
+fn video_mode(embeds: &EmbedsConfig, video: &Video) -> Option<EmbedMode> {
+    if !embeds.enabled {
+        return None;
+    }
+    match video {
+        Video::YouTube { .. } => Some(embeds.youtube),
+        Video::Vimeo { .. } => Some(embeds.vimeo),
+        Video::File { .. } => None,
+    }
+}
+
+fn twitter_mode(embeds: &EmbedsConfig) -> Option<EmbedMode> {
+    (embeds.enabled && embeds.twitter != EmbedMode::Off).then_some(embeds.twitter)
+}
+
+/// The `[markdown.video]` settings for a video, with privacy mode applied
+pub fn video_config(embeds: &EmbedsConfig, video: &Video, base: &VideoConfig) -> VideoConfig {
+    let mut config = base.clone();
+    match video_mode(embeds, video) {
+        Some(EmbedMode::ClickToLoad) => {
+            config.privacy_enhanced = true;
+            config.click_to_load = true;
+        }
+        Some(EmbedMode::PrivacyEnhanced) => {
+            config.privacy_enhanced = true;
+            config.click_to_load = false;
+        }
+        Some(EmbedMode::Off) | None => {}
+    }
+    config
+}
+
+/// Whether pages can have placeholders that need the click-to-load script
+pub fn uses_click_to_load(embeds: &EmbedsConfig) -> bool {
+    embeds.enabled
+        && [embeds.youtube, embeds.vimeo, embeds.twitter].contains(&EmbedMode::ClickToLoad)
+}
+
+/// Apply privacy mode to embeds pasted into rendered HTML. `url` turns a
+/// site path into a link.
+pub fn rewrite(
+    html: &str,
+    embeds: &EmbedsConfig,
+    base: &VideoConfig,
+    url: impl Fn(&str) -> String,
+) -> String {
+    if !embeds.enabled {
+        return html.to_string();
+    }
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let name_len = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len() - 1);
+        let replaced = match rest[1..1 + name_len].to_ascii_lowercase().as_str() {
+            "div" => blogr_video(rest, name_len),
+            "iframe" => video_iframe(rest, name_len, embeds, base, &url),
+            "blockquote" => tweet(rest, name_len, embeds, &url),
+            "script" => twitter_script(rest, name_len, embeds),
+            _ => None,
+        };
+        match replaced {
+            Some((replacement, consumed)) => {
+                result.push_str(&replacement);
+                rest = &rest[consumed..];
+            }
+            None => {
+                result.push('<');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The element starting at `html`: its attributes, the length of its start
+/// tag and the length through its end tag
+fn element(
+    html: &str,
+    name_len: usize,
+    end_tag: &str,
+) -> Option<(HashMap<String, String>, usize, usize)> {
+    let (attributes, after) = parse_attributes(&html[1 + name_len..]);
+    let start_tag = html.len() - after.len();
+    let end = after.to_ascii_lowercase().find(end_tag)?;
+    Some((attributes, start_tag, start_tag + end + end_tag.len()))
+}
+
+/// An embed from a video shortcode, which already follows privacy mode and
+/// is kept as it is
+fn blogr_video(html: &str, name_len: usize) -> Option<(String, usize)> {
+    let (attributes, _, end) = element(html, name_len, "</div>")?;
+    let class = attributes.get("class")?;
+    class
+        .split_whitespace()
+        .any(|class| class == "blogr-video")
+        .then(|| (html[..end].to_string(), end))
+}
+
+/// A YouTube or Vimeo iframe, as blogr's embed
+fn video_iframe(
+    html: &str,
+    name_len: usize,
+    embeds: &EmbedsConfig,
+    base: &VideoConfig,
+    url: impl Fn(&str) -> String,
+) -> Option<(String, usize)> {
+    let (attributes, _, end) = element(html, name_len, "</iframe>")?;
+    let video = Video::parse(attributes.get("src")?).ok()?;
+    if video_mode(embeds, &video).is_none_or(|mode| mode == EmbedMode::Off) {
+        return None;
+    }
+    let options = VideoOptions {
+        title: attributes.get("title").cloned(),
+        ..Default::default()
+    };
+    let config = video_config(embeds, &video, base);
+    Some((video::embed_html(&video, &options, &config, url), end))
+}
+
+/// A tweet blockquote; click-to-load wraps it in a placeholder
+fn tweet(
+    html: &str,
+    name_len: usize,
+    embeds: &EmbedsConfig,
+    url: impl Fn(&str) -> String,
+) -> Option<(String, usize)> {
+    if twitter_mode(embeds)? != EmbedMode::ClickToLoad {
+        return None;
+    }
+    let (attributes, start_tag, end) = element(html, name_len, "</blockquote>")?;
+    let is_tweet = attributes.get("class").is_some_and(|class| {
+        class
+            .split_whitespace()
+            .any(|class| class == "twitter-tweet")
+    });
+    if !is_tweet {
+        return None;
+    }
+    let quote = format!(
+        "{}{}",
+        html[..start_tag].replacen("twitter-tweet", PENDING_TWEET_CLASS, 1),
+        &html[start_tag..end]
+    );
+    Some((
+        format!(
+            "<div class=\"blogr-tweet\" data-blogr-tweet>{}<button type=\"button\">Load tweet from Twitter</button><p>Loading shows the tweet with Twitter's script.</p></div><script src=\"{}\" defer></script>",
+            quote,
+            url(VIDEO_SCRIPT)
+        ),
+        end,
+    ))
+}
+
+/// Twitter's `widgets.js`, which is dropped
+fn twitter_script(html: &str, name_len: usize, embeds: &EmbedsConfig) -> Option<(String, usize)> {
+    twitter_mode(embeds)?;
+    let (attributes, _, end) = element(html, name_len, "</script>")?;
+    let src = attributes.get("src")?;
+    let host = src
+        .trim_start_matches("https:")
+        .trim_start_matches("http:")
+        .strip_prefix("//")?
+        .split('/')
+        .next()?;
+    (host == TWITTER_SCRIPT_HOST).then(|| (String::new(), end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWEET: &str = r#"<blockquote class="twitter-tweet"><p>Hello</p>&mdash; Ann (@ann) <a href="https://twitter.com/ann/status/1">March 1, 2025</a></blockquote>
+<script async src="https://platform.twitter.com/widgets.js" charset="utf-8"></script>"#;
+
+    fn embeds(youtube: EmbedMode, twitter: EmbedMode) -> EmbedsConfig {
+        EmbedsConfig {
+            enabled: true,
+            youtube,
+            vimeo: EmbedMode::Off,
+            twitter,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_iframes() {
+        let html = r#"<p>Watch:</p><iframe width="560" src="https://www.youtube.com/embed/dQw4w9WgXcQ" title="Talk"></iframe><iframe src="https://player.vimeo.com/video/76979871"></iframe>"#;
+        let url = |path: &str| format!("/{}", path);
+        let video = VideoConfig::default();
+
+        let enhanced = rewrite(
+            html,
+            &embeds(EmbedMode::PrivacyEnhanced, EmbedMode::Off),
+            &video,
+            url,
+        );
+        assert!(enhanced.starts_with("<p>Watch:</p><div class=\"blogr-video blogr-video-youtube\""));
+        assert!(enhanced
+            .contains("src=\"https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ\" title=\"Talk\""));
+        // Vimeo is off, so its iframe is left alone
+        assert!(
+            enhanced.ends_with("<iframe src=\"https://player.vimeo.com/video/76979871\"></iframe>")
+        );
+
+        let click = rewrite(
+            html,
+            &embeds(EmbedMode::ClickToLoad, EmbedMode::Off),
+            &video,
+            url,
+        );
+        assert!(click
+            .contains("data-blogr-video=\"https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ\""));
+        assert!(click.contains("<script src=\"/js/video.js\" defer></script>"));
+
+        // Shortcode embeds are already done
+        assert_eq!(
+            rewrite(
+                &enhanced,
+                &embeds(EmbedMode::ClickToLoad, EmbedMode::Off),
+                &video,
+                url
+            ),
+            enhanced
+        );
+
+        let disabled = EmbedsConfig::default();
+        assert_eq!(rewrite(html, &disabled, &video, url), html);
+    }
+
+    #[test]
+    fn test_rewrite_tweets() {
+        let url = |path: &str| format!("/{}", path);
+        let video = VideoConfig::default();
+
+        let enhanced = rewrite(
+            TWEET,
+            &embeds(EmbedMode::Off, EmbedMode::PrivacyEnhanced),
+            &video,
+            url,
+        );
+        assert_eq!(
+            enhanced,
+            TWEET.split('\n').next().unwrap().to_string() + "\n"
+        );
+
+        let click = rewrite(
+            TWEET,
+            &embeds(EmbedMode::Off, EmbedMode::ClickToLoad),
+            &video,
+            url,
+        );
+        assert!(click.starts_with(
+            "<div class=\"blogr-tweet\" data-blogr-tweet><blockquote class=\"blogr-twitter-tweet\"><p>Hello</p>"
+        ));
+        assert!(!click.contains("platform.twitter.com"));
+        assert!(uses_click_to_load(&embeds(
+            EmbedMode::Off,
+            EmbedMode::ClickToLoad
+        )));
+        assert!(!uses_click_to_load(&embeds(EmbedMode::Off, EmbedMode::Off)));
+    }
+}
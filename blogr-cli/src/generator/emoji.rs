@@ -0,0 +1,188 @@
+//! `:emoji:` shortcodes
+//!
+//! Replaces GitHub-style shortcodes such as `:tada:` with the emoji. Unknown
+//! names are left as they are, so text like `10:30:00` is safe.
+
+/// Shortcode names and their emoji
+const EMOJI: &[(&str, &str)] = &[
+    ("+1", "👍"),
+    ("-1", "👎"),
+    ("100", "💯"),
+    ("alarm_clock", "⏰"),
+    ("angry", "😠"),
+    ("apple", "🍎"),
+    ("art", "🎨"),
+    ("baby", "👶"),
+    ("beer", "🍺"),
+    ("bell", "🔔"),
+    ("bike", "🚲"),
+    ("bird", "🐦"),
+    ("blush", "😊"),
+    ("book", "📖"),
+    ("books", "📚"),
+    ("boom", "💥"),
+    ("bread", "🍞"),
+    ("broken_heart", "💔"),
+    ("bug", "🐛"),
+    ("bulb", "💡"),
+    ("cake", "🍰"),
+    ("calendar", "📆"),
+    ("camera", "📷"),
+    ("car", "🚗"),
+    ("cat", "🐱"),
+    ("chart_with_upwards_trend", "📈"),
+    ("clap", "👏"),
+    ("clipboard", "📋"),
+    ("cloud", "☁️"),
+    ("coffee", "☕"),
+    ("computer", "💻"),
+    ("confused", "😕"),
+    ("construction", "🚧"),
+    ("cool", "🆒"),
+    ("crab", "🦀"),
+    ("cry", "😢"),
+    ("dog", "🐶"),
+    ("email", "📧"),
+    ("eyes", "👀"),
+    ("fire", "🔥"),
+    ("flushed", "😳"),
+    ("gear", "⚙️"),
+    ("gift", "🎁"),
+    ("globe_with_meridians", "🌐"),
+    ("grin", "😁"),
+    ("grinning", "😀"),
+    ("hammer", "🔨"),
+    ("hammer_and_wrench", "🛠️"),
+    ("heart", "❤️"),
+    ("heart_eyes", "😍"),
+    ("heavy_check_mark", "✔️"),
+    ("hourglass", "⌛"),
+    ("house", "🏠"),
+    ("hugs", "🤗"),
+    ("information_source", "ℹ️"),
+    ("innocent", "😇"),
+    ("joy", "😂"),
+    ("key", "🔑"),
+    ("laughing", "😆"),
+    ("link", "🔗"),
+    ("lock", "🔒"),
+    ("mag", "🔍"),
+    ("memo", "📝"),
+    ("moon", "🌔"),
+    ("muscle", "💪"),
+    ("musical_note", "🎵"),
+    ("no_entry", "⛔"),
+    ("ok_hand", "👌"),
+    ("package", "📦"),
+    ("party_popper", "🎉"),
+    ("pencil", "📝"),
+    ("pencil2", "✏️"),
+    ("penguin", "🐧"),
+    ("pizza", "🍕"),
+    ("point_down", "👇"),
+    ("point_left", "👈"),
+    ("point_right", "👉"),
+    ("point_up", "☝️"),
+    ("pray", "🙏"),
+    ("question", "❓"),
+    ("raised_hands", "🙌"),
+    ("recycle", "♻️"),
+    ("relaxed", "☺️"),
+    ("rocket", "🚀"),
+    ("rofl", "🤣"),
+    ("rose", "🌹"),
+    ("scream", "😱"),
+    ("see_no_evil", "🙈"),
+    ("seedling", "🌱"),
+    ("shrug", "🤷"),
+    ("sleeping", "😴"),
+    ("slightly_smiling_face", "🙂"),
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("smirk", "😏"),
+    ("snake", "🐍"),
+    ("snowflake", "❄️"),
+    ("sob", "😭"),
+    ("sparkles", "✨"),
+    ("star", "⭐"),
+    ("star_struck", "🤩"),
+    ("stuck_out_tongue", "😛"),
+    ("sun_with_face", "🌞"),
+    ("sunglasses", "😎"),
+    ("sunny", "☀️"),
+    ("sweat_smile", "😅"),
+    ("tada", "🎉"),
+    ("thinking", "🤔"),
+    ("thumbsdown", "👎"),
+    ("thumbsup", "👍"),
+    ("trophy", "🏆"),
+    ("turtle", "🐢"),
+    ("umbrella", "☔"),
+    ("unicorn", "🦄"),
+    ("warning", "⚠️"),
+    ("wave", "👋"),
+    ("white_check_mark", "✅"),
+    ("wink", "😉"),
+    ("wrench", "🔧"),
+    ("x", "❌"),
+    ("zap", "⚡"),
+    ("zzz", "💤"),
+];
+
+/// The emoji for a shortcode name, without the colons
+pub fn lookup(name: &str) -> Option<&'static str> {
+    EMOJI
+        .binary_search_by(|(known, _)| known.cmp(&name))
+        .ok()
+        .map(|index| EMOJI[index].1)
+}
+
+/// Replace every known `:name:` in `text`
+pub fn replace_shortcodes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(':') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name_length = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')))
+            .unwrap_or(after.len());
+        let emoji = after[name_length..]
+            .starts_with(':')
+            .then(|| lookup(&after[..name_length]))
+            .flatten();
+        match emoji {
+            Some(emoji) => {
+                output.push_str(emoji);
+                rest = &after[name_length + 1..];
+            }
+            // The closing colon may open the next shortcode
+            None => {
+                output.push(':');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_shortcodes() {
+        assert!(EMOJI.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(
+            replace_shortcodes("Shipped :tada::rocket:!"),
+            "Shipped 🎉🚀!"
+        );
+        assert_eq!(replace_shortcodes(":+1: from me"), "👍 from me");
+        assert_eq!(
+            replace_shortcodes("At 10:30:00 see note: :unknown: :"),
+            "At 10:30:00 see note: :unknown: :"
+        );
+    }
+}
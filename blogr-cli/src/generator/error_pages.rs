@@ -0,0 +1,150 @@
+//! `404.html` and other error pages
+//!
+//! Every build writes `404.html` at the root of the output, where Netlify,
+//! Cloudflare Pages, Vercel and GitHub Pages look for it, and `blogr serve`
+//! answers unknown paths with it. `[error_pages]` can replace its text with a
+//! markdown file and add pages for other status codes, written as
+//! `<status>.html` for servers configured to use them.
+
+use crate::config::Config;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Status code of the page every build writes
+pub const NOT_FOUND: u16 = 404;
+
+/// An error page, as the `error` template variable
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorPage {
+    pub status: u16,
+    pub title: String,
+    pub message: String,
+    /// Markdown from the configured file, without its title heading
+    #[serde(skip)]
+    pub content: String,
+}
+
+impl ErrorPage {
+    /// The built-in page for a status code
+    pub fn new(status: u16) -> Self {
+        let (title, message) = match status {
+            400 => ("Bad request", "The request could not be understood."),
+            401 => ("Unauthorized", "You need to sign in to see this page."),
+            403 => ("Forbidden", "You don't have permission to see this page."),
+            404 => (
+                "Page not found",
+                "The page you're looking for doesn't exist.",
+            ),
+            410 => ("Gone", "This page has been removed."),
+            500 => ("Server error", "Something went wrong on our side."),
+            503 => (
+                "Service unavailable",
+                "The site is down for maintenance. Please try again soon.",
+            ),
+            _ if status >= 500 => ("Server error", "Something went wrong on our side."),
+            _ => ("Error", "The page could not be shown."),
+        };
+        Self {
+            status,
+            title: title.to_string(),
+            message: message.to_string(),
+            content: String::new(),
+        }
+    }
+
+    /// A page from markdown; a leading `# ` heading becomes the title
+    pub fn from_markdown(status: u16, markdown: &str) -> Self {
+        let mut page = Self::new(status);
+        match markdown.trim_start().strip_prefix("# ") {
+            Some(rest) => {
+                let (heading, rest) = rest.split_once('\n').unwrap_or((rest, ""));
+                page.title = heading.trim().to_string();
+                page.content = rest.to_string();
+            }
+            None => page.content = markdown.to_string(),
+        }
+        page
+    }
+
+    /// Output file, relative to the output directory
+    pub fn output_path(&self) -> String {
+        format!("{}.html", self.status)
+    }
+
+    /// Theme template for this status, e.g. `404.html`
+    pub fn template(&self) -> String {
+        self.output_path()
+    }
+}
+
+/// The 404 page and any configured ones, in status order
+pub fn load(project_root: &Path, config: &Config) -> Result<Vec<ErrorPage>> {
+    let mut pages = Vec::new();
+    let configured: Vec<(u16, &String)> = config
+        .error_pages
+        .iter()
+        .filter_map(|(status, file)| status.parse().ok().map(|status| (status, file)))
+        .collect();
+    if !configured.iter().any(|(status, _)| *status == NOT_FOUND) {
+        pages.push(ErrorPage::new(NOT_FOUND));
+    }
+
+    for (status, file) in configured {
+        let path = project_root.join(file);
+        let markdown = fs::read_to_string(&path).map_err(|e| {
+            anyhow!(
+                "Failed to read the {} error page {}: {}",
+                status,
+                path.display(),
+                e
+            )
+        })?;
+        pages.push(ErrorPage::from_markdown(status, &markdown));
+    }
+    pages.sort_by_key(|page| page.status);
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_error_pages() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("gone.md"),
+            "\n# Retired\n\nThis post was taken down.\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("oops.md"), "Try again later.").unwrap();
+
+        let mut config = Config::default();
+        let pages = load(dir.path(), &config).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].title, "Page not found");
+        assert_eq!(pages[0].output_path(), "404.html");
+
+        config
+            .error_pages
+            .insert("503".to_string(), "oops.md".to_string());
+        config
+            .error_pages
+            .insert("410".to_string(), "gone.md".to_string());
+        let pages = load(dir.path(), &config).unwrap();
+        let statuses: Vec<u16> = pages.iter().map(|page| page.status).collect();
+        assert_eq!(statuses, [404, 410, 503]);
+        assert_eq!(pages[1].title, "Retired");
+        assert_eq!(pages[1].content.trim(), "This post was taken down.");
+        assert_eq!(pages[2].title, "Service unavailable");
+        assert_eq!(pages[2].content, "Try again later.");
+
+        config
+            .error_pages
+            .insert("404".to_string(), "missing.md".to_string());
+        assert!(load(dir.path(), &config).is_err());
+    }
+}
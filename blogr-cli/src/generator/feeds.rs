@@ -0,0 +1,81 @@
+//! Feed discovery
+//!
+//! Every page of a site with a blog gets `<link rel="alternate">` tags for the
+//! RSS and Atom feeds, and tag pages and posts also for the feeds of their
+//! tags, so feed readers find them whatever the theme. Feeds a page already
+//! links to are left alone.
+
+use crate::generator::markdown::html_escape;
+use crate::generator::structured_data::insert_in_head;
+
+pub const RSS_TYPE: &str = "application/rss+xml";
+pub const ATOM_TYPE: &str = "application/atom+xml";
+
+/// A feed to advertise in the `<head>` of pages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedLink {
+    pub title: String,
+    pub href: String,
+    pub mime: &'static str,
+}
+
+/// Output path of a tag's RSS feed, next to the tag's page
+pub fn tag_feed_path(tag: &str) -> String {
+    format!("tags/{}.xml", tag)
+}
+
+/// `html` with discovery links for the feeds it doesn't link to yet. Pages
+/// without a `<head>`, such as partials, are returned as they are.
+pub fn add_discovery(html: &str, feeds: &[FeedLink]) -> String {
+    if !html.to_ascii_lowercase().contains("</head>") {
+        return html.to_string();
+    }
+    let tags: Vec<String> = feeds
+        .iter()
+        .filter(|feed| !html.contains(&format!("\"{}\"", feed.href)))
+        .map(|feed| {
+            format!(
+                r#"<link rel="alternate" type="{}" title="{}" href="{}">"#,
+                feed.mime,
+                html_escape(&feed.title),
+                html_escape(&feed.href)
+            )
+        })
+        .collect();
+    if tags.is_empty() {
+        html.to_string()
+    } else {
+        insert_in_head(html, &tags.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_discovery() {
+        let feeds = [
+            FeedLink {
+                title: "Notes".to_string(),
+                href: "https://example.com/rss.xml".to_string(),
+                mime: RSS_TYPE,
+            },
+            FeedLink {
+                title: "Notes: rust & web".to_string(),
+                href: "https://example.com/tags/rust.xml".to_string(),
+                mime: RSS_TYPE,
+            },
+        ];
+
+        let page = r#"<html><head><link rel="alternate" type="application/rss+xml" href="https://example.com/rss.xml"></head></html>"#;
+        let html = add_discovery(page, &feeds);
+        assert_eq!(html.matches("https://example.com/rss.xml").count(), 1);
+        assert!(html.contains(
+            r#"<link rel="alternate" type="application/rss+xml" title="Notes: rust &amp; web" href="https://example.com/tags/rust.xml">"#
+        ));
+
+        let partial = "<form></form>";
+        assert_eq!(add_discovery(partial, &feeds), partial);
+    }
+}
@@ -0,0 +1,438 @@
+//! Other renderings of posts from `[[output_formats]]`
+//!
+//! Every post can be written again in further formats, each with the template
+//! `formats/<name>.<extension>` to `posts/<slug>/<name>.<extension>`. Three
+//! are built in: `text` for plain text, `gemini` for Gemini capsules and
+//! `lite`, a minimal HTML page without scripts. Themes can replace their
+//! templates and add formats of their own. The template gets the post's
+//! content as HTML, plain text or gemtext, as the format asks for.
+
+use crate::config::OutputFormatConfig;
+use crate::generator::markdown::html_escape;
+use anyhow::{bail, Result};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use std::collections::HashSet;
+
+/// Templates of the built-in formats, for themes without their own
+pub const DEFAULT_TEMPLATES: [(&str, &str); 3] = [
+    (
+        "formats/text.txt",
+        include_str!("../templates/site/formats/text.txt"),
+    ),
+    (
+        "formats/gemini.gmi",
+        include_str!("../templates/site/formats/gemini.gmi"),
+    ),
+    (
+        "formats/lite.html",
+        include_str!("../templates/site/formats/lite.html"),
+    ),
+];
+
+/// Built-in formats: name, extension, media type and content
+const BUILT_IN: [(&str, &str, &str, Content); 3] = [
+    ("text", "txt", "text/plain", Content::Text),
+    ("gemini", "gmi", "text/gemini", Content::Gemtext),
+    ("lite", "html", "text/html", Content::Html),
+];
+
+/// What a format's template gets as `content`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Content {
+    Html,
+    Text,
+    Gemtext,
+}
+
+/// A format with the defaults of built-in formats filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputFormat {
+    pub name: String,
+    pub extension: String,
+    pub media_type: String,
+    pub content: Content,
+}
+
+impl OutputFormat {
+    /// Check `[[output_formats]]` and fill in defaults
+    pub fn resolve(formats: &[OutputFormatConfig]) -> Result<Vec<Self>> {
+        let mut names = HashSet::new();
+        formats
+            .iter()
+            .map(|format| {
+                if !is_simple_name(&format.name) {
+                    bail!(
+                        "Output format name '{}' may only contain lowercase letters, digits and '-'",
+                        format.name
+                    );
+                }
+                if !names.insert(format.name.as_str()) {
+                    bail!("Output format '{}' is listed twice", format.name);
+                }
+                let built_in = BUILT_IN.iter().find(|(name, ..)| *name == format.name);
+                let Some(extension) = format
+                    .extension
+                    .clone()
+                    .or_else(|| built_in.map(|(_, extension, ..)| extension.to_string()))
+                else {
+                    bail!("Output format '{}' needs an extension", format.name);
+                };
+                if !is_simple_name(&extension) {
+                    bail!(
+                        "Extension '{}' of output format '{}' may only contain lowercase letters, digits and '-'",
+                        extension,
+                        format.name
+                    );
+                }
+                let content = match format.content.as_deref() {
+                    Some("html") => Content::Html,
+                    Some("text") => Content::Text,
+                    Some("gemtext") => Content::Gemtext,
+                    Some(other) => bail!(
+                        "Output format '{}' has unknown content '{}' (use html, text or gemtext)",
+                        format.name,
+                        other
+                    ),
+                    None => built_in.map_or(Content::Html, |(.., content)| *content),
+                };
+                let media_type = format
+                    .media_type
+                    .clone()
+                    .or_else(|| built_in.map(|(_, _, media_type, _)| media_type.to_string()))
+                    .unwrap_or_else(|| media_type_for(&extension).to_string());
+                Ok(Self {
+                    name: format.name.clone(),
+                    extension,
+                    media_type,
+                    content,
+                })
+            })
+            .collect()
+    }
+
+    /// The template rendering this format
+    pub fn template(&self) -> String {
+        format!("formats/{}.{}", self.name, self.extension)
+    }
+
+    /// Where a post's copy is written, relative to the output directory
+    pub fn path(&self, slug: &str) -> String {
+        format!("posts/{}/{}.{}", slug, self.name, self.extension)
+    }
+}
+
+/// `<link rel="alternate">` tags for the other formats of a post, with their URLs
+pub fn head_tags(formats: &[(OutputFormat, String)]) -> String {
+    formats
+        .iter()
+        .map(|(format, href)| {
+            format!(
+                r#"<link rel="alternate" type="{}" title="{} version" href="{}">"#,
+                html_escape(&format.media_type),
+                html_escape(&format.name),
+                html_escape(href)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_simple_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn media_type_for(extension: &str) -> &'static str {
+    match extension {
+        "html" | "htm" => "text/html",
+        "txt" | "md" => "text/plain",
+        "gmi" => "text/gemini",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Plain text for reading as is: paragraphs stay apart, links keep their URL
+pub fn markdown_to_plain(markdown: &str, resolve: impl Fn(&str) -> String) -> String {
+    Writer::new(Content::Text, &resolve).write(markdown)
+}
+
+/// Gemtext: links on lines of their own after the block they appear in
+pub fn markdown_to_gemtext(markdown: &str, resolve: impl Fn(&str) -> String) -> String {
+    Writer::new(Content::Gemtext, &resolve).write(markdown)
+}
+
+/// Writes markdown as text a block at a time
+struct Writer<'a> {
+    style: Content,
+    /// Makes link targets absolute
+    resolve: &'a dyn Fn(&str) -> String,
+    out: String,
+    /// Text of the current block
+    block: String,
+    /// Gemtext links of the current block, written after it
+    links: Vec<(String, String)>,
+    /// Where the text of each open link starts in the block
+    link_starts: Vec<usize>,
+    /// Open lists, with the next number of ordered ones
+    lists: Vec<Option<u64>>,
+    quotes: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(style: Content, resolve: &'a dyn Fn(&str) -> String) -> Self {
+        Self {
+            style,
+            resolve,
+            out: String::new(),
+            block: String::new(),
+            links: Vec::new(),
+            link_starts: Vec::new(),
+            lists: Vec::new(),
+            quotes: 0,
+        }
+    }
+
+    fn write(mut self, markdown: &str) -> String {
+        for event in Parser::new(markdown) {
+            self.event(event);
+        }
+        self.flush();
+        let mut out = self.out.trim_end().to_string();
+        out.push('\n');
+        out
+    }
+
+    fn gemtext(&self) -> bool {
+        self.style == Content::Gemtext
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.flush();
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                let code = std::mem::take(&mut self.block);
+                if self.gemtext() {
+                    self.out.push_str("```\n");
+                    self.out.push_str(code.trim_end_matches('\n'));
+                    self.out.push_str("\n```\n\n");
+                } else {
+                    for line in code.trim_end_matches('\n').lines() {
+                        self.out.push_str(format!("    {}", line).trim_end());
+                        self.out.push('\n');
+                    }
+                    self.out.push('\n');
+                }
+            }
+            Event::Text(text) | Event::Code(text) => self.block.push_str(&text),
+            Event::SoftBreak => self.block.push(' '),
+            Event::HardBreak => self.block.push('\n'),
+            Event::Start(Tag::Link(..) | Tag::Image(..)) => {
+                if !self.gemtext() && matches!(event, Event::Start(Tag::Image(..))) {
+                    self.block.push_str("[image: ");
+                }
+                self.link_starts.push(self.block.len());
+            }
+            Event::End(tag @ (Tag::Link(..) | Tag::Image(..))) => {
+                let (Tag::Link(_, url, _) | Tag::Image(_, url, _)) = &tag else {
+                    return;
+                };
+                let start = self.link_starts.pop().unwrap_or(0);
+                let label = self.block[start..].trim().to_string();
+                let url = (self.resolve)(url);
+                if self.gemtext() {
+                    self.links.push((url, label));
+                } else if matches!(tag, Tag::Image(..)) {
+                    self.block.push(']');
+                } else if label != url && !url.starts_with('#') {
+                    self.block.push_str(&format!(" <{}>", url));
+                }
+            }
+            Event::Start(Tag::Heading(..)) => self.flush(),
+            Event::End(Tag::Heading(level, ..)) => {
+                let title = std::mem::take(&mut self.block);
+                let title = title.trim();
+                if self.gemtext() {
+                    let marks = match level {
+                        HeadingLevel::H1 => "#",
+                        HeadingLevel::H2 => "##",
+                        _ => "###",
+                    };
+                    self.block = format!("{} {}", marks, title);
+                    self.flush();
+                } else {
+                    let underline = match level {
+                        HeadingLevel::H1 => Some('='),
+                        HeadingLevel::H2 => Some('-'),
+                        _ => None,
+                    };
+                    self.block = title.to_string();
+                    if let Some(underline) = underline {
+                        let width = title.chars().count();
+                        self.block.push('\n');
+                        self.block.extend(std::iter::repeat_n(underline, width));
+                    }
+                    self.flush();
+                }
+            }
+            Event::End(Tag::Paragraph) if self.lists.is_empty() => self.flush(),
+            Event::End(Tag::Paragraph) => self.block.push(' '),
+            Event::Start(Tag::BlockQuote) => {
+                self.flush();
+                self.quotes += 1;
+            }
+            Event::End(Tag::BlockQuote) => {
+                self.flush();
+                self.quotes -= 1;
+            }
+            Event::Start(Tag::List(first)) => {
+                self.list_item();
+                self.lists.push(first);
+            }
+            Event::End(Tag::List(_)) => {
+                self.list_item();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.out.push('\n');
+                }
+            }
+            Event::End(Tag::Item) => self.list_item(),
+            Event::TaskListMarker(done) => self.block.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::End(Tag::TableCell) => self.block.push_str(" | "),
+            Event::End(Tag::TableHead | Tag::TableRow) => {
+                let row = self.block.trim_end_matches(" | ").to_string();
+                self.block = row + "\n";
+            }
+            Event::End(Tag::Table(_)) => self.flush(),
+            Event::End(Tag::FootnoteDefinition(_)) => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.out.push_str("* * *\n\n");
+            }
+            _ => {}
+        }
+    }
+
+    /// Write the text of a list item, if there is any, with its marker
+    fn list_item(&mut self) {
+        if self.block.trim().is_empty() {
+            self.block.clear();
+            return;
+        }
+        let depth = self.lists.len().saturating_sub(1);
+        let gemtext = self.gemtext();
+        let marker = match self.lists.last_mut() {
+            _ if gemtext => "* ".to_string(),
+            Some(Some(number)) => {
+                *number += 1;
+                format!("{}. ", *number - 1)
+            }
+            _ => "- ".to_string(),
+        };
+        // Gemtext has no nested lists
+        let indent = if gemtext {
+            String::new()
+        } else {
+            "  ".repeat(depth)
+        };
+        self.write_block(&format!("{}{}", indent, marker));
+    }
+
+    /// Write the current block as a paragraph
+    fn flush(&mut self) {
+        if self.block.trim().is_empty() {
+            self.block.clear();
+            self.write_links();
+            return;
+        }
+        self.write_block("");
+        if self.lists.is_empty() {
+            self.out.push('\n');
+        }
+    }
+
+    fn write_block(&mut self, prefix: &str) {
+        let block = std::mem::take(&mut self.block);
+        let quote = "> ".repeat(self.quotes);
+        for (i, line) in block.trim().lines().enumerate() {
+            let lead = if i == 0 {
+                prefix.to_string()
+            } else {
+                " ".repeat(prefix.chars().count())
+            };
+            self.out
+                .push_str(format!("{}{}{}", quote, lead, line.trim()).trim_end());
+            self.out.push('\n');
+        }
+        self.write_links();
+    }
+
+    fn write_links(&mut self) {
+        for (url, label) in std::mem::take(&mut self.links) {
+            if label.is_empty() {
+                self.out.push_str(&format!("=> {}\n", url));
+            } else {
+                self.out.push_str(&format!("=> {} {}\n", url, label));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN: &str = "# Hello\n\nSome *text* with [a link](/about.html).\n\n\
+        - one\n- two\n\n> quoted\n\n```\nlet x = 1;\n```\n";
+
+    fn resolve(url: &str) -> String {
+        match url.strip_prefix('/') {
+            Some(path) => format!("https://example.com/{}", path),
+            None => url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_gemtext() {
+        assert_eq!(
+            markdown_to_gemtext(MARKDOWN, resolve),
+            "# Hello\n\nSome text with a link.\n=> https://example.com/about.html a link\n\n\
+             * one\n* two\n\n> quoted\n\n```\nlet x = 1;\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(
+            markdown_to_plain(MARKDOWN, resolve),
+            "Hello\n=====\n\nSome text with a link <https://example.com/about.html>.\n\n\
+             - one\n- two\n\n> quoted\n\n    let x = 1;\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_formats() {
+        let config = |name: &str, extension: Option<&str>| OutputFormatConfig {
+            name: name.to_string(),
+            extension: extension.map(str::to_string),
+            media_type: None,
+            content: None,
+        };
+        let formats =
+            OutputFormat::resolve(&[config("gemini", None), config("amp", Some("html"))]).unwrap();
+        assert_eq!(formats[0].template(), "formats/gemini.gmi");
+        assert_eq!(formats[0].media_type, "text/gemini");
+        assert_eq!(formats[0].content, Content::Gemtext);
+        assert_eq!(formats[1].path("hello"), "posts/hello/amp.html");
+        assert_eq!(formats[1].content, Content::Html);
+
+        assert!(OutputFormat::resolve(&[config("epub", None)]).is_err());
+        assert!(OutputFormat::resolve(&[config("text", None), config("text", None)]).is_err());
+        assert!(OutputFormat::resolve(&[config("../x", Some("txt"))]).is_err());
+    }
+}
@@ -0,0 +1,337 @@
+//! Photo galleries
+//!
+//! Each folder in `galleries/` is a gallery. Its images are published at
+//! `/galleries/<name>/` with JPEG thumbnails, ordered by the date they were
+//! taken (from EXIF) and rendered with the theme's `gallery.html`. An optional
+//! `gallery.yaml` sets the title, description and per-image captions.
+
+use crate::generator::markdown::html_escape;
+use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+
+/// Project folder holding one folder per gallery
+pub const GALLERIES_DIR: &str = "galleries";
+
+/// Longest side of a thumbnail, in pixels
+pub const THUMBNAIL_SIZE: u32 = 480;
+
+const THUMBNAIL_QUALITY: u8 = 82;
+
+/// The lightbox script, relative to the site root
+pub const GALLERY_SCRIPT: &str = "js/gallery.js";
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp"];
+
+/// Settings from a gallery's `gallery.yaml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GalleryMeta {
+    title: Option<String>,
+    description: Option<String>,
+    /// Captions by image file name
+    captions: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Gallery {
+    /// Folder name, also the gallery's URL segment
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// Page path, e.g. `galleries/trip/index.html`
+    pub path: String,
+    pub images: Vec<GalleryImage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GalleryImage {
+    pub file: String,
+    /// Path of the full-size image
+    pub path: String,
+    /// Path of the JPEG thumbnail
+    pub thumbnail: String,
+    /// Size of the full image as displayed, after EXIF rotation
+    pub width: u32,
+    pub height: u32,
+    /// When the photo was taken, from EXIF
+    pub taken_at: Option<NaiveDateTime>,
+    pub caption: Option<String>,
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+impl Gallery {
+    /// Read `galleries/<name>/`
+    pub fn load(dir: &Path) -> Result<Self> {
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("Invalid gallery folder {}", dir.display()))?;
+
+        let meta_path = dir.join("gallery.yaml");
+        let meta: GalleryMeta = if meta_path.exists() {
+            let source = fs::read_to_string(&meta_path)
+                .with_context(|| format!("Failed to read {}", meta_path.display()))?;
+            serde_yaml::from_str(&source)
+                .map_err(|e| anyhow!("Invalid {}: {}", meta_path.display(), e))?
+        } else {
+            GalleryMeta::default()
+        };
+
+        let mut images = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_image = path.extension().is_some_and(|ext| {
+                IMAGE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+            });
+            if !path.is_file() || !is_image {
+                continue;
+            }
+
+            let file = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let exif = read_exif(&path);
+            let (width, height) = displayed_size(&path, &exif)?;
+
+            images.push(GalleryImage {
+                path: format!("{}/{}/{}", GALLERIES_DIR, name, file),
+                thumbnail: format!("{}/{}/thumbs/{}.jpg", GALLERIES_DIR, name, stem),
+                caption: meta.captions.get(&file).cloned(),
+                taken_at: exif.taken_at,
+                width,
+                height,
+                file,
+                source: path,
+            });
+        }
+
+        // Oldest photo first; photos without a date go last, by name
+        images.sort_by(|a, b| {
+            (a.taken_at.is_none(), a.taken_at, &a.file).cmp(&(
+                b.taken_at.is_none(),
+                b.taken_at,
+                &b.file,
+            ))
+        });
+
+        Ok(Self {
+            title: meta.title.unwrap_or_else(|| title_from_name(&name)),
+            description: meta.description,
+            path: format!("{}/{}/index.html", GALLERIES_DIR, name),
+            images,
+            name,
+        })
+    }
+
+    /// HTML for the `{{< gallery name >}}` shortcode. `url` turns a site path into a link.
+    pub fn embed_html(&self, url: impl Fn(&str) -> String) -> String {
+        let mut html = format!(
+            "<div class=\"blogr-gallery\" data-blogr-gallery=\"{}\">",
+            html_escape(&self.name)
+        );
+        for image in &self.images {
+            let alt = image.caption.as_deref().unwrap_or(&image.file);
+            html.push_str(&format!(
+                "<a href=\"{}\" data-caption=\"{}\"><img src=\"{}\" alt=\"{}\" width=\"{}\" height=\"{}\" loading=\"lazy\"></a>",
+                url(&image.path),
+                html_escape(image.caption.as_deref().unwrap_or_default()),
+                url(&image.thumbnail),
+                html_escape(alt),
+                image.width,
+                image.height,
+            ));
+        }
+        html.push_str(&format!(
+            "</div><script src=\"{}\" defer></script>",
+            url(GALLERY_SCRIPT)
+        ));
+        html
+    }
+}
+
+/// Every gallery in the project, by name
+pub fn load_galleries(project_root: &Path) -> Result<Vec<Gallery>> {
+    let dir = project_root.join(GALLERIES_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut folders: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    folders.sort();
+    folders.iter().map(|folder| Gallery::load(folder)).collect()
+}
+
+/// A JPEG thumbnail of an image, turned upright and fitted within `THUMBNAIL_SIZE`
+pub fn thumbnail(source: &[u8]) -> Result<Vec<u8>> {
+    let image = upright(source)?;
+    encode_jpeg(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE))
+}
+
+/// A JPEG copy of an image, turned upright and scaled to exactly `width` by `height`
+pub fn resized(source: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+    let image = upright(source)?;
+    encode_jpeg(&image.resize_exact(width, height, FilterType::Lanczos3))
+}
+
+/// Width and height of an image file as displayed, after EXIF rotation
+pub fn image_size(path: &Path) -> Result<(u32, u32)> {
+    displayed_size(path, &read_exif(path))
+}
+
+fn displayed_size(path: &Path, exif: &ExifInfo) -> Result<(u32, u32)> {
+    let (width, height) = image::image_dimensions(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    // Orientations 5 to 8 turn the image on its side
+    Ok(if exif.orientation.is_some_and(|o| (5..=8).contains(&o)) {
+        (height, width)
+    } else {
+        (width, height)
+    })
+}
+
+fn upright(source: &[u8]) -> Result<DynamicImage> {
+    let mut decoder = ImageReader::new(Cursor::new(source))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    JpegEncoder::new_with_quality(&mut output, THUMBNAIL_QUALITY).encode_image(&image.to_rgb8())?;
+    Ok(output)
+}
+
+#[derive(Default)]
+struct ExifInfo {
+    taken_at: Option<NaiveDateTime>,
+    orientation: Option<u32>,
+}
+
+/// The capture date and orientation, when the image has EXIF data
+fn read_exif(path: &Path) -> ExifInfo {
+    let Ok(file) = fs::File::open(path) else {
+        return ExifInfo::default();
+    };
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(file)) else {
+        return ExifInfo::default();
+    };
+
+    let taken_at = [exif::Tag::DateTimeOriginal, exif::Tag::DateTime]
+        .iter()
+        .find_map(
+            |tag| match &exif.get_field(*tag, exif::In::PRIMARY)?.value {
+                exif::Value::Ascii(values) => {
+                    let date = exif::DateTime::from_ascii(values.first()?).ok()?;
+                    NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())?
+                        .and_hms_opt(date.hour.into(), date.minute.into(), date.second.into())
+                }
+                _ => None,
+            },
+        );
+    let orientation = exif
+        .get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+        .and_then(|field| field.value.get_uint(0));
+
+    ExifInfo {
+        taken_at,
+        orientation,
+    }
+}
+
+/// "summer-2024" → "Summer 2024"
+fn title_from_name(name: &str) -> String {
+    name.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use tempfile::TempDir;
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        RgbImage::from_pixel(width, height, Rgb([200, 100, 50]))
+            .save_with_format(path, ImageFormat::Png)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_load_gallery() {
+        let dir = TempDir::new().unwrap();
+        let gallery_dir = dir.path().join(GALLERIES_DIR).join("summer-trip");
+        fs::create_dir_all(&gallery_dir).unwrap();
+        write_png(&gallery_dir.join("b.png"), 40, 20);
+        write_png(&gallery_dir.join("a.png"), 10, 30);
+        fs::write(gallery_dir.join("notes.txt"), "not an image").unwrap();
+        fs::write(
+            gallery_dir.join("gallery.yaml"),
+            "description: Two squares\ncaptions:\n  b.png: The wide one\n",
+        )
+        .unwrap();
+
+        let galleries = load_galleries(dir.path()).unwrap();
+        assert_eq!(galleries.len(), 1);
+        let gallery = &galleries[0];
+        assert_eq!(gallery.title, "Summer Trip");
+        assert_eq!(gallery.path, "galleries/summer-trip/index.html");
+        assert_eq!(gallery.images.len(), 2);
+        assert_eq!(gallery.images[0].file, "a.png");
+        assert_eq!(
+            gallery.images[1].thumbnail,
+            "galleries/summer-trip/thumbs/b.jpg"
+        );
+        assert_eq!(
+            (gallery.images[1].width, gallery.images[1].height),
+            (40, 20)
+        );
+        assert_eq!(gallery.images[1].caption.as_deref(), Some("The wide one"));
+
+        let html = gallery.embed_html(|path| format!("/{}", path));
+        assert!(html.contains("href=\"/galleries/summer-trip/b.png\""));
+        assert!(html.contains("data-caption=\"The wide one\""));
+        assert!(!html.contains('\n'));
+    }
+
+    #[test]
+    fn test_thumbnail() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("big.png");
+        write_png(&path, 1200, 600);
+
+        let jpeg = thumbnail(&fs::read(&path).unwrap()).unwrap();
+        let thumb = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!(
+            (thumb.width(), thumb.height()),
+            (THUMBNAIL_SIZE, THUMBNAIL_SIZE / 2)
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use crate::content::Post;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use git2::{DiffOptions, Repository, Sort};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// History of a single file derived from git
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitFileInfo {
+    /// Time of the most recent commit touching the file
+    pub updated_at: DateTime<Utc>,
+    /// Commit authors, in order of their first change to the file
+    pub contributors: Vec<String>,
+}
+
+/// Reads post history from the git repository containing the project
+pub struct GitMetadata {
+    repo: Repository,
+}
+
+impl GitMetadata {
+    /// Open the git repository containing `path`
+    pub fn discover(path: &Path) -> Result<Self> {
+        let repo = Repository::discover(path)
+            .map_err(|e| anyhow!("Failed to open git repository: {}", e))?;
+        Ok(Self { repo })
+    }
+
+    /// Collect history for the given files, keyed by the paths passed in.
+    /// Files with no commits (e.g. untracked) are left out.
+    pub fn collect(&self, files: &[PathBuf]) -> Result<HashMap<PathBuf, GitFileInfo>> {
+        let workdir = self
+            .repo
+            .workdir()
+            .ok_or_else(|| anyhow!("Git repository has no working directory"))?
+            .canonicalize()?;
+
+        // Map repository-relative paths back to the caller's paths
+        let mut wanted: HashMap<PathBuf, PathBuf> = HashMap::new();
+        for file in files {
+            if let Ok(relative) = file
+                .canonicalize()
+                .map_err(anyhow::Error::from)
+                .and_then(|abs| Ok(abs.strip_prefix(&workdir)?.to_path_buf()))
+            {
+                wanted.insert(relative, file.clone());
+            }
+        }
+
+        if wanted.is_empty() || self.repo.head().is_err() {
+            return Ok(HashMap::new());
+        }
+
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(Sort::TIME)?;
+
+        // Newest commits come first, so the first hit for a file is its last change
+        let mut updated: HashMap<PathBuf, DateTime<Utc>> = HashMap::new();
+        let mut authors: HashMap<PathBuf, Vec<String>> = HashMap::new();
+
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            let tree = commit.tree()?;
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree()?),
+                Err(_) => None,
+            };
+
+            let mut options = DiffOptions::new();
+            for path in wanted.keys() {
+                options.pathspec(path);
+            }
+            let diff = self.repo.diff_tree_to_tree(
+                parent_tree.as_ref(),
+                Some(&tree),
+                Some(&mut options),
+            )?;
+
+            for delta in diff.deltas() {
+                let Some(path) = delta.new_file().path() else {
+                    continue;
+                };
+                if !wanted.contains_key(path) {
+                    continue;
+                }
+
+                let time =
+                    DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now);
+                updated.entry(path.to_path_buf()).or_insert(time);
+
+                let author = commit.author();
+                if let Some(name) = author.name() {
+                    authors
+                        .entry(path.to_path_buf())
+                        .or_default()
+                        .push(name.to_string());
+                }
+            }
+        }
+
+        let mut result = HashMap::new();
+        for (relative, updated_at) in updated {
+            let mut contributors = Vec::new();
+            for name in authors
+                .remove(&relative)
+                .unwrap_or_default()
+                .into_iter()
+                .rev()
+            {
+                if !contributors.contains(&name) {
+                    contributors.push(name);
+                }
+            }
+            result.insert(
+                wanted[&relative].clone(),
+                GitFileInfo {
+                    updated_at,
+                    contributors,
+                },
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Fill in `updated_at` and `contributors` for posts that don't set them in front matter
+    pub fn apply(&self, posts: &mut [Post]) -> Result<()> {
+        let files: Vec<PathBuf> = posts.iter().map(|p| p.file_path.clone()).collect();
+        let history = self.collect(&files)?;
+
+        for post in posts.iter_mut() {
+            if let Some(info) = history.get(&post.file_path) {
+                if post.metadata.updated_at.is_none() {
+                    post.metadata.updated_at = Some(info.updated_at);
+                }
+                if post.metadata.contributors.is_empty() {
+                    post.metadata.contributors = info.contributors.clone();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_file(repo: &Repository, path: &str, content: &str, author: &str, time: i64) {
+        let workdir = repo.workdir().unwrap();
+        fs::create_dir_all(workdir.join(path).parent().unwrap()).unwrap();
+        fs::write(workdir.join(path), content).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        let signature =
+            Signature::new(author, "author@example.com", &git2::Time::new(time, 0)).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "update",
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_collect_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+
+        commit_file(&repo, "posts/a.md", "one", "Alice", 1_700_000_000);
+        commit_file(&repo, "posts/b.md", "one", "Alice", 1_700_100_000);
+        commit_file(&repo, "posts/a.md", "two", "Bob", 1_700_200_000);
+        commit_file(&repo, "posts/a.md", "three", "Alice", 1_700_300_000);
+
+        let a = temp_dir.path().join("posts/a.md");
+        let b = temp_dir.path().join("posts/b.md");
+        let untracked = temp_dir.path().join("posts/c.md");
+        fs::write(&untracked, "new").unwrap();
+
+        let git = GitMetadata::discover(temp_dir.path()).unwrap();
+        let history = git
+            .collect(&[a.clone(), b.clone(), untracked.clone()])
+            .unwrap();
+
+        let info = &history[&a];
+        assert_eq!(info.updated_at.timestamp(), 1_700_300_000);
+        assert_eq!(info.contributors, vec!["Alice", "Bob"]);
+        assert_eq!(history[&b].updated_at.timestamp(), 1_700_100_000);
+        assert!(!history.contains_key(&untracked));
+    }
+}
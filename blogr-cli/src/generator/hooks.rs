@@ -0,0 +1,266 @@
+//! Build and deploy hooks
+//!
+//! Runs the shell commands listed under `[hooks]` in blogr.toml, then the matching
+//! hook of every enabled plugin, so asset pipelines like Tailwind or esbuild can
+//! take part in builds without changes to blogr itself.
+
+use crate::config::Config;
+use crate::generator::output;
+use crate::newsletter::{create_plugin_context, PluginHook, PluginManager};
+use crate::project::Project;
+use crate::utils::Console;
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::process::Command;
+use std::sync::Arc;
+
+/// Point in a build or deploy where hooks run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    /// Before the output directory is cleaned and the site generated
+    PreBuild,
+    /// After the site has been written to the output directory
+    PostBuild,
+    /// After the built site has been pushed
+    PostDeploy,
+}
+
+impl HookStage {
+    /// Key of the command list in `[hooks]`
+    pub fn name(self) -> &'static str {
+        match self {
+            HookStage::PreBuild => "pre_build",
+            HookStage::PostBuild => "post_build",
+            HookStage::PostDeploy => "post_deploy",
+        }
+    }
+
+    fn plugin_hook(self) -> PluginHook {
+        match self {
+            HookStage::PreBuild => PluginHook::PreBuild,
+            HookStage::PostBuild => PluginHook::PostBuild,
+            HookStage::PostDeploy => PluginHook::PostDeploy,
+        }
+    }
+
+    fn commands(self, config: &Config) -> &[String] {
+        match self {
+            HookStage::PreBuild => &config.hooks.pre_build,
+            HookStage::PostBuild => &config.hooks.post_build,
+            HookStage::PostDeploy => &config.hooks.post_deploy,
+        }
+    }
+}
+
+/// Runs the hooks for one build or deploy
+pub struct HookRunner<'a> {
+    project: &'a Project,
+    config: &'a Config,
+    output_dir: &'a Path,
+    /// Extra `BLOGR_*` environment variables and plugin input, e.g. the deploy branch
+    extra: Vec<(&'static str, String)>,
+}
+
+impl<'a> HookRunner<'a> {
+    pub fn new(project: &'a Project, config: &'a Config, output_dir: &'a Path) -> Self {
+        Self {
+            project,
+            config,
+            output_dir,
+            extra: Vec::new(),
+        }
+    }
+
+    /// Pass an extra value to hooks, as `BLOGR_<NAME>` to commands and `name` to plugins
+    pub fn with(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.extra.push((name, value.into()));
+        self
+    }
+
+    /// Run the commands and plugins for a stage. A command that fails stops the
+    /// run with an error.
+    pub fn run(&self, stage: HookStage) -> Result<()> {
+        let commands = stage.commands(self.config);
+        // Commands work on files, and a site built in memory has none
+        if stage == HookStage::PostBuild && output::is_mounted(self.output_dir) {
+            if !commands.is_empty() {
+                Console::warn("Skipping post-build hook commands, the site is built in memory");
+            }
+        } else {
+            for command in commands {
+                self.run_command(stage, command)?;
+            }
+        }
+        self.run_plugins(stage)
+    }
+
+    fn run_command(&self, stage: HookStage, command: &str) -> Result<()> {
+        crate::status!("🪝 {}: {}", stage.name(), command);
+
+        let mut process = shell(command);
+        process
+            .current_dir(&self.project.root)
+            .env("BLOGR_HOOK", stage.name())
+            .env("BLOGR_PROJECT_ROOT", &self.project.root)
+            .env("BLOGR_OUTPUT_DIR", self.output_dir)
+            .env("BLOGR_BASE_URL", &self.config.blog.base_url);
+        for (name, value) in &self.extra {
+            process.env(format!("BLOGR_{}", name.to_uppercase()), value);
+        }
+
+        let status = process
+            .status()
+            .with_context(|| format!("Failed to run {} hook: {}", stage.name(), command))?;
+        if !status.success() {
+            bail!("{} hook failed ({}): {}", stage.name(), status, command);
+        }
+        Ok(())
+    }
+
+    fn run_plugins(&self, stage: HookStage) -> Result<()> {
+        let plugins = PluginManager::for_project(self.config, &self.project.root)?;
+        let hook = stage.plugin_hook();
+
+        let mut data = HashMap::new();
+        data.insert(
+            "base_url".to_string(),
+            serde_json::json!(self.config.blog.base_url),
+        );
+        if stage == HookStage::PostBuild {
+            data.insert(
+                "files".to_string(),
+                serde_json::json!(output_files(self.output_dir)),
+            );
+        }
+        for (name, value) in &self.extra {
+            data.insert(name.to_string(), serde_json::json!(value));
+        }
+        let context = create_plugin_context(
+            Arc::new(self.config.clone()),
+            None,
+            self.project.root.clone(),
+            hook.clone(),
+            data,
+        );
+
+        for result in plugins.execute_hook(hook, &context)? {
+            if let Some(message) = &result.message {
+                crate::status!("🔌 {}", message);
+            }
+            if stage == HookStage::PostBuild {
+                self.write_plugin_files(&result.data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the `files` a post-build plugin returned into the output directory
+    fn write_plugin_files(&self, data: &HashMap<String, serde_json::Value>) -> Result<()> {
+        let files = data.get("files").and_then(|files| files.as_array());
+        for file in files.into_iter().flatten() {
+            let (Some(path), Some(contents)) = (
+                file.get("path").and_then(|p| p.as_str()),
+                file.get("contents").and_then(|c| c.as_str()),
+            ) else {
+                Console::warn("Plugin returned a file without a path or contents");
+                continue;
+            };
+
+            // Plugins may only write inside the output directory
+            let relative = Path::new(path);
+            if !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                Console::warn(&format!("Plugin tried to write outside the site: {}", path));
+                continue;
+            }
+
+            let target = self.output_dir.join(relative);
+            if let Some(parent) = target.parent() {
+                output::create_dir_all(parent)?;
+            }
+            output::write(&target, contents)
+                .with_context(|| format!("Failed to write {}", target.display()))?;
+            crate::status!("🔌 Plugin wrote {}", path);
+        }
+
+        Ok(())
+    }
+}
+
+/// Paths of every file in the output directory, relative to it
+fn output_files(output_dir: &Path) -> Vec<String> {
+    output::files(output_dir)
+        .into_iter()
+        .filter_map(|path| {
+            let relative = path.strip_prefix(output_dir).ok()?;
+            Some(relative.to_string_lossy().replace('\\', "/"))
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut process = Command::new("sh");
+    process.arg("-c").arg(command);
+    process
+}
+
+#[cfg(windows)]
+pub(crate) fn shell(command: &str) -> Command {
+    let mut process = Command::new("cmd");
+    process.arg("/C").arg(command);
+    process
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(root: &Path) -> Project {
+        Project::new(root.to_path_buf(), Config::default())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commands_run_in_order_with_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("dist");
+        std::fs::create_dir_all(&output).unwrap();
+
+        let mut config = Config::default();
+        config.hooks.post_build = vec![
+            "echo \"$BLOGR_HOOK $BLOGR_BRANCH\" > hooks.log".to_string(),
+            "ls \"$BLOGR_OUTPUT_DIR\" >> hooks.log".to_string(),
+        ];
+        std::fs::write(output.join("index.html"), "").unwrap();
+
+        let project = project(dir.path());
+        HookRunner::new(&project, &config, &output)
+            .with("branch", "gh-pages")
+            .run(HookStage::PostBuild)
+            .unwrap();
+
+        let log = std::fs::read_to_string(dir.path().join("hooks.log")).unwrap();
+        assert_eq!(log, "post_build gh-pages\nindex.html\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_command_stops_the_build() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.hooks.pre_build = vec!["exit 3".to_string(), "touch ran".to_string()];
+
+        let project = project(dir.path());
+        let error = HookRunner::new(&project, &config, dir.path())
+            .run(HookStage::PreBuild)
+            .unwrap_err();
+
+        assert!(error.to_string().contains("pre_build hook failed"));
+        assert!(!dir.path().join("ran").exists());
+    }
+}
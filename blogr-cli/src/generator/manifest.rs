@@ -0,0 +1,212 @@
+//! Integrity manifest of the built site
+//!
+//! With `[deploy.verify]` enabled, builds write `blogr-manifest.json`: the
+//! SHA-256 of every file in the output. After a deploy, a random sample of the
+//! files is fetched from the live site and compared with it, so a deploy that
+//! never went live, or only partly, fails with a report instead of passing
+//! silently.
+
+use crate::config::VerifyConfig;
+use crate::generator::output;
+use crate::utils::{Console, Utils};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// File name of the manifest, at the root of the output
+pub const MANIFEST_FILE: &str = "blogr-manifest.json";
+
+/// Hashes of the files of one build
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub generated: DateTime<Utc>,
+    /// Output path, relative to the site root, to its hex SHA-256
+    pub files: BTreeMap<String, String>,
+}
+
+/// A sampled file the live site serves differently
+#[derive(Debug)]
+pub struct Mismatch {
+    pub path: String,
+    pub problem: String,
+}
+
+impl Manifest {
+    /// Hash every file in `dir`. Host configuration files like `_headers` and
+    /// `CNAME` are left out, since hosts don't serve them.
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        for file in output::files(dir) {
+            let path = file.strip_prefix(dir)?.to_string_lossy().replace('\\', "/");
+            if !is_served(&path) {
+                continue;
+            }
+            let bytes = output::read(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            files.insert(path, Utils::hex(&Sha256::digest(&bytes)));
+        }
+        Ok(Self {
+            generated: Utc::now(),
+            files,
+        })
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        output::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(self)? + "\n",
+        )
+        .map_err(|e| anyhow!("Failed to write {}: {}", MANIFEST_FILE, e))
+    }
+
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let text = output::read_to_string(&path).with_context(|| {
+            format!(
+                "{} not found. Enable [deploy.verify] and build the site first.",
+                path.display()
+            )
+        })?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Up to `size` paths picked at random, always with the home page
+    pub fn sample(&self, size: usize) -> Vec<String> {
+        let mut paths: Vec<&String> = self.files.keys().collect();
+        paths.sort_by_cached_key(|path| (path.as_str() != "index.html", Uuid::new_v4()));
+        paths.into_iter().take(size).cloned().collect()
+    }
+
+    /// Fetch `paths` from the site at `base_url`, returning the ones that
+    /// are missing or differ from the build
+    pub async fn check(
+        &self,
+        client: &reqwest::Client,
+        base_url: &str,
+        paths: &[String],
+    ) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+        for path in paths {
+            let url = format!("{}/{}", base_url.trim_end_matches('/'), path);
+            let problem = match client.get(&url).send().await {
+                Ok(response) if response.status().is_success() => match response.bytes().await {
+                    Ok(body)
+                        if self.files.get(path) == Some(&Utils::hex(&Sha256::digest(&body))) =>
+                    {
+                        continue
+                    }
+                    Ok(_) => "content differs from the build".to_string(),
+                    Err(e) => format!("could not read the response: {}", e),
+                },
+                Ok(response) => format!("HTTP {}", response.status()),
+                Err(e) => format!("could not fetch: {}", e),
+            };
+            mismatches.push(Mismatch {
+                path: path.clone(),
+                problem,
+            });
+        }
+        mismatches
+    }
+}
+
+/// Compare a sample of the live site with the build, retrying while the host
+/// catches up. Fails with a list of the files that still don't match.
+pub async fn verify(manifest: &Manifest, base_url: &str, config: &VerifyConfig) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .user_agent("blogr-cli")
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let mut pending = manifest.sample(config.sample);
+    let total = pending.len();
+    let attempts = config.attempts.max(1);
+
+    for attempt in 1..=attempts {
+        Console::info(&format!(
+            "Checking {} of {} files on {} (attempt {}/{})...",
+            pending.len(),
+            manifest.files.len(),
+            base_url,
+            attempt,
+            attempts
+        ));
+        let mismatches = manifest.check(&client, base_url, &pending).await;
+        if mismatches.is_empty() {
+            Console::success(&format!(
+                "The live site matches the build ({} files checked)",
+                total
+            ));
+            return Ok(());
+        }
+        if attempt == attempts {
+            let report: Vec<String> = mismatches
+                .iter()
+                .map(|mismatch| format!("  {}: {}", mismatch.path, mismatch.problem))
+                .collect();
+            bail!(
+                "The live site doesn't match the build ({} of {} files):\n{}",
+                mismatches.len(),
+                total,
+                report.join("\n")
+            );
+        }
+        pending = mismatches
+            .into_iter()
+            .map(|mismatch| mismatch.path)
+            .collect();
+        tokio::time::sleep(Duration::from_secs(config.wait)).await;
+    }
+    Ok(())
+}
+
+/// Whether hosts serve a file of the output, rather than reading it as configuration
+fn is_served(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    !(name.starts_with('_')
+        || name.starts_with('.')
+        || matches!(name, "CNAME" | "vercel.json" | MANIFEST_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("posts")).unwrap();
+        fs::write(dir.join("index.html"), "home").unwrap();
+        fs::write(dir.join("posts/hello.html"), "hello").unwrap();
+        fs::write(dir.join("_headers"), "/*").unwrap();
+        fs::write(dir.join("CNAME"), "example.com").unwrap();
+
+        let manifest = Manifest::from_dir(dir).unwrap();
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            ["index.html", "posts/hello.html"]
+        );
+        assert_eq!(
+            manifest.files["posts/hello.html"],
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+
+        manifest.write(dir).unwrap();
+        let loaded = Manifest::load(dir).unwrap();
+        assert_eq!(loaded.files, manifest.files);
+        assert!(!Manifest::from_dir(dir)
+            .unwrap()
+            .files
+            .contains_key(MANIFEST_FILE));
+
+        assert_eq!(manifest.sample(1), ["index.html"]);
+        assert_eq!(manifest.sample(10).len(), 2);
+    }
+}
@@ -1,22 +1,58 @@
+use crate::config::MarkdownConfig;
+use crate::generator::emoji;
 use anyhow::Result;
-use pulldown_cmark::{html, Event, Options, Parser, Tag};
+use pulldown_cmark::{html, Event, LinkType, Options, Parser, Tag};
 use syntect::highlighting::ThemeSet;
 use syntect::html::highlighted_html_for_string;
 use syntect::parsing::SyntaxSet;
+use url::Url;
 
 /// Render markdown to HTML with syntax highlighting
 pub fn render_markdown(markdown: &str) -> Result<String> {
+    render_markdown_with(markdown, &MarkdownConfig::default(), None, |_| None)
+}
+
+/// Render markdown to HTML with the site's `[markdown]` options, replacing the
+/// destination of each link and image for which `rewrite` returns a new one.
+/// With `external_links`, links to hosts other than `site_host` are marked.
+pub fn render_markdown_with(
+    markdown: &str,
+    config: &MarkdownConfig,
+    site_host: Option<&str>,
+    rewrite: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_FOOTNOTES);
     options.insert(Options::ENABLE_TASKLISTS);
+    // Code spans and blocks are never changed by smart punctuation
+    if config.smart_punctuation {
+        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    }
 
-    let parser = Parser::new_ext(markdown, options);
+    let mut parser: Box<dyn Iterator<Item = Event>> = Box::new(Parser::new_ext(markdown, options));
+    if config.emoji {
+        parser = Box::new(replace_emoji(parser));
+    }
 
     // Process events to add syntax highlighting
     let events: Vec<Event> = parser
-        .map(|event| process_code_blocks(event))
+        .map(|event| match event {
+            Event::Start(Tag::Link(kind, dest, title)) => {
+                let dest = rewrite(&dest).map(Into::into).unwrap_or(dest);
+                if config.external_links && kind != LinkType::Email && is_external(&dest, site_host)
+                {
+                    return Ok(Event::Html(external_link(&dest, &title).into()));
+                }
+                Ok(Event::Start(Tag::Link(kind, dest, title)))
+            }
+            Event::Start(Tag::Image(kind, dest, title)) => {
+                let dest = rewrite(&dest).map(Into::into).unwrap_or(dest);
+                Ok(Event::Start(Tag::Image(kind, dest, title)))
+            }
+            event => process_code_blocks(event),
+        })
         .collect::<Result<Vec<_>, _>>()?;
 
     let mut html_output = String::new();
@@ -25,6 +61,68 @@ pub fn render_markdown(markdown: &str) -> Result<String> {
     Ok(html_output)
 }
 
+/// Whether a link goes to another site: an absolute http(s) URL on another host
+fn is_external(dest: &str, site_host: Option<&str>) -> bool {
+    let Ok(url) = Url::parse(dest) else {
+        return false;
+    };
+    matches!(url.scheme(), "http" | "https")
+        && url
+            .host_str()
+            .is_some_and(|host| site_host.is_none_or(|site| !host.eq_ignore_ascii_case(site)))
+}
+
+/// Opening tag of a link to another site. The parser closes it with `</a>` as usual.
+fn external_link(dest: &str, title: &str) -> String {
+    let title = if title.is_empty() {
+        String::new()
+    } else {
+        format!(r#" title="{}""#, html_escape(title))
+    };
+    format!(
+        r#"<a href="{}"{} class="external" rel="external noopener">"#,
+        html_escape(dest),
+        title
+    )
+}
+
+/// Replace `:emoji:` shortcodes in text outside code blocks. Text is joined
+/// first, since the parser may split a shortcode over several events.
+fn replace_emoji<'a>(events: impl Iterator<Item = Event<'a>>) -> impl Iterator<Item = Event<'a>> {
+    let mut output: Vec<Event> = Vec::new();
+    let mut in_code_block = false;
+    for event in events {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(text) if !in_code_block => {
+                if let Some(Event::Text(previous)) = output.last_mut() {
+                    *previous = format!("{}{}", previous, text).into();
+                } else {
+                    output.push(Event::Text(text));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        output.push(event);
+    }
+
+    let mut in_code_block = false;
+    output.into_iter().map(move |event| match event {
+        Event::Start(Tag::CodeBlock(_)) => {
+            in_code_block = true;
+            event
+        }
+        Event::End(Tag::CodeBlock(_)) => {
+            in_code_block = false;
+            event
+        }
+        Event::Text(text) if !in_code_block => Event::Text(emoji::replace_shortcodes(&text).into()),
+        event => event,
+    })
+}
+
 /// Process code blocks to add syntax highlighting
 fn process_code_blocks(event: Event) -> Result<Event, anyhow::Error> {
     match event {
@@ -95,7 +193,7 @@ fn highlight_code(code: &str, language: &str) -> Result<String> {
 }
 
 /// HTML escape text
-fn html_escape(text: &str) -> String {
+pub fn html_escape(text: &str) -> String {
     text.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -113,6 +211,10 @@ pub fn markdown_to_text(markdown: &str) -> String {
         match event {
             Event::Text(t) | Event::Code(t) => text.push_str(&t),
             Event::SoftBreak | Event::HardBreak => text.push(' '),
+            // Keep the last word of a block apart from the first of the next
+            Event::End(
+                Tag::Paragraph | Tag::Heading(..) | Tag::Item | Tag::CodeBlock(_) | Tag::TableCell,
+            ) => text.push(' '),
             _ => {}
         }
     }
@@ -133,3 +235,52 @@ pub fn extract_excerpt(markdown: &str, word_limit: usize) -> String {
         excerpt
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emoji_and_smart_punctuation() {
+        let config = MarkdownConfig {
+            emoji: true,
+            smart_punctuation: true,
+            ..MarkdownConfig::default()
+        };
+        let markdown = "\"Done\" -- it works... :white_check_mark:\n\n\
+            Run `echo \"a\" -- b... :tada:` now.\n\n\
+            ```sh\necho \"quoted\" -- :rocket: ...\n```\n";
+        let html = render_markdown_with(markdown, &config, None, |_| None).unwrap();
+
+        assert!(html.contains("“Done” – it works… ✅"));
+        assert!(html.contains("<code>echo &quot;a&quot; -- b... :tada:</code>"));
+        assert!(html.contains("echo &quot;quoted&quot; -- :rocket: ..."));
+
+        // Both are off by default
+        let plain = render_markdown(markdown).unwrap();
+        assert!(plain.contains("&quot;Done&quot; -- it works... :white_check_mark:"));
+    }
+
+    #[test]
+    fn test_external_links() {
+        let config = MarkdownConfig {
+            external_links: true,
+            ..MarkdownConfig::default()
+        };
+        let markdown =
+            "[Rust](https://www.rust-lang.org \"Home\"), [me](https://example.com/about), \
+            [a post](posts/a.html) and <mail@example.org>";
+        let html = render_markdown_with(markdown, &config, Some("example.com"), |_| None).unwrap();
+
+        assert!(html.contains(
+            r#"<a href="https://www.rust-lang.org" title="Home" class="external" rel="external noopener">Rust</a>"#
+        ));
+        assert!(html.contains(r#"<a href="https://example.com/about">me</a>"#));
+        assert!(html.contains(r#"<a href="posts/a.html">a post</a>"#));
+        assert!(html.contains(r#"<a href="mailto:mail@example.org">"#));
+
+        // Off by default
+        let plain = render_markdown(markdown).unwrap();
+        assert!(!plain.contains("external"));
+    }
+}
@@ -0,0 +1,117 @@
+//! Microformats2 markup for themes
+//!
+//! Exposed to templates as `h_card()` and `h_entry_meta(post=post)`, so a theme
+//! only needs `class="h-entry"` on its post wrapper and `class="e-content"` on
+//! the rendered content for IndieWeb readers and webmention receivers to parse it.
+
+use crate::config::Config;
+use crate::generator::markdown::html_escape;
+use serde_json::Value;
+
+/// Author h-card linking to the site's home page
+pub fn h_card(config: &Config, author: Option<&str>) -> String {
+    let author = author
+        .filter(|author| !author.trim().is_empty())
+        .unwrap_or(&config.blog.author);
+    let home = format!("{}/", config.get_effective_base_url().trim_end_matches('/'));
+    let photo = config
+        .indieweb
+        .author_photo
+        .as_deref()
+        .map(|photo| {
+            format!(
+                r#"<img class="u-photo" src="{}" alt="">"#,
+                html_escape(photo)
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"<a class="p-author h-card" href="{}">{}{}</a>"#,
+        html_escape(&home),
+        photo,
+        html_escape(author)
+    )
+}
+
+/// Hidden h-entry properties for a post: name, permalink, dates, author,
+/// summary and categories
+pub fn h_entry_meta(config: &Config, post: &Value) -> String {
+    let metadata = &post["metadata"];
+    let text = |key: &str| metadata[key].as_str().unwrap_or_default();
+
+    let url = config.page_url(&format!("posts/{}.html", text("slug")));
+    let mut html = format!(
+        r#"<div class="h-entry-meta" hidden><span class="p-name">{}</span><a class="u-url" href="{}">{}</a><time class="dt-published" datetime="{}">{}</time>"#,
+        html_escape(text("title")),
+        html_escape(&url),
+        html_escape(&url),
+        html_escape(text("date")),
+        html_escape(text("date")),
+    );
+
+    if let Some(updated) = metadata["updated_at"].as_str() {
+        html.push_str(&format!(
+            r#"<time class="dt-updated" datetime="{}">{}</time>"#,
+            html_escape(updated),
+            html_escape(updated)
+        ));
+    }
+
+    html.push_str(&h_card(config, Some(text("author"))));
+
+    if !text("description").is_empty() {
+        html.push_str(&format!(
+            r#"<p class="p-summary">{}</p>"#,
+            html_escape(text("description"))
+        ));
+    }
+
+    for tag in metadata["tags"].as_array().into_iter().flatten() {
+        if let Some(tag) = tag.as_str() {
+            html.push_str(&format!(
+                r#"<a class="p-category" href="{}">{}</a>"#,
+                html_escape(&config.page_url(&format!("tags/{}.html", tag))),
+                html_escape(tag)
+            ));
+        }
+    }
+
+    html.push_str("</div>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_h_entry_meta() {
+        let mut config = Config::default();
+        config.blog.base_url = "https://blog.example.com".to_string();
+        config.indieweb.author_photo = Some("https://blog.example.com/me.jpg".to_string());
+
+        let post = serde_json::json!({
+            "metadata": {
+                "title": "Hello <World>",
+                "slug": "hello",
+                "date": "2024-05-01T00:00:00Z",
+                "updated_at": null,
+                "author": "",
+                "description": "",
+                "tags": ["rust"]
+            }
+        });
+
+        assert_eq!(
+            h_entry_meta(&config, &post),
+            concat!(
+                r#"<div class="h-entry-meta" hidden><span class="p-name">Hello &lt;World&gt;</span>"#,
+                r#"<a class="u-url" href="https://blog.example.com/posts/hello.html">https://blog.example.com/posts/hello.html</a>"#,
+                r#"<time class="dt-published" datetime="2024-05-01T00:00:00Z">2024-05-01T00:00:00Z</time>"#,
+                r#"<a class="p-author h-card" href="https://blog.example.com/"><img class="u-photo" src="https://blog.example.com/me.jpg" alt="">Anonymous</a>"#,
+                r#"<a class="p-category" href="https://blog.example.com/tags/rust.html">rust</a></div>"#,
+            )
+        );
+    }
+}
@@ -1,7 +1,46 @@
+pub mod activitypub;
+pub mod archive;
 pub mod assets;
+pub mod bundles;
+pub mod contact;
+pub mod content_api;
+pub mod covers;
+pub mod dependencies;
+pub mod docs_nav;
+pub mod domains;
+pub mod embeds;
+pub mod emoji;
+pub mod error_pages;
+pub mod feeds;
+pub mod formats;
+pub mod gallery;
+pub mod git_metadata;
+pub mod hooks;
+pub mod manifest;
 pub mod markdown;
+pub mod microformats;
+pub mod output;
+pub mod pages;
+pub mod private;
+pub mod reader;
+pub mod redirects;
+pub mod resume;
+pub mod robots;
+pub mod scss;
 pub mod search_index;
+pub mod security;
+pub mod shortcodes;
 pub mod site;
+pub mod sitemap;
+pub mod slugs;
+pub mod stopwords;
+pub mod structured_data;
+pub mod template_errors;
+pub mod template_helpers;
+pub mod theme_lint;
+pub mod video;
 
+pub use hooks::{HookRunner, HookStage};
+pub use redirects::RedirectGenerator;
 pub use search_index::SearchIndexer;
 pub use site::SiteBuilder;
@@ -0,0 +1,227 @@
+//! Writing the built site
+//!
+//! Builds write their output through these functions rather than `std::fs`.
+//! Normally they go straight to disk. `blogr serve --in-memory` mounts the
+//! output directory in RAM with [`mount`] instead: everything written under it
+//! lands in a [`MemoryDir`] that the server answers from, and nothing of the
+//! site touches the disk. Inputs, like posts and theme files, are still read
+//! with `std::fs`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Directories mounted in memory
+static MOUNTS: RwLock<Vec<Arc<MemoryDir>>> = RwLock::new(Vec::new());
+
+/// The files of an output directory held in RAM
+#[derive(Debug)]
+pub struct MemoryDir {
+    root: PathBuf,
+    /// File contents by path relative to the root
+    files: RwLock<BTreeMap<PathBuf, Arc<Vec<u8>>>>,
+}
+
+impl MemoryDir {
+    /// The contents of the file at `relative`
+    pub fn get(&self, relative: &Path) -> Option<Arc<Vec<u8>>> {
+        self.files.read().unwrap().get(relative).cloned()
+    }
+
+    /// Number of files and their total size in bytes
+    pub fn usage(&self) -> (usize, usize) {
+        let files = self.files.read().unwrap();
+        (files.len(), files.values().map(|file| file.len()).sum())
+    }
+
+    fn is_dir(&self, relative: &Path) -> bool {
+        let files = self.files.read().unwrap();
+        files
+            .range(relative.to_path_buf()..)
+            .next()
+            .is_some_and(|(path, _)| path.starts_with(relative) && path != relative)
+    }
+
+    fn remove_all(&self, relative: &Path) {
+        self.files
+            .write()
+            .unwrap()
+            .retain(|path, _| !path.starts_with(relative));
+    }
+}
+
+/// Keep everything written under `root` in memory from now on
+pub fn mount(root: &Path) -> Arc<MemoryDir> {
+    let dir = Arc::new(MemoryDir {
+        root: root.to_path_buf(),
+        files: RwLock::default(),
+    });
+    MOUNTS.write().unwrap().push(dir.clone());
+    dir
+}
+
+/// Whether `path` is in a directory mounted in memory
+pub fn is_mounted(path: &Path) -> bool {
+    mounted(path).is_some()
+}
+
+/// The memory directory holding `path`, and the path relative to it
+fn mounted(path: &Path) -> Option<(Arc<MemoryDir>, PathBuf)> {
+    MOUNTS.read().unwrap().iter().find_map(|dir| {
+        let relative = path.strip_prefix(&dir.root).ok()?;
+        Some((dir.clone(), relative.to_path_buf()))
+    })
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} not found in memory", path.display()),
+    )
+}
+
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let path = path.as_ref();
+    match mounted(path) {
+        Some((dir, relative)) => {
+            dir.files
+                .write()
+                .unwrap()
+                .insert(relative, Arc::new(contents.as_ref().to_vec()));
+            Ok(())
+        }
+        None => fs::write(path, contents),
+    }
+}
+
+/// Directories in memory exist as long as they have files, so this only
+/// creates directories on disk
+pub fn create_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if is_mounted(path) {
+        return Ok(());
+    }
+    fs::create_dir_all(path)
+}
+
+/// Copy a file from disk into the output
+pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    let to = to.as_ref();
+    if is_mounted(to) {
+        return write(to, fs::read(from)?);
+    }
+    fs::copy(from, to).map(|_| ())
+}
+
+pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+    let path = path.as_ref();
+    match mounted(path) {
+        Some((dir, relative)) => dir
+            .get(&relative)
+            .map(|contents| contents.to_vec())
+            .ok_or_else(|| not_found(path)),
+        None => fs::read(path),
+    }
+}
+
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    String::from_utf8(read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn remove_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    match mounted(path) {
+        Some((dir, relative)) => dir
+            .files
+            .write()
+            .unwrap()
+            .remove(&relative)
+            .map(|_| ())
+            .ok_or_else(|| not_found(path)),
+        None => fs::remove_file(path),
+    }
+}
+
+pub fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    match mounted(path) {
+        Some((dir, relative)) => {
+            dir.remove_all(&relative);
+            Ok(())
+        }
+        None => fs::remove_dir_all(path),
+    }
+}
+
+pub fn is_file<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    match mounted(path) {
+        Some((dir, relative)) => dir.get(&relative).is_some(),
+        None => path.is_file(),
+    }
+}
+
+pub fn is_dir<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    match mounted(path) {
+        Some((dir, relative)) => dir.is_dir(&relative),
+        None => path.is_dir(),
+    }
+}
+
+pub fn exists<P: AsRef<Path>>(path: P) -> bool {
+    is_file(&path) || is_dir(&path)
+}
+
+/// Every file under `dir`, at any depth
+pub fn files(dir: &Path) -> Vec<PathBuf> {
+    match mounted(dir) {
+        Some((memory, relative)) => memory
+            .files
+            .read()
+            .unwrap()
+            .keys()
+            .filter(|path| path.starts_with(&relative))
+            .map(|path| memory.root.join(path))
+            .collect(),
+        None => walkdir::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_dir() {
+        let root = Path::new("/blogr-test-output");
+        let dir = mount(root);
+
+        create_dir_all(root.join("posts")).unwrap();
+        write(root.join("index.html"), "home").unwrap();
+        write(root.join("posts/hello.html"), "hello").unwrap();
+        assert!(!Path::new("/blogr-test-output").exists());
+
+        assert_eq!(
+            read_to_string(root.join("posts/hello.html")).unwrap(),
+            "hello"
+        );
+        assert!(is_dir(root.join("posts")) && is_dir(root));
+        assert!(is_file(root.join("index.html")) && !is_dir(root.join("index.html")));
+        assert!(!exists(root.join("post")));
+        assert_eq!(files(&root.join("posts")), [root.join("posts/hello.html")]);
+        assert_eq!(dir.usage(), (2, 9));
+
+        remove_file(root.join("index.html")).unwrap();
+        assert!(remove_file(root.join("index.html")).is_err());
+        remove_dir_all(root).unwrap();
+        assert_eq!(dir.usage(), (0, 0));
+    }
+}
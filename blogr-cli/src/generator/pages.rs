@@ -0,0 +1,192 @@
+//! Standalone pages for personal sites
+//!
+//! Each markdown file in `content/` becomes its own page next to the home page
+//! built from `content.md`: `content/projects.md` is published at
+//! `/projects/` and `content/writing/talks.md` at `/writing/talks/`.
+//! Frontmatter is optional; without a `title` a leading `# ` heading or the
+//! file name is used.
+
+use crate::utils::Utils;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// A page from `content/`
+#[derive(Debug, Clone, Serialize)]
+pub struct Page {
+    /// URL path without slashes at either end, e.g. `projects` or `writing/talks`
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// Position in the navigation, lowest first
+    pub order: i64,
+    /// Whether the page is listed in the navigation
+    pub nav: bool,
+    /// Keep search engines from indexing the page and leave it out of the sitemap
+    pub noindex: bool,
+    /// Markdown body
+    #[serde(skip)]
+    pub content: String,
+    /// Any other frontmatter keys, for the theme to use
+    pub extra: serde_yaml::Mapping,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PageFrontmatter {
+    title: Option<String>,
+    description: Option<String>,
+    slug: Option<String>,
+    order: Option<i64>,
+    nav: Option<bool>,
+    noindex: Option<bool>,
+    #[serde(flatten)]
+    extra: serde_yaml::Mapping,
+}
+
+impl Page {
+    /// Output file for the page, relative to the site root
+    pub fn output_path(&self) -> String {
+        format!("{}/index.html", self.slug)
+    }
+
+    /// Parse a page; `relative_path` is the file's path inside `content/`
+    pub fn parse(source: &str, relative_path: &Path) -> Result<Self> {
+        let (frontmatter, body) = match source
+            .strip_prefix("---\n")
+            .or_else(|| source.strip_prefix("---\r\n"))
+            .and_then(|rest| rest.split_once("\n---\n"))
+        {
+            Some((yaml, body)) => {
+                let frontmatter: PageFrontmatter = serde_yaml::from_str(yaml).map_err(|e| {
+                    anyhow!(
+                        "Failed to parse frontmatter of content/{}: {}",
+                        relative_path.display(),
+                        e
+                    )
+                })?;
+                (frontmatter, body)
+            }
+            None => (PageFrontmatter::default(), source),
+        };
+
+        let default_slug = relative_path
+            .with_extension("")
+            .components()
+            .map(|part| Utils::slugify(&part.as_os_str().to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join("/");
+        let slug = frontmatter
+            .slug
+            .map(|slug| slug.trim_matches('/').to_string())
+            .unwrap_or(default_slug);
+        if slug.is_empty() || slug == "index" {
+            return Err(anyhow!(
+                "content/{} would replace the home page; content.md is the home page",
+                relative_path.display()
+            ));
+        }
+
+        // A leading `# ` heading becomes the title so it isn't shown twice
+        let mut content = body.to_string();
+        let title = match frontmatter.title {
+            Some(title) => title,
+            None => match body.trim_start().strip_prefix("# ") {
+                Some(rest) => {
+                    let (heading, rest) = rest.split_once('\n').unwrap_or((rest, ""));
+                    content = rest.to_string();
+                    heading.trim().to_string()
+                }
+                None => relative_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().replace(['-', '_'], " "))
+                    .unwrap_or_default(),
+            },
+        };
+
+        Ok(Self {
+            slug,
+            title,
+            description: frontmatter.description,
+            order: frontmatter.order.unwrap_or(0),
+            nav: frontmatter.nav.unwrap_or(true),
+            noindex: frontmatter.noindex.unwrap_or(false),
+            content,
+            extra: frontmatter.extra,
+        })
+    }
+}
+
+/// Load every page in `content_dir`, in navigation order
+pub fn load_pages(content_dir: &Path) -> Result<Vec<Page>> {
+    if !content_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut pages: Vec<Page> = Vec::new();
+    for entry in WalkDir::new(content_dir) {
+        let entry = entry?;
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+
+        let relative_path = path.strip_prefix(content_dir)?;
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let page = Page::parse(&source, relative_path)?;
+        if let Some(other) = pages.iter().find(|other| other.slug == page.slug) {
+            return Err(anyhow!(
+                "Two pages in content/ use the URL /{}/ ('{}' and '{}')",
+                page.slug,
+                other.title,
+                page.title
+            ));
+        }
+        pages.push(page);
+    }
+
+    pages.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.slug.cmp(&b.slug)));
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_pages() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("writing")).unwrap();
+        fs::write(
+            dir.path().join("projects.md"),
+            "---\ntitle: Things I Built\norder: 2\ncolor: teal\n---\nSome projects\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("about.md"), "# About Me\n\nHello").unwrap();
+        fs::write(
+            dir.path().join("writing/Talks.md"),
+            "---\nnav: false\norder: 1\n---\nSlides\n",
+        )
+        .unwrap();
+
+        let pages = load_pages(dir.path()).unwrap();
+        let slugs: Vec<&str> = pages.iter().map(|page| page.slug.as_str()).collect();
+        assert_eq!(slugs, ["about", "writing/talks", "projects"]);
+
+        assert_eq!(pages[0].title, "About Me");
+        assert_eq!(pages[0].content.trim(), "Hello");
+        assert_eq!(pages[1].title, "Talks");
+        assert!(!pages[1].nav);
+        assert_eq!(pages[2].title, "Things I Built");
+        assert_eq!(pages[2].extra["color"], "teal");
+        assert_eq!(pages[2].output_path(), "projects/index.html");
+    }
+
+    #[test]
+    fn test_index_page_is_rejected() {
+        assert!(Page::parse("Home", Path::new("index.md")).is_err());
+    }
+}
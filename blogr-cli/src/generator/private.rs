@@ -0,0 +1,149 @@
+//! Password-protected posts
+//!
+//! The text of a `private: true` post is encrypted at build time with
+//! AES-256-GCM, under a key derived from its password with PBKDF2-SHA256.
+//! Its page carries the ciphertext and a password form, and `js/private.js`
+//! decrypts it in the browser with the Web Crypto API. The title, date and
+//! tags stay visible; listings show a note instead of the text, and the post
+//! is left out of feeds, the search index and the sitemap.
+
+use crate::config::PrivateConfig;
+use crate::content::Post;
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::num::NonZeroU32;
+
+/// The unlock script, relative to the site root
+pub const PRIVATE_SCRIPT: &str = "js/private.js";
+
+/// Environment variable with the password of private posts that don't set one
+pub const PASSWORD_ENV: &str = "BLOGR_PRIVATE_PASSWORD";
+
+/// Shown in listings in place of a private post's summary
+pub const PRIVATE_NOTE: &str = "<p class=\"blogr-private-note\">This post is private.</p>";
+
+const SALT_LEN: usize = 16;
+
+/// Encrypted HTML and what the browser needs to decrypt it, base64-encoded
+#[derive(Debug)]
+pub struct Sealed {
+    pub salt: String,
+    pub iv: String,
+    pub data: String,
+    pub iterations: u32,
+}
+
+/// The password of a private post: its own, or the site's from `BLOGR_PRIVATE_PASSWORD`
+pub fn password(post: &Post) -> Result<String> {
+    let password = post
+        .metadata
+        .password
+        .clone()
+        .or_else(|| std::env::var(PASSWORD_ENV).ok())
+        .filter(|password| !password.is_empty());
+    match password {
+        Some(password) => Ok(password),
+        None => bail!(
+            "Post '{}' is private but has no password. Set `password` in its frontmatter or {} for the site.",
+            post.metadata.slug,
+            PASSWORD_ENV
+        ),
+    }
+}
+
+/// Encrypt `html` under `password`, with a fresh salt and IV
+pub fn seal(html: &str, password: &str, config: &PrivateConfig) -> Result<Sealed> {
+    let iterations = NonZeroU32::new(config.iterations)
+        .ok_or_else(|| anyhow!("private.iterations must be greater than 0"))?;
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut iv = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut iv))
+        .map_err(|_| anyhow!("Failed to generate random bytes for a private post"))?;
+
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        &salt,
+        password.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&AES_256_GCM, &key)
+        .map(LessSafeKey::new)
+        .map_err(|_| anyhow!("Failed to create the key for a private post"))?;
+
+    // Web Crypto expects the tag after the ciphertext, as ring appends it
+    let mut data = html.as_bytes().to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(iv), Aad::empty(), &mut data)
+        .map_err(|_| anyhow!("Failed to encrypt a private post"))?;
+
+    Ok(Sealed {
+        salt: STANDARD.encode(salt),
+        iv: STANDARD.encode(iv),
+        data: STANDARD.encode(data),
+        iterations: config.iterations,
+    })
+}
+
+/// The password form standing in for a private post's content
+pub fn locked_html(sealed: &Sealed, script_url: &str) -> String {
+    format!(
+        r#"<div class="blogr-private" data-blogr-private="{}" data-salt="{}" data-iv="{}" data-iterations="{}">
+  <form class="blogr-private-form">
+    <p>This post is private. Enter the password to read it.</p>
+    <input type="password" name="password" autocomplete="current-password" aria-label="Password" required>
+    <button type="submit">Unlock</button>
+    <p class="blogr-private-error" role="alert" hidden>That password didn't work.</p>
+  </form>
+  <noscript><p>Reading this post needs JavaScript.</p></noscript>
+</div>
+<script src="{}" defer></script>"#,
+        sealed.data, sealed.salt, sealed.iv, sealed.iterations, script_url
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(sealed: &Sealed, password: &str) -> Option<String> {
+        let mut key = [0u8; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(sealed.iterations).unwrap(),
+            &STANDARD.decode(&sealed.salt).unwrap(),
+            password.as_bytes(),
+            &mut key,
+        );
+        let key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).unwrap());
+        let iv: [u8; NONCE_LEN] = STANDARD.decode(&sealed.iv).unwrap().try_into().unwrap();
+        let mut data = STANDARD.decode(&sealed.data).unwrap();
+        let plain = key
+            .open_in_place(Nonce::assume_unique_for_key(iv), Aad::empty(), &mut data)
+            .ok()?;
+        Some(String::from_utf8(plain.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn test_seal_round_trip() {
+        let config = PrivateConfig { iterations: 1000 };
+        let html = "<p>Only for friends</p>";
+        let sealed = seal(html, "hunter2", &config).unwrap();
+
+        assert_eq!(open(&sealed, "hunter2").as_deref(), Some(html));
+        assert_eq!(open(&sealed, "wrong"), None);
+        assert!(!sealed.data.contains("friends"));
+
+        let again = seal(html, "hunter2", &config).unwrap();
+        assert_ne!(sealed.salt, again.salt);
+
+        let page = locked_html(&sealed, "/js/private.js");
+        assert!(page.contains(&format!("data-salt=\"{}\"", sealed.salt)));
+        assert!(page.contains("data-iterations=\"1000\""));
+    }
+}
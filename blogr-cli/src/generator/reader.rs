@@ -0,0 +1,64 @@
+//! Print stylesheet and plain copies of posts
+//!
+//! With `[reader] print_css`, post pages link a print stylesheet that hides
+//! the theme's navigation, forms and embeds, whatever the theme. With
+//! `[reader] plain`, each post is also written without the theme to
+//! `posts/<slug>/plain.html`, for saving and archiving; opening a post with
+//! `?plain=1` goes there.
+
+/// Where the print stylesheet is written
+pub const PRINT_STYLESHEET: &str = "css/blogr-print.css";
+
+pub const PRINT_CSS: &str = include_str!("../../static/css/print.css");
+
+/// Path of a post's plain copy
+pub fn plain_path(slug: &str) -> String {
+    format!("posts/{}/plain.html", slug)
+}
+
+/// Tags for the head of a post page: the print stylesheet, and a link to the
+/// plain copy with a script following `?plain=1` to it
+pub fn head_tags(print_css: Option<&str>, plain: Option<&str>) -> String {
+    let mut tags = Vec::new();
+    if let Some(href) = print_css {
+        tags.push(format!(
+            r#"<link rel="stylesheet" href="{}" media="print">"#,
+            href
+        ));
+    }
+    if let Some(href) = plain {
+        tags.push(format!(
+            r#"<link rel="alternate" type="text/html" title="Plain version" href="{}">"#,
+            href
+        ));
+        // serde_json quotes the URL as a JS string
+        tags.push(format!(
+            r#"<script>if (new URLSearchParams(location.search).get("plain") === "1") location.replace({});</script>"#,
+            serde_json::Value::from(href)
+        ));
+    }
+    tags.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_head_tags() {
+        assert_eq!(head_tags(None, None), "");
+        assert_eq!(
+            head_tags(Some("/css/blogr-print.css"), None),
+            r#"<link rel="stylesheet" href="/css/blogr-print.css" media="print">"#
+        );
+
+        let url = format!("https://example.com/{}", plain_path("hello"));
+        let tags = head_tags(None, Some(&url));
+        assert!(tags.starts_with(
+            r#"<link rel="alternate" type="text/html" title="Plain version" href="https://example.com/posts/hello/plain.html">"#
+        ));
+        assert!(tags.ends_with(
+            r#"location.replace("https://example.com/posts/hello/plain.html");</script>"#
+        ));
+    }
+}
@@ -0,0 +1,290 @@
+use crate::config::UrlConfig;
+use crate::content::Post;
+use crate::generator::domains;
+use crate::generator::markdown::html_escape;
+use crate::generator::output;
+use crate::utils::Console;
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// Generates redirect stubs for post aliases and `[redirects]` entries
+pub struct RedirectGenerator {
+    /// Base URL used to build absolute redirect targets
+    base_url: String,
+    /// URL style used for post targets
+    urls: UrlConfig,
+    /// Domains redirected to the base URL as a whole
+    alias_domains: Vec<String>,
+}
+
+impl RedirectGenerator {
+    /// Create a new redirect generator
+    pub fn new(base_url: String, urls: UrlConfig) -> Self {
+        Self {
+            base_url,
+            urls,
+            alias_domains: Vec::new(),
+        }
+    }
+
+    /// Also redirect these domains to the base URL, in `_redirects`
+    pub fn with_alias_domains(mut self, aliases: Vec<String>) -> Self {
+        self.alias_domains = aliases;
+        self
+    }
+
+    /// Collect all redirects as a map of source path to target path
+    pub fn collect(
+        &self,
+        posts: &[Post],
+        extra: &HashMap<String, String>,
+    ) -> BTreeMap<String, String> {
+        let mut redirects = BTreeMap::new();
+
+        for post in posts {
+            let target = format!(
+                "/{}",
+                self.urls
+                    .page_path(&format!("posts/{}.html", post.metadata.slug))
+            );
+            for alias in &post.metadata.aliases {
+                if let Some(source) = checked_source(alias) {
+                    redirects.insert(source, target.clone());
+                }
+            }
+        }
+
+        // Explicit config entries win over post aliases
+        for (from, to) in extra {
+            if let Some(source) = checked_source(from) {
+                redirects.insert(source, to.clone());
+            }
+        }
+
+        redirects
+    }
+
+    /// Write meta-refresh HTML stubs and a Netlify `_redirects` file
+    pub fn generate(
+        &self,
+        posts: &[Post],
+        extra: &HashMap<String, String>,
+        output_dir: &Path,
+    ) -> Result<()> {
+        let redirects = self.collect(posts, extra);
+        if redirects.is_empty() && self.alias_domains.is_empty() {
+            return Ok(());
+        }
+
+        // Domain rules come first, since Netlify uses the first match
+        let mut netlify = domains::redirect_rules(&self.alias_domains, &self.base_url);
+        let mut written = 0;
+
+        for (from, to) in &redirects {
+            netlify.push_str(&format!("{} {} 301\n", from, to));
+
+            let stub_path = output_dir.join(stub_file_for(from));
+            if output::exists(&stub_path) {
+                Console::warn(&format!(
+                    "Skipping redirect '{}': a page already exists at that path",
+                    from
+                ));
+                continue;
+            }
+
+            if let Some(parent) = stub_path.parent() {
+                output::create_dir_all(parent)?;
+            }
+            output::write(&stub_path, self.render_stub(to))
+                .map_err(|e| anyhow!("Failed to write redirect for '{}': {}", from, e))?;
+            written += 1;
+        }
+
+        output::write(output_dir.join("_redirects"), netlify)
+            .map_err(|e| anyhow!("Failed to write _redirects file: {}", e))?;
+
+        if !redirects.is_empty() {
+            crate::status!("↪️  Generated {} redirect pages", written);
+        }
+        if !self.alias_domains.is_empty() {
+            crate::status!(
+                "↪️  Redirecting {} to {}",
+                self.alias_domains.join(", "),
+                self.base_url
+            );
+        }
+        Ok(())
+    }
+
+    /// Render a meta-refresh HTML page pointing at the target
+    fn render_stub(&self, target: &str) -> String {
+        let url = if target.starts_with("http://") || target.starts_with("https://") {
+            target.to_string()
+        } else {
+            format!(
+                "{}/{}",
+                self.base_url.trim_end_matches('/'),
+                target.trim_start_matches('/')
+            )
+        };
+        let url = html_escape(&url);
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Redirecting&hellip;</title>
+    <link rel="canonical" href="{url}">
+    <meta name="robots" content="noindex">
+    <meta http-equiv="refresh" content="0; url={url}">
+</head>
+<body>
+    <p>This page has moved to <a href="{url}">{url}</a>.</p>
+</body>
+</html>
+"#
+        )
+    }
+}
+
+/// Ensure a redirect source is a root-relative path
+fn normalize_source(path: &str) -> String {
+    let trimmed = path.trim();
+    if trimmed.starts_with('/') {
+        trimmed.to_string()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
+/// The normalized source, or `None` with a warning if it isn't a plain path
+/// inside the site: its stub would be written outside the output directory
+fn checked_source(path: &str) -> Option<String> {
+    let source = normalize_source(path);
+    let relative = source.trim_start_matches('/');
+    let plain = !relative.is_empty()
+        && !relative.contains('\\')
+        && !relative.contains(char::is_whitespace)
+        && Path::new(relative)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !plain {
+        Console::warn(&format!(
+            "Skipping redirect '{}': sources must be paths inside the site",
+            path
+        ));
+        return None;
+    }
+    Some(source)
+}
+
+/// Map a redirect source path to the file that should hold its stub
+fn stub_file_for(source: &str) -> PathBuf {
+    let relative = source.trim_start_matches('/');
+    if !relative.ends_with('/') && Path::new(relative).extension().is_some() {
+        PathBuf::from(relative)
+    } else {
+        PathBuf::from(relative).join("index.html")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::{PostMetadata, PostStatus};
+    use chrono::Utc;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_post(slug: &str, aliases: &[&str]) -> Post {
+        Post {
+            metadata: PostMetadata {
+                title: "Test Post".to_string(),
+                date: Utc::now(),
+                author: "Test Author".to_string(),
+                description: "A test post".to_string(),
+                tags: vec![],
+                status: PostStatus::Published,
+                slug: slug.to_string(),
+                featured: false,
+                aliases: aliases.iter().map(|a| a.to_string()).collect(),
+                updated_at: None,
+                contributors: Vec::new(),
+                crosspost: Default::default(),
+                video: None,
+                link: None,
+                cover: None,
+                cover_alt: None,
+                noindex: false,
+                private: false,
+                password: None,
+            },
+            content: "Content".to_string(),
+            file_path: PathBuf::from(format!("{}.md", slug)),
+        }
+    }
+
+    #[test]
+    fn test_stub_file_paths() {
+        assert_eq!(
+            stub_file_for("/old-url/"),
+            PathBuf::from("old-url/index.html")
+        );
+        assert_eq!(
+            stub_file_for("/old-url"),
+            PathBuf::from("old-url/index.html")
+        );
+        assert_eq!(
+            stub_file_for("/posts/old.html"),
+            PathBuf::from("posts/old.html")
+        );
+    }
+
+    #[test]
+    fn test_generate_redirects() {
+        let temp_dir = TempDir::new().unwrap();
+        let post = create_test_post("new-slug", &["/old-slug/", "legacy.html"]);
+        let mut extra = HashMap::new();
+        extra.insert("/about-me/".to_string(), "/about.html".to_string());
+
+        let generator = RedirectGenerator::new(
+            "https://example.com/blog/".to_string(),
+            UrlConfig::default(),
+        );
+        generator
+            .generate(&[post], &extra, temp_dir.path())
+            .unwrap();
+
+        let stub = fs::read_to_string(temp_dir.path().join("old-slug/index.html")).unwrap();
+        assert!(stub.contains("url=https://example.com/blog/posts/new-slug.html"));
+        assert!(temp_dir.path().join("legacy.html").exists());
+        assert!(temp_dir.path().join("about-me/index.html").exists());
+
+        let netlify = fs::read_to_string(temp_dir.path().join("_redirects")).unwrap();
+        assert!(netlify.contains("/old-slug/ /posts/new-slug.html 301"));
+        assert!(netlify.contains("/legacy.html /posts/new-slug.html 301"));
+        assert!(netlify.contains("/about-me/ /about.html 301"));
+    }
+
+    #[test]
+    fn test_sources_outside_the_site_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = temp_dir.path().join("_site");
+        let post = create_test_post("new-slug", &["../../escaped", "/a/../../escaped.html", "/"]);
+        let mut extra = HashMap::new();
+        extra.insert("..\\escaped".to_string(), "/about.html".to_string());
+        extra.insert("/kept/".to_string(), "/about.html".to_string());
+
+        let generator =
+            RedirectGenerator::new("https://example.com/".to_string(), UrlConfig::default());
+        let redirects = generator.collect(std::slice::from_ref(&post), &extra);
+        assert_eq!(redirects.keys().collect::<Vec<_>>(), ["/kept/"]);
+
+        generator.generate(&[post], &extra, &output_dir).unwrap();
+        assert!(output_dir.join("kept/index.html").exists());
+        assert!(!temp_dir.path().join("escaped/index.html").exists());
+        assert!(!temp_dir.path().join("escaped.html").exists());
+    }
+}
@@ -0,0 +1,296 @@
+//! Resume page from structured data
+//!
+//! Personal sites can keep their CV in `resume.yaml` (or `resume.json`) using
+//! the [JSON Resume](https://jsonresume.org/schema) schema. It is rendered to
+//! `/resume/` with the theme's `resume.html`, and optionally printed to
+//! `resume.pdf` with a headless Chromium-based browser.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Files checked for resume data, in order
+pub const RESUME_FILES: &[&str] = &["resume.yaml", "resume.yml", "resume.json"];
+
+/// Output page of the resume, relative to the site root
+pub const RESUME_PAGE: &str = "resume/index.html";
+
+/// Browsers tried for PDF export when `[resume] browser` is not set
+const BROWSERS: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "microsoft-edge",
+];
+
+/// A resume in the JSON Resume schema. Field names keep the schema's
+/// camelCase, so templates use `job.startDate` as JSON Resume themes do.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Resume {
+    pub basics: Basics,
+    pub work: Vec<Work>,
+    pub volunteer: Vec<Volunteer>,
+    pub education: Vec<Education>,
+    pub awards: Vec<Award>,
+    pub certificates: Vec<Certificate>,
+    pub publications: Vec<Publication>,
+    pub skills: Vec<Skill>,
+    pub languages: Vec<Language>,
+    pub interests: Vec<Interest>,
+    pub references: Vec<Reference>,
+    pub projects: Vec<Project>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Basics {
+    pub name: String,
+    pub label: Option<String>,
+    pub image: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub url: Option<String>,
+    pub summary: Option<String>,
+    pub location: Option<Location>,
+    pub profiles: Vec<Profile>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Location {
+    pub address: Option<String>,
+    pub postal_code: Option<String>,
+    pub city: Option<String>,
+    pub country_code: Option<String>,
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Profile {
+    pub network: String,
+    pub username: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Work {
+    pub name: String,
+    pub position: Option<String>,
+    pub url: Option<String>,
+    pub location: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub summary: Option<String>,
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Volunteer {
+    pub organization: String,
+    pub position: Option<String>,
+    pub url: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub summary: Option<String>,
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Education {
+    pub institution: String,
+    pub url: Option<String>,
+    pub area: Option<String>,
+    pub study_type: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub score: Option<String>,
+    pub courses: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Award {
+    pub title: String,
+    pub date: Option<String>,
+    pub awarder: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Certificate {
+    pub name: String,
+    pub date: Option<String>,
+    pub issuer: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Publication {
+    pub name: String,
+    pub publisher: Option<String>,
+    pub release_date: Option<String>,
+    pub url: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Skill {
+    pub name: String,
+    pub level: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Language {
+    pub language: String,
+    pub fluency: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Interest {
+    pub name: String,
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Reference {
+    pub name: String,
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Project {
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub roles: Vec<String>,
+    pub highlights: Vec<String>,
+    pub keywords: Vec<String>,
+}
+
+/// Path of the project's resume data, if it has one
+pub fn find_resume(project_root: &Path) -> Option<PathBuf> {
+    RESUME_FILES
+        .iter()
+        .map(|name| project_root.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Load the project's resume data, if it has any
+pub fn load_resume(project_root: &Path) -> Result<Option<Resume>> {
+    let Some(path) = find_resume(project_root) else {
+        return Ok(None);
+    };
+
+    let source =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let resume: Resume = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&source)
+            .map_err(|e| anyhow!("Invalid resume in {}: {}", path.display(), e))?
+    } else {
+        serde_yaml::from_str(&source)
+            .map_err(|e| anyhow!("Invalid resume in {}: {}", path.display(), e))?
+    };
+
+    if resume.basics.name.trim().is_empty() {
+        return Err(anyhow!(
+            "{} needs a name under 'basics'",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+    }
+
+    Ok(Some(resume))
+}
+
+/// Print an HTML file to PDF with a headless Chromium-based browser
+pub fn print_pdf(html: &Path, pdf: &Path, browser: Option<&str>) -> Result<()> {
+    let browser = match browser {
+        Some(browser) => browser.to_string(),
+        None => BROWSERS
+            .iter()
+            .find(|candidate| Command::new(candidate).arg("--version").output().is_ok())
+            .map(|candidate| candidate.to_string())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No Chromium-based browser found for PDF export. \
+                     Install Chromium or set [resume] browser in blogr.toml."
+                )
+            })?,
+    };
+
+    let output = Command::new(&browser)
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg("--no-pdf-header-footer")
+        .arg(format!("--print-to-pdf={}", pdf.display()))
+        .arg(format!("file://{}", html.display()))
+        .output()
+        .with_context(|| format!("Failed to run {}", browser))?;
+
+    if !output.status.success() || !pdf.exists() {
+        return Err(anyhow!(
+            "{} could not print the resume: {}",
+            browser,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_resume() {
+        let dir = TempDir::new().unwrap();
+        assert!(load_resume(dir.path()).unwrap().is_none());
+
+        fs::write(
+            dir.path().join("resume.yaml"),
+            "basics:\n  name: Ada Lovelace\n  label: Analyst\n  profiles:\n    - network: GitHub\n      url: https://github.com/ada\n\
+             work:\n  - name: Analytical Engine\n    position: Programmer\n    startDate: 1842-01\n    highlights: [First program]\n\
+             skills:\n  - name: Mathematics\n    keywords: [Analysis]\n",
+        )
+        .unwrap();
+
+        let resume = load_resume(dir.path()).unwrap().unwrap();
+        assert_eq!(resume.basics.name, "Ada Lovelace");
+        assert_eq!(resume.work[0].start_date.as_deref(), Some("1842-01"));
+        assert_eq!(resume.skills[0].keywords, ["Analysis"]);
+
+        // Templates see the schema's own field names
+        let value = serde_json::to_value(&resume).unwrap();
+        assert_eq!(value["work"][0]["startDate"], "1842-01");
+    }
+
+    #[test]
+    fn test_resume_needs_a_name() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("resume.json"),
+            r#"{"basics": {"label": "Analyst"}}"#,
+        )
+        .unwrap();
+        assert!(load_resume(dir.path()).is_err());
+    }
+}
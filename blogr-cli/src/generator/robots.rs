@@ -0,0 +1,117 @@
+//! `robots.txt` and `noindex` pages
+//!
+//! Posts and pages with `noindex: true` in their frontmatter get a robots
+//! meta tag and are left out of the sitemap. Builds with a profile that
+//! disallows all crawlers, such as `--profile preview`, mark every page.
+
+use crate::config::Config;
+use crate::generator::output;
+use crate::generator::structured_data::insert_in_head;
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+const NOINDEX_META: &str = r#"<meta name="robots" content="noindex">"#;
+
+/// Contents of `robots.txt` for a build with the given profile
+pub fn robots_txt(config: &Config, profile: Option<&str>) -> String {
+    let robots = &config.robots;
+    if robots.disallows_all(profile) {
+        return "User-agent: *\nDisallow: /\n".to_string();
+    }
+
+    let mut text = String::from("User-agent: *\n");
+    push_rules(&mut text, &robots.allow, &robots.disallow);
+    for agent in &robots.agents {
+        text.push_str(&format!("\nUser-agent: {}\n", agent.user_agent.trim()));
+        push_rules(&mut text, &agent.allow, &agent.disallow);
+    }
+    text.push_str(&format!("\nSitemap: {}\n", config.page_url("sitemap.xml")));
+    text
+}
+
+/// Allow and Disallow lines; an empty Disallow allows everything
+fn push_rules(text: &mut String, allow: &[String], disallow: &[String]) {
+    for path in allow {
+        text.push_str(&format!("Allow: {}\n", path));
+    }
+    for path in disallow {
+        text.push_str(&format!("Disallow: {}\n", path));
+    }
+    if disallow.is_empty() {
+        text.push_str("Disallow:\n");
+    }
+}
+
+/// Write `robots.txt` to the root of the output
+pub fn generate_robots_txt(
+    config: &Config,
+    profile: Option<&str>,
+    output_dir: &Path,
+) -> Result<()> {
+    if !config.robots.enabled {
+        return Ok(());
+    }
+    output::write(output_dir.join("robots.txt"), robots_txt(config, profile))
+        .map_err(|e| anyhow!("Failed to write robots.txt: {}", e))
+}
+
+/// Add a `noindex` robots meta tag to the head of a page
+pub fn add_noindex(html: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    if lower.contains(r#"name="robots""#) {
+        return html.to_string();
+    }
+    insert_in_head(html, NOINDEX_META)
+}
+
+/// Mark every HTML page in the output `noindex`
+pub fn noindex_all(output_dir: &Path) -> Result<()> {
+    for path in output::files(output_dir)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+    {
+        let html = output::read_to_string(&path)?;
+        output::write(&path, add_noindex(&html))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{RobotsAgent, RobotsProfile};
+
+    #[test]
+    fn test_robots_txt() {
+        let mut config = Config::default();
+        config.blog.base_url = "https://example.com".to_string();
+        assert_eq!(
+            robots_txt(&config, None),
+            "User-agent: *\nDisallow:\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+
+        config.robots.disallow = vec!["/drafts/".to_string()];
+        config.robots.agents.push(RobotsAgent {
+            user_agent: "GPTBot".to_string(),
+            allow: Vec::new(),
+            disallow: vec!["/".to_string()],
+        });
+        let text = robots_txt(&config, Some("production"));
+        assert!(text
+            .starts_with("User-agent: *\nDisallow: /drafts/\n\nUser-agent: GPTBot\nDisallow: /\n"));
+
+        assert_eq!(
+            robots_txt(&config, Some("preview")),
+            "User-agent: *\nDisallow: /\n"
+        );
+        config
+            .robots
+            .profiles
+            .insert("preview".to_string(), RobotsProfile::default());
+        assert!(robots_txt(&config, Some("preview")).contains("Sitemap:"));
+
+        let html = add_noindex("<html><head><title>T</title></head><body></body></html>");
+        assert!(html.contains(&format!("{}\n</head>", NOINDEX_META)));
+        assert_eq!(add_noindex(&html), html);
+    }
+}
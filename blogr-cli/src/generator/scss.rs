@@ -0,0 +1,208 @@
+//! Sass/SCSS compilation for theme assets and `static/`
+//!
+//! Every `.scss` file whose name does not start with `_` is compiled to a
+//! `.css` file next to it; `_partials` are only used through `@use` and
+//! `@import`. Development builds keep the output expanded and write a source
+//! map listing the original files. grass does not track positions, so the map
+//! carries the sources without line mappings.
+
+use anyhow::{anyhow, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Compiled stylesheet and, for development builds, its source map
+pub struct CompiledCss {
+    pub css: String,
+    pub source_map: Option<String>,
+}
+
+/// Whether a path is a Sass entry point that should be compiled
+pub fn is_entry(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "scss")
+        && !path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('_'))
+}
+
+/// Name of the compiled file, e.g. `css/main.scss` -> `css/main.css`
+pub fn output_path(path: &Path) -> PathBuf {
+    path.with_extension("css")
+}
+
+/// Compile one entry point. `fs` resolves the entry and its imports;
+/// `source_name` turns a resolved path into the name shown in the source map.
+pub fn compile(
+    entry: &Path,
+    fs: &dyn grass::Fs,
+    dev: bool,
+    source_name: impl Fn(&Path) -> String,
+) -> Result<CompiledCss> {
+    let recorder = RecordingFs {
+        inner: fs,
+        read: RefCell::new(Vec::new()),
+    };
+    let style = if dev {
+        grass::OutputStyle::Expanded
+    } else {
+        grass::OutputStyle::Compressed
+    };
+    let options = grass::Options::default().fs(&recorder).style(style);
+
+    let mut css = grass::from_path(entry, &options)
+        .map_err(|e| anyhow!("Failed to compile {}\n{}", source_name(entry), e))?;
+
+    if !dev {
+        return Ok(CompiledCss {
+            css,
+            source_map: None,
+        });
+    }
+
+    let map_name = format!(
+        "{}.map",
+        output_path(entry)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    );
+    let (sources, contents): (Vec<String>, Vec<String>) = recorder
+        .read
+        .into_inner()
+        .into_iter()
+        .map(|(path, content)| (source_name(&path), content))
+        .unzip();
+    let source_map = serde_json::json!({
+        "version": 3,
+        "file": map_name.trim_end_matches(".map"),
+        "sources": sources,
+        "sourcesContent": contents,
+        "names": [],
+        "mappings": "",
+    });
+
+    if !css.ends_with('\n') {
+        css.push('\n');
+    }
+    css.push_str(&format!("/*# sourceMappingURL={} */\n", map_name));
+    Ok(CompiledCss {
+        css,
+        source_map: Some(serde_json::to_string(&source_map)?),
+    })
+}
+
+/// Keeps the files the compiler read, in order, for the source map
+#[derive(Debug)]
+struct RecordingFs<'a> {
+    inner: &'a dyn grass::Fs,
+    read: RefCell<Vec<(PathBuf, String)>>,
+}
+
+impl grass::Fs for RecordingFs<'_> {
+    fn is_dir(&self, path: &Path) -> bool {
+        self.inner.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.inner.is_file(path)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let content = self.inner.read(path)?;
+        let mut read = self.read.borrow_mut();
+        if !read.iter().any(|(seen, _)| seen == path) {
+            read.push((
+                path.to_path_buf(),
+                String::from_utf8_lossy(&content).into_owned(),
+            ));
+        }
+        Ok(content)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.inner.canonicalize(path)
+    }
+}
+
+/// Built-in theme assets, which live in memory rather than on disk
+#[derive(Debug)]
+pub struct ThemeAssetsFs<'a> {
+    assets: &'a HashMap<String, Vec<u8>>,
+}
+
+impl<'a> ThemeAssetsFs<'a> {
+    pub fn new(assets: &'a HashMap<String, Vec<u8>>) -> Self {
+        Self { assets }
+    }
+
+    fn key(path: &Path) -> String {
+        path.to_string_lossy()
+            .replace('\\', "/")
+            .trim_start_matches("./")
+            .to_string()
+    }
+}
+
+impl grass::Fs for ThemeAssetsFs<'_> {
+    fn is_dir(&self, path: &Path) -> bool {
+        let prefix = format!("{}/", Self::key(path));
+        self.assets.keys().any(|key| key.starts_with(&prefix))
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.assets.contains_key(&Self::key(path))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.assets.get(&Self::key(path)).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not a theme asset", path.display()),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_theme_assets() {
+        let assets = HashMap::from([
+            (
+                "css/_colors.scss".to_string(),
+                b"$accent: #ff6b35;".to_vec(),
+            ),
+            (
+                "css/style.scss".to_string(),
+                b"@import 'colors';\na { color: $accent; &:hover { color: red; } }".to_vec(),
+            ),
+        ]);
+        let fs = ThemeAssetsFs::new(&assets);
+        let name = |path: &Path| format!("theme/{}", path.display());
+
+        let compiled = compile(Path::new("css/style.scss"), &fs, false, name).unwrap();
+        assert_eq!(compiled.css, "a{color:#ff6b35}a:hover{color:red}");
+        assert!(compiled.source_map.is_none());
+
+        let compiled = compile(Path::new("css/style.scss"), &fs, true, name).unwrap();
+        assert!(compiled
+            .css
+            .ends_with("/*# sourceMappingURL=style.css.map */\n"));
+        let map: serde_json::Value = serde_json::from_str(&compiled.source_map.unwrap()).unwrap();
+        assert_eq!(map["file"], "style.css");
+        assert_eq!(
+            map["sources"],
+            serde_json::json!(["theme/css/style.scss", "theme/css/_colors.scss"])
+        );
+    }
+
+    #[test]
+    fn test_is_entry() {
+        assert!(is_entry(Path::new("css/main.scss")));
+        assert!(!is_entry(Path::new("css/_partial.scss")));
+        assert!(!is_entry(Path::new("css/main.css")));
+    }
+}
@@ -1,9 +1,30 @@
 use crate::content::Post;
-use crate::generator::markdown;
+use crate::generator::output;
+use crate::generator::pages::Page;
+use crate::generator::{markdown, stopwords};
+use crate::utils::Console;
 use anyhow::Result;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The index search.js loads: every document, or the list of chunks
+const INDEX_FILE: &str = "search_index.json";
+
+/// Name of the `n`th chunk of a split index
+fn chunk_file(n: usize) -> String {
+    format!("search_index.{}.json", n)
+}
+
+/// `path` with a compression extension appended, as servers look for it
+fn compressed_path(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
 
 /// Search document schema for the index
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,16 +49,36 @@ pub struct SearchDocument {
 
 // Re-export SearchConfig from the main config module
 pub use crate::config::SearchConfig;
+use crate::config::UrlConfig;
 
 /// Search index generator
 pub struct SearchIndexer {
     config: SearchConfig,
+    urls: UrlConfig,
+    /// Pages from `content/`, indexed along with the posts
+    pages: Vec<Page>,
 }
 
 impl SearchIndexer {
     /// Create a new search indexer with custom configuration
     pub fn new(config: SearchConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            urls: UrlConfig::default(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// Use the given URL style when generating document URLs
+    pub fn with_url_config(mut self, urls: UrlConfig) -> Self {
+        self.urls = urls;
+        self
+    }
+
+    /// Also index these pages, as documentation sites need
+    pub fn with_pages(mut self, pages: &[Page]) -> Self {
+        self.pages = pages.to_vec();
+        self
     }
 
     /// Generate search index from posts
@@ -46,7 +87,7 @@ impl SearchIndexer {
             return Ok(());
         }
 
-        println!("🔍 Generating search index...");
+        crate::status!("🔍 Generating search index...");
 
         // Convert posts to search documents
         let mut documents = Vec::new();
@@ -56,22 +97,98 @@ impl SearchIndexer {
                 documents.push(document);
             }
         }
+        for page in self.pages.iter().filter(|page| !page.noindex) {
+            documents.push(self.page_to_search_document(page));
+        }
+
+        let documents = documents
+            .iter()
+            .map(|document| self.to_json(document))
+            .collect::<Result<Vec<_>>>()?;
+
+        for encoding in &self.config.precompress {
+            if encoding != "gzip" {
+                Console::warn(&format!(
+                    "Not precompressing the search index with '{}': only gzip is supported",
+                    encoding
+                ));
+            }
+        }
 
-        // Serialize to JSON (minified or pretty based on config)
-        let json_content = if self.config.minify {
-            serde_json::to_string(&documents)
-                .map_err(|e| anyhow::anyhow!("Failed to serialize search index: {}", e))?
+        let chunk_size = self.config.chunk_size;
+        let chunks = if chunk_size == 0 || documents.len() <= chunk_size {
+            self.write_file(&output_dir.join(INDEX_FILE), &documents)?;
+            0
         } else {
-            serde_json::to_string_pretty(&documents)
-                .map_err(|e| anyhow::anyhow!("Failed to serialize search index: {}", e))?
+            // The index file lists the chunks, which search.js loads one by one
+            let names: Vec<String> = documents
+                .chunks(chunk_size)
+                .enumerate()
+                .map(|(i, chunk)| {
+                    let name = chunk_file(i + 1);
+                    self.write_file(&output_dir.join(&name), chunk)?;
+                    Ok(name)
+                })
+                .collect::<Result<_>>()?;
+            let manifest = serde_json::json!({
+                "chunks": names,
+                "documents": documents.len(),
+            });
+            self.write_file(&output_dir.join(INDEX_FILE), &manifest)?;
+            names.len()
         };
 
-        // Write to output directory
-        let index_file = output_dir.join("search_index.json");
-        fs::write(&index_file, json_content)
-            .map_err(|e| anyhow::anyhow!("Failed to write search index: {}", e))?;
+        // Chunks of an earlier, larger index
+        let mut stale = chunks + 1;
+        while output::exists(output_dir.join(chunk_file(stale))) {
+            let path = output_dir.join(chunk_file(stale));
+            output::remove_file(&path)?;
+            let _ = output::remove_file(compressed_path(&path, "gz"));
+            stale += 1;
+        }
 
-        println!("✅ Search index generated: {} documents", documents.len());
+        if chunks > 0 {
+            crate::status!(
+                "✅ Search index generated: {} documents in {} chunks",
+                documents.len(),
+                chunks
+            );
+        } else {
+            crate::status!("✅ Search index generated: {} documents", documents.len());
+        }
+        Ok(())
+    }
+
+    /// A document as JSON, without the fields in `exclude_fields`
+    fn to_json(&self, document: &SearchDocument) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(document)?;
+        if let Some(fields) = value.as_object_mut() {
+            for field in &self.config.exclude_fields {
+                fields.remove(field);
+            }
+        }
+        Ok(value)
+    }
+
+    /// Write one index file, minified or pretty, with its compressed copies
+    fn write_file<T: Serialize + ?Sized>(&self, path: &Path, value: &T) -> Result<()> {
+        let json = if self.config.minify {
+            serde_json::to_string(value)
+        } else {
+            serde_json::to_string_pretty(value)
+        }
+        .map_err(|e| anyhow::anyhow!("Failed to serialize search index: {}", e))?;
+
+        for encoding in &self.config.precompress {
+            if encoding == "gzip" {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+                encoder.write_all(json.as_bytes())?;
+                output::write(compressed_path(path, "gz"), encoder.finish()?)
+                    .map_err(|e| anyhow::anyhow!("Failed to write search index: {}", e))?;
+            }
+        }
+        output::write(path, json)
+            .map_err(|e| anyhow::anyhow!("Failed to write search index: {}", e))?;
         Ok(())
     }
 
@@ -86,7 +203,7 @@ impl SearchIndexer {
         }
 
         // Only include published posts
-        post.metadata.status == crate::content::PostStatus::Published
+        post.metadata.status.is_public()
     }
 
     /// Convert a post to a search document
@@ -99,29 +216,22 @@ impl SearchIndexer {
             plain_text = self.remove_stopwords(&plain_text);
         }
 
-        // Truncate content if needed
-        let content = if plain_text.len() > self.config.max_content_chars {
-            let truncated = &plain_text[..self.config.max_content_chars];
-            // Find the last complete word
-            if let Some(last_space) = truncated.rfind(' ') {
-                format!("{}...", &truncated[..last_space])
-            } else {
-                format!("{}...", truncated)
-            }
-        } else {
-            plain_text
-        };
+        let content = self.truncate(plain_text);
 
         // Generate excerpt
         let excerpt = markdown::extract_excerpt(&post.content, self.config.excerpt_words);
 
         // Generate URL path
-        let url = format!("/posts/{}.html", post.metadata.slug);
+        let url = format!(
+            "/{}",
+            self.urls
+                .page_path(&format!("posts/{}.html", post.metadata.slug))
+        );
 
         Ok(SearchDocument {
             id: post.metadata.slug.clone(),
             url,
-            title: post.metadata.title.clone(),
+            title: post.display_title(),
             tags: post.metadata.tags.clone(),
             date: post.metadata.date.format("%Y-%m-%d").to_string(),
             description: post.metadata.description.clone(),
@@ -130,23 +240,61 @@ impl SearchIndexer {
         })
     }
 
-    /// Remove common English stopwords from text
+    /// Cut indexed text down to `max_content_chars`, at a word boundary
+    fn truncate(&self, plain_text: String) -> String {
+        let Some((end, _)) = plain_text.char_indices().nth(self.config.max_content_chars) else {
+            return plain_text;
+        };
+        let truncated = &plain_text[..end];
+        // Find the last complete word
+        if let Some(last_space) = truncated.rfind(' ') {
+            format!("{}...", &truncated[..last_space])
+        } else {
+            format!("{}...", truncated)
+        }
+    }
+
+    /// Convert a page from `content/` to a search document
+    fn page_to_search_document(&self, page: &Page) -> SearchDocument {
+        let mut plain_text = markdown::markdown_to_text(&page.content);
+        if self.config.remove_stopwords {
+            plain_text = self.remove_stopwords(&plain_text);
+        }
+
+        SearchDocument {
+            id: format!("page:{}", page.slug),
+            url: format!("/{}", self.urls.page_path(&page.output_path())),
+            title: page.title.clone(),
+            tags: Vec::new(),
+            date: String::new(),
+            description: page.description.clone().unwrap_or_default(),
+            content: self.truncate(plain_text),
+            excerpt: markdown::extract_excerpt(&page.content, self.config.excerpt_words),
+        }
+    }
+
+    /// Get the stop words for the configured language, including custom ones
+    pub fn stop_words(&self) -> Vec<String> {
+        let language = self.config.language.as_deref().unwrap_or("en");
+        let mut words: Vec<String> = stopwords::for_language(language)
+            .iter()
+            .map(|w| w.to_string())
+            .chain(self.config.stop_words.iter().map(|w| w.to_lowercase()))
+            .collect();
+        words.sort();
+        words.dedup();
+        words
+    }
+
+    /// Remove stopwords for the configured language from text
     fn remove_stopwords(&self, text: &str) -> String {
-        // Common English stopwords
-        const STOPWORDS: &[&str] = &[
-            "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "he", "in",
-            "is", "it", "its", "of", "on", "that", "the", "to", "was", "will", "with", "but", "or",
-            "not", "this", "these", "those", "they", "their", "them", "we", "our", "us", "you",
-            "your", "i", "me", "my", "mine", "have", "had", "do", "does", "did", "can", "could",
-            "should", "would", "may", "might", "must", "shall", "will", "am", "is", "are", "was",
-            "were", "been", "being", "get", "got",
-        ];
+        let stop_words = self.stop_words();
 
         text.split_whitespace()
             .filter(|word| {
                 let lower = word.to_lowercase();
                 let clean = lower.trim_matches(|c: char| !c.is_alphabetic());
-                !STOPWORDS.contains(&clean)
+                !stop_words.iter().any(|w| w == clean)
             })
             .collect::<Vec<_>>()
             .join(" ")
@@ -158,6 +306,8 @@ mod tests {
     use super::*;
     use crate::content::{Post, PostMetadata, PostStatus};
     use chrono::Utc;
+    use std::fs;
+    use std::io::Read;
     use std::path::PathBuf;
     use tempfile::TempDir;
 
@@ -172,6 +322,17 @@ mod tests {
                 status: PostStatus::Published,
                 slug: "test-post".to_string(),
                 featured: false,
+                aliases: Vec::new(),
+                updated_at: None,
+                contributors: Vec::new(),
+                crosspost: Default::default(),
+                video: None,
+                link: None,
+                cover: None,
+                cover_alt: None,
+                noindex: false,
+                private: false,
+                password: None,
             },
             content: "# Test Post\n\nThis is a test post with some content.".to_string(),
             file_path: PathBuf::from("test-post.md"),
@@ -198,7 +359,14 @@ mod tests {
             minify: true,
             lazy_load: true,
             remove_stopwords: false,
+            language: None,
+            stop_words: Vec::new(),
+            stemming: false,
+            prefix: true,
             field_boosts,
+            exclude_fields: Vec::new(),
+            chunk_size: 0,
+            precompress: Vec::new(),
         };
         let indexer = SearchIndexer::new(config);
         let post = create_test_post();
@@ -232,7 +400,14 @@ mod tests {
             minify: true,
             lazy_load: true,
             remove_stopwords: false,
+            language: None,
+            stop_words: Vec::new(),
+            stemming: false,
+            prefix: true,
             field_boosts,
+            exclude_fields: Vec::new(),
+            chunk_size: 0,
+            precompress: Vec::new(),
         };
         let indexer = SearchIndexer::new(config);
         let post = create_test_post();
@@ -271,7 +446,14 @@ mod tests {
             minify: true,
             lazy_load: true,
             remove_stopwords: false,
+            language: None,
+            stop_words: Vec::new(),
+            stemming: false,
+            prefix: true,
             field_boosts,
+            exclude_fields: Vec::new(),
+            chunk_size: 0,
+            precompress: Vec::new(),
         };
         let indexer = SearchIndexer::new(config);
         let temp_dir = TempDir::new().unwrap();
@@ -283,4 +465,94 @@ mod tests {
         let documents: Vec<SearchDocument> = serde_json::from_str(&content).unwrap();
         assert_eq!(documents.len(), 0);
     }
+
+    #[test]
+    fn test_chunked_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let posts: Vec<Post> = (0..5)
+            .map(|i| {
+                let mut post = create_test_post();
+                post.metadata.slug = format!("post-{}", i);
+                post
+            })
+            .collect();
+        let config = SearchConfig {
+            exclude_fields: vec!["content".to_string()],
+            chunk_size: 2,
+            precompress: vec!["gzip".to_string()],
+            ..SearchConfig::default()
+        };
+        // Left over from a build with more posts
+        fs::write(temp_dir.path().join("search_index.4.json"), "[]").unwrap();
+
+        SearchIndexer::new(config)
+            .generate_index(&posts, temp_dir.path())
+            .unwrap();
+
+        let manifest: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(temp_dir.path().join("search_index.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest["documents"], 5);
+        assert_eq!(
+            manifest["chunks"],
+            serde_json::json!([
+                "search_index.1.json",
+                "search_index.2.json",
+                "search_index.3.json"
+            ])
+        );
+        assert!(!temp_dir.path().join("search_index.4.json").exists());
+
+        let last: Vec<serde_json::Value> = serde_json::from_str(
+            &fs::read_to_string(temp_dir.path().join("search_index.3.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0]["id"], "post-4");
+        assert!(last[0].get("content").is_none() && last[0].get("excerpt").is_some());
+
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(
+            fs::File::open(temp_dir.path().join("search_index.3.json.gz")).unwrap(),
+        )
+        .read_to_string(&mut json)
+        .unwrap();
+        assert_eq!(
+            json,
+            fs::read_to_string(temp_dir.path().join("search_index.3.json")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        let indexer = SearchIndexer::new(SearchConfig {
+            max_content_chars: 8,
+            ..SearchConfig::default()
+        });
+        assert_eq!(
+            indexer.truncate("héllo wörld again".to_string()),
+            "héllo..."
+        );
+        assert_eq!(indexer.truncate("kurz".to_string()), "kurz");
+    }
+
+    #[test]
+    fn test_language_stopwords() {
+        let config = SearchConfig {
+            remove_stopwords: true,
+            language: Some("de-DE".to_string()),
+            stop_words: vec!["Blog".to_string()],
+            ..SearchConfig::default()
+        };
+        let indexer = SearchIndexer::new(config);
+
+        let stop_words = indexer.stop_words();
+        assert!(stop_words.contains(&"und".to_string()));
+        assert!(stop_words.contains(&"blog".to_string()));
+        assert!(!stop_words.contains(&"the".to_string()));
+
+        let text = indexer.remove_stopwords("Der Hund und die Katze im Blog");
+        assert_eq!(text, "Hund Katze");
+    }
 }
@@ -0,0 +1,505 @@
+//! Security headers for static hosts
+//!
+//! Writes `_headers` (Netlify and Cloudflare Pages) and `vercel.json` with
+//! the usual hardening headers and a Content-Security-Policy worked out from
+//! the files the build emitted: the origins of scripts, styles, images,
+//! fonts, media, embeds and form targets, and hashes of inline scripts and
+//! styles. Pages that use inline event handlers or `style` attributes need
+//! `'unsafe-inline'`, which is used instead of hashes in that case.
+
+use crate::config::{Config, SecurityConfig};
+use crate::generator::output;
+use crate::webmention::{html_tags, parse_attributes};
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use url::Url;
+
+/// Above this many inline scripts or styles the policy falls back to
+/// `'unsafe-inline'`, since hosts limit how long a header can be
+const MAX_HASHES: usize = 30;
+
+/// Hosts that read the headers files
+pub const HOSTS: &[&str] = &["netlify", "cloudflare", "vercel"];
+
+/// What the emitted pages load, gathered from HTML and CSS files
+#[derive(Debug, Default)]
+pub struct CspSources {
+    /// Origins (or `data:`) by directive
+    origins: BTreeMap<&'static str, BTreeSet<String>>,
+    script_hashes: BTreeSet<String>,
+    style_hashes: BTreeSet<String>,
+    /// `onclick=` and friends
+    inline_handlers: bool,
+    style_attributes: bool,
+}
+
+impl CspSources {
+    /// Record what an HTML page loads. `origin` is the site's own origin.
+    pub fn scan_html(&mut self, html: &str, origin: &str) {
+        for tag in html_tags(html, &[]) {
+            let attribute = |name: &str| tag.attributes.get(name).map(String::as_str);
+            if tag
+                .attributes
+                .keys()
+                .any(|name| name.len() > 2 && name.starts_with("on"))
+            {
+                self.inline_handlers = true;
+            }
+            if tag.attributes.contains_key("style") {
+                self.style_attributes = true;
+            }
+            // Click-to-load videos create their iframe from this attribute
+            if let Some(src) = attribute("data-blogr-video") {
+                self.add("frame-src", src, origin);
+            }
+            // Click-to-load tweets fetch Twitter's script, which adds an iframe
+            if tag.attributes.contains_key("data-blogr-tweet") {
+                self.add("script-src", "https://platform.twitter.com", origin);
+                self.add("frame-src", "https://platform.twitter.com", origin);
+            }
+
+            match tag.name.as_str() {
+                "script" => {
+                    if let Some(src) = attribute("src") {
+                        self.add("script-src", src, origin);
+                    }
+                }
+                "img" => {
+                    if let Some(src) = attribute("src") {
+                        self.add("img-src", src, origin);
+                    }
+                    self.add_srcset("img-src", attribute("srcset"), origin);
+                }
+                "source" => {
+                    if let Some(src) = attribute("src") {
+                        self.add("media-src", src, origin);
+                    }
+                    self.add_srcset("img-src", attribute("srcset"), origin);
+                }
+                "audio" | "video" | "track" => {
+                    if let Some(src) = attribute("src") {
+                        self.add("media-src", src, origin);
+                    }
+                    if let Some(poster) = attribute("poster") {
+                        self.add("img-src", poster, origin);
+                    }
+                }
+                "iframe" => {
+                    if let Some(src) = attribute("src") {
+                        self.add("frame-src", src, origin);
+                    }
+                }
+                "form" => {
+                    if let Some(action) = attribute("action") {
+                        self.add("form-action", action, origin);
+                        // The newsletter form posts with fetch()
+                        self.add("connect-src", action, origin);
+                    }
+                }
+                "link" => {
+                    let rel = attribute("rel").unwrap_or("").to_ascii_lowercase();
+                    let Some(href) = attribute("href") else {
+                        continue;
+                    };
+                    let rels: Vec<&str> = rel.split_whitespace().collect();
+                    if rels.contains(&"stylesheet") {
+                        self.add("style-src", href, origin);
+                        // Google Fonts stylesheets load the fonts from another host
+                        if href.contains("fonts.googleapis.com") {
+                            self.add("font-src", "https://fonts.gstatic.com", origin);
+                        }
+                    } else if rels.iter().any(|rel| rel.contains("icon")) {
+                        self.add("img-src", href, origin);
+                    } else if rels.contains(&"preload") && attribute("as") == Some("font") {
+                        self.add("font-src", href, origin);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (attributes, body) in elements(html, "script") {
+            let executable = attributes.get("type").is_none_or(|kind| {
+                let kind = kind.to_ascii_lowercase();
+                kind.is_empty() || kind.contains("javascript") || kind == "module"
+            });
+            if !attributes.contains_key("src") && executable && !body.trim().is_empty() {
+                self.script_hashes.insert(hash(body));
+            }
+        }
+        for (_, body) in elements(html, "style") {
+            self.style_hashes.insert(hash(body));
+        }
+    }
+
+    /// Record the fonts, images and stylesheets a CSS file pulls in
+    pub fn scan_css(&mut self, css: &str, origin: &str) {
+        let mut rest = css;
+        while let Some(start) = rest.find("url(") {
+            rest = &rest[start + 4..];
+            let end = rest.find(')').unwrap_or(rest.len());
+            let url = rest[..end].trim().trim_matches(['"', '\'']);
+            let path = url
+                .split(['?', '#'])
+                .next()
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            let directive = if [".woff2", ".woff", ".ttf", ".otf", ".eot"]
+                .iter()
+                .any(|ext| path.ends_with(ext))
+            {
+                "font-src"
+            } else if path.ends_with(".css") {
+                "style-src"
+            } else {
+                "img-src"
+            };
+            self.add(directive, url, origin);
+            rest = &rest[end..];
+        }
+
+        for line in css
+            .lines()
+            .filter(|line| line.trim_start().starts_with("@import"))
+        {
+            if let Some(url) = line.split(['"', '\'']).nth(1) {
+                self.add("style-src", url, origin);
+            }
+        }
+    }
+
+    fn add(&mut self, directive: &'static str, url: &str, origin: &str) {
+        let url = url.trim();
+        let source = if url.starts_with("data:") {
+            "data:".to_string()
+        } else if url.starts_with("blob:") {
+            "blob:".to_string()
+        } else {
+            let absolute = match url.strip_prefix("//") {
+                Some(rest) => format!("https://{}", rest),
+                None => url.to_string(),
+            };
+            match Url::parse(&absolute) {
+                Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {
+                    let source = parsed.origin().ascii_serialization();
+                    if source == origin {
+                        return;
+                    }
+                    source
+                }
+                // mailto:, javascript: and the like
+                Ok(_) => return,
+                // Relative URLs are on the site itself
+                Err(_) => return,
+            }
+        };
+        self.origins.entry(directive).or_default().insert(source);
+    }
+
+    fn add_srcset(&mut self, directive: &'static str, srcset: Option<&str>, origin: &str) {
+        for candidate in srcset.unwrap_or("").split(',') {
+            if let Some(url) = candidate.split_whitespace().next() {
+                self.add(directive, url, origin);
+            }
+        }
+    }
+
+    /// The policy, with the configured extra sources merged in
+    pub fn policy(&self, extra: &BTreeMap<String, Vec<String>>) -> String {
+        let mut directives: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut directive = |name: &str, mut sources: Vec<String>| {
+            let origins = self.origins.iter().filter(|(key, _)| **key == name);
+            sources.extend(origins.flat_map(|(_, origins)| origins.iter().cloned()));
+            if let Some(more) = extra.get(name) {
+                sources.extend(more.iter().cloned());
+            }
+            let mut seen = BTreeSet::new();
+            sources.retain(|source| seen.insert(source.clone()));
+            directives.insert(name.to_string(), sources);
+        };
+        let own = || vec!["'self'".to_string()];
+
+        directive("default-src", own());
+        directive(
+            "script-src",
+            inline_sources(&self.script_hashes, self.inline_handlers),
+        );
+        directive(
+            "style-src",
+            inline_sources(&self.style_hashes, self.style_attributes),
+        );
+        directive("img-src", own());
+        for name in [
+            "font-src",
+            "media-src",
+            "frame-src",
+            "connect-src",
+            "form-action",
+        ] {
+            directive(name, own());
+        }
+        directive("object-src", vec!["'none'".to_string()]);
+        directive("base-uri", own());
+        directive("frame-ancestors", vec!["'none'".to_string()]);
+        // Directives only named in the configuration
+        for (name, sources) in extra {
+            if !directives.contains_key(name) {
+                directives.insert(name.clone(), sources.clone());
+            }
+        }
+
+        // default-src first, for readability
+        let mut policy = vec![format!(
+            "default-src {}",
+            directives["default-src"].join(" ")
+        )];
+        for (name, sources) in &directives {
+            if name == "default-src" {
+                continue;
+            }
+            // Directives that add nothing to default-src are left out
+            let same_as_default = sources == &directives["default-src"]
+                && !matches!(
+                    name.as_str(),
+                    "form-action" | "base-uri" | "frame-ancestors"
+                );
+            if !same_as_default {
+                policy.push(format!("{} {}", name, sources.join(" ")).trim().to_string());
+            }
+        }
+        policy.join("; ")
+    }
+
+    /// Whether the policy had to allow all inline scripts
+    pub fn needs_unsafe_inline_scripts(&self) -> bool {
+        self.inline_handlers || self.script_hashes.len() > MAX_HASHES
+    }
+}
+
+/// `'self'` plus hashes, or `'unsafe-inline'` when hashes can't cover it
+fn inline_sources(hashes: &BTreeSet<String>, attributes: bool) -> Vec<String> {
+    let mut sources = vec!["'self'".to_string()];
+    if attributes || hashes.len() > MAX_HASHES {
+        sources.push("'unsafe-inline'".to_string());
+    } else {
+        sources.extend(hashes.iter().map(|hash| format!("'{}'", hash)));
+    }
+    sources
+}
+
+fn hash(body: &str) -> String {
+    let digest = Sha256::digest(body.as_bytes());
+    format!(
+        "sha256-{}",
+        base64::engine::general_purpose::STANDARD.encode(digest)
+    )
+}
+
+/// Attributes and text of every `<name>` element, skipping comments
+fn elements<'a>(
+    html: &'a str,
+    name: &str,
+) -> Vec<(std::collections::HashMap<String, String>, &'a str)> {
+    // ASCII lowercasing keeps byte offsets the same
+    let lower = html.to_ascii_lowercase();
+    let open = format!("<{}", name);
+    let close = format!("</{}", name);
+    let mut found = Vec::new();
+    let mut position = 0;
+
+    while let Some(start) = lower[position..].find(&open).map(|i| position + i) {
+        if let Some(comment) = lower[position..start].rfind("<!--") {
+            let comment = position + comment;
+            if !lower[comment..start].contains("-->") {
+                position = lower[start..]
+                    .find("-->")
+                    .map_or(lower.len(), |end| start + end + 3);
+                continue;
+            }
+        }
+        let after_name = start + open.len();
+        // `<scripts>` or `<styled-thing>` are other elements
+        if !lower[after_name..].starts_with(|c: char| c.is_whitespace() || c == '>') {
+            position = after_name;
+            continue;
+        }
+        let (attributes, rest) = parse_attributes(&html[after_name..]);
+        let body_start = html.len() - rest.len();
+        let body_end = lower[body_start..]
+            .find(&close)
+            .map_or(html.len(), |end| body_start + end);
+        found.push((attributes, &html[body_start..body_end]));
+        position = body_end;
+    }
+    found
+}
+
+/// Headers for every page, in order
+pub fn headers(config: &SecurityConfig, csp: Option<String>) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = vec![
+        ("X-Content-Type-Options".into(), "nosniff".into()),
+        ("X-Frame-Options".into(), "DENY".into()),
+        (
+            "Referrer-Policy".into(),
+            "strict-origin-when-cross-origin".into(),
+        ),
+        (
+            "Permissions-Policy".into(),
+            "camera=(), microphone=(), geolocation=(), interest-cohort=()".into(),
+        ),
+    ];
+    if let Some(csp) = csp {
+        headers.push(("Content-Security-Policy".into(), csp));
+    }
+    // Configured headers replace built-in ones; an empty value removes one
+    for (name, value) in &config.headers {
+        headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        if !value.is_empty() {
+            headers.push((name.clone(), value.clone()));
+        }
+    }
+    headers
+}
+
+/// `_headers` for Netlify and Cloudflare Pages
+pub fn netlify_headers(headers: &[(String, String)]) -> String {
+    let mut text = String::from("/*\n");
+    for (name, value) in headers {
+        text.push_str(&format!("  {}: {}\n", name, value));
+    }
+    text
+}
+
+/// `vercel.json` with the headers for every path
+pub fn vercel_json(headers: &[(String, String)]) -> Result<String> {
+    let headers: Vec<serde_json::Value> = headers
+        .iter()
+        .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+        .collect();
+    let json = serde_json::json!({
+        "headers": [{ "source": "/(.*)", "headers": headers }]
+    });
+    Ok(serde_json::to_string_pretty(&json)? + "\n")
+}
+
+/// Work out the policy from the output and write the headers files
+pub fn generate(config: &Config, output_dir: &Path) -> Result<()> {
+    let security = &config.security;
+    if !security.enabled {
+        return Ok(());
+    }
+
+    let csp = if security.csp {
+        let origin = Url::parse(&config.get_effective_base_url())
+            .map(|url| url.origin().ascii_serialization())
+            .unwrap_or_default();
+        let mut sources = CspSources::default();
+        for path in output::files(output_dir) {
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("html") => sources.scan_html(&output::read_to_string(&path)?, &origin),
+                Some("css") => sources.scan_css(&output::read_to_string(&path)?, &origin),
+                _ => {}
+            }
+        }
+        if sources.needs_unsafe_inline_scripts() {
+            crate::status!("⚠️  Inline event handlers (onclick=...) in the theme need 'unsafe-inline' in the script policy");
+        }
+        Some(sources.policy(&security.sources))
+    } else {
+        None
+    };
+
+    let headers = headers(security, csp);
+    for host in &security.hosts {
+        let (file, content) = match host.as_str() {
+            "vercel" => ("vercel.json", vercel_json(&headers)?),
+            _ => ("_headers", netlify_headers(&headers)),
+        };
+        output::write(output_dir.join(file), content)
+            .map_err(|e| anyhow!("Failed to write {}: {}", file, e))?;
+    }
+    crate::status!(
+        "🔒 Wrote security headers for {}",
+        security.hosts.join(", ")
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGIN: &str = "https://example.com";
+
+    #[test]
+    fn test_content_security_policy() {
+        let mut sources = CspSources::default();
+        sources.scan_html(
+            r#"<html><head>
+<link rel="stylesheet" href="https://fonts.googleapis.com/css2?family=Inter">
+<link rel="stylesheet" href="/css/style.css">
+<script src="https://example.com/js/search.js"></script>
+<script>console.log("hi")</script>
+<script type="application/ld+json">{"@type": "BlogPosting"}</script>
+<!-- <script src="https://commented.example/x.js"></script> -->
+<style>body { color: red }</style>
+</head><body>
+<img src="https://images.example.org/a.png" alt=""><img src="data:image/png;base64,AAAA" alt="">
+<div data-blogr-video="https://www.youtube-nocookie.com/embed/x"></div>
+<form action="https://news.example.net/subscribe" method="post"></form>
+</body></html>"#,
+            ORIGIN,
+        );
+        sources.scan_css(
+            "@import 'https://cdn.example.org/base.css';\n@font-face { src: url(\"https://fonts.gstatic.com/s/inter.woff2\") }",
+            ORIGIN,
+        );
+
+        let extra = BTreeMap::from([(
+            "script-src".to_string(),
+            vec!["https://plausible.io".to_string()],
+        )]);
+        let policy = sources.policy(&extra);
+        let directives: Vec<&str> = policy.split("; ").collect();
+        assert_eq!(directives[0], "default-src 'self'");
+        let script = directives
+            .iter()
+            .find(|d| d.starts_with("script-src"))
+            .unwrap();
+        assert!(script.contains(&format!("'{}'", hash(r#"console.log("hi")"#))));
+        assert!(script.ends_with("https://plausible.io"));
+        assert_eq!(script.matches("sha256-").count(), 1);
+        assert!(!policy.contains("commented.example"));
+        assert!(policy.contains("style-src 'self' 'sha256-"));
+        assert!(policy.contains("https://cdn.example.org https://fonts.googleapis.com"));
+        assert!(policy.contains("img-src 'self' data: https://images.example.org"));
+        assert!(policy.contains("font-src 'self' https://fonts.gstatic.com;"));
+        assert!(policy.contains("frame-src 'self' https://www.youtube-nocookie.com"));
+        assert!(policy.contains("connect-src 'self' https://news.example.net"));
+        assert!(policy.contains("form-action 'self' https://news.example.net"));
+        assert!(!policy.contains("media-src"));
+
+        sources.scan_html(
+            r#"<button onclick="go()" style="color: red">Go</button>"#,
+            ORIGIN,
+        );
+        let policy = sources.policy(&BTreeMap::new());
+        assert!(policy.contains("script-src 'self' 'unsafe-inline';"));
+        assert!(policy.contains("style-src 'self' 'unsafe-inline' https://"));
+
+        let mut config = SecurityConfig::default();
+        config
+            .headers
+            .insert("X-Frame-Options".to_string(), String::new());
+        let headers = headers(&config, Some(policy));
+        assert!(!headers.iter().any(|(name, _)| name == "X-Frame-Options"));
+        let file = netlify_headers(&headers);
+        assert!(file.starts_with("/*\n  X-Content-Type-Options: nosniff\n"));
+        assert!(vercel_json(&headers)
+            .unwrap()
+            .contains("\"source\": \"/(.*)\""));
+    }
+}
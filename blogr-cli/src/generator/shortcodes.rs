@@ -0,0 +1,227 @@
+//! Shortcodes in markdown
+//!
+//! A shortcode is written `{{< name arg key="some value" >}}` on a single line
+//! and is replaced with HTML before the markdown is rendered. Shortcodes in
+//! fenced code blocks and inline code are left alone, so posts can show them.
+
+use anyhow::Result;
+
+const OPEN: &str = "{{<";
+const CLOSE: &str = ">}}";
+
+/// A shortcode call
+#[derive(Debug, PartialEq)]
+pub struct Shortcode {
+    pub name: String,
+    /// Arguments in order, with quotes removed
+    pub args: Vec<String>,
+}
+
+impl Shortcode {
+    fn parse(source: &str) -> Option<Self> {
+        let mut tokens = tokenize(source).into_iter();
+        let name = tokens.next()?;
+        Some(Self {
+            name,
+            args: tokens.collect(),
+        })
+    }
+
+    /// The `index`th argument that isn't a `key=value` pair
+    pub fn positional(&self, index: usize) -> Option<&str> {
+        self.args
+            .iter()
+            .filter(|arg| named_pair(arg).is_none())
+            .nth(index)
+            .map(String::as_str)
+    }
+
+    /// The value of a `key=value` argument
+    pub fn named(&self, key: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .filter_map(|arg| named_pair(arg))
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+    }
+}
+
+fn named_pair(arg: &str) -> Option<(&str, &str)> {
+    let (key, value) = arg.split_once('=')?;
+    (!key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .then_some((key, value))
+}
+
+/// Split on whitespace outside double quotes, dropping the quotes
+fn tokenize(source: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_token = false;
+
+    for c in source.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Replace every shortcode with the HTML `render` returns for it
+pub fn expand(
+    markdown: &str,
+    mut render: impl FnMut(&Shortcode) -> Result<String>,
+) -> Result<String> {
+    if !markdown.contains(OPEN) {
+        return Ok(markdown.to_string());
+    }
+
+    let mut output = String::with_capacity(markdown.len());
+    let mut fence: Option<(char, usize)> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        if let Some(marker) = marker {
+            let length = trimmed.chars().take_while(|c| *c == marker).count();
+            if length >= 3 && line.len() - trimmed.len() <= 3 {
+                match fence {
+                    None => fence = Some((marker, length)),
+                    Some((open, open_length)) if open == marker && length >= open_length => {
+                        fence = None
+                    }
+                    Some(_) => {}
+                }
+                output.push_str(line);
+                continue;
+            }
+        }
+
+        if fence.is_some() {
+            output.push_str(line);
+        } else {
+            expand_line(line, &mut output, &mut render)?;
+        }
+    }
+
+    Ok(output)
+}
+
+fn expand_line(
+    line: &str,
+    output: &mut String,
+    render: &mut impl FnMut(&Shortcode) -> Result<String>,
+) -> Result<()> {
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        let next_code = rest.find('`');
+        let next_shortcode = rest.find(OPEN);
+
+        match (next_code, next_shortcode) {
+            // Inline code comes first: copy it through untouched
+            (Some(code), shortcode) if shortcode.is_none_or(|s| code < s) => {
+                let ticks = rest[code..].chars().take_while(|c| *c == '`').count();
+                let after_open = code + ticks;
+                let closing = find_code_close(&rest[after_open..], ticks)
+                    .map(|end| after_open + end + ticks)
+                    .unwrap_or(after_open);
+                output.push_str(&rest[..closing]);
+                rest = &rest[closing..];
+            }
+            (_, Some(start)) => {
+                let inner_start = start + OPEN.len();
+                let Some(length) = rest[inner_start..].find(CLOSE) else {
+                    output.push_str(rest);
+                    return Ok(());
+                };
+                output.push_str(&rest[..start]);
+                let end = inner_start + length + CLOSE.len();
+                match Shortcode::parse(&rest[inner_start..inner_start + length]) {
+                    Some(shortcode) => output.push_str(&render(&shortcode)?),
+                    None => output.push_str(&rest[start..end]),
+                }
+                rest = &rest[end..];
+            }
+            _ => {
+                output.push_str(rest);
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Offset of the backtick run of exactly `ticks` that closes a code span
+fn find_code_close(text: &str, ticks: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'`' {
+            let run = bytes[i..].iter().take_while(|b| **b == b'`').count();
+            if run == ticks {
+                return Some(i);
+            }
+            i += run;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(shortcode: &Shortcode) -> Result<String> {
+        Ok(format!("[{}:{}]", shortcode.name, shortcode.args.join("|")))
+    }
+
+    #[test]
+    fn test_expand() {
+        let markdown = "Intro {{< gallery trip >}} and {{<video src=\"a b.mp4\" loop>}}\n";
+        assert_eq!(
+            expand(markdown, render).unwrap(),
+            "Intro [gallery:trip] and [video:src=a b.mp4|loop]\n"
+        );
+    }
+
+    #[test]
+    fn test_code_is_left_alone() {
+        let markdown = "Use `{{< gallery trip >}}` to embed.\n\n```md\n{{< gallery trip >}}\n```\n\n{{< gallery trip >}}\n";
+        assert_eq!(
+            expand(markdown, render).unwrap(),
+            "Use `{{< gallery trip >}}` to embed.\n\n```md\n{{< gallery trip >}}\n```\n\n[gallery:trip]\n"
+        );
+
+        // An unclosed shortcode is plain text
+        assert_eq!(expand("{{< gallery", render).unwrap(), "{{< gallery");
+    }
+
+    #[test]
+    fn test_arguments() {
+        let shortcode = Shortcode::parse(r#"video clip.mp4 poster="a b.jpg" 2"#).unwrap();
+        assert_eq!(shortcode.name, "video");
+        assert_eq!(shortcode.positional(0), Some("clip.mp4"));
+        assert_eq!(shortcode.positional(1), Some("2"));
+        assert_eq!(shortcode.named("poster"), Some("a b.jpg"));
+        assert_eq!(shortcode.named("clip.mp4"), None);
+    }
+}
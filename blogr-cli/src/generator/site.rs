@@ -1,19 +1,168 @@
-use crate::config::Config;
+use crate::build_cache::BuildCache;
+use crate::config::{Config, UrlConfig, BLOG_SECTION};
 use crate::content::{Post, PostManager, PostStatus};
+use crate::fonts::SelfHostedFonts;
+use crate::generator::activitypub;
+use crate::generator::archive::{
+    group_by_month, month_path, year_path, ArchivePeriod, ArchiveYear,
+};
+use crate::generator::bundles::{bundle_files, bundle_link, bundle_output_dir, copy_bundle};
+use crate::generator::contact::{ContactForm, THANKS_PAGE};
+use crate::generator::content_api;
+use crate::generator::covers::Cover;
+use crate::generator::dependencies::DependencyGraph;
+use crate::generator::docs_nav::DocsNav;
+use crate::generator::domains;
+use crate::generator::embeds;
+use crate::generator::error_pages;
+use crate::generator::feeds::{self, tag_feed_path, FeedLink, ATOM_TYPE, RSS_TYPE};
+use crate::generator::formats::{self, OutputFormat};
+use crate::generator::gallery::{
+    load_galleries, resized, thumbnail, Gallery, GALLERIES_DIR, GALLERY_SCRIPT, THUMBNAIL_SIZE,
+};
+use crate::generator::git_metadata::GitMetadata;
+use crate::generator::hooks::{HookRunner, HookStage};
+use crate::generator::manifest::Manifest;
+use crate::generator::markdown::{html_escape, render_markdown_with};
+use crate::generator::microformats;
+use crate::generator::output;
+use crate::generator::pages::{load_pages, Page};
+use crate::generator::private::{self, PRIVATE_NOTE, PRIVATE_SCRIPT};
+use crate::generator::reader;
+use crate::generator::resume::{find_resume, load_resume, print_pdf, RESUME_PAGE};
+use crate::generator::robots;
+use crate::generator::scss;
+use crate::generator::security;
+use crate::generator::shortcodes;
+use crate::generator::slugs;
+use crate::generator::structured_data;
+use crate::generator::template_errors::TemplateError;
+use crate::generator::template_helpers;
+use crate::generator::video::{self, poster_frame, poster_path, Video, VideoOptions, VIDEO_SCRIPT};
+use crate::github_projects;
+use crate::logging::timed;
+use crate::progress::Progress;
 use crate::project::Project;
+use crate::utils::Console;
 use anyhow::{anyhow, Result};
-use blogr_themes::{get_theme_by_name, Theme};
-use chrono::{Datelike, Utc};
+use blogr_themes::Theme;
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tera::{Context, Tera};
+use walkdir::WalkDir;
 
 // Embed search assets so they are always available in builds and deployments
 // Paths are relative to this file: blogr-cli/src/generator/site.rs → ../../static/...
 const EMBEDDED_SEARCH_JS: &str = include_str!("../../static/js/search.js");
 const EMBEDDED_MINISEARCH_JS: &str = include_str!("../../static/js/vendor/minisearch.min.js");
+const EMBEDDED_SEARCH_PAGE_JS: &str = include_str!("../../static/js/search-page.js");
+
+// Default search page, used when the theme does not provide its own `search.html`
+const DEFAULT_SEARCH_TEMPLATE: &str = include_str!("../templates/site/search.html");
+
+// Default page for `content/` files on personal sites, for themes without a `page.html`
+const DEFAULT_PAGE_TEMPLATE: &str = include_str!("../templates/site/page.html");
+
+// Default error page, for themes without a template for the status, e.g. `404.html`
+const DEFAULT_ERROR_TEMPLATE: &str = include_str!("../templates/site/error.html");
+
+// Contact form and the page readers land on after sending, for themes without
+// their own `contact_form.html` and `contact_thanks.html`
+const DEFAULT_CONTACT_FORM_TEMPLATE: &str = include_str!("../templates/site/contact_form.html");
+const DEFAULT_CONTACT_THANKS_TEMPLATE: &str = include_str!("../templates/site/contact_thanks.html");
+
+// Plain copy of a post, for themes without a `plain.html`
+const DEFAULT_PLAIN_TEMPLATE: &str = include_str!("../templates/site/plain.html");
+
+// Default gallery page and its lightbox script
+const DEFAULT_GALLERY_TEMPLATE: &str = include_str!("../templates/site/gallery.html");
+const EMBEDDED_GALLERY_JS: &str = include_str!("../../static/js/gallery.js");
+
+// Click-to-load script for YouTube and Vimeo embeds
+const EMBEDDED_VIDEO_JS: &str = include_str!("../../static/js/video.js");
+
+// Newsletter signup form for themes without a `newsletter_form.html`, and its script
+const DEFAULT_NEWSLETTER_FORM_TEMPLATE: &str =
+    include_str!("../templates/site/newsletter_form.html");
+const EMBEDDED_NEWSLETTER_JS: &str = include_str!("../../static/js/newsletter.js");
+
+/// The newsletter form's script, relative to the site root
+pub const NEWSLETTER_SCRIPT: &str = "js/newsletter.js";
+
+// Unlock form script for private posts
+const EMBEDDED_PRIVATE_JS: &str = include_str!("../../static/js/private.js");
+
+// Resume page from `resume.yaml`: the sections, the page for themes without a
+// `resume.html`, and the standalone document printed to PDF
+const DEFAULT_RESUME_BODY_TEMPLATE: &str = include_str!("../templates/site/resume_body.html");
+const DEFAULT_RESUME_TEMPLATE: &str = include_str!("../templates/site/resume.html");
+const DEFAULT_RESUME_PRINT_TEMPLATE: &str = include_str!("../templates/site/resume_print.html");
+
+// Built-in templates registered for themes without their own
+const DEFAULT_TEMPLATES: [(&str, &str); 11] = [
+    ("search.html", DEFAULT_SEARCH_TEMPLATE),
+    ("page.html", DEFAULT_PAGE_TEMPLATE),
+    ("gallery.html", DEFAULT_GALLERY_TEMPLATE),
+    ("error.html", DEFAULT_ERROR_TEMPLATE),
+    ("plain.html", DEFAULT_PLAIN_TEMPLATE),
+    ("resume_body.html", DEFAULT_RESUME_BODY_TEMPLATE),
+    ("resume.html", DEFAULT_RESUME_TEMPLATE),
+    ("resume_print.html", DEFAULT_RESUME_PRINT_TEMPLATE),
+    ("newsletter_form.html", DEFAULT_NEWSLETTER_FORM_TEMPLATE),
+    ("contact_form.html", DEFAULT_CONTACT_FORM_TEMPLATE),
+    ("contact_thanks.html", DEFAULT_CONTACT_THANKS_TEMPLATE),
+];
+
+/// Variables for `newsletter_form.html`, or None when the newsletter is off
+/// or has nowhere to send signups
+pub(crate) fn newsletter_form_context(config: &Config, script_url: &str) -> Option<Context> {
+    let newsletter = &config.newsletter;
+    if !newsletter.enabled {
+        return None;
+    }
+
+    let mut context = Context::new();
+    context.insert("site", config);
+    context.insert("newsletter", newsletter);
+    context.insert("newsletter_script", script_url);
+    if newsletter.subscribe_endpoint.is_none() {
+        let subscribe_email = newsletter.subscribe_email.as_ref()?;
+        let confirmation_subject = newsletter
+            .confirmation_subject
+            .as_deref()
+            .unwrap_or("Newsletter Subscription Request");
+        let email_body = "Please add my email address to your newsletter list.";
+        context.insert("subscribe_email", subscribe_email);
+        context.insert("confirmation_subject", confirmation_subject);
+        context.insert(
+            "subscribe_url",
+            &format!(
+                "mailto:{}?subject={}&body={}",
+                subscribe_email,
+                urlencoding::encode(confirmation_subject),
+                urlencoding::encode(email_body)
+            ),
+        );
+    }
+    Some(context)
+}
+
+/// What a build produced
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BuildSummary {
+    /// Posts published in the build
+    pub posts: usize,
+    /// Pages from `content/`
+    pub pages: usize,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+}
 
 /// Static site generator
 pub struct SiteBuilder {
@@ -33,120 +182,43 @@ pub struct SiteBuilder {
     include_future: bool,
     /// Pre-loaded content.md (used during deploy to preserve uncommitted changes)
     content_md: Option<String>,
+    /// Cache for rendered markdown
+    cache: BuildCache,
+    /// Photo galleries from `galleries/`
+    galleries: Vec<Gallery>,
+    /// Posters made for local videos this build, by video path
+    posters: RefCell<HashMap<String, Option<String>>>,
+    /// Build profile from `--profile`, which picks the `[robots.profiles]` rules
+    profile: Option<String>,
+    /// Posts of the last blog build and the pages they appear on
+    dependencies: RefCell<Option<DependencyGraph>>,
+    /// Fonts downloaded by `fonts::refresh` to serve instead of Google's
+    fonts: SelfHostedFonts,
 }
 
 impl SiteBuilder {
-    /// Generate newsletter subscription form HTML
-    fn generate_newsletter_form(&self) -> String {
-        if !self.config.newsletter.enabled {
-            return String::new();
-        }
-
-        let subscribe_email = match &self.config.newsletter.subscribe_email {
-            Some(email) => email,
-            None => return String::new(),
+    /// The newsletter signup form, from the theme's `newsletter_form.html` or
+    /// the built-in one; empty when the newsletter has no form
+    fn generate_newsletter_form(&self) -> Result<String> {
+        let Some(context) =
+            newsletter_form_context(&self.config, &self.root_url(NEWSLETTER_SCRIPT))
+        else {
+            return Ok(String::new());
         };
+        self.render_template("newsletter_form.html", &context, "the newsletter form")
+    }
 
-        let confirmation_subject = self
-            .config
-            .newsletter
-            .confirmation_subject
-            .as_deref()
-            .unwrap_or("Newsletter Subscription Request");
-
-        // Create the email body with user's email
-        let email_body = "Please add my email address to your newsletter list.";
-        let mailto_url = format!(
-            "mailto:{}?subject={}&body={}",
-            subscribe_email,
-            urlencoding::encode(confirmation_subject),
-            urlencoding::encode(email_body)
-        );
-
-        let form_html = format!(
-            r#"<div class="newsletter-subscription">
-  <div class="newsletter-header">
-    <h3>Stay Updated</h3>
-    <p>Join our newsletter for the latest posts and updates</p>
-  </div>
-  <form class="newsletter-form" onsubmit="handleNewsletterSubmit(event, '{}')" method="get">
-    <div class="newsletter-input-group">
-      <input 
-        type="email" 
-        id="newsletter-email"
-        name="email" 
-        placeholder="Enter your email address" 
-        required 
-        class="newsletter-email-input"
-        aria-label="Email address for newsletter subscription">
-      <button type="submit" class="newsletter-submit-btn">
-        <span class="btn-text">Subscribe</span>
-        <svg class="btn-icon" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
-          <path d="M22 2L11 13"/>
-          <path d="M22 2L15 22L11 13L2 9L22 2Z"/>
-        </svg>
-      </button>
-    </div>
-    <p class="newsletter-privacy">
-      <small>We respect your privacy. Unsubscribe at any time.</small>
-    </p>
-  </form>
-  <noscript>
-    <p class="newsletter-fallback">
-      <a href="{}">Subscribe via email</a>
-    </p>
-  </noscript>
-</div>
-
-<script>
-function handleNewsletterSubmit(event, subscribeEmail) {{
-  event.preventDefault();
-  
-  const emailInput = document.getElementById('newsletter-email');
-  const userEmail = emailInput.value;
-  
-  if (!userEmail) {{
-    alert('Please enter your email address');
-    return;
-  }}
-  
-  const subject = encodeURIComponent('{}');
-  const body = encodeURIComponent(`Hello,
-
-I would like to subscribe to your newsletter.
-
-My email address is: ${{userEmail}}
-
-Thank you!`);
-  
-  const mailtoUrl = `mailto:${{subscribeEmail}}?subject=${{subject}}&body=${{body}}`;
-  
-  // Try to open the email client
-  window.location.href = mailtoUrl;
-  
-  // Show confirmation message
-  const form = event.target;
-  const originalContent = form.innerHTML;
-  
-  form.innerHTML = `
-    <div style="text-align: center; padding: 20px;">
-      <div style="font-size: 24px; margin-bottom: 10px;">✅</div>
-      <h4 style="margin: 0 0 10px 0; color: var(--color-primary, #007acc);">Email Client Opened!</h4>
-      <p style="margin: 0; color: var(--color-text-muted, #666);">Please send the email to complete your subscription.</p>
-      <button onclick="location.reload()" style="margin-top: 15px; padding: 8px 16px; background: var(--color-primary, #007acc); color: white; border: none; border-radius: 6px; cursor: pointer;">Try Again</button>
-    </div>
-  `;
-  
-  // Reset form after 10 seconds
-  setTimeout(() => {{
-    form.innerHTML = originalContent;
-  }}, 10000);
-}}
-</script>"#,
-            subscribe_email, mailto_url, confirmation_subject
-        );
-
-        form_html
+    /// The contact form, from the theme's `contact_form.html` or the built-in
+    /// one; empty when `[contact]` is off
+    fn generate_contact_form(&self) -> Result<String> {
+        if !self.config.contact.enabled {
+            return Ok(String::new());
+        }
+        let form = ContactForm::new(&self.config.contact, &self.config.page_url(THANKS_PAGE))?;
+        let mut context = Context::new();
+        context.insert("site", &self.config);
+        context.insert("contact", &form);
+        self.render_template("contact_form.html", &context, "the contact form")
     }
 
     /// Create a new site builder
@@ -170,20 +242,11 @@ Thank you!`);
     ) -> Result<Self> {
         // Get theme
         let theme_name = &config.theme.name;
-        let theme = get_theme_by_name(theme_name)
+        let theme = project
+            .load_theme(theme_name)?
             .ok_or_else(|| anyhow!("Theme '{}' not found", theme_name))?;
 
-        // Set up template engine (create empty Tera instance)
-        let mut tera = Tera::default();
-
-        // Register theme templates
-        for (name, template) in theme.templates() {
-            tera.add_raw_template(name, template)
-                .map_err(|e| anyhow!("Failed to register template '{}': {}", name, e))?;
-        }
-
-        // Register template functions for URL generation
-        Self::register_template_functions(&mut tera, &config)?;
+        let tera = Self::template_engine(theme.as_ref(), &config)?;
 
         let output_dir = output_dir.unwrap_or_else(|| {
             config
@@ -194,6 +257,10 @@ Thank you!`);
                 .unwrap_or_else(|| project.root.join("_site"))
         });
 
+        let cache = BuildCache::new(&config.build.cache);
+        let galleries = load_galleries(&project.root)?;
+        let fonts = SelfHostedFonts::load(&project.root, &config);
+
         Ok(Self {
             project,
             config,
@@ -203,9 +270,51 @@ Thank you!`);
             include_drafts,
             include_future,
             content_md: None,
+            cache,
+            galleries,
+            posters: RefCell::default(),
+            profile: None,
+            dependencies: RefCell::default(),
+            fonts,
         })
     }
 
+    /// Build with a profile such as `preview`
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Template engine with the theme's templates and blogr's template functions
+    pub(crate) fn template_engine(theme: &dyn Theme, config: &Config) -> Result<Tera> {
+        // Set up template engine (create empty Tera instance)
+        let mut tera = Tera::default();
+
+        // Register theme templates
+        for (name, template) in theme.templates() {
+            tera.add_raw_template(&name, &template)
+                .map_err(|e| TemplateError::new(&e, &name, None, |_| Some(template.to_string())))?;
+        }
+
+        // Fall back to the built-in search, page, gallery, error, plain, resume
+        // and output format templates for themes without them
+        for (name, template) in DEFAULT_TEMPLATES
+            .into_iter()
+            .chain(formats::DEFAULT_TEMPLATES)
+        {
+            if !tera.get_template_names().any(|existing| existing == name) {
+                tera.add_raw_template(name, template).map_err(|e| {
+                    TemplateError::new(&e, name, None, |_| Some(template.to_string()))
+                })?;
+            }
+        }
+
+        // Register template functions for URL generation
+        Self::register_template_functions(&mut tera, config)?;
+
+        Ok(tera)
+    }
+
     /// Create a new site builder with pre-loaded config and content.md
     /// Used during deployment to preserve uncommitted changes
     pub fn new_with_config_and_content(
@@ -223,77 +332,345 @@ Thank you!`);
     }
 
     /// Build the entire site
-    pub fn build(&self) -> Result<()> {
-        println!("🚀 Building site with theme '{}'", self.config.theme.name);
+    pub fn build(&self) -> Result<BuildSummary> {
+        crate::status!("🚀 Building site with theme '{}'", self.config.theme.name);
+        let _span = tracing::info_span!("build", theme = %self.config.theme.name).entered();
+
+        let hooks = HookRunner::new(&self.project, &self.config, &self.output_dir);
+        timed("ran pre-build hooks", || hooks.run(HookStage::PreBuild))?;
 
         // Clean output directory
         self.clean_output_dir()?;
 
-        let is_personal = self.config.site.site_type == "personal";
+        let site_type = self.config.site.site_type.as_str();
+        let mut section_cache = (0, 0);
+
+        let (posts, pages) = match site_type {
+            "personal" => {
+                // Personal website - the index page plus any pages in content/
+                let pages = load_pages(&self.project.root.join("content"))?;
+                timed(&format!("rendered {} pages", pages.len()), || {
+                    self.generate_personal_index(&pages)?;
+                    self.generate_personal_pages(&pages)?;
+                    self.generate_resume(&pages)
+                })?;
+                if self.config.search.enabled {
+                    timed("generated search index", || {
+                        self.generate_search_index(&[], &pages)?;
+                        self.generate_search_page()?;
+                        self.copy_search_assets()
+                    })?;
+                }
+                (Vec::new(), pages)
+            }
+            "hybrid" => {
+                // Hybrid site - the blog under /blog/, then the landing page and
+                // pages at the root
+                let blog = self.blog_section()?;
+                let posts = blog.build_blog()?;
+                blog.copy_theme_assets()?;
+                section_cache = blog.cache.usage();
+
+                let pages = load_pages(&self.project.root.join("content"))?;
+                slugs::check_collisions(&self.config, &posts, &pages)?;
+                timed(&format!("rendered {} pages", pages.len()), || {
+                    self.generate_hybrid_home(&pages, &posts)?;
+                    self.generate_personal_pages(&pages)?;
+                    self.generate_resume(&pages)
+                })?;
+
+                // The landing page's search box queries the blog's index
+                self.copy_search_assets()?;
+                (posts, pages)
+            }
+            _ => (self.build_blog()?, Vec::new()),
+        };
 
-        if is_personal {
-            // Personal website - just generate the index page
-            self.generate_personal_index()?;
-        } else {
-            // Blog mode - generate all blog pages
-            // Load all posts
-            let post_manager = PostManager::new(self.project.posts_dir());
-            let mut all_posts = post_manager.load_all_posts()?;
+        self.generate_galleries(&pages)?;
+        self.generate_error_pages(&pages)?;
+        if matches!(site_type, "personal" | "hybrid") {
+            self.generate_contact_thanks(&pages)?;
+        }
+        if posts.iter().any(|post| post.metadata.private) {
+            self.write_script(PRIVATE_SCRIPT, EMBEDDED_PRIVATE_JS)?;
+        }
+        if newsletter_form_context(&self.config, NEWSLETTER_SCRIPT).is_some() {
+            self.write_script(NEWSLETTER_SCRIPT, EMBEDDED_NEWSLETTER_JS)?;
+        }
+        if self.config.markdown.video.click_to_load
+            || embeds::uses_click_to_load(&self.config.privacy.embeds)
+        {
+            self.write_script(VIDEO_SCRIPT, EMBEDDED_VIDEO_JS)?;
+        }
+
+        timed("copied theme and static assets", || {
+            // Copy theme assets (both blog and personal)
+            self.copy_theme_assets()?;
+
+            // Copy project static assets (both blog and personal)
+            self.copy_static_assets()
+        })?;
+
+        timed("generated sitemap, robots.txt and redirects", || {
+            // Generate sitemap and robots.txt
+            self.generate_sitemap(&posts, &pages)?;
+            robots::generate_robots_txt(&self.config, self.profile.as_deref(), &self.output_dir)?;
+
+            // Generate redirect stubs for aliases and configured redirects
+            self.generate_redirects(&posts)
+        })?;
+
+        // Generate CNAME file if domain configuration exists
+        self.generate_cname_file()?;
+
+        if self.config.activitypub.enabled && !matches!(site_type, "personal" | "hybrid") {
+            let public: Vec<Post> = posts
+                .iter()
+                .filter(|post| !post.metadata.private)
+                .cloned()
+                .collect();
+            activitypub::generate(&self.project.root, &self.config, &public, &self.output_dir)?;
+        }
+
+        // Review builds are kept out of search engines
+        if self.config.robots.disallows_all(self.profile.as_deref()) {
+            robots::noindex_all(&self.output_dir)?;
+            crate::status!(
+                "🙈 Profile '{}' keeps every page out of search engines",
+                self.profile.as_deref().unwrap_or_default()
+            );
+        }
+
+        timed("ran post-build hooks", || hooks.run(HookStage::PostBuild))?;
 
-            // Filter posts based on build options
-            all_posts.retain(|post| self.should_include_post(post));
+        // After the hooks, so inline script hashes match the final pages
+        security::generate(&self.config, &self.output_dir)?;
 
-            // Sort posts by date (newest first)
-            all_posts.sort_by(|a, b| b.metadata.date.cmp(&a.metadata.date));
+        // Last, so it covers every file the build and its hooks wrote
+        if self.config.deploy.verify.enabled {
+            Manifest::from_dir(&self.output_dir)?.write(&self.output_dir)?;
+        }
+
+        let (hits, misses) = self.cache.usage();
+        let (hits, misses) = (hits + section_cache.0, misses + section_cache.1);
+        if hits + misses > 0 {
+            crate::status!("♻️  Build cache: {} hit(s), {} miss(es)", hits, misses);
+        }
+
+        crate::status!(
+            "✅ Site built successfully to: {}",
+            self.output_dir.display()
+        );
+        Ok(BuildSummary {
+            posts: posts.len(),
+            pages: pages.len(),
+            cache_hits: hits,
+            cache_misses: misses,
+        })
+    }
+
+    /// Write one of blogr's scripts to `path` in the output
+    fn write_script(&self, path: &str, script: &str) -> Result<()> {
+        let file = self.output_dir.join(path);
+        if let Some(parent) = file.parent() {
+            output::create_dir_all(parent)?;
+        }
+        output::write(&file, script)?;
+        Ok(())
+    }
 
-            println!("📝 Processing {} posts", all_posts.len());
+    /// The posts to publish, newest first
+    pub(crate) fn load_posts(&self) -> Result<Vec<Post>> {
+        // Load all posts
+        let post_manager = PostManager::new(self.project.posts_dir());
+        let mut all_posts = timed("loaded posts", || post_manager.load_all_posts())?;
 
-            // Generate individual post pages
-            self.generate_post_pages(&all_posts)?;
+        // Filter posts based on build options
+        all_posts.retain(|post| self.should_include_post(post));
+        slugs::check_collisions(&self.config, &all_posts, &[])?;
 
+        // Sort posts by date (newest first)
+        all_posts.sort_by_key(|post| Reverse(post.metadata.date));
+
+        if self.config.build.git_metadata {
+            self.apply_git_metadata(&mut all_posts);
+        }
+        Ok(all_posts)
+    }
+
+    /// Generate the posts and blog pages, returning the published posts
+    fn build_blog(&self) -> Result<Vec<Post>> {
+        let all_posts = self.load_posts()?;
+        crate::status!("📝 Processing {} posts", all_posts.len());
+
+        // Generate individual post pages
+        timed(&format!("rendered {} posts", all_posts.len()), || {
+            self.generate_post_pages(&all_posts)
+        })?;
+
+        timed("rendered index, archive and tag pages", || {
             // Generate index page
             self.generate_index_page(&all_posts)?;
 
             // Generate archive pages
-            self.generate_archive_pages(&all_posts)?;
+            self.generate_archive_pages(&all_posts, None)?;
 
             // Generate tag pages
-            self.generate_tag_pages(&all_posts)?;
+            self.generate_tag_pages(&all_posts, None)
+        })?;
 
+        // Private posts are listed, but their text stays out of feeds and search
+        let public_posts: Vec<Post> = all_posts
+            .iter()
+            .filter(|post| !post.metadata.private)
+            .cloned()
+            .collect();
+
+        timed("generated feeds", || {
             // Generate RSS feed
-            self.generate_rss_feed(&all_posts)?;
+            self.generate_rss_feed(&public_posts)?;
 
             // Generate static JSON files for pagination
-            self.generate_posts_json(&all_posts)?;
+            self.generate_posts_json(&public_posts)?;
+            self.generate_content_api(&public_posts)
+        })?;
 
+        timed("generated search index", || {
             // Generate search index
-            self.generate_search_index(&all_posts)?;
+            self.generate_search_index(&public_posts, &[])?;
+
+            // Generate search results page
+            self.generate_search_page()?;
 
             // Copy built-in search assets
-            self.copy_search_assets()?;
-        }
+            self.copy_search_assets()
+        })?;
 
-        // Copy theme assets (both blog and personal)
-        self.copy_theme_assets()?;
+        *self.dependencies.borrow_mut() = Some(DependencyGraph::new(&all_posts));
+        Ok(all_posts)
+    }
 
-        // Copy project static assets (both blog and personal)
-        self.copy_static_assets()?;
+    /// The posts of the last build and the pages they appear on. Only blog
+    /// builds record them.
+    pub fn take_dependencies(&self) -> Option<DependencyGraph> {
+        self.dependencies.take()
+    }
 
-        // Generate CNAME file if domain configuration exists
-        self.generate_cname_file()?;
+    /// Update the output of the build `previous` describes after the post
+    /// files in `changed` were edited, added or removed, writing only their
+    /// pages and the listings they appear on. Hooks aren't run. Returns None
+    /// when a full build is needed: when anything other than posts changed,
+    /// or for personal and hybrid sites.
+    pub fn rebuild_posts(
+        &self,
+        previous: &DependencyGraph,
+        changed: &[PathBuf],
+    ) -> Result<Option<BuildSummary>> {
+        let posts_dir = self.project.posts_dir();
+        let only_posts = changed.iter().all(|path| {
+            path.starts_with(&posts_dir) && path.extension().is_some_and(|ext| ext == "md")
+        });
+        if !only_posts || matches!(self.config.site.site_type.as_str(), "personal" | "hybrid") {
+            return Ok(None);
+        }
 
-        println!(
-            "✅ Site built successfully to: {}",
-            self.output_dir.display()
+        let all_posts = self.load_posts()?;
+        let graph = DependencyGraph::new(&all_posts);
+        let plan = previous.plan(&graph, changed);
+        crate::status!(
+            "🔁 Rebuilding {} post(s), {} tag(s) and {} archive month(s)",
+            plan.posts.len(),
+            plan.tags.len(),
+            plan.months.len()
         );
-        Ok(())
+
+        for slug in &plan.removed {
+            self.remove_page(&format!("posts/{}.html", slug))?;
+            remove_file(&self.output_dir.join(reader::plain_path(slug)))?;
+            for format in OutputFormat::resolve(&self.config.output_formats)? {
+                remove_file(&self.output_dir.join(format.path(slug)))?;
+            }
+            remove_file(&self.output_dir.join(content_api::post_path(slug)))?;
+        }
+        for tag in plan.tags.iter().filter(|tag| !graph.has_tag(tag)) {
+            self.remove_page(&format!("tags/{}.html", tag))?;
+            remove_file(&self.output_dir.join(tag_feed_path(tag)))?;
+        }
+        for &(year, month) in &plan.months {
+            if !graph.has_month(year, month) {
+                self.remove_page(&month_path(year, month))?;
+            }
+            if !graph.has_year(year) {
+                self.remove_page(&year_path(year))?;
+            }
+        }
+
+        let changed_posts: Vec<Post> = all_posts
+            .iter()
+            .filter(|post| plan.posts.contains(&post.metadata.slug))
+            .cloned()
+            .collect();
+        self.generate_post_pages(&changed_posts)?;
+        if changed_posts.iter().any(|post| post.metadata.private) {
+            self.write_script(PRIVATE_SCRIPT, EMBEDDED_PRIVATE_JS)?;
+        }
+        self.generate_index_page(&all_posts)?;
+        self.generate_archive_pages(&all_posts, Some(&plan.months))?;
+        self.generate_tag_pages(&all_posts, Some(&plan.tags))?;
+
+        let public_posts: Vec<Post> = all_posts
+            .iter()
+            .filter(|post| !post.metadata.private)
+            .cloned()
+            .collect();
+        self.generate_rss_feed(&public_posts)?;
+        self.generate_posts_json(&public_posts)?;
+        self.generate_content_api(&public_posts)?;
+        self.generate_search_index(&public_posts, &[])?;
+        self.generate_sitemap(&all_posts, &[])?;
+        self.generate_redirects(&all_posts)?;
+
+        *self.dependencies.borrow_mut() = Some(graph);
+        let (cache_hits, cache_misses) = self.cache.usage();
+        Ok(Some(BuildSummary {
+            posts: all_posts.len(),
+            pages: 0,
+            cache_hits,
+            cache_misses,
+        }))
+    }
+
+    /// Delete a page written by `write_page`, if it exists
+    fn remove_page(&self, path: &str) -> Result<()> {
+        remove_file(&self.output_dir.join(self.config.urls.output_path(path)))
+    }
+
+    /// Builder for the blog of a hybrid site, writing to `/blog/` in the output directory
+    fn blog_section(&self) -> Result<Self> {
+        let config = self.config.blog_section();
+        let output_dir = self
+            .output_dir
+            .join(config.urls.section.as_deref().unwrap_or_default());
+        output::create_dir_all(&output_dir)
+            .map_err(|e| anyhow!("Failed to create blog output directory: {}", e))?;
+        Ok(Self::new_with_config(
+            self.project.clone(),
+            config,
+            Some(output_dir),
+            self.include_drafts,
+            self.include_future,
+        )?
+        .with_profile(self.profile.clone()))
     }
 
     /// Check if a post should be included in the build
     fn should_include_post(&self, post: &Post) -> bool {
-        // Check draft status
-        if post.metadata.status == PostStatus::Draft && !self.include_drafts {
-            return false;
+        // Check workflow status: archived posts are never built, drafts and
+        // posts in review only when drafts are included
+        match post.metadata.status {
+            PostStatus::Archived => return false,
+            status if status.is_unfinished() && !self.include_drafts => return false,
+            _ => {}
         }
 
         // Check future posts
@@ -309,137 +686,1065 @@ Thank you!`);
 
     /// Clean the output directory
     fn clean_output_dir(&self) -> Result<()> {
-        if self.output_dir.exists() {
-            fs::remove_dir_all(&self.output_dir)
+        if output::exists(&self.output_dir) {
+            output::remove_dir_all(&self.output_dir)
                 .map_err(|e| anyhow!("Failed to clean output directory: {}", e))?;
         }
-        fs::create_dir_all(&self.output_dir)
+        output::create_dir_all(&self.output_dir)
             .map_err(|e| anyhow!("Failed to create output directory: {}", e))?;
         Ok(())
     }
 
+    /// Fill post `updated_at` and contributors from git history, warning if unavailable
+    fn apply_git_metadata(&self, posts: &mut [Post]) {
+        let result = GitMetadata::discover(&self.project.root).and_then(|git| git.apply(posts));
+        if let Err(e) = result {
+            Console::warn(&format!("Skipping git metadata: {}", e));
+        }
+    }
+
     /// Generate individual post pages
     fn generate_post_pages(&self, posts: &[Post]) -> Result<()> {
+        if self.config.reader.print_css && !posts.is_empty() {
+            let stylesheet = self.output_dir.join(reader::PRINT_STYLESHEET);
+            if let Some(parent) = stylesheet.parent() {
+                output::create_dir_all(parent)?;
+            }
+            output::write(&stylesheet, reader::PRINT_CSS)
+                .map_err(|e| anyhow!("Failed to write print stylesheet: {}", e))?;
+        }
+
+        let output_formats = self.output_formats()?;
+        let progress = Progress::new(posts.len() as u64, "Rendering posts");
         for post in posts {
-            let mut context = Context::new();
+            let path = format!("posts/{}.html", post.metadata.slug);
+            let _span = tracing::trace_span!("page", path = %path).entered();
+            let html = self.render_post(post)?;
 
-            // Add site config
-            context.insert("site", &self.config);
+            // Write to file
+            self.write_page(&path, html)
+                .map_err(|e| anyhow!("Failed to write post file: {}", e))?;
+
+            if self.config.reader.plain && !post.metadata.private {
+                // Written as-is, beside the post's folder whatever the URL style
+                let file = self
+                    .output_dir
+                    .join(reader::plain_path(&post.metadata.slug));
+                if let Some(parent) = file.parent() {
+                    output::create_dir_all(parent)?;
+                }
+                output::write(&file, self.render_plain_post(post)?)
+                    .map_err(|e| anyhow!("Failed to write plain post file: {}", e))?;
+            }
+            if !post.metadata.private {
+                for format in &output_formats {
+                    let file = self.output_dir.join(format.path(&post.metadata.slug));
+                    if let Some(parent) = file.parent() {
+                        output::create_dir_all(parent)?;
+                    }
+                    output::write(&file, self.render_post_format(post, format)?).map_err(|e| {
+                        anyhow!("Failed to write the {} copy of a post: {}", format.name, e)
+                    })?;
+                }
+            }
 
-            // Add newsletter config and generated form
-            context.insert("newsletter", &self.config.newsletter);
-            context.insert("newsletter_form", &self.generate_newsletter_form());
+            if let Some(dir) = self.bundle_dir(post) {
+                copy_bundle(dir, &self.output_dir, &post.metadata.slug)?;
+            }
+            if let Some(cover) = self.post_cover(post)? {
+                self.write_cover_sizes(&cover)?;
+            }
+            progress.inc();
+        }
+        Ok(())
+    }
+
+    /// Publish a cover's resized copies, made through the build cache
+    fn write_cover_sizes(&self, cover: &Cover) -> Result<()> {
+        let Some(source_path) = &cover.source else {
+            return Ok(());
+        };
+        if cover.sizes.is_empty() {
+            return Ok(());
+        }
+        let source = fs::read(source_path)?;
+        for size in &cover.sizes {
+            let key = BuildCache::key(&[
+                &source,
+                &size.width.to_le_bytes(),
+                &size.height.to_le_bytes(),
+            ]);
+            let copy = self.cache.get_or_insert_bytes("cover", &key, || {
+                resized(&source, size.width, size.height)
+                    .map_err(|e| anyhow!("Failed to resize cover {}: {}", source_path.display(), e))
+            })?;
+            let file = self.output_dir.join(&size.path);
+            if let Some(parent) = file.parent() {
+                output::create_dir_all(parent)?;
+            }
+            output::write(&file, copy)?;
+        }
+        Ok(())
+    }
+
+    /// Render markdown to HTML through the build cache
+    fn render_markdown(&self, markdown: &str) -> Result<String> {
+        let _span = tracing::trace_span!("markdown").entered();
+        let markdown = self.expand_shortcodes(markdown)?;
+        let key = BuildCache::key(&[markdown.as_bytes(), &self.markdown_options()]);
+        let html = self.cache.get_or_insert_with("markdown", &key, || {
+            render_markdown_with(
+                &markdown,
+                &self.config.markdown,
+                self.site_host().as_deref(),
+                |dest| self.alias_link(dest),
+            )
+        })?;
+        Ok(self.private_embeds(&html))
+    }
+
+    /// Apply `[privacy.embeds]` to embeds pasted into markdown as HTML
+    fn private_embeds(&self, html: &str) -> String {
+        embeds::rewrite(
+            html,
+            &self.config.privacy.embeds,
+            &self.config.markdown.video,
+            |path| self.root_url(path),
+        )
+    }
+
+    /// Render a theme template, timed for `--profile-build`. Errors are
+    /// reported as a `TemplateError` naming what was being rendered.
+    fn render_template(
+        &self,
+        template: &str,
+        context: &Context,
+        rendering: &str,
+    ) -> Result<String> {
+        let _span = tracing::trace_span!("templates", template).entered();
+        let html = self.tera.render(template, context).map_err(|e| {
+            TemplateError::new(&e, template, Some(rendering.to_string()), |name| {
+                self.template_source(name)
+            })
+        })?;
+        let html = feeds::add_discovery(&html, &self.site_feeds());
+        Ok(self.fonts.rewrite(&html, |path| {
+            self.root_url(&self.config.urls.asset_path(path))
+        }))
+    }
+
+    /// The blog's RSS and Atom feeds, which every page advertises; personal
+    /// sites have none
+    fn site_feeds(&self) -> Vec<FeedLink> {
+        if self.config.site.site_type == "personal" {
+            return Vec::new();
+        }
+        vec![
+            FeedLink {
+                title: self.config.blog.title.clone(),
+                href: self.feed_url("rss.xml"),
+                mime: RSS_TYPE,
+            },
+            FeedLink {
+                title: format!("{} (Atom)", self.config.blog.title),
+                href: self.feed_url("atom.xml"),
+                mime: ATOM_TYPE,
+            },
+        ]
+    }
+
+    /// The RSS feeds of these tags
+    fn tag_feeds(&self, tags: &[String]) -> Vec<FeedLink> {
+        tags.iter()
+            .map(|tag| FeedLink {
+                title: format!("{}: {}", self.config.blog.title, tag),
+                href: self.feed_url(&tag_feed_path(tag)),
+                mime: RSS_TYPE,
+            })
+            .collect()
+    }
+
+    /// Link to one of the blog's feeds; a hybrid site's root pages link to
+    /// the blog section's
+    fn feed_url(&self, path: &str) -> String {
+        if self.config.site.site_type == "hybrid" && self.config.urls.section.is_none() {
+            self.root_url(&format!("{}/{}", BLOG_SECTION, path))
+        } else {
+            self.root_url(&self.config.urls.asset_path(path))
+        }
+    }
+
+    /// The source of a theme or built-in template
+    fn template_source(&self, name: &str) -> Option<String> {
+        self.theme
+            .templates()
+            .into_iter()
+            .find(|(theme_name, _)| theme_name == name)
+            .map(|(_, source)| source.into_owned())
+            .or_else(|| {
+                DEFAULT_TEMPLATES
+                    .iter()
+                    .find(|(default, _)| *default == name)
+                    .map(|(_, source)| source.to_string())
+            })
+    }
+
+    /// The options that change rendered HTML, for cache keys: `[markdown]`
+    /// settings, and the domains whose links are pointed at the main one
+    fn markdown_options(&self) -> Vec<u8> {
+        let markdown = &self.config.markdown;
+        let mut options = vec![
+            markdown.emoji as u8,
+            markdown.smart_punctuation as u8,
+            markdown.external_links as u8,
+        ];
+        for alias in self.config.alias_domains() {
+            options.extend_from_slice(alias.as_bytes());
+            options.push(b' ');
+        }
+        options
+    }
+
+    /// Host of the site's base URL; links elsewhere are external
+    fn site_host(&self) -> Option<String> {
+        url::Url::parse(&self.config.get_effective_base_url())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+    }
+
+    /// A link to an alias domain, moved to the main domain so it doesn't
+    /// take a redirect
+    fn alias_link(&self, dest: &str) -> Option<String> {
+        domains::canonical_link(
+            dest,
+            &self.config.alias_domains(),
+            &self.config.get_effective_base_url(),
+        )
+    }
+
+    /// Render markdown from a post, pointing relative links at the files of its page bundle
+    fn render_post_markdown(&self, post: &Post, markdown: &str) -> Result<String> {
+        let Some(dir) = self.bundle_dir(post) else {
+            return self.render_markdown(markdown);
+        };
+        let _span = tracing::trace_span!("markdown").entered();
+        let markdown = self.expand_shortcodes(markdown)?;
+        let slug = &post.metadata.slug;
+        // Links depend on where the files are published and which ones exist
+        let base = self.root_url(&self.config.urls.asset_path(&bundle_output_dir(slug)));
+        let files = bundle_files(dir).join("\n");
+        let key = BuildCache::key(&[
+            markdown.as_bytes(),
+            &self.markdown_options(),
+            base.as_bytes(),
+            files.as_bytes(),
+        ]);
+        let html = self.cache.get_or_insert_with("markdown", &key, || {
+            render_markdown_with(
+                &markdown,
+                &self.config.markdown,
+                self.site_host().as_deref(),
+                |dest| {
+                    bundle_link(dir, slug, dest)
+                        .map(|path| self.root_url(&self.config.urls.asset_path(&path)))
+                        .or_else(|| self.alias_link(dest))
+                },
+            )
+        })?;
+        Ok(self.private_embeds(&html))
+    }
+
+    /// Folder of a post's page bundle, if it has one
+    fn bundle_dir<'a>(&self, post: &'a Post) -> Option<&'a Path> {
+        post.bundle_dir()
+            .filter(|dir| *dir != self.project.posts_dir())
+    }
+
+    /// Replace shortcodes such as `{{< gallery name >}}` with their HTML
+    fn expand_shortcodes(&self, markdown: &str) -> Result<String> {
+        shortcodes::expand(markdown, |shortcode| match shortcode.name.as_str() {
+            "gallery" => {
+                let name = shortcode
+                    .positional(0)
+                    .ok_or_else(|| anyhow!("The gallery shortcode needs a gallery name"))?;
+                let gallery = self
+                    .galleries
+                    .iter()
+                    .find(|gallery| gallery.name == name)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Unknown gallery '{}': no folder {}/{}",
+                            name,
+                            GALLERIES_DIR,
+                            name
+                        )
+                    })?;
+                Ok(gallery.embed_html(|path| self.root_url(path)))
+            }
+            "youtube" | "vimeo" | "video" => self.video_html(
+                &Video::from_shortcode(shortcode)?,
+                VideoOptions::from_shortcode(shortcode),
+            ),
+            other => Err(anyhow!("Unknown shortcode '{}'", other)),
+        })
+    }
+
+    /// Embed HTML for a video, making a poster for local files that have none
+    fn video_html(&self, video: &Video, mut options: VideoOptions) -> Result<String> {
+        if options.poster.is_none() && self.config.markdown.video.posters {
+            if let Some(src) = video.local_file() {
+                options.poster = self.video_poster(src);
+            }
+        }
+        Ok(video::embed_html(
+            video,
+            &options,
+            &embeds::video_config(
+                &self.config.privacy.embeds,
+                video,
+                &self.config.markdown.video,
+            ),
+            |path| self.root_url(path),
+        ))
+    }
+
+    /// Write the poster frame of a project video to the output, returning its site path.
+    /// A video is read once per build, however many pages embed it.
+    fn video_poster(&self, src: &str) -> Option<String> {
+        if let Some(poster) = self.posters.borrow().get(src) {
+            return poster.clone();
+        }
+
+        let file = self.project.root.join(src);
+        let poster = if file.is_file() {
+            let path = poster_path(src);
+            let made = fs::read(&file)
+                .map_err(anyhow::Error::from)
+                .and_then(|source| {
+                    let key = BuildCache::key(&[&source]);
+                    self.cache.get_or_insert_bytes("poster", &key, || {
+                        poster_frame(&file, self.config.markdown.video.ffmpeg.as_deref())
+                    })
+                })
+                .and_then(|jpeg| {
+                    let output = self.site_output_dir().join(&path);
+                    if let Some(parent) = output.parent() {
+                        output::create_dir_all(parent)?;
+                    }
+                    Ok(output::write(output, jpeg)?)
+                });
+            match made {
+                Ok(()) => Some(path),
+                Err(e) => {
+                    Console::warn(&format!("No poster for {}: {}", src, e));
+                    None
+                }
+            }
+        } else {
+            Console::warn(&format!("Video file not found: {}", file.display()));
+            None
+        };
+
+        self.posters
+            .borrow_mut()
+            .insert(src.to_string(), poster.clone());
+        poster
+    }
+
+    /// Output directory of the whole site; a hybrid site's blog builds into a folder of it
+    fn site_output_dir(&self) -> &Path {
+        match self.config.urls.section {
+            Some(_) => self.output_dir.parent().unwrap_or(&self.output_dir),
+            None => &self.output_dir,
+        }
+    }
+
+    /// Link to a file from the root of the site, for HTML written outside templates
+    fn root_url(&self, path: &str) -> String {
+        if std::env::var("BLOGR_DEV").is_ok() {
+            format!("/{}", path)
+        } else {
+            format!("{}/{}", self.config.get_effective_base_url(), path)
+        }
+    }
+
+    /// Absolute link to a published file, for feeds and social cards
+    fn absolute_url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.config.get_effective_base_url().trim_end_matches('/'),
+            self.config.urls.asset_path(path)
+        )
+    }
+
+    /// A post's `cover`, resolved against the project and its bundle
+    fn post_cover(&self, post: &Post) -> Result<Option<Cover>> {
+        Cover::resolve(post, &self.project.root)
+    }
+
+    /// A post's summary as HTML for listings and feeds: the part before its
+    /// `<!--more-->` marker, else its description, else its first words
+    fn post_summary(&self, post: &Post) -> Result<String> {
+        if let Some(markdown) = post.summary_markdown() {
+            return self.render_post_markdown(post, markdown);
+        }
+        let description = post.metadata.description.trim();
+        let text = if description.is_empty() {
+            // Embeds have no words worth summarizing
+            let markdown = shortcodes::expand(&post.content, |_| Ok(String::new()))?;
+            crate::generator::markdown::extract_excerpt(
+                &markdown,
+                self.config.markdown.summary_words,
+            )
+        } else {
+            description.to_string()
+        };
+        Ok(format!("<p>{}</p>", html_escape(&text)))
+    }
+
+    /// Render a post page with the theme's `post.html`
+    pub fn render_post(&self, post: &Post) -> Result<String> {
+        let mut context = Context::new();
 
-            // Add post data
+        // Add site config
+        context.insert("site", &self.config);
+
+        // Add newsletter config and generated form
+        context.insert("newsletter", &self.config.newsletter);
+        context.insert("newsletter_form", &self.generate_newsletter_form()?);
+
+        // Add post data; a private post's markdown stays out of the page
+        if post.metadata.private {
+            let mut locked = post.clone();
+            locked.content.clear();
+            context.insert("post", &locked);
+        } else {
             context.insert("post", post);
+        }
+        context.insert("display_title", &post.display_title());
+        self.insert_canonical_url(&mut context, &format!("posts/{}.html", post.metadata.slug));
+        let plain_url = self.plain_url(post);
+        if let Some(plain_url) = &plain_url {
+            context.insert("plain_url", plain_url);
+        }
+        let format_urls = self.format_urls(post)?;
+        let alternates: Vec<Value> = format_urls
+            .iter()
+            .map(|(format, url)| {
+                serde_json::json!({
+                    "name": format.name,
+                    "media_type": format.media_type,
+                    "url": url,
+                })
+            })
+            .collect();
+        context.insert("formats", &alternates);
+        let content = self.post_content(post)?;
+        if post.metadata.private {
+            let sealed = private::seal(&content, &private::password(post)?, &self.config.private)?;
+            context.insert(
+                "content",
+                &private::locked_html(&sealed, &self.root_url(PRIVATE_SCRIPT)),
+            );
+        } else {
+            context.insert("content", &content);
+        }
 
-            // Convert markdown to HTML
-            let html_content = crate::generator::markdown::render_markdown(&post.content)?;
-            context.insert("content", &html_content);
+        let reading = post.reading_time(&self.config);
+        context.insert("reading_time", &reading.minutes);
+        context.insert("word_count", &reading.words);
+        let cover = self.post_cover(post)?;
+        if let Some(cover) = &cover {
+            context.insert(
+                "cover",
+                &cover.context(|path| self.root_url(&self.config.urls.asset_path(path))),
+            );
+        }
 
-            // Calculate reading time (average 200 words per minute)
-            let word_count = post.content.split_whitespace().count();
-            let reading_time = (word_count / 200).max(1);
-            context.insert("reading_time", &reading_time);
+        // Render template
+        let html = self.render_template(
+            "post.html",
+            &context,
+            &format!("post '{}'", post.metadata.slug),
+        )?;
+        let mut posting = structured_data::blog_posting(&self.config, post);
+        if let Some(cover) = &cover {
+            posting["image"] = cover.context(|path| self.absolute_url(path))["url"].clone();
+        }
+        let html = self.with_structured_data(
+            html,
+            &[
+                posting,
+                structured_data::post_breadcrumbs(&self.config, post),
+            ],
+        );
+        let html = feeds::add_discovery(&html, &self.tag_feeds(&post.metadata.tags));
+        let html = if post.metadata.noindex || post.metadata.private {
+            robots::add_noindex(&html)
+        } else {
+            html
+        };
 
-            // Render template
-            let html = self.tera.render("post.html", &context).map_err(|e| {
-                eprintln!("Full Tera error: {:?}", e);
-                anyhow!("Failed to render post template: {}", e)
-            })?;
+        let print_css = self
+            .config
+            .reader
+            .print_css
+            .then(|| self.root_url(&self.config.urls.asset_path(reader::PRINT_STYLESHEET)));
+        let mut tags = reader::head_tags(print_css.as_deref(), plain_url.as_deref());
+        if !format_urls.is_empty() {
+            if !tags.is_empty() {
+                tags.push('\n');
+            }
+            tags.push_str(&formats::head_tags(&format_urls));
+        }
+        // Themes that set their own social image keep it
+        if let Some(cover) = cover.filter(|_| !html.contains("og:image")) {
+            if !tags.is_empty() {
+                tags.push('\n');
+            }
+            tags.push_str(&cover.meta_tags(|path| self.absolute_url(path)));
+        }
+        Ok(if tags.is_empty() {
+            html
+        } else {
+            structured_data::insert_in_head(&html, &tags)
+        })
+    }
 
-            // Write to file
-            let post_dir = self.output_dir.join("posts");
-            fs::create_dir_all(&post_dir)?;
+    /// A post's HTML, with its frontmatter video above it
+    fn post_content(&self, post: &Post) -> Result<String> {
+        let html_content = self.render_post_markdown(post, &post.content)?;
+        let Some(source) = &post.metadata.video else {
+            return Ok(html_content);
+        };
+        let options = VideoOptions {
+            title: Some(post.display_title()),
+            ..VideoOptions::default()
+        };
+        let video = Video::parse(source)
+            .and_then(|video| self.video_html(&video, options))
+            .map_err(|e| anyhow!("Invalid video in '{}': {}", post.metadata.slug, e))?;
+        Ok(format!("{}\n{}", video, html_content))
+    }
+
+    /// URL of a post's plain copy, when they are written
+    fn plain_url(&self, post: &Post) -> Option<String> {
+        (self.config.reader.plain && !post.metadata.private).then(|| {
+            self.root_url(
+                &self
+                    .config
+                    .urls
+                    .asset_path(&reader::plain_path(&post.metadata.slug)),
+            )
+        })
+    }
+
+    /// The formats of `[[output_formats]]`, once their templates are known to exist
+    fn output_formats(&self) -> Result<Vec<OutputFormat>> {
+        let formats = OutputFormat::resolve(&self.config.output_formats)?;
+        for format in &formats {
+            let template = format.template();
+            if !self.tera.get_template_names().any(|name| name == template) {
+                anyhow::bail!(
+                    "Output format '{}' needs the template '{}' in the theme",
+                    format.name,
+                    template
+                );
+            }
+        }
+        Ok(formats)
+    }
+
+    /// The other formats of a post with their URLs; private posts have none
+    fn format_urls(&self, post: &Post) -> Result<Vec<(OutputFormat, String)>> {
+        if post.metadata.private {
+            return Ok(Vec::new());
+        }
+        Ok(OutputFormat::resolve(&self.config.output_formats)?
+            .into_iter()
+            .map(|format| {
+                let url = self.root_url(
+                    &self
+                        .config
+                        .urls
+                        .asset_path(&format.path(&post.metadata.slug)),
+                );
+                (format, url)
+            })
+            .collect())
+    }
+
+    /// Render a post with the template of one of `[[output_formats]]`
+    fn render_post_format(&self, post: &Post, format: &OutputFormat) -> Result<String> {
+        let author = if post.metadata.author.trim().is_empty() {
+            &self.config.blog.author
+        } else {
+            &post.metadata.author
+        };
+        // Links in text and gemtext are read away from the site, so they need to be absolute
+        let resolve = |url: &str| match url.strip_prefix('/') {
+            Some(path) => format!(
+                "{}/{}",
+                self.config.get_effective_base_url().trim_end_matches('/'),
+                path
+            ),
+            None => url.to_string(),
+        };
+        let content = match format.content {
+            formats::Content::Html => self.post_content(post)?,
+            formats::Content::Text => formats::markdown_to_plain(&post.content, resolve),
+            formats::Content::Gemtext => formats::markdown_to_gemtext(&post.content, resolve),
+        };
+
+        let mut context = Context::new();
+        context.insert("site", &self.config);
+        context.insert("post", post);
+        context.insert("author", author);
+        context.insert(
+            "post_url",
+            &self
+                .config
+                .page_url(&format!("posts/{}.html", post.metadata.slug)),
+        );
+        context.insert("content", &content);
+        context.insert("reading_time", &post.reading_time(&self.config).minutes);
+        context.insert("title", &post.display_title());
+        context.insert("format", &format.name);
+        self.render_template(
+            &format.template(),
+            &context,
+            &format!("the {} copy of '{}'", format.name, post.metadata.slug),
+        )
+    }
+
+    /// Render a post without the theme's layout
+    fn render_plain_post(&self, post: &Post) -> Result<String> {
+        let author = if post.metadata.author.trim().is_empty() {
+            &self.config.blog.author
+        } else {
+            &post.metadata.author
+        };
+        let mut context = Context::new();
+        context.insert("site", &self.config);
+        context.insert("post", post);
+        context.insert("author", author);
+        context.insert(
+            "post_url",
+            &self
+                .config
+                .page_url(&format!("posts/{}.html", post.metadata.slug)),
+        );
+        context.insert("content", &self.post_content(post)?);
+        context.insert("reading_time", &post.reading_time(&self.config).minutes);
+        self.render_template(
+            "plain.html",
+            &context,
+            &format!("the plain copy of '{}'", post.metadata.slug),
+        )
+    }
+
+    /// Add JSON-LD to the head of a rendered page, unless turned off
+    fn with_structured_data(&self, html: String, items: &[Value]) -> String {
+        if !self.config.structured_data.enabled {
+            return html;
+        }
+        structured_data::insert_in_head(&html, &structured_data::script(items))
+    }
+
+    /// `Person` data for the home page of personal and hybrid sites
+    fn person_data(&self) -> Result<Value> {
+        let resume = load_resume(&self.project.root)?;
+        Ok(structured_data::person(&self.config, resume.as_ref()))
+    }
+
+    /// Variables shared by the home page and the standalone pages of a personal website
+    fn personal_context(&self, pages: &[Page]) -> Result<Context> {
+        let mut context = Context::new();
+
+        // Add site config with all necessary fields
+        context.insert("blog_title", &self.config.blog.title);
+        context.insert("blog_description", &self.config.blog.description);
+        context.insert("author", &self.config.blog.author);
+        context.insert("base_url", &self.config.get_effective_base_url());
+        context.insert(
+            "language",
+            &self.config.blog.language.as_deref().unwrap_or("en"),
+        );
+        context.insert("site", &self.config);
+        context.insert("theme_config", &self.config.theme.config);
+
+        // Add current year
+        context.insert("current_year", &Utc::now().year());
+
+        // Navigation between the home page, pages from content/ and the resume
+        let mut nav: Vec<Value> = pages
+            .iter()
+            .filter(|page| page.nav)
+            .map(|page| {
+                serde_json::json!({
+                    "title": page.title,
+                    "slug": page.slug,
+                    "path": page.output_path(),
+                })
+            })
+            .collect();
+        if find_resume(&self.project.root).is_some() {
+            nav.push(serde_json::json!({
+                "title": "Resume",
+                "slug": "resume",
+                "path": RESUME_PAGE,
+            }));
+        }
+        context.insert("pages", &nav);
+        context.insert("docs", &DocsNav::new(&self.config.docs, pages)?.context(""));
+
+        // Repositories fetched by `github_projects::refresh` before the build
+        context.insert(
+            "github_projects",
+            &github_projects::load_projects(&self.project.root, &self.config.github_projects),
+        );
+
+        context.insert("contact_form", &self.generate_contact_form()?);
+
+        // A hybrid site's pages link to its blog
+        if self.config.site.site_type == "hybrid" {
+            context.insert("blog_path", &self.config.blog_section().urls.page_path(""));
+        }
+
+        // Read and parse content.md for sections data
+        if let Some(content_md) = self.read_content_md()? {
+            // Parse frontmatter to get sections
+            if let Ok((frontmatter, _)) = self.parse_frontmatter(&content_md) {
+                if let Ok(frontmatter_data) =
+                    serde_yaml::from_str::<serde_yaml::Value>(&frontmatter)
+                {
+                    // Override description with content.md description if it exists
+                    if let Some(description) = frontmatter_data.get("description") {
+                        if let Some(desc_str) = description.as_str() {
+                            context.insert("blog_description", desc_str);
+                        }
+                    }
+
+                    // Override title with content.md title if it exists
+                    if let Some(title) = frontmatter_data.get("title") {
+                        if let Some(title_str) = title.as_str() {
+                            context.insert("blog_title", title_str);
+                        }
+                    }
+
+                    // Extract sections if they exist
+                    if let Some(sections) = frontmatter_data.get("sections") {
+                        context.insert("sections", sections);
+                    }
+
+                    // Override theme_config with content.md theme_config if it exists
+                    if let Some(content_theme_config) = frontmatter_data.get("theme_config") {
+                        context.insert("theme_config", content_theme_config);
+                    }
+                }
+            }
+        }
+
+        Ok(context)
+    }
+
+    /// The site's content.md, if it has one
+    fn read_content_md(&self) -> Result<Option<String>> {
+        // Use pre-loaded content if available (for deployment with uncommitted changes),
+        // otherwise read from disk
+        if let Some(preloaded) = &self.content_md {
+            return Ok(Some(preloaded.clone()));
+        }
+
+        let content_md_path = self.project.root.join("content.md");
+        if !content_md_path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&content_md_path)
+            .map(Some)
+            .map_err(|e| anyhow!("Failed to read content.md: {}", e))
+    }
+
+    /// Generate the landing page of a hybrid site from content.md and the latest posts
+    fn generate_hybrid_home(&self, pages: &[Page], posts: &[Post]) -> Result<()> {
+        if !self
+            .tera
+            .get_template_names()
+            .any(|name| name == "home.html")
+        {
+            return Err(anyhow!(
+                "Theme '{}' has no home.html and can't build hybrid sites. \
+                 Choose a theme that supports them with 'blogr theme list'.",
+                self.config.theme.name
+            ));
+        }
+
+        let mut context = self.personal_context(pages)?;
+        context.insert("current_page", "");
+        self.insert_canonical_url(&mut context, "index.html");
+
+        context.insert("content", &self.content_md_body()?);
+
+        let blog_urls = self.config.blog_section().urls;
+        let recent_posts: Vec<Value> = posts
+            .iter()
+            .take(5)
+            .map(|post| {
+                serde_json::json!({
+                    "metadata": post.metadata,
+                    "path": blog_urls.page_path(&format!("posts/{}.html", post.metadata.slug)),
+                })
+            })
+            .collect();
+        context.insert("recent_posts", &recent_posts);
+
+        let html = self.render_template("home.html", &context, "the home page")?;
+        let html = self.with_structured_data(html, &[self.person_data()?]);
+        output::write(self.output_dir.join("index.html"), html)
+            .map_err(|e| anyhow!("Failed to write index file: {}", e))?;
+
+        Ok(())
+    }
+
+    /// content.md's body, below the frontmatter, rendered
+    fn content_md_body(&self) -> Result<String> {
+        match self.read_content_md()? {
+            Some(content_md) => {
+                let body = self
+                    .parse_frontmatter(&content_md)
+                    .map(|(_, body)| body)
+                    .unwrap_or(content_md);
+                self.render_markdown(&body)
+            }
+            None => Ok(String::new()),
+        }
+    }
+
+    /// Generate personal website index page
+    fn generate_personal_index(&self, pages: &[Page]) -> Result<()> {
+        let mut context = self.personal_context(pages)?;
+        context.insert("current_page", "");
+        context.insert("content", &self.content_md_body()?);
+        self.insert_canonical_url(&mut context, "index.html");
+
+        // Render template
+        let html = self.render_template("index.html", &context, "the home page")?;
+        let html = self.with_structured_data(html, &[self.person_data()?]);
+
+        // Write to file
+        let index_file = self.output_dir.join("index.html");
+        output::write(&index_file, html)
+            .map_err(|e| anyhow!("Failed to write index file: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Generate a page for each markdown file in `content/`
+    fn generate_personal_pages(&self, pages: &[Page]) -> Result<()> {
+        if pages.is_empty() {
+            return Ok(());
+        }
+
+        let shared = self.personal_context(pages)?;
+        let docs_nav = DocsNav::new(&self.config.docs, pages)?;
+        for page in pages {
+            let _span = tracing::trace_span!("page", path = %page.output_path()).entered();
+            let mut context = shared.clone();
+            context.insert("page", page);
+            context.insert("current_page", &page.slug);
+            context.insert("docs", &docs_nav.context(&page.slug));
+            context.insert("content", &self.render_markdown(&page.content)?);
+            self.insert_canonical_url(&mut context, &page.output_path());
+
+            let mut html =
+                self.render_template("page.html", &context, &format!("page '{}'", page.slug))?;
+            html = self.with_structured_data(
+                html,
+                &[structured_data::page_breadcrumbs(&self.config, page, pages)],
+            );
+            if page.noindex {
+                html = robots::add_noindex(&html);
+            }
+            self.write_page(&page.output_path(), html)
+                .map_err(|e| anyhow!("Failed to write page '{}': {}", page.slug, e))?;
+        }
+
+        crate::status!("📄 Generated {} page(s) from content/", pages.len());
+        Ok(())
+    }
+
+    /// The page the contact form's backend sends readers to
+    fn generate_contact_thanks(&self, pages: &[Page]) -> Result<()> {
+        if !self.config.contact.enabled {
+            return Ok(());
+        }
+        let mut context = self.personal_context(pages)?;
+        context.insert("current_page", "");
+        let html = self.render_template("contact_thanks.html", &context, "the contact page")?;
+        self.write_page(THANKS_PAGE, robots::add_noindex(&html))
+            .map_err(|e| anyhow!("Failed to write the contact page: {}", e))
+    }
 
-            let post_file = post_dir.join(format!("{}.html", post.metadata.slug));
-            fs::write(&post_file, html).map_err(|e| anyhow!("Failed to write post file: {}", e))?;
+    /// Generate `404.html` and the pages configured in `[error_pages]`
+    fn generate_error_pages(&self, pages: &[Page]) -> Result<()> {
+        let mut shared = self.personal_context(pages)?;
+        shared.insert("newsletter", &self.config.newsletter);
+        shared.insert("newsletter_form", &self.generate_newsletter_form()?);
+        shared.insert("current_page", "");
+
+        for error in error_pages::load(&self.project.root, &self.config)? {
+            let mut context = shared.clone();
+            context.insert("error", &error);
+            context.insert("content", &self.render_markdown(&error.content)?);
+
+            let template = error.template();
+            let template = if self.tera.get_template_names().any(|name| name == template) {
+                template.as_str()
+            } else {
+                "error.html"
+            };
+            let html = self.render_template(
+                template,
+                &context,
+                &format!("the {} error page", error.status),
+            )?;
+            // Written as-is: error pages must stay at e.g. /404.html whatever
+            // the URL style
+            output::write(
+                self.output_dir.join(error.output_path()),
+                robots::add_noindex(&html),
+            )
+            .map_err(|e| anyhow!("Failed to write {}: {}", error.output_path(), e))?;
         }
         Ok(())
     }
 
-    /// Generate personal website index page
-    fn generate_personal_index(&self) -> Result<()> {
-        let mut context = Context::new();
-
-        // Add site config with all necessary fields
-        context.insert("blog_title", &self.config.blog.title);
-        context.insert("blog_description", &self.config.blog.description);
-        context.insert("author", &self.config.blog.author);
-        context.insert("base_url", &self.config.get_effective_base_url());
-        context.insert(
-            "language",
-            &self.config.blog.language.as_deref().unwrap_or("en"),
-        );
-        context.insert("site", &self.config);
-        context.insert("theme_config", &self.config.theme.config);
+    /// Generate `/resume/` from `resume.yaml`, and `resume.pdf` when enabled
+    fn generate_resume(&self, pages: &[Page]) -> Result<()> {
+        let Some(resume) = load_resume(&self.project.root)? else {
+            return Ok(());
+        };
+        if pages.iter().any(|page| page.slug == "resume") {
+            return Err(anyhow!(
+                "Both content/ and the resume data publish /resume/; remove or rename one"
+            ));
+        }
 
-        // Add current year
-        context.insert("current_year", &Utc::now().year());
+        let mut context = self.personal_context(pages)?;
+        context.insert("resume", &resume);
+        context.insert("current_page", "resume");
+        self.insert_canonical_url(&mut context, RESUME_PAGE);
 
-        // Read and parse content.md for sections data
-        // Use pre-loaded content if available (for deployment with uncommitted changes),
-        // otherwise read from disk
-        let content_md = if let Some(preloaded) = &self.content_md {
-            Some(preloaded.clone())
-        } else {
-            let content_md_path = self.project.root.join("content.md");
-            if content_md_path.exists() {
-                Some(
-                    fs::read_to_string(&content_md_path)
-                        .map_err(|e| anyhow!("Failed to read content.md: {}", e))?,
-                )
-            } else {
-                None
-            }
-        };
+        if self.config.resume.pdf && self.print_resume(&context)? {
+            context.insert("resume_pdf", "resume.pdf");
+        }
 
-        if let Some(content_md) = content_md {
-            // Parse frontmatter to get sections
-            if let Ok((frontmatter, _)) = self.parse_frontmatter(&content_md) {
-                if let Ok(frontmatter_data) =
-                    serde_yaml::from_str::<serde_yaml::Value>(&frontmatter)
-                {
-                    // Override description with content.md description if it exists
-                    if let Some(description) = frontmatter_data.get("description") {
-                        if let Some(desc_str) = description.as_str() {
-                            context.insert("blog_description", desc_str);
-                        }
-                    }
+        let html = self.render_template("resume.html", &context, "the resume")?;
+        self.write_page(RESUME_PAGE, html)
+            .map_err(|e| anyhow!("Failed to write resume page: {}", e))?;
 
-                    // Override title with content.md title if it exists
-                    if let Some(title) = frontmatter_data.get("title") {
-                        if let Some(title_str) = title.as_str() {
-                            context.insert("blog_title", title_str);
-                        }
-                    }
+        crate::status!("📇 Generated resume page");
+        Ok(())
+    }
 
-                    // Extract sections if they exist
-                    if let Some(sections) = frontmatter_data.get("sections") {
-                        context.insert("sections", sections);
-                    }
+    /// Publish each gallery's images, thumbnails and page
+    fn generate_galleries(&self, pages: &[Page]) -> Result<()> {
+        if self.galleries.is_empty() {
+            return Ok(());
+        }
 
-                    // Override theme_config with content.md theme_config if it exists
-                    if let Some(content_theme_config) = frontmatter_data.get("theme_config") {
-                        context.insert("theme_config", content_theme_config);
+        let images = self.galleries.iter().map(|g| g.images.len() as u64).sum();
+        let progress = Progress::new(images, "Making thumbnails");
+        for gallery in &self.galleries {
+            for image in &gallery.images {
+                progress.inc();
+                let _span = tracing::trace_span!("assets", image = %image.path).entered();
+                let source = fs::read(&image.source)?;
+                let key = BuildCache::key(&[&source, &THUMBNAIL_SIZE.to_le_bytes()]);
+                let thumb = self.cache.get_or_insert_bytes("thumbnail", &key, || {
+                    thumbnail(&source).map_err(|e| {
+                        anyhow!(
+                            "Failed to make a thumbnail of {}: {}",
+                            image.source.display(),
+                            e
+                        )
+                    })
+                })?;
+                for (path, content) in [(&image.path, &source), (&image.thumbnail, &thumb)] {
+                    let file = self.output_dir.join(path);
+                    if let Some(parent) = file.parent() {
+                        output::create_dir_all(parent)?;
                     }
+                    output::write(&file, content)?;
                 }
             }
-        }
 
-        // Render template
-        let html = self
-            .tera
-            .render("index.html", &context)
-            .map_err(|e| anyhow!("Failed to render personal index template: {}", e))?;
+            let mut context =
+                if matches!(self.config.site.site_type.as_str(), "personal" | "hybrid") {
+                    let mut context = self.personal_context(pages)?;
+                    context.insert("current_page", "");
+                    context
+                } else {
+                    let mut context = Context::new();
+                    context.insert("site", &self.config);
+                    context.insert("newsletter", &self.config.newsletter);
+                    context.insert("newsletter_form", &self.generate_newsletter_form()?);
+                    context
+                };
+            context.insert("gallery", gallery);
+            self.insert_canonical_url(&mut context, &gallery.path);
+
+            let html = self.render_template(
+                "gallery.html",
+                &context,
+                &format!("gallery '{}'", gallery.name),
+            )?;
+            self.write_page(&gallery.path, html)
+                .map_err(|e| anyhow!("Failed to write gallery page: {}", e))?;
+        }
 
-        // Write to file
-        let index_file = self.output_dir.join("index.html");
-        fs::write(&index_file, html).map_err(|e| anyhow!("Failed to write index file: {}", e))?;
+        let script = self.output_dir.join(GALLERY_SCRIPT);
+        if let Some(parent) = script.parent() {
+            output::create_dir_all(parent)?;
+        }
+        output::write(&script, EMBEDDED_GALLERY_JS)?;
 
+        crate::status!("🖼️  Generated {} gallery page(s)", self.galleries.len());
         Ok(())
     }
 
+    /// Print the standalone resume document to `resume.pdf`, returning whether it worked.
+    /// The document keeps the theme's header and remote assets out of the PDF.
+    fn print_resume(&self, context: &Context) -> Result<bool> {
+        // The browser prints from and to files on disk
+        if output::is_mounted(&self.output_dir) {
+            crate::status!("📄 Skipping resume.pdf, the site is in memory");
+            return Ok(false);
+        }
+        let html = self.render_template("resume_print.html", context, "the printable resume")?;
+        let output_dir = fs::canonicalize(&self.output_dir)?;
+        let print_file = output_dir.join(".resume-print.html");
+        fs::write(&print_file, html)?;
+
+        let result = print_pdf(
+            &print_file,
+            &output_dir.join("resume.pdf"),
+            self.config.resume.browser.as_deref(),
+        );
+        let _ = fs::remove_file(&print_file);
+
+        match result {
+            Ok(()) => {
+                crate::status!("📄 Generated resume.pdf");
+                Ok(true)
+            }
+            Err(e) => {
+                Console::warn(&format!("Skipping resume PDF: {}", e));
+                Ok(false)
+            }
+        }
+    }
+
     /// Parse frontmatter from markdown content
     fn parse_frontmatter(&self, content: &str) -> Result<(String, String)> {
         if !content.starts_with("---\n") && !content.starts_with("---\r\n") {
@@ -476,52 +1781,85 @@ Thank you!`);
 
         // Add newsletter config and generated form
         context.insert("newsletter", &self.config.newsletter);
-        context.insert("newsletter_form", &self.generate_newsletter_form());
+        context.insert("newsletter_form", &self.generate_newsletter_form()?);
 
         // Prepare posts with rendered content for index
         // Load first batch of posts for initial page load
-        let initial_posts: Vec<_> = posts.iter().take(10).collect();
+        let per_page = self.config.build.pagination.per_page();
+        let initial_posts: Vec<_> = posts.iter().take(per_page).collect();
         let mut posts_with_content = Vec::new();
 
         for post in &initial_posts {
-            // Convert markdown to HTML for each post
-            let html_content = crate::generator::markdown::render_markdown(&post.content)?;
-
-            // Calculate reading time (average 200 words per minute)
-            let word_count = post.content.split_whitespace().count();
-            let reading_time = (word_count / 200).max(1);
-
-            // Create a struct that includes both post data and rendered content
-            let post_data = serde_json::json!({
-                "metadata": post.metadata,
-                "content": html_content,
-                "reading_time": reading_time
-            });
-
-            posts_with_content.push(post_data);
+            posts_with_content.push(self.listing_post(post)?);
         }
 
         context.insert("posts", &posts_with_content);
 
-        // Add pagination info
-        context.insert("has_more", &(posts.len() > 10));
+        // The newest featured post, for themes with a hero; it may be older
+        // than the first page of posts
+        if let Some(post) = posts.iter().find(|post| post.metadata.featured) {
+            context.insert("featured_post", &self.listing_post(post)?);
+        }
+
+        // Add pagination info and the layout hint
+        context.insert("has_more", &(posts.len() > per_page));
         context.insert("total_posts", &posts.len());
+        context.insert("pagination", &self.pagination_context(posts.len()));
+        self.insert_canonical_url(&mut context, "index.html");
 
         // Render template
-        let html = self
-            .tera
-            .render("index.html", &context)
-            .map_err(|e| anyhow!("Failed to render index template: {}", e))?;
+        let html = self.render_template("index.html", &context, "the home page")?;
 
         // Write to file
         let index_file = self.output_dir.join("index.html");
-        fs::write(&index_file, html).map_err(|e| anyhow!("Failed to write index file: {}", e))?;
+        output::write(&index_file, html)
+            .map_err(|e| anyhow!("Failed to write index file: {}", e))?;
 
         Ok(())
     }
 
-    /// Generate archive pages
-    fn generate_archive_pages(&self, posts: &[Post]) -> Result<()> {
+    /// `pagination` for listing templates: the page size, the number of JSON
+    /// pages, and whether to show summaries or full posts (`null` leaves it
+    /// to the theme)
+    fn pagination_context(&self, total_posts: usize) -> Value {
+        let pagination = &self.config.build.pagination;
+        serde_json::json!({
+            "posts_per_page": pagination.per_page(),
+            "total_pages": total_posts.div_ceil(pagination.per_page()),
+            "index_content": pagination.index_content,
+        })
+    }
+
+    /// A post with its rendered content, summary and reading time, for listings
+    fn listing_post(&self, post: &Post) -> Result<Value> {
+        let html_content = self.render_post_markdown(post, &post.content)?;
+        let reading = post.reading_time(&self.config);
+        let cover = self
+            .post_cover(post)?
+            .map(|cover| cover.context(|path| self.root_url(&self.config.urls.asset_path(path))));
+        let (html_content, summary) = if post.metadata.private {
+            (PRIVATE_NOTE.to_string(), PRIVATE_NOTE.to_string())
+        } else {
+            (html_content, self.post_summary(post)?)
+        };
+        Ok(serde_json::json!({
+            "metadata": post.metadata,
+            "display_title": post.display_title(),
+            "cover": cover,
+            "content": html_content,
+            "summary": summary,
+            "reading_time": reading.minutes,
+            "word_count": reading.words
+        }))
+    }
+
+    /// Generate archive pages: the full archive, and the year and month
+    /// pages of `months`, or of every month with posts
+    fn generate_archive_pages(
+        &self,
+        posts: &[Post],
+        months: Option<&BTreeSet<(i32, u32)>>,
+    ) -> Result<()> {
         let mut context = Context::new();
 
         // Add site config
@@ -529,60 +1867,91 @@ Thank you!`);
 
         // Add newsletter config and generated form
         context.insert("newsletter", &self.config.newsletter);
-        context.insert("newsletter_form", &self.generate_newsletter_form());
+        context.insert("newsletter_form", &self.generate_newsletter_form()?);
 
         // Prepare posts with rendered content
         let mut posts_with_content = Vec::new();
         for post in posts {
-            // Convert markdown to HTML for each post
-            let html_content = crate::generator::markdown::render_markdown(&post.content)?;
-
-            // Calculate reading time (average 200 words per minute)
-            let word_count = post.content.split_whitespace().count();
-            let reading_time = (word_count / 200).max(1);
-
-            let post_data = serde_json::json!({
-                "metadata": post.metadata,
-                "content": html_content,
-                "reading_time": reading_time
-            });
-
-            posts_with_content.push(post_data);
+            posts_with_content.push(self.listing_post(post)?);
         }
 
-        context.insert("posts", &posts_with_content);
-
-        // Group posts by year
-        let mut posts_by_year: HashMap<i32, Vec<serde_json::Value>> = HashMap::new();
-        for post_data in &posts_with_content {
-            let year = post_data["metadata"]["date"]
-                .as_str()
-                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                .map(|dt| dt.year())
-                .unwrap_or(2024); // fallback year
-            posts_by_year
-                .entry(year)
-                .or_default()
-                .push(post_data.clone());
+        // The full archive, then one page per year and per month
+        let dated: Vec<(DateTime<Utc>, Value)> = posts
+            .iter()
+            .map(|post| post.metadata.date)
+            .zip(posts_with_content.iter().cloned())
+            .collect();
+        let archives = group_by_month(&dated);
+        self.render_archive(
+            &context,
+            "archive.html",
+            &posts_with_content,
+            &archives,
+            None,
+        )?;
+        let wanted = |year: i32, month: Option<u32>| {
+            months.is_none_or(|months| {
+                months
+                    .iter()
+                    .any(|&(y, m)| y == year && month.is_none_or(|month| month == m))
+            })
+        };
+        for year in archives.iter().filter(|year| wanted(year.year, None)) {
+            self.render_archive(
+                &context,
+                &year.path,
+                &year.posts,
+                std::slice::from_ref(year),
+                Some(year.period()),
+            )?;
+            for month in year
+                .months
+                .iter()
+                .filter(|month| wanted(year.year, Some(month.month)))
+            {
+                self.render_archive(
+                    &context,
+                    &month.path,
+                    &month.posts,
+                    &[year.only_month(month)],
+                    Some(month.period()),
+                )?;
+            }
         }
-        context.insert("posts_by_year", &posts_by_year);
 
-        // Render template
-        let html = self
-            .tera
-            .render("archive.html", &context)
-            .map_err(|e| anyhow!("Failed to render archive template: {}", e))?;
+        Ok(())
+    }
 
-        // Write to file
-        let archive_file = self.output_dir.join("archive.html");
-        fs::write(&archive_file, html)
-            .map_err(|e| anyhow!("Failed to write archive file: {}", e))?;
+    /// Render one archive page with the theme's `archive.html`
+    fn render_archive(
+        &self,
+        base: &Context,
+        path: &str,
+        posts: &[Value],
+        archives: &[ArchiveYear<Value>],
+        period: Option<ArchivePeriod>,
+    ) -> Result<()> {
+        let mut context = base.clone();
+        context.insert("posts", posts);
+        context.insert("archives", archives);
+        // Kept for themes written before `archives`; a map has no order
+        let posts_by_year: HashMap<i32, &Vec<Value>> = archives
+            .iter()
+            .map(|year| (year.year, &year.posts))
+            .collect();
+        context.insert("posts_by_year", &posts_by_year);
+        if let Some(period) = period {
+            context.insert("archive_period", &period);
+        }
+        self.insert_canonical_url(&mut context, path);
 
-        Ok(())
+        let html = self.render_template("archive.html", &context, "the archive")?;
+        self.write_page(path, html)
+            .map_err(|e| anyhow!("Failed to write archive file: {}", e))
     }
 
-    /// Generate tag pages
-    fn generate_tag_pages(&self, posts: &[Post]) -> Result<()> {
+    /// Generate the tags index and the pages and feeds of `tags`, or of every tag
+    fn generate_tag_pages(&self, posts: &[Post], tags: Option<&BTreeSet<String>>) -> Result<()> {
         // Group posts by tag
         let mut posts_by_tag: HashMap<String, Vec<&Post>> = HashMap::new();
         for post in posts {
@@ -593,10 +1962,13 @@ Thank you!`);
 
         // Create tags directory
         let tags_dir = self.output_dir.join("tags");
-        fs::create_dir_all(&tags_dir)?;
+        output::create_dir_all(&tags_dir)?;
 
         // Generate individual tag pages
-        for (tag, tag_posts) in &posts_by_tag {
+        for (tag, tag_posts) in posts_by_tag
+            .iter()
+            .filter(|(tag, _)| tags.is_none_or(|tags| tags.contains(*tag)))
+        {
             let mut context = Context::new();
 
             // Add site config
@@ -604,42 +1976,42 @@ Thank you!`);
 
             // Add newsletter config and generated form
             context.insert("newsletter", &self.config.newsletter);
-            context.insert("newsletter_form", &self.generate_newsletter_form());
+            context.insert("newsletter_form", &self.generate_newsletter_form()?);
 
             // Add tag info
             context.insert("tag", tag);
+            self.insert_canonical_url(&mut context, &format!("tags/{}.html", tag));
 
             // Prepare posts with rendered content for this tag
             let mut posts_with_content = Vec::new();
             for post in tag_posts {
-                // Convert markdown to HTML for each post
-                let html_content = crate::generator::markdown::render_markdown(&post.content)?;
-
-                // Calculate reading time (average 200 words per minute)
-                let word_count = post.content.split_whitespace().count();
-                let reading_time = (word_count / 200).max(1);
-
-                let post_data = serde_json::json!({
-                    "metadata": post.metadata,
-                    "content": html_content,
-                    "reading_time": reading_time
-                });
-
-                posts_with_content.push(post_data);
+                posts_with_content.push(self.listing_post(post)?);
             }
 
             context.insert("posts", &posts_with_content);
 
             // Render template
-            let html = self
-                .tera
-                .render("tag.html", &context)
-                .map_err(|e| anyhow!("Failed to render tag template for '{}': {}", tag, e))?;
+            let html = self.render_template("tag.html", &context, &format!("tag '{}'", tag))?;
+            let html = feeds::add_discovery(&html, &self.tag_feeds(std::slice::from_ref(tag)));
 
             // Write to file
-            let tag_file = tags_dir.join(format!("{}.html", tag));
-            fs::write(&tag_file, html)
+            self.write_page(&format!("tags/{}.html", tag), html)
                 .map_err(|e| anyhow!("Failed to write tag file for '{}': {}", tag, e))?;
+
+            let public: Vec<&Post> = tag_posts
+                .iter()
+                .copied()
+                .filter(|post| !post.metadata.private)
+                .collect();
+            let rss = self.rss_document(
+                &public,
+                &format!("{}: {}", self.config.blog.title, tag),
+                &format!("Posts tagged {} on {}", tag, self.config.blog.title),
+                &self.config.page_url(&format!("tags/{}.html", tag)),
+                &tag_feed_path(tag),
+            )?;
+            output::write(self.output_dir.join(tag_feed_path(tag)), rss)
+                .map_err(|e| anyhow!("Failed to write the feed of tag '{}': {}", tag, e))?;
         }
 
         // Generate tags index
@@ -648,7 +2020,7 @@ Thank you!`);
 
         // Add newsletter config and generated form
         context.insert("newsletter", &self.config.newsletter);
-        context.insert("newsletter_form", &self.generate_newsletter_form());
+        context.insert("newsletter_form", &self.generate_newsletter_form()?);
 
         // Create tag info with post counts
         let tag_info: Vec<(String, usize)> = posts_by_tag
@@ -656,38 +2028,67 @@ Thank you!`);
             .map(|(tag, posts)| (tag.clone(), posts.len()))
             .collect();
         context.insert("tags", &tag_info);
+        self.insert_canonical_url(&mut context, "tags/index.html");
 
-        let html = self
-            .tera
-            .render("tags.html", &context)
-            .map_err(|e| anyhow!("Failed to render tags index template: {}", e))?;
+        let html = self.render_template("tags.html", &context, "the tags index")?;
 
         let tags_index = self.output_dir.join("tags").join("index.html");
-        fs::write(&tags_index, html).map_err(|e| anyhow!("Failed to write tags index: {}", e))?;
+        output::write(&tags_index, html)
+            .map_err(|e| anyhow!("Failed to write tags index: {}", e))?;
 
         Ok(())
     }
 
     /// Generate RSS feed
-    fn generate_rss_feed(&self, posts: &[Post]) -> Result<()> {
-        // Get effective base URL for all feed URLs
-        let effective_base_url = self.config.get_effective_base_url();
+    pub(crate) fn generate_rss_feed(&self, posts: &[Post]) -> Result<()> {
+        let _span = tracing::trace_span!("feeds").entered();
 
-        // Take only the most recent 20 posts for the RSS feed
-        let recent_posts: Vec<&Post> = posts.iter().take(20).collect();
+        let rss_content = self.rss_document(
+            &posts.iter().collect::<Vec<_>>(),
+            &self.config.blog.title,
+            &self.config.blog.description,
+            &self.config.get_effective_base_url(),
+            "rss.xml",
+        )?;
+
+        // Write RSS feed
+        let rss_file = self.output_dir.join("rss.xml");
+        output::write(&rss_file, rss_content)
+            .map_err(|e| anyhow!("Failed to write RSS feed: {}", e))?;
+
+        // Also generate Atom feed
+        self.generate_atom_feed(posts)?;
+
+        Ok(())
+    }
 
+    /// An RSS document of the newest 20 of `posts`, published at `path`
+    fn rss_document(
+        &self,
+        posts: &[&Post],
+        title: &str,
+        description: &str,
+        link: &str,
+        path: &str,
+    ) -> Result<String> {
         let mut rss_items = Vec::new();
 
-        for post in recent_posts {
-            // Convert markdown to HTML for RSS content
-            let html_content = crate::generator::markdown::render_markdown(&post.content)?;
+        for post in posts.iter().take(20) {
+            // Readers show the summary, or the full post from content:encoded
+            let html_content = self.render_post_markdown(post, &post.content)?;
 
             // Create RSS item
-            let post_url = format!(
-                "{}/posts/{}.html",
-                effective_base_url.trim_end_matches('/'),
-                post.metadata.slug
-            );
+            let post_url = self
+                .config
+                .page_url(&format!("posts/{}.html", post.metadata.slug));
+
+            // A link post's item goes to the page it's about, as on link blogs
+            let link = post.metadata.link.as_deref().unwrap_or(&post_url);
+
+            let media = self
+                .post_cover(post)?
+                .map(|cover| cover.media_rss(|path| self.absolute_url(path)))
+                .unwrap_or_default();
 
             let rss_item = format!(
                 r#"    <item>
@@ -695,15 +2096,18 @@ Thank you!`);
       <link>{}</link>
       <guid>{}</guid>
       <description><![CDATA[{}]]></description>
+      <content:encoded><![CDATA[{}]]></content:encoded>
       <pubDate>{}</pubDate>
-      <author>{}</author>
+      <author>{}</author>{}
     </item>"#,
-                post.metadata.title,
-                post_url,
+                post.display_title(),
+                html_escape(link),
                 post_url,
+                self.post_summary(post)?,
                 html_content,
                 post.metadata.date.format("%a, %d %b %Y %H:%M:%S %z"),
-                self.config.blog.author
+                self.config.blog.author,
+                media
             );
 
             rss_items.push(rss_item);
@@ -712,11 +2116,11 @@ Thank you!`);
         // Generate RSS XML
         let rss_content = format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
-<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:media="http://search.yahoo.com/mrss/">
   <channel>
     <title><![CDATA[{}]]></title>
     <link>{}</link>
-    <atom:link href="{}/rss.xml" rel="self" type="application/rss+xml" />
+    <atom:link href="{}" rel="self" type="application/rss+xml" />
     <description><![CDATA[{}]]></description>
     <language>{}</language>
     <lastBuildDate>{}</lastBuildDate>
@@ -724,24 +2128,16 @@ Thank you!`);
 {}
   </channel>
 </rss>"#,
-            self.config.blog.title,
-            effective_base_url,
-            effective_base_url.trim_end_matches('/'),
-            self.config.blog.description,
+            title,
+            link,
+            self.absolute_url(path),
+            description,
             self.config.blog.language.as_deref().unwrap_or("en"),
             Utc::now().format("%a, %d %b %Y %H:%M:%S %z"),
             rss_items.join("\n")
         );
 
-        // Write RSS feed
-        let rss_file = self.output_dir.join("rss.xml");
-        fs::write(&rss_file, rss_content)
-            .map_err(|e| anyhow!("Failed to write RSS feed: {}", e))?;
-
-        // Also generate Atom feed
-        self.generate_atom_feed(posts)?;
-
-        Ok(())
+        Ok(rss_content)
     }
 
     /// Generate Atom feed
@@ -756,14 +2152,14 @@ Thank you!`);
 
         for post in recent_posts {
             // Convert markdown to HTML for Atom content
-            let html_content = crate::generator::markdown::render_markdown(&post.content)?;
+            let html_content = self.render_post_markdown(post, &post.content)?;
 
             // Create Atom entry
-            let post_url = format!(
-                "{}/posts/{}.html",
-                effective_base_url.trim_end_matches('/'),
-                post.metadata.slug
-            );
+            let post_url = self
+                .config
+                .page_url(&format!("posts/{}.html", post.metadata.slug));
+
+            let link = post.metadata.link.as_deref().unwrap_or(&post_url);
 
             let atom_entry = format!(
                 r#"  <entry>
@@ -771,17 +2167,17 @@ Thank you!`);
     <link href="{}"/>
     <id>{}</id>
     <updated>{}</updated>
-    <summary><![CDATA[{}]]></summary>
+    <summary type="html"><![CDATA[{}]]></summary>
     <content type="html"><![CDATA[{}]]></content>
     <author>
       <name>{}</name>
     </author>
   </entry>"#,
-                post.metadata.title,
-                post_url,
+                post.display_title(),
+                html_escape(link),
                 post_url,
                 post.metadata.date.format("%Y-%m-%dT%H:%M:%S%z"),
-                &post.metadata.description,
+                self.post_summary(post)?,
                 html_content,
                 self.config.blog.author
             );
@@ -795,7 +2191,7 @@ Thank you!`);
 <feed xmlns="http://www.w3.org/2005/Atom">
   <title><![CDATA[{}]]></title>
   <link href="{}"/>
-  <link href="{}/atom.xml" rel="self"/>
+  <link href="{}" rel="self"/>
   <id>{}</id>
   <updated>{}</updated>
   <subtitle><![CDATA[{}]]></subtitle>
@@ -804,7 +2200,7 @@ Thank you!`);
 </feed>"#,
             self.config.blog.title,
             effective_base_url,
-            effective_base_url.trim_end_matches('/'),
+            self.absolute_url("atom.xml"),
             effective_base_url,
             Utc::now().format("%Y-%m-%dT%H:%M:%S%z"),
             self.config.blog.description,
@@ -813,42 +2209,93 @@ Thank you!`);
 
         // Write Atom feed
         let atom_file = self.output_dir.join("atom.xml");
-        fs::write(&atom_file, atom_content)
+        output::write(&atom_file, atom_content)
             .map_err(|e| anyhow!("Failed to write Atom feed: {}", e))?;
 
         Ok(())
     }
 
-    /// Copy theme assets
+    /// Copy theme assets, compiling Sass entry points to CSS
     fn copy_theme_assets(&self) -> Result<()> {
+        let _span = tracing::trace_span!("assets").entered();
         let assets = self.theme.assets();
+        let theme_fs = scss::ThemeAssetsFs::new(&assets);
+        let dev = std::env::var("BLOGR_DEV").is_ok();
+
         for (path, content) in &assets {
+            let source = Path::new(path);
+            if source.extension().is_some_and(|ext| ext == "scss") {
+                if scss::is_entry(source) {
+                    let compiled = scss::compile(source, &theme_fs, dev, |path| {
+                        format!("theme/{}", path.display())
+                    })?;
+                    self.write_stylesheet(&scss::output_path(source), compiled)?;
+                }
+                continue;
+            }
+
             // Place assets directly in output directory (e.g., css/style.css -> /css/style.css)
             let asset_path = self.output_dir.join(path);
 
             // Create parent directories if needed
             if let Some(parent) = asset_path.parent() {
-                fs::create_dir_all(parent)?;
+                output::create_dir_all(parent)?;
             }
 
-            fs::write(&asset_path, content)
+            output::write(&asset_path, content)
                 .map_err(|e| anyhow!("Failed to write asset '{}': {}", path, e))?;
         }
 
-        Ok(())
+        self.fonts.copy_to(&self.output_dir)
     }
 
-    /// Copy project static assets
+    /// Copy project static assets, compiling Sass entry points to CSS
     fn copy_static_assets(&self) -> Result<()> {
+        let _span = tracing::trace_span!("assets").entered();
         let static_dir = self.project.root.join("static");
         if !static_dir.exists() {
             return Ok(());
         }
 
         let output_static = self.output_dir.join("static");
-        crate::generator::assets::copy_dir_recursive(&static_dir, &output_static)
-            .map_err(|e| anyhow!("Failed to copy static assets: {}", e))?;
+        crate::generator::assets::copy_dir_filtered(&static_dir, &output_static, |path| {
+            path.extension().is_none_or(|ext| ext != "scss")
+        })
+        .map_err(|e| anyhow!("Failed to copy static assets: {}", e))?;
+
+        let dev = std::env::var("BLOGR_DEV").is_ok();
+        for entry in WalkDir::new(&static_dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file() && scss::is_entry(entry.path()))
+        {
+            let compiled = scss::compile(entry.path(), &grass::StdFs, dev, |path| {
+                let relative = path.strip_prefix(&self.project.root).unwrap_or(path);
+                format!("/{}", relative.to_string_lossy().replace('\\', "/"))
+            })?;
+            let relative = entry.path().strip_prefix(&static_dir)?;
+            self.write_stylesheet(
+                &Path::new("static").join(scss::output_path(relative)),
+                compiled,
+            )?;
+        }
+
+        Ok(())
+    }
 
+    /// Write compiled CSS, and its source map if there is one, under the output directory
+    fn write_stylesheet(&self, path: &Path, compiled: scss::CompiledCss) -> Result<()> {
+        let css_path = self.output_dir.join(path);
+        if let Some(parent) = css_path.parent() {
+            output::create_dir_all(parent)?;
+        }
+        output::write(&css_path, compiled.css)
+            .map_err(|e| anyhow!("Failed to write '{}': {}", css_path.display(), e))?;
+        if let Some(source_map) = compiled.source_map {
+            let mut map_path = css_path.into_os_string();
+            map_path.push(".map");
+            output::write(&map_path, source_map)?;
+        }
         Ok(())
     }
 
@@ -856,30 +2303,60 @@ Thank you!`);
     fn copy_search_assets(&self) -> Result<()> {
         // Always emit embedded assets first for reliability (works in release binaries)
         let js_dir = self.output_dir.join("js");
-        fs::create_dir_all(&js_dir)?;
+        output::create_dir_all(&js_dir)?;
 
         // Inject configuration into search.js
         let field_boosts_json = serde_json::to_string(&self.config.search.field_boosts)
             .unwrap_or_else(|_| r#"{"title": 5, "tags": 3, "content": 1}"#.to_string());
 
+        // Stop words are only sent to the browser when stop word removal is enabled
+        let search_config = self.search_config();
+        let stop_words = if search_config.remove_stopwords {
+            crate::generator::SearchIndexer::new(search_config.clone()).stop_words()
+        } else {
+            Vec::new()
+        };
+        let language = crate::generator::stopwords::primary_language(
+            search_config.language.as_deref().unwrap_or("en"),
+        );
+
         let search_js_content = EMBEDDED_SEARCH_JS
             .replace(
                 "lazyLoad: true,",
                 &format!("lazyLoad: {},", self.config.search.lazy_load),
             )
+            .replace(
+                "language: 'en',",
+                &format!("language: {},", serde_json::to_string(&language)?),
+            )
+            .replace(
+                "stemming: false,",
+                &format!("stemming: {},", search_config.stemming),
+            )
+            .replace(
+                "stopWords: [],",
+                &format!("stopWords: {},", serde_json::to_string(&stop_words)?),
+            )
+            .replace(
+                "prefix: true,",
+                &format!("prefix: {},", search_config.prefix),
+            )
             .replace(
                 r#"boost: { title: 5, tags: 3, content: 1 }"#,
                 &format!("boost: {}", field_boosts_json),
             );
 
         let search_js_dst = js_dir.join("search.js");
-        fs::write(&search_js_dst, search_js_content)
+        output::write(&search_js_dst, search_js_content)
             .map_err(|e| anyhow!("Failed to write embedded search.js: {}", e))?;
 
         let vendor_dir_dst = js_dir.join("vendor");
-        fs::create_dir_all(&vendor_dir_dst)?;
+        output::create_dir_all(&vendor_dir_dst)?;
+        output::write(js_dir.join("search-page.js"), EMBEDDED_SEARCH_PAGE_JS)
+            .map_err(|e| anyhow!("Failed to write embedded search-page.js: {}", e))?;
+
         let minisearch_dst = vendor_dir_dst.join("minisearch.min.js");
-        fs::write(&minisearch_dst, EMBEDDED_MINISEARCH_JS)
+        output::write(&minisearch_dst, EMBEDDED_MINISEARCH_JS)
             .map_err(|e| anyhow!("Failed to write embedded minisearch.min.js: {}", e))?;
 
         // Optionally override with local files if present (useful during development)
@@ -887,7 +2364,7 @@ Thank you!`);
         if dev_static.exists() {
             let search_js_src = dev_static.join("js/search.js");
             if search_js_src.exists() {
-                let _ = fs::copy(&search_js_src, &search_js_dst);
+                let _ = output::copy(&search_js_src, &search_js_dst);
             }
             let vendor_src = dev_static.join("js/vendor");
             if vendor_src.exists() {
@@ -905,23 +2382,31 @@ Thank you!`);
         &self.output_dir
     }
 
-    /// Generate CNAME file for custom domains (GitHub Pages)
+    /// Generate CNAME file for custom domains (GitHub Pages). GitHub only
+    /// takes one domain; it redirects the `www.` or apex form itself.
     fn generate_cname_file(&self) -> Result<()> {
-        if let Some(domains) = &self.config.blog.domains {
-            if let Some(github_domain) = &domains.github_pages_domain {
-                let cname_file = self.output_dir.join("CNAME");
-                fs::write(&cname_file, format!("{}\n", github_domain))
-                    .map_err(|e| anyhow!("Failed to write CNAME file: {}", e))?;
+        let Some(domains) = &self.config.blog.domains else {
+            return Ok(());
+        };
+        let Some(github_domain) = domains
+            .github_pages_domain
+            .clone()
+            .or_else(|| self.config.main_domain())
+        else {
+            return Ok(());
+        };
 
-                println!("📝 Generated CNAME file for: {}", github_domain);
-            }
-        }
+        let cname_file = self.output_dir.join("CNAME");
+        output::write(&cname_file, format!("{}\n", github_domain))
+            .map_err(|e| anyhow!("Failed to write CNAME file: {}", e))?;
+        crate::status!("📝 Generated CNAME file for: {}", github_domain);
         Ok(())
     }
 
     /// Register template functions for URL generation
     fn register_template_functions(tera: &mut Tera, config: &Config) -> Result<()> {
         let base_url = config.get_effective_base_url();
+        let url_config = config.urls.clone();
 
         // Use relative paths when running the local dev server; otherwise use base_url-prefixed URLs
         let is_dev = std::env::var("BLOGR_DEV").is_ok();
@@ -930,6 +2415,11 @@ Thank you!`);
         // Clone base_url for use in closures
         let base_url_for_asset = base_url.clone();
         let base_url_for_url = base_url.clone();
+        let asset_urls = url_config.clone();
+        let root_urls = UrlConfig {
+            section: None,
+            ..url_config.clone()
+        };
 
         // Register asset_url function
         let use_relative_for_asset = use_relative_paths;
@@ -946,22 +2436,20 @@ Thank you!`);
                     return Ok(Value::String(path.to_string()));
                 }
 
+                let path = asset_urls.asset_path(path);
                 let url = if use_relative_for_asset {
                     // Local development: root-relative
-                    format!("/{}", path.trim_start_matches('/'))
+                    format!("/{}", path)
                 } else {
                     // Production: prefix with base_url
-                    format!(
-                        "{}/{}",
-                        base_url_for_asset.trim_end_matches('/'),
-                        path.trim_start_matches('/')
-                    )
+                    format!("{}/{}", base_url_for_asset.trim_end_matches('/'), path)
                 };
                 Ok(Value::String(url))
             },
         );
 
-        // Register url function for internal links
+        // Register url function for internal links; `root=true` links from the
+        // site root rather than the section, e.g. from a hybrid site's blog to its home page
         let use_relative_for_url = use_relative_paths;
         tera.register_function(
             "url",
@@ -976,6 +2464,15 @@ Thank you!`);
                     return Ok(Value::String(path.to_string()));
                 }
 
+                // Apply the configured page URL style (pretty URLs, trailing slashes)
+                let from_root = args.get("root").and_then(|v| v.as_bool()).unwrap_or(false);
+                let path = if from_root {
+                    root_urls.page_path(path)
+                } else {
+                    url_config.page_path(path)
+                };
+                let path = path.as_str();
+
                 let url = if use_relative_for_url {
                     // Local development: root-relative
                     if path.is_empty() {
@@ -995,41 +2492,72 @@ Thank you!`);
             },
         );
 
+        // Register microformats2 helpers
+        let config_for_card = config.clone();
+        tera.register_function(
+            "h_card",
+            move |_args: &HashMap<String, Value>| -> tera::Result<Value> {
+                Ok(Value::String(microformats::h_card(&config_for_card, None)))
+            },
+        );
+
+        let config_for_entry = config.clone();
+        tera.register_function(
+            "h_entry_meta",
+            move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+                let post = args
+                    .get("post")
+                    .ok_or_else(|| tera::Error::msg("h_entry_meta requires a 'post' argument"))?;
+                Ok(Value::String(microformats::h_entry_meta(
+                    &config_for_entry,
+                    post,
+                )))
+            },
+        );
+
+        // Standard filters and functions documented for theme authors
+        template_helpers::register(tera, config);
+
         Ok(())
     }
 
     /// Generate static JSON files for post pagination
     fn generate_posts_json(&self, posts: &[Post]) -> Result<()> {
-        const POSTS_PER_PAGE: usize = 10;
+        let _span = tracing::trace_span!("feeds").entered();
+        let per_page = self.config.build.pagination.per_page();
 
         // Create api directory
         let api_dir = self.output_dir.join("api");
-        fs::create_dir_all(&api_dir)?;
+        output::create_dir_all(&api_dir)?;
 
         // Generate paginated JSON files
         let total_posts = posts.len();
-        let total_pages = total_posts.div_ceil(POSTS_PER_PAGE);
+        let total_pages = total_posts.div_ceil(per_page);
 
         for page in 1..=total_pages {
-            let start_index = (page - 1) * POSTS_PER_PAGE;
-            let end_index = (start_index + POSTS_PER_PAGE).min(total_posts);
+            let start_index = (page - 1) * per_page;
+            let end_index = (start_index + per_page).min(total_posts);
             let page_posts = &posts[start_index..end_index];
 
             // Prepare posts with rendered content
             let mut posts_with_content = Vec::new();
             for post in page_posts {
                 // Convert markdown to HTML for each post
-                let html_content = crate::generator::markdown::render_markdown(&post.content)?;
+                let html_content = self.render_post_markdown(post, &post.content)?;
 
-                // Calculate reading time (average 200 words per minute)
-                let word_count = post.content.split_whitespace().count();
-                let reading_time = (word_count / 200).max(1);
+                let reading = post.reading_time(&self.config);
 
                 // Create a struct that includes both post data and rendered content
                 let post_data = serde_json::json!({
                     "metadata": post.metadata,
                     "content": html_content,
-                    "reading_time": reading_time
+                    "summary": self.post_summary(post)?,
+                    "reading_time": reading.minutes,
+                    "word_count": reading.words,
+                    "url": self
+                        .config
+                        .urls
+                        .page_path(&format!("posts/{}.html", post.metadata.slug))
                 });
 
                 posts_with_content.push(post_data);
@@ -1041,7 +2569,7 @@ Thank you!`);
                 "has_more": page < total_pages,
                 "total": total_posts,
                 "page": page,
-                "limit": POSTS_PER_PAGE
+                "limit": per_page
             });
 
             // Write JSON file for this page
@@ -1049,20 +2577,188 @@ Thank you!`);
             let json_content = serde_json::to_string_pretty(&response)
                 .map_err(|e| anyhow!("Failed to serialize posts JSON for page {}: {}", page, e))?;
 
-            fs::write(&json_file, json_content)
+            output::write(&json_file, json_content)
                 .map_err(|e| anyhow!("Failed to write posts JSON file for page {}: {}", page, e))?;
         }
 
-        println!("📄 Generated {} paginated JSON files", total_pages);
+        crate::status!("📄 Generated {} paginated JSON files", total_pages);
+        Ok(())
+    }
+
+    /// Write the JSON API of `[build.api]`
+    fn generate_content_api(&self, posts: &[Post]) -> Result<()> {
+        if !self.config.build.api.enabled {
+            return Ok(());
+        }
+        let _span = tracing::trace_span!("feeds").entered();
+
+        let mut summaries = Vec::new();
+        for post in posts {
+            let reading = post.reading_time(&self.config);
+            let cover = self.post_cover(post)?.map(|cover| {
+                cover.context(|path| self.absolute_url(path))["url"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            });
+            summaries.push(content_api::PostSummary {
+                slug: post.metadata.slug.clone(),
+                title: post.display_title(),
+                date: post.metadata.date.to_rfc3339(),
+                updated: post.metadata.updated_at.map(|date| date.to_rfc3339()),
+                author: if post.metadata.author.trim().is_empty() {
+                    self.config.blog.author.clone()
+                } else {
+                    post.metadata.author.clone()
+                },
+                description: post.metadata.description.clone(),
+                tags: post.metadata.tags.clone(),
+                featured: post.metadata.featured,
+                reading_time: reading.minutes,
+                word_count: reading.words,
+                cover,
+                url: self
+                    .config
+                    .page_url(&format!("posts/{}.html", post.metadata.slug)),
+                json_url: self.absolute_url(&content_api::post_path(&post.metadata.slug)),
+            });
+        }
+
+        let mut documents = Vec::new();
+        for (i, (post, summary)) in posts.iter().zip(&summaries).enumerate() {
+            documents.push(content_api::PostDocument {
+                schema_version: content_api::SCHEMA_VERSION,
+                post: summary.clone(),
+                summary_html: self.post_summary(post)?,
+                content_html: self.post_content(post)?,
+                newer: i
+                    .checked_sub(1)
+                    .map(|newer| posts[newer].metadata.slug.clone()),
+                older: posts.get(i + 1).map(|older| older.metadata.slug.clone()),
+            });
+        }
+        let tags = content_api::tags(&summaries, |tag| {
+            self.config.page_url(&format!("tags/{}.html", tag))
+        });
+
+        let site = content_api::SiteDocument {
+            schema_version: content_api::SCHEMA_VERSION,
+            title: self.config.blog.title.clone(),
+            description: self.config.blog.description.clone(),
+            author: self.config.blog.author.clone(),
+            language: self
+                .config
+                .blog
+                .language
+                .clone()
+                .unwrap_or_else(|| "en".to_string()),
+            base_url: self.config.get_effective_base_url(),
+            post_count: posts.len(),
+            tag_count: tags.len(),
+            feeds: content_api::Feeds {
+                rss: self.absolute_url("rss.xml"),
+                atom: self.absolute_url("atom.xml"),
+            },
+            endpoints: content_api::Endpoints {
+                posts: self.absolute_url("api/posts.json"),
+                post: self.absolute_url(&content_api::post_path("{slug}")),
+                tags: self.absolute_url("api/tags.json"),
+            },
+        };
+        content_api::write(&self.output_dir, &site, &documents, &tags)
+    }
+
+    /// Write a rendered page to the output path for its URL style
+    fn write_page(&self, path: &str, html: String) -> Result<()> {
+        let file = self.output_dir.join(self.config.urls.output_path(path));
+        if let Some(parent) = file.parent() {
+            output::create_dir_all(parent)?;
+        }
+        output::write(&file, html)?;
+        Ok(())
+    }
+
+    /// Add the canonical URL for a page to the template context
+    fn insert_canonical_url(&self, context: &mut Context, path: &str) {
+        if self.config.urls.canonical {
+            context.insert("canonical_url", &self.config.page_url(path));
+        }
+    }
+
+    /// Generate sitemap.xml
+    fn generate_sitemap(&self, posts: &[Post], pages: &[Page]) -> Result<()> {
+        crate::generator::sitemap::generate_sitemap(
+            &self.config,
+            posts,
+            pages,
+            &self.galleries,
+            &self.output_dir,
+        )
+    }
+
+    /// Generate redirect pages for post aliases and `[redirects]` entries
+    fn generate_redirects(&self, posts: &[Post]) -> Result<()> {
+        use crate::generator::RedirectGenerator;
+
+        // Posts of a hybrid site live under /blog/
+        let urls = if self.config.site.site_type == "hybrid" {
+            self.config.blog_section().urls
+        } else {
+            self.config.urls.clone()
+        };
+        let generator = RedirectGenerator::new(self.config.get_effective_base_url(), urls)
+            .with_alias_domains(self.config.alias_domains());
+        generator.generate(posts, &self.config.redirects, &self.output_dir)?;
+        Ok(())
+    }
+
+    /// Generate the search results page at /search/
+    fn generate_search_page(&self) -> Result<()> {
+        if !self.config.search.enabled {
+            return Ok(());
+        }
+
+        let mut context = Context::new();
+        context.insert("site", &self.config);
+        context.insert("newsletter", &self.config.newsletter);
+        context.insert("newsletter_form", &self.generate_newsletter_form()?);
+        self.insert_canonical_url(&mut context, "search/index.html");
+
+        let html = self.render_template("search.html", &context, "the search page")?;
+
+        self.write_page("search/index.html", html)
+            .map_err(|e| anyhow!("Failed to write search page: {}", e))?;
         Ok(())
     }
 
+    /// Search configuration with the language falling back to the blog's language
+    fn search_config(&self) -> crate::config::SearchConfig {
+        let mut search = self.config.search.clone();
+        if search.language.is_none() {
+            search.language = self.config.blog.language.clone();
+        }
+        search
+    }
+
     /// Generate search index
-    fn generate_search_index(&self, posts: &[Post]) -> Result<()> {
+    fn generate_search_index(&self, posts: &[Post], pages: &[Page]) -> Result<()> {
+        let _span = tracing::trace_span!("search index").entered();
         use crate::generator::SearchIndexer;
 
-        let indexer = SearchIndexer::new(self.config.search.clone());
+        let indexer = SearchIndexer::new(self.search_config())
+            .with_url_config(self.config.urls.clone())
+            .with_pages(pages);
         indexer.generate_index(posts, &self.output_dir)?;
         Ok(())
     }
 }
+
+/// Delete a file of an earlier build, if it's still there
+fn remove_file(path: &Path) -> Result<()> {
+    match output::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(anyhow!("Failed to remove {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
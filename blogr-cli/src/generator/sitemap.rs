@@ -0,0 +1,100 @@
+use crate::config::Config;
+use crate::content::Post;
+use crate::generator::archive::group_by_month;
+use crate::generator::gallery::Gallery;
+use crate::generator::output;
+use crate::generator::pages::Page;
+use crate::generator::resume::RESUME_PAGE;
+use anyhow::{anyhow, Result};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Generate sitemap.xml listing every page of the site
+pub fn generate_sitemap(
+    config: &Config,
+    posts: &[Post],
+    pages: &[Page],
+    galleries: &[Gallery],
+    output_dir: &Path,
+) -> Result<()> {
+    let mut entries: Vec<(String, Option<String>)> = vec![(config.page_url("index.html"), None)];
+
+    for page in pages.iter().filter(|page| !page.noindex) {
+        entries.push((config.page_url(&page.output_path()), None));
+    }
+    for gallery in galleries {
+        entries.push((config.page_url(&gallery.path), None));
+    }
+    if output::is_file(output_dir.join(RESUME_PAGE)) {
+        entries.push((config.page_url(RESUME_PAGE), None));
+    }
+
+    // A hybrid site's blog is published under /blog/
+    let blog = match config.site.site_type.as_str() {
+        "personal" => None,
+        "hybrid" => Some(config.blog_section()),
+        _ => Some(config.clone()),
+    };
+
+    if let Some(blog) = blog {
+        if blog.urls.section.is_some() {
+            entries.push((blog.page_url("index.html"), None));
+        }
+        entries.push((blog.page_url("archive.html"), None));
+        let dated: Vec<_> = posts.iter().map(|post| (post.metadata.date, ())).collect();
+        for year in group_by_month(&dated) {
+            entries.push((blog.page_url(&year.path), None));
+            for month in &year.months {
+                entries.push((blog.page_url(&month.path), None));
+            }
+        }
+        entries.push((blog.page_url("tags/index.html"), None));
+
+        let tags: BTreeSet<&String> = posts.iter().flat_map(|p| &p.metadata.tags).collect();
+        for tag in tags {
+            entries.push((blog.page_url(&format!("tags/{}.html", tag)), None));
+        }
+
+        for post in posts
+            .iter()
+            .filter(|post| !post.metadata.noindex && !post.metadata.private)
+        {
+            entries.push((
+                blog.page_url(&format!("posts/{}.html", post.metadata.slug)),
+                Some(
+                    post.metadata
+                        .updated_at
+                        .unwrap_or(post.metadata.date)
+                        .format("%Y-%m-%d")
+                        .to_string(),
+                ),
+            ));
+        }
+    }
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for (loc, lastmod) in &entries {
+        xml.push_str("  <url>\n");
+        xml.push_str(&format!("    <loc>{}</loc>\n", xml_escape(loc)));
+        if let Some(lastmod) = lastmod {
+            xml.push_str(&format!("    <lastmod>{}</lastmod>\n", lastmod));
+        }
+        xml.push_str("  </url>\n");
+    }
+    xml.push_str("</urlset>\n");
+
+    output::write(output_dir.join("sitemap.xml"), xml)
+        .map_err(|e| anyhow!("Failed to write sitemap: {}", e))?;
+
+    crate::status!("🗺️  Generated sitemap with {} URLs", entries.len());
+    Ok(())
+}
+
+/// Escape the characters XML does not allow in text content
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,116 @@
+//! Slug collisions
+//!
+//! Two posts with the same slug, or a page placed inside the blog of a hybrid
+//! site, would silently overwrite each other's output files. `blogr new` and
+//! the build check for this first. Paths are compared without case, since two
+//! files that differ only in case are the same file on macOS and Windows.
+
+use crate::config::{Config, BLOG_SECTION};
+use crate::content::Post;
+use crate::generator::pages::Page;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Output file of a post, relative to the site root
+pub fn post_output_file(config: &Config, slug: &str) -> PathBuf {
+    let file = config.urls.output_path(&format!("posts/{}.html", slug));
+    if config.site.site_type == "hybrid" {
+        Path::new(BLOG_SECTION).join(file)
+    } else {
+        file
+    }
+}
+
+/// Fail if any two posts, or a post and a page, would be written to the same file
+pub fn check_collisions(config: &Config, posts: &[Post], pages: &[Page]) -> Result<()> {
+    let mut outputs: HashMap<String, String> = HashMap::new();
+    let mut claim = |file: PathBuf, owner: String| -> Result<()> {
+        let key = file.to_string_lossy().to_lowercase();
+        match outputs.get(&key) {
+            Some(other) => Err(anyhow!(
+                "Both {} and {} would be written to {}; give one of them a different slug",
+                other,
+                owner,
+                file.display()
+            )),
+            None => {
+                outputs.insert(key, owner);
+                Ok(())
+            }
+        }
+    };
+
+    for post in posts {
+        let file_name = post
+            .file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| post.filename());
+        claim(
+            post_output_file(config, &post.metadata.slug),
+            format!("post '{}' ({})", post.metadata.slug, file_name),
+        )?;
+    }
+
+    for page in pages {
+        let in_blog = page
+            .slug
+            .split('/')
+            .next()
+            .is_some_and(|first| first.eq_ignore_ascii_case(BLOG_SECTION));
+        if config.site.site_type == "hybrid" && in_blog {
+            return Err(anyhow!(
+                "Page '{}' uses /{}/, which is where the blog is published; give it a different slug",
+                page.title,
+                page.slug
+            ));
+        }
+        claim(
+            config.urls.output_path(&page.output_path()),
+            format!("page '{}'", page.title),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::PostStatus;
+
+    fn post(slug: &str, file: &str) -> Post {
+        let mut post = Post::new(
+            slug.to_string(),
+            "Test Author".to_string(),
+            None,
+            vec![],
+            Some(slug.to_string()),
+            PostStatus::Published,
+        );
+        post.file_path = PathBuf::from(file);
+        post
+    }
+
+    #[test]
+    fn test_check_collisions() {
+        let mut config = Config::default();
+        let posts = [post("hello", "hello.md"), post("other", "other.md")];
+        assert!(check_collisions(&config, &posts, &[]).is_ok());
+
+        let posts = [post("hello", "hello.md"), post("Hello", "copy.md")];
+        let error = check_collisions(&config, &posts, &[])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("hello.md"));
+        assert!(error.contains("copy.md"));
+
+        // The blog of a hybrid site owns /blog/
+        config.site.site_type = "hybrid".to_string();
+        let page = Page::parse("# Hello", Path::new("blog/hello.md")).unwrap();
+        assert!(check_collisions(&config, &posts[..1], &[page]).is_err());
+        let page = Page::parse("# Hello", Path::new("posts/hello.md")).unwrap();
+        assert!(check_collisions(&config, &posts[..1], &[page]).is_ok());
+    }
+}
@@ -0,0 +1,74 @@
+//! Built-in stop word lists for search indexing
+
+const ENGLISH: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "he", "in", "is", "it",
+    "its", "of", "on", "that", "the", "to", "was", "will", "with", "but", "or", "not", "this",
+    "these", "those", "they", "their", "them", "we", "our", "us", "you", "your", "i", "me", "my",
+    "mine", "have", "had", "do", "does", "did", "can", "could", "should", "would", "may", "might",
+    "must", "shall", "am", "were", "been", "being", "get", "got",
+];
+
+const GERMAN: &[&str] = &[
+    "aber", "als", "am", "an", "auch", "auf", "aus", "bei", "bin", "bis", "da", "das", "dass",
+    "dem", "den", "der", "des", "die", "doch", "du", "ein", "eine", "einem", "einen", "einer",
+    "es", "für", "hat", "ich", "ihr", "im", "in", "ist", "ja", "kein", "man", "mit", "nach",
+    "nicht", "noch", "nur", "oder", "sich", "sie", "sind", "so", "um", "und", "uns", "von", "war",
+    "was", "wie", "wir", "wird", "zu", "zum", "zur",
+];
+
+const FRENCH: &[&str] = &[
+    "au", "aux", "avec", "ce", "ces", "dans", "de", "des", "du", "elle", "en", "est", "et", "eux",
+    "il", "ils", "je", "la", "le", "les", "leur", "lui", "ma", "mais", "me", "mes", "moi", "mon",
+    "ne", "nos", "notre", "nous", "on", "ou", "par", "pas", "pour", "qu", "que", "qui", "sa", "se",
+    "ses", "son", "sur", "ta", "te", "tes", "toi", "ton", "tu", "un", "une", "vos", "votre",
+    "vous",
+];
+
+const SPANISH: &[&str] = &[
+    "a", "al", "como", "con", "de", "del", "el", "ella", "ellos", "en", "es", "esta", "este",
+    "fue", "ha", "la", "las", "le", "les", "lo", "los", "me", "mi", "muy", "no", "nos", "o",
+    "para", "pero", "por", "que", "se", "si", "sin", "su", "sus", "te", "tu", "un", "una", "uno",
+    "y", "ya", "yo",
+];
+
+const ITALIAN: &[&str] = &[
+    "a", "al", "alla", "che", "chi", "ci", "come", "con", "da", "dal", "del", "della", "di", "e",
+    "gli", "ha", "ho", "i", "il", "in", "io", "la", "le", "lo", "ma", "mi", "ne", "nel", "non",
+    "o", "per", "più", "se", "si", "su", "sua", "suo", "tu", "un", "una", "uno",
+];
+
+const PORTUGUESE: &[&str] = &[
+    "a", "ao", "as", "com", "como", "da", "das", "de", "do", "dos", "e", "ela", "ele", "em", "era",
+    "eu", "foi", "há", "isso", "já", "mais", "mas", "me", "na", "nas", "no", "nos", "não", "o",
+    "os", "ou", "para", "pela", "pelo", "por", "que", "se", "sem", "seu", "sua", "um", "uma",
+];
+
+const DUTCH: &[&str] = &[
+    "aan", "al", "als", "bij", "dat", "de", "den", "der", "die", "dit", "een", "en", "er", "had",
+    "heb", "het", "hij", "ik", "in", "is", "je", "maar", "me", "met", "mijn", "na", "niet", "nog",
+    "of", "om", "ook", "op", "te", "tot", "uit", "van", "voor", "was", "wat", "we", "wel", "zich",
+    "zij", "zijn", "zo",
+];
+
+/// Reduce a language tag such as `en-US` to its primary subtag (`en`)
+pub fn primary_language(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Get the built-in stop words for a language, or an empty list if unsupported
+pub fn for_language(language: &str) -> &'static [&'static str] {
+    match primary_language(language).as_str() {
+        "en" => ENGLISH,
+        "de" => GERMAN,
+        "fr" => FRENCH,
+        "es" => SPANISH,
+        "it" => ITALIAN,
+        "pt" => PORTUGUESE,
+        "nl" => DUTCH,
+        _ => &[],
+    }
+}
@@ -0,0 +1,244 @@
+//! schema.org JSON-LD
+//!
+//! Added to the `<head>` of posts (`BlogPosting`), standalone pages and posts
+//! (`BreadcrumbList`) and the home page of personal and hybrid sites
+//! (`Person`) after the templates are rendered, so rich results work with any
+//! theme.
+
+use crate::config::Config;
+use crate::content::Post;
+use crate::generator::pages::Page;
+use crate::generator::resume::Resume;
+use serde_json::{json, Map, Value};
+
+/// URL of the site's home page, with a trailing slash
+pub fn home_url(config: &Config) -> String {
+    format!("{}/", config.get_effective_base_url().trim_end_matches('/'))
+}
+
+/// `BlogPosting` for a post, from its frontmatter
+pub fn blog_posting(config: &Config, post: &Post) -> Value {
+    let metadata = &post.metadata;
+    let url = config.page_url(&format!("posts/{}.html", metadata.slug));
+    let author = if metadata.author.trim().is_empty() {
+        &config.blog.author
+    } else {
+        &metadata.author
+    };
+
+    let mut posting = Map::new();
+    posting.insert("@context".into(), json!("https://schema.org"));
+    posting.insert("@type".into(), json!("BlogPosting"));
+    posting.insert("headline".into(), json!(post.display_title()));
+    if !metadata.description.trim().is_empty() {
+        posting.insert("description".into(), json!(metadata.description.trim()));
+    }
+    posting.insert("url".into(), json!(url));
+    posting.insert(
+        "mainEntityOfPage".into(),
+        json!({ "@type": "WebPage", "@id": url }),
+    );
+    posting.insert("datePublished".into(), json!(metadata.date.to_rfc3339()));
+    posting.insert(
+        "dateModified".into(),
+        json!(metadata.updated_at.unwrap_or(metadata.date).to_rfc3339()),
+    );
+    posting.insert(
+        "author".into(),
+        json!({ "@type": "Person", "name": author, "url": home_url(config) }),
+    );
+    let contributors: Vec<Value> = metadata
+        .contributors
+        .iter()
+        .filter(|contributor| *contributor != author)
+        .map(|contributor| json!({ "@type": "Person", "name": contributor }))
+        .collect();
+    if !contributors.is_empty() {
+        posting.insert("contributor".into(), Value::Array(contributors));
+    }
+    if !metadata.tags.is_empty() {
+        posting.insert("keywords".into(), json!(metadata.tags.join(", ")));
+    }
+    if let Some(language) = &config.blog.language {
+        posting.insert("inLanguage".into(), json!(language));
+    }
+    Value::Object(posting)
+}
+
+/// `BreadcrumbList` for a trail of (name, URL) pairs, starting at the home page
+pub fn breadcrumbs(trail: &[(String, String)]) -> Value {
+    let items: Vec<Value> = trail
+        .iter()
+        .enumerate()
+        .map(|(index, (name, url))| {
+            json!({
+                "@type": "ListItem",
+                "position": index + 1,
+                "name": name,
+                "item": url,
+            })
+        })
+        .collect();
+    json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": items,
+    })
+}
+
+/// Home, then the blog of a hybrid site, then the post
+pub fn post_breadcrumbs(config: &Config, post: &Post) -> Value {
+    let mut trail = vec![(config.blog.title.clone(), home_url(config))];
+    if config.urls.section.is_some() {
+        trail.push(("Blog".to_string(), config.page_url("index.html")));
+    }
+    trail.push((
+        post.display_title(),
+        config.page_url(&format!("posts/{}.html", post.metadata.slug)),
+    ));
+    breadcrumbs(&trail)
+}
+
+/// Home, then each parent page that exists, then the page
+pub fn page_breadcrumbs(config: &Config, page: &Page, pages: &[Page]) -> Value {
+    let mut trail = vec![(config.blog.title.clone(), home_url(config))];
+    let parts: Vec<&str> = page.slug.split('/').collect();
+    for depth in 1..parts.len() {
+        let parent = parts[..depth].join("/");
+        if let Some(parent) = pages.iter().find(|other| other.slug == parent) {
+            trail.push((parent.title.clone(), config.page_url(&parent.output_path())));
+        }
+    }
+    trail.push((page.title.clone(), config.page_url(&page.output_path())));
+    breadcrumbs(&trail)
+}
+
+/// `Person` for the site's owner, filled in from the resume when there is one
+pub fn person(config: &Config, resume: Option<&Resume>) -> Value {
+    let basics = resume.map(|resume| &resume.basics);
+    let name = basics
+        .map(|basics| basics.name.trim())
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&config.blog.author);
+
+    let mut person = Map::new();
+    person.insert("@context".into(), json!("https://schema.org"));
+    person.insert("@type".into(), json!("Person"));
+    person.insert("name".into(), json!(name));
+    person.insert("url".into(), json!(home_url(config)));
+
+    let description = basics
+        .and_then(|basics| basics.summary.clone())
+        .unwrap_or_else(|| config.blog.description.clone());
+    if !description.trim().is_empty() {
+        person.insert("description".into(), json!(description.trim()));
+    }
+    if let Some(label) = basics.and_then(|basics| basics.label.as_ref()) {
+        person.insert("jobTitle".into(), json!(label));
+    }
+    let image = config
+        .indieweb
+        .author_photo
+        .as_ref()
+        .or_else(|| basics.and_then(|basics| basics.image.as_ref()));
+    if let Some(image) = image {
+        let image = if image.starts_with("http://") || image.starts_with("https://") {
+            image.clone()
+        } else {
+            format!("{}{}", home_url(config), image.trim_start_matches('/'))
+        };
+        person.insert("image".into(), json!(image));
+    }
+    let same_as: Vec<&String> = basics
+        .map(|basics| {
+            basics
+                .profiles
+                .iter()
+                .filter_map(|profile| profile.url.as_ref())
+                .collect()
+        })
+        .unwrap_or_default();
+    if !same_as.is_empty() {
+        person.insert("sameAs".into(), json!(same_as));
+    }
+    Value::Object(person)
+}
+
+/// `<script>` elements for a page's JSON-LD
+pub fn script(items: &[Value]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            // `</script>` inside a string would end the element early
+            let json = item.to_string().replace("</", "<\\/");
+            format!(r#"<script type="application/ld+json">{}</script>"#, json)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Put `snippet` at the end of a page's `<head>`, or at the top without one
+pub fn insert_in_head(html: &str, snippet: &str) -> String {
+    match html.to_ascii_lowercase().find("</head>") {
+        Some(pos) => format!("{}{}\n{}", &html[..pos], snippet, &html[pos..]),
+        None => format!("{}\n{}", snippet, html),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::PostStatus;
+    use std::path::Path;
+
+    #[test]
+    fn test_structured_data() {
+        let mut config = Config::default();
+        config.blog.base_url = "https://example.com".to_string();
+        config.blog.title = "Example".to_string();
+        let mut post = Post::new(
+            "Hello </script>".to_string(),
+            "Ann".to_string(),
+            Some("A first post".to_string()),
+            vec!["rust".to_string(), "web".to_string()],
+            Some("hello".to_string()),
+            PostStatus::Published,
+        );
+        post.metadata.contributors = vec!["Ann".to_string(), "Bob".to_string()];
+
+        let posting = blog_posting(&config, &post);
+        assert_eq!(posting["@type"], "BlogPosting");
+        assert_eq!(posting["url"], "https://example.com/posts/hello.html");
+        assert_eq!(posting["author"]["name"], "Ann");
+        assert_eq!(posting["contributor"][0]["name"], "Bob");
+        assert_eq!(posting["keywords"], "rust, web");
+
+        let crumbs = post_breadcrumbs(&config.blog_section(), &post);
+        let items = crumbs["itemListElement"].as_array().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1]["item"], "https://example.com/blog/index.html");
+        assert_eq!(items[2]["position"], 3);
+
+        let pages = [
+            Page::parse("# Projects", Path::new("projects.md")).unwrap(),
+            Page::parse("# Blogr", Path::new("projects/blogr.md")).unwrap(),
+        ];
+        let crumbs = page_breadcrumbs(&config, &pages[1], &pages);
+        assert_eq!(crumbs["itemListElement"][1]["name"], "Projects");
+
+        let mut resume = Resume::default();
+        resume.basics.name = "Ann Example".to_string();
+        resume.basics.label = Some("Engineer".to_string());
+        let person = person(&config, Some(&resume));
+        assert_eq!(person["name"], "Ann Example");
+        assert_eq!(person["jobTitle"], "Engineer");
+
+        let html = insert_in_head(
+            "<html><head></head><body></body></html>",
+            &script(&[posting]),
+        );
+        assert!(html.contains(r#"<script type="application/ld+json">{"#));
+        assert!(html.contains(r#"Hello <\/script>"#));
+        assert!(html.ends_with("</script>\n</head><body></body></html>"));
+    }
+}
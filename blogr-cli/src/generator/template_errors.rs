@@ -0,0 +1,280 @@
+//! Readable template errors
+//!
+//! Tera reports a failed render as a chain of errors, e.g. "Failed to render
+//! 'post.html' (error happened in 'base.html')" caused by "Variable
+//! `post.cover` not found in context". `TemplateError` pulls out the template
+//! the problem is in, the variable, filter or function involved and where it
+//! appears in the template source, and shows them with the page being built.
+
+use std::fmt;
+use tera::ErrorKind;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    /// In characters, from 1
+    pub column: usize,
+    /// The source line
+    pub text: String,
+    /// Characters to underline
+    pub width: usize,
+}
+
+#[derive(Debug)]
+pub struct TemplateError {
+    /// The template the problem is in, which may be a parent of the one rendered
+    pub template: String,
+    /// What was being rendered, e.g. `post 'hello-world'`
+    pub rendering: Option<String>,
+    /// The variable, filter, function or template the error is about
+    pub subject: Option<String>,
+    /// Tera's innermost message
+    pub message: String,
+    pub location: Option<Location>,
+    hint: Option<&'static str>,
+}
+
+impl TemplateError {
+    /// Describe `error` from rendering (or parsing) `template`. `source`
+    /// looks up a template's text to locate the problem.
+    pub fn new(
+        error: &tera::Error,
+        template: &str,
+        rendering: Option<String>,
+        source: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let outer = error.to_string();
+        let template = quoted_after(&outer, "error happened in '")
+            .unwrap_or(template)
+            .to_string();
+
+        let mut message = outer.clone();
+        let mut subject = None;
+        let mut needle = None;
+        let mut hint = None;
+        let mut current: Option<&(dyn std::error::Error + 'static)> = Some(error);
+        while let Some(e) = current {
+            message = e.to_string();
+            if let Some(e) = e.downcast_ref::<tera::Error>() {
+                match &e.kind {
+                    ErrorKind::FilterNotFound(name) | ErrorKind::CallFilter(name) => {
+                        subject = Some(name.clone());
+                        needle = Some(name.clone());
+                        if matches!(e.kind, ErrorKind::FilterNotFound(_)) {
+                            hint = Some("check the filter's name; blogr's own filters are listed in docs/THEMES.md");
+                        }
+                    }
+                    ErrorKind::FunctionNotFound(name) | ErrorKind::CallFunction(name) => {
+                        subject = Some(name.clone());
+                        needle = Some(format!("{}(", name));
+                    }
+                    ErrorKind::TemplateNotFound(name) => {
+                        subject = Some(name.clone());
+                        needle = Some(name.clone());
+                    }
+                    ErrorKind::Msg(msg) if msg.contains("not found in context") => {
+                        if let Some(variable) = quoted_after(msg, "Variable `") {
+                            subject = Some(variable.to_string());
+                            needle = Some(variable.to_string());
+                            hint = Some(
+                                "if it can be missing, guard it with `{% if ... %}` or use `| default(value=\"\")`",
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            current = e.source();
+        }
+
+        let location = match parse_location(&message) {
+            Some((line, column)) => source(&template).and_then(|text| {
+                let text = text.lines().nth(line.checked_sub(1)?)?.to_string();
+                Some(Location {
+                    line,
+                    column,
+                    text,
+                    width: 1,
+                })
+            }),
+            None => needle.and_then(|needle| find_in_tags(&source(&template)?, &needle)),
+        };
+
+        Self {
+            template,
+            rendering,
+            subject,
+            message,
+            location,
+            hint,
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Template error in '{}'", self.template)?;
+        if let Some(location) = &self.location {
+            write!(f, " at line {}, column {}", location.line, location.column)?;
+        }
+        if let Some(rendering) = &self.rendering {
+            write!(f, " while rendering {}", rendering)?;
+        }
+        writeln!(f, ":")?;
+        // Parse errors come with their own excerpt
+        for line in self.message.lines() {
+            writeln!(f, "  {}", line)?;
+        }
+        if let Some(location) = self
+            .location
+            .as_ref()
+            .filter(|_| !self.message.contains("-->"))
+        {
+            let number = location.line.to_string();
+            let pad = " ".repeat(number.len());
+            let before: String = location
+                .text
+                .chars()
+                .take(location.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            writeln!(f, "  {} |", pad)?;
+            writeln!(f, "  {} | {}", number, location.text)?;
+            writeln!(f, "  {} | {}{}", pad, before, "^".repeat(location.width))?;
+        }
+        if let Some(hint) = self.hint {
+            writeln!(f, "  hint: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// The text between `prefix` and the next closing quote or backtick
+fn quoted_after<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = text.find(prefix)? + prefix.len();
+    let rest = &text[start..];
+    let end = rest.find(['\'', '`'])?;
+    Some(&rest[..end])
+}
+
+/// `line:column` from a parse error's `--> 3:5` marker
+fn parse_location(message: &str) -> Option<(usize, usize)> {
+    let rest = &message[message.find("-->")? + 3..];
+    let position = rest.split_whitespace().next()?;
+    let (line, column) = position.split_once(':')?;
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
+
+/// The first use of `needle` inside a `{{ }}` or `{% %}` tag, as a whole name
+fn find_in_tags(source: &str, needle: &str) -> Option<Location> {
+    let is_name = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+    let mut offset = 0;
+    while let Some(start) = source[offset..].find('{') {
+        let tag_start = offset + start;
+        let close = match source[tag_start..].get(..2) {
+            Some("{{") => "}}",
+            Some("{%") => "%}",
+            _ => {
+                offset = tag_start + 1;
+                continue;
+            }
+        };
+        let body_start = tag_start + 2;
+        let body_end = body_start + source[body_start..].find(close)?;
+        let body = &source[body_start..body_end];
+
+        let mut from = 0;
+        while let Some(found) = body[from..].find(needle) {
+            let at = from + found;
+            let before = body[..at].chars().next_back();
+            let after = body[at + needle.len()..].chars().next();
+            let whole = before.is_none_or(|c| !is_name(c))
+                && (needle.ends_with('(') || after.is_none_or(|c| !is_name(c)));
+            if whole {
+                let absolute = body_start + at;
+                let line_start = source[..absolute].rfind('\n').map_or(0, |i| i + 1);
+                let line_end = source[absolute..]
+                    .find('\n')
+                    .map_or(source.len(), |i| absolute + i);
+                return Some(Location {
+                    line: source[..absolute].matches('\n').count() + 1,
+                    column: source[line_start..absolute].chars().count() + 1,
+                    text: source[line_start..line_end].to_string(),
+                    width: needle.trim_end_matches('(').chars().count(),
+                });
+            }
+            from = at + needle.len();
+        }
+        offset = body_end + 2;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tera::{Context, Tera};
+
+    fn render_error(templates: &[(&str, &str)], name: &str) -> TemplateError {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(templates.to_vec()).unwrap();
+        let error = tera.render(name, &Context::new()).unwrap_err();
+        TemplateError::new(&error, name, Some("post 'hello'".to_string()), |name| {
+            templates
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, source)| source.to_string())
+        })
+    }
+
+    #[test]
+    fn test_missing_variable_in_parent() {
+        let error = render_error(
+            &[
+                (
+                    "base.html",
+                    "<title>{{ site.title }}</title>\n<p>{{ post.cover_image }}</p>\n{% block body %}{% endblock %}",
+                ),
+                ("post.html", "{% extends \"base.html\" %}{% block body %}x{% endblock %}"),
+            ],
+            "post.html",
+        );
+        assert_eq!(error.template, "base.html");
+        assert_eq!(error.subject.as_deref(), Some("site.title"));
+        let location = error.location.as_ref().unwrap();
+        assert_eq!(
+            (location.line, location.column, location.width),
+            (1, 11, 10)
+        );
+
+        let report = error.to_string();
+        assert!(report.starts_with(
+            "Template error in 'base.html' at line 1, column 11 while rendering post 'hello':"
+        ));
+        assert!(
+            report.contains("  1 | <title>{{ site.title }}</title>\n    |           ^^^^^^^^^^\n")
+        );
+        assert!(report.contains("hint:"));
+    }
+
+    #[test]
+    fn test_unknown_filter_and_parse_error() {
+        // Only whole names match: `shout` inside `'shouty'` is skipped
+        let error = render_error(
+            &[("post.html", "{{ 'shouty' | upper }}\n  {{ 'b' | shout }}")],
+            "post.html",
+        );
+        assert_eq!(error.subject.as_deref(), Some("shout"));
+        assert_eq!(error.location.map(|l| (l.line, l.column)), Some((2, 12)));
+
+        let mut tera = Tera::default();
+        let error = tera
+            .add_raw_template("page.html", "<p>\n{{ broken }\n</p>")
+            .unwrap_err();
+        let source = "<p>\n{{ broken }\n</p>".to_string();
+        let error = TemplateError::new(&error, "page.html", None, |_| Some(source.clone()));
+        assert_eq!(error.location.map(|l| l.line), Some(2));
+    }
+}
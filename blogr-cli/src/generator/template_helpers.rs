@@ -0,0 +1,264 @@
+//! Filters and functions every theme can rely on
+//!
+//! These are the template helpers documented for theme authors in
+//! `docs/THEMES.md`; keep the two in sync.
+
+use crate::config::{Config, MarkdownConfig, ReadingTimeConfig, UrlConfig};
+use crate::content::ReadingTime;
+use crate::generator::markdown::render_markdown_with;
+use crate::utils::Utils;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use tera::Tera;
+
+/// Register the helper filters and the `absolute_url` function
+pub fn register(tera: &mut Tera, config: &Config) {
+    let timezone = config.blog.timezone.clone();
+    let base_url = config.get_effective_base_url();
+    let urls = config.urls.clone();
+    let reading = config.reading_time.clone();
+    let language = config.blog.language.clone();
+    let markdown_config = config.markdown.clone();
+
+    tera.register_filter(
+        "markdown",
+        move |value: &Value, args: &HashMap<String, Value>| markdown(value, args, &markdown_config),
+    );
+    tera.register_filter("truncate_words", truncate_words);
+    tera.register_filter(
+        "reading_time",
+        move |value: &Value, args: &HashMap<String, Value>| {
+            reading_time(value, args, &reading, language.as_deref())
+        },
+    );
+    tera.register_filter("slugify", slugify);
+    tera.register_filter("jsonify", jsonify);
+    tera.register_filter(
+        "date_format",
+        move |value: &Value, args: &HashMap<String, Value>| {
+            date_format(value, args, timezone.as_deref())
+        },
+    );
+
+    let (filter_base, filter_urls) = (base_url.clone(), urls.clone());
+    tera.register_filter(
+        "absolute_url",
+        move |value: &Value, _args: &HashMap<String, Value>| {
+            let path = value
+                .as_str()
+                .ok_or_else(|| tera::Error::msg("absolute_url expects a string"))?;
+            Ok(Value::String(absolute_url(
+                &filter_base,
+                &filter_urls,
+                path,
+            )))
+        },
+    );
+    tera.register_function("absolute_url", move |args: &HashMap<String, Value>| {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| tera::Error::msg("absolute_url requires a 'path' argument"))?;
+        Ok(Value::String(absolute_url(&base_url, &urls, path)))
+    });
+}
+
+fn string_arg<'a>(value: &'a Value, filter: &str) -> tera::Result<&'a str> {
+    value
+        .as_str()
+        .ok_or_else(|| tera::Error::msg(format!("{} expects a string", filter)))
+}
+
+/// `{{ text | markdown }}`; `inline=true` drops the paragraph around a single line
+fn markdown(
+    value: &Value,
+    args: &HashMap<String, Value>,
+    config: &MarkdownConfig,
+) -> tera::Result<Value> {
+    let html = render_markdown_with(string_arg(value, "markdown")?, config, None, |_| None)
+        .map_err(|e| tera::Error::msg(e.to_string()))?;
+    let inline = args.get("inline").and_then(Value::as_bool).unwrap_or(false);
+
+    let html = match html.trim_end().strip_prefix("<p>") {
+        Some(rest) if inline && !rest.contains("<p>") => {
+            rest.trim_end().trim_end_matches("</p>").to_string()
+        }
+        _ => html,
+    };
+    Ok(Value::String(html))
+}
+
+/// `{{ text | truncate_words(count=30, end="…") }}`
+fn truncate_words(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let text = string_arg(value, "truncate_words")?;
+    let count = args.get("count").and_then(Value::as_u64).unwrap_or(30) as usize;
+    let end = args.get("end").and_then(Value::as_str).unwrap_or("…");
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() <= count {
+        return Ok(Value::String(words.join(" ")));
+    }
+    Ok(Value::String(format!(
+        "{}{}",
+        words[..count].join(" "),
+        end
+    )))
+}
+
+/// `{{ content | reading_time }}`: minutes to read markdown or HTML, at least 1.
+/// Uses `[reading_time]` and the blog language unless `wpm` is given.
+fn reading_time(
+    value: &Value,
+    args: &HashMap<String, Value>,
+    config: &ReadingTimeConfig,
+    language: Option<&str>,
+) -> tera::Result<Value> {
+    let text = string_arg(value, "reading_time")?;
+    let estimate = match args.get("wpm").and_then(Value::as_u64) {
+        Some(wpm) => {
+            let config = ReadingTimeConfig {
+                words_per_minute: (wpm as usize).max(1),
+                languages: HashMap::new(),
+                ..config.clone()
+            };
+            ReadingTime::estimate(text, &config, None)
+        }
+        None => ReadingTime::estimate(text, config, language),
+    };
+    Ok(Value::from(estimate.minutes as u64))
+}
+
+/// `{{ title | slugify }}`, matching the slugs blogr gives posts
+fn slugify(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<Value> {
+    Ok(Value::String(Utils::slugify(string_arg(value, "slugify")?)))
+}
+
+/// `{{ data | jsonify | safe }}`, safe to embed in a `<script>` element
+fn jsonify(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let pretty = args.get("pretty").and_then(Value::as_bool).unwrap_or(false);
+    let json = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+    .map_err(|e| tera::Error::msg(e.to_string()))?;
+    Ok(Value::String(json.replace("</", "<\\/")))
+}
+
+/// `{{ post.date | date_format(format="%B %-d, %Y", timezone="Europe/Berlin") }}`.
+/// The timezone defaults to `blog.timezone`.
+fn date_format(
+    value: &Value,
+    args: &HashMap<String, Value>,
+    default_timezone: Option<&str>,
+) -> tera::Result<Value> {
+    let date = parse_date(value)
+        .ok_or_else(|| tera::Error::msg(format!("date_format cannot read the date {}", value)))?;
+    let format = args
+        .get("format")
+        .and_then(Value::as_str)
+        .unwrap_or("%B %-d, %Y");
+    let timezone = args
+        .get("timezone")
+        .and_then(Value::as_str)
+        .or(default_timezone)
+        .unwrap_or("UTC");
+
+    let timezone: chrono_tz::Tz = timezone
+        .parse()
+        .map_err(|_| tera::Error::msg(format!("date_format: unknown timezone '{}'", timezone)))?;
+    Ok(Value::String(
+        date.with_timezone(&timezone).format(format).to_string(),
+    ))
+}
+
+/// Dates as posts serialize them (RFC 3339), plain `YYYY-MM-DD`, or a Unix timestamp
+fn parse_date(value: &Value) -> Option<DateTime<Utc>> {
+    if let Some(timestamp) = value.as_i64() {
+        return Utc.timestamp_opt(timestamp, 0).single();
+    }
+    let text = value.as_str()?.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S") {
+        return Some(date.and_utc());
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
+
+/// Full URL of a site path under the effective base URL
+fn absolute_url(base_url: &str, urls: &UrlConfig, path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return path.to_string();
+    }
+    format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        urls.page_path(path).trim_start_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, context: &tera::Context) -> String {
+        let mut config = Config::default();
+        config.blog.base_url = "https://example.com/blog".to_string();
+        config.blog.timezone = Some("America/New_York".to_string());
+        config.urls.pretty = true;
+        let mut tera = Tera::default();
+        register(&mut tera, &config);
+        tera.render_str(template, context).unwrap()
+    }
+
+    #[test]
+    fn test_template_helpers() {
+        let mut context = tera::Context::new();
+        context.insert("date", "2024-03-01T03:30:00Z");
+        context.insert("text", "one two three four five");
+        context.insert("data", &serde_json::json!({"html": "</script>"}));
+
+        assert_eq!(
+            render("{{ '*hi*' | markdown(inline=true) | safe }}", &context),
+            "<em>hi</em>"
+        );
+        assert_eq!(
+            render("{{ text | truncate_words(count=3) }}", &context),
+            "one two three…"
+        );
+        assert_eq!(render("{{ text | reading_time }}", &context), "1");
+        assert_eq!(
+            render("{{ 'Hello, World!' | slugify }}", &context),
+            "hello-world"
+        );
+        assert_eq!(
+            render("{{ data | jsonify | safe }}", &context),
+            r#"{"html":"<\/script>"}"#
+        );
+        // Formatted in the blog's timezone unless one is given
+        assert_eq!(
+            render(
+                "{{ date | date_format(format='%Y-%m-%d %H:%M') }}",
+                &context
+            ),
+            "2024-02-29 22:30"
+        );
+        assert_eq!(
+            render(
+                "{{ date | date_format(format='%H:%M', timezone='Asia/Tokyo') }}",
+                &context
+            ),
+            "12:30"
+        );
+        assert_eq!(
+            render("{{ absolute_url(path='posts/hello.html') }}", &context),
+            "https://example.com/blog/posts/hello/"
+        );
+    }
+}
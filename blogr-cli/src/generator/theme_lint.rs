@@ -0,0 +1,770 @@
+//! `blogr theme lint`: render a theme against a synthetic site
+//!
+//! Every template is rendered with the context the site builder gives it,
+//! filled with sample posts, tags, menu entries and (for personal sites) the
+//! theme's example `content.md` sections. Keep the contexts here in step with
+//! `SiteBuilder` so theme authors see the same variables a real build has.
+
+use crate::config::{Config, ContactConfig, DocsConfig, MenuItem};
+use crate::content::{Post, PostStatus};
+use crate::generator::archive::group_by_month;
+use crate::generator::contact::{ContactForm, THANKS_PAGE};
+use crate::generator::covers::{Cover, CoverSize};
+use crate::generator::docs_nav::DocsNav;
+use crate::generator::error_pages::{ErrorPage, NOT_FOUND};
+use crate::generator::gallery::{Gallery, GalleryImage};
+use crate::generator::markdown::render_markdown;
+use crate::generator::pages::Page;
+use crate::generator::resume::Resume;
+use crate::generator::scss;
+use crate::generator::site::{newsletter_form_context, SiteBuilder};
+use crate::github_projects::GitHubProject;
+use crate::project::Project;
+use blogr_themes::{SiteType, Theme};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tera::{Context, ErrorKind, Tera};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintKind {
+    /// A template does not parse or extends/imports a missing template
+    Syntax,
+    MissingVariable,
+    UndefinedFilter,
+    UndefinedFunction,
+    /// Any other error raised while rendering
+    Render,
+    /// An asset no template or stylesheet points to
+    UnreferencedAsset,
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            LintKind::Syntax => "syntax error",
+            LintKind::MissingVariable => "missing variable",
+            LintKind::UndefinedFilter => "undefined filter",
+            LintKind::UndefinedFunction => "undefined function",
+            LintKind::Render => "render error",
+            LintKind::UnreferencedAsset => "unreferenced asset",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A problem found in one template or asset
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LintIssue {
+    /// Template name or asset path
+    pub file: String,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+/// Lint a theme, returning its problems ordered by file
+pub fn lint_theme(theme: &dyn Theme) -> Vec<LintIssue> {
+    let info = theme.info();
+    let config = sample_config(theme);
+    let mut issues = BTreeSet::new();
+
+    let tera = match SiteBuilder::template_engine(theme, &config) {
+        Ok(tera) => tera,
+        Err(e) => {
+            issues.insert(LintIssue {
+                file: info.name.clone(),
+                kind: LintKind::Syntax,
+                message: format!("{:#}", e),
+            });
+            return issues.into_iter().collect();
+        }
+    };
+
+    let mut templates: Vec<String> = theme
+        .templates()
+        .into_iter()
+        .map(|(name, _)| name.into_owned())
+        .collect();
+    // Built-in fallbacks the site builder renders for themes that lack them
+    let fallbacks: &[&str] = match info.site_type {
+        SiteType::Blog => &["search.html", "gallery.html", "error.html", "plain.html"],
+        SiteType::Personal => &[
+            "page.html",
+            "gallery.html",
+            "error.html",
+            "resume.html",
+            "resume_print.html",
+            "contact_thanks.html",
+        ],
+        SiteType::Hybrid => &[
+            "search.html",
+            "page.html",
+            "gallery.html",
+            "error.html",
+            "plain.html",
+            "resume.html",
+            "resume_print.html",
+            "contact_thanks.html",
+        ],
+    };
+    for fallback in fallbacks
+        .iter()
+        .chain(&["newsletter_form.html", "contact_form.html"])
+    {
+        if !templates.iter().any(|name| name == fallback) {
+            templates.push(fallback.to_string());
+        }
+    }
+
+    let mut pages = page_contexts(&config, &info.site_type);
+    if let Some(form) = newsletter_form_context(&config, "/js/newsletter.js") {
+        pages.insert("newsletter_form.html", form);
+    }
+    pages.insert("contact_form.html", contact_form_context(&config));
+    let mut all_pages = Context::new();
+    for context in pages.values() {
+        all_pages.extend(context.clone());
+    }
+
+    // Render each page with its own context; partials and layouts get every
+    // variable a page could pass them
+    for name in &templates {
+        let context = pages.get(name.as_str()).unwrap_or(&all_pages);
+        if let Err(e) = tera.render(name, context) {
+            issues.insert(render_issue(name, &e));
+        }
+    }
+
+    // Filters in branches the sample site never reaches
+    let mut probe = tera.clone();
+    for (name, source) in theme.templates() {
+        for filter in filters_used(&source) {
+            if !filter_exists(&mut probe, &filter) {
+                issues.insert(LintIssue {
+                    file: name.to_string(),
+                    kind: LintKind::UndefinedFilter,
+                    message: format!("Filter '{}' not found", filter),
+                });
+            }
+        }
+    }
+
+    issues.extend(unreferenced_assets(theme));
+    issues.into_iter().collect()
+}
+
+/// A config for the theme with a menu, tags and a newsletter form
+fn sample_config(theme: &dyn Theme) -> Config {
+    let info = theme.info();
+    let mut config = Config::new_with_defaults(
+        "Lint Site".to_string(),
+        "Theme Linter".to_string(),
+        "A synthetic site for checking themes".to_string(),
+        None,
+        None,
+    );
+    config.site.site_type = info.site_type.to_string();
+    config.theme.name = info.name.to_lowercase().replace(' ', "-");
+    for (option_name, option) in info.config_schema {
+        config.theme.config.insert(option_name, option.value);
+    }
+    config.menu = vec![
+        MenuItem {
+            name: "Archive".to_string(),
+            url: "archive.html".to_string(),
+        },
+        MenuItem {
+            name: "Source".to_string(),
+            url: "https://example.com/source".to_string(),
+        },
+    ];
+    config.newsletter.enabled = true;
+    config.newsletter.subscribe_email = Some("newsletter@example.com".to_string());
+    config
+}
+
+fn sample_posts() -> Vec<Post> {
+    let mut first = Post::new(
+        "Hello, World".to_string(),
+        "Theme Linter".to_string(),
+        Some("The first sample post".to_string()),
+        vec!["rust".to_string(), "web".to_string()],
+        None,
+        PostStatus::Published,
+    );
+    first.metadata.featured = true;
+    first.metadata.cover = Some("static/images/cover.jpg".to_string());
+    first.metadata.cover_alt = Some("A sample cover image".to_string());
+    first.metadata.date = Utc::now() - Duration::days(400);
+    first.content = "## A heading\n\nSome *text* with a [link](https://example.com).\n\n\
+        ```rust\nfn main() {}\n```\n"
+        .to_string();
+
+    let mut second = Post::new(
+        "Second Post".to_string(),
+        "Theme Linter".to_string(),
+        None,
+        vec!["web".to_string()],
+        None,
+        PostStatus::Published,
+    );
+    second.metadata.updated_at = Some(Utc::now());
+    second.metadata.link = Some("https://example.org/article".to_string());
+    second.metadata.contributors = vec!["Theme Linter".to_string(), "Editor".to_string()];
+    second.content = "> A quote\n\n- one\n- two\n".to_string();
+
+    vec![second, first]
+}
+
+/// Context each page template is rendered with, by template name
+fn page_contexts(config: &Config, site_type: &SiteType) -> HashMap<&'static str, Context> {
+    if *site_type == SiteType::Personal {
+        let mut pages = personal_contexts(config);
+        pages.extend(error_contexts(config));
+        return pages;
+    }
+
+    let mut common = Context::new();
+    common.insert("site", config);
+    common.insert("newsletter", &config.newsletter);
+    common.insert("newsletter_form", "<form class=\"newsletter-form\"></form>");
+    common.insert("canonical_url", &config.page_url("index.html"));
+
+    let posts = sample_posts();
+    let summaries: Vec<Value> = posts.iter().map(post_summary).collect();
+    let mut pages = HashMap::new();
+
+    let mut index = common.clone();
+    index.insert("posts", &summaries);
+    index.insert("has_more", &false);
+    index.insert("total_posts", &summaries.len());
+    index.insert(
+        "pagination",
+        &serde_json::json!({ "posts_per_page": 10, "total_pages": 1, "index_content": null }),
+    );
+    if let Some(featured) = posts.iter().position(|post| post.metadata.featured) {
+        index.insert("featured_post", &summaries[featured]);
+    }
+    pages.insert("index.html", index);
+
+    let mut post = common.clone();
+    post.insert("post", &posts[0]);
+    post.insert(
+        "content",
+        &render_markdown(&posts[0].content).unwrap_or_default(),
+    );
+    post.insert("reading_time", &1);
+    post.insert("word_count", &120);
+    let mut plain = post.clone();
+    plain.insert("author", &config.blog.author);
+    plain.insert(
+        "post_url",
+        &config.page_url(&format!("posts/{}.html", posts[0].metadata.slug)),
+    );
+    pages.insert("post.html", post);
+    pages.insert("plain.html", plain);
+
+    let dated: Vec<(DateTime<Utc>, Value)> = posts
+        .iter()
+        .map(|post| post.metadata.date)
+        .zip(summaries.iter().cloned())
+        .collect();
+    let archives = group_by_month(&dated);
+    let posts_by_year: HashMap<i32, &Vec<Value>> = archives
+        .iter()
+        .map(|year| (year.year, &year.posts))
+        .collect();
+    let mut archive = common.clone();
+    archive.insert("posts", &summaries);
+    archive.insert("archives", &archives);
+    archive.insert("posts_by_year", &posts_by_year);
+    archive.insert("archive_period", &archives[0].period());
+    pages.insert("archive.html", archive);
+
+    let mut tag = common.clone();
+    tag.insert("tag", "web");
+    tag.insert("posts", &summaries);
+    pages.insert("tag.html", tag);
+
+    let mut tags = common.clone();
+    tags.insert(
+        "tags",
+        &vec![("rust".to_string(), 1usize), ("web".to_string(), 2usize)],
+    );
+    pages.insert("tags.html", tags);
+
+    let mut gallery = common.clone();
+    gallery.insert("gallery", &sample_gallery());
+    pages.insert("gallery.html", gallery);
+
+    pages.insert("search.html", common);
+    pages.extend(error_contexts(config));
+
+    // Hybrid themes add a landing page and the personal site's pages
+    if *site_type == SiteType::Hybrid {
+        let mut personal = personal_contexts(config);
+        let mut home = personal.remove("index.html").unwrap_or_default();
+        let recent_posts: Vec<Value> = posts
+            .iter()
+            .map(|post| {
+                serde_json::json!({
+                    "metadata": post.metadata,
+                    "path": format!("blog/posts/{}.html", post.metadata.slug),
+                })
+            })
+            .collect();
+        home.insert("recent_posts", &recent_posts);
+        home.insert("content", "<p>Welcome to my site.</p>");
+        pages.insert("home.html", home);
+        pages.extend(personal);
+    }
+    pages
+}
+
+/// A post as index, archive and tag pages list it
+fn post_summary(post: &Post) -> Value {
+    serde_json::json!({
+        "metadata": post.metadata,
+        "cover": sample_cover(post),
+        "content": render_markdown(&post.content).unwrap_or_default(),
+        "summary": format!("<p>{}</p>", post.metadata.description),
+        "reading_time": 1,
+        "word_count": 120,
+    })
+}
+
+/// A sample post's `cover`, as if its image were 1600 by 900
+fn sample_cover(post: &Post) -> Option<Value> {
+    let cover = Cover {
+        src: post.metadata.cover.clone()?,
+        alt: post.metadata.cover_alt.clone().unwrap_or_default(),
+        width: Some(1600),
+        height: Some(900),
+        sizes: [(480, 270), (960, 540)]
+            .into_iter()
+            .map(|(width, height)| CoverSize {
+                path: format!("covers/{}-{}.jpg", post.metadata.slug, width),
+                width,
+                height,
+            })
+            .collect(),
+        source: Some(PathBuf::from(post.metadata.cover.clone()?)),
+    };
+    Some(cover.context(|path| format!("/{}", path)))
+}
+
+/// Home page and a `content/` page of a personal site
+fn personal_contexts(config: &Config) -> HashMap<&'static str, Context> {
+    let page = Page::parse(
+        "---\ntitle: Projects\ndescription: Things I built\n---\nSome **work**.\n",
+        Path::new("projects.md"),
+    )
+    .expect("sample page parses");
+    let shared = personal_context(config, std::slice::from_ref(&page));
+
+    let mut index = shared.clone();
+    index.insert("current_page", "");
+    index.insert("content", "<p>Welcome</p>");
+    index.insert("canonical_url", &config.page_url("index.html"));
+
+    let mut gallery = shared.clone();
+    gallery.insert("gallery", &sample_gallery());
+    gallery.insert("current_page", "");
+
+    let mut resume = shared.clone();
+    resume.insert("resume", &sample_resume());
+    resume.insert("current_page", "resume");
+    resume.insert("resume_pdf", "resume.pdf");
+
+    let mut page_context = shared;
+    page_context.insert("page", &page);
+    page_context.insert("current_page", &page.slug);
+    if let Ok(docs) = DocsNav::new(&DocsConfig::default(), std::slice::from_ref(&page)) {
+        page_context.insert("docs", &docs.context(&page.slug));
+    }
+    page_context.insert(
+        "content",
+        &render_markdown(&page.content).unwrap_or_default(),
+    );
+    page_context.insert("canonical_url", &config.page_url(&page.output_path()));
+
+    HashMap::from([
+        ("index.html", index),
+        ("page.html", page_context),
+        ("gallery.html", gallery),
+        ("resume_print.html", resume.clone()),
+        ("resume.html", resume),
+    ])
+}
+
+/// `contact_form.html`, with the project's backend when it has a working one
+fn contact_form_context(config: &Config) -> Context {
+    let sample = ContactConfig {
+        enabled: true,
+        form_id: Some("lint".to_string()),
+        ..Default::default()
+    };
+    let thanks = config.page_url(THANKS_PAGE);
+    let form = ContactForm::new(&config.contact, &thanks)
+        .or_else(|_| ContactForm::new(&sample, &thanks))
+        .expect("sample contact form is valid");
+    let mut context = Context::new();
+    context.insert("site", config);
+    context.insert("contact", &form);
+    context
+}
+
+/// The 404 page, from the theme or the built-in template
+fn error_contexts(config: &Config) -> HashMap<&'static str, Context> {
+    let mut context = personal_context(config, &[]);
+    context.insert("newsletter", &config.newsletter);
+    context.insert("newsletter_form", "<form class=\"newsletter-form\"></form>");
+    context.insert("current_page", "");
+    context.insert("error", &ErrorPage::new(NOT_FOUND));
+    context.insert("content", "");
+    HashMap::from([("404.html", context.clone()), ("error.html", context)])
+}
+
+/// A gallery with a dated, captioned photo and a plain one
+fn sample_gallery() -> Gallery {
+    let image = |file: &str, caption: Option<&str>, taken_at: Option<NaiveDateTime>| GalleryImage {
+        file: file.to_string(),
+        path: format!("galleries/trip/{}", file),
+        thumbnail: format!("galleries/trip/thumbs/{}", file),
+        width: 1200,
+        height: 800,
+        taken_at,
+        caption: caption.map(String::from),
+        source: file.into(),
+    };
+    Gallery {
+        name: "trip".to_string(),
+        title: "Trip".to_string(),
+        description: Some("Photos from a trip".to_string()),
+        path: "galleries/trip/index.html".to_string(),
+        images: vec![
+            image(
+                "beach.jpg",
+                Some("The beach"),
+                NaiveDate::from_ymd_opt(2024, 6, 1).and_then(|date| date.and_hms_opt(9, 30, 0)),
+            ),
+            image("hill.jpg", None, None),
+        ],
+    }
+}
+
+/// A resume with every section filled in
+fn sample_resume() -> Resume {
+    serde_yaml::from_str(
+        "basics:\n  name: Theme Linter\n  label: Tester\n  email: lint@example.com\n  url: https://example.com\n  summary: Checks themes.\n  location: {city: Berlin, region: BE}\n  profiles: [{network: GitHub, url: 'https://github.com/lint'}, {network: Mastodon, username: lint}]\n\
+         work: [{name: Example, position: Engineer, url: 'https://example.com', startDate: '2020-01', summary: Built things, highlights: [Shipped]}]\n\
+         volunteer: [{organization: Club, position: Helper, startDate: '2019-01', endDate: '2020-01'}]\n\
+         education: [{institution: University, area: Physics, studyType: BSc, startDate: '2015', endDate: '2019', score: '1.3', courses: [Optics]}]\n\
+         awards: [{title: Prize, awarder: Jury, date: '2021'}]\n\
+         certificates: [{name: Certified, issuer: Board, url: 'https://example.com/cert'}]\n\
+         publications: [{name: Paper, publisher: Journal, releaseDate: '2022'}]\n\
+         skills: [{name: Rust, level: Expert, keywords: [async]}]\n\
+         languages: [{language: English, fluency: Native}]\n\
+         interests: [{name: Climbing, keywords: [bouldering]}]\n\
+         references: [{name: Colleague, reference: Great to work with.}]\n\
+         projects: [{name: Blogr, description: A blog generator, url: 'https://example.com/blogr', startDate: '2023', highlights: [Themes], keywords: [rust]}]\n",
+    )
+    .expect("sample resume parses")
+}
+
+fn personal_context(config: &Config, pages: &[Page]) -> Context {
+    let mut context = Context::new();
+    context.insert("site", config);
+    context.insert("blog_title", &config.blog.title);
+    context.insert("blog_description", &config.blog.description);
+    context.insert("author", &config.blog.author);
+    context.insert("base_url", &config.get_effective_base_url());
+    context.insert("language", "en");
+    context.insert("theme_config", &config.theme.config);
+    context.insert("current_year", &Utc::now().year());
+    let nav: Vec<Value> = pages
+        .iter()
+        .map(|page| {
+            serde_json::json!({
+                "title": page.title,
+                "slug": page.slug,
+                "path": page.output_path(),
+            })
+        })
+        .collect();
+    context.insert("pages", &nav);
+    // The configured tree may name pages the sample doesn't have
+    let docs =
+        DocsNav::new(&config.docs, pages).or_else(|_| DocsNav::new(&DocsConfig::default(), pages));
+    if let Ok(docs) = docs {
+        context.insert("docs", &docs.context(""));
+    }
+    context.insert(
+        "github_projects",
+        &[GitHubProject {
+            name: "blogr".to_string(),
+            full_name: "lint/blogr".to_string(),
+            description: Some("A static site generator".to_string()),
+            url: "https://github.com/lint/blogr".to_string(),
+            homepage: None,
+            language: Some("Rust".to_string()),
+            stars: 42,
+            forks: 3,
+            topics: vec!["ssg".to_string()],
+            updated_at: None,
+        }],
+    );
+    if config.site.site_type == "hybrid" {
+        context.insert("blog_path", "blog/");
+    }
+    context.insert("contact_form", "<form class=\"contact-form\"></form>");
+
+    let content_md = Project::example_personal_content(config);
+    let frontmatter = content_md
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .and_then(|(yaml, _)| serde_yaml::from_str::<serde_yaml::Value>(yaml).ok());
+    if let Some(frontmatter) = frontmatter {
+        if let Some(sections) = frontmatter.get("sections") {
+            context.insert("sections", sections);
+        }
+        if let Some(theme_config) = frontmatter.get("theme_config") {
+            context.insert("theme_config", theme_config);
+        }
+    }
+    context
+}
+
+fn render_issue(template: &str, error: &tera::Error) -> LintIssue {
+    let mut kind = LintKind::Render;
+    let mut message = error.to_string();
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(error);
+
+    // The useful message is at the bottom of Tera's error chain
+    while let Some(e) = source {
+        message = e.to_string();
+        if let Some(e) = e.downcast_ref::<tera::Error>() {
+            kind = match &e.kind {
+                ErrorKind::FilterNotFound(_) => LintKind::UndefinedFilter,
+                ErrorKind::FunctionNotFound(_) => LintKind::UndefinedFunction,
+                ErrorKind::TemplateNotFound(_) | ErrorKind::MissingParent { .. } => {
+                    LintKind::Syntax
+                }
+                ErrorKind::Msg(msg) if msg.contains("not found in context") => {
+                    LintKind::MissingVariable
+                }
+                _ => kind,
+            };
+        }
+        source = e.source();
+    }
+
+    LintIssue {
+        file: template.to_string(),
+        kind,
+        message,
+    }
+}
+
+/// Filter names used in `{{ }}` and `{% %}` tags, ignoring string literals
+fn filters_used(source: &str) -> BTreeSet<String> {
+    let mut filters = BTreeSet::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find('{') {
+        let tag = &rest[start..];
+        let close = if tag.starts_with("{{") {
+            "}}"
+        } else if tag.starts_with("{%") {
+            "%}"
+        } else {
+            rest = &tag[1..];
+            continue;
+        };
+        let Some(end) = tag[2..].find(close) else {
+            break;
+        };
+        let body = strip_strings(&tag[2..2 + end]);
+        rest = &tag[2 + end + 2..];
+
+        let trimmed = body.trim_start_matches('-').trim();
+        if let Some(name) = trimmed.strip_prefix("filter ") {
+            filters.insert(identifier(name).to_string());
+        }
+        for part in body.split('|').skip(1) {
+            let name = identifier(part.trim_start());
+            if !name.is_empty() {
+                filters.insert(name.to_string());
+            }
+        }
+    }
+    filters
+}
+
+fn strip_strings(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut quote = None;
+    for c in text.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if matches!(c, '"' | '\'' | '`') => quote = Some(c),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+fn identifier(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+fn filter_exists(probe: &mut Tera, filter: &str) -> bool {
+    let template = format!("{{{{ '' | {} }}}}", filter);
+    match probe.render_str(&template, &Context::new()) {
+        Ok(_) => true,
+        Err(e) => {
+            let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&e);
+            while let Some(err) = source {
+                if let Some(err) = err.downcast_ref::<tera::Error>() {
+                    if matches!(err.kind, ErrorKind::FilterNotFound(_)) {
+                        return false;
+                    }
+                }
+                source = err.source();
+            }
+            // The filter exists but rejected the probe's arguments
+            true
+        }
+    }
+}
+
+/// Assets whose path appears in no template and no other asset
+fn unreferenced_assets(theme: &dyn Theme) -> Vec<LintIssue> {
+    let assets = theme.assets();
+    let templates: Vec<_> = theme.templates().into_iter().map(|(_, t)| t).collect();
+
+    let mut unused: Vec<LintIssue> = assets
+        .keys()
+        .filter(|path| {
+            let asset = Path::new(path.as_str());
+            let reference = if scss::is_entry(asset) {
+                scss::output_path(asset).to_string_lossy().into_owned()
+            } else if asset.extension().is_some_and(|ext| ext == "scss") {
+                // Partials are imported by name, without `_` or extension
+                asset
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().trim_start_matches('_').to_string())
+                    .unwrap_or_default()
+            } else {
+                path.to_string()
+            };
+
+            let in_templates = templates.iter().any(|t| t.contains(&reference));
+            let in_assets = assets.iter().any(|(other, content)| {
+                other != *path && String::from_utf8_lossy(content).contains(&reference)
+            });
+            !in_templates && !in_assets
+        })
+        .map(|path| LintIssue {
+            file: path.clone(),
+            kind: LintKind::UnreferencedAsset,
+            message: "Not referenced by any template or stylesheet".to_string(),
+        })
+        .collect();
+    unused.sort();
+    unused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes_pass_lint() {
+        for theme in blogr_themes::get_all_themes() {
+            let issues = lint_theme(theme.as_ref());
+            assert!(
+                issues.is_empty(),
+                "{} has lint issues: {:#?}",
+                theme.info().name,
+                issues
+            );
+        }
+    }
+
+    struct BrokenTheme;
+
+    impl Theme for BrokenTheme {
+        fn info(&self) -> blogr_themes::ThemeInfo {
+            blogr_themes::ThemeInfo {
+                name: "Broken".to_string(),
+                version: "0.1.0".to_string(),
+                author: "Test".to_string(),
+                description: "A theme with mistakes".to_string(),
+                config_schema: HashMap::new(),
+                site_type: SiteType::Blog,
+            }
+        }
+
+        fn templates(&self) -> blogr_themes::ThemeTemplates {
+            blogr_themes::ThemeTemplates::new(
+                "base.html",
+                "<link href=\"{{ asset_url(path='css/style.css') }}\">{% block content %}{% endblock %}",
+            )
+            .with_template(
+                "post.html",
+                "{% extends \"base.html\" %}{% block content %}{{ post.subtitle }}{% endblock %}",
+            )
+            .with_template(
+                "index.html",
+                "{% extends \"base.html\" %}{% block content %}\
+                 {% if posts | length > 9 %}{{ posts | shout }}{% endif %}{% endblock %}",
+            )
+        }
+
+        fn assets(&self) -> HashMap<String, Vec<u8>> {
+            HashMap::from([
+                ("css/style.css".to_string(), b"body {}".to_vec()),
+                ("img/unused.png".to_string(), Vec::new()),
+            ])
+        }
+
+        fn preview_tui_style(&self) -> ratatui::style::Style {
+            ratatui::style::Style::default()
+        }
+    }
+
+    #[test]
+    fn test_lint_reports_broken_theme() {
+        let issues: Vec<(String, LintKind)> = lint_theme(&BrokenTheme)
+            .into_iter()
+            .map(|issue| (issue.file, issue.kind))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                ("img/unused.png".to_string(), LintKind::UnreferencedAsset),
+                ("index.html".to_string(), LintKind::UndefinedFilter),
+                ("post.html".to_string(), LintKind::MissingVariable),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filters_used() {
+        let filters = filters_used(
+            "{{ title | upper }} {% if x | length > 1 %}{{ 'a | b' }}{% endif %}\
+             {% filter markdown %}x{% endfilter %}{{ y|date(format='%Y') }}",
+        );
+        assert_eq!(
+            filters.into_iter().collect::<Vec<_>>(),
+            ["date", "length", "markdown", "upper"]
+        );
+    }
+}
@@ -0,0 +1,449 @@
+//! Video embeds
+//!
+//! YouTube and Vimeo videos are embedded as lazy-loaded iframes, from
+//! youtube-nocookie.com and with Vimeo's do-not-track flag unless
+//! `[markdown.video] privacy_enhanced` is off. With `click_to_load` the player
+//! is only fetched once the reader asks for it. Local files get a `<video>`
+//! element, with a poster frame taken by ffmpeg.
+
+use crate::config::VideoConfig;
+use crate::generator::markdown::html_escape;
+use crate::generator::shortcodes::Shortcode;
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+use url::Url;
+
+/// The click-to-load script, relative to the site root
+pub const VIDEO_SCRIPT: &str = "js/video.js";
+
+/// A video to embed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Video {
+    YouTube {
+        id: String,
+        start: Option<u32>,
+    },
+    Vimeo {
+        id: String,
+    },
+    /// A video file, as a site path or a full URL
+    File {
+        src: String,
+    },
+}
+
+/// Presentation options from a shortcode
+#[derive(Debug, Default)]
+pub struct VideoOptions {
+    pub title: Option<String>,
+    /// Poster image of a video file, as a site path or a full URL
+    pub poster: Option<String>,
+    pub autoplay: bool,
+    pub looped: bool,
+    pub muted: bool,
+}
+
+impl Video {
+    /// A YouTube or Vimeo link, or else a video file
+    pub fn parse(source: &str) -> Result<Self> {
+        let source = source.trim();
+        if source.is_empty() {
+            bail!("A video needs a source");
+        }
+        if let Ok(url) = Url::parse(source) {
+            let host = url
+                .host_str()
+                .unwrap_or_default()
+                .trim_start_matches("www.");
+            if matches!(
+                host,
+                "youtube.com" | "m.youtube.com" | "youtu.be" | "youtube-nocookie.com"
+            ) {
+                return Self::youtube(source);
+            }
+            if matches!(host, "vimeo.com" | "player.vimeo.com") {
+                return Self::vimeo(source);
+            }
+        }
+        Ok(Self::File {
+            src: source.to_string(),
+        })
+    }
+
+    /// A YouTube video from its ID or any of its URLs
+    pub fn youtube(source: &str) -> Result<Self> {
+        let (id, start) = match Url::parse(source) {
+            Ok(url) => {
+                let query = |name: &str| {
+                    url.query_pairs()
+                        .find(|(key, _)| key == name)
+                        .map(|(_, value)| value.into_owned())
+                };
+                let mut segments = url.path_segments().into_iter().flatten();
+                let id = if url.host_str() == Some("youtu.be") {
+                    segments.next().map(String::from)
+                } else {
+                    match segments.next() {
+                        Some("watch") => query("v"),
+                        Some("embed" | "shorts" | "live") => segments.next().map(String::from),
+                        _ => None,
+                    }
+                };
+                let start = query("t")
+                    .or_else(|| query("start"))
+                    .and_then(|t| t.trim_end_matches('s').parse().ok());
+                (id.unwrap_or_default(), start)
+            }
+            Err(_) => (source.to_string(), None),
+        };
+
+        let valid = id.len() == 11
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            bail!("'{}' is not a YouTube video ID or link", source);
+        }
+        Ok(Self::YouTube { id, start })
+    }
+
+    /// A Vimeo video from its ID or URL
+    pub fn vimeo(source: &str) -> Result<Self> {
+        let id = match Url::parse(source) {
+            Ok(url) => url
+                .path_segments()
+                .into_iter()
+                .flatten()
+                .find(|segment| !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()))
+                .unwrap_or_default()
+                .to_string(),
+            Err(_) => source.to_string(),
+        };
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+            bail!("'{}' is not a Vimeo video ID or link", source);
+        }
+        Ok(Self::Vimeo { id })
+    }
+
+    /// The video of a `youtube`, `vimeo` or `video` shortcode
+    pub fn from_shortcode(shortcode: &Shortcode) -> Result<Self> {
+        let source = shortcode
+            .named("src")
+            .or_else(|| shortcode.positional(0))
+            .ok_or_else(|| anyhow!("The {} shortcode needs a video", shortcode.name))?;
+        let mut video = match shortcode.name.as_str() {
+            "youtube" => Self::youtube(source)?,
+            "vimeo" => Self::vimeo(source)?,
+            _ => Self::parse(source)?,
+        };
+        if let (Self::YouTube { start, .. }, Some(seconds)) = (&mut video, shortcode.named("start"))
+        {
+            *start =
+                Some(seconds.parse().map_err(|_| {
+                    anyhow!("start must be a number of seconds, not '{}'", seconds)
+                })?);
+        }
+        Ok(video)
+    }
+
+    /// Path of a video file in the project, when it is not a remote URL
+    pub fn local_file(&self) -> Option<&str> {
+        match self {
+            Self::File { src } if Url::parse(src).is_err() => Some(src.trim_start_matches('/')),
+            _ => None,
+        }
+    }
+
+    /// The player page embedded in an iframe
+    fn player_url(&self, config: &VideoConfig) -> Option<String> {
+        match self {
+            Self::YouTube { id, start } => {
+                let host = if config.privacy_enhanced {
+                    "www.youtube-nocookie.com"
+                } else {
+                    "www.youtube.com"
+                };
+                let mut url = format!("https://{}/embed/{}", host, id);
+                if let Some(start) = start {
+                    url.push_str(&format!("?start={}", start));
+                }
+                Some(url)
+            }
+            Self::Vimeo { id } => Some(if config.privacy_enhanced {
+                format!("https://player.vimeo.com/video/{}?dnt=1", id)
+            } else {
+                format!("https://player.vimeo.com/video/{}", id)
+            }),
+            Self::File { .. } => None,
+        }
+    }
+
+    /// The video's own page, linked from the click-to-load placeholder
+    fn watch_url(&self) -> Option<String> {
+        match self {
+            Self::YouTube { id, .. } => Some(format!("https://www.youtube.com/watch?v={}", id)),
+            Self::Vimeo { id } => Some(format!("https://vimeo.com/{}", id)),
+            Self::File { .. } => None,
+        }
+    }
+
+    fn provider(&self) -> &'static str {
+        match self {
+            Self::YouTube { .. } => "YouTube",
+            Self::Vimeo { .. } => "Vimeo",
+            Self::File { .. } => "",
+        }
+    }
+}
+
+impl VideoOptions {
+    pub fn from_shortcode(shortcode: &Shortcode) -> Self {
+        let flag = |name: &str| shortcode.args.iter().any(|arg| arg == name);
+        Self {
+            title: shortcode.named("title").map(String::from),
+            poster: shortcode.named("poster").map(String::from),
+            autoplay: flag("autoplay"),
+            looped: flag("loop"),
+            muted: flag("muted"),
+        }
+    }
+}
+
+/// HTML for a video, on a single line so it can sit inside markdown.
+/// `url` turns a site path into a link.
+pub fn embed_html(
+    video: &Video,
+    options: &VideoOptions,
+    config: &VideoConfig,
+    url: impl Fn(&str) -> String,
+) -> String {
+    let link = |src: &str| {
+        if Url::parse(src).is_ok() {
+            src.to_string()
+        } else {
+            url(src.trim_start_matches('/'))
+        }
+    };
+
+    let Some(player) = video.player_url(config) else {
+        let Video::File { src } = video else {
+            return String::new();
+        };
+        let mut attributes = format!("controls playsinline preload=\"{}\"", config.preload);
+        if let Some(poster) = &options.poster {
+            attributes.push_str(&format!(" poster=\"{}\"", html_escape(&link(poster))));
+        }
+        // Browsers only autoplay muted videos
+        if options.autoplay {
+            attributes.push_str(" autoplay");
+        }
+        if options.looped {
+            attributes.push_str(" loop");
+        }
+        if options.muted || options.autoplay {
+            attributes.push_str(" muted");
+        }
+        if let Some(title) = &options.title {
+            attributes.push_str(&format!(" aria-label=\"{}\"", html_escape(title)));
+        }
+        let src = html_escape(&link(src));
+        return format!(
+            "<video class=\"blogr-video\" {}><source src=\"{}\"{}><a href=\"{}\">Download the video</a></video>",
+            attributes,
+            src,
+            mime_type(&src)
+                .map(|mime| format!(" type=\"{}\"", mime))
+                .unwrap_or_default(),
+            src,
+        );
+    };
+
+    let provider = video.provider();
+    let title = html_escape(
+        &options
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("{} video", provider)),
+    );
+    let style = "position:relative;aspect-ratio:16/9;width:100%";
+
+    if !config.click_to_load {
+        return format!(
+            "<div class=\"blogr-video blogr-video-{}\" style=\"{}\"><iframe src=\"{}\" title=\"{}\" loading=\"lazy\" allow=\"autoplay; encrypted-media; fullscreen; picture-in-picture\" referrerpolicy=\"strict-origin-when-cross-origin\" allowfullscreen style=\"position:absolute;inset:0;width:100%;height:100%;border:0\"></iframe></div>",
+            provider.to_lowercase(),
+            style,
+            html_escape(&player),
+            title
+        );
+    }
+
+    format!(
+        "<div class=\"blogr-video blogr-video-{}\" style=\"{}\" data-blogr-video=\"{}\" data-title=\"{}\"><button type=\"button\">Play video from {}</button><p>Playing loads the video from {}. <a href=\"{}\">Watch on {}</a></p></div><script src=\"{}\" defer></script>",
+        provider.to_lowercase(),
+        style,
+        html_escape(&player),
+        title,
+        provider,
+        provider,
+        html_escape(&video.watch_url().unwrap_or_default()),
+        provider,
+        url(VIDEO_SCRIPT),
+    )
+}
+
+fn mime_type(src: &str) -> Option<&'static str> {
+    let extension = Path::new(src.split(['?', '#']).next().unwrap_or_default())
+        .extension()?
+        .to_string_lossy()
+        .to_lowercase();
+    match extension.as_str() {
+        "mp4" | "m4v" => Some("video/mp4"),
+        "webm" => Some("video/webm"),
+        "ogv" | "ogg" => Some("video/ogg"),
+        "mov" => Some("video/quicktime"),
+        _ => None,
+    }
+}
+
+/// Site path of the poster made for a local video: `clip.mp4` → `clip.poster.jpg`
+pub fn poster_path(src: &str) -> String {
+    let path = Path::new(src);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        Some(parent) => format!("{}/{}.poster.jpg", parent.to_string_lossy(), stem),
+        None => format!("{}.poster.jpg", stem),
+    }
+}
+
+/// A JPEG of an early frame of a video, taken with ffmpeg
+pub fn poster_frame(video: &Path, ffmpeg: Option<&str>) -> Result<Vec<u8>> {
+    let ffmpeg = ffmpeg.unwrap_or("ffmpeg");
+    let mut stderr = String::new();
+
+    // One second in skips fades from black; very short clips fall back to the first frame
+    for seek in ["1", "0"] {
+        let output = Command::new(ffmpeg)
+            .args(["-v", "error", "-ss", seek, "-i"])
+            .arg(video)
+            .args(["-frames:v", "1", "-q:v", "3", "-f", "image2", "-c:v", "mjpeg", "pipe:1"])
+            .output()
+            .with_context(|| {
+                format!(
+                    "Failed to run {}. Install ffmpeg, set [markdown.video] ffmpeg, or turn off posters",
+                    ffmpeg
+                )
+            })?;
+        if output.status.success() && !output.stdout.is_empty() {
+            return Ok(output.stdout);
+        }
+        stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    }
+
+    Err(anyhow!(
+        "{} could not read {}: {}",
+        ffmpeg,
+        video.display(),
+        stderr
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcode(name: &str, args: &[&str]) -> Shortcode {
+        Shortcode {
+            name: name.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_links() {
+        let expected = Video::YouTube {
+            id: "dQw4w9WgXcQ".to_string(),
+            start: None,
+        };
+        for link in [
+            "dQw4w9WgXcQ",
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=x",
+            "https://youtu.be/dQw4w9WgXcQ",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+        ] {
+            assert_eq!(Video::youtube(link).unwrap(), expected);
+        }
+        assert_eq!(
+            Video::parse("https://youtu.be/dQw4w9WgXcQ?t=42").unwrap(),
+            Video::YouTube {
+                id: "dQw4w9WgXcQ".to_string(),
+                start: Some(42)
+            }
+        );
+        assert_eq!(
+            Video::parse("https://vimeo.com/76979871").unwrap(),
+            Video::Vimeo {
+                id: "76979871".to_string()
+            }
+        );
+        assert!(Video::youtube("not-an-id").is_err());
+        assert!(Video::vimeo("https://vimeo.com/channels/staffpicks").is_err());
+
+        let file = Video::parse("/static/clip.mp4").unwrap();
+        assert_eq!(file.local_file(), Some("static/clip.mp4"));
+        assert_eq!(
+            Video::parse("https://cdn.example.com/clip.mp4")
+                .unwrap()
+                .local_file(),
+            None
+        );
+        assert_eq!(poster_path("static/clip.mp4"), "static/clip.poster.jpg");
+    }
+
+    #[test]
+    fn test_embeds() {
+        let url = |path: &str| format!("/{}", path);
+        let mut config = VideoConfig::default();
+
+        let youtube = shortcode("youtube", &["dQw4w9WgXcQ", "start=30", "title=A song"]);
+        let video = Video::from_shortcode(&youtube).unwrap();
+        let options = VideoOptions::from_shortcode(&youtube);
+        let html = embed_html(&video, &options, &config, url);
+        assert!(
+            html.contains("src=\"https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ?start=30\"")
+        );
+        assert!(html.contains("loading=\"lazy\""));
+        assert!(!html.contains('\n'));
+
+        let vimeo = Video::vimeo("76979871").unwrap();
+        let html = embed_html(&vimeo, &VideoOptions::default(), &config, url);
+        assert!(html.contains("https://player.vimeo.com/video/76979871?dnt=1"));
+
+        config.click_to_load = true;
+        let html = embed_html(&vimeo, &VideoOptions::default(), &config, url);
+        assert!(!html.contains("<iframe"));
+        assert!(html.contains("data-blogr-video=\"https://player.vimeo.com/video/76979871?dnt=1\""));
+        assert!(html.contains("<script src=\"/js/video.js\" defer>"));
+
+        let clip = shortcode(
+            "video",
+            &[
+                "static/clip.webm",
+                "poster=static/clip.jpg",
+                "autoplay",
+                "loop",
+            ],
+        );
+        let html = embed_html(
+            &Video::from_shortcode(&clip).unwrap(),
+            &VideoOptions::from_shortcode(&clip),
+            &config,
+            url,
+        );
+        assert!(html.starts_with("<video class=\"blogr-video\" controls playsinline preload=\"metadata\" poster=\"/static/clip.jpg\" autoplay loop muted>"));
+        assert!(html.contains("<source src=\"/static/clip.webm\" type=\"video/webm\">"));
+    }
+}
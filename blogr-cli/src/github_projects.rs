@@ -0,0 +1,372 @@
+//! Projects showcase fetched from GitHub
+//!
+//! Builds ask GitHub for the configured user's pinned or starred repositories
+//! and keep the answer in `.blogr/github-projects.json` for `cache_hours`, so
+//! repeated builds (and builds without network access) reuse the last result.
+//! Templates get the cached repositories as `github_projects`.
+
+use crate::config::{EnvConfig, GitHubProjectsConfig};
+use crate::utils::Console;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// GitHub returns at most six pinned items
+const MAX_PINNED: usize = 6;
+
+const PINNED_QUERY: &str = "query($login: String!, $count: Int!) {
+  user(login: $login) {
+    pinnedItems(first: $count, types: REPOSITORY) {
+      nodes {
+        ... on Repository {
+          name
+          nameWithOwner
+          description
+          url
+          homepageUrl
+          stargazerCount
+          forkCount
+          primaryLanguage { name }
+          repositoryTopics(first: 10) { nodes { topic { name } } }
+          updatedAt
+        }
+      }
+    }
+  }
+}";
+
+/// A repository as templates see it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitHubProject {
+    pub name: String,
+    /// `owner/name`
+    pub full_name: String,
+    pub description: Option<String>,
+    pub url: String,
+    /// Project website, when the repository has one
+    pub homepage: Option<String>,
+    pub language: Option<String>,
+    pub stars: u64,
+    pub forks: u64,
+    pub topics: Vec<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// The last fetch, from `.blogr/github-projects.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectsCache {
+    pub user: String,
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+    pub projects: Vec<GitHubProject>,
+}
+
+impl ProjectsCache {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".blogr").join("github-projects.json")
+    }
+
+    /// The cached fetch, if there is a readable one
+    pub fn load(project_root: &Path) -> Option<Self> {
+        let content = fs::read_to_string(Self::path(project_root)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Whether the cache was fetched for the current settings
+    fn matches(&self, config: &GitHubProjectsConfig) -> bool {
+        config
+            .user
+            .as_deref()
+            .is_some_and(|user| user.eq_ignore_ascii_case(&self.user))
+            && self.source == config.source
+    }
+
+    fn is_fresh(&self, config: &GitHubProjectsConfig, now: DateTime<Utc>) -> bool {
+        let max_age = Duration::hours(i64::try_from(config.cache_hours).unwrap_or(i64::MAX));
+        self.matches(config) && now.signed_duration_since(self.fetched_at) < max_age
+    }
+}
+
+/// Fetch the showcase again unless the cache is still fresh. A failed fetch
+/// keeps the previous cache, so it is reported but never fails a build.
+pub async fn refresh(project_root: &Path, config: &GitHubProjectsConfig) {
+    if let Err(e) = try_refresh(project_root, config).await {
+        Console::warn(&format!("Could not fetch GitHub projects: {}", e));
+    }
+}
+
+async fn try_refresh(project_root: &Path, config: &GitHubProjectsConfig) -> Result<()> {
+    let Some(user) = config.user.as_deref() else {
+        return Ok(());
+    };
+    if ProjectsCache::load(project_root).is_some_and(|cache| cache.is_fresh(config, Utc::now())) {
+        return Ok(());
+    }
+
+    let projects = fetch(user, &config.source).await?;
+    crate::status!(
+        "🐙 Fetched {} {} GitHub repositories for {}",
+        projects.len(),
+        config.source,
+        user
+    );
+    ProjectsCache {
+        user: user.to_string(),
+        source: config.source.clone(),
+        fetched_at: Utc::now(),
+        projects,
+    }
+    .save(project_root)
+}
+
+/// Repositories for the `github_projects` template variable
+pub fn load_projects(project_root: &Path, config: &GitHubProjectsConfig) -> Vec<GitHubProject> {
+    match ProjectsCache::load(project_root) {
+        Some(cache) if cache.matches(config) => {
+            cache.projects.into_iter().take(config.limit).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+async fn fetch(user: &str, source: &str) -> Result<Vec<GitHubProject>> {
+    let token = EnvConfig::github_token();
+    let client = reqwest::Client::builder()
+        .user_agent("blogr-cli")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+
+    let request = match source {
+        "pinned" => {
+            let token = token.ok_or_else(|| {
+                anyhow!("Pinned repositories need a GitHub token. Set GITHUB_TOKEN or use source = \"starred\"")
+            })?;
+            client
+                .post("https://api.github.com/graphql")
+                .bearer_auth(token)
+                .json(&json!({
+                    "query": PINNED_QUERY,
+                    "variables": { "login": user, "count": MAX_PINNED },
+                }))
+        }
+        "starred" => {
+            // Fetch a full page; `limit` is applied when the cache is read
+            let request = client
+                .get(format!(
+                    "https://api.github.com/users/{}/starred?per_page=100",
+                    user
+                ))
+                .header("Accept", "application/vnd.github+json");
+            match token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            }
+        }
+        other => bail!("Unknown github_projects.source '{}'", other),
+    };
+
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("GitHub API error ({status}): {body}");
+    }
+    let body: Value = response.json().await?;
+
+    if source == "pinned" {
+        parse_pinned(&body)
+    } else {
+        parse_starred(&body)
+    }
+}
+
+/// Read the repositories from a GraphQL `pinnedItems` response
+fn parse_pinned(body: &Value) -> Result<Vec<GitHubProject>> {
+    if let Some(message) = body["errors"][0]["message"].as_str() {
+        bail!("GitHub API error: {}", message);
+    }
+    let nodes = body["data"]["user"]["pinnedItems"]["nodes"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Unexpected response from GitHub"))?;
+
+    Ok(nodes
+        .iter()
+        .filter_map(|repo| {
+            Some(GitHubProject {
+                name: repo["name"].as_str()?.to_string(),
+                full_name: repo["nameWithOwner"].as_str()?.to_string(),
+                description: non_empty(&repo["description"]),
+                url: repo["url"].as_str()?.to_string(),
+                homepage: non_empty(&repo["homepageUrl"]),
+                language: non_empty(&repo["primaryLanguage"]["name"]),
+                stars: repo["stargazerCount"].as_u64().unwrap_or(0),
+                forks: repo["forkCount"].as_u64().unwrap_or(0),
+                topics: repo["repositoryTopics"]["nodes"]
+                    .as_array()
+                    .map(|topics| {
+                        topics
+                            .iter()
+                            .filter_map(|topic| topic["topic"]["name"].as_str())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                updated_at: repo["updatedAt"].as_str().and_then(|d| d.parse().ok()),
+            })
+        })
+        .collect())
+}
+
+/// Read the repositories from a REST `/users/{user}/starred` response
+fn parse_starred(body: &Value) -> Result<Vec<GitHubProject>> {
+    let repos = body
+        .as_array()
+        .ok_or_else(|| anyhow!("Unexpected response from GitHub"))?;
+
+    Ok(repos
+        .iter()
+        .filter_map(|repo| {
+            Some(GitHubProject {
+                name: repo["name"].as_str()?.to_string(),
+                full_name: repo["full_name"].as_str()?.to_string(),
+                description: non_empty(&repo["description"]),
+                url: repo["html_url"].as_str()?.to_string(),
+                homepage: non_empty(&repo["homepage"]),
+                language: non_empty(&repo["language"]),
+                stars: repo["stargazers_count"].as_u64().unwrap_or(0),
+                forks: repo["forks_count"].as_u64().unwrap_or(0),
+                topics: repo["topics"]
+                    .as_array()
+                    .map(|topics| {
+                        topics
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                updated_at: repo["pushed_at"].as_str().and_then(|d| d.parse().ok()),
+            })
+        })
+        .collect())
+}
+
+/// GitHub sends empty strings as well as nulls for unset fields
+fn non_empty(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .filter(|s| !s.trim().is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(user: &str, source: &str) -> GitHubProjectsConfig {
+        GitHubProjectsConfig {
+            user: Some(user.to_string()),
+            source: source.to_string(),
+            ..GitHubProjectsConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_pinned() {
+        let body = json!({"data": {"user": {"pinnedItems": {"nodes": [{
+            "name": "blogr",
+            "nameWithOwner": "bcorey/blogr",
+            "description": "A blog generator",
+            "url": "https://github.com/bcorey/blogr",
+            "homepageUrl": "",
+            "stargazerCount": 42,
+            "forkCount": 3,
+            "primaryLanguage": {"name": "Rust"},
+            "repositoryTopics": {"nodes": [{"topic": {"name": "ssg"}}]},
+            "updatedAt": "2025-01-02T03:04:05Z"
+        }]}}}});
+        let projects = parse_pinned(&body).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].full_name, "bcorey/blogr");
+        assert_eq!(projects[0].homepage, None);
+        assert_eq!(projects[0].language.as_deref(), Some("Rust"));
+        assert_eq!(projects[0].topics, ["ssg"]);
+        assert_eq!(projects[0].stars, 42);
+
+        let error = json!({"errors": [{"message": "Could not resolve to a User"}]});
+        assert!(parse_pinned(&error).is_err());
+    }
+
+    #[test]
+    fn test_parse_starred() {
+        let body = json!([{
+            "name": "tera",
+            "full_name": "Keats/tera",
+            "description": null,
+            "html_url": "https://github.com/Keats/tera",
+            "homepage": "https://keats.github.io/tera/",
+            "language": "Rust",
+            "stargazers_count": 3000,
+            "forks_count": 300,
+            "topics": ["templates"],
+            "pushed_at": "2025-01-02T03:04:05Z"
+        }]);
+        let projects = parse_starred(&body).unwrap();
+        assert_eq!(projects[0].name, "tera");
+        assert_eq!(projects[0].description, None);
+        assert_eq!(
+            projects[0].homepage.as_deref(),
+            Some("https://keats.github.io/tera/")
+        );
+    }
+
+    #[test]
+    fn test_cache_follows_settings() {
+        let dir = TempDir::new().unwrap();
+        let project = |name: &str| GitHubProject {
+            name: name.to_string(),
+            full_name: format!("octocat/{}", name),
+            description: None,
+            url: format!("https://github.com/octocat/{}", name),
+            homepage: None,
+            language: None,
+            stars: 0,
+            forks: 0,
+            topics: Vec::new(),
+            updated_at: None,
+        };
+        let fetched_at = Utc::now() - Duration::hours(2);
+        ProjectsCache {
+            user: "octocat".to_string(),
+            source: "starred".to_string(),
+            fetched_at,
+            projects: vec![project("one"), project("two")],
+        }
+        .save(dir.path())
+        .unwrap();
+
+        let mut starred = config("Octocat", "starred");
+        starred.limit = 1;
+        assert_eq!(load_projects(dir.path(), &starred).len(), 1);
+        assert!(load_projects(dir.path(), &config("octocat", "pinned")).is_empty());
+        assert!(load_projects(dir.path(), &config("someone", "starred")).is_empty());
+
+        let cache = ProjectsCache::load(dir.path()).unwrap();
+        assert!(cache.is_fresh(&starred, Utc::now()));
+        starred.cache_hours = 1;
+        assert!(!cache.is_fresh(&starred, Utc::now()));
+    }
+}
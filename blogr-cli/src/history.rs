@@ -0,0 +1,204 @@
+//! Local version history for posts
+//!
+//! Every save from the editor or TUI stores a copy of the post file in
+//! `.blogr/history/<slug>/`, named `<version>-<timestamp>.md`. Identical
+//! consecutive saves are stored once and only the newest versions are kept.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Versions kept per post; older ones are removed as new ones are saved
+const MAX_VERSIONS: usize = 50;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// A stored copy of a post
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub number: u32,
+    pub saved_at: DateTime<Utc>,
+    pub path: PathBuf,
+}
+
+impl Version {
+    pub fn read(&self) -> Result<String> {
+        fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))
+    }
+}
+
+/// History store for one project
+#[derive(Debug, Clone)]
+pub struct PostHistory {
+    dir: PathBuf,
+}
+
+impl PostHistory {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            dir: project_root.join(".blogr").join("history"),
+        }
+    }
+
+    /// Versions of a post, oldest first
+    pub fn versions(&self, slug: &str) -> Result<Vec<Version>> {
+        let dir = self.dir.join(slug);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let Some((number, timestamp)) = stem.split_once('-') else {
+                continue;
+            };
+            let (Ok(number), Ok(saved_at)) = (
+                number.parse(),
+                NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT),
+            ) else {
+                continue;
+            };
+            versions.push(Version {
+                number,
+                saved_at: saved_at.and_utc(),
+                path,
+            });
+        }
+        versions.sort_by_key(|version| version.number);
+        Ok(versions)
+    }
+
+    pub fn version(&self, slug: &str, number: u32) -> Result<Version> {
+        self.versions(slug)?
+            .into_iter()
+            .find(|version| version.number == number)
+            .ok_or_else(|| anyhow!("Post '{}' has no version {}", slug, number))
+    }
+
+    /// Store `content` as a new version, unless it matches the latest one.
+    /// Returns the new version number.
+    pub fn snapshot(&self, slug: &str, content: &str) -> Result<Option<u32>> {
+        let versions = self.versions(slug)?;
+        if let Some(latest) = versions.last() {
+            if latest.read()? == content {
+                return Ok(None);
+            }
+        }
+
+        let number = versions.last().map_or(1, |latest| latest.number + 1);
+        let dir = self.dir.join(slug);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "{}-{}.md",
+            number,
+            Utc::now().format(TIMESTAMP_FORMAT)
+        ));
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+
+        if versions.len() >= MAX_VERSIONS {
+            for old in &versions[..=versions.len() - MAX_VERSIONS] {
+                fs::remove_file(&old.path)?;
+            }
+        }
+        Ok(Some(number))
+    }
+
+    /// Snapshot the post file at `path`, if it exists
+    pub fn snapshot_file(&self, slug: &str, path: &Path) -> Result<Option<u32>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.snapshot(slug, &content)
+    }
+}
+
+/// One line of a diff
+#[derive(Debug, PartialEq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff from the longest common subsequence
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lengths[i][j]: common lines between old[i..] and new[j..]
+    let mut lengths = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = PostHistory::new(temp_dir.path());
+
+        assert_eq!(history.snapshot("hello", "one").unwrap(), Some(1));
+        assert_eq!(history.snapshot("hello", "one").unwrap(), None);
+        assert_eq!(history.snapshot("hello", "two").unwrap(), Some(2));
+        assert_eq!(history.version("hello", 1).unwrap().read().unwrap(), "one");
+        assert!(history.version("hello", 3).is_err());
+
+        for i in 0..MAX_VERSIONS {
+            history.snapshot("hello", &i.to_string()).unwrap();
+        }
+        let versions = history.versions("hello").unwrap();
+        assert_eq!(versions.len(), MAX_VERSIONS);
+        assert_eq!(versions[0].number, 3);
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(
+            diff_lines("a\nb\nc\n", "a\nc\nd\n"),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Console and log file output
+//!
+//! Status messages (`status!`, `Console::success` and friends) are `tracing`
+//! events at info level, printed as-is on stdout, or on stderr with `--json`.
+//! Warnings and errors go to stderr with an icon. `-v` adds debug events,
+//! such as how long each build phase took, and `-vv` trace events;
+//! `--quiet` keeps only warnings and errors. `--log-file` appends
+//! everything, with timestamps, to a file.
+
+use crate::build_profile::ProfileLayer;
+use crate::progress;
+use crate::utils::Console;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::writer::{EitherWriter, MakeWriter};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Events with this target are written to the log file only
+pub const LOG_FILE_ONLY: &str = "blogr::log_file";
+
+/// Set up console and log file output, and recording spans for
+/// `--profile-build`. `verbose` is the number of `-v` flags.
+pub fn init(verbose: u8, quiet: bool, log_file: Option<&Path>, profile: bool) -> Result<()> {
+    let (ours, theirs) = match (quiet, verbose) {
+        (true, _) => (LevelFilter::WARN, LevelFilter::WARN),
+        (false, 0) => (LevelFilter::INFO, LevelFilter::WARN),
+        (false, 1) => (LevelFilter::DEBUG, LevelFilter::WARN),
+        (false, _) => (LevelFilter::TRACE, LevelFilter::DEBUG),
+    };
+    let console = tracing_subscriber::fmt::layer()
+        .event_format(ConsoleFormat)
+        .with_writer(ConsoleWriter)
+        .with_ansi(io::stderr().is_terminal())
+        .with_filter(
+            Targets::new()
+                .with_target("blogr", ours)
+                .with_target(LOG_FILE_ONLY, LevelFilter::OFF)
+                .with_default(theirs),
+        );
+
+    let file = match log_file {
+        Some(path) => {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            writeln!(
+                file,
+                "\n=== {} {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                std::env::args().collect::<Vec<_>>().join(" ")
+            )?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(file))
+                    .with_filter(
+                        Targets::new()
+                            .with_target("blogr", LevelFilter::TRACE)
+                            .with_default(LevelFilter::DEBUG),
+                    ),
+            )
+        }
+        None => None,
+    };
+
+    let profile = profile.then(|| {
+        ProfileLayer::new().with_filter(Targets::new().with_target("blogr", LevelFilter::TRACE))
+    });
+
+    tracing_subscriber::registry()
+        .with(console)
+        .with(file)
+        .with(profile)
+        .try_init()
+        .context("Failed to set up logging")
+}
+
+/// Run one phase of a longer task, logging `<done> in <n>ms` at debug level
+/// once it finishes
+pub fn timed<T>(done: &str, phase: impl FnOnce() -> Result<T>) -> Result<T> {
+    let started = Instant::now();
+    let result = tracing::debug_span!("phase", done).in_scope(phase);
+    if result.is_ok() {
+        tracing::debug!("{} in {}ms", done, started.elapsed().as_millis());
+    }
+    result
+}
+
+/// Info events as plain lines, warnings and errors with an icon, and debug
+/// and trace events with their level (and the crate, for dependencies)
+struct ConsoleFormat;
+
+impl<S, N> FormatEvent<S, N> for ConsoleFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let dim = writer.has_ansi_escapes() && *metadata.level() > Level::INFO;
+        match *metadata.level() {
+            Level::ERROR => write!(writer, "❌ ")?,
+            Level::WARN => write!(writer, "⚠️  ")?,
+            Level::INFO => {}
+            level => {
+                if dim {
+                    write!(writer, "\x1b[2m")?;
+                }
+                write!(writer, "{:>5} ", level)?;
+                if !metadata.target().starts_with("blogr") {
+                    write!(writer, "{}: ", metadata.target())?;
+                }
+            }
+        }
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        if dim {
+            write!(writer, "\x1b[0m")?;
+        }
+        writeln!(writer)
+    }
+}
+
+/// Info events go to stdout, unless it carries a JSON report; everything
+/// else goes to stderr
+struct ConsoleWriter;
+
+impl<'a> MakeWriter<'a> for ConsoleWriter {
+    type Writer = AboveProgress<EitherWriter<io::Stdout, io::Stderr>>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        AboveProgress(EitherWriter::A(io::stdout()))
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        if *meta.level() == Level::INFO && !Console::is_json() {
+            AboveProgress(EitherWriter::A(io::stdout()))
+        } else {
+            AboveProgress(EitherWriter::B(io::stderr()))
+        }
+    }
+}
+
+/// Clears progress bars while writing, so lines don't run into them
+struct AboveProgress<W>(W);
+
+impl<W: Write> Write for AboveProgress<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        progress::suspend(|| self.0.write(buf))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        progress::suspend(|| self.0.write_all(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
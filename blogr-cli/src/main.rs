@@ -1,16 +1,40 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use utils::Console;
 
+mod activitypub;
+mod announce;
+mod build_cache;
+mod build_profile;
 mod commands;
 mod config;
+mod config_path;
 mod content;
+mod crosspost;
+mod domain_check;
+mod draft_sync;
+mod fonts;
 mod generator;
+mod github_projects;
+mod history;
+mod logging;
 mod newsletter;
+mod progress;
 mod project;
+mod prose;
+mod query;
+mod s3;
+mod seo;
+mod starter;
+mod theme_package;
+mod trash;
 mod tui;
 mod tui_launcher;
 mod utils;
+mod watcher;
+mod webmention;
+mod workspace;
 
 use commands::*;
 
@@ -19,6 +43,21 @@ use commands::*;
 #[command(about = "A CLI static site generator for blogs")]
 #[command(version)]
 struct Cli {
+    /// Run in this project: a directory, or the name of a project in the workspace
+    #[arg(long, global = true, value_name = "DIR|NAME")]
+    project: Option<String>,
+    /// Print a JSON report on stdout instead of human-readable output
+    #[arg(long, global = true)]
+    json: bool,
+    /// Show more detail: -v for timings and debug messages, -vv for tracing
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+    /// Only show warnings and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    /// Also append detailed, timestamped logs to this file
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<PathBuf>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -44,6 +83,12 @@ enum Commands {
         /// Initialize as a personal website (no blog posts)
         #[arg(long)]
         personal: bool,
+        /// Initialize as a personal website with a blog under /blog/
+        #[arg(long, conflicts_with = "personal")]
+        hybrid: bool,
+        /// Start from a starter kit with its own theme, sample content and menu
+        #[arg(long, value_enum, conflicts_with_all = ["personal", "hybrid"])]
+        template: Option<starter::StarterKit>,
     },
     /// Create a new blog post
     New {
@@ -61,6 +106,9 @@ enum Commands {
         /// Post tags (comma-separated)
         #[arg(long)]
         tags: Option<String>,
+        /// Create the post as a folder (`posts/<slug>/index.md`) to keep its images next to it
+        #[arg(long)]
+        bundle: bool,
         /// Open in TUI editor instead of external editor
         #[arg(long)]
         tui: bool,
@@ -73,6 +121,9 @@ enum Commands {
         /// Show only published posts
         #[arg(long)]
         published: bool,
+        /// Show only posts with this status (draft, in-review, scheduled, published, archived)
+        #[arg(long, conflicts_with_all = ["drafts", "published"])]
+        status: Option<content::PostStatus>,
         /// Filter by tag
         #[arg(short, long)]
         tag: Option<String>,
@@ -88,13 +139,38 @@ enum Commands {
         #[arg(long)]
         tui: bool,
     },
-    /// Delete a blog post
+    /// Delete a blog post (moves it to the trash)
     Delete {
         /// Post slug to delete
-        slug: String,
+        #[arg(required_unless_present = "purge")]
+        slug: Option<String>,
         /// Skip confirmation prompt
         #[arg(short, long)]
         force: bool,
+        /// Delete permanently instead of moving to the trash; without a slug, empty the trash
+        #[arg(long)]
+        purge: bool,
+    },
+    /// Publish a post now, or schedule it for a later time
+    Publish {
+        /// Post slug to publish
+        slug: String,
+        /// Publish date in local time ("2024-06-01 09:00") or RFC 3339; a future date schedules the post
+        #[arg(long, conflicts_with = "keep_date")]
+        at: Option<String>,
+        /// Keep the post's current date instead of setting it to now
+        #[arg(long)]
+        keep_date: bool,
+    },
+    /// Restore a deleted post from the trash, or list the trash
+    Restore {
+        /// Post slug to restore
+        slug: Option<String>,
+    },
+    /// Post history commands
+    Post {
+        #[command(subcommand)]
+        action: PostAction,
     },
     /// Build the static site
     Build {
@@ -107,6 +183,12 @@ enum Commands {
         /// Include future-dated posts
         #[arg(long)]
         future: bool,
+        /// Build profile; `preview` keeps search engines out (see [robots.profiles])
+        #[arg(long)]
+        profile: Option<String>,
+        /// Report time per phase and the slowest pages, and write a trace to .blogr/build-profile.json
+        #[arg(long)]
+        profile_build: bool,
     },
     /// Start development server with live reload
     Serve {
@@ -122,15 +204,62 @@ enum Commands {
         /// Open browser automatically
         #[arg(long)]
         open: bool,
+        /// Expose endpoints that render unsaved markdown for editor previews
+        #[arg(long)]
+        editor_api: bool,
+        /// Answer queries about posts, tags and pages at /api/query, for dashboards and tooling
+        #[arg(long)]
+        query_api: bool,
+        /// Keep the built site in memory instead of writing it to the output directory
+        #[arg(long)]
+        in_memory: bool,
+    },
+    /// Time builds of a synthetic site with many posts
+    #[command(hide = true)]
+    Bench {
+        /// Number of generated posts
+        #[arg(long, default_value = "1000")]
+        posts: usize,
+        /// Runs per benchmark; the median is reported
+        #[arg(long, default_value = "3")]
+        runs: usize,
+        /// Generate the site in this directory and keep it
+        #[arg(long, value_name = "DIR")]
+        fixture: Option<PathBuf>,
+        /// A `blogr bench --json` report to compare against
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+    },
+    /// Build cache commands
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Check posts for problems
+    Check {
+        #[command(subcommand)]
+        action: CheckAction,
+    },
+    /// Diagnose common environment and configuration problems
+    Doctor {
+        /// Skip checks that need network access (GitHub, SMTP/IMAP)
+        #[arg(long)]
+        offline: bool,
     },
     /// Deploy the site to GitHub Pages
     Deploy {
-        /// Deploy branch (default: gh-pages)
-        #[arg(short, long, default_value = "gh-pages")]
-        branch: String,
+        /// Deploy branch (default: gh-pages, or deploy.preview.branch with --preview)
+        #[arg(short, long)]
+        branch: Option<String>,
         /// Deployment message
         #[arg(short, long)]
         message: Option<String>,
+        /// Deploy a review copy with drafts and future posts, hidden from search engines
+        #[arg(long, conflicts_with = "all")]
+        preview: bool,
+        /// Deploy to every target in [[deploy.targets]]
+        #[arg(long, conflicts_with = "branch")]
+        all: bool,
     },
     /// Theme management commands
     Theme {
@@ -142,6 +271,11 @@ enum Commands {
         #[command(subcommand)]
         action: ProjectAction,
     },
+    /// Work with several projects in one checkout (see blogr-workspace.toml)
+    Projects {
+        #[command(subcommand)]
+        action: ProjectsAction,
+    },
     /// Configuration management commands
     Config {
         #[command(subcommand)]
@@ -152,6 +286,129 @@ enum Commands {
         #[command(subcommand)]
         action: NewsletterAction,
     },
+    /// Publish a copy of a post on dev.to, Hashnode or Medium
+    Crosspost {
+        /// Post slug
+        slug: String,
+        /// Platforms (comma-separated: devto, hashnode, medium)
+        #[arg(long)]
+        to: String,
+        /// Create the copies as drafts (dev.to and Medium)
+        #[arg(long)]
+        draft: bool,
+    },
+    /// Announce a post on Mastodon, Bluesky or X
+    Announce {
+        /// Post slug
+        slug: String,
+        /// Networks (comma-separated: mastodon, bluesky, x); defaults to announce.networks
+        #[arg(long)]
+        to: Option<String>,
+        /// Print the announcements without posting them
+        #[arg(long)]
+        dry_run: bool,
+        /// Post again on networks the post was already announced on
+        #[arg(long)]
+        force: bool,
+    },
+    /// Webmention commands
+    Webmention {
+        #[command(subcommand)]
+        action: WebmentionAction,
+    },
+    /// ActivityPub (Fediverse) commands
+    #[command(name = "activitypub")]
+    ActivityPub {
+        #[command(subcommand)]
+        action: ActivityPubAction,
+    },
+    /// Draft commands
+    Drafts {
+        #[command(subcommand)]
+        action: DraftsAction,
+    },
+    /// Print a shell completion script (bash, zsh, fish, elvish, powershell)
+    Completions { shell: clap_complete::Shell },
+    /// Print the man page for blogr or one of its commands
+    Man {
+        /// Command to show, e.g. `newsletter plugin run`
+        command: Vec<String>,
+        /// Write pages for every command into this directory instead
+        #[arg(long, value_name = "DIR", conflicts_with = "command")]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Show the cache location and size
+    Stats,
+    /// Remove cached artifacts
+    Clear {
+        /// Only remove this kind of artifact (e.g. markdown)
+        #[arg(long)]
+        kind: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CheckAction {
+    /// Spell-check posts and flag passive voice, long sentences and repeated words
+    Prose {
+        /// Only check this post
+        slug: Option<String>,
+        /// Skip spelling and run the style rules only
+        #[arg(long)]
+        no_spelling: bool,
+    },
+    /// Audit the built site for titles, descriptions, canonical links, alt text and orphan pages
+    Seo {
+        /// Fail if the score is below this (0-100)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(0..=100))]
+        min_score: Option<u32>,
+    },
+    /// Compare a sample of the live site with the last build's integrity manifest
+    Deploy {
+        /// How many files to fetch (default: deploy.verify.sample)
+        #[arg(long)]
+        sample: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PostAction {
+    /// List saved versions of a post, or show what changed since one
+    History {
+        /// Post slug
+        slug: String,
+        /// Show the changes between this version and the current post
+        #[arg(long)]
+        version: Option<u32>,
+    },
+    /// Restore a saved version of a post
+    Restore {
+        /// Post slug
+        slug: String,
+        /// Version number from 'blogr post history'
+        #[arg(long)]
+        version: u32,
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DraftsAction {
+    /// Sync drafts with a private branch or gist so they can be edited on other machines
+    Sync {
+        /// Only download changes
+        #[arg(long, conflicts_with = "push")]
+        pull: bool,
+        /// Only upload changes
+        #[arg(long)]
+        push: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -173,6 +430,68 @@ enum ThemeAction {
         /// Theme name
         name: String,
     },
+    /// Install a theme package from a git repository or local path
+    Install {
+        /// Git URL or path to a directory with a theme.toml
+        source: String,
+        /// Tag, branch or commit to install (git sources only)
+        #[arg(long)]
+        rev: Option<String>,
+        /// Install into the workspace's shared themes_dir for every project
+        #[arg(long)]
+        shared: bool,
+    },
+    /// Reinstall installed themes from their source
+    Update {
+        /// Installed theme to update (defaults to all)
+        name: Option<String>,
+        /// Move the pin to another tag, branch or commit
+        #[arg(long, requires = "name")]
+        rev: Option<String>,
+    },
+    /// Render a theme against a sample site and report problems
+    Lint {
+        /// Theme name (defaults to the project's theme, or all themes outside a project)
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ActivityPubAction {
+    /// List followers that receive new posts
+    Followers,
+    /// Add a follower by handle (@user@host) or actor URL
+    AddFollower {
+        /// Fediverse account
+        account: String,
+    },
+    /// Stop sending posts to a follower
+    RemoveFollower {
+        /// Fediverse account
+        account: String,
+    },
+    /// Send posts that haven't been announced yet to followers
+    Announce {
+        /// Send this post, even if it was announced before
+        #[arg(long)]
+        post: Option<String>,
+        /// Show what would be sent without sending
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum WebmentionAction {
+    /// Notify sites linked from published posts
+    Send {
+        /// Only send for this post
+        #[arg(long)]
+        post: Option<String>,
+        /// List the links that would be notified without sending
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -183,17 +502,23 @@ enum ProjectAction {
     Check,
     /// Clean build artifacts
     Clean,
-    /// Show project statistics
+    /// Show content statistics
     Stats,
 }
 
+#[derive(Subcommand)]
+enum ProjectsAction {
+    /// List the projects in this workspace or below the current directory
+    List,
+}
+
 #[derive(Subcommand)]
 enum ConfigAction {
     /// Open interactive configuration editor (TUI)
     Edit,
     /// Get configuration value
     Get {
-        /// Configuration key (e.g., blog.title, domains.primary)
+        /// Configuration key (e.g., blog.title, theme.config.show_avatar, menu[0].url)
         key: String,
     },
     /// Set configuration value
@@ -203,6 +528,16 @@ enum ConfigAction {
         /// Configuration value
         value: String,
     },
+    /// Remove a setting from blogr.toml so its default applies
+    Unset {
+        /// Configuration key
+        key: String,
+    },
+    /// List every setting, with whether it comes from blogr.toml or is a default
+    List {
+        /// Only settings under this key (e.g., newsletter)
+        prefix: Option<String>,
+    },
     /// Domain configuration commands
     Domain {
         #[command(subcommand)]
@@ -225,6 +560,12 @@ enum DomainAction {
         /// Create CNAME file for GitHub Pages
         #[arg(long)]
         github_pages: bool,
+        /// Check the domain's DNS records and HTTPS certificate for GitHub Pages
+        #[arg(long)]
+        verify: bool,
+        /// With --verify, how long to wait for GitHub to issue the certificate
+        #[arg(long, value_name = "SECONDS", default_value = "0", requires = "verify")]
+        wait: u64,
     },
     /// List all configured domains
     List,
@@ -249,9 +590,22 @@ enum NewsletterAction {
         /// Interactive IMAP server configuration
         #[arg(long)]
         interactive: bool,
+        /// Only read emails received on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
     },
+    /// Sign IMAP and SMTP accounts in with OAuth2 (see [newsletter.oauth])
+    Login,
+    /// Remove stored OAuth2 sign-ins from the OS keychain
+    Logout,
     /// Launch approval UI for managing subscriber requests
     Approve,
+    /// Write a newsletter in a TUI with live preview, then send or schedule it
+    Compose {
+        /// Email template to use (see 'blogr newsletter templates')
+        #[arg(long)]
+        template: Option<String>,
+    },
     /// List all subscribers
     List {
         /// Filter by status (pending, approved, declined)
@@ -266,6 +620,34 @@ enum NewsletterAction {
         #[arg(short, long)]
         force: bool,
     },
+    /// Add tags to a subscriber
+    Tag {
+        /// Subscriber email address
+        email: String,
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from a subscriber
+    Untag {
+        /// Subscriber email address
+        email: String,
+        /// Tags to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Find duplicate, invalid and role addresses in the subscriber list
+    Clean {
+        /// Treat Gmail addresses that differ only by dots or a +tag as duplicates
+        #[arg(long)]
+        gmail_aliases: bool,
+        /// Offer to fix each issue (remove, rename or decline)
+        #[arg(long)]
+        fix: bool,
+        /// Apply every fix without asking (with --fix)
+        #[arg(short, long, requires = "fix")]
+        yes: bool,
+    },
     /// Export subscribers to CSV or JSON
     Export {
         /// Output format (csv, json)
@@ -277,12 +659,54 @@ enum NewsletterAction {
         /// Filter by status (pending, approved, declined)
         #[arg(long)]
         status: Option<String>,
+        /// Only export subscribers in a segment (e.g. tag:rust); repeat to combine
+        #[arg(long)]
+        segment: Vec<String>,
     },
     /// Send newsletter with latest blog post
     SendLatest {
         /// Interactive confirmation before sending
         #[arg(long)]
         interactive: bool,
+        /// Schedule the send instead of sending now ("YYYY-MM-DD HH:MM", local time)
+        #[arg(long)]
+        at: Option<String>,
+        /// Only send to subscribers in a segment (e.g. tag:rust); repeat to combine
+        #[arg(long)]
+        segment: Vec<String>,
+        /// Email template to use (see 'blogr newsletter templates')
+        #[arg(long)]
+        template: Option<String>,
+        /// Second subject line: A/B test it against the first one
+        #[arg(long)]
+        subject_b: Option<String>,
+        /// Percentage of subscribers in the A/B test (default: 20)
+        #[arg(long, requires = "subject_b")]
+        ab_percent: Option<u8>,
+        /// Hours to wait before sending the winning subject to everyone else (default: 4)
+        #[arg(long, requires = "subject_b")]
+        ab_wait: Option<u64>,
+    },
+    /// Send a digest of every post published since a date or the last send
+    SendDigest {
+        /// Start of the digest: "YYYY-MM-DD", "YYYY-MM-DD HH:MM" (local time) or last-send
+        #[arg(long, default_value = "last-send")]
+        since: String,
+        /// Subject line (default: "<newsletter title>: N new posts")
+        #[arg(long)]
+        subject: Option<String>,
+        /// Interactive confirmation before sending
+        #[arg(long)]
+        interactive: bool,
+        /// Schedule the send instead of sending now ("YYYY-MM-DD HH:MM", local time)
+        #[arg(long)]
+        at: Option<String>,
+        /// Only send to subscribers in a segment (e.g. tag:rust); repeat to combine
+        #[arg(long)]
+        segment: Vec<String>,
+        /// Email template to use (default: newsletter.digest_template, or the digest layout)
+        #[arg(long)]
+        template: Option<String>,
     },
     /// Send custom newsletter
     SendCustom {
@@ -293,16 +717,102 @@ enum NewsletterAction {
         /// Interactive confirmation before sending
         #[arg(long)]
         interactive: bool,
+        /// Schedule the send instead of sending now ("YYYY-MM-DD HH:MM", local time)
+        #[arg(long)]
+        at: Option<String>,
+        /// Only send to subscribers in a segment (e.g. tag:rust); repeat to combine
+        #[arg(long)]
+        segment: Vec<String>,
+        /// Email template to use (see 'blogr newsletter templates')
+        #[arg(long)]
+        template: Option<String>,
+        /// Second subject line: A/B test it against the first one
+        #[arg(long)]
+        subject_b: Option<String>,
+        /// Percentage of subscribers in the A/B test (default: 20)
+        #[arg(long, requires = "subject_b")]
+        ab_percent: Option<u8>,
+        /// Hours to wait before sending the winning subject to everyone else (default: 4)
+        #[arg(long, requires = "subject_b")]
+        ab_wait: Option<u64>,
+    },
+    /// Send new posts to subscribers: one post alone, several as a digest (run from cron)
+    Auto {
+        /// Show what would be sent without sending
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Send scheduled newsletters that are due (run from cron)
+    RunQueue {
+        /// Show what would be sent without sending
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List scheduled newsletters
+    Queue {
+        /// Cancel a pending newsletter by ID
+        #[arg(long)]
+        cancel: Option<i64>,
+    },
+    /// List past newsletter sends
+    History {
+        /// Number of sends to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Show delivery results for a send
+    Report {
+        /// Send ID (see 'blogr newsletter history')
+        send_id: i64,
+        /// Also list every successful recipient
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show A/B subject tests and their results
+    AbStatus {
+        /// A/B test ID; lists all tests when omitted
+        id: Option<i64>,
+    },
+    /// Send the winning subject of an A/B test to the remaining subscribers
+    AbFinish {
+        /// A/B test ID
+        id: i64,
+        /// Winning variant (a or b); picked from open/click rates when omitted
+        #[arg(long)]
+        winner: Option<String>,
+        /// Interactive SMTP password prompt
+        #[arg(long)]
+        interactive: bool,
+    },
+    /// Show subscriber growth, or open and click statistics for a send
+    Stats {
+        /// Send ID (printed when the newsletter is sent); shows subscriber growth when omitted
+        send_id: Option<i64>,
+        /// Group growth by month instead of by week
+        #[arg(long, conflicts_with = "send_id")]
+        monthly: bool,
+        /// Number of weeks or months to show
+        #[arg(long, default_value = "12", value_parser = clap::value_parser!(u16).range(1..), conflicts_with = "send_id")]
+        periods: u16,
     },
     /// Preview newsletter without sending (latest post)
-    DraftLatest,
+    DraftLatest {
+        /// Email template to use (see 'blogr newsletter templates')
+        #[arg(long)]
+        template: Option<String>,
+    },
     /// Preview custom newsletter without sending
     DraftCustom {
         /// Newsletter subject
         subject: String,
         /// Newsletter content (markdown)
         content: String,
+        /// Email template to use (see 'blogr newsletter templates')
+        #[arg(long)]
+        template: Option<String>,
     },
+    /// List available email templates
+    Templates,
     /// Send test email to specific address
     Test {
         /// Test email address
@@ -310,6 +820,9 @@ enum NewsletterAction {
         /// Interactive SMTP configuration
         #[arg(long)]
         interactive: bool,
+        /// Email template to use (see 'blogr newsletter templates')
+        #[arg(long)]
+        template: Option<String>,
     },
     /// Import subscribers from external services
     Import {
@@ -347,7 +860,7 @@ enum NewsletterAction {
         /// Host to bind to
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
-        /// API key for authentication (optional)
+        /// API key required by all but the public signup, contact and tracking routes
         #[arg(long)]
         api_key: Option<String>,
         /// Disable CORS
@@ -387,11 +900,30 @@ enum PluginAction {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    dotenvy::dotenv().ok(); // Load .env file if it exists
-
     let cli = Cli::parse();
+    let json = cli.json;
+    Console::set_json(json);
+    let profile_build = matches!(
+        cli.command,
+        Commands::Build {
+            profile_build: true,
+            ..
+        }
+    );
+    // Build output would drown the timings
+    let quiet = cli.quiet || matches!(cli.command, Commands::Bench { .. });
+    logging::init(cli.verbose, quiet, cli.log_file.as_deref(), profile_build)?;
+    progress::init(!quiet && !json);
 
-    match cli.command {
+    if let Some(project) = &cli.project {
+        let root = workspace::resolve_project(project, &std::env::current_dir()?)?;
+        std::env::set_current_dir(&root)
+            .map_err(|e| anyhow::anyhow!("Failed to enter {}: {}", root.display(), e))?;
+    }
+
+    dotenvy::dotenv().ok(); // Load .env file if it exists
+
+    let result = match cli.command {
         Commands::Init {
             name,
             path,
@@ -399,6 +931,8 @@ async fn main() -> Result<()> {
             github_repo,
             no_github,
             personal,
+            hybrid,
+            template,
         } => {
             init::handle_init(
                 name,
@@ -407,6 +941,8 @@ async fn main() -> Result<()> {
                 github_repo,
                 no_github,
                 personal,
+                hybrid,
+                template,
             )
             .await
         }
@@ -416,39 +952,93 @@ async fn main() -> Result<()> {
             draft,
             slug,
             tags,
+            bundle,
             tui,
-        } => new::handle_new(title, template, draft, slug, tags, tui).await,
+        } => new::handle_new(title, template, draft, slug, tags, bundle, tui).await,
         Commands::List {
             drafts,
             published,
+            status,
             tag,
             sort,
-        } => list::handle_list(drafts, published, tag, sort).await,
+        } => list::handle_list(drafts, published, status, tag, sort).await,
         Commands::Edit { slug, tui } => edit::handle_edit(slug, tui).await,
-        Commands::Delete { slug, force } => delete::handle_delete(slug, force).await,
+        Commands::Delete { slug, force, purge } => delete::handle_delete(slug, force, purge).await,
+        Commands::Publish {
+            slug,
+            at,
+            keep_date,
+        } => publish::handle_publish(slug, at, keep_date).await,
+        Commands::Restore { slug } => restore::handle_restore(slug).await,
+        Commands::Post { action } => match action {
+            PostAction::History { slug, version } => post::handle_history(slug, version).await,
+            PostAction::Restore {
+                slug,
+                version,
+                force,
+            } => post::handle_restore(slug, version, force).await,
+        },
         Commands::Build {
             output,
             drafts,
             future,
-        } => build::handle_build(output, drafts, future).await,
+            profile,
+            profile_build,
+        } => build::handle_build(output, drafts, future, profile, profile_build).await,
         Commands::Serve {
             port,
             host,
             drafts,
             open,
-        } => serve::handle_serve(port, host, drafts, open).await,
-        Commands::Deploy { branch, message } => deploy::handle_deploy(branch, message).await,
+            editor_api,
+            query_api,
+            in_memory,
+        } => serve::handle_serve(port, host, drafts, open, editor_api, query_api, in_memory).await,
+        Commands::Bench {
+            posts,
+            runs,
+            fixture,
+            baseline,
+        } => bench::handle_bench(posts, runs, fixture, baseline).await,
+        Commands::Cache { action } => match action {
+            CacheAction::Stats => cache::handle_stats().await,
+            CacheAction::Clear { kind } => cache::handle_clear(kind).await,
+        },
+        Commands::Check { action } => match action {
+            CheckAction::Prose { slug, no_spelling } => {
+                check::handle_prose(slug, no_spelling).await
+            }
+            CheckAction::Seo { min_score } => check::handle_seo(min_score).await,
+            CheckAction::Deploy { sample } => check::handle_deploy(sample).await,
+        },
+        Commands::Doctor { offline } => doctor::handle_doctor(offline).await,
+        Commands::Deploy {
+            branch,
+            message,
+            preview,
+            all,
+        } => deploy::handle_deploy(branch, message, preview, all).await,
         Commands::Theme { action } => match action {
             ThemeAction::List => theme::handle_list().await,
             ThemeAction::Info { name } => theme::handle_info(name).await,
             ThemeAction::Set { name } => theme::handle_set(name).await,
             ThemeAction::Preview { name } => theme::handle_preview(name).await,
+            ThemeAction::Install {
+                source,
+                rev,
+                shared,
+            } => theme::handle_install(source, rev, shared).await,
+            ThemeAction::Update { name, rev } => theme::handle_update(name, rev).await,
+            ThemeAction::Lint { name } => theme::handle_lint(name).await,
         },
         Commands::Project { action } => match action {
             ProjectAction::Info => project_cmd::handle_info().await,
             ProjectAction::Check => project_cmd::handle_check().await,
             ProjectAction::Clean => project_cmd::handle_clean().await,
-            ProjectAction::Stats => project_cmd::handle_stats().await,
+            ProjectAction::Stats => project_cmd::handle_stats(json).await,
+        },
+        Commands::Projects { action } => match action {
+            ProjectsAction::List => project_cmd::handle_list_projects(json),
         },
         Commands::Config { action } => match action {
             ConfigAction::Edit => {
@@ -463,6 +1053,8 @@ async fn main() -> Result<()> {
             }
             ConfigAction::Get { key } => commands::config::handle_get(key).await,
             ConfigAction::Set { key, value } => commands::config::handle_set(key, value).await,
+            ConfigAction::Unset { key } => commands::config::handle_unset(key).await,
+            ConfigAction::List { prefix } => commands::config::handle_list(prefix).await,
             ConfigAction::Domain { action } => {
                 let domain_action = match action {
                     DomainAction::Set {
@@ -470,11 +1062,15 @@ async fn main() -> Result<()> {
                         subdomain,
                         enforce_https,
                         github_pages,
+                        verify,
+                        wait,
                     } => commands::config::DomainAction::Set {
                         domain,
                         subdomain,
                         enforce_https,
                         github_pages,
+                        verify,
+                        wait,
                     },
                     DomainAction::List => commands::config::DomainAction::List,
                     DomainAction::Clear => commands::config::DomainAction::Clear,
@@ -489,34 +1085,126 @@ async fn main() -> Result<()> {
             }
         },
         Commands::Newsletter { action } => match action {
-            NewsletterAction::FetchSubscribers { interactive } => {
-                commands::newsletter::handle_fetch_subscribers(interactive).await
+            NewsletterAction::FetchSubscribers { interactive, since } => {
+                commands::newsletter::handle_fetch_subscribers(interactive, since).await
             }
+            NewsletterAction::Login => commands::newsletter::handle_login().await,
+            NewsletterAction::Logout => commands::newsletter::handle_logout(),
             NewsletterAction::Approve => commands::newsletter::handle_approve(),
+            NewsletterAction::Compose { template } => {
+                commands::newsletter::handle_compose(template)
+            }
             NewsletterAction::List { status } => commands::newsletter::handle_list(status),
             NewsletterAction::Remove { email, force } => {
                 commands::newsletter::handle_remove(&email, force)
             }
+            NewsletterAction::Tag { email, tags } => {
+                commands::newsletter::handle_tag(&email, &tags, false)
+            }
+            NewsletterAction::Untag { email, tags } => {
+                commands::newsletter::handle_tag(&email, &tags, true)
+            }
+            NewsletterAction::Clean {
+                gmail_aliases,
+                fix,
+                yes,
+            } => commands::newsletter::handle_clean(gmail_aliases, fix, yes),
             NewsletterAction::Export {
                 format,
                 output,
                 status,
-            } => commands::newsletter::handle_export(&format, output.as_deref(), status),
-            NewsletterAction::SendLatest { interactive } => {
-                commands::newsletter::handle_send_latest(interactive).await
+                segment,
+            } => commands::newsletter::handle_export(&format, output.as_deref(), status, segment),
+            NewsletterAction::SendLatest {
+                interactive,
+                at,
+                segment,
+                template,
+                subject_b,
+                ab_percent,
+                ab_wait,
+            } => {
+                let ab = commands::newsletter::ab_test_options(subject_b, ab_percent, ab_wait);
+                commands::newsletter::handle_send_latest(interactive, at, segment, template, ab)
+                    .await
+            }
+            NewsletterAction::SendDigest {
+                since,
+                subject,
+                interactive,
+                at,
+                segment,
+                template,
+            } => {
+                commands::newsletter::handle_send_digest(
+                    &since,
+                    subject,
+                    interactive,
+                    at,
+                    segment,
+                    template,
+                )
+                .await
             }
             NewsletterAction::SendCustom {
                 subject,
                 content,
                 interactive,
-            } => commands::newsletter::handle_send_custom(subject, content, interactive).await,
-            NewsletterAction::DraftLatest => commands::newsletter::handle_draft_latest().await,
-            NewsletterAction::DraftCustom { subject, content } => {
-                commands::newsletter::handle_draft_custom(subject, content).await
+                at,
+                segment,
+                template,
+                subject_b,
+                ab_percent,
+                ab_wait,
+            } => {
+                commands::newsletter::handle_send_custom(
+                    subject,
+                    content,
+                    interactive,
+                    at,
+                    segment,
+                    template,
+                    commands::newsletter::ab_test_options(subject_b, ab_percent, ab_wait),
+                )
+                .await
             }
-            NewsletterAction::Test { email, interactive } => {
-                commands::newsletter::handle_test_email(email, interactive).await
+            NewsletterAction::Auto { dry_run } => commands::newsletter::handle_auto(dry_run).await,
+            NewsletterAction::RunQueue { dry_run } => {
+                commands::newsletter::handle_run_queue(dry_run).await
             }
+            NewsletterAction::Queue { cancel } => commands::newsletter::handle_queue(cancel),
+            NewsletterAction::History { limit } => commands::newsletter::handle_history(limit),
+            NewsletterAction::Report { send_id, all } => {
+                commands::newsletter::handle_report(send_id, all)
+            }
+            NewsletterAction::AbStatus { id } => commands::newsletter::handle_ab_status(id),
+            NewsletterAction::AbFinish {
+                id,
+                winner,
+                interactive,
+            } => commands::newsletter::handle_ab_finish(id, winner, interactive),
+            NewsletterAction::Stats {
+                send_id,
+                monthly,
+                periods,
+            } => match send_id {
+                Some(send_id) => commands::newsletter::handle_stats(send_id),
+                None => commands::newsletter::handle_growth(monthly, periods as usize, json),
+            },
+            NewsletterAction::DraftLatest { template } => {
+                commands::newsletter::handle_draft_latest(template).await
+            }
+            NewsletterAction::DraftCustom {
+                subject,
+                content,
+                template,
+            } => commands::newsletter::handle_draft_custom(subject, content, template).await,
+            NewsletterAction::Templates => commands::newsletter::handle_templates(),
+            NewsletterAction::Test {
+                email,
+                interactive,
+                template,
+            } => commands::newsletter::handle_test_email(email, interactive, template).await,
             NewsletterAction::Import {
                 source,
                 file,
@@ -562,5 +1250,49 @@ async fn main() -> Result<()> {
                     .await
             }
         },
+        Commands::Crosspost { slug, to, draft } => {
+            commands::crosspost::handle_crosspost(slug, to, draft).await
+        }
+        Commands::Announce {
+            slug,
+            to,
+            dry_run,
+            force,
+        } => commands::announce::handle_announce(slug, to, dry_run, force).await,
+        Commands::Webmention { action } => match action {
+            WebmentionAction::Send { post, dry_run } => {
+                commands::webmention::handle_send(post, dry_run).await
+            }
+        },
+        Commands::ActivityPub { action } => match action {
+            ActivityPubAction::Followers => commands::activitypub::handle_followers().await,
+            ActivityPubAction::AddFollower { account } => {
+                commands::activitypub::handle_add_follower(account).await
+            }
+            ActivityPubAction::RemoveFollower { account } => {
+                commands::activitypub::handle_remove_follower(account).await
+            }
+            ActivityPubAction::Announce { post, dry_run } => {
+                commands::activitypub::handle_announce(post, dry_run).await
+            }
+        },
+        Commands::Drafts { action } => match action {
+            DraftsAction::Sync { pull, push } => commands::drafts::handle_sync(pull, push).await,
+        },
+        Commands::Completions { shell } => completions::handle_completions(shell),
+        Commands::Man { command, out_dir } => completions::handle_man(command, out_dir),
+    };
+
+    // Scripts reading --json output get the error as JSON too, unless the
+    // command already printed a report saying it failed
+    if let Err(e) = &result {
+        tracing::error!(target: logging::LOG_FILE_ONLY, "{:#}", e);
+        if json && !Console::json_reported() {
+            Console::report(
+                "blogr",
+                serde_json::json!({ "status": "error", "error": format!("{:#}", e) }),
+            )?;
+        }
     }
+    result
 }
@@ -0,0 +1,414 @@
+//! Subject line A/B testing
+//!
+//! A test sends subject A and subject B to two equal, randomly chosen groups that
+//! together make up `test_percent` of the audience. Once the waiting period is over,
+//! the subject with the higher open rate (then click rate) is sent to everyone else.
+//! Picking the winner automatically needs open/click tracking; without it, choose
+//! one with `blogr newsletter ab-finish <id> --winner a|b`.
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Utc};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use super::composer::Newsletter;
+use super::config::{newsletter_key, stable_hash};
+use super::database::{AbTest, AbTestStatus, Subscriber, SubscriberStatus, TrackingStats};
+use super::segment::Segment;
+use super::sender::SendReport;
+use super::NewsletterManager;
+
+/// One of the two subject lines in a test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variant {
+    A,
+    B,
+}
+
+impl Variant {
+    /// The subject line this variant stands for
+    pub fn subject(self, test: &AbTest) -> &str {
+        match self {
+            Variant::A => &test.subject_a,
+            Variant::B => &test.subject_b,
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::A => write!(f, "a"),
+            Variant::B => write!(f, "b"),
+        }
+    }
+}
+
+impl FromStr for Variant {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "a" => Ok(Variant::A),
+            "b" => Ok(Variant::B),
+            _ => Err(anyhow!("Invalid variant '{}'. Use 'a' or 'b'", s)),
+        }
+    }
+}
+
+/// Options for starting an A/B test; unset values come from `[newsletter.ab_test]`
+#[derive(Debug, Clone)]
+pub struct AbTestOptions {
+    pub subject_b: String,
+    pub test_percent: Option<u8>,
+    pub wait_hours: Option<u64>,
+}
+
+/// Subscribers split into the two test groups and everyone else
+#[derive(Debug)]
+pub struct AbGroups {
+    pub a: Vec<Subscriber>,
+    pub b: Vec<Subscriber>,
+    pub remainder: Vec<Subscriber>,
+}
+
+/// Randomly split subscribers into test groups. `seed` decides the order, so the
+/// same seed always gives the same split.
+pub fn split_groups(mut subscribers: Vec<Subscriber>, test_percent: u8, seed: &str) -> AbGroups {
+    subscribers.sort_by_key(|s| stable_hash(seed.bytes().chain([0]).chain(s.email.bytes())));
+
+    let total = subscribers.len();
+    let test_size = (total * usize::from(test_percent.min(100))).div_ceil(100);
+    let test_size = test_size.max(2).min(total);
+
+    let remainder = subscribers.split_off(test_size);
+    let b = subscribers.split_off(test_size.div_ceil(2));
+
+    AbGroups {
+        a: subscribers,
+        b,
+        remainder,
+    }
+}
+
+/// Pick the variant with the higher open rate, using click rate to break ties.
+/// Returns None when neither variant has any opens or clicks.
+pub fn pick_winner(a: &TrackingStats, b: &TrackingStats) -> Option<Variant> {
+    if a.unique_opens + a.unique_clicks + b.unique_opens + b.unique_clicks == 0 {
+        return None;
+    }
+
+    let rate = |count: usize, stats: &TrackingStats| {
+        if stats.tracked == 0 {
+            0.0
+        } else {
+            count as f64 / stats.tracked as f64
+        }
+    };
+    let score = |stats: &TrackingStats| {
+        (
+            rate(stats.unique_opens, stats),
+            rate(stats.unique_clicks, stats),
+        )
+    };
+
+    if score(b) > score(a) {
+        Some(Variant::B)
+    } else {
+        Some(Variant::A)
+    }
+}
+
+impl NewsletterManager {
+    /// Send both subject lines to their test groups and store the test.
+    /// Returns the A/B test ID.
+    pub fn start_ab_test(
+        &self,
+        newsletter: &Newsletter,
+        options: &AbTestOptions,
+        segments: &[Segment],
+        interactive: bool,
+    ) -> Result<i64> {
+        let settings = &self.config().newsletter.ab_test;
+        let test_percent = options.test_percent.unwrap_or(settings.test_percent);
+        let wait_hours = options.wait_hours.unwrap_or(settings.wait_hours);
+
+        if !(1..=100).contains(&test_percent) {
+            return Err(anyhow!("The A/B test percentage must be between 1 and 100"));
+        }
+        let subject_b = options.subject_b.trim();
+        if subject_b.is_empty() || subject_b == newsletter.subject {
+            return Err(anyhow!(
+                "Subject B must be non-empty and different from subject A"
+            ));
+        }
+
+        let mut subscribers = self.segment_subscribers(segments)?;
+        subscribers.retain(|s| s.status == SubscriberStatus::Approved);
+        if subscribers.len() < 2 {
+            return Err(anyhow!(
+                "An A/B test needs at least 2 approved subscribers, found {}",
+                subscribers.len()
+            ));
+        }
+
+        let password = self.smtp_password(interactive)?;
+        let groups = split_groups(subscribers, test_percent, &uuid::Uuid::new_v4().to_string());
+
+        let segment_specs: Vec<String> = segments.iter().map(|s| s.to_string()).collect();
+        let decide_at = Utc::now() + Duration::hours(wait_hours as i64);
+        let id = self.database().create_ab_test(
+            &newsletter.subject,
+            subject_b,
+            &newsletter.html_content,
+            &newsletter.text_content,
+            &segment_specs,
+            test_percent,
+            decide_at,
+        )?;
+
+        println!(
+            "🧪 A/B test #{}: {} subscribers get A, {} get B, {} get the winner",
+            id,
+            groups.a.len(),
+            groups.b.len(),
+            groups.remainder.len()
+        );
+
+        for (variant, subject, group) in [
+            (Variant::A, newsletter.subject.as_str(), groups.a),
+            (Variant::B, subject_b, groups.b),
+        ] {
+            println!("📤 Sending variant {}: '{}'", variant, subject);
+            let mut variant_newsletter = newsletter.clone();
+            variant_newsletter.subject = subject.to_string();
+
+            let key = format!(
+                "{}:ab{}-{}",
+                newsletter_key(&variant_newsletter),
+                id,
+                variant
+            );
+            let (run_id, _) = self.send_to(&variant_newsletter, &key, group, &password)?;
+            self.database()
+                .set_ab_test_run(id, &variant.to_string(), run_id)?;
+        }
+
+        let decide_local = decide_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M");
+        if self.config().newsletter.tracking.enabled {
+            println!(
+                "⏳ 'blogr newsletter run-queue' will send the winning subject after {}",
+                decide_local
+            );
+        } else {
+            println!(
+                "⏳ Tracking is disabled, so pick the winner yourself after {}:",
+                decide_local
+            );
+            println!("   blogr newsletter ab-finish {} --winner a|b", id);
+        }
+
+        Ok(id)
+    }
+
+    /// Open and click stats for both variants of a test, when they were tracked
+    pub fn ab_test_stats(
+        &self,
+        test: &AbTest,
+    ) -> Result<(Option<TrackingStats>, Option<TrackingStats>)> {
+        let stats = |run: Option<i64>| -> Result<Option<TrackingStats>> {
+            match run {
+                Some(run_id) => self.database().get_tracking_stats(run_id),
+                None => Ok(None),
+            }
+        };
+
+        Ok((stats(test.run_a)?, stats(test.run_b)?))
+    }
+
+    /// Send the winning subject to everyone who was not in a test group.
+    /// Without an explicit `winner`, it is picked from the tracked open and click rates.
+    pub fn finish_ab_test(
+        &self,
+        id: i64,
+        winner: Option<Variant>,
+        interactive: bool,
+    ) -> Result<SendReport> {
+        let test = self
+            .database()
+            .get_ab_test(id)?
+            .ok_or_else(|| anyhow!("No A/B test with ID {}", id))?;
+
+        match test.status {
+            AbTestStatus::Completed => {
+                return Err(anyhow!("A/B test #{} has already been completed", id))
+            }
+            AbTestStatus::Sending => return Err(anyhow!("A/B test #{} is already being sent", id)),
+            AbTestStatus::Testing | AbTestStatus::Failed => {}
+        }
+
+        let winner = match winner {
+            Some(winner) => winner,
+            None => self.auto_winner(&test)?,
+        };
+
+        let password = self.smtp_password(interactive)?;
+        if !self.database().claim_ab_test(id)? {
+            return Err(anyhow!("A/B test #{} is already being sent", id));
+        }
+
+        println!(
+            "🏆 A/B test #{}: sending variant {} ('{}') to the remaining subscribers",
+            id,
+            winner,
+            winner.subject(&test)
+        );
+
+        let result = self.send_ab_winner(&test, winner, &password);
+        match result {
+            Ok((run_id, report)) => {
+                self.database()
+                    .finish_ab_test(id, &winner.to_string(), Some(run_id), None)?;
+                Ok(report)
+            }
+            Err(e) => {
+                self.database().finish_ab_test(
+                    id,
+                    &winner.to_string(),
+                    None,
+                    Some(&e.to_string()),
+                )?;
+                Err(e)
+            }
+        }
+    }
+
+    fn auto_winner(&self, test: &AbTest) -> Result<Variant> {
+        let no_data = || {
+            anyhow!(
+                "A/B test #{} has no open or click data. Enable tracking, or choose with --winner a|b",
+                test.id
+            )
+        };
+
+        match self.ab_test_stats(test)? {
+            (Some(a), Some(b)) if a.tracked > 0 && b.tracked > 0 => Ok(pick_winner(&a, &b)
+                .unwrap_or_else(|| {
+                    println!("🤷 No opens or clicks recorded for either subject, keeping A");
+                    Variant::A
+                })),
+            _ => Err(no_data()),
+        }
+    }
+
+    fn send_ab_winner(
+        &self,
+        test: &AbTest,
+        winner: Variant,
+        password: &str,
+    ) -> Result<(i64, SendReport)> {
+        let mut tested: HashSet<String> = HashSet::new();
+        for run_id in [test.run_a, test.run_b].into_iter().flatten() {
+            tested.extend(
+                self.database()
+                    .get_deliveries(run_id)?
+                    .into_iter()
+                    .map(|d| d.email),
+            );
+        }
+
+        let segments = Segment::parse_all(&test.segments)?;
+        let mut remainder = self.segment_subscribers(&segments)?;
+        remainder.retain(|s| !tested.contains(&s.email));
+
+        let newsletter = Newsletter::new(
+            winner.subject(test).to_string(),
+            test.html_content.clone(),
+            test.text_content.clone(),
+        );
+        let key = format!("{}:ab{}-final", newsletter_key(&newsletter), test.id);
+        self.send_to(&newsletter, &key, remainder, password)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscribers(count: usize) -> Vec<Subscriber> {
+        (0..count)
+            .map(|i| {
+                let mut subscriber = Subscriber::new(format!("user{}@example.com", i), None);
+                subscriber.status = SubscriberStatus::Approved;
+                subscriber
+            })
+            .collect()
+    }
+
+    fn stats(tracked: usize, unique_opens: usize, unique_clicks: usize) -> TrackingStats {
+        TrackingStats {
+            run_id: 1,
+            subject: "Subject".to_string(),
+            started_at: "2024-01-01 00:00:00".to_string(),
+            delivered: tracked,
+            tracked,
+            unique_opens,
+            total_opens: unique_opens,
+            unique_clicks,
+            total_clicks: unique_clicks,
+            links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_split_groups() {
+        let groups = split_groups(subscribers(100), 20, "seed");
+        assert_eq!(groups.a.len(), 10);
+        assert_eq!(groups.b.len(), 10);
+        assert_eq!(groups.remainder.len(), 80);
+
+        let emails = |list: &[Subscriber]| list.iter().map(|s| s.email.clone()).collect::<Vec<_>>();
+        let again = split_groups(subscribers(100), 20, "seed");
+        assert_eq!(emails(&groups.a), emails(&again.a));
+        let other = split_groups(subscribers(100), 20, "other seed");
+        assert_ne!(emails(&groups.a), emails(&other.a));
+
+        // Tiny audiences still get one subscriber per variant
+        let small = split_groups(subscribers(3), 10, "seed");
+        assert_eq!(
+            (small.a.len(), small.b.len(), small.remainder.len()),
+            (1, 1, 1)
+        );
+
+        let everyone = split_groups(subscribers(5), 100, "seed");
+        assert_eq!(
+            (everyone.a.len(), everyone.b.len(), everyone.remainder.len()),
+            (3, 2, 0)
+        );
+    }
+
+    #[test]
+    fn test_pick_winner() {
+        assert_eq!(
+            pick_winner(&stats(10, 3, 0), &stats(10, 5, 0)),
+            Some(Variant::B)
+        );
+        assert_eq!(
+            pick_winner(&stats(10, 5, 0), &stats(20, 5, 0)),
+            Some(Variant::A)
+        );
+        // Equal open rates fall back to clicks
+        assert_eq!(
+            pick_winner(&stats(10, 5, 1), &stats(10, 5, 2)),
+            Some(Variant::B)
+        );
+        assert_eq!(
+            pick_winner(&stats(10, 5, 2), &stats(10, 5, 2)),
+            Some(Variant::A)
+        );
+        assert_eq!(pick_winner(&stats(10, 0, 0), &stats(10, 0, 0)), None);
+    }
+}
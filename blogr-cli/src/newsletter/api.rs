@@ -5,18 +5,26 @@
 
 use anyhow::{Context, Result};
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
-    routing::{delete, get, post, put},
+    extract::{ConnectInfo, Form, Path, Query, Request, State},
+    handler::Handler,
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Json, Redirect, Response},
+    routing::{self, get, MethodRouter},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::net::TcpListener;
 // use tower_http::cors::{Any, CorsLayer}; // Commented out - requires tower-http with cors feature
 
+use super::database;
+use super::openapi::{self, Body, Operation, QueryParam, Reply};
+use super::plugin::{create_plugin_context, PluginHook, PluginManager};
+use super::webhook::{WebhookDispatcher, WebhookEvent};
 use super::{NewsletterManager, Subscriber, SubscriberStatus};
 use crate::config::Config;
 
@@ -43,6 +51,14 @@ impl Default for ApiConfig {
     }
 }
 
+/// Page size used when `limit` is not given
+const DEFAULT_PAGE_SIZE: usize = 50;
+/// Largest accepted `limit`
+const MAX_PAGE_SIZE: usize = 500;
+/// Signup attempts allowed per client within `SUBSCRIBE_WINDOW`
+const SUBSCRIBE_LIMIT: usize = 5;
+const SUBSCRIBE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
 /// API server application state
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -50,6 +66,33 @@ pub struct ApiState {
     pub newsletter_manager: Arc<NewsletterManager>,
     pub config: Arc<Config>,
     pub api_config: Arc<ApiConfig>,
+    pub webhooks: Arc<WebhookDispatcher>,
+    pub subscribe_limiter: Arc<RateLimiter>,
+    pub plugins: Arc<PluginManager>,
+}
+
+/// Sliding-window limit on signup attempts per client address
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    attempts: Mutex<HashMap<IpAddr, Vec<Instant>>>,
+}
+
+impl RateLimiter {
+    /// Record an attempt, returning false if the client is over the limit
+    pub fn allow(&self, client: IpAddr, now: Instant) -> bool {
+        let mut attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        attempts.retain(|_, times| {
+            times.retain(|t| now.duration_since(*t) < SUBSCRIBE_WINDOW);
+            !times.is_empty()
+        });
+
+        let times = attempts.entry(client).or_default();
+        if times.len() >= SUBSCRIBE_LIMIT {
+            return false;
+        }
+        times.push(now);
+        true
+    }
 }
 
 /// API response wrapper
@@ -71,7 +114,6 @@ impl<T> ApiResponse<T> {
         }
     }
 
-    #[allow(dead_code)]
     pub fn error(message: String) -> ApiResponse<()> {
         ApiResponse {
             success: false,
@@ -86,16 +128,110 @@ impl<T> ApiResponse<T> {
 #[derive(Deserialize)]
 pub struct SubscriberQuery {
     pub status: Option<String>,
+    /// Substring of the email address or notes
+    pub search: Option<String>,
+    pub tag: Option<String>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
 }
 
+impl SubscriberQuery {
+    /// Database filter for these parameters; an unknown status is a bad request
+    fn filter(&self) -> Result<database::SubscriberQuery, StatusCode> {
+        let status = match self.status.as_deref() {
+            Some(status) => Some(status.parse().map_err(|_| StatusCode::BAD_REQUEST)?),
+            None => None,
+        };
+
+        Ok(database::SubscriberQuery {
+            status,
+            search: self.search.clone(),
+            tag: self.tag.clone(),
+        })
+    }
+}
+
+/// One page of subscribers
+#[derive(Serialize)]
+pub struct SubscriberPage {
+    pub subscribers: Vec<Subscriber>,
+    /// Number of subscribers matching the filters, across all pages
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    /// Offset of the next page, if there is one
+    pub next_offset: Option<usize>,
+}
+
+/// Action applied to many subscribers at once
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulkAction {
+    Approve,
+    Decline,
+    Delete,
+}
+
+/// Fields posted by the site's signup form
+#[derive(Deserialize)]
+pub struct SubscribeRequest {
+    pub email: String,
+    #[serde(default)]
+    pub name: String,
+    /// Honeypot field hidden from people; anything in it means a bot filled the form
+    #[serde(default)]
+    pub website: String,
+}
+
+/// Fields posted by the site's contact form
+#[derive(Deserialize)]
+pub struct ContactRequest {
+    pub name: String,
+    pub email: String,
+    pub message: String,
+    #[serde(default)]
+    pub subject: String,
+    /// Honeypot, as on the signup form
+    #[serde(default)]
+    pub website: String,
+    /// Page to send the reader to afterwards, for posts without JavaScript
+    #[serde(default)]
+    pub redirect: String,
+}
+
+/// A contact form message as kept in `.blogr/contact-messages.jsonl`
+#[derive(Serialize)]
+struct ContactMessage<'a> {
+    received_at: chrono::DateTime<chrono::Utc>,
+    name: &'a str,
+    email: &'a str,
+    subject: &'a str,
+    message: &'a str,
+}
+
+/// Bulk action request
+#[derive(Deserialize)]
+pub struct BulkRequest {
+    pub action: BulkAction,
+    pub emails: Vec<String>,
+}
+
+/// Bulk action result
+#[derive(Serialize)]
+pub struct BulkResponse {
+    /// Number of subscribers the action was applied to
+    pub processed: usize,
+    /// Emails that did not match a subscriber
+    pub not_found: Vec<String>,
+}
+
 /// Subscriber creation request
 #[derive(Deserialize)]
 pub struct CreateSubscriberRequest {
     pub email: String,
     pub status: Option<SubscriberStatus>,
     pub notes: Option<String>,
+    pub name: Option<String>,
 }
 
 /// Subscriber update request
@@ -103,6 +239,8 @@ pub struct CreateSubscriberRequest {
 pub struct UpdateSubscriberRequest {
     pub status: Option<SubscriberStatus>,
     pub notes: Option<String>,
+    /// Empty to clear
+    pub name: Option<String>,
 }
 
 /// Newsletter sending request
@@ -146,10 +284,19 @@ impl NewsletterApiServer {
         config: Config,
         api_config: ApiConfig,
     ) -> Self {
+        let plugins = PluginManager::for_project(&config, newsletter_manager.project_root())
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load plugins: {:#}", e);
+                PluginManager::new(newsletter_manager.project_root().to_path_buf())
+            });
+
         let state = ApiState {
             newsletter_manager: Arc::new(newsletter_manager),
+            webhooks: Arc::new(WebhookDispatcher::new(config.newsletter.webhooks.clone())),
             config: Arc::new(config),
             api_config: Arc::new(api_config),
+            subscribe_limiter: Arc::new(RateLimiter::default()),
+            plugins: Arc::new(plugins),
         };
 
         Self { state }
@@ -163,39 +310,50 @@ impl NewsletterApiServer {
         );
         let app = self.create_router();
 
-        println!("Starting Newsletter API server on {}", addr);
+        crate::status!("Starting Newsletter API server on {}", addr);
 
         let listener = TcpListener::bind(&addr)
             .await
             .with_context(|| format!("Failed to bind to address {}", addr))?;
 
-        axum::serve(listener, app)
-            .await
-            .context("API server error")?;
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .context("API server error")?;
 
         Ok(())
     }
 
     /// Create the router with all endpoints
     fn create_router(self) -> Router {
-        Router::new()
-            // Health check
-            .route("/health", get(health_check))
-            // Subscriber management
-            .route("/subscribers", get(list_subscribers))
-            .route("/subscribers", post(create_subscriber))
-            .route("/subscribers/:email", get(get_subscriber))
-            .route("/subscribers/:email", put(update_subscriber))
-            .route("/subscribers/:email", delete(delete_subscriber))
-            // Newsletter operations
-            .route("/newsletter/send", post(send_newsletter))
-            .route("/newsletter/send-latest", post(send_latest_post))
-            .route("/newsletter/preview", post(preview_newsletter))
-            // Import/export
-            .route("/import", post(import_subscribers))
-            .route("/export", get(export_subscribers))
-            // Statistics
-            .route("/stats", get(get_stats))
+        let endpoints = endpoints();
+        let spec = openapi::document(
+            endpoints
+                .iter()
+                .map(|endpoint| (endpoint.method, endpoint.path, &endpoint.doc)),
+        );
+
+        let mut public = Router::new();
+        let mut protected = Router::new();
+        for endpoint in endpoints {
+            if endpoint.doc.public {
+                public = public.route(endpoint.path, endpoint.handler);
+            } else {
+                protected = protected.route(endpoint.path, endpoint.handler);
+            }
+        }
+        let protected = protected.route_layer(middleware::from_fn_with_state(
+            self.state.clone(),
+            require_api_key,
+        ));
+
+        public
+            // API description
+            .route("/openapi.json", get(move || async move { Json(spec) }))
+            .route("/docs", get(|| async { Html(openapi::SWAGGER_UI) }))
+            .merge(protected)
             .with_state(self.state)
 
         // Add CORS if enabled (commented out - requires tower-http with cors feature)
@@ -210,6 +368,327 @@ impl NewsletterApiServer {
     }
 }
 
+/// Reject requests that don't carry `Authorization: Bearer <api_key>`. Without
+/// a configured key every protected route is refused.
+async fn require_api_key(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match (&state.api_config.api_key, token) {
+        (Some(key), Some(token)) if key_matches(key, token) => next.run(request).await,
+        (None, _) => (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse::<()>::error(
+                "Start the API server with --api-key to use this endpoint".to_string(),
+            )),
+        )
+            .into_response(),
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(ApiResponse::<()>::error(
+                "Missing or invalid API key".to_string(),
+            )),
+        )
+            .into_response(),
+    }
+}
+
+/// Compare in constant time so the key can't be guessed byte by byte
+fn key_matches(key: &str, token: &str) -> bool {
+    key.len() == token.len()
+        && key
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// A route together with its OpenAPI documentation
+struct Endpoint {
+    method: &'static str,
+    path: &'static str,
+    handler: MethodRouter<ApiState>,
+    doc: Operation,
+}
+
+impl Endpoint {
+    fn get<H: Handler<T, ApiState>, T: 'static>(
+        path: &'static str,
+        handler: H,
+        doc: Operation,
+    ) -> Self {
+        Self {
+            method: "GET",
+            path,
+            handler: routing::get(handler),
+            doc,
+        }
+    }
+
+    fn post<H: Handler<T, ApiState>, T: 'static>(
+        path: &'static str,
+        handler: H,
+        doc: Operation,
+    ) -> Self {
+        Self {
+            method: "POST",
+            path,
+            handler: routing::post(handler),
+            doc,
+        }
+    }
+
+    fn put<H: Handler<T, ApiState>, T: 'static>(
+        path: &'static str,
+        handler: H,
+        doc: Operation,
+    ) -> Self {
+        Self {
+            method: "PUT",
+            path,
+            handler: routing::put(handler),
+            doc,
+        }
+    }
+
+    fn delete<H: Handler<T, ApiState>, T: 'static>(
+        path: &'static str,
+        handler: H,
+        doc: Operation,
+    ) -> Self {
+        Self {
+            method: "DELETE",
+            path,
+            handler: routing::delete(handler),
+            doc,
+        }
+    }
+}
+
+/// Every documented route of the API server
+fn endpoints() -> Vec<Endpoint> {
+    const NO_QUERY: &[QueryParam] = &[];
+
+    vec![
+        // Health check
+        Endpoint::get(
+            "/health",
+            health_check,
+            Operation {
+                summary: "Health check",
+                tag: "Server",
+                query: NO_QUERY,
+                body: None,
+                reply: Reply::Json("StringMap"),
+                public: true,
+            },
+        ),
+        // Public signup form target
+        Endpoint::post(
+            "/subscribe",
+            subscribe,
+            Operation {
+                summary: "Sign up from the site's newsletter form (rate limited, adds a pending subscriber)",
+                tag: "Signup",
+                query: NO_QUERY,
+                body: Some(Body::Form("SubscribeRequest")),
+                reply: Reply::Json("Text"),
+                public: true,
+            },
+        ),
+        // Public contact form target
+        Endpoint::post(
+            "/contact",
+            contact,
+            Operation {
+                summary: "Send a message from the site's contact form (rate limited, kept in .blogr/contact-messages.jsonl)",
+                tag: "Signup",
+                query: NO_QUERY,
+                body: Some(Body::Form("ContactRequest")),
+                reply: Reply::Json("Text"),
+                public: true,
+            },
+        ),
+        // Subscriber management
+        Endpoint::get(
+            "/subscribers",
+            list_subscribers,
+            Operation {
+                summary: "List subscribers, one page at a time",
+                tag: "Subscribers",
+                query: openapi::SUBSCRIBER_FILTERS,
+                body: None,
+                reply: Reply::Json("SubscriberPage"),
+                public: false,
+            },
+        ),
+        Endpoint::post(
+            "/subscribers",
+            create_subscriber,
+            Operation {
+                summary: "Create a subscriber",
+                tag: "Subscribers",
+                query: NO_QUERY,
+                body: Some(Body::Json("CreateSubscriberRequest")),
+                reply: Reply::Json("Subscriber"),
+                public: false,
+            },
+        ),
+        Endpoint::post(
+            "/subscribers/bulk",
+            bulk_subscribers,
+            Operation {
+                summary: "Approve, decline or delete many subscribers",
+                tag: "Subscribers",
+                query: NO_QUERY,
+                body: Some(Body::Json("BulkRequest")),
+                reply: Reply::Json("BulkResponse"),
+                public: false,
+            },
+        ),
+        Endpoint::get(
+            "/subscribers/{email}",
+            get_subscriber,
+            Operation {
+                summary: "Get a subscriber",
+                tag: "Subscribers",
+                query: NO_QUERY,
+                body: None,
+                reply: Reply::Json("Subscriber"),
+                public: false,
+            },
+        ),
+        Endpoint::put(
+            "/subscribers/{email}",
+            update_subscriber,
+            Operation {
+                summary: "Change a subscriber's status or notes",
+                tag: "Subscribers",
+                query: NO_QUERY,
+                body: Some(Body::Json("UpdateSubscriberRequest")),
+                reply: Reply::Json("Subscriber"),
+                public: false,
+            },
+        ),
+        Endpoint::delete(
+            "/subscribers/{email}",
+            delete_subscriber,
+            Operation {
+                summary: "Remove a subscriber",
+                tag: "Subscribers",
+                query: NO_QUERY,
+                body: None,
+                reply: Reply::Empty,
+                public: false,
+            },
+        ),
+        // Newsletter operations
+        Endpoint::post(
+            "/newsletter/send",
+            send_newsletter,
+            Operation {
+                summary: "Send a custom newsletter (not yet implemented)",
+                tag: "Newsletters",
+                query: NO_QUERY,
+                body: Some(Body::Json("SendNewsletterRequest")),
+                reply: Reply::Json("StringMap"),
+                public: false,
+            },
+        ),
+        Endpoint::post(
+            "/newsletter/send-latest",
+            send_latest_post,
+            Operation {
+                summary: "Send the latest post (not yet implemented)",
+                tag: "Newsletters",
+                query: NO_QUERY,
+                body: None,
+                reply: Reply::Json("StringMap"),
+                public: false,
+            },
+        ),
+        Endpoint::post(
+            "/newsletter/preview",
+            preview_newsletter,
+            Operation {
+                summary: "Preview a newsletter (not yet implemented)",
+                tag: "Newsletters",
+                query: NO_QUERY,
+                body: Some(Body::Json("SendNewsletterRequest")),
+                reply: Reply::Json("StringMap"),
+                public: false,
+            },
+        ),
+        // Import/export
+        Endpoint::post(
+            "/import",
+            import_subscribers,
+            Operation {
+                summary: "Import subscribers (not yet implemented)",
+                tag: "Import/Export",
+                query: NO_QUERY,
+                body: Some(Body::Json("ImportRequest")),
+                reply: Reply::Json("StringMap"),
+                public: false,
+            },
+        ),
+        Endpoint::get(
+            "/export",
+            export_subscribers,
+            Operation {
+                summary: "Export all subscribers matching the filters",
+                tag: "Import/Export",
+                query: openapi::SUBSCRIBER_FILTERS,
+                body: None,
+                reply: Reply::Json("SubscriberList"),
+                public: false,
+            },
+        ),
+        // Statistics
+        Endpoint::get(
+            "/stats",
+            get_stats,
+            Operation {
+                summary: "Subscriber counts by status",
+                tag: "Server",
+                query: NO_QUERY,
+                body: None,
+                reply: Reply::Json("StatsResponse"),
+                public: false,
+            },
+        ),
+        // Open and click tracking
+        Endpoint::get(
+            "/t/o/{token}",
+            track_open,
+            Operation {
+                summary: "Open tracking pixel",
+                tag: "Tracking",
+                query: NO_QUERY,
+                body: None,
+                reply: Reply::Image,
+                public: true,
+            },
+        ),
+        Endpoint::get(
+            "/t/c/{token}/{link}",
+            track_click,
+            Operation {
+                summary: "Tracked link redirect",
+                tag: "Tracking",
+                query: NO_QUERY,
+                body: None,
+                reply: Reply::Redirect,
+                public: true,
+            },
+        ),
+    ]
+}
+
 /// Health check endpoint
 async fn health_check() -> Json<ApiResponse<HashMap<String, String>>> {
     let mut data = HashMap::new();
@@ -220,47 +699,293 @@ async fn health_check() -> Json<ApiResponse<HashMap<String, String>>> {
     Json(ApiResponse::success(data))
 }
 
-/// List subscribers endpoint
+/// List subscribers endpoint, paginated and filtered by status, search text and tag
 async fn list_subscribers(
     State(state): State<ApiState>,
     Query(params): Query<SubscriberQuery>,
-) -> Result<Json<ApiResponse<Vec<Subscriber>>>, StatusCode> {
-    let status_filter = params
-        .status
-        .as_deref()
-        .and_then(|s| match s.to_lowercase().as_str() {
-            "pending" => Some(SubscriberStatus::Pending),
-            "approved" => Some(SubscriberStatus::Approved),
-            "declined" => Some(SubscriberStatus::Declined),
-            _ => None,
-        });
+) -> Result<Json<ApiResponse<SubscriberPage>>, StatusCode> {
+    let filter = params.filter()?;
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = params.offset.unwrap_or(0);
 
     match state
         .newsletter_manager
         .database()
-        .get_subscribers(status_filter)
+        .query_subscribers(&filter, Some(limit), offset)
     {
-        Ok(mut subscribers) => {
-            // Apply pagination
-            if let Some(offset) = params.offset {
-                if offset < subscribers.len() {
-                    subscribers = subscribers.into_iter().skip(offset).collect();
-                } else {
-                    subscribers = Vec::new();
-                }
-            }
+        Ok((subscribers, total)) => {
+            let next_offset = (offset + subscribers.len() < total).then(|| offset + limit);
+            Ok(Json(ApiResponse::success(SubscriberPage {
+                subscribers,
+                total,
+                limit,
+                offset,
+                next_offset,
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to list subscribers: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Public signup endpoint for the site's newsletter form. New subscribers start
+/// out pending. Replies with JSON for `fetch` requests and a small HTML page for
+/// plain form posts, and never reveals whether an address was already subscribed.
+async fn subscribe(
+    State(state): State<ApiState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Form(request): Form<SubscribeRequest>,
+) -> Response {
+    let client = client_ip(addr, &headers);
+    if !state.subscribe_limiter.allow(client, Instant::now()) {
+        return subscribe_reply(
+            &state,
+            &headers,
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many signup attempts. Please try again later.",
+        );
+    }
+
+    let thanks = "Thanks for subscribing! You'll start receiving the newsletter once your subscription is approved.";
+
+    // Pretend to succeed so bots don't learn to avoid the honeypot
+    if !request.website.is_empty() {
+        return subscribe_reply(&state, &headers, StatusCode::OK, thanks);
+    }
+
+    let email = request.email.trim().to_lowercase();
+    if !super::fetcher::is_valid_email(&email) {
+        return subscribe_reply(
+            &state,
+            &headers,
+            StatusCode::BAD_REQUEST,
+            "Please enter a valid email address.",
+        );
+    }
+
+    match add_form_subscriber(&state, &email, request.name.trim()) {
+        Ok(()) => subscribe_reply(&state, &headers, StatusCode::OK, thanks),
+        Err(e) => {
+            tracing::error!("Failed to add subscriber from signup form: {}", e);
+            subscribe_reply(
+                &state,
+                &headers,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Something went wrong. Please try again later.",
+            )
+        }
+    }
+}
+
+/// Contact form target: keeps the message for the site owner
+async fn contact(
+    State(state): State<ApiState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Form(request): Form<ContactRequest>,
+) -> Response {
+    let client = client_ip(addr, &headers);
+    if !state.subscribe_limiter.allow(client, Instant::now()) {
+        return subscribe_reply(
+            &state,
+            &headers,
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many messages. Please try again later.",
+        );
+    }
 
-            if let Some(limit) = params.limit {
-                subscribers.truncate(limit);
+    let thanks = "Thanks for your message!";
+    let sent = |state: &ApiState| {
+        let wants_page = !headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|accept| accept.contains("application/json"));
+        if wants_page {
+            if let Some(url) = contact_redirect(&request.redirect, &state.config.blog.base_url) {
+                return Redirect::to(url.as_str()).into_response();
             }
+        }
+        subscribe_reply(state, &headers, StatusCode::OK, thanks)
+    };
+
+    if !request.website.is_empty() {
+        return sent(&state);
+    }
 
-            Ok(Json(ApiResponse::success(subscribers)))
+    let email = request.email.trim();
+    if !super::fetcher::is_valid_email(email) {
+        return subscribe_reply(
+            &state,
+            &headers,
+            StatusCode::BAD_REQUEST,
+            "Please enter a valid email address.",
+        );
+    }
+    if request.message.trim().is_empty() {
+        return subscribe_reply(
+            &state,
+            &headers,
+            StatusCode::BAD_REQUEST,
+            "Please write a message.",
+        );
+    }
+
+    let message = ContactMessage {
+        received_at: chrono::Utc::now(),
+        name: request.name.trim(),
+        email,
+        subject: request.subject.trim(),
+        message: request.message.trim(),
+    };
+    match save_contact_message(state.newsletter_manager.project_root(), &message) {
+        Ok(()) => {
+            tracing::info!("Contact form message from {}", email);
+            sent(&state)
         }
         Err(e) => {
-            eprintln!("Failed to list subscribers: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!("Failed to save contact form message: {}", e);
+            subscribe_reply(
+                &state,
+                &headers,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Something went wrong. Please try again later.",
+            )
+        }
+    }
+}
+
+/// Where to send the reader after a contact form post: a path on the blog,
+/// or a URL with the blog's origin. Anything else is ignored, so the API
+/// can't be used to redirect to other sites.
+fn contact_redirect(redirect: &str, base_url: &str) -> Option<url::Url> {
+    let base = url::Url::parse(base_url).ok()?;
+    let redirect = redirect.trim();
+    if redirect.starts_with('/') && !redirect.starts_with("//") && !redirect.contains('\\') {
+        return base.join(redirect).ok();
+    }
+    let url = url::Url::parse(redirect).ok()?;
+    (url.origin() == base.origin()).then_some(url)
+}
+
+fn save_contact_message(project_root: &std::path::Path, message: &ContactMessage) -> Result<()> {
+    use std::io::Write;
+
+    let dir = project_root.join(".blogr");
+    std::fs::create_dir_all(&dir)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join("contact-messages.jsonl"))?;
+    writeln!(file, "{}", serde_json::to_string(message)?)?;
+    Ok(())
+}
+
+fn add_form_subscriber(state: &ApiState, email: &str, name: &str) -> Result<()> {
+    let database = state.newsletter_manager.database();
+    if database.get_subscriber_by_email(email)?.is_some() {
+        return Ok(());
+    }
+
+    let mut subscriber = Subscriber::new(email.to_string(), Some("form".to_string()));
+    subscriber.name = (!name.is_empty()).then(|| name.to_string());
+    database.add_subscriber(&subscriber)?;
+
+    if let Some(created) = database.get_subscriber_by_email(email)? {
+        notify(state, WebhookEvent::Created, &created);
+    }
+    Ok(())
+}
+
+/// Tell webhooks and plugins about a subscriber change
+fn notify(state: &ApiState, event: WebhookEvent, subscriber: &Subscriber) {
+    state.webhooks.dispatch(event, subscriber);
+
+    let mut data = HashMap::new();
+    data.insert("event".to_string(), serde_json::json!(event.as_str()));
+    data.insert(
+        "subscriber".to_string(),
+        serde_json::to_value(subscriber).unwrap_or_default(),
+    );
+    let context = create_plugin_context(
+        state.config.clone(),
+        None,
+        state.newsletter_manager.project_root().to_path_buf(),
+        PluginHook::SubscriberEvent,
+        data,
+    );
+    if let Err(e) = state
+        .plugins
+        .execute_hook(PluginHook::SubscriberEvent, &context)
+    {
+        tracing::error!("Failed to run subscriber event plugins: {}", e);
+    }
+}
+
+/// Address a signup attempt is counted against. Behind a reverse proxy on the same
+/// machine every connection comes from loopback, so the proxy's `X-Forwarded-For`
+/// is used instead; remote clients can't reach a loopback address to spoof it.
+fn client_ip(addr: SocketAddr, headers: &HeaderMap) -> IpAddr {
+    if addr.ip().is_loopback() {
+        let forwarded = headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .and_then(|ip| ip.trim().parse().ok());
+        if let Some(ip) = forwarded {
+            return ip;
         }
     }
+    addr.ip()
+}
+
+fn subscribe_reply(
+    state: &ApiState,
+    headers: &HeaderMap,
+    status: StatusCode,
+    message: &str,
+) -> Response {
+    let wants_json = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"));
+
+    let mut response = if wants_json {
+        let body = if status.is_success() {
+            serde_json::to_value(ApiResponse::success(message))
+        } else {
+            serde_json::to_value(ApiResponse::<()>::error(message.to_string()))
+        };
+        (status, Json(body.unwrap_or_default())).into_response()
+    } else {
+        // Plain form post (no JavaScript): show the message with a way back
+        let back = headers
+            .get(header::REFERER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+            .unwrap_or(&state.config.blog.base_url);
+        let page = format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title></head>\n<body><h1>{title}</h1><p>{message}</p><p><a href=\"{back}\">Back to the site</a></p></body></html>\n",
+            title = crate::generator::markdown::html_escape(&state.config.blog.title),
+            message = crate::generator::markdown::html_escape(message),
+            back = crate::generator::markdown::html_escape(back),
+        );
+        (status, Html(page)).into_response()
+    };
+
+    // Let the static site read the reply from a different origin
+    if state.api_config.cors_enabled {
+        response.headers_mut().insert(
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            header::HeaderValue::from_static("*"),
+        );
+    }
+    response
 }
 
 /// Create subscriber endpoint
@@ -276,6 +1001,11 @@ async fn create_subscriber(
         approved_at: None,
         source_email_id: Some("api".to_string()),
         notes: request.notes,
+        tags: Vec::new(),
+        name: request
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty()),
     };
 
     match state
@@ -290,16 +1020,22 @@ async fn create_subscriber(
                 .database()
                 .get_subscriber_by_email(&subscriber.email)
             {
-                Ok(Some(created_subscriber)) => Ok(Json(ApiResponse::success(created_subscriber))),
+                Ok(Some(created_subscriber)) => {
+                    notify(&state, WebhookEvent::Created, &created_subscriber);
+                    if created_subscriber.status == SubscriberStatus::Approved {
+                        notify(&state, WebhookEvent::Approved, &created_subscriber);
+                    }
+                    Ok(Json(ApiResponse::success(created_subscriber)))
+                }
                 Ok(None) => Err(StatusCode::INTERNAL_SERVER_ERROR),
                 Err(e) => {
-                    eprintln!("Failed to fetch created subscriber: {}", e);
+                    tracing::error!("Failed to fetch created subscriber: {}", e);
                     Err(StatusCode::INTERNAL_SERVER_ERROR)
                 }
             }
         }
         Err(e) => {
-            eprintln!("Failed to create subscriber: {}", e);
+            tracing::error!("Failed to create subscriber: {}", e);
             if e.to_string().contains("UNIQUE constraint failed") {
                 Err(StatusCode::CONFLICT)
             } else {
@@ -322,7 +1058,7 @@ async fn get_subscriber(
         Ok(Some(subscriber)) => Ok(Json(ApiResponse::success(subscriber))),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(e) => {
-            eprintln!("Failed to get subscriber: {}", e);
+            tracing::error!("Failed to get subscriber: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
@@ -335,7 +1071,7 @@ async fn update_subscriber(
     Json(request): Json<UpdateSubscriberRequest>,
 ) -> Result<Json<ApiResponse<Subscriber>>, StatusCode> {
     // First, get the existing subscriber
-    let mut subscriber = match state
+    let subscriber = match state
         .newsletter_manager
         .database()
         .get_subscriber_by_email(&email)
@@ -343,38 +1079,46 @@ async fn update_subscriber(
         Ok(Some(subscriber)) => subscriber,
         Ok(None) => return Err(StatusCode::NOT_FOUND),
         Err(e) => {
-            eprintln!("Failed to get subscriber: {}", e);
+            tracing::error!("Failed to get subscriber: {}", e);
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
 
-    // Update fields
-    if let Some(status) = request.status {
-        subscriber.status = status.clone();
-        if status == SubscriberStatus::Approved && subscriber.approved_at.is_none() {
-            subscriber.approved_at = Some(chrono::Utc::now());
+    let database = state.newsletter_manager.database();
+    let id = subscriber.id.ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let newly_approved = request.status == Some(SubscriberStatus::Approved)
+        && subscriber.status != SubscriberStatus::Approved;
+
+    let result = (|| -> Result<Option<Subscriber>> {
+        if let Some(status) = request.status {
+            if status != subscriber.status {
+                database.update_subscriber_status(id, status)?;
+            }
         }
-    }
+        if let Some(notes) = request.notes.as_deref() {
+            let notes = notes.trim();
+            database.set_subscriber_notes(&email, (!notes.is_empty()).then_some(notes))?;
+        }
+        if let Some(name) = request.name.as_deref() {
+            let name = name.trim();
+            database.set_subscriber_name(&email, (!name.is_empty()).then_some(name))?;
+        }
+        database.get_subscriber_by_email(&email)
+    })();
 
-    if let Some(notes) = request.notes {
-        subscriber.notes = Some(notes);
+    match result {
+        Ok(Some(subscriber)) => {
+            if newly_approved {
+                notify(&state, WebhookEvent::Approved, &subscriber);
+            }
+            Ok(Json(ApiResponse::success(subscriber)))
+        }
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to update subscriber: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
     }
-
-    // Save changes (commented out - update_subscriber method needs to be implemented)
-    // match state
-    //     .newsletter_manager
-    //     .database()
-    //     .update_subscriber(&subscriber)
-    // {
-    //     Ok(_) => Ok(Json(ApiResponse::success(subscriber))),
-    //     Err(e) => {
-    //         eprintln!("Failed to update subscriber: {}", e);
-    //         Err(StatusCode::INTERNAL_SERVER_ERROR)
-    //     }
-    // }
-
-    // Temporary placeholder response
-    Ok(Json(ApiResponse::success(subscriber)))
 }
 
 /// Delete subscriber endpoint
@@ -382,20 +1126,84 @@ async fn delete_subscriber(
     State(state): State<ApiState>,
     Path(email): Path<String>,
 ) -> Result<Json<ApiResponse<()>>, StatusCode> {
-    match state
-        .newsletter_manager
-        .database()
-        .remove_subscriber(&email)
-    {
-        Ok(true) => Ok(Json(ApiResponse::success(()))),
-        Ok(false) => Err(StatusCode::NOT_FOUND),
+    let database = state.newsletter_manager.database();
+    let result = database
+        .get_subscriber_by_email(&email)
+        .and_then(|subscriber| match subscriber {
+            Some(subscriber) => Ok(database.remove_subscriber(&email)?.then_some(subscriber)),
+            None => Ok(None),
+        });
+
+    match result {
+        Ok(Some(subscriber)) => {
+            notify(&state, WebhookEvent::Removed, &subscriber);
+            Ok(Json(ApiResponse::success(())))
+        }
+        Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(e) => {
-            eprintln!("Failed to delete subscriber: {}", e);
+            tracing::error!("Failed to delete subscriber: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
+/// Approve, decline or delete many subscribers in one request
+async fn bulk_subscribers(
+    State(state): State<ApiState>,
+    Json(request): Json<BulkRequest>,
+) -> Result<Json<ApiResponse<BulkResponse>>, StatusCode> {
+    if request.emails.len() > MAX_PAGE_SIZE {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    match apply_bulk_action(&state, &request) {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            tracing::error!("Failed to apply bulk action: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn apply_bulk_action(state: &ApiState, request: &BulkRequest) -> Result<BulkResponse> {
+    let database = state.newsletter_manager.database();
+    let mut response = BulkResponse {
+        processed: 0,
+        not_found: Vec::new(),
+    };
+
+    for email in &request.emails {
+        let Some(subscriber) = database.get_subscriber_by_email(email)? else {
+            response.not_found.push(email.clone());
+            continue;
+        };
+        let id = subscriber
+            .id
+            .ok_or_else(|| anyhow::anyhow!("Subscriber {} has no ID", email))?;
+
+        match request.action {
+            BulkAction::Approve => {
+                database.update_subscriber_status(id, SubscriberStatus::Approved)?;
+                if subscriber.status != SubscriberStatus::Approved {
+                    if let Some(approved) = database.get_subscriber_by_email(email)? {
+                        notify(state, WebhookEvent::Approved, &approved);
+                    }
+                }
+            }
+            BulkAction::Decline => {
+                database.update_subscriber_status(id, SubscriberStatus::Declined)?;
+            }
+            BulkAction::Delete => {
+                database.remove_subscriber(email)?;
+                notify(state, WebhookEvent::Removed, &subscriber);
+            }
+        }
+        response.processed += 1;
+    }
+
+    Ok(response)
+}
+
 /// Send newsletter endpoint
 async fn send_newsletter(
     State(_state): State<ApiState>,
@@ -452,13 +1260,24 @@ async fn import_subscribers(
     Ok(Json(ApiResponse::success(response)))
 }
 
-/// Export subscribers endpoint
+/// Export subscribers endpoint; takes the same filters as the list, without paging
 async fn export_subscribers(
     State(state): State<ApiState>,
     Query(params): Query<SubscriberQuery>,
 ) -> Result<Json<ApiResponse<Vec<Subscriber>>>, StatusCode> {
-    // Reuse the list_subscribers logic for export
-    list_subscribers(State(state), Query(params)).await
+    let filter = params.filter()?;
+
+    match state.newsletter_manager.database().query_subscribers(
+        &filter,
+        params.limit,
+        params.offset.unwrap_or(0),
+    ) {
+        Ok((subscribers, _)) => Ok(Json(ApiResponse::success(subscribers))),
+        Err(e) => {
+            tracing::error!("Failed to export subscribers: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
 }
 
 /// Get statistics endpoint
@@ -468,7 +1287,7 @@ async fn get_stats(
     let all_subscribers = match state.newsletter_manager.database().get_subscribers(None) {
         Ok(subscribers) => subscribers,
         Err(e) => {
-            eprintln!("Failed to get subscribers for stats: {}", e);
+            tracing::error!("Failed to get subscribers for stats: {}", e);
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
@@ -497,6 +1316,40 @@ async fn get_stats(
     Ok(Json(ApiResponse::success(stats)))
 }
 
+/// Tracking pixel endpoint; always returns the pixel so emails render normally
+async fn track_open(State(state): State<ApiState>, Path(token): Path<String>) -> impl IntoResponse {
+    if let Err(e) = state.newsletter_manager.database().record_open(&token) {
+        tracing::error!("Failed to record open: {}", e);
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, "image/gif"),
+            (header::CACHE_CONTROL, "no-store, max-age=0"),
+        ],
+        super::tracking::PIXEL_GIF,
+    )
+}
+
+/// Tracked link endpoint; redirects only to links stored for the send
+async fn track_click(
+    State(state): State<ApiState>,
+    Path((token, link)): Path<(String, i64)>,
+) -> Result<Redirect, StatusCode> {
+    match state
+        .newsletter_manager
+        .database()
+        .record_click(&token, link)
+    {
+        Ok(Some(url)) => Ok(Redirect::to(&url)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to record click: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -516,9 +1369,95 @@ mod tests {
             newsletter_manager: Arc::new(newsletter_manager),
             config: Arc::new(config),
             api_config: Arc::new(ApiConfig::default()),
+            webhooks: Arc::new(WebhookDispatcher::new(Vec::new())),
+            subscribe_limiter: Arc::new(RateLimiter::default()),
+            plugins: Arc::new(PluginManager::new(temp_dir.path().to_path_buf())),
+        }
+    }
+
+    /// Serve the full router on a free port and return its base URL
+    async fn spawn_server(temp_dir: &std::path::Path, api_key: Option<&str>) -> String {
+        let config = Config::default();
+        let newsletter_manager = NewsletterManager::new(config.clone(), temp_dir).unwrap();
+        let api_config = ApiConfig {
+            api_key: api_key.map(str::to_string),
+            ..ApiConfig::default()
+        };
+        let app = NewsletterApiServer::new(newsletter_manager, config, api_config).create_router();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_api_key_required() {
+        let temp_dir = tempdir().unwrap();
+        let base = spawn_server(temp_dir.path(), Some("secret")).await;
+        let client = reqwest::Client::new();
+
+        for (method, path) in [
+            (reqwest::Method::GET, "/subscribers"),
+            (reqwest::Method::GET, "/export"),
+            (reqwest::Method::POST, "/subscribers/bulk"),
+            (reqwest::Method::DELETE, "/subscribers/a@example.com"),
+        ] {
+            let url = format!("{}{}", base, path);
+            let response = client.request(method.clone(), &url).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{}", path);
+            let response = client
+                .request(method, &url)
+                .bearer_auth("wrong")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{}", path);
+        }
+
+        let response = client
+            .get(format!("{}/subscribers", base))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for path in ["/health", "/openapi.json", "/docs"] {
+            let response = client
+                .get(format!("{}{}", base, path))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", path);
         }
     }
 
+    #[tokio::test]
+    async fn test_protected_routes_closed_without_key() {
+        let temp_dir = tempdir().unwrap();
+        let base = spawn_server(temp_dir.path(), None).await;
+
+        let response = reqwest::get(format!("{}/export", base)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = reqwest::get(format!("{}/health", base)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_key_matches() {
+        assert!(key_matches("secret", "secret"));
+        assert!(!key_matches("secret", "secreT"));
+        assert!(!key_matches("secret", "secret2"));
+        assert!(!key_matches("secret", ""));
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         let response = health_check().await;
@@ -526,6 +1465,126 @@ mod tests {
         assert!(response.0.data.is_some());
     }
 
+    #[test]
+    fn test_openapi_refs_resolve() {
+        let endpoints = endpoints();
+        let spec = openapi::document(
+            endpoints
+                .iter()
+                .map(|endpoint| (endpoint.method, endpoint.path, &endpoint.doc)),
+        );
+        let schemas = &spec["components"]["schemas"];
+
+        fn check_refs(value: &serde_json::Value, schemas: &serde_json::Value) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    if let Some(reference) = map.get("$ref").and_then(|r| r.as_str()) {
+                        let name = reference.trim_start_matches("#/components/schemas/");
+                        assert!(schemas.get(name).is_some(), "missing schema {}", name);
+                    }
+                    map.values().for_each(|v| check_refs(v, schemas));
+                }
+                serde_json::Value::Array(items) => {
+                    items.iter().for_each(|v| check_refs(v, schemas))
+                }
+                _ => {}
+            }
+        }
+        check_refs(&spec, schemas);
+
+        assert!(spec["paths"]["/subscribers"]["get"].is_object());
+        assert!(spec["paths"]["/subscribers"]["post"].is_object());
+    }
+
+    #[test]
+    fn test_openapi_security_matches_router() {
+        let endpoints = endpoints();
+        let spec = openapi::document(
+            endpoints
+                .iter()
+                .map(|endpoint| (endpoint.method, endpoint.path, &endpoint.doc)),
+        );
+        assert_eq!(
+            spec["components"]["securitySchemes"]["apiKey"]["scheme"],
+            "bearer"
+        );
+
+        for endpoint in &endpoints {
+            let operation = &spec["paths"][endpoint.path][endpoint.method.to_lowercase()];
+            assert_eq!(
+                operation.get("security") == Some(&serde_json::json!([])),
+                endpoint.doc.public,
+                "{} {}",
+                endpoint.method,
+                endpoint.path
+            );
+        }
+
+        let protected: Vec<_> = endpoints
+            .iter()
+            .filter(|endpoint| !endpoint.doc.public)
+            .map(|endpoint| endpoint.path)
+            .collect();
+        assert!(protected.contains(&"/subscribers/bulk"));
+        assert!(protected.contains(&"/export"));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::default();
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let other: IpAddr = "203.0.113.8".parse().unwrap();
+        let start = Instant::now();
+
+        for _ in 0..SUBSCRIBE_LIMIT {
+            assert!(limiter.allow(client, start));
+        }
+        assert!(!limiter.allow(client, start));
+        assert!(limiter.allow(other, start));
+
+        // Attempts age out of the window
+        assert!(limiter.allow(client, start + SUBSCRIBE_WINDOW));
+    }
+
+    #[test]
+    fn test_contact_redirect() {
+        let base = "https://blog.example.com/";
+        assert_eq!(
+            contact_redirect("https://blog.example.com/contact/thanks.html", base)
+                .unwrap()
+                .as_str(),
+            "https://blog.example.com/contact/thanks.html"
+        );
+        assert_eq!(
+            contact_redirect("/contact/thanks.html", base)
+                .unwrap()
+                .as_str(),
+            "https://blog.example.com/contact/thanks.html"
+        );
+        assert!(contact_redirect("https://evil.example/phish", base).is_none());
+        assert!(contact_redirect("http://blog.example.com/", base).is_none());
+        assert!(contact_redirect("//evil.example/phish", base).is_none());
+        assert!(contact_redirect("/\\evil.example", base).is_none());
+        assert!(contact_redirect("javascript:alert(1)", base).is_none());
+        assert!(contact_redirect("", base).is_none());
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "198.51.100.1, 10.0.0.1".parse().unwrap());
+
+        let proxied: SocketAddr = "127.0.0.1:5000".parse().unwrap();
+        assert_eq!(
+            client_ip(proxied, &headers),
+            "198.51.100.1".parse::<IpAddr>().unwrap()
+        );
+
+        // Only a local proxy is trusted to report the client address
+        let direct: SocketAddr = "203.0.113.7:5000".parse().unwrap();
+        assert_eq!(client_ip(direct, &headers), direct.ip());
+    }
+
     // #[tokio::test]
     // async fn test_create_subscriber() {
     //     let state = create_test_state().await;
@@ -0,0 +1,191 @@
+//! State for `blogr newsletter auto`
+//!
+//! The command runs from cron and mails new posts to subscribers. The slugs
+//! of posts that were already sent (or were on the site before automation
+//! started) are kept in `.blogr/newsletter-auto.json`, and a lock file stops
+//! two runs from sending the same post twice.
+
+use crate::content::Post;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const STATE_FILE: &str = ".blogr/newsletter-auto.json";
+const LOCK_FILE: &str = ".blogr/newsletter-auto.lock";
+
+/// Locks older than this are left over from a run that crashed
+const STALE_LOCK: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Posts that automation has already taken care of
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AutoState {
+    /// When automation started watching for posts
+    pub since: Option<DateTime<Utc>>,
+    /// Slugs of posts that were sent, or existed before `since`
+    pub announced: BTreeSet<String>,
+}
+
+impl AutoState {
+    /// Load the state of a project; `None` before the first run
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(STATE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let state = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(state))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = project_root.join(STATE_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// State for a first run: everything already published counts as sent
+    pub fn starting_now<'a>(posts: impl IntoIterator<Item = &'a Post>) -> Self {
+        Self {
+            since: Some(Utc::now()),
+            announced: posts
+                .into_iter()
+                .map(|post| post.metadata.slug.clone())
+                .collect(),
+        }
+    }
+
+    /// Posts that haven't been sent yet, in the order given
+    pub fn new_posts<'a>(&self, posts: &'a [Post]) -> Vec<&'a Post> {
+        posts
+            .iter()
+            .filter(|post| !self.announced.contains(&post.metadata.slug))
+            .filter(|post| !post.metadata.private)
+            .collect()
+    }
+
+    /// Mark new private posts as sent without mailing them, so they are only
+    /// reported once. Returns them.
+    pub fn skip_private<'a>(&mut self, posts: &'a [Post]) -> Vec<&'a Post> {
+        let private: Vec<&Post> = posts
+            .iter()
+            .filter(|post| post.metadata.private)
+            .filter(|post| !self.announced.contains(&post.metadata.slug))
+            .collect();
+        self.mark_sent(private.iter().copied());
+        private
+    }
+
+    pub fn mark_sent<'a>(&mut self, posts: impl IntoIterator<Item = &'a Post>) {
+        self.announced
+            .extend(posts.into_iter().map(|post| post.metadata.slug.clone()));
+    }
+}
+
+/// Held while a run is in progress; the lock file is removed on drop
+pub struct AutoLock {
+    path: PathBuf,
+}
+
+impl AutoLock {
+    pub fn acquire(project_root: &Path) -> Result<Self> {
+        let path = project_root.join(LOCK_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id())?;
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+                    if age.is_some_and(|age| age < STALE_LOCK) {
+                        bail!(
+                            "Another 'blogr newsletter auto' is running ({} exists). Delete it if that run was interrupted.",
+                            path.display()
+                        );
+                    }
+                    tracing::warn!("Removing stale lock file {}", path.display());
+                    fs::remove_file(&path)?;
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to create {}", path.display()))
+                }
+            }
+        }
+        bail!("Failed to create {}", path.display())
+    }
+}
+
+impl Drop for AutoLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::PostStatus;
+    use tempfile::TempDir;
+
+    fn post(slug: &str) -> Post {
+        Post::new(
+            slug.to_string(),
+            "Ann".to_string(),
+            None,
+            Vec::new(),
+            Some(slug.to_string()),
+            PostStatus::Published,
+        )
+    }
+
+    #[test]
+    fn test_state_and_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        assert!(AutoState::load(root).unwrap().is_none());
+
+        let mut state = AutoState::starting_now(&[post("old")]);
+        let posts = [post("new"), post("old")];
+        let new: Vec<&str> = state
+            .new_posts(&posts)
+            .iter()
+            .map(|post| post.metadata.slug.as_str())
+            .collect();
+        assert_eq!(new, ["new"]);
+
+        state.mark_sent(&posts[..1]);
+        state.save(root).unwrap();
+        let mut state = AutoState::load(root).unwrap().unwrap();
+        assert!(state.new_posts(&posts).is_empty());
+
+        let mut secret = post("secret");
+        secret.metadata.private = true;
+        let posts = [secret];
+        assert!(state.new_posts(&posts).is_empty());
+        assert_eq!(state.skip_private(&posts).len(), 1);
+        assert!(state.announced.contains("secret"));
+        assert!(state.skip_private(&posts).is_empty());
+
+        let lock = AutoLock::acquire(root).unwrap();
+        assert!(AutoLock::acquire(root).is_err());
+        drop(lock);
+        assert!(AutoLock::acquire(root).is_ok());
+    }
+}
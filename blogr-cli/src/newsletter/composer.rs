@@ -1,18 +1,48 @@
 //! Email composition for newsletters
 //!
 //! This module handles converting blog posts to email format,
-//! creating custom newsletters, and managing email templates.
+//! creating custom newsletters and digests of several posts, and managing
+//! email templates.
+//!
+//! Besides the built-in layouts, projects can ship their own email templates
+//! in `newsletter/templates/*.html` and pick one per send. The plain text part
+//! is rendered from the Markdown with `email/text.txt`, or with a project
+//! `<template>.txt` or `default.txt` in place of it.
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use blogr_themes::Theme;
 use chrono::{DateTime, Utc};
-use html2text::from_read;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 use tera::{Context as TeraContext, Tera};
 
 use crate::config::Config;
 use crate::content::Post;
-use crate::generator::markdown;
+use crate::generator::{markdown, shortcodes};
+use crate::newsletter::plaintext::{self, markdown_to_plain};
+use crate::newsletter::sender::{verbatim, Personalization};
+
+/// Built-in email templates that can be selected by name
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("default", "Simple layout used when no template is chosen"),
+    ("table", "Table-based layout with brand color and logo"),
+];
+
+/// Prefix under which project templates are registered with Tera
+const PROJECT_TEMPLATE_PREFIX: &str = "newsletter/";
+
+/// Recipient shown in previews
+const SAMPLE_NAME: &str = "Ada Lovelace";
+const SAMPLE_EMAIL: &str = "ada@example.com";
+
+/// `subscriber` in email templates: tokens the sender fills in for each recipient
+fn subscriber_tokens() -> HashMap<&'static str, &'static str> {
+    HashMap::from([
+        ("name", "{{subscriber.name}}"),
+        ("email", "{{subscriber.email}}"),
+    ])
+}
 
 /// Site context for email templates
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +57,28 @@ pub struct SiteContext {
     pub timezone: Option<String>,
 }
 
+/// Branding context for email templates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrandContext {
+    pub color: String,
+    pub logo_url: Option<String>,
+}
+
+/// A post as listed in a digest
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestPost {
+    pub title: String,
+    pub url: String,
+    pub date: DateTime<Utc>,
+    pub author: String,
+    pub tags: Vec<String>,
+    /// HTML: the part before `<!--more-->`, else the description, else the first words
+    pub summary: String,
+    /// The summary as plain text
+    pub summary_text: String,
+    pub reading_time: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Newsletter {
     pub subject: String,
@@ -59,6 +111,8 @@ pub struct NewsletterComposer {
     theme: Box<dyn Theme>,
     config: Config,
     tera: Tera,
+    /// Selected template; `None` uses the default post/custom layouts
+    template: Option<String>,
 }
 
 impl NewsletterComposer {
@@ -75,14 +129,140 @@ impl NewsletterComposer {
         tera.add_raw_template("email/base.html", email_base_template)?;
         tera.add_raw_template("email/post.html", email_post_template)?;
         tera.add_raw_template("email/custom.html", email_custom_template)?;
+        tera.add_raw_template(
+            "email/digest.html",
+            include_str!("../templates/email/digest.html"),
+        )?;
+        tera.add_raw_template(
+            "email/digest_posts.html",
+            include_str!("../templates/email/digest_posts.html"),
+        )?;
+        tera.add_raw_template(
+            "email/text.txt",
+            include_str!("../templates/email/text.txt"),
+        )?;
+        tera.add_raw_template(
+            "email/table.html",
+            include_str!("../templates/email/table.html"),
+        )?;
 
         Ok(Self {
             theme,
             config,
             tera,
+            template: None,
         })
     }
 
+    /// Register project email templates from a directory (e.g. `newsletter/templates`)
+    pub fn with_templates_dir(mut self, dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            return Ok(self);
+        }
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read templates from {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("html" | "txt")
+            ) {
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    let name = format!("{}{}", PROJECT_TEMPLATE_PREFIX, name);
+                    files.push((path.clone(), Some(name)));
+                }
+            }
+        }
+
+        self.tera
+            .add_template_files(files)
+            .map_err(|e| anyhow!("Failed to load newsletter templates: {}", e))?;
+        Ok(self)
+    }
+
+    /// Select the email template by name, falling back to `newsletter.template` in config.
+    ///
+    /// Project templates in `newsletter/templates` take precedence over built-in ones.
+    pub fn with_template(mut self, name: Option<&str>) -> Result<Self> {
+        let name = name
+            .map(str::to_string)
+            .or_else(|| self.config.newsletter.template.clone());
+
+        self.template = match name.as_deref().map(|n| n.trim_end_matches(".html")) {
+            None | Some("default") => None,
+            Some(name) => {
+                let project = format!("{}{}.html", PROJECT_TEMPLATE_PREFIX, name);
+                if self.tera.get_template_names().any(|t| t == project) {
+                    Some(project)
+                } else if BUILTIN_TEMPLATES
+                    .iter()
+                    .any(|(builtin, _)| *builtin == name)
+                {
+                    Some(format!("email/{}.html", name))
+                } else {
+                    return Err(anyhow!(
+                        "Unknown newsletter template '{}'. Available templates: {}",
+                        name,
+                        self.available_templates().join(", ")
+                    ));
+                }
+            }
+        };
+
+        Ok(self)
+    }
+
+    /// Select the template for digests, falling back to `newsletter.digest_template`
+    /// and then to the built-in digest layout
+    pub fn with_digest_template(self, name: Option<&str>) -> Result<Self> {
+        let name = name
+            .map(str::to_string)
+            .or_else(|| self.config.newsletter.digest_template.clone())
+            .unwrap_or_else(|| "default".to_string());
+        self.with_template(Some(&name))
+    }
+
+    /// Names of all selectable templates, built-in first
+    pub fn available_templates(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_TEMPLATES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        let mut project: Vec<String> = self
+            .tera
+            .get_template_names()
+            .filter_map(|t| t.strip_prefix(PROJECT_TEMPLATE_PREFIX))
+            .filter_map(|t| t.strip_suffix(".html"))
+            .map(str::to_string)
+            .filter(|t| !names.contains(t))
+            .collect();
+        project.sort();
+
+        names.extend(project);
+        names
+    }
+
+    /// Descriptions of the built-in templates
+    pub fn builtin_templates() -> &'static [(&'static str, &'static str)] {
+        BUILTIN_TEMPLATES
+    }
+
+    /// Create branding context for email templates
+    fn create_brand_context(&self) -> BrandContext {
+        BrandContext {
+            color: self
+                .config
+                .newsletter
+                .brand_color
+                .clone()
+                .unwrap_or_else(|| "#2c3e50".to_string()),
+            logo_url: self.config.newsletter.logo_url.clone(),
+        }
+    }
+
     /// Create site context for email templates
     fn create_site_context(&self) -> SiteContext {
         let email = self
@@ -107,46 +287,70 @@ impl NewsletterComposer {
 
     /// Compose newsletter from a blog post
     pub fn compose_from_post(&self, post: &Post) -> Result<Newsletter> {
+        if post.metadata.private {
+            return Err(anyhow!(
+                "'{}' is a private post and can't be sent as a newsletter",
+                post.metadata.title
+            ));
+        }
+
         let mut context = TeraContext::new();
 
         // Add site config with email support
         let site_context = self.create_site_context();
         context.insert("site", &site_context);
 
+        context.insert("brand", &self.create_brand_context());
+        context.insert("kind", "post");
+
         // Add post data
         context.insert("post", post);
+        context.insert(
+            "post_url",
+            &self
+                .config
+                .page_url(&format!("posts/{}.html", post.metadata.slug)),
+        );
 
-        // Convert markdown to HTML
+        // Convert markdown to HTML. The post is sent as written, so tokens in
+        // it aren't personalized.
         let html_content = markdown::render_markdown(&post.content)?;
-        context.insert("content", &html_content);
+        context.insert("content", &verbatim(&html_content));
+        let post_text = markdown_to_plain(
+            &shortcodes::expand(&post.content, |_| Ok(String::new()))?,
+            plaintext::WIDTH,
+        );
+        let post_text = verbatim(post_text.trim_end());
 
-        // Calculate reading time
-        let word_count = post.content.split_whitespace().count();
-        let reading_time = (word_count / 200).max(1);
-        context.insert("reading_time", &reading_time);
+        let reading = post.reading_time(&self.config);
+        context.insert("reading_time", &reading.minutes);
+        context.insert("word_count", &reading.words);
 
         // Add newsletter metadata
         context.insert("newsletter_title", &self.get_newsletter_title());
+        context.insert("subscriber", &subscriber_tokens());
         context.insert(
             "unsubscribe_url",
             &self.generate_unsubscribe_url("{{unsubscribe_token}}"),
         );
 
+        // Generate subject line
+        let subject = format!("{}: {}", self.get_newsletter_title(), post.metadata.title);
+        context.insert("subject", &subject);
+
         // Render HTML email
+        let template = self.template.as_deref().unwrap_or("email/post.html");
         let html_email = self
             .tera
-            .render("email/post.html", &context)
-            .context("Failed to render email template")?;
+            .render(template, &context)
+            .with_context(|| format!("Failed to render email template '{}'", template))?;
 
         // Inline CSS for better email client compatibility
         let inlined_html = css_inline::inline(&html_email)
             .map_err(|e| anyhow::anyhow!("Failed to inline CSS: {}", e))?;
 
-        // Generate plain text version
-        let text_content = self.html_to_text(&inlined_html)?;
-
-        // Generate subject line
-        let subject = format!("{}: {}", self.get_newsletter_title(), post.metadata.title);
+        // Plain text version from the Markdown source
+        let text_content = self.render_text(&mut context, &post_text)?;
 
         Ok(Newsletter::new(subject, inlined_html, text_content))
     }
@@ -158,13 +362,17 @@ impl NewsletterComposer {
         // Add site config with email support
         let site_context = self.create_site_context();
         context.insert("site", &site_context);
+        context.insert("brand", &self.create_brand_context());
+        context.insert("kind", "custom");
 
         // Convert markdown content to HTML
         let html_content = markdown::render_markdown(&content)?;
         context.insert("content", &html_content);
+        let content_text = markdown_to_plain(&content, plaintext::WIDTH);
 
         // Add newsletter metadata
         context.insert("newsletter_title", &self.get_newsletter_title());
+        context.insert("subscriber", &subscriber_tokens());
         context.insert(
             "unsubscribe_url",
             &self.generate_unsubscribe_url("{{unsubscribe_token}}"),
@@ -172,25 +380,133 @@ impl NewsletterComposer {
         context.insert("subject", &subject);
 
         // Render HTML email
+        let template = self.template.as_deref().unwrap_or("email/custom.html");
         let html_email = self
             .tera
-            .render("email/custom.html", &context)
-            .context("Failed to render custom email template")?;
+            .render(template, &context)
+            .with_context(|| format!("Failed to render custom email template '{}'", template))?;
 
         // Inline CSS for better email client compatibility
         let inlined_html = css_inline::inline(&html_email)
             .map_err(|e| anyhow::anyhow!("Failed to inline CSS: {}", e))?;
 
-        // Generate plain text version
-        let text_content = self.html_to_text(&inlined_html)?;
+        // Plain text version from the Markdown source
+        let text_content = self.render_text(&mut context, &content_text)?;
 
         Ok(Newsletter::new(subject, inlined_html, text_content))
     }
 
-    /// Preview newsletter in terminal
+    /// Compose a digest of `posts`, newest first, published since `since`.
+    ///
+    /// `content` is the list of posts with their summaries, so any template
+    /// works; digest templates can also loop over `posts` themselves.
+    pub fn compose_digest(
+        &self,
+        posts: &[Post],
+        since: Option<DateTime<Utc>>,
+        subject: Option<String>,
+    ) -> Result<Newsletter> {
+        if let Some(post) = posts.iter().find(|post| post.metadata.private) {
+            return Err(anyhow!(
+                "'{}' is a private post and can't be sent in a digest",
+                post.metadata.title
+            ));
+        }
+
+        let mut context = TeraContext::new();
+
+        context.insert("site", &self.create_site_context());
+        context.insert("brand", &self.create_brand_context());
+        context.insert("kind", "digest");
+
+        let digest_posts = posts
+            .iter()
+            .map(|post| self.digest_post(post))
+            .collect::<Result<Vec<_>>>()?;
+        context.insert("posts", &digest_posts);
+        context.insert("since", &since);
+
+        context.insert("newsletter_title", &self.get_newsletter_title());
+        context.insert("subscriber", &subscriber_tokens());
+        context.insert(
+            "unsubscribe_url",
+            &self.generate_unsubscribe_url("{{unsubscribe_token}}"),
+        );
+
+        let subject = subject.unwrap_or_else(|| {
+            format!(
+                "{}: {} new post{}",
+                self.get_newsletter_title(),
+                posts.len(),
+                if posts.len() == 1 { "" } else { "s" }
+            )
+        });
+        context.insert("subject", &subject);
+
+        let html_content = self
+            .tera
+            .render("email/digest_posts.html", &context)
+            .context("Failed to render digest posts")?;
+        context.insert("content", &verbatim(&html_content));
+
+        // Render HTML email
+        let template = self.template.as_deref().unwrap_or("email/digest.html");
+        let html_email = self
+            .tera
+            .render(template, &context)
+            .with_context(|| format!("Failed to render digest email template '{}'", template))?;
+
+        // Inline CSS for better email client compatibility
+        let inlined_html = css_inline::inline(&html_email)
+            .map_err(|e| anyhow::anyhow!("Failed to inline CSS: {}", e))?;
+
+        let text_content = self.render_text(&mut context, "")?;
+
+        Ok(Newsletter::new(subject, inlined_html, text_content))
+    }
+
+    /// A post with its summary, the way the site's listings summarize it
+    fn digest_post(&self, post: &Post) -> Result<DigestPost> {
+        let (summary, summary_text) = match post.summary_markdown() {
+            Some(markdown) => (
+                markdown::render_markdown(markdown)?,
+                markdown_to_plain(markdown, plaintext::WIDTH),
+            ),
+            None => {
+                let description = post.metadata.description.trim();
+                let text = if description.is_empty() {
+                    let content = shortcodes::expand(&post.content, |_| Ok(String::new()))?;
+                    markdown::extract_excerpt(&content, self.config.markdown.summary_words)
+                } else {
+                    description.to_string()
+                };
+                (
+                    format!("<p>{}</p>", markdown::html_escape(&text)),
+                    markdown_to_plain(&text, plaintext::WIDTH),
+                )
+            }
+        };
+
+        Ok(DigestPost {
+            title: post.display_title(),
+            url: self
+                .config
+                .page_url(&format!("posts/{}.html", post.metadata.slug)),
+            date: post.metadata.date,
+            author: post.metadata.author.clone(),
+            tags: post.metadata.tags.clone(),
+            summary: verbatim(&summary),
+            summary_text: verbatim(summary_text.trim_end()),
+            reading_time: post.reading_time(&self.config).minutes,
+        })
+    }
+
+    /// Preview newsletter in terminal, personalized for a sample subscriber
     pub fn preview_in_terminal(&self, newsletter: &Newsletter) -> Result<()> {
+        let newsletter = &Self::sample_personalization(newsletter);
         println!("📧 Newsletter Preview");
         println!("══════════════════════════════════════");
+        println!("To: {} <{}> (sample subscriber)", SAMPLE_NAME, SAMPLE_EMAIL);
         println!("Subject: {}", newsletter.subject);
         println!(
             "Created: {}",
@@ -220,12 +536,58 @@ impl NewsletterComposer {
         Ok(())
     }
 
-    /// Convert HTML to plain text
-    fn html_to_text(&self, html: &str) -> Result<String> {
-        let text = from_read(html.as_bytes(), 80);
+    /// The newsletter as the sample subscriber would get it
+    pub fn sample_personalization(newsletter: &Newsletter) -> Newsletter {
+        let values = Personalization {
+            name: SAMPLE_NAME,
+            email: SAMPLE_EMAIL,
+            unsubscribe_url: "mailto:unsubscribe@example.com?subject=Unsubscribe",
+            unsubscribe_token: "sample-token",
+        };
+        let mut personalized = newsletter.clone();
+        personalized.subject = values.apply(&newsletter.subject, false);
+        personalized.html_content = values.apply(&newsletter.html_content, true);
+        personalized.text_content = values.apply(&newsletter.text_content, false);
+        personalized
+    }
+
+    /// Render the plain text part; `text` is the body as plain text
+    fn render_text(&self, context: &mut TeraContext, text: &str) -> Result<String> {
+        context.insert("text", text);
+        let template = self.text_template();
+        let rendered = self
+            .tera
+            .render(&template, context)
+            .with_context(|| format!("Failed to render text template '{}'", template))?;
+
+        // Template tags leave blank lines behind; keep one at most
+        let mut text = String::new();
+        let mut blank = false;
+        for line in rendered.trim().lines().map(str::trim_end) {
+            if line.is_empty() && blank {
+                continue;
+            }
+            blank = line.is_empty();
+            text.push_str(line);
+            text.push('\n');
+        }
         Ok(text)
     }
 
+    /// `<template>.txt` next to a project template, else a project `default.txt`,
+    /// else the built-in layout
+    fn text_template(&self) -> String {
+        let names: Vec<&str> = self.tera.get_template_names().collect();
+        self.template
+            .as_deref()
+            .and_then(|template| template.strip_suffix(".html"))
+            .map(|template| format!("{}.txt", template))
+            .into_iter()
+            .chain([format!("{}default.txt", PROJECT_TEMPLATE_PREFIX)])
+            .find(|template| names.contains(&template.as_str()))
+            .unwrap_or_else(|| "email/text.txt".to_string())
+    }
+
     /// Get newsletter title from config
     fn get_newsletter_title(&self) -> String {
         self.config
@@ -322,7 +684,6 @@ mod tests {
     use chrono::Utc;
     use std::path::PathBuf;
 
-    #[allow(dead_code)]
     fn create_test_post() -> Post {
         Post {
             metadata: PostMetadata {
@@ -334,6 +695,17 @@ mod tests {
                 status: PostStatus::Published,
                 slug: "test-post".to_string(),
                 featured: false,
+                aliases: Vec::new(),
+                updated_at: None,
+                contributors: Vec::new(),
+                crosspost: Default::default(),
+                video: None,
+                link: None,
+                cover: None,
+                cover_alt: None,
+                noindex: false,
+                private: false,
+                password: None,
             },
             content: "# Test Content\n\nThis is a test post with some **bold** text.".to_string(),
             file_path: PathBuf::from("test.md"),
@@ -352,4 +724,176 @@ mod tests {
         assert!(newsletter.html_content.contains("Test"));
         assert_eq!(newsletter.text_content, "Test");
     }
+
+    #[test]
+    fn test_project_templates() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("digest.html"),
+            "<html><body><h1>{{ subject }}</h1>{{ content | safe }}\
+             <a href=\"{{ unsubscribe_url }}\">Unsubscribe</a>\
+             <p style=\"color: {{ brand.color }}\">{{ kind }}</p></body></html>",
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.newsletter.brand_color = Some("#ff6600".to_string());
+        let theme = blogr_themes::get_theme("minimal-retro").unwrap();
+
+        let composer = NewsletterComposer::new(theme, config)
+            .unwrap()
+            .with_templates_dir(temp_dir.path())
+            .unwrap();
+        assert!(composer
+            .available_templates()
+            .contains(&"digest".to_string()));
+
+        let composer = composer.with_template(Some("digest")).unwrap();
+        let newsletter = composer.compose_from_post(&create_test_post()).unwrap();
+        assert!(newsletter.html_content.contains("Test Post"));
+        assert!(newsletter.html_content.contains("#ff6600"));
+        assert!(newsletter.html_content.contains(">post<"));
+        assert!(newsletter.html_content.contains("Unsubscribe"));
+
+        let custom = composer
+            .compose_custom("Hello".to_string(), "Some **news**".to_string())
+            .unwrap();
+        assert!(custom.html_content.contains(">custom<"));
+        assert!(custom.text_content.starts_with("Hello\n\nSome *news*\n"));
+
+        std::fs::write(
+            temp_dir.path().join("digest.txt"),
+            "{{ subject | upper }}\n\n{{ text }}",
+        )
+        .unwrap();
+        let composer = NewsletterComposer::new(
+            blogr_themes::get_theme("minimal-retro").unwrap(),
+            Config::default(),
+        )
+        .unwrap()
+        .with_templates_dir(temp_dir.path())
+        .unwrap()
+        .with_template(Some("digest"))
+        .unwrap();
+        let custom = composer
+            .compose_custom("Hello".to_string(), "Some **news**".to_string())
+            .unwrap();
+        assert_eq!(custom.text_content, "HELLO\n\nSome *news*\n");
+    }
+
+    #[test]
+    fn test_compose_digest() {
+        let theme = blogr_themes::get_theme("minimal-retro").unwrap();
+        let composer = NewsletterComposer::new(theme, Config::default()).unwrap();
+
+        let mut second = create_test_post();
+        second.metadata.title = "Second Post".to_string();
+        second.metadata.slug = "second-post".to_string();
+        second.content = "Intro paragraph.\n\n<!--more-->\n\nThe rest.".to_string();
+
+        let newsletter = composer
+            .compose_digest(&[second, create_test_post()], None, None)
+            .unwrap();
+        assert!(newsletter.subject.ends_with(": 2 new posts"));
+        assert!(newsletter.html_content.contains("second-post.html"));
+        assert!(newsletter.html_content.contains("Intro paragraph."));
+        assert!(!newsletter.html_content.contains("The rest."));
+        assert!(newsletter.html_content.contains("Test description"));
+        let text = NewsletterComposer::sample_personalization(&newsletter).text_content;
+        assert!(text.contains(": 2 new posts\n2 new posts\n"));
+        assert!(text.contains("\nSecond Post\n"));
+        assert!(text.contains("\nIntro paragraph.\n"));
+        assert!(!text.contains("<"));
+
+        let mut private = create_test_post();
+        private.metadata.private = true;
+        assert!(composer
+            .compose_digest(&[create_test_post(), private], None, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_tokens_in_posts_are_not_personalized() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("greeting.html"),
+            "<html><body><p>Hi {{ subscriber.name }}</p>{{ content | safe }}\
+             <a href=\"{{ unsubscribe_url }}\">Unsubscribe</a></body></html>",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("greeting.txt"),
+            "Hi {{ subscriber.name }}\n\n{{ text }}\n\n{{ unsubscribe_url }}",
+        )
+        .unwrap();
+        let composer = NewsletterComposer::new(
+            blogr_themes::get_theme("minimal-retro").unwrap(),
+            Config::default(),
+        )
+        .unwrap()
+        .with_templates_dir(temp_dir.path())
+        .unwrap()
+        .with_template(Some("greeting"))
+        .unwrap();
+
+        let mut post = create_test_post();
+        post.content = "Greet readers with {{ subscriber.name }}.\n\n\
+                        Link {{ unsubscribe_url }} in the footer."
+            .to_string();
+        let newsletter =
+            NewsletterComposer::sample_personalization(&composer.compose_from_post(&post).unwrap());
+
+        let html = &newsletter.html_content;
+        assert!(html.contains(&format!("Hi {}", SAMPLE_NAME)));
+        assert!(html.contains("Greet readers with {{ subscriber.name }}."));
+        assert!(html.contains("Link {{ unsubscribe_url }} in the footer."));
+        assert!(html.contains("href=\"mailto:unsubscribe@example.com"));
+        assert!(!html.contains(['\u{E000}', '\u{E001}']));
+
+        let text = &newsletter.text_content;
+        assert!(text.starts_with(&format!(
+            "Hi {}\n\nGreet readers with {{{{ subscriber.name }}}}.",
+            SAMPLE_NAME
+        )));
+        assert!(text.contains("Link {{ unsubscribe_url }} in the footer.\n\nmailto:"));
+        assert!(!text.contains(['\u{E000}', '\u{E001}']));
+    }
+
+    #[test]
+    fn test_private_latest_post_is_not_mailed() {
+        let theme = blogr_themes::get_theme("minimal-retro").unwrap();
+        let composer = NewsletterComposer::new(theme, Config::default()).unwrap();
+
+        let mut older = create_test_post();
+        older.metadata.date = Utc::now() - chrono::Duration::days(1);
+        let mut latest = create_test_post();
+        latest.metadata.title = "Secret Post".to_string();
+        latest.metadata.private = true;
+        latest.content = "Members only".to_string();
+
+        let now = Utc::now();
+        let mut posts = vec![older, latest];
+        posts.retain(|p| p.is_mailable(now));
+        posts.sort_by_key(|p| std::cmp::Reverse(p.metadata.date));
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].metadata.title, "Test Post");
+
+        let mut private = create_test_post();
+        private.metadata.private = true;
+        let error = composer.compose_from_post(&private).unwrap_err();
+        assert!(error.to_string().contains("private post"));
+    }
+
+    #[test]
+    fn test_unknown_template_is_rejected() {
+        let theme = blogr_themes::get_theme("minimal-retro").unwrap();
+        let composer = NewsletterComposer::new(theme, Config::default()).unwrap();
+        let error = composer
+            .with_template(Some("missing"))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("missing"));
+        assert!(error.contains("table"));
+    }
 }
@@ -1,19 +1,25 @@
 //! Newsletter management and configuration
 
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::composer::{Newsletter, NewsletterComposer};
-use super::database::NewsletterDatabase;
-use super::fetcher::EmailFetcher;
+use super::database::{NewsletterDatabase, Subscriber};
+use super::fetcher::{EmailAction, EmailFetcher};
+use super::oauth::{self, OAuthProvider};
+use super::plugin::{create_plugin_context, PluginHook, PluginManager};
+use super::segment::Segment;
 use super::sender::NewsletterSender;
+use super::tracking::{extract_links, Tracker};
 use crate::config::{Config, ImapConfig, SmtpConfig};
 use crate::content::Post;
 
 pub struct NewsletterManager {
     config: Config,
     database: NewsletterDatabase,
+    project_root: PathBuf,
 }
 
 impl NewsletterManager {
@@ -29,7 +35,11 @@ impl NewsletterManager {
         let database =
             NewsletterDatabase::open(&db_path).context("Failed to open newsletter database")?;
 
-        Ok(Self { config, database })
+        Ok(Self {
+            config,
+            database,
+            project_root: project_root.to_path_buf(),
+        })
     }
 
     /// Check if newsletter functionality is enabled
@@ -37,6 +47,16 @@ impl NewsletterManager {
         self.config.newsletter.enabled
     }
 
+    /// Get the project configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Root directory of the project
+    pub fn project_root(&self) -> &Path {
+        &self.project_root
+    }
+
     /// Get a reference to the database
     pub fn database(&self) -> &NewsletterDatabase {
         &self.database
@@ -66,6 +86,7 @@ impl NewsletterManager {
                 port,
                 username,
                 use_tls: Some(true),
+                oauth: None,
             }))
         } else {
             Ok(None)
@@ -91,6 +112,7 @@ impl NewsletterManager {
                 port,
                 username,
                 use_tls: Some(true),
+                oauth: None,
             }))
         } else {
             Ok(None)
@@ -110,7 +132,7 @@ impl NewsletterManager {
     }
 
     /// Fetch subscribers from email inbox
-    pub fn fetch_subscribers(&mut self, interactive: bool) -> Result<()> {
+    pub fn fetch_subscribers(&mut self, interactive: bool, since: Option<NaiveDate>) -> Result<()> {
         if !self.is_enabled() {
             return Err(anyhow::anyhow!(
                 "Newsletter functionality is not enabled. Set newsletter.enabled = true in blogr.toml"
@@ -122,18 +144,18 @@ impl NewsletterManager {
                 "IMAP configuration not found. Set up IMAP config in blogr.toml or environment variables"
             ))?;
 
-        let password = if interactive {
-            self.prompt_for_password("IMAP")?
-        } else {
-            self.get_imap_password()?
+        let password = match &imap_config.oauth {
+            Some(provider) => self.oauth_token(provider, &imap_config.username)?,
+            None if interactive => self.prompt_for_password("IMAP")?,
+            None => self.get_imap_password()?,
         };
 
         println!("Connecting to IMAP server...");
-        let mut fetcher = EmailFetcher::new();
+        let mut fetcher = EmailFetcher::new().with_rules(self.config.newsletter.fetch.clone());
         fetcher.connect(&imap_config, &password)?;
 
         println!("Fetching subscription emails...");
-        let emails = fetcher.fetch_subscription_emails()?;
+        let emails = fetcher.fetch_subscription_emails(since)?;
 
         if emails.is_empty() {
             println!("No new subscription emails found.");
@@ -153,13 +175,33 @@ impl NewsletterManager {
             println!("\nUse 'blogr newsletter approve' to review and approve these subscribers.");
         }
 
+        let removed = fetcher.process_unsubscribes(&emails, &mut self.database)?;
+        if !removed.is_empty() {
+            println!("Removed {} subscribers who asked to leave.", removed.len());
+        }
+
         // Mark emails as processed
         let email_ids: Vec<u32> = emails.iter().map(|e| e.id).collect();
         fetcher.mark_emails_as_seen(&email_ids)?;
 
+        let handled: Vec<u32> = emails
+            .iter()
+            .filter(|email| fetcher.classify(email) != EmailAction::Ignore)
+            .map(|email| email.id)
+            .collect();
+        fetcher.archive_emails(&handled)?;
+
         Ok(())
     }
 
+    /// A current access token for an account that signs in with `provider`
+    fn oauth_token(&self, provider: &str, username: &str) -> Result<String> {
+        let config = self.config.newsletter.oauth.get(provider).ok_or_else(|| {
+            anyhow::anyhow!("No [newsletter.oauth.{}] section in blogr.toml", provider)
+        })?;
+        oauth::access_token(&OAuthProvider::from_config(provider, config)?, username)
+    }
+
     /// Prompt user for password securely
     fn prompt_for_password(&self, service: &str) -> Result<String> {
         use std::io::{self, Write};
@@ -204,14 +246,19 @@ impl NewsletterManager {
             Some(ref config) => {
                 println!("IMAP Server: {}:{}", config.server, config.port);
                 println!("IMAP Username: {}", config.username);
-                println!(
-                    "IMAP Password: {}",
-                    if self.get_imap_password().is_ok() {
-                        "✓ Set"
-                    } else {
-                        "✗ Not set"
+                match &config.oauth {
+                    Some(provider) => {
+                        println!("IMAP Sign-in: {}", oauth_status(provider, &config.username))
                     }
-                );
+                    None => println!(
+                        "IMAP Password: {}",
+                        if self.get_imap_password().is_ok() {
+                            "✓ Set"
+                        } else {
+                            "✗ Not set"
+                        }
+                    ),
+                }
             }
             None => {
                 println!("IMAP Configuration: Not configured");
@@ -223,20 +270,35 @@ impl NewsletterManager {
             Some(ref config) => {
                 println!("SMTP Server: {}:{}", config.server, config.port);
                 println!("SMTP Username: {}", config.username);
-                println!(
-                    "SMTP Password: {}",
-                    if self.get_smtp_password().is_ok() {
-                        "✓ Set"
-                    } else {
-                        "✗ Not set"
+                match &config.oauth {
+                    Some(provider) => {
+                        println!("SMTP Sign-in: {}", oauth_status(provider, &config.username))
                     }
-                );
+                    None => println!(
+                        "SMTP Password: {}",
+                        if self.get_smtp_password().is_ok() {
+                            "✓ Set"
+                        } else {
+                            "✗ Not set"
+                        }
+                    ),
+                }
             }
             None => {
                 println!("SMTP Configuration: Not configured");
             }
         }
 
+        let tracking = &self.config.newsletter.tracking;
+        if tracking.enabled {
+            println!(
+                "Open/Click Tracking: ✓ Enabled ({})",
+                tracking.base_url.as_deref().unwrap_or("base_url not set")
+            );
+        } else {
+            println!("Open/Click Tracking: ✗ Disabled");
+        }
+
         // Database statistics
         let total_subscribers = self.database.get_subscriber_count(None)?;
         let pending_count = self
@@ -292,9 +354,22 @@ pub fn setup_imap_config() -> Result<ImapConfig> {
         port,
         username,
         use_tls: Some(true),
+        oauth: None,
     })
 }
 
+/// Whether an OAuth2 account has signed in, for status output
+fn oauth_status(provider: &str, username: &str) -> String {
+    match oauth::stored_token(provider, username) {
+        Ok(Some(_)) => format!("✓ Signed in with {}", provider),
+        Ok(None) => format!(
+            "✗ Not signed in with {} (run 'blogr newsletter login')",
+            provider
+        ),
+        Err(e) => format!("✗ {}", e),
+    }
+}
+
 /// Interactive SMTP configuration setup
 #[allow(dead_code)]
 pub fn setup_smtp_config() -> Result<SmtpConfig> {
@@ -333,16 +408,36 @@ pub fn setup_smtp_config() -> Result<SmtpConfig> {
         port,
         username,
         use_tls: Some(true),
+        oauth: None,
     })
 }
 
 impl NewsletterManager {
-    /// Create a newsletter composer
+    /// Directory holding project email templates
+    pub fn templates_dir(&self) -> PathBuf {
+        self.project_root.join("newsletter").join("templates")
+    }
+
+    /// Create a newsletter composer using the given template, or the configured default
     pub fn create_composer(
         &self,
         theme: Box<dyn blogr_themes::Theme>,
+        template: Option<&str>,
+    ) -> Result<NewsletterComposer> {
+        NewsletterComposer::new(theme, self.config.clone())?
+            .with_templates_dir(&self.templates_dir())?
+            .with_template(template)
+    }
+
+    /// Create a composer for digests, with `template` or else `newsletter.digest_template`
+    pub fn create_digest_composer(
+        &self,
+        theme: Box<dyn blogr_themes::Theme>,
+        template: Option<&str>,
     ) -> Result<NewsletterComposer> {
-        NewsletterComposer::new(theme, self.config.clone())
+        NewsletterComposer::new(theme, self.config.clone())?
+            .with_templates_dir(&self.templates_dir())?
+            .with_digest_template(template)
     }
 
     /// Create a newsletter sender
@@ -353,9 +448,13 @@ impl NewsletterManager {
             ))?;
 
         let sender_name = self.config.newsletter.sender_name.clone();
-        let rate_limit = emails_per_minute.unwrap_or(10); // Default to 10 emails per minute
+        let mut sending = self.config.newsletter.sending.clone();
+        if let Some(rate) = emails_per_minute {
+            sending.emails_per_minute = rate;
+        }
 
-        NewsletterSender::new(smtp_config, sender_name, rate_limit)
+        Ok(NewsletterSender::new(smtp_config, sender_name, sending)?
+            .with_fallback_name(&self.config.newsletter.fallback_name))
     }
 
     /// Compose newsletter from latest blog post
@@ -363,8 +462,9 @@ impl NewsletterManager {
         &self,
         theme: Box<dyn blogr_themes::Theme>,
         posts: &[Post],
+        template: Option<&str>,
     ) -> Result<Newsletter> {
-        let composer = self.create_composer(theme)?;
+        let composer = self.create_composer(theme, template)?;
 
         let latest_post = posts
             .first()
@@ -373,23 +473,195 @@ impl NewsletterManager {
         composer.compose_from_post(latest_post)
     }
 
-    /// Send newsletter to all approved subscribers
+    /// Send newsletter to all approved subscribers.
+    ///
+    /// Each delivery is recorded as it happens, so if a send is interrupted, sending the
+    /// same newsletter again only reaches subscribers who did not get it the first time.
     pub fn send_newsletter(
         &self,
         newsletter: &Newsletter,
         interactive: bool,
+        segments: &[Segment],
     ) -> Result<super::sender::SendReport> {
-        let mut sender = self.create_sender(None)?;
+        let password = self.smtp_password(interactive)?;
+        let subscribers = self.segment_subscribers(segments)?;
+
+        let (_, report) = self.send_to(
+            newsletter,
+            &newsletter_key(newsletter),
+            subscribers,
+            &password,
+        )?;
+        Ok(report)
+    }
+
+    /// Read the SMTP password, prompting for it in interactive mode, or get an
+    /// access token when SMTP signs in with OAuth2
+    pub(super) fn smtp_password(&self, interactive: bool) -> Result<String> {
+        if let Some(smtp) = self.get_smtp_config()? {
+            if let Some(provider) = &smtp.oauth {
+                return self.oauth_token(provider, &smtp.username);
+            }
+        }
+        if interactive {
+            self.prompt_for_password("SMTP")
+        } else {
+            self.get_smtp_password()
+        }
+    }
+
+    /// Subscribers matching every segment (all subscribers when there are none)
+    pub(super) fn segment_subscribers(&self, segments: &[Segment]) -> Result<Vec<Subscriber>> {
+        let subscribers = Segment::filter(segments, self.database.get_subscribers(None)?);
+        if !segments.is_empty() {
+            let names: Vec<String> = segments.iter().map(|s| s.to_string()).collect();
+            println!(
+                "🎯 Segment {}: {} matching subscribers",
+                names.join(" + "),
+                subscribers.len()
+            );
+        }
+
+        Ok(subscribers)
+    }
+
+    /// Send a newsletter to the given subscribers as the send run identified by `key`,
+    /// recording each delivery. Returns the send run ID and the report.
+    pub(super) fn send_to(
+        &self,
+        newsletter: &Newsletter,
+        key: &str,
+        mut subscribers: Vec<Subscriber>,
+        password: &str,
+    ) -> Result<(i64, super::sender::SendReport)> {
+        let newsletter = &self.apply_pre_send_plugins(newsletter, subscribers.len())?;
+        let sender = self.create_sender(None)?;
+
+        let (run_id, resumed) = self
+            .database
+            .start_or_resume_send_run(key, &newsletter.subject)?;
+
+        let recipients = subscribers
+            .iter()
+            .filter(|s| s.status == super::SubscriberStatus::Approved)
+            .count();
+        self.database.set_send_run_recipients(run_id, recipients)?;
+        if resumed {
+            let delivered = self.database.get_delivered_emails(run_id)?;
+            if !delivered.is_empty() {
+                println!(
+                    "↩️  Resuming an earlier send: skipping {} subscribers who already received this newsletter",
+                    delivered.len()
+                );
+                subscribers.retain(|s| !delivered.contains(&s.email));
+            }
+        }
 
-        let password = if interactive {
-            self.prompt_for_password("SMTP")?
+        let mut sender = if self.config.newsletter.tracking.enabled {
+            sender.with_tracker(self.create_tracker(run_id, newsletter, &subscribers)?)
         } else {
-            self.get_smtp_password()?
+            sender
         };
 
-        let subscribers = self.database.get_subscribers(None)?;
+        println!("🆔 Send ID: {}", run_id);
+        let database = &self.database;
+        let report = sender.send_to_subscribers(
+            newsletter,
+            &subscribers,
+            password,
+            None,
+            Some(Box::new(move |subscriber, error| {
+                database.record_delivery(run_id, &subscriber.email, error)
+            })),
+        )?;
+
+        if report.failed_sends == 0 {
+            self.database.complete_send_run(run_id)?;
+        } else {
+            println!(
+                "💡 Run the same send again to retry the {} failed deliveries",
+                report.failed_sends
+            );
+        }
 
-        sender.send_to_subscribers(newsletter, &subscribers, &password, None)
+        Ok((run_id, report))
+    }
+
+    /// Let enabled plugins rewrite a newsletter before it goes out. Every plugin sees
+    /// the composed newsletter; if several change the same field, the last one wins.
+    fn apply_pre_send_plugins(
+        &self,
+        newsletter: &Newsletter,
+        recipients: usize,
+    ) -> Result<Newsletter> {
+        let mut newsletter = newsletter.clone();
+        let plugins = PluginManager::for_project(&self.config, &self.project_root)?;
+
+        let mut data = std::collections::HashMap::new();
+        data.insert("subject".to_string(), serde_json::json!(newsletter.subject));
+        data.insert(
+            "html_content".to_string(),
+            serde_json::json!(newsletter.html_content),
+        );
+        data.insert(
+            "text_content".to_string(),
+            serde_json::json!(newsletter.text_content),
+        );
+        data.insert("recipients".to_string(), serde_json::json!(recipients));
+        let context = create_plugin_context(
+            std::sync::Arc::new(self.config.clone()),
+            None,
+            self.project_root.clone(),
+            PluginHook::PreSend,
+            data,
+        );
+
+        for result in plugins.execute_hook(PluginHook::PreSend, &context)? {
+            if let Some(message) = &result.message {
+                println!("🔌 {}", message);
+            }
+            if let Some(modified) = result.modified_newsletter {
+                newsletter.subject = modified.subject;
+                newsletter.html_content = modified.html_content;
+                newsletter.text_content = modified.text_content;
+            }
+        }
+
+        Ok(newsletter)
+    }
+
+    /// Set up open and click tracking for a send run
+    fn create_tracker(
+        &self,
+        run_id: i64,
+        newsletter: &Newsletter,
+        subscribers: &[Subscriber],
+    ) -> Result<Tracker> {
+        let base_url = self
+            .config
+            .newsletter
+            .tracking
+            .base_url
+            .as_deref()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Tracking is enabled but newsletter.tracking.base_url is not set. \
+                     Set it to the public URL of 'blogr newsletter api-server'"
+                )
+            })?;
+
+        let links = extract_links(&newsletter.html_content);
+        self.database.set_tracking_links(run_id, &links)?;
+
+        let emails: Vec<&str> = subscribers.iter().map(|s| s.email.as_str()).collect();
+        let tokens = self.database.tracking_tokens(run_id, &emails)?;
+
+        println!(
+            "📈 Tracking opens and clicks via {} ({} links)",
+            base_url,
+            links.len()
+        );
+        Ok(Tracker::new(base_url, &links, tokens))
     }
 
     /// Send test newsletter
@@ -401,12 +673,30 @@ impl NewsletterManager {
     ) -> Result<()> {
         let mut sender = self.create_sender(None)?;
 
-        let password = if interactive {
-            self.prompt_for_password("SMTP")?
-        } else {
-            self.get_smtp_password()?
-        };
+        let password = self.smtp_password(interactive)?;
 
         sender.send_test_email(newsletter, test_email, &password)
     }
 }
+
+/// Stable identifier for a newsletter's content, used to resume interrupted sends
+pub(super) fn newsletter_key(newsletter: &Newsletter) -> String {
+    let hash = stable_hash(
+        newsletter
+            .subject
+            .bytes()
+            .chain([0])
+            .chain(newsletter.html_content.bytes()),
+    );
+    format!("{:016x}", hash)
+}
+
+/// FNV-1a: stable across Rust versions, unlike the std hasher
+pub(super) fn stable_hash(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -4,6 +4,7 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, Row};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Mutex;
 
@@ -46,6 +47,12 @@ pub struct Subscriber {
     pub approved_at: Option<DateTime<Utc>>,
     pub source_email_id: Option<String>,
     pub notes: Option<String>,
+    /// Topic tags used to target segments (lowercase)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Name for greetings in newsletters (`{{ subscriber.name }}`)
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 impl Subscriber {
@@ -58,9 +65,16 @@ impl Subscriber {
             approved_at: None,
             source_email_id,
             notes: None,
+            tags: Vec::new(),
+            name: None,
         }
     }
 
+    /// Check whether the subscriber has a tag (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
     #[allow(dead_code)]
     pub fn approve(&mut self) {
         self.status = SubscriberStatus::Approved;
@@ -74,6 +88,247 @@ impl Subscriber {
     }
 }
 
+/// Lifecycle of a scheduled newsletter in the send queue
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum QueueStatus {
+    Pending,
+    Sending,
+    Sent,
+    Failed,
+    Cancelled,
+}
+
+impl std::fmt::Display for QueueStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueueStatus::Pending => write!(f, "pending"),
+            QueueStatus::Sending => write!(f, "sending"),
+            QueueStatus::Sent => write!(f, "sent"),
+            QueueStatus::Failed => write!(f, "failed"),
+            QueueStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for QueueStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(QueueStatus::Pending),
+            "sending" => Ok(QueueStatus::Sending),
+            "sent" => Ok(QueueStatus::Sent),
+            "failed" => Ok(QueueStatus::Failed),
+            "cancelled" => Ok(QueueStatus::Cancelled),
+            _ => Err(anyhow::anyhow!("Invalid queue status: {}", s)),
+        }
+    }
+}
+
+/// State of a subject line A/B test
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AbTestStatus {
+    /// Both variants sent, waiting to pick a winner
+    Testing,
+    /// Winner is being sent to the remaining subscribers
+    Sending,
+    Completed,
+    /// Sending the winner failed; finishing again resumes it
+    Failed,
+}
+
+impl std::fmt::Display for AbTestStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbTestStatus::Testing => write!(f, "testing"),
+            AbTestStatus::Sending => write!(f, "sending"),
+            AbTestStatus::Completed => write!(f, "completed"),
+            AbTestStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for AbTestStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "testing" => Ok(AbTestStatus::Testing),
+            "sending" => Ok(AbTestStatus::Sending),
+            "completed" => Ok(AbTestStatus::Completed),
+            "failed" => Ok(AbTestStatus::Failed),
+            _ => Err(anyhow::anyhow!("Invalid A/B test status: {}", s)),
+        }
+    }
+}
+
+/// Filters for listing subscribers; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct SubscriberQuery {
+    pub status: Option<SubscriberStatus>,
+    /// Case-insensitive substring of the email address or notes
+    pub search: Option<String>,
+    pub tag: Option<String>,
+}
+
+/// The email a subscription request arrived in, kept for review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceEmail {
+    pub from: String,
+    pub subject: String,
+    /// `Date` header as sent
+    pub date: Option<String>,
+    pub body: String,
+}
+
+/// A subject line A/B test and the send runs belonging to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbTest {
+    pub id: i64,
+    pub subject_a: String,
+    pub subject_b: String,
+    pub html_content: String,
+    pub text_content: String,
+    pub segments: Vec<String>,
+    /// Share of the audience in the test groups, split evenly between A and B
+    pub test_percent: u8,
+    pub run_a: Option<i64>,
+    pub run_b: Option<i64>,
+    /// When the winner may be picked and sent to everyone else
+    pub decide_at: DateTime<Utc>,
+    pub status: AbTestStatus,
+    /// "a" or "b" once decided
+    pub winner: Option<String>,
+    pub remainder_run: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A composed newsletter waiting in the send queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedNewsletter {
+    pub id: i64,
+    pub subject: String,
+    pub html_content: String,
+    pub text_content: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub status: QueueStatus,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    /// Segment filters the newsletter is sent to (e.g. `tag:rust`); empty means everyone
+    pub segments: Vec<String>,
+}
+
+/// Summary of one newsletter send
+#[derive(Debug, Clone, Serialize)]
+pub struct SendRun {
+    pub id: i64,
+    pub subject: String,
+    pub started_at: DateTime<Utc>,
+    /// Set once every recipient has received the newsletter
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Subscribers the send was addressed to
+    pub recipients: usize,
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// Outcome of sending a newsletter to one recipient
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryRecord {
+    pub email: String,
+    pub sent: bool,
+    pub attempted_at: DateTime<Utc>,
+    pub error: Option<String>,
+}
+
+/// Open and click counts for one send run
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackingStats {
+    pub run_id: i64,
+    pub subject: String,
+    pub started_at: String,
+    /// Recipients the newsletter was delivered to
+    pub delivered: usize,
+    /// Recipients that were given a tracking token
+    pub tracked: usize,
+    pub unique_opens: usize,
+    pub total_opens: usize,
+    pub unique_clicks: usize,
+    pub total_clicks: usize,
+    pub links: Vec<LinkClicks>,
+}
+
+/// Clicks on a single link in a send
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkClicks {
+    pub url: String,
+    pub unique_clicks: usize,
+    pub total_clicks: usize,
+}
+
+/// Timestamp format used for queue columns; sorts lexically in UTC
+const QUEUE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Send run summary columns, in the order `row_to_send_run` expects
+const SEND_RUN_SELECT: &str = "SELECT r.id, r.subject, r.started_at, r.completed_at, r.recipients,
+        COUNT(CASE WHEN d.status = 'sent' THEN 1 END),
+        COUNT(CASE WHEN d.status = 'failed' THEN 1 END)
+     FROM send_runs r LEFT JOIN send_deliveries d ON d.run_id = r.id";
+
+/// A/B test columns, in the order `row_to_ab_test` expects
+const AB_TEST_SELECT: &str =
+    "SELECT id, subject_a, subject_b, html_content, text_content, segments,
+        test_percent, run_a, run_b, decide_at, status, winner, remainder_run, error, created_at
+     FROM ab_tests";
+
+/// Schema changes applied in order to existing databases, tracked with `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    // 1: subscriber tags and queue segments
+    "ALTER TABLE subscribers ADD COLUMN tags TEXT NOT NULL DEFAULT '';
+     ALTER TABLE send_queue ADD COLUMN segments TEXT NOT NULL DEFAULT '';",
+    // 2: number of recipients targeted by each send
+    "ALTER TABLE send_runs ADD COLUMN recipients INTEGER NOT NULL DEFAULT 0;",
+    // 3: when subscribers were removed, for growth statistics
+    "CREATE TABLE subscriber_removals (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        removed_at DATETIME NOT NULL
+     );",
+    // 4: subscriber names for personalized newsletters
+    "ALTER TABLE subscribers ADD COLUMN name TEXT;",
+];
+
+/// Normalize tags for storage: trimmed, lowercase, deduplicated and sorted.
+/// Tags are stored comma-separated, so commas are removed.
+pub fn normalize_tags<S: AsRef<str>>(tags: &[S]) -> Vec<String> {
+    let mut normalized: Vec<String> = tags
+        .iter()
+        .map(|t| t.as_ref().replace(',', "").trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// Parse a timestamp stored in `QUEUE_TIME_FORMAT`
+fn parse_time(index: usize, name: &str, value: String) -> rusqlite::Result<DateTime<Utc>> {
+    chrono::NaiveDateTime::parse_from_str(&value, QUEUE_TIME_FORMAT)
+        .map(|t| t.and_utc())
+        .map_err(|_| {
+            rusqlite::Error::InvalidColumnType(index, name.to_string(), rusqlite::types::Type::Text)
+        })
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct NewsletterDatabase {
     conn: Mutex<Connection>,
@@ -112,10 +367,113 @@ impl NewsletterDatabase {
             CREATE INDEX IF NOT EXISTS idx_subscribers_status ON subscribers(status);
             CREATE INDEX IF NOT EXISTS idx_subscribers_email ON subscribers(email);
             CREATE INDEX IF NOT EXISTS idx_subscribers_subscribed_at ON subscribers(subscribed_at);
+
+            CREATE TABLE IF NOT EXISTS send_queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                subject TEXT NOT NULL,
+                html_content TEXT NOT NULL,
+                text_content TEXT NOT NULL,
+                scheduled_at DATETIME NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending'
+                    CHECK (status IN ('pending', 'sending', 'sent', 'failed', 'cancelled')),
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                sent_at DATETIME,
+                error TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_send_queue_due ON send_queue(status, scheduled_at);
+
+            CREATE TABLE IF NOT EXISTS send_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                newsletter_key TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                started_at DATETIME NOT NULL,
+                completed_at DATETIME
+            );
+
+            CREATE TABLE IF NOT EXISTS send_deliveries (
+                run_id INTEGER NOT NULL REFERENCES send_runs(id) ON DELETE CASCADE,
+                email TEXT NOT NULL,
+                status TEXT NOT NULL CHECK (status IN ('sent', 'failed')),
+                attempted_at DATETIME NOT NULL,
+                error TEXT,
+                PRIMARY KEY (run_id, email)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_send_runs_key ON send_runs(newsletter_key, completed_at);
+
+            CREATE TABLE IF NOT EXISTS tracking_recipients (
+                token TEXT PRIMARY KEY,
+                run_id INTEGER NOT NULL REFERENCES send_runs(id) ON DELETE CASCADE,
+                email TEXT NOT NULL,
+                UNIQUE (run_id, email)
+            );
+
+            CREATE TABLE IF NOT EXISTS tracking_links (
+                run_id INTEGER NOT NULL REFERENCES send_runs(id) ON DELETE CASCADE,
+                link_index INTEGER NOT NULL,
+                url TEXT NOT NULL,
+                PRIMARY KEY (run_id, link_index)
+            );
+
+            CREATE TABLE IF NOT EXISTS tracking_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                token TEXT NOT NULL REFERENCES tracking_recipients(token) ON DELETE CASCADE,
+                kind TEXT NOT NULL CHECK (kind IN ('open', 'click')),
+                link_index INTEGER,
+                created_at DATETIME NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_tracking_events_token ON tracking_events(token);
+
+            CREATE TABLE IF NOT EXISTS ab_tests (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                subject_a TEXT NOT NULL,
+                subject_b TEXT NOT NULL,
+                html_content TEXT NOT NULL,
+                text_content TEXT NOT NULL,
+                segments TEXT NOT NULL DEFAULT '',
+                test_percent INTEGER NOT NULL,
+                run_a INTEGER REFERENCES send_runs(id),
+                run_b INTEGER REFERENCES send_runs(id),
+                decide_at DATETIME NOT NULL,
+                status TEXT NOT NULL DEFAULT 'testing'
+                    CHECK (status IN ('testing', 'sending', 'completed', 'failed')),
+                winner TEXT CHECK (winner IN ('a', 'b')),
+                remainder_run INTEGER REFERENCES send_runs(id),
+                error TEXT,
+                created_at DATETIME NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS source_emails (
+                subscriber_email TEXT PRIMARY KEY,
+                sender TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                sent_date TEXT,
+                body TEXT NOT NULL
+            );
             "#,
             )
             .context("Failed to initialize database schema")?;
 
+        self.migrate()
+    }
+
+    /// Apply pending schema migrations
+    fn migrate(&mut self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let version: usize =
+            conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))? as usize;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            conn.execute_batch(&format!(
+                "BEGIN; {} PRAGMA user_version = {}; COMMIT;",
+                migration,
+                index + 1
+            ))
+            .with_context(|| format!("Failed to apply database migration {}", index + 1))?;
+        }
+
         Ok(())
     }
 
@@ -123,8 +481,8 @@ impl NewsletterDatabase {
     pub fn add_subscriber(&self, subscriber: &Subscriber) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "INSERT INTO subscribers (email, status, subscribed_at, source_email_id, notes) 
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO subscribers (email, status, subscribed_at, source_email_id, notes, tags, name)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         )?;
 
         let id = stmt.insert(params![
@@ -136,6 +494,8 @@ impl NewsletterDatabase {
                 .to_string(),
             subscriber.source_email_id,
             subscriber.notes,
+            normalize_tags(&subscriber.tags).join(","),
+            subscriber.name,
         ])?;
 
         Ok(id)
@@ -167,13 +527,13 @@ impl NewsletterDatabase {
     ) -> Result<Vec<Subscriber>> {
         let (query, params): (String, Vec<String>) = match status_filter {
             Some(status) => (
-                "SELECT id, email, status, subscribed_at, approved_at, source_email_id, notes 
+                "SELECT id, email, status, subscribed_at, approved_at, source_email_id, notes, tags, name 
                  FROM subscribers WHERE status = ?1 ORDER BY subscribed_at DESC"
                     .to_string(),
                 vec![status.to_string()],
             ),
             None => (
-                "SELECT id, email, status, subscribed_at, approved_at, source_email_id, notes 
+                "SELECT id, email, status, subscribed_at, approved_at, source_email_id, notes, tags, name 
                  FROM subscribers ORDER BY subscribed_at DESC"
                     .to_string(),
                 vec![],
@@ -194,11 +554,65 @@ impl NewsletterDatabase {
         Ok(subscribers)
     }
 
+    /// One page of subscribers matching a filter, newest first, and the total number of matches
+    pub fn query_subscribers(
+        &self,
+        filter: &SubscriberQuery,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<(Vec<Subscriber>, usize)> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<String> = Vec::new();
+        if let Some(status) = &filter.status {
+            values.push(status.to_string());
+            conditions.push(format!("status = ?{}", values.len()));
+        }
+        if let Some(search) = filter.search.as_deref().filter(|s| !s.is_empty()) {
+            values.push(format!("%{}%", search.to_lowercase()));
+            conditions.push(format!(
+                "(LOWER(email) LIKE ?{n} OR LOWER(COALESCE(notes, '')) LIKE ?{n})",
+                n = values.len()
+            ));
+        }
+        if let Some(tag) = filter.tag.as_deref().filter(|t| !t.is_empty()) {
+            values.push(format!("%,{},%", tag.trim().to_lowercase()));
+            conditions.push(format!("(',' || tags || ',') LIKE ?{}", values.len()));
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let conn = self.conn.lock().unwrap();
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM subscribers {}", where_clause),
+            rusqlite::params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, email, status, subscribed_at, approved_at, source_email_id, notes, tags, name
+             FROM subscribers {} ORDER BY subscribed_at DESC, id DESC LIMIT {} OFFSET {}",
+            where_clause,
+            // SQLite treats a negative limit as "no limit"
+            limit.map_or(-1, |limit| limit as i64),
+            offset
+        ))?;
+        let subscribers = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+                self.row_to_subscriber(row)
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok((subscribers, total as usize))
+    }
+
     /// Get subscriber by email
     pub fn get_subscriber_by_email(&self, email: &str) -> Result<Option<Subscriber>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, email, status, subscribed_at, approved_at, source_email_id, notes 
+            "SELECT id, email, status, subscribed_at, approved_at, source_email_id, notes, tags, name 
              FROM subscribers WHERE email = ?1",
         )?;
 
@@ -211,17 +625,147 @@ impl NewsletterDatabase {
         }
     }
 
+    /// Replace a subscriber's tags. Returns false if the subscriber doesn't exist.
+    pub fn set_subscriber_tags(&self, email: &str, tags: &[String]) -> Result<bool> {
+        let rows_affected = self.conn.lock().unwrap().execute(
+            "UPDATE subscribers SET tags = ?1 WHERE email = ?2",
+            params![normalize_tags(tags).join(","), email],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Replace a subscriber's name. Returns false if the subscriber doesn't exist.
+    pub fn set_subscriber_name(&self, email: &str, name: Option<&str>) -> Result<bool> {
+        let rows_affected = self.conn.lock().unwrap().execute(
+            "UPDATE subscribers SET name = ?1 WHERE email = ?2",
+            params![name, email],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Replace a subscriber's notes. Returns false if the subscriber doesn't exist.
+    pub fn set_subscriber_notes(&self, email: &str, notes: Option<&str>) -> Result<bool> {
+        let rows_affected = self.conn.lock().unwrap().execute(
+            "UPDATE subscribers SET notes = ?1 WHERE email = ?2",
+            params![notes, email],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Change a subscriber's email address. Returns false if the subscriber doesn't exist.
+    pub fn rename_subscriber(&self, email: &str, new_email: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let rows_affected = conn.execute(
+            "UPDATE subscribers SET email = ?1 WHERE email = ?2",
+            params![new_email, email],
+        )?;
+        conn.execute(
+            "UPDATE source_emails SET subscriber_email = ?1 WHERE subscriber_email = ?2",
+            params![new_email, email],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Put back a subscriber's earlier status and approval time, e.g. to undo an action
+    pub fn restore_subscriber_status(
+        &self,
+        id: i64,
+        status: SubscriberStatus,
+        approved_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "UPDATE subscribers SET status = ?1, approved_at = ?2 WHERE id = ?3",
+            params![
+                status.to_string(),
+                approved_at.map(|t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string()),
+                id
+            ],
+        )?;
+
+        Ok(())
+    }
+
     /// Remove subscriber by email
     pub fn remove_subscriber(&self, email: &str) -> Result<bool> {
-        let rows_affected = self
-            .conn
-            .lock()
-            .unwrap()
-            .execute("DELETE FROM subscribers WHERE email = ?1", params![email])?;
+        let conn = self.conn.lock().unwrap();
+        let rows_affected =
+            conn.execute("DELETE FROM subscribers WHERE email = ?1", params![email])?;
+        conn.execute(
+            "DELETE FROM source_emails WHERE subscriber_email = ?1",
+            params![email],
+        )?;
+        // Only the time is kept, not who left
+        if rows_affected > 0 {
+            conn.execute(
+                "INSERT INTO subscriber_removals (removed_at) VALUES (?1)",
+                params![Utc::now().format(QUEUE_TIME_FORMAT).to_string()],
+            )?;
+        }
 
         Ok(rows_affected > 0)
     }
 
+    /// Add back a subscriber that was just removed, and drop the newest
+    /// removal so growth statistics don't count it
+    pub fn restore_subscriber(&self, subscriber: &Subscriber) -> Result<i64> {
+        let id = self.add_subscriber(subscriber)?;
+        self.conn.lock().unwrap().execute(
+            "DELETE FROM subscriber_removals
+             WHERE id = (SELECT MAX(id) FROM subscriber_removals)",
+            [],
+        )?;
+        Ok(id)
+    }
+
+    /// When subscribers were removed, oldest first
+    pub fn get_removal_times(&self) -> Result<Vec<DateTime<Utc>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT removed_at FROM subscriber_removals ORDER BY removed_at")?;
+        let times = stmt
+            .query_map([], |row| parse_time(0, "removed_at", row.get(0)?))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(times)
+    }
+
+    /// Store the email a subscriber's request arrived in
+    pub fn set_source_email(&self, email: &str, source: &SourceEmail) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO source_emails (subscriber_email, sender, subject, sent_date, body)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![email, source.from, source.subject, source.date, source.body],
+        )?;
+
+        Ok(())
+    }
+
+    /// The email a subscriber's request arrived in, if it was kept
+    pub fn get_source_email(&self, email: &str) -> Result<Option<SourceEmail>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            "SELECT sender, subject, sent_date, body FROM source_emails WHERE subscriber_email = ?1",
+            params![email],
+            |row| {
+                Ok(SourceEmail {
+                    from: row.get(0)?,
+                    subject: row.get(1)?,
+                    date: row.get(2)?,
+                    body: row.get(3)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(source) => Ok(Some(source)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Get subscriber count by status
     pub fn get_subscriber_count(&self, status: Option<SubscriberStatus>) -> Result<i64> {
         let (query, params): (String, Vec<String>) = match status {
@@ -253,6 +797,623 @@ impl NewsletterDatabase {
         }
     }
 
+    /// Add a composed newsletter to the send queue
+    pub fn enqueue_newsletter(
+        &self,
+        subject: &str,
+        html_content: &str,
+        text_content: &str,
+        scheduled_at: DateTime<Utc>,
+        segments: &[String],
+    ) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "INSERT INTO send_queue (subject, html_content, text_content, scheduled_at, status, created_at, segments)
+             VALUES (?1, ?2, ?3, ?4, 'pending', ?5, ?6)",
+        )?;
+
+        let id = stmt.insert(params![
+            subject,
+            html_content,
+            text_content,
+            scheduled_at.format(QUEUE_TIME_FORMAT).to_string(),
+            Utc::now().format(QUEUE_TIME_FORMAT).to_string(),
+            segments.join(","),
+        ])?;
+
+        Ok(id)
+    }
+
+    /// Get queued newsletters, oldest schedule first, with optional status filter
+    pub fn get_queued_newsletters(
+        &self,
+        status_filter: Option<QueueStatus>,
+    ) -> Result<Vec<QueuedNewsletter>> {
+        let (query, params): (String, Vec<String>) = match status_filter {
+            Some(status) => (
+                "SELECT id, subject, html_content, text_content, scheduled_at, status, created_at, sent_at, error, segments
+                 FROM send_queue WHERE status = ?1 ORDER BY scheduled_at ASC"
+                    .to_string(),
+                vec![status.to_string()],
+            ),
+            None => (
+                "SELECT id, subject, html_content, text_content, scheduled_at, status, created_at, sent_at, error, segments
+                 FROM send_queue ORDER BY scheduled_at ASC"
+                    .to_string(),
+                vec![],
+            ),
+        };
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            Self::row_to_queued(row)
+        })?;
+
+        let mut queued = Vec::new();
+        for item in rows {
+            queued.push(item?);
+        }
+
+        Ok(queued)
+    }
+
+    /// Get pending newsletters whose scheduled time has passed
+    pub fn get_due_newsletters(&self, now: DateTime<Utc>) -> Result<Vec<QueuedNewsletter>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, subject, html_content, text_content, scheduled_at, status, created_at, sent_at, error, segments
+             FROM send_queue WHERE status = 'pending' AND scheduled_at <= ?1
+             ORDER BY scheduled_at ASC",
+        )?;
+        let rows = stmt.query_map(
+            params![now.format(QUEUE_TIME_FORMAT).to_string()],
+            Self::row_to_queued,
+        )?;
+
+        let mut due = Vec::new();
+        for item in rows {
+            due.push(item?);
+        }
+
+        Ok(due)
+    }
+
+    /// Update the status of a queued newsletter, recording the send time or error
+    pub fn update_queue_status(
+        &self,
+        id: i64,
+        status: QueueStatus,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let sent_at =
+            (status == QueueStatus::Sent).then(|| Utc::now().format(QUEUE_TIME_FORMAT).to_string());
+
+        self.conn.lock().unwrap().execute(
+            "UPDATE send_queue SET status = ?1, sent_at = COALESCE(?2, sent_at), error = ?3 WHERE id = ?4",
+            params![status.to_string(), sent_at, error, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Atomically move a pending newsletter to `sending`.
+    /// Returns false if it was already claimed (e.g. by an overlapping cron run).
+    pub fn claim_queued_newsletter(&self, id: i64) -> Result<bool> {
+        let rows_affected = self.conn.lock().unwrap().execute(
+            "UPDATE send_queue SET status = 'sending' WHERE id = ?1 AND status = 'pending'",
+            params![id],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Cancel a pending newsletter. Returns false if it is not pending.
+    pub fn cancel_queued_newsletter(&self, id: i64) -> Result<bool> {
+        let rows_affected = self.conn.lock().unwrap().execute(
+            "UPDATE send_queue SET status = 'cancelled' WHERE id = ?1 AND status = 'pending'",
+            params![id],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Store a new A/B test and return its ID
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_ab_test(
+        &self,
+        subject_a: &str,
+        subject_b: &str,
+        html_content: &str,
+        text_content: &str,
+        segments: &[String],
+        test_percent: u8,
+        decide_at: DateTime<Utc>,
+    ) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO ab_tests (subject_a, subject_b, html_content, text_content, segments,
+                                   test_percent, decide_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                subject_a,
+                subject_b,
+                html_content,
+                text_content,
+                segments.join(","),
+                test_percent,
+                decide_at.format(QUEUE_TIME_FORMAT).to_string(),
+                Utc::now().format(QUEUE_TIME_FORMAT).to_string()
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Link the send run of one test variant (`"a"` or `"b"`) to an A/B test
+    pub fn set_ab_test_run(&self, id: i64, variant: &str, run_id: i64) -> Result<()> {
+        let column = match variant {
+            "a" => "run_a",
+            "b" => "run_b",
+            _ => return Err(anyhow::anyhow!("Invalid A/B test variant: {}", variant)),
+        };
+        self.conn.lock().unwrap().execute(
+            &format!("UPDATE ab_tests SET {} = ?1 WHERE id = ?2", column),
+            params![run_id, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// List A/B tests, newest first
+    pub fn get_ab_tests(&self) -> Result<Vec<AbTest>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("{} ORDER BY id DESC", AB_TEST_SELECT))?;
+        let tests = stmt
+            .query_map([], Self::row_to_ab_test)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(tests)
+    }
+
+    /// Get a single A/B test by ID
+    pub fn get_ab_test(&self, id: i64) -> Result<Option<AbTest>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            &format!("{} WHERE id = ?1", AB_TEST_SELECT),
+            params![id],
+            Self::row_to_ab_test,
+        );
+
+        match result {
+            Ok(test) => Ok(Some(test)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// A/B tests whose waiting period has ended
+    pub fn get_due_ab_tests(&self, now: DateTime<Utc>) -> Result<Vec<AbTest>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE status = 'testing' AND decide_at <= ?1 ORDER BY decide_at",
+            AB_TEST_SELECT
+        ))?;
+        let tests = stmt
+            .query_map(
+                params![now.format(QUEUE_TIME_FORMAT).to_string()],
+                Self::row_to_ab_test,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(tests)
+    }
+
+    /// Atomically mark an A/B test as sending its winner.
+    /// Returns false if it is already being sent or has completed.
+    pub fn claim_ab_test(&self, id: i64) -> Result<bool> {
+        let rows_affected = self.conn.lock().unwrap().execute(
+            "UPDATE ab_tests SET status = 'sending', error = NULL
+             WHERE id = ?1 AND status IN ('testing', 'failed')",
+            params![id],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Record the outcome of sending an A/B test's winner; `error` is None on success
+    pub fn finish_ab_test(
+        &self,
+        id: i64,
+        winner: &str,
+        remainder_run: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let status = if error.is_some() {
+            AbTestStatus::Failed
+        } else {
+            AbTestStatus::Completed
+        };
+        self.conn.lock().unwrap().execute(
+            "UPDATE ab_tests SET status = ?1, winner = ?2, remainder_run = ?3, error = ?4
+             WHERE id = ?5",
+            params![status.to_string(), winner, remainder_run, error, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Find the unfinished send run for a newsletter, or start a new one.
+    /// Returns the run ID and whether an earlier run is being resumed.
+    pub fn start_or_resume_send_run(
+        &self,
+        newsletter_key: &str,
+        subject: &str,
+    ) -> Result<(i64, bool)> {
+        let conn = self.conn.lock().unwrap();
+        let existing = conn.query_row(
+            "SELECT id FROM send_runs WHERE newsletter_key = ?1 AND completed_at IS NULL
+             ORDER BY id DESC LIMIT 1",
+            params![newsletter_key],
+            |row| row.get::<_, i64>(0),
+        );
+
+        match existing {
+            Ok(id) => Ok((id, true)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                conn.execute(
+                    "INSERT INTO send_runs (newsletter_key, subject, started_at) VALUES (?1, ?2, ?3)",
+                    params![
+                        newsletter_key,
+                        subject,
+                        Utc::now().format(QUEUE_TIME_FORMAT).to_string()
+                    ],
+                )?;
+                Ok((conn.last_insert_rowid(), false))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Emails that already received the newsletter in a send run
+    pub fn get_delivered_emails(&self, run_id: i64) -> Result<HashSet<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT email FROM send_deliveries WHERE run_id = ?1 AND status = 'sent'")?;
+        let rows = stmt.query_map(params![run_id], |row| row.get::<_, String>(0))?;
+
+        let mut emails = HashSet::new();
+        for email in rows {
+            emails.insert(email?);
+        }
+
+        Ok(emails)
+    }
+
+    /// Record the outcome of a single delivery; `error` is None on success
+    pub fn record_delivery(&self, run_id: i64, email: &str, error: Option<&str>) -> Result<()> {
+        let status = if error.is_some() { "failed" } else { "sent" };
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO send_deliveries (run_id, email, status, attempted_at, error)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(run_id, email) DO UPDATE SET
+                status = excluded.status, attempted_at = excluded.attempted_at, error = excluded.error",
+            params![
+                run_id,
+                email,
+                status,
+                Utc::now().format(QUEUE_TIME_FORMAT).to_string(),
+                error
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Mark a send run as finished so the next send of the same newsletter starts fresh
+    pub fn complete_send_run(&self, run_id: i64) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "UPDATE send_runs SET completed_at = ?1 WHERE id = ?2",
+            params![Utc::now().format(QUEUE_TIME_FORMAT).to_string(), run_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Record how many subscribers a send run is addressed to
+    pub fn set_send_run_recipients(&self, run_id: i64, recipients: usize) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "UPDATE send_runs SET recipients = ?1 WHERE id = ?2",
+            params![recipients as i64, run_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// List past sends, newest first
+    pub fn get_send_runs(&self, limit: usize) -> Result<Vec<SendRun>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} GROUP BY r.id ORDER BY r.id DESC LIMIT ?1",
+            SEND_RUN_SELECT
+        ))?;
+        let runs = stmt
+            .query_map(params![limit as i64], Self::row_to_send_run)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(runs)
+    }
+
+    /// When the most recent finished send started
+    pub fn last_send_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock().unwrap();
+        let started_at: Option<String> = conn.query_row(
+            "SELECT MAX(started_at) FROM send_runs WHERE completed_at IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        Ok(started_at
+            .map(|value| parse_time(0, "started_at", value))
+            .transpose()?)
+    }
+
+    /// Get a single send by ID
+    pub fn get_send_run(&self, run_id: i64) -> Result<Option<SendRun>> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.query_row(
+            &format!("{} WHERE r.id = ?1 GROUP BY r.id", SEND_RUN_SELECT),
+            params![run_id],
+            Self::row_to_send_run,
+        );
+
+        match result {
+            Ok(run) => Ok(Some(run)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Per-recipient results of a send, failures first
+    pub fn get_deliveries(&self, run_id: i64) -> Result<Vec<DeliveryRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT email, status, attempted_at, error FROM send_deliveries
+             WHERE run_id = ?1 ORDER BY status = 'sent', email",
+        )?;
+        let deliveries = stmt
+            .query_map(params![run_id], |row| {
+                Ok(DeliveryRecord {
+                    email: row.get(0)?,
+                    sent: row.get::<_, String>(1)? == "sent",
+                    attempted_at: parse_time(2, "attempted_at", row.get(2)?)?,
+                    error: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(deliveries)
+    }
+
+    /// Get or create tracking tokens for the recipients of a send run, keyed by email.
+    /// Resumed runs reuse the tokens handed out the first time.
+    pub fn tracking_tokens(
+        &self,
+        run_id: i64,
+        emails: &[&str],
+    ) -> Result<std::collections::HashMap<String, String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut tokens = std::collections::HashMap::new();
+
+        for email in emails {
+            conn.execute(
+                "INSERT OR IGNORE INTO tracking_recipients (token, run_id, email) VALUES (?1, ?2, ?3)",
+                params![uuid::Uuid::new_v4().simple().to_string(), run_id, email],
+            )?;
+            let token: String = conn.query_row(
+                "SELECT token FROM tracking_recipients WHERE run_id = ?1 AND email = ?2",
+                params![run_id, email],
+                |row| row.get(0),
+            )?;
+            tokens.insert(email.to_string(), token);
+        }
+
+        Ok(tokens)
+    }
+
+    /// Store the tracked links of a send run; link positions are stable across resumes
+    pub fn set_tracking_links(&self, run_id: i64, links: &[String]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        for (index, url) in links.iter().enumerate() {
+            conn.execute(
+                "INSERT OR IGNORE INTO tracking_links (run_id, link_index, url) VALUES (?1, ?2, ?3)",
+                params![run_id, index as i64, url],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Record an email open. Returns false for unknown tokens.
+    pub fn record_open(&self, token: &str) -> Result<bool> {
+        let rows_affected = self.conn.lock().unwrap().execute(
+            "INSERT INTO tracking_events (token, kind, created_at)
+             SELECT token, 'open', ?2 FROM tracking_recipients WHERE token = ?1",
+            params![token, Utc::now().format(QUEUE_TIME_FORMAT).to_string()],
+        )?;
+
+        Ok(rows_affected > 0)
+    }
+
+    /// Record a link click and return the link's target, or None for unknown tokens or links
+    pub fn record_click(&self, token: &str, link_index: i64) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let url = conn.query_row(
+            "SELECT l.url FROM tracking_recipients r
+             JOIN tracking_links l ON l.run_id = r.run_id
+             WHERE r.token = ?1 AND l.link_index = ?2",
+            params![token, link_index],
+            |row| row.get::<_, String>(0),
+        );
+
+        match url {
+            Ok(url) => {
+                conn.execute(
+                    "INSERT INTO tracking_events (token, kind, link_index, created_at)
+                     VALUES (?1, 'click', ?2, ?3)",
+                    params![
+                        token,
+                        link_index,
+                        Utc::now().format(QUEUE_TIME_FORMAT).to_string()
+                    ],
+                )?;
+                Ok(Some(url))
+            }
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Open and click counts for a send run, or None if the run doesn't exist
+    pub fn get_tracking_stats(&self, run_id: i64) -> Result<Option<TrackingStats>> {
+        let conn = self.conn.lock().unwrap();
+
+        let run = conn.query_row(
+            "SELECT subject, started_at FROM send_runs WHERE id = ?1",
+            params![run_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        );
+        let (subject, started_at) = match run {
+            Ok(run) => run,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let count = |sql: &str| -> Result<usize> {
+            Ok(conn.query_row(sql, params![run_id], |row| row.get::<_, i64>(0))? as usize)
+        };
+
+        let delivered =
+            count("SELECT COUNT(*) FROM send_deliveries WHERE run_id = ?1 AND status = 'sent'")?;
+        let tracked = count("SELECT COUNT(*) FROM tracking_recipients WHERE run_id = ?1")?;
+
+        let events = |kind: &str| -> Result<(usize, usize)> {
+            let (unique, total) = conn.query_row(
+                "SELECT COUNT(DISTINCT e.token), COUNT(*) FROM tracking_events e
+                 JOIN tracking_recipients r ON r.token = e.token
+                 WHERE r.run_id = ?1 AND e.kind = ?2",
+                params![run_id, kind],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )?;
+            Ok((unique as usize, total as usize))
+        };
+        let (unique_opens, total_opens) = events("open")?;
+        let (unique_clicks, total_clicks) = events("click")?;
+
+        let mut stmt = conn.prepare(
+            "SELECT l.url, COUNT(DISTINCT e.token), COUNT(e.id) FROM tracking_links l
+             LEFT JOIN tracking_recipients r ON r.run_id = l.run_id
+             LEFT JOIN tracking_events e
+                ON e.token = r.token AND e.kind = 'click' AND e.link_index = l.link_index
+             WHERE l.run_id = ?1
+             GROUP BY l.link_index
+             ORDER BY COUNT(e.id) DESC, l.link_index",
+        )?;
+        let links = stmt
+            .query_map(params![run_id], |row| {
+                Ok(LinkClicks {
+                    url: row.get(0)?,
+                    unique_clicks: row.get::<_, i64>(1)? as usize,
+                    total_clicks: row.get::<_, i64>(2)? as usize,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(Some(TrackingStats {
+            run_id,
+            subject,
+            started_at,
+            delivered,
+            tracked,
+            unique_opens,
+            total_opens,
+            unique_clicks,
+            total_clicks,
+            links,
+        }))
+    }
+
+    /// Helper function to convert a send_queue row to QueuedNewsletter
+    fn row_to_queued(row: &Row) -> rusqlite::Result<QueuedNewsletter> {
+        let status_str: String = row.get(5)?;
+        let status = status_str.parse().map_err(|_| {
+            rusqlite::Error::InvalidColumnType(5, "status".to_string(), rusqlite::types::Type::Text)
+        })?;
+        let sent_at: Option<String> = row.get(7)?;
+
+        Ok(QueuedNewsletter {
+            id: row.get(0)?,
+            subject: row.get(1)?,
+            html_content: row.get(2)?,
+            text_content: row.get(3)?,
+            scheduled_at: parse_time(4, "scheduled_at", row.get(4)?)?,
+            status,
+            created_at: parse_time(6, "created_at", row.get(6)?)?,
+            sent_at: sent_at
+                .map(|value| parse_time(7, "sent_at", value))
+                .transpose()?,
+            error: row.get(8)?,
+            segments: split_list(&row.get::<_, String>(9)?),
+        })
+    }
+
+    /// Helper function to convert an ab_tests row to AbTest
+    fn row_to_ab_test(row: &Row) -> rusqlite::Result<AbTest> {
+        let status_str: String = row.get(10)?;
+        let status = status_str.parse().map_err(|_| {
+            rusqlite::Error::InvalidColumnType(
+                10,
+                "status".to_string(),
+                rusqlite::types::Type::Text,
+            )
+        })?;
+
+        Ok(AbTest {
+            id: row.get(0)?,
+            subject_a: row.get(1)?,
+            subject_b: row.get(2)?,
+            html_content: row.get(3)?,
+            text_content: row.get(4)?,
+            segments: split_list(&row.get::<_, String>(5)?),
+            test_percent: row.get(6)?,
+            run_a: row.get(7)?,
+            run_b: row.get(8)?,
+            decide_at: parse_time(9, "decide_at", row.get(9)?)?,
+            status,
+            winner: row.get(11)?,
+            remainder_run: row.get(12)?,
+            error: row.get(13)?,
+            created_at: parse_time(14, "created_at", row.get(14)?)?,
+        })
+    }
+
+    /// Helper function to convert a send_runs summary row to SendRun
+    fn row_to_send_run(row: &Row) -> rusqlite::Result<SendRun> {
+        let completed_at: Option<String> = row.get(3)?;
+
+        Ok(SendRun {
+            id: row.get(0)?,
+            subject: row.get(1)?,
+            started_at: parse_time(2, "started_at", row.get(2)?)?,
+            completed_at: completed_at
+                .map(|value| parse_time(3, "completed_at", value))
+                .transpose()?,
+            recipients: row.get::<_, i64>(4)? as usize,
+            sent: row.get::<_, i64>(5)? as usize,
+            failed: row.get::<_, i64>(6)? as usize,
+        })
+    }
+
     /// Helper function to convert database row to Subscriber
     fn row_to_subscriber(&self, row: &Row) -> rusqlite::Result<Subscriber> {
         let subscribed_at_str: String = row.get(3)?;
@@ -310,6 +1471,8 @@ impl NewsletterDatabase {
             approved_at,
             source_email_id: row.get(5)?,
             notes: row.get(6)?,
+            tags: split_list(&row.get::<_, String>(7)?),
+            name: row.get(8)?,
         })
     }
 }
@@ -364,6 +1527,353 @@ mod tests {
         // Test removing subscriber
         assert!(db.remove_subscriber("test@example.com")?);
         assert!(!db.email_exists("test@example.com")?);
+        assert!(!db.remove_subscriber("test@example.com")?);
+        assert_eq!(db.get_removal_times()?.len(), 1);
+
+        // Restoring undoes the removal in the statistics too
+        let other = Subscriber::new("other@example.com".to_string(), None);
+        db.add_subscriber(&other)?;
+        assert!(db.remove_subscriber("other@example.com")?);
+        assert_eq!(db.get_removal_times()?.len(), 2);
+        db.restore_subscriber(&other)?;
+        assert!(db.email_exists("other@example.com")?);
+        assert_eq!(db.get_removal_times()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_queue() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        let now = Utc::now();
+        let past = db.enqueue_newsletter(
+            "Past",
+            "<p>html</p>",
+            "text",
+            now - chrono::Duration::hours(1),
+            &["tag:rust".to_string()],
+        )?;
+        let future = db.enqueue_newsletter(
+            "Future",
+            "<p>html</p>",
+            "text",
+            now + chrono::Duration::hours(1),
+            &[],
+        )?;
+
+        let due = db.get_due_newsletters(now)?;
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, past);
+        assert_eq!(due[0].subject, "Past");
+        assert_eq!(due[0].segments, vec!["tag:rust"]);
+
+        // Only one run can claim a newsletter
+        assert!(db.claim_queued_newsletter(past)?);
+        assert!(!db.claim_queued_newsletter(past)?);
+        assert!(db.get_due_newsletters(now)?.is_empty());
+
+        db.update_queue_status(past, QueueStatus::Sent, None)?;
+        let sent = db.get_queued_newsletters(Some(QueueStatus::Sent))?;
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].sent_at.is_some());
+
+        assert!(db.cancel_queued_newsletter(future)?);
+        assert!(!db.cancel_queued_newsletter(past)?);
+        assert_eq!(
+            db.get_queued_newsletters(Some(QueueStatus::Cancelled))?
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resumable_send_runs() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        let (run_id, resumed) = db.start_or_resume_send_run("key-1", "Hello")?;
+        assert!(!resumed);
+
+        db.record_delivery(run_id, "a@example.com", None)?;
+        db.record_delivery(run_id, "b@example.com", Some("421 try later"))?;
+
+        // A second attempt picks up the same run and skips delivered addresses
+        let (resumed_id, resumed) = db.start_or_resume_send_run("key-1", "Hello")?;
+        assert!(resumed);
+        assert_eq!(resumed_id, run_id);
+        let delivered = db.get_delivered_emails(run_id)?;
+        assert!(delivered.contains("a@example.com"));
+        assert!(!delivered.contains("b@example.com"));
+
+        db.record_delivery(run_id, "b@example.com", None)?;
+        assert_eq!(db.get_delivered_emails(run_id)?.len(), 2);
+
+        db.complete_send_run(run_id)?;
+        let (new_id, resumed) = db.start_or_resume_send_run("key-1", "Hello")?;
+        assert!(!resumed);
+        assert_ne!(new_id, run_id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_history() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        let (first, _) = db.start_or_resume_send_run("key-1", "First")?;
+        db.set_send_run_recipients(first, 2)?;
+        db.record_delivery(first, "a@example.com", None)?;
+        db.record_delivery(first, "b@example.com", None)?;
+        db.complete_send_run(first)?;
+
+        let (second, _) = db.start_or_resume_send_run("key-2", "Second")?;
+        db.set_send_run_recipients(second, 3)?;
+        db.record_delivery(second, "a@example.com", None)?;
+        db.record_delivery(second, "c@example.com", Some("550 mailbox unavailable"))?;
+
+        let runs = db.get_send_runs(10)?;
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, second);
+        assert_eq!(
+            (runs[0].recipients, runs[0].sent, runs[0].failed),
+            (3, 1, 1)
+        );
+        assert!(runs[0].completed_at.is_none());
+        assert_eq!((runs[1].sent, runs[1].failed), (2, 0));
+        assert!(runs[1].completed_at.is_some());
+        assert_eq!(db.get_send_runs(1)?.len(), 1);
+
+        let deliveries = db.get_deliveries(second)?;
+        assert_eq!(deliveries[0].email, "c@example.com");
+        assert!(!deliveries[0].sent);
+        assert_eq!(
+            deliveries[0].error.as_deref(),
+            Some("550 mailbox unavailable")
+        );
+        assert!(deliveries[1].sent);
+
+        assert_eq!(db.get_send_run(first)?.unwrap().subject, "First");
+        assert!(db.get_send_run(99)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tracking_events() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        let (run_id, _) = db.start_or_resume_send_run("key-1", "Hello")?;
+        db.set_tracking_links(
+            run_id,
+            &[
+                "https://example.com/a".to_string(),
+                "https://example.com/b".to_string(),
+            ],
+        )?;
+        let tokens = db.tracking_tokens(run_id, &["a@example.com", "b@example.com"])?;
+        assert_eq!(
+            db.tracking_tokens(run_id, &["a@example.com"])?["a@example.com"],
+            tokens["a@example.com"]
+        );
+        db.record_delivery(run_id, "a@example.com", None)?;
+        db.record_delivery(run_id, "b@example.com", None)?;
+
+        let a = &tokens["a@example.com"];
+        let b = &tokens["b@example.com"];
+        assert!(db.record_open(a)?);
+        assert!(db.record_open(a)?);
+        assert!(db.record_open(b)?);
+        assert!(!db.record_open("unknown")?);
+
+        assert_eq!(
+            db.record_click(a, 1)?.as_deref(),
+            Some("https://example.com/b")
+        );
+        db.record_click(b, 1)?;
+        db.record_click(b, 1)?;
+        assert_eq!(db.record_click(a, 7)?, None);
+        assert_eq!(db.record_click("unknown", 0)?, None);
+
+        let stats = db.get_tracking_stats(run_id)?.unwrap();
+        assert_eq!(stats.delivered, 2);
+        assert_eq!(stats.tracked, 2);
+        assert_eq!((stats.unique_opens, stats.total_opens), (2, 3));
+        assert_eq!((stats.unique_clicks, stats.total_clicks), (2, 3));
+        assert_eq!(stats.links[0].url, "https://example.com/b");
+        assert_eq!(
+            (stats.links[0].unique_clicks, stats.links[0].total_clicks),
+            (2, 3)
+        );
+        assert_eq!(stats.links[1].total_clicks, 0);
+
+        assert!(db.get_tracking_stats(run_id + 1)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ab_tests() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        let now = Utc::now();
+        let id = db.create_ab_test(
+            "Subject A",
+            "Subject B",
+            "<p>Hi</p>",
+            "Hi",
+            &["tag:rust".to_string()],
+            20,
+            now + chrono::Duration::hours(4),
+        )?;
+        let (run_a, _) = db.start_or_resume_send_run("key-a", "Subject A")?;
+        db.set_ab_test_run(id, "a", run_a)?;
+        assert!(db.set_ab_test_run(id, "c", run_a).is_err());
+
+        let test = db.get_ab_test(id)?.unwrap();
+        assert_eq!(test.status, AbTestStatus::Testing);
+        assert_eq!(test.run_a, Some(run_a));
+        assert_eq!(test.run_b, None);
+        assert_eq!(test.segments, vec!["tag:rust"]);
+
+        assert!(db.get_due_ab_tests(now)?.is_empty());
+        assert_eq!(
+            db.get_due_ab_tests(now + chrono::Duration::hours(5))?.len(),
+            1
+        );
+
+        assert!(db.claim_ab_test(id)?);
+        assert!(!db.claim_ab_test(id)?);
+        db.finish_ab_test(id, "b", None, Some("SMTP down"))?;
+        assert_eq!(db.get_ab_test(id)?.unwrap().status, AbTestStatus::Failed);
+
+        assert!(db.claim_ab_test(id)?);
+        db.finish_ab_test(id, "b", Some(run_a), None)?;
+        let test = db.get_ab_test(id)?.unwrap();
+        assert_eq!(test.status, AbTestStatus::Completed);
+        assert_eq!(test.winner.as_deref(), Some("b"));
+        assert!(test.error.is_none());
+        assert_eq!(db.get_ab_tests()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_subscribers() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        for i in 0..5 {
+            let mut subscriber = Subscriber::new(format!("user{}@example.com", i), None);
+            subscriber.subscribed_at = Utc::now() - chrono::Duration::minutes(i);
+            if i % 2 == 0 {
+                subscriber.status = SubscriberStatus::Approved;
+                subscriber.tags = vec!["rust".to_string()];
+            }
+            if i == 3 {
+                subscriber.notes = Some("Found via Mastodon".to_string());
+            }
+            db.add_subscriber(&subscriber)?;
+        }
+
+        let (page, total) = db.query_subscribers(&SubscriberQuery::default(), Some(2), 2)?;
+        assert_eq!(total, 5);
+        let emails: Vec<&str> = page.iter().map(|s| s.email.as_str()).collect();
+        assert_eq!(emails, vec!["user2@example.com", "user3@example.com"]);
+
+        let approved = SubscriberQuery {
+            status: Some(SubscriberStatus::Approved),
+            tag: Some("Rust".to_string()),
+            ..Default::default()
+        };
+        let (page, total) = db.query_subscribers(&approved, None, 0)?;
+        assert_eq!((page.len(), total), (3, 3));
+
+        let search = SubscriberQuery {
+            search: Some("mastodon".to_string()),
+            ..Default::default()
+        };
+        let (page, _) = db.query_subscribers(&search, Some(10), 0)?;
+        assert_eq!(page[0].email, "user3@example.com");
+
+        let (page, total) = db.query_subscribers(&SubscriberQuery::default(), Some(10), 10)?;
+        assert!(page.is_empty());
+        assert_eq!(total, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscriber_details() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        let subscriber = Subscriber::new("reader@example.com".to_string(), Some("7".to_string()));
+        let id = db.add_subscriber(&subscriber)?;
+        let source = SourceEmail {
+            from: "Reader <reader@example.com>".to_string(),
+            subject: "Subscribe".to_string(),
+            date: Some("Mon, 1 Jan 2024 10:00:00 +0000".to_string()),
+            body: "Please add me".to_string(),
+        };
+        db.set_source_email("reader@example.com", &source)?;
+        assert_eq!(db.get_source_email("reader@example.com")?, Some(source));
+
+        assert!(db.set_subscriber_notes("reader@example.com", Some("Met at RustConf"))?);
+        let stored = db.get_subscriber_by_email("reader@example.com")?.unwrap();
+        assert_eq!(stored.notes.as_deref(), Some("Met at RustConf"));
+
+        db.update_subscriber_status(id, SubscriberStatus::Approved)?;
+        db.restore_subscriber_status(id, SubscriberStatus::Pending, None)?;
+        let stored = db.get_subscriber_by_email("reader@example.com")?.unwrap();
+        assert_eq!(stored.status, SubscriberStatus::Pending);
+        assert!(stored.approved_at.is_none());
+
+        assert!(db.remove_subscriber("reader@example.com")?);
+        assert_eq!(db.get_source_email("reader@example.com")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscriber_tags() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let db = NewsletterDatabase::open(temp_file.path())?;
+
+        let mut subscriber = Subscriber::new("tagged@example.com".to_string(), None);
+        subscriber.tags = vec!["Rust".to_string(), " cli ".to_string(), "rust".to_string()];
+        db.add_subscriber(&subscriber)?;
+
+        let stored = db.get_subscriber_by_email("tagged@example.com")?.unwrap();
+        assert_eq!(stored.tags, vec!["cli", "rust"]);
+        assert!(stored.has_tag("RUST"));
+
+        assert!(db.set_subscriber_tags("tagged@example.com", &["web".to_string()])?);
+        let stored = db.get_subscriber_by_email("tagged@example.com")?.unwrap();
+        assert_eq!(stored.tags, vec!["web"]);
+
+        // A comma would split the tag when it is read back
+        let tags = ["news,letters".to_string(), ",".to_string()];
+        assert!(db.set_subscriber_tags("tagged@example.com", &tags)?);
+        let reloaded = db.get_subscriber_by_email("tagged@example.com")?.unwrap();
+        assert_eq!(reloaded.tags, vec!["newsletters"]);
+        assert!(!db.set_subscriber_tags("missing@example.com", &[])?);
+
+        assert_eq!(stored.name, None);
+        assert!(db.set_subscriber_name("tagged@example.com", Some("Ada"))?);
+        let stored = db.get_subscriber_by_email("tagged@example.com")?.unwrap();
+        assert_eq!(stored.name.as_deref(), Some("Ada"));
+
+        // Reopening must not re-run migrations
+        drop(db);
+        let db = NewsletterDatabase::open(temp_file.path())?;
+        assert_eq!(db.get_subscriber_count(None)?, 1);
 
         Ok(())
     }
@@ -1,17 +1,45 @@
 //! Email fetching and parsing functionality
+//!
+//! Which mailbox is read and what counts as a subscribe or unsubscribe
+//! request come from `[newsletter.fetch]`.
 
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use imap::Session;
 use mailparse::{parse_mail, MailHeaderMap};
 use native_tls::{TlsConnector, TlsStream};
 use std::collections::HashSet;
 use std::net::TcpStream;
 
-use super::database::{NewsletterDatabase, Subscriber};
-use crate::config::ImapConfig;
+use super::database::{NewsletterDatabase, SourceEmail, Subscriber};
+use super::oauth;
+use crate::config::{FetchRulesConfig, ImapConfig};
 
 pub struct EmailFetcher {
     session: Option<Session<TlsStream<TcpStream>>>,
+    rules: FetchRulesConfig,
+}
+
+/// SASL XOAUTH2 sign-in with an OAuth2 access token
+struct XOAuth2<'a> {
+    username: &'a str,
+    access_token: &'a str,
+}
+
+impl imap::Authenticator for XOAuth2<'_> {
+    type Response = String;
+
+    fn process(&self, _challenge: &[u8]) -> Self::Response {
+        oauth::xoauth2(self.username, self.access_token)
+    }
+}
+
+/// What a fetched email asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailAction {
+    Subscribe,
+    Unsubscribe,
+    Ignore,
 }
 
 #[derive(Debug, Clone)]
@@ -20,13 +48,21 @@ pub struct FetchedEmail {
     pub from: String,
     pub subject: String,
     pub body: String,
-    #[allow(dead_code)]
     pub date: Option<String>,
 }
 
 impl EmailFetcher {
     pub fn new() -> Self {
-        Self { session: None }
+        Self {
+            session: None,
+            rules: FetchRulesConfig::default(),
+        }
+    }
+
+    /// Use `[newsletter.fetch]` rules instead of the defaults
+    pub fn with_rules(mut self, rules: FetchRulesConfig) -> Self {
+        self.rules = rules;
+        self
     }
 
     /// Connect to IMAP server with authentication
@@ -38,51 +74,74 @@ impl EmailFetcher {
         let client = imap::connect((config.server.as_str(), config.port), &config.server, &tls)
             .context("Failed to connect to IMAP server")?;
 
-        let session = client
-            .login(&config.username, password)
-            .map_err(|e| anyhow::anyhow!("IMAP login failed: {:?}", e.0))?;
+        // With OAuth2 the password is an access token
+        let session = match &config.oauth {
+            Some(_) => client
+                .authenticate(
+                    "XOAUTH2",
+                    &XOAuth2 {
+                        username: &config.username,
+                        access_token: password,
+                    },
+                )
+                .map_err(|e| anyhow::anyhow!("IMAP XOAUTH2 sign-in failed: {:?}", e.0))?,
+            None => client
+                .login(&config.username, password)
+                .map_err(|e| anyhow::anyhow!("IMAP login failed: {:?}", e.0))?,
+        };
 
         self.session = Some(session);
-        println!(
+        crate::status!(
             "✓ Connected to IMAP server: {}:{}",
-            config.server, config.port
+            config.server,
+            config.port
         );
 
         Ok(())
     }
 
-    /// Fetch unseen emails from the inbox
-    pub fn fetch_subscription_emails(&mut self) -> Result<Vec<FetchedEmail>> {
+    /// Fetch emails from the configured folder, optionally only those received
+    /// on or after `since`
+    pub fn fetch_subscription_emails(
+        &mut self,
+        since: Option<NaiveDate>,
+    ) -> Result<Vec<FetchedEmail>> {
         let session = self
             .session
             .as_mut()
             .ok_or_else(|| anyhow::anyhow!("Not connected to IMAP server"))?;
 
-        // Select INBOX
-        session.select("INBOX").context("Failed to select INBOX")?;
+        let folder = &self.rules.folder;
+        session
+            .select(folder)
+            .with_context(|| format!("Failed to select folder {}", folder))?;
 
-        // Search for unseen emails
-        let message_ids = session
-            .search("UNSEEN")
-            .context("Failed to search for unseen emails")?;
+        let query = search_query(self.rules.unseen_only, since);
+        tracing::debug!("Searching {} for {}", folder, query);
+        let mut message_ids: Vec<u32> = session
+            .search(&query)
+            .with_context(|| format!("Failed to search {} for {}", folder, query))?
+            .into_iter()
+            .collect();
+        message_ids.sort_unstable();
 
         if message_ids.is_empty() {
-            println!("No new emails found.");
+            crate::status!("No new emails found.");
             return Ok(Vec::new());
         }
 
-        println!("Found {} new emails to process", message_ids.len());
+        crate::status!("Found {} new emails to process", message_ids.len());
 
         let mut fetched_emails = Vec::new();
 
         for &msg_id in &message_ids {
             match Self::fetch_single_email(session, msg_id) {
                 Ok(email) => {
-                    println!("Processed email from: {}", email.from);
+                    tracing::debug!("Processed email {} from: {}", msg_id, email.from);
                     fetched_emails.push(email);
                 }
                 Err(e) => {
-                    eprintln!("Failed to process email {}: {}", msg_id, e);
+                    tracing::warn!("Failed to process email {}: {:#}", msg_id, e);
                     continue;
                 }
             }
@@ -164,28 +223,29 @@ impl EmailFetcher {
         let mut seen_emails = HashSet::new();
 
         for email in emails {
+            if !self.is_subscription_email(email) {
+                continue;
+            }
+
             // Extract email address from "From" field
             let subscriber_email = self.extract_email_address(&email.from)?;
 
             // Skip duplicates
-            if seen_emails.contains(&subscriber_email) {
+            if !seen_emails.insert(subscriber_email.clone()) {
                 continue;
             }
-            seen_emails.insert(subscriber_email.clone());
 
             // Validate email format
             if !self.is_valid_email(&subscriber_email) {
-                println!("Warning: Skipping invalid email: {}", subscriber_email);
+                tracing::warn!("Skipping invalid email: {}", subscriber_email);
                 continue;
             }
 
-            // Check if this looks like a subscription email
-            if self.is_subscription_email(email) {
-                let subscriber =
-                    Subscriber::new(subscriber_email.clone(), Some(email.id.to_string()));
-                println!("Found subscription from: {}", subscriber.email);
-                subscribers.push(subscriber);
-            }
+            let mut subscriber =
+                Subscriber::new(subscriber_email.clone(), Some(email.id.to_string()));
+            subscriber.name = display_name(&email.from);
+            crate::status!("Found subscription from: {}", subscriber.email);
+            subscribers.push(subscriber);
         }
 
         Ok(subscribers)
@@ -220,45 +280,50 @@ impl EmailFetcher {
 
     /// Basic email validation
     fn is_valid_email(&self, email: &str) -> bool {
-        email.contains('@')
-            && email.contains('.')
-            && !email.starts_with('@')
-            && !email.ends_with('@')
-            && email.len() > 5
+        is_valid_email(email)
     }
 
     /// Check if email looks like a subscription request
     fn is_subscription_email(&self, email: &FetchedEmail) -> bool {
-        let subject_lower = email.subject.to_lowercase();
-        let body_lower = email.body.to_lowercase();
-
-        // Look for subscription-related keywords
-        let subscription_keywords = [
-            "subscribe",
-            "subscription",
-            "newsletter",
-            "sign up",
-            "signup",
-            "join",
-            "mailing list",
-            "updates",
-            "notifications",
-        ];
-
-        let unsubscribe_keywords = ["unsubscribe", "remove", "stop", "opt out", "opt-out"];
-
-        // Check for subscription keywords
-        let has_subscribe_keywords = subscription_keywords
-            .iter()
-            .any(|keyword| subject_lower.contains(keyword) || body_lower.contains(keyword));
+        self.classify(email) == EmailAction::Subscribe
+    }
 
-        // Check for unsubscribe keywords (we want to exclude these)
-        let has_unsubscribe_keywords = unsubscribe_keywords
+    /// Sort an email by the sender and subject (and body) patterns. Unsubscribe
+    /// patterns are checked first, since "unsubscribe" contains "subscribe".
+    pub fn classify(&self, email: &FetchedEmail) -> EmailAction {
+        let rules = &self.rules;
+        let sender = self
+            .extract_email_address(&email.from)
+            .unwrap_or_else(|_| email.from.to_lowercase());
+        if rules
+            .ignore_senders
             .iter()
-            .any(|keyword| subject_lower.contains(keyword) || body_lower.contains(keyword));
+            .any(|pattern| matches_pattern(pattern, &sender))
+        {
+            return EmailAction::Ignore;
+        }
+        if !rules.senders.is_empty()
+            && !rules
+                .senders
+                .iter()
+                .any(|pattern| matches_pattern(pattern, &sender))
+        {
+            return EmailAction::Ignore;
+        }
 
-        // This is a subscription email if it has subscribe keywords but no unsubscribe keywords
-        has_subscribe_keywords && !has_unsubscribe_keywords
+        let matches = |patterns: &[String]| {
+            patterns.iter().any(|pattern| {
+                matches_pattern(pattern, &email.subject)
+                    || (rules.match_body && matches_pattern(pattern, &email.body))
+            })
+        };
+        if matches(&rules.unsubscribe) {
+            EmailAction::Unsubscribe
+        } else if matches(&rules.subscribe) {
+            EmailAction::Subscribe
+        } else {
+            EmailAction::Ignore
+        }
     }
 
     /// Mark processed emails as seen
@@ -274,10 +339,64 @@ impl EmailFetcher {
                 .with_context(|| format!("Failed to mark email {} as seen", email_id))?;
         }
 
-        println!("Marked {} emails as seen", email_ids.len());
+        tracing::debug!("Marked {} emails as seen", email_ids.len());
         Ok(())
     }
 
+    /// Move emails to the configured archive folder, if any
+    pub fn archive_emails(&mut self, email_ids: &[u32]) -> Result<()> {
+        let Some(folder) = self.rules.archive_folder.clone() else {
+            return Ok(());
+        };
+        if email_ids.is_empty() {
+            return Ok(());
+        }
+        let session = self
+            .session
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Not connected to IMAP server"))?;
+
+        let set = email_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        session
+            .copy(&set, &folder)
+            .with_context(|| format!("Failed to copy emails to {}", folder))?;
+        session
+            .store(&set, "+FLAGS (\\Deleted)")
+            .context("Failed to flag archived emails for deletion")?;
+        session
+            .expunge()
+            .context("Failed to expunge archived emails")?;
+
+        crate::status!("Archived {} emails to {}", email_ids.len(), folder);
+        Ok(())
+    }
+
+    /// Remove the senders of unsubscribe requests, returning the removed addresses
+    pub fn process_unsubscribes(
+        &self,
+        emails: &[FetchedEmail],
+        database: &mut NewsletterDatabase,
+    ) -> Result<Vec<String>> {
+        let mut removed = Vec::new();
+        for email in emails {
+            if self.classify(email) != EmailAction::Unsubscribe {
+                continue;
+            }
+            let Ok(address) = self.extract_email_address(&email.from) else {
+                continue;
+            };
+            if !removed.contains(&address) && database.remove_subscriber(&address)? {
+                crate::status!("Removed subscriber: {}", address);
+                removed.push(address);
+            }
+        }
+        Ok(removed)
+    }
+
     /// Process new subscribers and add them to database
     pub fn process_subscribers(
         &self,
@@ -290,18 +409,33 @@ impl EmailFetcher {
         for subscriber in new_subscribers {
             // Check if subscriber already exists
             if database.email_exists(&subscriber.email)? {
-                println!("Subscriber already exists: {}", subscriber.email);
+                tracing::debug!("Subscriber already exists: {}", subscriber.email);
                 continue;
             }
 
             // Add new subscriber
             match database.add_subscriber(&subscriber) {
                 Ok(_) => {
-                    println!("Added new subscriber: {}", subscriber.email);
+                    crate::status!("Added new subscriber: {}", subscriber.email);
+                    // Keep the request itself so it can be reviewed before approving
+                    let source = emails
+                        .iter()
+                        .find(|e| subscriber.source_email_id == Some(e.id.to_string()));
+                    if let Some(email) = source {
+                        database.set_source_email(
+                            &subscriber.email,
+                            &SourceEmail {
+                                from: email.from.clone(),
+                                subject: email.subject.clone(),
+                                date: email.date.clone(),
+                                body: email.body.clone(),
+                            },
+                        )?;
+                    }
                     added_subscribers.push(subscriber);
                 }
                 Err(e) => {
-                    eprintln!("Failed to add subscriber {}: {}", subscriber.email, e);
+                    tracing::error!("Failed to add subscriber {}: {:#}", subscriber.email, e);
                 }
             }
         }
@@ -315,12 +449,62 @@ impl EmailFetcher {
             session
                 .logout()
                 .context("Failed to logout from IMAP server")?;
-            println!("✓ Disconnected from IMAP server");
+            tracing::debug!("Disconnected from IMAP server");
         }
         Ok(())
     }
 }
 
+/// IMAP SEARCH criteria for the fetch
+fn search_query(unseen_only: bool, since: Option<NaiveDate>) -> String {
+    let mut criteria = Vec::new();
+    if unseen_only {
+        criteria.push("UNSEEN".to_string());
+    }
+    if let Some(date) = since {
+        criteria.push(format!("SINCE {}", date.format("%-d-%b-%Y")));
+    }
+    if criteria.is_empty() {
+        "ALL".to_string()
+    } else {
+        criteria.join(" ")
+    }
+}
+
+/// Case-insensitive match anywhere in `text`, where `*` in the pattern
+/// matches any run of characters
+pub fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    if pattern.is_empty() {
+        return false;
+    }
+    let text = text.to_lowercase();
+    let mut rest = text.as_str();
+    for part in pattern.split('*').filter(|part| !part.is_empty()) {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// The name in a "From" field like `"John Doe" <john@example.com>`
+fn display_name(from_field: &str) -> Option<String> {
+    let (name, _) = from_field.split_once('<')?;
+    let name = name.trim().trim_matches('"').trim();
+    (!name.is_empty() && !name.contains('@')).then(|| name.to_string())
+}
+
+/// Basic email validation, shared with the API server's signup endpoint
+pub fn is_valid_email(email: &str) -> bool {
+    email.contains('@')
+        && email.contains('.')
+        && !email.starts_with('@')
+        && !email.ends_with('@')
+        && email.len() > 5
+}
+
 impl Drop for EmailFetcher {
     fn drop(&mut self) {
         let _ = self.disconnect();
@@ -359,6 +543,13 @@ mod tests {
                 .unwrap(),
             "john@example.com"
         );
+
+        assert_eq!(
+            display_name("\"John Doe\" <john@example.com>").as_deref(),
+            Some("John Doe")
+        );
+        assert_eq!(display_name("<john@example.com>"), None);
+        assert_eq!(display_name("john@example.com"), None);
     }
 
     #[test]
@@ -408,4 +599,60 @@ mod tests {
 
         assert!(!fetcher.is_subscription_email(&regular_email));
     }
+
+    #[test]
+    fn test_fetch_rules() {
+        let email = |from: &str, subject: &str, body: &str| FetchedEmail {
+            id: 1,
+            from: from.to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+            date: None,
+        };
+
+        assert!(matches_pattern("*@example.com", "jane@example.com"));
+        assert!(matches_pattern("Join", "please JOIN the list"));
+        assert!(matches_pattern("sign*up", "sign me up"));
+        assert!(!matches_pattern("sign*up", "up, sign"));
+        assert!(!matches_pattern("", "anything"));
+
+        let fetcher = EmailFetcher::new();
+        assert_eq!(
+            fetcher.classify(&email("jane@example.com", "Unsubscribe", "")),
+            EmailAction::Unsubscribe
+        );
+        assert_eq!(
+            fetcher.classify(&email("noreply@shop.com", "Join our newsletter", "")),
+            EmailAction::Ignore
+        );
+
+        let fetcher = EmailFetcher::new().with_rules(FetchRulesConfig {
+            subscribe: vec!["[blog] subscribe".to_string()],
+            unsubscribe: vec!["[blog] leave".to_string()],
+            match_body: false,
+            senders: vec!["*@example.com".to_string()],
+            ..FetchRulesConfig::default()
+        });
+        assert_eq!(
+            fetcher.classify(&email("Jane <jane@example.com>", "[Blog] Subscribe", "")),
+            EmailAction::Subscribe
+        );
+        assert_eq!(
+            fetcher.classify(&email("jane@example.com", "[blog] leave", "")),
+            EmailAction::Unsubscribe
+        );
+        assert_eq!(
+            fetcher.classify(&email("jane@example.com", "Hi", "[blog] subscribe")),
+            EmailAction::Ignore
+        );
+        assert_eq!(
+            fetcher.classify(&email("bob@other.org", "[blog] subscribe", "")),
+            EmailAction::Ignore
+        );
+
+        let since = NaiveDate::from_ymd_opt(2024, 3, 5);
+        assert_eq!(search_query(true, None), "UNSEEN");
+        assert_eq!(search_query(true, since), "UNSEEN SINCE 5-Mar-2024");
+        assert_eq!(search_query(false, None), "ALL");
+    }
 }
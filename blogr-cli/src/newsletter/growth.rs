@@ -0,0 +1,243 @@
+//! Subscriber growth over time
+//!
+//! Counts new, approved and removed subscribers per week or month from the
+//! subscribers' `subscribed_at` and `approved_at` times and the removal log.
+//! Removals are only logged from the version that added them, and an
+//! approved subscriber who was later removed no longer counts as approved.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+use super::database::{Subscriber, SubscriberStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrowthPeriod {
+    /// Weeks starting on Monday
+    Week,
+    Month,
+}
+
+impl GrowthPeriod {
+    /// First day of the period containing `date`
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            GrowthPeriod::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            GrowthPeriod::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the period after the one starting at `start`
+    fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            GrowthPeriod::Week => start + Duration::days(7),
+            GrowthPeriod::Month => start
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(start),
+        }
+    }
+
+    /// First day of the period before the one starting at `start`
+    fn previous(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            GrowthPeriod::Week => start - Duration::days(7),
+            GrowthPeriod::Month => start
+                .checked_sub_months(chrono::Months::new(1))
+                .unwrap_or(start),
+        }
+    }
+
+    fn label(&self, start: NaiveDate) -> String {
+        match self {
+            GrowthPeriod::Week => start.format("%Y-%m-%d").to_string(),
+            GrowthPeriod::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+impl fmt::Display for GrowthPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrowthPeriod::Week => write!(f, "week"),
+            GrowthPeriod::Month => write!(f, "month"),
+        }
+    }
+}
+
+impl FromStr for GrowthPeriod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "week" | "weekly" => Ok(GrowthPeriod::Week),
+            "month" | "monthly" => Ok(GrowthPeriod::Month),
+            other => Err(anyhow::anyhow!(
+                "Unknown period '{}'. Expected 'week' or 'month'.",
+                other
+            )),
+        }
+    }
+}
+
+/// Changes in one week or month
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GrowthRow {
+    /// `2024-05-06` for the week starting that Monday, `2024-05` for a month
+    pub period: String,
+    pub start: NaiveDate,
+    /// Subscription requests received
+    pub new: usize,
+    pub approved: usize,
+    pub removed: usize,
+    /// Subscribers of any status at the end of the period
+    pub total: usize,
+}
+
+/// The last `periods` weeks or months up to `today`, oldest first, including
+/// ones where nothing happened
+pub fn growth(
+    subscribers: &[Subscriber],
+    removals: &[DateTime<Utc>],
+    period: GrowthPeriod,
+    periods: usize,
+    today: NaiveDate,
+) -> Vec<GrowthRow> {
+    let mut starts = vec![period.start(today)];
+    while starts.len() < periods {
+        let previous = period.previous(starts[starts.len() - 1]);
+        starts.push(previous);
+    }
+    starts.reverse();
+
+    let subscribed: Vec<NaiveDate> = subscribers
+        .iter()
+        .map(|subscriber| subscriber.subscribed_at.date_naive())
+        .collect();
+    let approved: Vec<NaiveDate> = subscribers
+        .iter()
+        .filter(|subscriber| subscriber.status == SubscriberStatus::Approved)
+        .filter_map(|subscriber| subscriber.approved_at)
+        .map(|at| at.date_naive())
+        .collect();
+    let removed: Vec<NaiveDate> = removals.iter().map(|at| at.date_naive()).collect();
+
+    let between = |dates: &[NaiveDate], from: NaiveDate, to: NaiveDate| {
+        dates
+            .iter()
+            .filter(|date| **date >= from && **date < to)
+            .count()
+    };
+    let after =
+        |dates: &[NaiveDate], from: NaiveDate| dates.iter().filter(|date| **date >= from).count();
+
+    starts
+        .into_iter()
+        .map(|start| {
+            let end = period.next(start);
+            // Work back from today's count so subscribers from before the
+            // first period are included
+            let total =
+                (subscribers.len() + after(&removed, end)).saturating_sub(after(&subscribed, end));
+            GrowthRow {
+                period: period.label(start),
+                start,
+                new: between(&subscribed, start, end),
+                approved: between(&approved, start, end),
+                removed: between(&removed, start, end),
+                total,
+            }
+        })
+        .collect()
+}
+
+/// One block character per value, scaled to the largest
+pub fn sparkline(values: &[usize]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(
+            |value| match (value * (BLOCKS.len() - 1)).checked_div(max) {
+                Some(level) => BLOCKS[level],
+                None => BLOCKS[0],
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> DateTime<Utc> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    fn subscriber(subscribed: &str, approved: Option<&str>) -> Subscriber {
+        let mut subscriber = Subscriber::new(format!("{}@example.com", subscribed), None);
+        subscriber.subscribed_at = at(subscribed);
+        if let Some(approved) = approved {
+            subscriber.status = SubscriberStatus::Approved;
+            subscriber.approved_at = Some(at(approved));
+        }
+        subscriber
+    }
+
+    #[test]
+    fn test_growth() {
+        let subscribers = vec![
+            subscriber("2024-01-10", Some("2024-01-10")),
+            subscriber("2024-04-29", Some("2024-05-07")),
+            subscriber("2024-05-08", None),
+            subscriber("2024-05-20", Some("2024-05-21")),
+        ];
+        // Someone who subscribed in January and left in May
+        let removals = vec![at("2024-05-09")];
+        let today = NaiveDate::from_ymd_opt(2024, 5, 22).unwrap();
+
+        let weeks = growth(&subscribers, &removals, GrowthPeriod::Week, 4, today);
+        let summary: Vec<(&str, usize, usize, usize, usize)> = weeks
+            .iter()
+            .map(|row| {
+                (
+                    row.period.as_str(),
+                    row.new,
+                    row.approved,
+                    row.removed,
+                    row.total,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("2024-04-29", 1, 0, 0, 3),
+                ("2024-05-06", 1, 1, 1, 3),
+                ("2024-05-13", 0, 0, 0, 3),
+                ("2024-05-20", 1, 1, 0, 4),
+            ]
+        );
+
+        let months = growth(&subscribers, &removals, GrowthPeriod::Month, 5, today);
+        assert_eq!(months[0].period, "2024-01");
+        assert_eq!(months[0].new, 1);
+        assert_eq!(months[3].total, 3);
+        assert_eq!(months[4].new, 2);
+        assert_eq!(months[4].total, 4);
+
+        assert_eq!(sparkline(&[0, 1, 4, 8]), "▁▁▄█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(
+            "monthly".parse::<GrowthPeriod>().unwrap(),
+            GrowthPeriod::Month
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Subscriber list hygiene
+//!
+//! Finds duplicate, malformed and role addresses so `blogr newsletter clean`
+//! can report or fix them.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::database::{Subscriber, SubscriberStatus};
+
+/// Local parts that belong to a function or team rather than a person
+const ROLE_ACCOUNTS: &[&str] = &[
+    "abuse",
+    "admin",
+    "administrator",
+    "billing",
+    "contact",
+    "donotreply",
+    "do-not-reply",
+    "help",
+    "hostmaster",
+    "info",
+    "mailer-daemon",
+    "marketing",
+    "no-reply",
+    "noreply",
+    "office",
+    "postmaster",
+    "root",
+    "sales",
+    "security",
+    "support",
+    "team",
+    "webmaster",
+];
+
+/// Domains where dots and `+tag` suffixes in the local part are ignored
+const GMAIL_DOMAINS: &[&str] = &["gmail.com", "googlemail.com"];
+
+/// A problem found with a subscriber's address
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// Not a usable email address
+    Invalid,
+    /// Same mailbox as another subscriber, which is kept
+    Duplicate { of: String },
+    /// Address has upper case letters or surrounding whitespace
+    Unnormalized { normalized: String },
+    /// Shared mailbox such as `noreply@` or `admin@`
+    RoleAccount,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Invalid => write!(f, "invalid address"),
+            Issue::Duplicate { of } => write!(f, "duplicate of {}", of),
+            Issue::Unnormalized { normalized } => write!(f, "should be {}", normalized),
+            Issue::RoleAccount => write!(f, "role account"),
+        }
+    }
+}
+
+/// A subscriber with a problem
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub subscriber: Subscriber,
+    pub issue: Issue,
+}
+
+/// Lower-case and trim an address. With `gmail_aliases`, Gmail addresses also
+/// lose dots and `+tag` suffixes, so `J.Doe+news@gmail.com` matches `jdoe@gmail.com`.
+pub fn normalize_email(email: &str, gmail_aliases: bool) -> String {
+    let email = email.trim().to_lowercase();
+    if !gmail_aliases {
+        return email;
+    }
+
+    match email.rsplit_once('@') {
+        Some((local, domain)) if GMAIL_DOMAINS.contains(&domain) => {
+            let local = local.split('+').next().unwrap_or(local).replace('.', "");
+            format!("{}@gmail.com", local)
+        }
+        _ => email,
+    }
+}
+
+/// Stricter than the check used while fetching: one `@`, no whitespace, and a
+/// domain with a dot that isn't at either end
+pub fn is_valid_address(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && !domain.contains('@')
+        && !email.chars().any(char::is_whitespace)
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains("..")
+}
+
+/// Whether an address belongs to a role rather than a person
+pub fn is_role_account(email: &str) -> bool {
+    let local = email.split('@').next().unwrap_or_default().to_lowercase();
+    let local = local.split('+').next().unwrap_or_default();
+    ROLE_ACCOUNTS.contains(&local)
+}
+
+/// Check every subscriber. Of a group of duplicates, the one with the best
+/// status (approved, then pending, then declined) is kept, oldest first.
+pub fn analyze(subscribers: &[Subscriber], gmail_aliases: bool) -> Vec<Finding> {
+    let mut groups: HashMap<String, Vec<&Subscriber>> = HashMap::new();
+    for subscriber in subscribers {
+        groups
+            .entry(normalize_email(&subscriber.email, gmail_aliases))
+            .or_default()
+            .push(subscriber);
+    }
+
+    let mut findings = Vec::new();
+    for subscriber in subscribers {
+        let group = &groups[&normalize_email(&subscriber.email, gmail_aliases)];
+        let kept = group
+            .iter()
+            .min_by_key(|s| (status_rank(&s.status), s.subscribed_at, s.id))
+            .expect("group contains the subscriber");
+
+        let issue = if kept.email != subscriber.email || kept.id != subscriber.id {
+            Issue::Duplicate {
+                of: kept.email.clone(),
+            }
+        } else if !is_valid_address(subscriber.email.trim()) {
+            Issue::Invalid
+        } else if normalize_email(&subscriber.email, false) != subscriber.email {
+            Issue::Unnormalized {
+                normalized: normalize_email(&subscriber.email, false),
+            }
+        } else if is_role_account(&subscriber.email) {
+            Issue::RoleAccount
+        } else {
+            continue;
+        };
+
+        findings.push(Finding {
+            subscriber: subscriber.clone(),
+            issue,
+        });
+    }
+
+    findings
+}
+
+fn status_rank(status: &SubscriberStatus) -> u8 {
+    match status {
+        SubscriberStatus::Approved => 0,
+        SubscriberStatus::Pending => 1,
+        SubscriberStatus::Declined => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscriber(id: i64, email: &str, status: SubscriberStatus) -> Subscriber {
+        let mut subscriber = Subscriber::new(email.to_string(), None);
+        subscriber.id = Some(id);
+        subscriber.status = status;
+        subscriber
+    }
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(
+            normalize_email(" Jane@Example.COM ", false),
+            "jane@example.com"
+        );
+        assert_eq!(
+            normalize_email("J.Doe+news@gmail.com", false),
+            "j.doe+news@gmail.com"
+        );
+        assert_eq!(
+            normalize_email("J.Doe+news@googlemail.com", true),
+            "jdoe@gmail.com"
+        );
+        assert_eq!(
+            normalize_email("j.doe+news@example.com", true),
+            "j.doe+news@example.com"
+        );
+    }
+
+    #[test]
+    fn test_address_checks() {
+        assert!(is_valid_address("jane@example.com"));
+        assert!(!is_valid_address("jane@example"));
+        assert!(!is_valid_address("jane@@example.com"));
+        assert!(!is_valid_address("ja ne@example.com"));
+        assert!(!is_valid_address("jane@example..com"));
+
+        assert!(is_role_account("NoReply@example.com"));
+        assert!(is_role_account("admin+site@example.com"));
+        assert!(!is_role_account("jane@example.com"));
+    }
+
+    #[test]
+    fn test_analyze() {
+        let subscribers = vec![
+            subscriber(1, "jane@example.com", SubscriberStatus::Pending),
+            subscriber(2, "Jane@Example.com", SubscriberStatus::Approved),
+            subscriber(3, "Bob@example.com", SubscriberStatus::Approved),
+            subscriber(4, "broken@", SubscriberStatus::Pending),
+            subscriber(5, "info@example.com", SubscriberStatus::Approved),
+            subscriber(6, "j.doe@gmail.com", SubscriberStatus::Approved),
+            subscriber(7, "jdoe+news@gmail.com", SubscriberStatus::Pending),
+        ];
+
+        let issues: Vec<(i64, Issue)> = analyze(&subscribers, true)
+            .into_iter()
+            .map(|f| (f.subscriber.id.unwrap(), f.issue))
+            .collect();
+
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    1,
+                    Issue::Duplicate {
+                        of: "Jane@Example.com".to_string()
+                    }
+                ),
+                (
+                    2,
+                    Issue::Unnormalized {
+                        normalized: "jane@example.com".to_string()
+                    }
+                ),
+                (
+                    3,
+                    Issue::Unnormalized {
+                        normalized: "bob@example.com".to_string()
+                    }
+                ),
+                (4, Issue::Invalid),
+                (5, Issue::RoleAccount),
+                (
+                    7,
+                    Issue::Duplicate {
+                        of: "j.doe@gmail.com".to_string()
+                    }
+                ),
+            ]
+        );
+
+        // Without alias folding the Gmail addresses are distinct
+        assert_eq!(analyze(&subscribers, false).len(), 5);
+    }
+}
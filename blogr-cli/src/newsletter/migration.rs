@@ -11,6 +11,7 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 use super::database::{NewsletterDatabase, Subscriber, SubscriberStatus};
+use crate::progress::Progress;
 
 /// Supported migration sources
 #[derive(Debug, Clone, PartialEq)]
@@ -187,14 +188,14 @@ impl MigrationManager {
 
     /// Import subscribers from a file
     pub fn import_from_file(&mut self, config: &MigrationConfig) -> Result<MigrationResult> {
-        println!("Starting migration from {:?} source...", config.source);
+        crate::status!("Starting migration from {:?} source...", config.source);
 
         let imported_data = match config.source {
             MigrationSource::Json => self.parse_json_file(config)?,
             _ => self.parse_csv_file(config)?,
         };
 
-        println!("Parsed {} subscribers from file", imported_data.len());
+        crate::status!("Parsed {} subscribers from file", imported_data.len());
 
         let mut result = MigrationResult {
             total_processed: imported_data.len(),
@@ -204,14 +205,16 @@ impl MigrationManager {
             imported_subscribers: Vec::new(),
         };
 
+        let progress = Progress::new(imported_data.len() as u64, "Importing subscribers");
         for imported in imported_data {
+            progress.inc();
             match self.import_subscriber(&imported, &config.source) {
                 Ok(subscriber) => {
                     // Check if subscriber already exists
                     match self.database.get_subscriber_by_email(&subscriber.email) {
                         Ok(Some(_)) => {
                             result.skipped_duplicates += 1;
-                            println!("Skipped duplicate: {}", subscriber.email);
+                            tracing::debug!("Skipped duplicate: {}", subscriber.email);
                         }
                         Ok(None) => {
                             // Add new subscriber
@@ -219,30 +222,31 @@ impl MigrationManager {
                                 Ok(_) => {
                                     result.successfully_imported += 1;
                                     result.imported_subscribers.push(subscriber);
-                                    println!("Imported: {}", imported.email);
+                                    tracing::debug!("Imported: {}", imported.email);
                                 }
                                 Err(e) => {
                                     let error = format!("Failed to save {}: {}", imported.email, e);
                                     result.errors.push(error.clone());
-                                    eprintln!("Error: {}", error);
+                                    tracing::error!("{}", error);
                                 }
                             }
                         }
                         Err(e) => {
                             let error = format!("Database error for {}: {}", imported.email, e);
                             result.errors.push(error.clone());
-                            eprintln!("Error: {}", error);
+                            tracing::error!("{}", error);
                         }
                     }
                 }
                 Err(e) => {
                     let error = format!("Failed to process {}: {}", imported.email, e);
                     result.errors.push(error.clone());
-                    eprintln!("Error: {}", error);
+                    tracing::error!("{}", error);
                 }
             }
         }
 
+        drop(progress);
         println!("\nMigration completed:");
         println!("  Total processed: {}", result.total_processed);
         println!("  Successfully imported: {}", result.successfully_imported);
@@ -279,7 +283,7 @@ impl MigrationManager {
 
             match self.parse_csv_subscriber(&line, &headers, config) {
                 Ok(subscriber) => subscribers.push(subscriber),
-                Err(e) => eprintln!("Warning: Failed to parse line {}: {}", line_num + 1, e),
+                Err(e) => tracing::warn!("Failed to parse line {}: {}", line_num + 1, e),
             }
         }
 
@@ -565,9 +569,6 @@ impl MigrationManager {
 
         // Create notes with migration info
         let mut notes = format!("Migrated from {:?}", source);
-        if !imported.tags.is_empty() {
-            notes.push_str(&format!(" | Tags: {}", imported.tags.join(", ")));
-        }
         if !imported.custom_fields.is_empty() {
             notes.push_str(&format!(" | Custom fields: {:?}", imported.custom_fields));
         }
@@ -584,6 +585,8 @@ impl MigrationManager {
             },
             source_email_id: Some(format!("migration-{:?}", source)),
             notes: Some(notes),
+            tags: imported.tags.clone(),
+            name: imported.name.clone(),
         })
     }
 
@@ -637,7 +640,7 @@ impl MigrationManager {
         config: &MigrationConfig,
         limit: Option<usize>,
     ) -> Result<Vec<ImportedSubscriber>> {
-        println!("Previewing migration from {:?} source...", config.source);
+        crate::status!("Previewing migration from {:?} source...", config.source);
 
         let imported_data = match config.source {
             MigrationSource::Json => self.parse_json_file(config)?,
@@ -7,20 +7,35 @@
 //! - Email composition and sending
 //! - Plugin system for third-party extensions
 
+pub mod ab_test;
 pub mod api;
+pub mod auto;
 pub mod composer;
 pub mod config;
 pub mod database;
 pub mod fetcher;
+pub mod growth;
+pub mod hygiene;
 pub mod migration;
+pub mod oauth;
+pub mod openapi;
+pub mod plaintext;
 pub mod plugin;
+pub mod segment;
 pub mod sender;
+pub mod tracking;
 pub mod ui;
+pub mod wasm;
+pub mod webhook;
 
+pub use ab_test::{AbTestOptions, Variant};
 pub use api::{ApiConfig, NewsletterApiServer};
-pub use composer::Newsletter;
+pub use composer::{Newsletter, NewsletterComposer};
 pub use config::NewsletterManager;
-pub use database::{NewsletterDatabase, Subscriber, SubscriberStatus};
+pub use database::{NewsletterDatabase, QueueStatus, SourceEmail, Subscriber, SubscriberStatus};
 pub use migration::{MigrationConfig, MigrationManager, MigrationSource};
-pub use plugin::{create_plugin_context, PluginConfig, PluginHook, PluginManager};
-pub use ui::{ApprovalResult, ModernApprovalApp};
+pub use plugin::{
+    check_plugin_config, create_plugin_context, PluginConfig, PluginHook, PluginManager,
+};
+pub use segment::Segment;
+pub use ui::{ApprovalResult, ComposeApp, ComposeResult, ModernApprovalApp};
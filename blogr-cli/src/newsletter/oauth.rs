@@ -0,0 +1,633 @@
+//! OAuth2 sign-in for IMAP and SMTP
+//!
+//! Providers that no longer accept app passwords are signed in to once with
+//! `blogr newsletter login`. Most use the device flow: blogr shows a code and
+//! the user enters it in a browser. Google doesn't allow the mail scope with
+//! the device flow, so it is signed in to through the browser instead, which
+//! redirects back to a local port (with PKCE). The tokens are kept in the OS
+//! keychain, refreshed when they expire and sent with the XOAUTH2 mechanism.
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::future::Future;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use crate::config::OAuthProviderConfig;
+
+/// Keychain service the tokens are stored under
+const KEYRING_SERVICE: &str = "blogr";
+
+/// Tokens this close to expiring are refreshed before use
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// How long a browser sign-in waits for the redirect
+const BROWSER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// How an account is signed in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignInFlow {
+    /// The user enters a code shown by blogr at the provider's device page
+    Device { device_url: String },
+    /// The user consents in the browser, which redirects to a local port
+    Browser { auth_url: String },
+}
+
+/// A provider with its endpoints resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthProvider {
+    pub name: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub flow: SignInFlow,
+    pub token_url: String,
+    pub scopes: Vec<String>,
+}
+
+impl OAuthProvider {
+    /// Fill in the built-in endpoints and scopes of `google` and `microsoft`
+    pub fn from_config(name: &str, config: &OAuthProviderConfig) -> Result<Self> {
+        let (flow, token_url, scopes): (Option<SignInFlow>, &str, &[&str]) = match name {
+            "google" => (
+                Some(SignInFlow::Browser {
+                    auth_url: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+                }),
+                "https://oauth2.googleapis.com/token",
+                &["https://mail.google.com/"],
+            ),
+            "microsoft" => (
+                Some(SignInFlow::Device {
+                    device_url: "https://login.microsoftonline.com/common/oauth2/v2.0/devicecode"
+                        .to_string(),
+                }),
+                "https://login.microsoftonline.com/common/oauth2/v2.0/token",
+                &[
+                    "https://outlook.office.com/IMAP.AccessAsUser.All",
+                    "https://outlook.office.com/SMTP.Send",
+                    "offline_access",
+                ],
+            ),
+            _ => (None, "", &[]),
+        };
+
+        // A configured endpoint picks the flow
+        let flow = match (&config.auth_url, &config.device_url) {
+            (Some(auth_url), _) => Some(SignInFlow::Browser {
+                auth_url: auth_url.clone(),
+            }),
+            (None, Some(device_url)) => Some(SignInFlow::Device {
+                device_url: device_url.clone(),
+            }),
+            (None, None) => flow,
+        };
+        let token_url = config.token_url.as_deref().unwrap_or(token_url);
+        let flow = match flow {
+            Some(flow) if !token_url.is_empty() => flow,
+            _ => {
+                return Err(anyhow!(
+                    "OAuth provider '{}' needs token_url and either device_url or auth_url",
+                    name
+                ))
+            }
+        };
+        let scopes = if config.scopes.is_empty() {
+            scopes.iter().map(|scope| scope.to_string()).collect()
+        } else {
+            config.scopes.clone()
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            client_id: config.client_id.clone(),
+            client_secret: env::var(client_secret_var(name)).ok(),
+            flow,
+            token_url: token_url.to_string(),
+            scopes,
+        })
+    }
+
+    /// Form fields identifying the app, plus `fields`
+    fn form<'a>(&'a self, fields: &[(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+        let mut form = vec![("client_id", self.client_id.as_str())];
+        if let Some(secret) = &self.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+        form.extend_from_slice(fields);
+        form
+    }
+}
+
+/// Environment variable holding a provider's client secret
+pub fn client_secret_var(provider: &str) -> String {
+    format!(
+        "NEWSLETTER_OAUTH_{}_CLIENT_SECRET",
+        provider.to_uppercase().replace('-', "_")
+    )
+}
+
+/// Tokens kept in the keychain for one account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl OAuthToken {
+    fn from_response(
+        response: TokenResponse,
+        previous: Option<&OAuthToken>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            access_token: response.access_token,
+            // Refresh responses usually leave the refresh token out
+            refresh_token: response
+                .refresh_token
+                .or_else(|| previous.and_then(|token| token.refresh_token.clone())),
+            expires_at: now + Duration::seconds(response.expires_in.unwrap_or(3600)),
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - Duration::seconds(EXPIRY_MARGIN_SECS) <= now
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCode {
+    device_code: String,
+    user_code: String,
+    /// Google calls this `verification_url`
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    expires_in: i64,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// The SASL XOAUTH2 initial response, before base64
+pub fn xoauth2(username: &str, access_token: &str) -> String {
+    format!("user={}\x01auth=Bearer {}\x01\x01", username, access_token)
+}
+
+fn keyring_entry(provider: &str, username: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(
+        KEYRING_SERVICE,
+        &format!("oauth2:{}:{}", provider, username),
+    )
+    .context("Failed to open the OS keychain")
+}
+
+/// The stored tokens of an account, if it has signed in
+pub fn stored_token(provider: &str, username: &str) -> Result<Option<OAuthToken>> {
+    match keyring_entry(provider, username)?.get_password() {
+        Ok(json) => Ok(Some(
+            serde_json::from_str(&json).context("Stored OAuth token is corrupt")?,
+        )),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(anyhow!("Failed to read the OS keychain: {}", e)),
+    }
+}
+
+fn store_token(provider: &str, username: &str, token: &OAuthToken) -> Result<()> {
+    keyring_entry(provider, username)?
+        .set_password(&serde_json::to_string(token)?)
+        .map_err(|e| anyhow!("Failed to save the OAuth token to the OS keychain: {}", e))
+}
+
+/// Forget an account's tokens, returning whether there were any
+pub fn delete_token(provider: &str, username: &str) -> Result<bool> {
+    match keyring_entry(provider, username)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(anyhow!("Failed to update the OS keychain: {}", e)),
+    }
+}
+
+fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(concat!("blogr/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// Sign an account in and store its tokens
+pub async fn login(provider: &OAuthProvider, username: &str) -> Result<OAuthToken> {
+    let token = match &provider.flow {
+        SignInFlow::Device { device_url } => device_login(provider, device_url, username).await?,
+        SignInFlow::Browser { auth_url } => browser_login(provider, auth_url, username).await?,
+    };
+    store_token(&provider.name, username, &token)?;
+    Ok(token)
+}
+
+async fn device_login(
+    provider: &OAuthProvider,
+    device_url: &str,
+    username: &str,
+) -> Result<OAuthToken> {
+    let client = http_client()?;
+    let scope = provider.scopes.join(" ");
+    let response = client
+        .post(device_url)
+        .form(&provider.form(&[("scope", scope.as_str())]))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", device_url))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!(
+            "{} refused the sign-in request ({}): {}",
+            provider.name,
+            status,
+            body.trim()
+        ));
+    }
+    let device: DeviceCode = response
+        .json()
+        .await
+        .context("Unexpected device authorization response")?;
+
+    println!("To sign in {} with {}:", username, provider.name);
+    println!("  1. Open {}", device.verification_uri);
+    println!("  2. Enter the code {}", device.user_code);
+    println!("Waiting for you to finish in the browser...");
+
+    let deadline = Utc::now() + Duration::seconds(device.expires_in);
+    let mut interval = device.interval;
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+        if Utc::now() > deadline {
+            return Err(anyhow!("The sign-in code expired. Run the command again."));
+        }
+
+        let response = client
+            .post(&provider.token_url)
+            .form(&provider.form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("device_code", device.device_code.as_str()),
+            ]))
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", provider.token_url))?;
+
+        if response.status().is_success() {
+            return Ok(OAuthToken::from_response(
+                response.json().await.context("Unexpected token response")?,
+                None,
+                Utc::now(),
+            ));
+        }
+
+        let error: TokenError = response
+            .json()
+            .await
+            .context("Unexpected token error response")?;
+        match error.error.as_str() {
+            "authorization_pending" => {}
+            "slow_down" => interval += 5,
+            _ => {
+                return Err(anyhow!(
+                    "Sign-in failed: {}",
+                    error.error_description.unwrap_or(error.error)
+                ))
+            }
+        }
+    }
+}
+
+/// Sign an account in through the browser: the provider redirects back to a
+/// local port with a code, which is exchanged along with the PKCE verifier
+async fn browser_login(
+    provider: &OAuthProvider,
+    auth_url: &str,
+    username: &str,
+) -> Result<OAuthToken> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .context("Failed to listen for the sign-in redirect")?;
+    let redirect_uri = format!("http://127.0.0.1:{}", listener.local_addr()?.port());
+    let verifier = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let state = Uuid::new_v4().simple().to_string();
+    let scope = provider.scopes.join(" ");
+    let url = url::Url::parse_with_params(
+        auth_url,
+        &[
+            ("client_id", provider.client_id.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", scope.as_str()),
+            ("code_challenge", pkce_challenge(&verifier).as_str()),
+            ("code_challenge_method", "S256"),
+            ("state", state.as_str()),
+            // Google only returns a refresh token for offline access
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+        ],
+    )
+    .with_context(|| format!("Invalid auth_url {}", auth_url))?;
+
+    println!(
+        "To sign in {} with {}, open this URL:",
+        username, provider.name
+    );
+    println!("  {}", url);
+    let _ = open::that(url.as_str());
+    println!("Waiting for you to finish in the browser...");
+
+    let code = tokio::time::timeout(BROWSER_TIMEOUT, receive_code(&listener, &state))
+        .await
+        .map_err(|_| anyhow!("The sign-in timed out. Run the command again."))??;
+
+    let response = http_client()?
+        .post(&provider.token_url)
+        .form(&provider.form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("code_verifier", verifier.as_str()),
+        ]))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", provider.token_url))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!(
+            "{} refused the sign-in ({}): {}",
+            provider.name,
+            status,
+            body.trim()
+        ));
+    }
+
+    Ok(OAuthToken::from_response(
+        response.json().await.context("Unexpected token response")?,
+        None,
+        Utc::now(),
+    ))
+}
+
+/// The S256 PKCE challenge for a verifier
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Answer requests on the listener until the browser comes back with a code
+async fn receive_code(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buffer = vec![0; 8192];
+        let read = stream.read(&mut buffer).await?;
+        let request = String::from_utf8_lossy(&buffer[..read]);
+
+        // Other requests, such as the browser asking for /favicon.ico
+        let Some(result) = redirect_code(&request, state) else {
+            let _ = stream
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            continue;
+        };
+
+        let message = match &result {
+            Ok(_) => "Signed in. You can close this tab and go back to blogr.",
+            Err(_) => "Sign-in failed. See blogr for details.",
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            message.len(),
+            message
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return result;
+    }
+}
+
+/// The code in a redirect request; `None` if the request isn't the redirect
+fn redirect_code(request: &str, state: &str) -> Option<Result<String>> {
+    let target = request
+        .lines()
+        .next()?
+        .strip_prefix("GET ")?
+        .split(' ')
+        .next()?;
+    let url = url::Url::parse(&format!("http://127.0.0.1{}", target)).ok()?;
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+    if let Some(error) = params.get("error") {
+        return Some(Err(anyhow!(
+            "Sign-in failed: {}",
+            params.get("error_description").unwrap_or(error)
+        )));
+    }
+    let code = params.get("code")?;
+    if params.get("state").map(String::as_str) != Some(state) {
+        return Some(Err(anyhow!(
+            "The sign-in redirect doesn't belong to this sign-in. Run the command again."
+        )));
+    }
+    Some(Ok(code.clone()))
+}
+
+/// Exchange a refresh token for a new access token and store it
+pub async fn refresh(
+    provider: &OAuthProvider,
+    username: &str,
+    token: &OAuthToken,
+) -> Result<OAuthToken> {
+    let refresh_token = token.refresh_token.as_deref().ok_or_else(|| {
+        anyhow!(
+            "The {} sign-in for {} has expired. Run 'blogr newsletter login' again.",
+            provider.name,
+            username
+        )
+    })?;
+
+    let response = http_client()?
+        .post(&provider.token_url)
+        .form(&provider.form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ]))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", provider.token_url))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!(
+            "{} refused to refresh the sign-in for {} ({}): {}. Run 'blogr newsletter login' again.",
+            provider.name,
+            username,
+            status,
+            body.trim()
+        ));
+    }
+
+    let refreshed = OAuthToken::from_response(
+        response.json().await.context("Unexpected token response")?,
+        Some(token),
+        Utc::now(),
+    );
+    store_token(&provider.name, username, &refreshed)?;
+    Ok(refreshed)
+}
+
+/// A current access token for an account, refreshing it if needed. Usable
+/// from synchronous code, inside the runtime or not.
+pub fn access_token(provider: &OAuthProvider, username: &str) -> Result<String> {
+    let token = stored_token(&provider.name, username)?.ok_or_else(|| {
+        anyhow!(
+            "{} is not signed in with {}. Run 'blogr newsletter login' first.",
+            username,
+            provider.name
+        )
+    })?;
+    if !token.is_expired(Utc::now()) {
+        return Ok(token.access_token);
+    }
+
+    let provider = provider.clone();
+    let username = username.to_string();
+    let refreshed = block_on(async move { refresh(&provider, &username, &token).await })??;
+    Ok(refreshed.access_token)
+}
+
+/// Run a future on its own thread and runtime, so blocking callers work
+/// whether or not they are already inside tokio
+fn block_on<F>(future: F) -> Result<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map(|runtime| runtime.block_on(future))
+    })
+    .join()
+    .map_err(|_| anyhow!("OAuth token refresh panicked"))?
+    .context("Failed to start a runtime for the OAuth token refresh")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oauth_provider_and_tokens() {
+        let config = OAuthProviderConfig {
+            client_id: "abc.apps.example".to_string(),
+            device_url: None,
+            auth_url: None,
+            token_url: None,
+            scopes: Vec::new(),
+        };
+        let google = OAuthProvider::from_config("google", &config).unwrap();
+        assert_eq!(google.token_url, "https://oauth2.googleapis.com/token");
+        assert_eq!(google.scopes, ["https://mail.google.com/"]);
+        // Google refuses the mail scope with the device flow
+        assert!(matches!(google.flow, SignInFlow::Browser { .. }));
+        let microsoft = OAuthProvider::from_config("microsoft", &config).unwrap();
+        assert!(matches!(microsoft.flow, SignInFlow::Device { .. }));
+        assert!(OAuthProvider::from_config("fastmail", &config).is_err());
+        let fastmail = OAuthProvider::from_config(
+            "fastmail",
+            &OAuthProviderConfig {
+                device_url: Some("https://example.com/device".to_string()),
+                token_url: Some("https://example.com/token".to_string()),
+                ..config.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            fastmail.flow,
+            SignInFlow::Device {
+                device_url: "https://example.com/device".to_string()
+            }
+        );
+        assert_eq!(
+            client_secret_var("my-mail"),
+            "NEWSLETTER_OAUTH_MY_MAIL_CLIENT_SECRET"
+        );
+
+        assert_eq!(
+            xoauth2("me@example.com", "ya29.token"),
+            "user=me@example.com\x01auth=Bearer ya29.token\x01\x01"
+        );
+
+        let now = Utc::now();
+        let first = OAuthToken::from_response(
+            TokenResponse {
+                access_token: "one".to_string(),
+                refresh_token: Some("refresh".to_string()),
+                expires_in: Some(3600),
+            },
+            None,
+            now,
+        );
+        assert!(!first.is_expired(now));
+        assert!(first.is_expired(now + Duration::seconds(3550)));
+
+        let second = OAuthToken::from_response(
+            TokenResponse {
+                access_token: "two".to_string(),
+                refresh_token: None,
+                expires_in: None,
+            },
+            Some(&first),
+            now,
+        );
+        assert_eq!(second.refresh_token.as_deref(), Some("refresh"));
+    }
+
+    #[test]
+    fn test_browser_sign_in() {
+        // Example from RFC 7636, appendix B
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let request = "GET /?state=xyz&code=4%2F0Ab&scope=mail HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n";
+        assert_eq!(redirect_code(request, "xyz").unwrap().unwrap(), "4/0Ab");
+        assert!(redirect_code(request, "other").unwrap().is_err());
+        assert!(redirect_code("GET /favicon.ico HTTP/1.1\r\n", "xyz").is_none());
+        let denied = redirect_code("GET /?error=access_denied&state=xyz HTTP/1.1\r\n", "xyz");
+        assert!(denied
+            .unwrap()
+            .unwrap_err()
+            .to_string()
+            .contains("access_denied"));
+    }
+}
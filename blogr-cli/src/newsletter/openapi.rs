@@ -0,0 +1,400 @@
+//! OpenAPI description of the newsletter API server
+//!
+//! Each route in `api.rs` carries an [`Operation`]; the document served at
+//! `/openapi.json` is assembled from those, so it can't drift from the router.
+
+use serde_json::{json, Map, Value};
+
+/// Documentation for one route
+pub struct Operation {
+    pub summary: &'static str,
+    pub tag: &'static str,
+    pub query: &'static [QueryParam],
+    pub body: Option<Body>,
+    pub reply: Reply,
+    /// Reachable without the API key
+    pub public: bool,
+}
+
+/// A query string parameter
+pub struct QueryParam {
+    pub name: &'static str,
+    /// JSON schema type, e.g. "string" or "integer"
+    pub kind: &'static str,
+    pub description: &'static str,
+}
+
+/// Request body, naming a schema from [`schemas`]
+pub enum Body {
+    Json(&'static str),
+    Form(&'static str),
+}
+
+/// Successful response
+pub enum Reply {
+    /// The usual `ApiResponse` envelope with `data` of the named schema
+    Json(&'static str),
+    /// The envelope with `data: null`
+    Empty,
+    /// A 1x1 GIF
+    Image,
+    /// A redirect to another URL
+    Redirect,
+}
+
+/// Query parameters shared by the subscriber list and export
+pub const SUBSCRIBER_FILTERS: &[QueryParam] = &[
+    QueryParam {
+        name: "status",
+        kind: "string",
+        description: "pending, approved or declined",
+    },
+    QueryParam {
+        name: "search",
+        kind: "string",
+        description: "Case-insensitive match on email address or notes",
+    },
+    QueryParam {
+        name: "tag",
+        kind: "string",
+        description: "Only subscribers with this tag",
+    },
+    QueryParam {
+        name: "limit",
+        kind: "integer",
+        description: "Page size (list: 50 by default, at most 500)",
+    },
+    QueryParam {
+        name: "offset",
+        kind: "integer",
+        description: "Number of subscribers to skip",
+    },
+];
+
+/// Build the OpenAPI 3.0 document for a set of routes
+pub fn document<'a>(routes: impl IntoIterator<Item = (&'a str, &'a str, &'a Operation)>) -> Value {
+    let mut paths = Map::new();
+
+    for (method, path, operation) in routes {
+        let entry = paths.entry(path.to_string()).or_insert_with(|| json!({}));
+        entry[method.to_lowercase()] = operation_object(path, operation);
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Blogr Newsletter API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Manage newsletter subscribers and sends for a blogr site.",
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "apiKey": { "type": "http", "scheme": "bearer" },
+            },
+        },
+        "security": [{ "apiKey": [] }],
+    })
+}
+
+fn operation_object(path: &str, operation: &Operation) -> Value {
+    let mut parameters: Vec<Value> = path_params(path)
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": if name == "link" { "integer" } else { "string" } },
+            })
+        })
+        .collect();
+    parameters.extend(operation.query.iter().map(|param| {
+        json!({
+            "name": param.name,
+            "in": "query",
+            "required": false,
+            "description": param.description,
+            "schema": { "type": param.kind },
+        })
+    }));
+
+    let mut object = json!({
+        "summary": operation.summary,
+        "tags": [operation.tag],
+        "responses": {
+            "200": reply_object(&operation.reply),
+            "default": { "description": "Error status, usually without a body" },
+        },
+    });
+    if operation.public {
+        object["security"] = json!([]);
+    }
+    if !parameters.is_empty() {
+        object["parameters"] = Value::Array(parameters);
+    }
+    if let Some(body) = &operation.body {
+        let (content_type, schema) = match body {
+            Body::Json(schema) => ("application/json", schema),
+            Body::Form(schema) => ("application/x-www-form-urlencoded", schema),
+        };
+        object["requestBody"] = json!({
+            "required": true,
+            "content": { content_type: { "schema": schema_ref(schema) } },
+        });
+    }
+
+    object
+}
+
+fn reply_object(reply: &Reply) -> Value {
+    match reply {
+        Reply::Json(schema) => json!({
+            "description": "Success",
+            "content": { "application/json": { "schema": envelope(schema_ref(schema)) } },
+        }),
+        Reply::Empty => json!({
+            "description": "Success",
+            "content": { "application/json": { "schema": envelope(json!({ "nullable": true })) } },
+        }),
+        Reply::Image => json!({
+            "description": "Tracking pixel",
+            "content": { "image/gif": { "schema": { "type": "string", "format": "binary" } } },
+        }),
+        Reply::Redirect => json!({ "description": "Redirect to the original link" }),
+    }
+}
+
+/// Names of the `{param}` segments in an axum route
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+/// Schema of the `ApiResponse` wrapper around every JSON reply
+fn envelope(data: Value) -> Value {
+    json!({
+        "type": "object",
+        "required": ["success", "timestamp"],
+        "properties": {
+            "success": { "type": "boolean" },
+            "data": data,
+            "error": { "type": "string", "nullable": true },
+            "timestamp": { "type": "string", "format": "date-time" },
+        },
+    })
+}
+
+/// Component schemas, matching the serde shapes of the types in `api.rs` and `database.rs`
+pub fn schemas() -> Value {
+    let nullable_string = json!({ "type": "string", "nullable": true });
+
+    json!({
+        "SubscriberStatus": {
+            "type": "string",
+            "enum": ["Pending", "Approved", "Declined"],
+        },
+        "Subscriber": {
+            "type": "object",
+            "required": ["email", "status", "subscribed_at", "tags"],
+            "properties": {
+                "id": { "type": "integer", "nullable": true },
+                "email": { "type": "string", "format": "email" },
+                "status": schema_ref("SubscriberStatus"),
+                "subscribed_at": { "type": "string", "format": "date-time" },
+                "approved_at": { "type": "string", "format": "date-time", "nullable": true },
+                "source_email_id": nullable_string,
+                "notes": nullable_string,
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "name": nullable_string,
+            },
+        },
+        "SubscriberList": {
+            "type": "array",
+            "items": schema_ref("Subscriber"),
+        },
+        "SubscriberPage": {
+            "type": "object",
+            "required": ["subscribers", "total", "limit", "offset"],
+            "properties": {
+                "subscribers": { "type": "array", "items": schema_ref("Subscriber") },
+                "total": { "type": "integer", "description": "Matches across all pages" },
+                "limit": { "type": "integer" },
+                "offset": { "type": "integer" },
+                "next_offset": {
+                    "type": "integer",
+                    "nullable": true,
+                    "description": "Offset of the next page; null on the last page",
+                },
+            },
+        },
+        "CreateSubscriberRequest": {
+            "type": "object",
+            "required": ["email"],
+            "properties": {
+                "email": { "type": "string", "format": "email" },
+                "status": schema_ref("SubscriberStatus"),
+                "notes": { "type": "string" },
+                "name": { "type": "string" },
+            },
+        },
+        "UpdateSubscriberRequest": {
+            "type": "object",
+            "properties": {
+                "status": schema_ref("SubscriberStatus"),
+                "notes": { "type": "string", "description": "Empty to clear" },
+                "name": { "type": "string", "description": "Empty to clear" },
+            },
+        },
+        "BulkRequest": {
+            "type": "object",
+            "required": ["action", "emails"],
+            "properties": {
+                "action": { "type": "string", "enum": ["approve", "decline", "delete"] },
+                "emails": { "type": "array", "items": { "type": "string" }, "maxItems": 500 },
+            },
+        },
+        "BulkResponse": {
+            "type": "object",
+            "required": ["processed", "not_found"],
+            "properties": {
+                "processed": { "type": "integer" },
+                "not_found": { "type": "array", "items": { "type": "string" } },
+            },
+        },
+        "SubscribeRequest": {
+            "type": "object",
+            "required": ["email"],
+            "properties": {
+                "email": { "type": "string", "format": "email" },
+                "name": { "type": "string" },
+                "website": { "type": "string", "description": "Honeypot; must be left empty" },
+            },
+        },
+        "ContactRequest": {
+            "type": "object",
+            "required": ["name", "email", "message"],
+            "properties": {
+                "name": { "type": "string" },
+                "email": { "type": "string", "format": "email" },
+                "message": { "type": "string" },
+                "subject": { "type": "string" },
+                "website": { "type": "string", "description": "Honeypot; must be left empty" },
+                "redirect": { "type": "string", "description": "Page to redirect to after a plain form post" },
+            },
+        },
+        "SendNewsletterRequest": {
+            "type": "object",
+            "required": ["subject", "content"],
+            "properties": {
+                "subject": { "type": "string" },
+                "content": { "type": "string", "description": "Markdown body" },
+                "test_mode": { "type": "boolean" },
+                "test_email": { "type": "string", "format": "email" },
+            },
+        },
+        "ImportRequest": {
+            "type": "object",
+            "required": ["source", "data"],
+            "properties": {
+                "source": { "type": "string" },
+                "data": { "description": "CSV data as a string, or a JSON array" },
+                "preview_only": { "type": "boolean" },
+                "column_mappings": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                },
+            },
+        },
+        "StatsResponse": {
+            "type": "object",
+            "required": [
+                "total_subscribers",
+                "approved_subscribers",
+                "pending_subscribers",
+                "declined_subscribers",
+            ],
+            "properties": {
+                "total_subscribers": { "type": "integer" },
+                "approved_subscribers": { "type": "integer" },
+                "pending_subscribers": { "type": "integer" },
+                "declined_subscribers": { "type": "integer" },
+            },
+        },
+        "StringMap": {
+            "type": "object",
+            "additionalProperties": { "type": "string" },
+        },
+        "Text": { "type": "string" },
+    })
+}
+
+/// Swagger UI page for `/docs`, loading the viewer from a CDN
+pub const SWAGGER_UI: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Blogr Newsletter API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: 'openapi.json', dom_id: '#swagger-ui' });
+  </script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATION: Operation = Operation {
+        summary: "Get subscriber",
+        tag: "Subscribers",
+        query: &[],
+        body: Some(Body::Json("UpdateSubscriberRequest")),
+        reply: Reply::Json("Subscriber"),
+        public: false,
+    };
+
+    const HEALTH: Operation = Operation {
+        summary: "Health check",
+        tag: "Server",
+        query: &[],
+        body: None,
+        reply: Reply::Json("StringMap"),
+        public: true,
+    };
+
+    #[test]
+    fn test_path_params() {
+        let params: Vec<_> = path_params("/t/c/{token}/{link}").collect();
+        assert_eq!(params, vec!["token", "link"]);
+        assert_eq!(path_params("/health").count(), 0);
+    }
+
+    #[test]
+    fn test_document() {
+        let document = document([
+            ("PUT", "/subscribers/{email}", &OPERATION),
+            ("GET", "/health", &HEALTH),
+        ]);
+
+        let operation = &document["paths"]["/subscribers/{email}"]["put"];
+        assert_eq!(operation["parameters"][0]["name"], "email");
+        assert_eq!(
+            operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/UpdateSubscriberRequest"
+        );
+        assert_eq!(document["security"][0]["apiKey"], json!([]));
+        assert!(operation.get("security").is_none());
+        assert_eq!(document["paths"]["/health"]["get"]["security"], json!([]));
+    }
+}
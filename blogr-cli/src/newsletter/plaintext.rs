@@ -0,0 +1,356 @@
+//! Plain text versions of newsletters
+//!
+//! The `text/plain` part of each email is written from the Markdown source
+//! rather than stripped from the HTML: headings are underlined, lists keep
+//! their markers, code is indented, paragraphs are wrapped, and links are
+//! numbered with their URLs listed after the text.
+
+use html2text::from_read;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+
+/// Line width of the plain text part
+pub const WIDTH: usize = 72;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Gap {
+    None,
+    Line,
+    Blank,
+}
+
+struct Writer {
+    out: String,
+    /// Text of the block being read
+    inline: String,
+    /// Prefixes of the open block quotes and list items
+    prefixes: Vec<String>,
+    /// Marker of a list item whose first line hasn't been written
+    marker: Option<String>,
+    /// Next number of each open list; `None` for bullet lists
+    lists: Vec<Option<u64>>,
+    /// URLs of the open links and images
+    open_links: Vec<String>,
+    links: Vec<String>,
+    gap: Gap,
+    code_block: Option<String>,
+    html_block: String,
+    table_row: Vec<String>,
+}
+
+/// Format Markdown as plain text wrapped at `width`
+pub fn markdown_to_plain(markdown: &str, width: usize) -> String {
+    let mut writer = Writer {
+        out: String::new(),
+        inline: String::new(),
+        prefixes: Vec::new(),
+        marker: None,
+        lists: Vec::new(),
+        open_links: Vec::new(),
+        links: Vec::new(),
+        gap: Gap::None,
+        code_block: None,
+        html_block: String::new(),
+        table_row: Vec::new(),
+    };
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    for event in Parser::new_ext(markdown, options) {
+        writer.event(event, width);
+    }
+    writer.flush_html(width);
+    writer.flush(width);
+
+    let mut out = writer.out.trim_end().to_string();
+    if !writer.links.is_empty() {
+        out.push_str("\n\n");
+        for (index, url) in writer.links.iter().enumerate() {
+            out.push_str(&format!("[{}] {}\n", index + 1, url));
+        }
+        out.truncate(out.trim_end().len());
+    }
+    out
+}
+
+impl Writer {
+    fn event(&mut self, event: Event, width: usize) {
+        if !matches!(event, Event::Html(_)) {
+            self.flush_html(width);
+        }
+        match event {
+            Event::Start(tag) => self.start(tag, width),
+            Event::End(tag) => self.end(tag, width),
+            Event::Text(text) => match &mut self.code_block {
+                Some(code) => code.push_str(&text),
+                None => self.inline.push_str(&text),
+            },
+            Event::Code(code) => {
+                self.inline.push('`');
+                self.inline.push_str(&code);
+                self.inline.push('`');
+            }
+            Event::Html(html) => {
+                if self.inline.is_empty() {
+                    self.html_block.push_str(&html);
+                }
+            }
+            Event::SoftBreak => self.inline.push(' '),
+            Event::HardBreak => self.inline.push('\n'),
+            Event::Rule => {
+                self.flush(width);
+                self.gap = Gap::Blank;
+                self.line("-".repeat(width.min(20)));
+            }
+            Event::TaskListMarker(done) => self.inline.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::FootnoteReference(name) => self.inline.push_str(&format!("[^{}]", name)),
+        }
+    }
+
+    fn start(&mut self, tag: Tag, width: usize) {
+        match tag {
+            Tag::Paragraph | Tag::Heading(..) | Tag::Table(_) => {
+                self.flush(width);
+                self.gap = Gap::Blank;
+            }
+            Tag::CodeBlock(_) => {
+                self.flush(width);
+                self.gap = Gap::Blank;
+                self.code_block = Some(String::new());
+            }
+            Tag::BlockQuote => {
+                self.flush(width);
+                self.gap = Gap::Blank;
+                self.prefixes.push("> ".to_string());
+            }
+            Tag::List(start) => {
+                self.flush(width);
+                self.gap = if self.lists.is_empty() {
+                    Gap::Blank
+                } else {
+                    Gap::Line
+                };
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush(width);
+                if self.gap == Gap::None {
+                    self.gap = Gap::Line;
+                }
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.prefixes.push(" ".repeat(marker.len()));
+                self.marker = Some(marker);
+            }
+            Tag::Emphasis => self.inline.push('_'),
+            Tag::Strong => self.inline.push('*'),
+            Tag::Strikethrough => self.inline.push('~'),
+            Tag::Link(_, url, _) => self.open_links.push(url.to_string()),
+            Tag::Image(_, url, _) => {
+                self.inline.push_str("[image: ");
+                self.open_links.push(url.to_string());
+            }
+            Tag::TableHead | Tag::TableRow => self.table_row.clear(),
+            Tag::TableCell => {}
+            Tag::FootnoteDefinition(name) => {
+                self.flush(width);
+                self.gap = Gap::Blank;
+                self.inline.push_str(&format!("[^{}]: ", name));
+            }
+        }
+    }
+
+    fn end(&mut self, tag: Tag, width: usize) {
+        match tag {
+            Tag::Paragraph | Tag::FootnoteDefinition(_) => self.flush(width),
+            Tag::Heading(level, ..) => {
+                let text = std::mem::take(&mut self.inline).trim().to_string();
+                let underline = match level {
+                    HeadingLevel::H1 => Some('='),
+                    HeadingLevel::H2 => Some('-'),
+                    _ => None,
+                };
+                match underline {
+                    Some(c) => {
+                        let length = text.chars().count();
+                        self.line(text);
+                        self.line(c.to_string().repeat(length));
+                    }
+                    None => self.line(format!("{} {}", "#".repeat(level as usize), text)),
+                }
+            }
+            Tag::CodeBlock(_) => {
+                let code = self.code_block.take().unwrap_or_default();
+                for line in code.trim_end_matches('\n').lines() {
+                    self.line(format!("    {}", line));
+                }
+            }
+            Tag::BlockQuote => {
+                self.flush(width);
+                self.prefixes.pop();
+                self.gap = Gap::Blank;
+            }
+            Tag::List(_) => {
+                self.flush(width);
+                self.lists.pop();
+                self.gap = Gap::Blank;
+            }
+            Tag::Item => {
+                self.flush(width);
+                self.prefixes.pop();
+                self.marker = None;
+                self.gap = Gap::Line;
+            }
+            Tag::Emphasis => self.inline.push('_'),
+            Tag::Strong => self.inline.push('*'),
+            Tag::Strikethrough => self.inline.push('~'),
+            Tag::Link(..) => {
+                let url = self.open_links.pop().unwrap_or_default();
+                let text = self.inline.trim_end();
+                let shown = text.ends_with(&url)
+                    || url
+                        .strip_prefix("mailto:")
+                        .is_some_and(|address| text.ends_with(address));
+                if !shown && !url.is_empty() && !url.starts_with('#') {
+                    self.links.push(url);
+                    self.inline.push_str(&format!(" [{}]", self.links.len()));
+                }
+            }
+            Tag::Image(..) => {
+                let url = self.open_links.pop().unwrap_or_default();
+                self.links.push(url);
+                self.inline.push_str(&format!("] [{}]", self.links.len()));
+            }
+            Tag::TableCell => {
+                let cell = std::mem::take(&mut self.inline).trim().to_string();
+                self.table_row.push(cell);
+            }
+            Tag::TableHead | Tag::TableRow => {
+                let row = std::mem::take(&mut self.table_row).join(" | ");
+                self.line(row);
+                self.gap = Gap::Line;
+            }
+            Tag::Table(_) => self.gap = Gap::Blank,
+        }
+    }
+
+    /// Write the block being read, wrapped to fit after the prefixes
+    fn flush(&mut self, width: usize) {
+        let text = std::mem::take(&mut self.inline);
+        if text.trim().is_empty() {
+            return;
+        }
+        let indent: usize = self.prefixes.iter().map(|p| p.chars().count()).sum();
+        let available = width.saturating_sub(indent).max(20);
+        for line in text.split('\n') {
+            for wrapped in wrap(line.trim(), available) {
+                self.line(wrapped);
+            }
+        }
+    }
+
+    fn flush_html(&mut self, width: usize) {
+        if self.html_block.trim().is_empty() {
+            self.html_block.clear();
+            return;
+        }
+        let html = std::mem::take(&mut self.html_block);
+        let text = from_read(html.as_bytes(), width);
+        self.gap = Gap::Blank;
+        for line in text.trim_end().lines() {
+            self.line(line.to_string());
+        }
+        self.gap = Gap::Blank;
+    }
+
+    /// Write one line with the current prefixes, after the pending gap
+    fn line(&mut self, text: String) {
+        if !self.out.is_empty() {
+            match self.gap {
+                Gap::Blank if !self.out.ends_with("\n\n") => self.out.push('\n'),
+                _ => {}
+            }
+        }
+        self.gap = Gap::None;
+
+        let mut prefix = self.prefixes.concat();
+        if let Some(marker) = self.marker.take() {
+            let item = self.prefixes.last().map_or(0, |p| p.len());
+            prefix.truncate(prefix.len() - item);
+            prefix.push_str(&marker);
+        }
+        let line = format!("{}{}", prefix, text);
+        self.out.push_str(line.trim_end());
+        self.out.push('\n');
+    }
+}
+
+/// Break `text` into lines of at most `width` characters where it can.
+/// Tokens like `{{ subscriber.name }}` are never split.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match words.last_mut() {
+            Some(last) if last.matches("{{").count() > last.matches("}}").count() => {
+                last.push(' ');
+                last.push_str(word);
+            }
+            _ => words.push(word.to_string()),
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in words {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_plain() {
+        let markdown = "# Release notes\n\n\
+            Version 2 is out with *faster* builds and a [new theme](https://example.com/themes). \
+            Read the {{ subscriber.name }} guide at <https://example.com/guide>.\n\n\
+            - One\n- Two\n  1. Nested\n\n\
+            > Quoted text\n\n\
+            ```\nfn main() {}\n```\n";
+        let text = markdown_to_plain(markdown, 40);
+
+        assert_eq!(
+            text,
+            "Release notes\n\
+             =============\n\
+             \n\
+             Version 2 is out with _faster_ builds\n\
+             and a new theme [1]. Read the\n\
+             {{ subscriber.name }} guide at\n\
+             https://example.com/guide.\n\
+             \n\
+             - One\n\
+             - Two\n  \
+               1. Nested\n\
+             \n\
+             > Quoted text\n\
+             \n    \
+             fn main() {}\n\
+             \n\
+             [1] https://example.com/themes"
+        );
+    }
+}
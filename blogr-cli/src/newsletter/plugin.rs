@@ -6,15 +6,21 @@
 //! - Additional sending providers
 //! - Enhanced analytics
 //! - Integration with external services
+//!
+//! Besides plugins compiled into blogr, WebAssembly plugins are loaded from the
+//! project's `plugins/` directory and the workspace's shared one (see
+//! [`super::wasm`] and [`crate::workspace`]).
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use super::wasm::WasmPlugin;
 use super::{Newsletter, NewsletterDatabase, Subscriber};
 use crate::config::Config;
+use crate::workspace::Workspace;
 
 /// Plugin metadata and configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +41,7 @@ pub struct PluginMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfig {
     pub enabled: bool,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub config: HashMap<String, serde_json::Value>,
 }
 
@@ -62,6 +69,48 @@ pub enum PluginHook {
     CustomCommand,
     /// Called for custom email templates
     CustomTemplate,
+    /// Called before a build generates the site
+    PreBuild,
+    /// Called after a build writes the site
+    PostBuild,
+    /// Called after `blogr deploy` pushes the site
+    PostDeploy,
+    /// Called when the API server creates, approves or removes a subscriber
+    SubscriberEvent,
+}
+
+impl PluginHook {
+    /// Name passed to plugins in their input
+    pub fn name(&self) -> &'static str {
+        match self {
+            PluginHook::PreFetch => "pre_fetch",
+            PluginHook::PostFetch => "post_fetch",
+            PluginHook::PreApprove => "pre_approve",
+            PluginHook::PostApprove => "post_approve",
+            PluginHook::PreCompose => "pre_compose",
+            PluginHook::PostCompose => "post_compose",
+            PluginHook::PreSend => "pre_send",
+            PluginHook::PostSend => "post_send",
+            PluginHook::CustomCommand => "custom_command",
+            PluginHook::CustomTemplate => "custom_template",
+            PluginHook::PreBuild => "pre_build",
+            PluginHook::PostBuild => "post_build",
+            PluginHook::PostDeploy => "post_deploy",
+            PluginHook::SubscriberEvent => "subscriber_event",
+        }
+    }
+
+    /// Function a WASM plugin exports to handle this hook, for hooks in the host API
+    pub fn wasm_export(&self) -> Option<&'static str> {
+        match self {
+            PluginHook::PreBuild => Some("blogr_pre_build"),
+            PluginHook::PostBuild => Some("blogr_post_build"),
+            PluginHook::PostDeploy => Some("blogr_post_deploy"),
+            PluginHook::PreSend => Some("blogr_pre_send"),
+            PluginHook::SubscriberEvent => Some("blogr_subscriber_event"),
+            _ => None,
+        }
+    }
 }
 
 /// Context passed to plugin hooks
@@ -69,7 +118,8 @@ pub enum PluginHook {
 #[allow(dead_code)]
 pub struct PluginContext {
     pub config: Arc<Config>,
-    pub database: Arc<NewsletterDatabase>,
+    /// Not available to hooks that run outside the newsletter, such as post-build
+    pub database: Option<Arc<NewsletterDatabase>>,
     pub project_root: PathBuf,
     pub hook: PluginHook,
     pub data: HashMap<String, serde_json::Value>,
@@ -101,6 +151,11 @@ pub trait NewsletterPlugin: Send + Sync {
     /// Execute the plugin hook
     fn execute_hook(&self, context: &PluginContext) -> Result<PluginResult>;
 
+    /// JSON Schema for the plugin's `config` table, checked when it is enabled
+    fn config_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
     /// Get available custom commands provided by this plugin
     fn custom_commands(&self) -> Vec<String> {
         Vec::new()
@@ -151,6 +206,55 @@ impl PluginManager {
         }
     }
 
+    /// Plugin manager with the project's plugin configuration and WASM plugins loaded
+    pub fn for_project(config: &Config, project_root: &Path) -> Result<Self> {
+        let mut manager = Self::new(project_root.to_path_buf());
+        manager.load_plugin_configs(config)?;
+        manager.load_wasm_plugins()?;
+        Ok(manager)
+    }
+
+    /// Load every `.wasm` file in the project's `plugins/` directory, then those
+    /// in the workspace's shared `plugins_dir` not overridden by a project file
+    /// of the same name. A plugin that fails to load is reported and skipped,
+    /// so it can't break builds or sends.
+    pub fn load_wasm_plugins(&mut self) -> Result<()> {
+        let shared = Workspace::find(&self.project_root)?.and_then(|w| w.plugins_dir());
+        let dirs = std::iter::once(self.project_root.join("plugins")).chain(shared);
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for dir in dirs.filter(|dir| dir.is_dir()) {
+            let mut found: Vec<PathBuf> = std::fs::read_dir(&dir)
+                .with_context(|| format!("Failed to read {}", dir.display()))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+                .filter(|path| {
+                    !paths
+                        .iter()
+                        .any(|loaded| loaded.file_name() == path.file_name())
+                })
+                .collect();
+            found.sort();
+            paths.extend(found);
+        }
+
+        for path in paths {
+            match WasmPlugin::load(&path) {
+                Ok(plugin) => self.register_plugin(Box::new(plugin))?,
+                Err(e) => tracing::warn!("Skipping plugin {}: {:#}", path.display(), e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a plugin is enabled in blogr.toml
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.plugin_configs
+            .get(name)
+            .is_some_and(|config| config.enabled)
+    }
+
     /// Load plugin configuration from blogr.toml
     pub fn load_plugin_configs(&mut self, config: &Config) -> Result<()> {
         // For now, plugins are configured in the newsletter section
@@ -170,6 +274,9 @@ impl PluginManager {
 
         // Initialize plugin with its configuration
         if let Some(config) = self.plugin_configs.get(&plugin_name) {
+            if config.enabled {
+                check_plugin_config(plugin.as_ref(), config)?;
+            }
             plugin
                 .initialize(config)
                 .with_context(|| format!("Failed to initialize plugin '{}'", plugin_name))?;
@@ -216,9 +323,11 @@ impl PluginManager {
             match plugin.execute_hook(context) {
                 Ok(result) => results.push(result),
                 Err(e) => {
-                    eprintln!(
-                        "Warning: Plugin '{}' failed to execute hook {:?}: {}",
-                        plugin_name, hook, e
+                    tracing::warn!(
+                        "Plugin '{}' failed to execute hook {:?}: {}",
+                        plugin_name,
+                        hook,
+                        e
                     );
                     results.push(PluginResult {
                         success: false,
@@ -337,8 +446,12 @@ impl PluginManager {
     }
 
     /// List all loaded plugins
-    pub fn list_plugins(&self) -> Vec<&PluginMetadata> {
-        self.plugins.iter().map(|p| p.metadata()).collect()
+    /// Loaded plugins, with whether each is enabled in blogr.toml
+    pub fn list_plugins(&self) -> Vec<(&PluginMetadata, bool)> {
+        self.plugins
+            .iter()
+            .map(|p| (p.metadata(), self.is_enabled(&p.metadata().name)))
+            .collect()
     }
 
     /// Get plugin by name
@@ -350,10 +463,120 @@ impl PluginManager {
     }
 }
 
+/// Check a plugin's configuration against the schema it declares
+pub fn check_plugin_config(plugin: &dyn NewsletterPlugin, config: &PluginConfig) -> Result<()> {
+    let Some(schema) = plugin.config_schema() else {
+        return Ok(());
+    };
+
+    let value = serde_json::Value::Object(config.config.clone().into_iter().collect());
+    let errors = validate_config(&schema, &value);
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    anyhow::bail!(
+        "Invalid configuration for plugin '{}':\n  - {}",
+        plugin.metadata().name,
+        errors.join("\n  - ")
+    )
+}
+
+/// Validate a value against the subset of JSON Schema plugins can use: `type`,
+/// `enum`, `minimum`, `maximum`, `properties`, `required`,
+/// `additionalProperties: false` and `items`. Returns one message per problem.
+pub fn validate_config(schema: &serde_json::Value, value: &serde_json::Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check_value(schema, value, "config", &mut errors);
+    errors
+}
+
+fn check_value(
+    schema: &serde_json::Value,
+    value: &serde_json::Value,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    use serde_json::Value;
+
+    if let Some(kind) = schema.get("type").and_then(Value::as_str) {
+        let matches = match kind {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            errors.push(format!("{} must be {} {}", path, article(kind), kind));
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let options: Vec<String> = options.iter().map(Value::to_string).collect();
+            errors.push(format!("{} must be one of {}", path, options.join(", ")));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                errors.push(format!("{} must be at least {}", path, minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                errors.push(format!("{} must be at most {}", path, maximum));
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let required = schema.get("required").and_then(Value::as_array);
+        for name in required.into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                errors.push(format!("{}.{} is required", path, name));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+        let mut keys: Vec<&String> = object.keys().collect();
+        keys.sort();
+        for key in keys {
+            let field = format!("{}.{}", path, key);
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(property) => check_value(property, &object[key], &field, errors),
+                None if closed => errors.push(format!("{} is not a known setting", field)),
+                None => {}
+            }
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (index, item) in array.iter().enumerate() {
+            check_value(items, item, &format!("{}[{}]", path, index), errors);
+        }
+    }
+}
+
+fn article(kind: &str) -> &'static str {
+    if kind.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    }
+}
+
 /// Helper function to create a plugin context
 pub fn create_plugin_context(
     config: Arc<Config>,
-    database: Arc<NewsletterDatabase>,
+    database: Option<Arc<NewsletterDatabase>>,
     project_root: PathBuf,
     hook: PluginHook,
     data: HashMap<String, serde_json::Value>,
@@ -437,4 +660,39 @@ mod tests {
         let manager = PluginManager::new(PathBuf::from("/tmp"));
         assert_eq!(manager.plugins.len(), 0);
     }
+
+    #[test]
+    fn test_validate_config() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["api_key"],
+            "additionalProperties": false,
+            "properties": {
+                "api_key": { "type": "string" },
+                "mode": { "enum": ["daily", "weekly"] },
+                "retries": { "type": "integer", "minimum": 0, "maximum": 5 },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        });
+
+        let valid = serde_json::json!({ "api_key": "k", "mode": "daily", "retries": 2 });
+        assert!(validate_config(&schema, &valid).is_empty());
+
+        let invalid = serde_json::json!({
+            "mode": "hourly",
+            "retries": 9,
+            "tags": ["a", 1],
+            "colour": "red"
+        });
+        assert_eq!(
+            validate_config(&schema, &invalid),
+            vec![
+                "config.api_key is required",
+                "config.colour is not a known setting",
+                "config.mode must be one of \"daily\", \"weekly\"",
+                "config.retries must be at most 5",
+                "config.tags[1] must be a string",
+            ]
+        );
+    }
 }
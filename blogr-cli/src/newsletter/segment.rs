@@ -0,0 +1,100 @@
+//! Subscriber segments for targeted newsletters
+//!
+//! A segment is written as `tag:<name>`. When several segments are given,
+//! a subscriber must match all of them.
+
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+use super::database::Subscriber;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// Subscribers carrying a tag
+    Tag(String),
+}
+
+impl Segment {
+    /// Check whether a subscriber belongs to this segment
+    pub fn matches(&self, subscriber: &Subscriber) -> bool {
+        match self {
+            Segment::Tag(tag) => subscriber.has_tag(tag),
+        }
+    }
+
+    /// Parse a list of segment specs such as `["tag:rust", "tag:cli"]`
+    pub fn parse_all<S: AsRef<str>>(specs: &[S]) -> Result<Vec<Segment>> {
+        specs.iter().map(|spec| spec.as_ref().parse()).collect()
+    }
+
+    /// Keep only the subscribers that match every segment
+    pub fn filter(segments: &[Segment], subscribers: Vec<Subscriber>) -> Vec<Subscriber> {
+        subscribers
+            .into_iter()
+            .filter(|subscriber| segments.iter().all(|segment| segment.matches(subscriber)))
+            .collect()
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Tag(tag) => write!(f, "tag:{}", tag),
+        }
+    }
+}
+
+impl FromStr for Segment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().split_once(':') {
+            Some(("tag", tag)) if !tag.trim().is_empty() => {
+                Ok(Segment::Tag(tag.trim().to_lowercase()))
+            }
+            _ => Err(anyhow!(
+                "Invalid segment '{}'. Use the form tag:<name>, e.g. tag:rust",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscriber(email: &str, tags: &[&str]) -> Subscriber {
+        let mut subscriber = Subscriber::new(email.to_string(), None);
+        subscriber.tags = tags.iter().map(|t| t.to_string()).collect();
+        subscriber
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        assert_eq!(
+            "tag:Rust".parse::<Segment>().unwrap(),
+            Segment::Tag("rust".to_string())
+        );
+        assert!("rust".parse::<Segment>().is_err());
+        assert!("tag:".parse::<Segment>().is_err());
+        assert!("status:approved".parse::<Segment>().is_err());
+    }
+
+    #[test]
+    fn test_filter_requires_all_segments() {
+        let subscribers = vec![
+            subscriber("a@example.com", &["rust", "cli"]),
+            subscriber("b@example.com", &["rust"]),
+            subscriber("c@example.com", &[]),
+        ];
+
+        let segments = Segment::parse_all(&["tag:rust", "tag:cli"]).unwrap();
+        let matched = Segment::filter(&segments, subscribers.clone());
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].email, "a@example.com");
+
+        assert_eq!(Segment::filter(&[], subscribers).len(), 3);
+    }
+}
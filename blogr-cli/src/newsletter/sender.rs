@@ -2,11 +2,16 @@
 //!
 //! This module handles sending newsletters via SMTP with rate limiting,
 //! bounce handling, and progress tracking.
+//!
+//! Each email is personalized on the way out: `{{ subscriber.name }}`,
+//! `{{ subscriber.email }}`, `{{ unsubscribe_url }}` and
+//! `{{ unsubscribe_token }}` in the subject and body are filled in for the
+//! recipient.
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use lettre::message::{header, Mailbox, MultiPart, SinglePart};
-use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
 use lettre::transport::smtp::client::{Tls, TlsParameters};
 use lettre::{Message, SmtpTransport, Transport};
 use serde::{Deserialize, Serialize};
@@ -14,9 +19,12 @@ use std::thread;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use crate::config::SmtpConfig;
+use crate::config::{SendingConfig, SmtpConfig};
+use crate::generator::markdown;
 use crate::newsletter::composer::Newsletter;
 use crate::newsletter::database::{Subscriber, SubscriberStatus};
+use crate::newsletter::tracking::Tracker;
+use crate::progress::Progress;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendReport {
@@ -104,7 +112,7 @@ impl RateLimiter {
             let oldest_send = self.last_send_times[0];
             let wait_time = Duration::from_secs(60) - (now - oldest_send);
             if wait_time > Duration::from_secs(0) {
-                println!(
+                crate::status!(
                     "⏳ Rate limit reached, waiting {} seconds...",
                     wait_time.as_secs()
                 );
@@ -117,10 +125,105 @@ impl RateLimiter {
     }
 }
 
+/// Receives each delivery outcome as it happens (`None` on success, the error otherwise)
+pub type DeliveryCallback<'a> = Box<dyn FnMut(&Subscriber, Option<&str>) -> Result<()> + 'a>;
+
 pub struct NewsletterSender {
     smtp_config: SmtpConfig,
     rate_limiter: RateLimiter,
     from_address: Mailbox,
+    sending: SendingConfig,
+    tracker: Option<Tracker>,
+    /// Stands in for `{{ subscriber.name }}` when a subscriber has no name
+    fallback_name: String,
+}
+
+/// Start and end of text that personalization leaves alone
+const VERBATIM_START: char = '\u{E000}';
+const VERBATIM_END: char = '\u{E001}';
+
+/// Mark text, such as a post body, whose tokens must reach every recipient
+/// unchanged (a post about templating may well contain `{{ unsubscribe_url }}`).
+/// `Personalization::apply` removes the marks.
+pub fn verbatim(text: &str) -> String {
+    format!("{VERBATIM_START}{text}{VERBATIM_END}")
+}
+
+/// Split `text`, which starts with `VERBATIM_START`, after its matching end mark
+fn split_verbatim(text: &str) -> (&str, &str) {
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            VERBATIM_START => depth += 1,
+            VERBATIM_END => {
+                depth -= 1;
+                if depth == 0 {
+                    return text.split_at(index + c.len_utf8());
+                }
+            }
+            _ => {}
+        }
+    }
+    (text, "")
+}
+
+fn push_unmarked(result: &mut String, text: &str) {
+    result.extend(
+        text.chars()
+            .filter(|c| !matches!(*c, VERBATIM_START | VERBATIM_END)),
+    );
+}
+
+/// The values filled in for one recipient
+pub struct Personalization<'a> {
+    pub name: &'a str,
+    pub email: &'a str,
+    pub unsubscribe_url: &'a str,
+    pub unsubscribe_token: &'a str,
+}
+
+impl Personalization<'_> {
+    /// Replace the tokens in `text`; values are HTML-escaped when `html` is set.
+    /// Unknown tokens and text marked with `verbatim` are left alone.
+    pub fn apply(&self, text: &str, html: bool) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        loop {
+            let protected = rest.find(VERBATIM_START).unwrap_or(rest.len());
+            let Some(start) = rest[..protected].find("{{") else {
+                push_unmarked(&mut result, &rest[..protected]);
+                if protected == rest.len() {
+                    break;
+                }
+                let (kept, after) = split_verbatim(&rest[protected..]);
+                push_unmarked(&mut result, kept);
+                rest = after;
+                continue;
+            };
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest[..protected - start].find("}}") else {
+                // Unclosed token: keep it as written
+                result.push_str("{{");
+                rest = &rest[2..];
+                continue;
+            };
+            let value = match rest[2..end].trim() {
+                "subscriber.name" => Some(self.name),
+                "subscriber.email" => Some(self.email),
+                "unsubscribe_url" => Some(self.unsubscribe_url),
+                "unsubscribe_token" => Some(self.unsubscribe_token),
+                _ => None,
+            };
+            match value {
+                Some(value) if html => result.push_str(&markdown::html_escape(value)),
+                Some(value) => result.push_str(value),
+                None => result.push_str(&rest[..end + 2]),
+            }
+            rest = &rest[end + 2..];
+        }
+        result
+    }
 }
 
 impl NewsletterSender {
@@ -128,7 +231,7 @@ impl NewsletterSender {
     pub fn new(
         smtp_config: SmtpConfig,
         sender_name: Option<String>,
-        emails_per_minute: u32,
+        sending: SendingConfig,
     ) -> Result<Self> {
         let from_name = sender_name.unwrap_or_else(|| "Newsletter".to_string());
         let from_address = format!("{} <{}>", from_name, smtp_config.username)
@@ -137,11 +240,26 @@ impl NewsletterSender {
 
         Ok(Self {
             smtp_config,
-            rate_limiter: RateLimiter::new(emails_per_minute),
+            rate_limiter: RateLimiter::new(sending.emails_per_minute.max(1)),
             from_address,
+            sending,
+            tracker: None,
+            fallback_name: String::new(),
         })
     }
 
+    /// Use `name` for `{{ subscriber.name }}` when a subscriber has none
+    pub fn with_fallback_name(mut self, name: &str) -> Self {
+        self.fallback_name = name.to_string();
+        self
+    }
+
+    /// Add open and click tracking to every email sent
+    pub fn with_tracker(mut self, tracker: Tracker) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
     /// Send newsletter to all approved subscribers
     pub fn send_to_subscribers(
         &mut self,
@@ -149,6 +267,7 @@ impl NewsletterSender {
         subscribers: &[Subscriber],
         smtp_password: &str,
         progress_callback: Option<Box<dyn Fn(usize, usize)>>,
+        mut on_delivery: Option<DeliveryCallback<'_>>,
     ) -> Result<SendReport> {
         let approved_subscribers: Vec<_> = subscribers
             .iter()
@@ -158,22 +277,32 @@ impl NewsletterSender {
         let mut report = SendReport::new(approved_subscribers.len());
 
         if approved_subscribers.is_empty() {
-            println!("⚠️  No approved subscribers found");
+            tracing::warn!("No approved subscribers found");
             report.complete();
             return Ok(report);
         }
 
-        println!(
+        crate::status!(
             "📤 Sending newsletter to {} approved subscribers",
             approved_subscribers.len()
         );
 
         // Create SMTP transport
         let transport = self.create_smtp_transport(smtp_password)?;
+        let batch_size = self.sending.batch_size.max(1);
 
+        let progress = Progress::new(approved_subscribers.len() as u64, "Sending");
         for (index, subscriber) in approved_subscribers.iter().enumerate() {
-            // Rate limiting
-            self.rate_limiter.wait_if_needed();
+            // Pause between batches to stay under provider limits
+            if index > 0 && index % batch_size == 0 && self.sending.batch_pause_secs > 0 {
+                crate::status!(
+                    "⏸️  Sent {} of {}, pausing {} seconds before the next batch...",
+                    index,
+                    approved_subscribers.len(),
+                    self.sending.batch_pause_secs
+                );
+                thread::sleep(Duration::from_secs(self.sending.batch_pause_secs));
+            }
 
             // Generate unsubscribe token
             let unsubscribe_token = self.generate_unsubscribe_token(&subscriber.email);
@@ -182,30 +311,69 @@ impl NewsletterSender {
             let personalized_newsletter =
                 self.personalize_newsletter(newsletter, subscriber, &unsubscribe_token)?;
 
-            match self.send_single_email(&transport, &personalized_newsletter, subscriber) {
+            match self.send_with_retry(&transport, &personalized_newsletter, subscriber) {
                 Ok(_) => {
                     report.add_success();
-                    println!("✅ Sent to {}", subscriber.email);
+                    crate::status!("✅ Sent to {}", subscriber.email);
+                    if let Some(ref mut callback) = on_delivery {
+                        callback(subscriber, None)?;
+                    }
                 }
                 Err(e) => {
-                    let error_msg = format!("Failed to send to {}: {}", subscriber.email, e);
+                    let error_msg = format!("Failed to send to {}: {:#}", subscriber.email, e);
                     report.add_error(subscriber.email.clone(), error_msg.clone());
-                    println!("❌ {}", error_msg);
+                    tracing::error!("{}", error_msg);
+                    if let Some(ref mut callback) = on_delivery {
+                        callback(subscriber, Some(&format!("{:#}", e)))?;
+                    }
                 }
             }
 
+            progress.inc();
             // Call progress callback if provided
             if let Some(ref callback) = progress_callback {
                 callback(index + 1, approved_subscribers.len());
             }
         }
 
+        drop(progress);
         report.complete();
         self.print_send_summary(&report);
 
         Ok(report)
     }
 
+    /// Send a single email, retrying transient SMTP failures with exponential backoff
+    fn send_with_retry(
+        &mut self,
+        transport: &SmtpTransport,
+        newsletter: &Newsletter,
+        subscriber: &Subscriber,
+    ) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            self.rate_limiter.wait_if_needed();
+
+            match self.send_single_email(transport, newsletter, subscriber) {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < self.sending.max_retries && is_transient(&e) => {
+                    let delay = retry_delay(self.sending.retry_backoff_secs, attempt);
+                    attempt += 1;
+                    tracing::warn!(
+                        "Temporary failure for {} ({:#}), retrying in {}s ({}/{})",
+                        subscriber.email,
+                        e,
+                        delay,
+                        attempt,
+                        self.sending.max_retries
+                    );
+                    thread::sleep(Duration::from_secs(delay));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     /// Send test email to a single address
     pub fn send_test_email(
         &mut self,
@@ -213,7 +381,7 @@ impl NewsletterSender {
         test_email: &str,
         smtp_password: &str,
     ) -> Result<()> {
-        println!("📧 Sending test email to {}", test_email);
+        crate::status!("📧 Sending test email to {}", test_email);
 
         let transport = self.create_smtp_transport(smtp_password)?;
 
@@ -226,6 +394,8 @@ impl NewsletterSender {
             approved_at: Some(Utc::now()),
             source_email_id: None,
             notes: Some("Test email".to_string()),
+            tags: Vec::new(),
+            name: None,
         };
 
         let unsubscribe_token = self.generate_unsubscribe_token(test_email);
@@ -235,11 +405,11 @@ impl NewsletterSender {
         self.send_single_email(&transport, &personalized_newsletter, &test_subscriber)
             .context("Failed to send test email")?;
 
-        println!("✅ Test email sent successfully");
+        crate::status!("✅ Test email sent successfully");
         Ok(())
     }
 
-    /// Create SMTP transport
+    /// Create SMTP transport; `password` is an access token when signing in with OAuth2
     fn create_smtp_transport(&self, password: &str) -> Result<SmtpTransport> {
         let credentials = Credentials::new(self.smtp_config.username.clone(), password.to_string());
 
@@ -258,6 +428,21 @@ impl NewsletterSender {
             )?));
         }
 
+        if self.smtp_config.oauth.is_some() {
+            builder = builder.authentication(vec![Mechanism::Xoauth2]);
+        }
+
+        tracing::debug!(
+            "SMTP server {}:{} as {}{}",
+            self.smtp_config.server,
+            self.smtp_config.port,
+            self.smtp_config.username,
+            if self.smtp_config.oauth.is_some() {
+                " (OAuth2)"
+            } else {
+                ""
+            }
+        );
         let transport = builder
             .port(self.smtp_config.port)
             .credentials(credentials)
@@ -308,32 +493,32 @@ impl NewsletterSender {
     fn personalize_newsletter(
         &self,
         newsletter: &Newsletter,
-        _subscriber: &Subscriber,
+        subscriber: &Subscriber,
         unsubscribe_token: &str,
     ) -> Result<Newsletter> {
         let mut personalized = newsletter.clone();
 
-        // Replace unsubscribe token in content
-        personalized.html_content = personalized
-            .html_content
-            .replace("{{unsubscribe_token}}", unsubscribe_token);
-        personalized.text_content = personalized
-            .text_content
-            .replace("{{unsubscribe_token}}", unsubscribe_token);
-
-        // Add personalized unsubscribe link
+        // Personalized unsubscribe link
         let unsubscribe_url = format!(
             "mailto:{}?subject=Unsubscribe&body=Please unsubscribe me from the newsletter. Token: {}",
             self.smtp_config.username,
             unsubscribe_token
         );
 
-        personalized.html_content = personalized
-            .html_content
-            .replace("{{unsubscribe_url}}", &unsubscribe_url);
-        personalized.text_content = personalized
-            .text_content
-            .replace("{{unsubscribe_url}}", &unsubscribe_url);
+        let values = Personalization {
+            name: subscriber.name.as_deref().unwrap_or(&self.fallback_name),
+            email: &subscriber.email,
+            unsubscribe_url: &unsubscribe_url,
+            unsubscribe_token,
+        };
+        personalized.subject = values.apply(&personalized.subject, false);
+        personalized.html_content = values.apply(&personalized.html_content, true);
+        personalized.text_content = values.apply(&personalized.text_content, false);
+
+        if let Some(tracker) = &self.tracker {
+            personalized.html_content =
+                tracker.instrument(&personalized.html_content, &subscriber.email);
+        }
 
         Ok(personalized)
     }
@@ -373,6 +558,20 @@ impl NewsletterSender {
     }
 }
 
+/// Seconds to wait before retry `attempt` (from 0): `backoff`, doubled each time
+fn retry_delay(backoff: u64, attempt: u32) -> u64 {
+    backoff.saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+}
+
+/// Whether a send error is worth retrying: SMTP 4xx replies, timeouts and connection
+/// problems are; permanent 5xx rejections and bad addresses are not
+fn is_transient(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<lettre::transport::smtp::Error>() {
+        Some(smtp_error) => !smtp_error.is_permanent() && !smtp_error.is_client(),
+        None => false,
+    }
+}
+
 // Add base64 encoding for unsubscribe tokens
 mod base64 {
     pub fn encode(input: &[u8]) -> String {
@@ -408,6 +607,52 @@ mod base64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_personalization() {
+        let values = Personalization {
+            name: "Ada & co",
+            email: "ada@example.com",
+            unsubscribe_url: "mailto:news@example.com?body=Token: abc",
+            unsubscribe_token: "abc",
+        };
+        assert_eq!(
+            values.apply(
+                "Hi {{ subscriber.name }} ({{subscriber.email}}), {{ post.title }}",
+                false
+            ),
+            "Hi Ada & co (ada@example.com), {{ post.title }}"
+        );
+        assert_eq!(
+            values.apply(
+                "<p>Hi {{ subscriber.name }}</p><a href=\"{{unsubscribe_url}}\">",
+                true
+            ),
+            "<p>Hi Ada &amp; co</p><a href=\"mailto:news@example.com?body=Token: abc\">"
+        );
+        assert_eq!(
+            values.apply("Unclosed {{ subscriber.name", false),
+            "Unclosed {{ subscriber.name"
+        );
+
+        // Marked text keeps its tokens, even nested or across a token's braces
+        let post = format!(
+            "<h1>{{{{ subscriber.name }}}}</h1>{}<p>{{{{ unsubscribe_url }}}}</p>",
+            verbatim(&format!(
+                "<p>Write {{{{ subscriber.name }}}} {}</p>",
+                verbatim("and {{ unsubscribe_url }}")
+            ))
+        );
+        assert_eq!(
+            values.apply(&post, true),
+            "<h1>Ada &amp; co</h1><p>Write {{ subscriber.name }} and {{ unsubscribe_url }}</p>\
+             <p>mailto:news@example.com?body=Token: abc</p>"
+        );
+        assert_eq!(
+            values.apply(&format!("{{{{ x {}", verbatim("}}")), false),
+            "{{ x }}"
+        );
+    }
+
     #[test]
     fn test_send_report() {
         let mut report = SendReport::new(5);
@@ -444,6 +689,15 @@ mod tests {
         assert_eq!(limiter.last_send_times.len(), 2);
     }
 
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(30, 0), 30);
+        assert_eq!(retry_delay(30, 2), 120);
+        assert_eq!(retry_delay(30, 63), u64::MAX);
+        assert_eq!(retry_delay(30, 64), u64::MAX);
+        assert_eq!(retry_delay(0, 100), 0);
+    }
+
     #[test]
     fn test_base64_encoding() {
         let input = "test@example.com";
@@ -0,0 +1,151 @@
+//! Optional open and click tracking for newsletters
+//!
+//! Each recipient of a send gets a random token. Tracked emails carry a 1x1 pixel
+//! and have their links rewritten to go through the newsletter API server, which
+//! records the event against the token before redirecting. Only the token, the
+//! event kind and the time are stored: no IP addresses or user agents.
+
+use std::collections::HashMap;
+
+/// Transparent 1x1 GIF served for open tracking
+pub const PIXEL_GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+
+/// Rewrites newsletter HTML so opens and clicks can be counted
+#[derive(Debug, Clone)]
+pub struct Tracker {
+    base_url: String,
+    /// Tracked links, indexed by their position in the send
+    links: HashMap<String, usize>,
+    /// Recipient email -> tracking token
+    tokens: HashMap<String, String>,
+}
+
+impl Tracker {
+    /// Create a tracker for a send; `base_url` is where the API server is reachable
+    pub fn new(base_url: &str, links: &[String], tokens: HashMap<String, String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            links: links
+                .iter()
+                .enumerate()
+                .map(|(index, url)| (url.clone(), index))
+                .collect(),
+            tokens,
+        }
+    }
+
+    /// Add the tracking pixel and wrap links for one recipient.
+    /// Recipients without a token get the HTML unchanged.
+    pub fn instrument(&self, html: &str, email: &str) -> String {
+        let Some(token) = self.tokens.get(email) else {
+            return html.to_string();
+        };
+
+        let mut tracked = rewrite_links(html, |url| {
+            self.links
+                .get(&unescape_url(url))
+                .map(|index| format!("{}/t/c/{}/{}", self.base_url, token, index))
+        });
+
+        let pixel = format!(
+            r#"<img src="{}/t/o/{}" width="1" height="1" alt="" style="display: block; border: 0;">"#,
+            self.base_url, token
+        );
+        match tracked.rfind("</body>") {
+            Some(pos) => tracked.insert_str(pos, &pixel),
+            None => tracked.push_str(&pixel),
+        }
+
+        tracked
+    }
+}
+
+/// Collect the distinct http(s) links in a newsletter, in order of appearance
+pub fn extract_links(html: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    rewrite_links(html, |url| {
+        let url = unescape_url(url);
+        if (url.starts_with("http://") || url.starts_with("https://")) && !links.contains(&url) {
+            links.push(url);
+        }
+        None
+    });
+    links
+}
+
+/// Replace the value of every `href` attribute for which `rewrite` returns a new URL
+fn rewrite_links(html: &str, mut rewrite: impl FnMut(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(pos) = rest.find("href=") {
+        let (before, after) = rest.split_at(pos + "href=".len());
+        output.push_str(before);
+
+        let quote = match after.chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => {
+                rest = after;
+                continue;
+            }
+        };
+        let Some(end) = after[1..].find(quote) else {
+            rest = after;
+            continue;
+        };
+
+        let url = &after[1..1 + end];
+        output.push(quote);
+        match rewrite(url) {
+            Some(replacement) => output.push_str(&replacement),
+            None => output.push_str(url),
+        }
+        output.push(quote);
+        rest = &after[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Attribute values are HTML-escaped; the redirect target must not be
+fn unescape_url(url: &str) -> String {
+    url.replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"<html><body><a href="https://example.com/a?x=1&amp;y=2">A</a>
+<a href='https://example.com/b'>B</a><a href="mailto:me@example.com">Mail</a>
+<a href="https://example.com/b">B again</a></body></html>"#;
+
+    #[test]
+    fn test_extract_links() {
+        assert_eq!(
+            extract_links(HTML),
+            vec!["https://example.com/a?x=1&y=2", "https://example.com/b"]
+        );
+    }
+
+    #[test]
+    fn test_instrument() {
+        let links = extract_links(HTML);
+        let tokens = HashMap::from([("a@example.com".to_string(), "tok123".to_string())]);
+        let tracker = Tracker::new("https://news.example.com/", &links, tokens);
+
+        let html = tracker.instrument(HTML, "a@example.com");
+        assert!(html.contains(r#"href="https://news.example.com/t/c/tok123/0""#));
+        assert!(html.contains(r#"href='https://news.example.com/t/c/tok123/1'"#));
+        assert!(html.contains(r#"href="mailto:me@example.com""#));
+        assert!(html.contains(r#"src="https://news.example.com/t/o/tok123""#));
+        assert!(html.find("/t/o/").unwrap() < html.find("</body>").unwrap());
+
+        assert_eq!(tracker.instrument(HTML, "unknown@example.com"), HTML);
+    }
+}
@@ -17,7 +17,9 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::newsletter::{NewsletterDatabase, Subscriber, SubscriberStatus};
+use crate::newsletter::{NewsletterDatabase, SourceEmail, Subscriber, SubscriberStatus};
+use crate::tui::editor::Editor;
+use crate::tui::theme::TuiTheme;
 
 pub type AppResult<T> = anyhow::Result<T>;
 
@@ -36,6 +38,10 @@ pub enum AppMode {
     Confirm,
     Loading,
     Search,
+    /// Full details of one subscriber
+    Detail,
+    /// Editing the notes of the subscriber shown in the detail view
+    EditNotes,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +51,23 @@ pub enum ConfirmAction {
     Delete,
 }
 
+impl ConfirmAction {
+    fn past_tense(&self) -> &'static str {
+        match self {
+            ConfirmAction::Approve => "approved",
+            ConfirmAction::Decline => "declined",
+            ConfirmAction::Delete => "deleted",
+        }
+    }
+}
+
+/// The subscribers as they were before the last action, so it can be undone
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub action: ConfirmAction,
+    pub subscribers: Vec<(Subscriber, Option<SourceEmail>)>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum SubscriberFilter {
     All,
@@ -162,6 +185,18 @@ pub struct ModernApprovalApp {
     pub status_message: Option<(String, Instant)>,
     /// Confirmation action
     pub confirm_action: Option<ConfirmAction>,
+    /// Last approve/decline/delete, for undo
+    last_action: Option<UndoEntry>,
+    /// Email of the subscriber shown in the detail view
+    detail_email: Option<String>,
+    /// Source email of the subscriber shown in the detail view
+    detail_source: Option<SourceEmail>,
+    /// Scroll offset of the source email in the detail view
+    detail_scroll: u16,
+    /// Notes editor, while editing
+    notes_editor: Option<Editor>,
+    /// Theme for the notes editor
+    editor_theme: TuiTheme,
     /// Database reference
     database: NewsletterDatabase,
     /// Modern theme
@@ -198,6 +233,12 @@ impl ModernApprovalApp {
             search_query: String::new(),
             status_message: Some(("Welcome to Newsletter Manager".to_string(), Instant::now())),
             confirm_action: None,
+            last_action: None,
+            detail_email: None,
+            detail_source: None,
+            detail_scroll: 0,
+            notes_editor: None,
+            editor_theme: TuiTheme::minimal_retro(),
             database,
             theme: ModernTheme::default(),
             last_redraw: Instant::now(),
@@ -296,6 +337,8 @@ impl ModernApprovalApp {
             AppMode::Help => self.handle_help_key_event(key),
             AppMode::Confirm => self.handle_confirm_key_event(key),
             AppMode::Search => self.handle_search_key_event(key),
+            AppMode::Detail => self.handle_detail_key_event(key),
+            AppMode::EditNotes => self.handle_notes_key_event(key),
             AppMode::Loading => Ok(ApprovalResult::Continue), // Ignore input during loading
         }
     }
@@ -366,8 +409,12 @@ impl ModernApprovalApp {
                 self.clear_selection();
                 Ok(ApprovalResult::Continue)
             }
+            KeyCode::Enter => {
+                self.open_detail()?;
+                Ok(ApprovalResult::Continue)
+            }
             // Actions
-            KeyCode::Char('A') | KeyCode::Enter => {
+            KeyCode::Char('A') => {
                 self.initiate_action(ConfirmAction::Approve)?;
                 Ok(ApprovalResult::Continue)
             }
@@ -379,6 +426,10 @@ impl ModernApprovalApp {
                 self.initiate_action(ConfirmAction::Delete)?;
                 Ok(ApprovalResult::Continue)
             }
+            KeyCode::Char('u') => {
+                self.undo_last_action()?;
+                Ok(ApprovalResult::Continue)
+            }
             // Filters
             KeyCode::Char('1') => {
                 self.set_filter(SubscriberFilter::All)?;
@@ -426,6 +477,62 @@ impl ModernApprovalApp {
         }
     }
 
+    fn handle_detail_key_event(&mut self, key: KeyEvent) -> AppResult<ApprovalResult> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                self.detail_email = None;
+                self.mode = AppMode::List;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.detail_scroll = self.detail_scroll.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.detail_scroll = self.detail_scroll.saturating_add(1);
+            }
+            KeyCode::Char('e') => {
+                let notes = self
+                    .detail_subscriber()
+                    .and_then(|s| s.notes.clone())
+                    .unwrap_or_default();
+                self.notes_editor = Some(Editor::new(notes));
+                self.mode = AppMode::EditNotes;
+            }
+            KeyCode::Char('A') => self.initiate_detail_action(ConfirmAction::Approve)?,
+            KeyCode::Char('D') => self.initiate_detail_action(ConfirmAction::Decline)?,
+            KeyCode::Char('X') | KeyCode::Delete => {
+                self.initiate_detail_action(ConfirmAction::Delete)?
+            }
+            KeyCode::Char('u') => {
+                self.undo_last_action()?;
+                if self.detail_subscriber().is_none() {
+                    self.detail_email = None;
+                    self.mode = AppMode::List;
+                }
+            }
+            _ => {}
+        }
+        Ok(ApprovalResult::Continue)
+    }
+
+    fn handle_notes_key_event(&mut self, key: KeyEvent) -> AppResult<ApprovalResult> {
+        match key.code {
+            KeyCode::Esc => {
+                self.notes_editor = None;
+                self.mode = AppMode::Detail;
+            }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.save_notes()?;
+                self.mode = AppMode::Detail;
+            }
+            _ => {
+                if let Some(editor) = self.notes_editor.as_mut() {
+                    editor.handle_key_event(key);
+                }
+            }
+        }
+        Ok(ApprovalResult::Continue)
+    }
+
     fn handle_search_key_event(&mut self, key: KeyEvent) -> AppResult<ApprovalResult> {
         match key.code {
             KeyCode::Esc => {
@@ -469,6 +576,89 @@ impl ModernApprovalApp {
         Ok(())
     }
 
+    fn open_detail(&mut self) -> Result<()> {
+        let Some(index) = self.current_subscriber_index() else {
+            self.set_status_message("No subscriber selected");
+            return Ok(());
+        };
+
+        let email = self.subscribers[index].email.clone();
+        self.detail_source = self.database.get_source_email(&email)?;
+        self.detail_email = Some(email);
+        self.detail_scroll = 0;
+        self.mode = AppMode::Detail;
+        Ok(())
+    }
+
+    fn detail_subscriber(&self) -> Option<&Subscriber> {
+        let email = self.detail_email.as_ref()?;
+        self.subscribers.iter().find(|s| &s.email == email)
+    }
+
+    /// Act on the subscriber shown in the detail view only
+    fn initiate_detail_action(&mut self, action: ConfirmAction) -> Result<()> {
+        let Some(email) = self.detail_email.clone() else {
+            return Ok(());
+        };
+
+        self.selected.clear();
+        if let Some(index) = self.subscribers.iter().position(|s| s.email == email) {
+            self.selected.insert(index);
+        }
+        self.detail_email = None;
+        self.initiate_action(action)
+    }
+
+    fn save_notes(&mut self) -> Result<()> {
+        let (Some(editor), Some(email)) = (self.notes_editor.take(), self.detail_email.clone())
+        else {
+            return Ok(());
+        };
+
+        let notes = editor.get_content();
+        let notes = notes.trim();
+        self.database
+            .set_subscriber_notes(&email, (!notes.is_empty()).then_some(notes))?;
+        self.refresh_data()?;
+        self.set_status_message(&format!("📝 Saved notes for {}", email));
+        Ok(())
+    }
+
+    fn undo_last_action(&mut self) -> Result<()> {
+        let Some(undo) = self.last_action.take() else {
+            self.set_status_message("Nothing to undo");
+            return Ok(());
+        };
+
+        for (subscriber, source) in &undo.subscribers {
+            let id = match undo.action {
+                ConfirmAction::Delete => {
+                    let id = self.database.restore_subscriber(subscriber)?;
+                    if let Some(source) = source {
+                        self.database.set_source_email(&subscriber.email, source)?;
+                    }
+                    Some(id)
+                }
+                ConfirmAction::Approve | ConfirmAction::Decline => subscriber.id,
+            };
+            if let Some(id) = id {
+                self.database.restore_subscriber_status(
+                    id,
+                    subscriber.status.clone(),
+                    subscriber.approved_at,
+                )?;
+            }
+        }
+
+        self.refresh_data()?;
+        self.set_status_message(&format!(
+            "↩️ Undone: {} {} subscriber(s)",
+            undo.action.past_tense(),
+            undo.subscribers.len()
+        ));
+        Ok(())
+    }
+
     fn set_filter(&mut self, filter: SubscriberFilter) -> Result<()> {
         if self.filter != filter {
             self.filter = filter;
@@ -581,10 +771,23 @@ impl ModernApprovalApp {
 
     // Rest of the implementation methods (same logic as before but optimized)
     fn execute_confirm_action(&mut self) -> Result<()> {
-        if let Some(action) = &self.confirm_action {
+        if let Some(action) = self.confirm_action.clone() {
             let selected_indices: Vec<usize> = self.selected.iter().cloned().collect();
             let count = selected_indices.len();
 
+            // Remember the subscribers as they are now, so the action can be undone
+            let mut previous = Vec::with_capacity(count);
+            for subscriber in selected_indices
+                .iter()
+                .filter_map(|&index| self.subscribers.get(index))
+            {
+                let source = match action {
+                    ConfirmAction::Delete => self.database.get_source_email(&subscriber.email)?,
+                    ConfirmAction::Approve | ConfirmAction::Decline => None,
+                };
+                previous.push((subscriber.clone(), source));
+            }
+
             // Batch database operations for better performance
             match action {
                 ConfirmAction::Approve => {
@@ -627,6 +830,10 @@ impl ModernApprovalApp {
                 }
             }
 
+            self.last_action = Some(UndoEntry {
+                action,
+                subscribers: previous,
+            });
             self.selected.clear();
             self.confirm_action = None;
 
@@ -764,6 +971,11 @@ impl ModernApprovalApp {
             AppMode::Help => self.render_help(frame, area),
             AppMode::Confirm => self.render_confirm(frame, area),
             AppMode::Search => self.render_search(frame, area),
+            AppMode::Detail => self.render_detail(frame, area),
+            AppMode::EditNotes => {
+                self.render_detail(frame, area);
+                self.render_notes_editor(frame, area);
+            }
         }
 
         self.mark_redrawn();
@@ -1187,9 +1399,20 @@ impl ModernApprovalApp {
             (
                 "Actions",
                 vec![
-                    ("A/Enter", "Approve selected"),
+                    ("Enter", "Show details and notes"),
+                    ("A", "Approve selected"),
                     ("D", "Decline selected"),
                     ("X/Del", "Delete selected"),
+                    ("u", "Undo last action"),
+                ],
+            ),
+            (
+                "Details",
+                vec![
+                    ("↑/↓", "Scroll source email"),
+                    ("e", "Edit notes (Ctrl+S saves)"),
+                    ("A/D/X", "Approve/decline/delete this subscriber"),
+                    ("Esc", "Back to list"),
                 ],
             ),
             (
@@ -1304,7 +1527,7 @@ impl ModernApprovalApp {
                     Span::raw(format!(" {} subscriber(s)?", count)),
                 ]),
                 Line::from(""),
-                Line::from("This action cannot be undone."),
+                Line::from("Press 'u' afterwards to undo."),
                 Line::from(""),
                 Line::from(vec![
                     Span::styled("Press ", Style::default().fg(self.theme.text_secondary)),
@@ -1347,8 +1570,194 @@ impl ModernApprovalApp {
     }
 }
 
+impl ModernApprovalApp {
+    fn render_detail(&self, frame: &mut Frame, area: Rect) {
+        let Some(subscriber) = self.detail_subscriber() else {
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(9),
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ])
+            .split(area);
+
+        let label = |text: &'static str| {
+            Span::styled(
+                format!("{:<12}", text),
+                Style::default().fg(self.theme.text_secondary),
+            )
+        };
+        let value = |text: String| Span::styled(text, Style::default().fg(self.theme.text));
+        let status_color = match subscriber.status {
+            SubscriberStatus::Pending => self.theme.warning,
+            SubscriberStatus::Approved => self.theme.success,
+            SubscriberStatus::Declined => self.theme.danger,
+        };
+        let decided_label = match subscriber.status {
+            SubscriberStatus::Declined => "Declined",
+            _ => "Approved",
+        };
+
+        let info = vec![
+            Line::from(vec![
+                label("Status"),
+                Span::styled(
+                    subscriber.status.to_string(),
+                    Style::default()
+                        .fg(status_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                label("Subscribed"),
+                value(
+                    subscriber
+                        .subscribed_at
+                        .format("%Y-%m-%d %H:%M UTC")
+                        .to_string(),
+                ),
+            ]),
+            Line::from(vec![
+                label(decided_label),
+                value(
+                    subscriber
+                        .approved_at
+                        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ]),
+            Line::from(vec![
+                label("Source"),
+                value(
+                    subscriber
+                        .source_email_id
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ]),
+            Line::from(vec![
+                label("Tags"),
+                value(if subscriber.tags.is_empty() {
+                    "-".to_string()
+                } else {
+                    subscriber.tags.join(", ")
+                }),
+            ]),
+            Line::from(vec![
+                label("Notes"),
+                value(
+                    subscriber
+                        .notes
+                        .as_deref()
+                        .unwrap_or("-")
+                        .replace('\n', " / "),
+                ),
+            ]),
+        ];
+
+        let info = Paragraph::new(info)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("📧 {}", subscriber.email))
+                    .border_style(Style::default().fg(self.theme.border_focused))
+                    .title_style(
+                        Style::default()
+                            .fg(self.theme.primary)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+            )
+            .wrap(Wrap { trim: false });
+        frame.render_widget(Clear, area);
+        frame.render_widget(info, chunks[0]);
+
+        let source_lines = match &self.detail_source {
+            Some(source) => {
+                let mut lines = vec![
+                    Line::from(vec![label("From"), value(source.from.clone())]),
+                    Line::from(vec![label("Subject"), value(source.subject.clone())]),
+                    Line::from(vec![
+                        label("Date"),
+                        value(source.date.clone().unwrap_or_else(|| "-".to_string())),
+                    ]),
+                    Line::from(""),
+                ];
+                lines.extend(source.body.lines().map(|line| Line::from(line.to_string())));
+                lines
+            }
+            None => vec![Line::from(Span::styled(
+                "The original email was not kept for this subscriber.",
+                Style::default().fg(self.theme.text_secondary),
+            ))],
+        };
+
+        let source = Paragraph::new(source_lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Source Email")
+                    .border_style(Style::default().fg(self.theme.border))
+                    .title_style(Style::default().fg(self.theme.accent)),
+            )
+            .style(Style::default().fg(self.theme.text))
+            .wrap(Wrap { trim: false })
+            .scroll((self.detail_scroll, 0));
+        frame.render_widget(source, chunks[1]);
+
+        let status_text = match &self.status_message {
+            Some((message, _)) => Line::from(message.clone()),
+            None => Line::from(vec![
+                Span::styled("e", Style::default().fg(self.theme.accent)),
+                Span::raw(" edit notes  "),
+                Span::styled("A", Style::default().fg(self.theme.success)),
+                Span::raw("/"),
+                Span::styled("D", Style::default().fg(self.theme.warning)),
+                Span::raw("/"),
+                Span::styled("X", Style::default().fg(self.theme.danger)),
+                Span::raw(" approve/decline/delete  "),
+                Span::styled("u", Style::default().fg(self.theme.accent)),
+                Span::raw(" undo  "),
+                Span::styled("Esc", Style::default().fg(self.theme.accent)),
+                Span::raw(" back"),
+            ]),
+        };
+        let status = Paragraph::new(status_text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.border)),
+            )
+            .style(Style::default().fg(self.theme.text));
+        frame.render_widget(status, chunks[2]);
+    }
+
+    fn render_notes_editor(&self, frame: &mut Frame, area: Rect) {
+        let Some(editor) = &self.notes_editor else {
+            return;
+        };
+
+        let popup_area = centered_rect(70, 50, area);
+        frame.render_widget(Clear, popup_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Notes - Ctrl+S to save, Esc to cancel")
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(self.theme.border_focused))
+            .title_style(
+                Style::default()
+                    .fg(self.theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            );
+        editor.render(frame, popup_area, block, &self.editor_theme);
+    }
+}
+
 /// Helper function to create a centered rectangle
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub(super) fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
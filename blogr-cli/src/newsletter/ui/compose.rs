@@ -0,0 +1,929 @@
+//! Newsletter composer TUI
+//!
+//! Edit the subject and markdown body of a newsletter with live plain text and
+//! HTML previews, pick the segments to send to, send a test email, and send or
+//! schedule the result.
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+use super::approval::{centered_rect, ModernTheme};
+use crate::newsletter::{Newsletter, NewsletterComposer, Segment, Subscriber, SubscriberStatus};
+use crate::tui::editor::Editor;
+use crate::tui::theme::TuiTheme;
+
+/// How long to wait after the last keystroke before re-rendering the preview
+const PREVIEW_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComposeResult {
+    Continue,
+    Quit,
+    /// Send a test email to this address
+    TestSend(String),
+    /// The draft was confirmed for sending or scheduling
+    Submit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComposeField {
+    Subject,
+    Body,
+    Segments,
+    Schedule,
+}
+
+impl ComposeField {
+    fn next(self) -> Self {
+        match self {
+            ComposeField::Subject => ComposeField::Body,
+            ComposeField::Body => ComposeField::Segments,
+            ComposeField::Segments => ComposeField::Schedule,
+            ComposeField::Schedule => ComposeField::Subject,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            ComposeField::Subject => ComposeField::Schedule,
+            ComposeField::Body => ComposeField::Subject,
+            ComposeField::Segments => ComposeField::Body,
+            ComposeField::Schedule => ComposeField::Segments,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComposeMode {
+    Edit,
+    Help,
+    TestEmail,
+    ConfirmSend,
+    ConfirmQuit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreviewKind {
+    Text,
+    Html,
+}
+
+/// What the user composed, ready to be sent or scheduled
+#[derive(Debug, Clone)]
+pub struct ComposeDraft {
+    pub subject: String,
+    pub content: String,
+    /// Segment specs such as `tag:rust`
+    pub segments: Vec<String>,
+    /// Schedule time as typed, if any
+    pub schedule: Option<String>,
+}
+
+/// Newsletter composition app
+pub struct ComposeApp {
+    /// Whether the app should continue running
+    pub running: bool,
+    /// Current mode
+    pub mode: ComposeMode,
+    /// Field receiving key presses
+    pub focus: ComposeField,
+    subject: String,
+    editor: Editor,
+    /// Subscriber tags and whether they are selected as segments
+    tags: Vec<(String, bool)>,
+    tag_cursor: usize,
+    schedule: String,
+    test_email: String,
+    /// Approved subscribers, used to count recipients
+    subscribers: Vec<Subscriber>,
+    composer: NewsletterComposer,
+    /// Last rendered preview, or the error rendering it
+    preview: std::result::Result<Newsletter, String>,
+    preview_kind: PreviewKind,
+    preview_scroll: u16,
+    preview_dirty: bool,
+    last_edit: Instant,
+    status_message: Option<(String, Instant)>,
+    theme: ModernTheme,
+    editor_theme: TuiTheme,
+    needs_redraw: bool,
+}
+
+impl ComposeApp {
+    pub fn new(composer: NewsletterComposer, subscribers: Vec<Subscriber>) -> Self {
+        let subscribers: Vec<Subscriber> = subscribers
+            .into_iter()
+            .filter(|s| s.status == SubscriberStatus::Approved)
+            .collect();
+
+        let mut tags: Vec<String> = subscribers.iter().flat_map(|s| s.tags.clone()).collect();
+        tags.sort();
+        tags.dedup();
+
+        let mut app = Self {
+            running: true,
+            mode: ComposeMode::Edit,
+            focus: ComposeField::Subject,
+            subject: String::new(),
+            editor: Editor::new(String::new()),
+            tags: tags.into_iter().map(|tag| (tag, false)).collect(),
+            tag_cursor: 0,
+            schedule: String::new(),
+            test_email: String::new(),
+            subscribers,
+            composer,
+            preview: Err(String::new()),
+            preview_kind: PreviewKind::Text,
+            preview_scroll: 0,
+            preview_dirty: false,
+            last_edit: Instant::now(),
+            status_message: Some((
+                "Tab switches fields, Ctrl+S sends, F1 for help".to_string(),
+                Instant::now(),
+            )),
+            theme: ModernTheme::default(),
+            editor_theme: TuiTheme::minimal_retro(),
+            needs_redraw: true,
+        };
+        app.refresh_preview();
+        app
+    }
+
+    /// Re-render the preview once typing pauses and expire status messages
+    pub fn update(&mut self) -> bool {
+        let mut updated = false;
+
+        if self.preview_dirty && self.last_edit.elapsed() >= PREVIEW_DELAY {
+            self.refresh_preview();
+            updated = true;
+        }
+
+        if let Some((_, timestamp)) = &self.status_message {
+            if timestamp.elapsed() > Duration::from_secs(5) {
+                self.status_message = None;
+                updated = true;
+            }
+        }
+
+        if updated {
+            self.needs_redraw = true;
+        }
+
+        updated
+    }
+
+    /// Mark the app for redraw
+    pub fn mark_for_redraw(&mut self) {
+        self.needs_redraw = true;
+    }
+
+    /// Check if redraw is needed
+    pub fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
+
+    /// Mark as redrawn
+    pub fn mark_redrawn(&mut self) {
+        self.needs_redraw = false;
+    }
+
+    pub fn set_status_message(&mut self, message: &str) {
+        self.status_message = Some((message.to_string(), Instant::now()));
+        self.needs_redraw = true;
+    }
+
+    /// The current draft
+    pub fn draft(&self) -> ComposeDraft {
+        let schedule = self.schedule.trim();
+        ComposeDraft {
+            subject: self.subject.trim().to_string(),
+            content: self.editor.get_content(),
+            segments: self.selected_segments(),
+            schedule: (!schedule.is_empty()).then(|| schedule.to_string()),
+        }
+    }
+
+    /// Render the current draft as a newsletter
+    pub fn newsletter(&self) -> Result<Newsletter> {
+        let draft = self.draft();
+        if draft.subject.is_empty() {
+            return Err(anyhow!("The subject is empty"));
+        }
+        if draft.content.trim().is_empty() {
+            return Err(anyhow!("The newsletter body is empty"));
+        }
+        self.composer.compose_custom(draft.subject, draft.content)
+    }
+
+    fn selected_segments(&self) -> Vec<String> {
+        self.tags
+            .iter()
+            .filter(|(_, selected)| *selected)
+            .map(|(tag, _)| format!("tag:{}", tag))
+            .collect()
+    }
+
+    /// Number of approved subscribers in the selected segments
+    fn recipient_count(&self) -> usize {
+        let segments = Segment::parse_all(&self.selected_segments()).unwrap_or_default();
+        self.subscribers
+            .iter()
+            .filter(|subscriber| segments.iter().all(|segment| segment.matches(subscriber)))
+            .count()
+    }
+
+    fn refresh_preview(&mut self) {
+        self.preview_dirty = false;
+        let subject = if self.subject.trim().is_empty() {
+            "(no subject)".to_string()
+        } else {
+            self.subject.trim().to_string()
+        };
+        self.preview = self
+            .composer
+            .compose_custom(subject, self.editor.get_content())
+            .map_err(|e| format!("{:#}", e));
+    }
+
+    fn content_changed(&mut self) {
+        self.preview_dirty = true;
+        self.last_edit = Instant::now();
+    }
+
+    /// Handle key events
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> Result<ComposeResult> {
+        self.needs_redraw = true;
+
+        match self.mode {
+            ComposeMode::Edit => Ok(self.handle_edit_key_event(key)),
+            ComposeMode::Help => {
+                if matches!(key.code, KeyCode::Esc | KeyCode::F(1) | KeyCode::Char('q')) {
+                    self.mode = ComposeMode::Edit;
+                }
+                Ok(ComposeResult::Continue)
+            }
+            ComposeMode::TestEmail => Ok(self.handle_test_email_key_event(key)),
+            ComposeMode::ConfirmSend => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.mode = ComposeMode::Edit;
+                    Ok(ComposeResult::Submit)
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.mode = ComposeMode::Edit;
+                    Ok(ComposeResult::Continue)
+                }
+                _ => Ok(ComposeResult::Continue),
+            },
+            ComposeMode::ConfirmQuit => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.running = false;
+                    Ok(ComposeResult::Quit)
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.mode = ComposeMode::Edit;
+                    Ok(ComposeResult::Continue)
+                }
+                _ => Ok(ComposeResult::Continue),
+            },
+        }
+    }
+
+    fn handle_edit_key_event(&mut self, key: KeyEvent) -> ComposeResult {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => return self.request_quit(),
+            KeyCode::Char('q') if ctrl => return self.request_quit(),
+            KeyCode::F(1) => {
+                self.mode = ComposeMode::Help;
+                return ComposeResult::Continue;
+            }
+            KeyCode::Tab => {
+                self.focus = self.focus.next();
+                return ComposeResult::Continue;
+            }
+            KeyCode::BackTab => {
+                self.focus = self.focus.previous();
+                return ComposeResult::Continue;
+            }
+            KeyCode::Char('s') if ctrl => {
+                self.request_send();
+                return ComposeResult::Continue;
+            }
+            KeyCode::Char('t') if ctrl => {
+                self.mode = ComposeMode::TestEmail;
+                return ComposeResult::Continue;
+            }
+            KeyCode::Char('p') if ctrl => {
+                self.preview_kind = match self.preview_kind {
+                    PreviewKind::Text => PreviewKind::Html,
+                    PreviewKind::Html => PreviewKind::Text,
+                };
+                self.preview_scroll = 0;
+                return ComposeResult::Continue;
+            }
+            KeyCode::Up if ctrl => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(3);
+                return ComposeResult::Continue;
+            }
+            KeyCode::Down if ctrl => {
+                self.preview_scroll = self.preview_scroll.saturating_add(3);
+                return ComposeResult::Continue;
+            }
+            _ => {}
+        }
+
+        match self.focus {
+            ComposeField::Subject => {
+                if edit_line(&mut self.subject, key) {
+                    self.content_changed();
+                }
+            }
+            ComposeField::Body => {
+                if self.editor.handle_key_event(key) {
+                    self.content_changed();
+                }
+            }
+            ComposeField::Segments => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.tag_cursor = self.tag_cursor.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.tag_cursor = (self.tag_cursor + 1).min(self.tags.len().saturating_sub(1));
+                }
+                KeyCode::Char(' ') | KeyCode::Enter => {
+                    if let Some((_, selected)) = self.tags.get_mut(self.tag_cursor) {
+                        *selected = !*selected;
+                    }
+                }
+                _ => {}
+            },
+            ComposeField::Schedule => {
+                edit_line(&mut self.schedule, key);
+            }
+        }
+
+        ComposeResult::Continue
+    }
+
+    fn handle_test_email_key_event(&mut self, key: KeyEvent) -> ComposeResult {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = ComposeMode::Edit;
+                ComposeResult::Continue
+            }
+            KeyCode::Enter => {
+                let email = self.test_email.trim().to_string();
+                if !email.contains('@') {
+                    self.set_status_message("Enter a valid email address");
+                    return ComposeResult::Continue;
+                }
+                self.mode = ComposeMode::Edit;
+                ComposeResult::TestSend(email)
+            }
+            _ => {
+                edit_line(&mut self.test_email, key);
+                ComposeResult::Continue
+            }
+        }
+    }
+
+    fn request_quit(&mut self) -> ComposeResult {
+        if self.subject.trim().is_empty() && self.editor.get_content().trim().is_empty() {
+            self.running = false;
+            return ComposeResult::Quit;
+        }
+        self.mode = ComposeMode::ConfirmQuit;
+        ComposeResult::Continue
+    }
+
+    fn request_send(&mut self) {
+        if let Err(e) = self.newsletter() {
+            self.set_status_message(&format!("Cannot send: {:#}", e));
+            return;
+        }
+        if self.recipient_count() == 0 {
+            self.set_status_message("No approved subscribers match the selected segments");
+            return;
+        }
+        self.mode = ComposeMode::ConfirmSend;
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let area = frame.area();
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(3),
+            ])
+            .split(area);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(rows[1]);
+        let left = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(self.tags.len().clamp(1, 6) as u16 + 2),
+                Constraint::Length(3),
+            ])
+            .split(columns[0]);
+
+        self.render_subject(frame, rows[0]);
+        self.render_body(frame, left[0]);
+        self.render_segments(frame, left[1]);
+        self.render_schedule(frame, left[2]);
+        self.render_preview(frame, columns[1]);
+        self.render_status_bar(frame, rows[2]);
+
+        match self.mode {
+            ComposeMode::Edit => {}
+            ComposeMode::Help => self.render_help(frame, area),
+            ComposeMode::TestEmail => self.render_test_email(frame, area),
+            ComposeMode::ConfirmSend => self.render_confirm_send(frame, area),
+            ComposeMode::ConfirmQuit => self.render_confirm_quit(frame, area),
+        }
+    }
+
+    fn field_block(&self, title: &str, field: ComposeField) -> Block<'static> {
+        let focused = self.focus == field;
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string())
+            .border_style(Style::default().fg(if focused {
+                self.theme.border_focused
+            } else {
+                self.theme.border
+            }))
+            .title_style(Style::default().fg(if focused {
+                self.theme.primary
+            } else {
+                self.theme.secondary
+            }))
+    }
+
+    fn render_subject(&self, frame: &mut Frame, area: Rect) {
+        let mut spans = vec![Span::styled(
+            self.subject.clone(),
+            Style::default().fg(self.theme.text),
+        )];
+        if self.focus == ComposeField::Subject {
+            spans.push(Span::styled("█", Style::default().fg(self.theme.primary)));
+        }
+
+        let subject = Paragraph::new(Line::from(spans))
+            .block(self.field_block("Subject", ComposeField::Subject));
+        frame.render_widget(subject, area);
+    }
+
+    fn render_body(&self, frame: &mut Frame, area: Rect) {
+        let block = self.field_block("Content (markdown)", ComposeField::Body);
+        self.editor.render(frame, area, block, &self.editor_theme);
+    }
+
+    fn render_segments(&self, frame: &mut Frame, area: Rect) {
+        let title = format!("Segments ({} recipients)", self.recipient_count());
+        let lines: Vec<Line> = if self.tags.is_empty() {
+            vec![Line::from(Span::styled(
+                "No subscriber tags yet: sending to all approved subscribers",
+                Style::default().fg(self.theme.text_secondary),
+            ))]
+        } else {
+            let visible = area.height.saturating_sub(2).max(1) as usize;
+            let start = self.tag_cursor.saturating_sub(visible - 1);
+            self.tags
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(visible)
+                .map(|(index, (tag, selected))| {
+                    let marker = if *selected { "[x]" } else { "[ ]" };
+                    let mut style = Style::default().fg(if *selected {
+                        self.theme.accent
+                    } else {
+                        self.theme.text
+                    });
+                    if self.focus == ComposeField::Segments && index == self.tag_cursor {
+                        style = style.bg(self.theme.surface).add_modifier(Modifier::BOLD);
+                    }
+                    Line::from(Span::styled(format!("{} tag:{}", marker, tag), style))
+                })
+                .collect()
+        };
+
+        let segments =
+            Paragraph::new(lines).block(self.field_block(&title, ComposeField::Segments));
+        frame.render_widget(segments, area);
+    }
+
+    fn render_schedule(&self, frame: &mut Frame, area: Rect) {
+        let line = if self.schedule.is_empty() && self.focus != ComposeField::Schedule {
+            Line::from(Span::styled(
+                "Send now (or type \"YYYY-MM-DD HH:MM\")",
+                Style::default().fg(self.theme.text_secondary),
+            ))
+        } else {
+            let mut spans = vec![Span::styled(
+                self.schedule.clone(),
+                Style::default().fg(self.theme.text),
+            )];
+            if self.focus == ComposeField::Schedule {
+                spans.push(Span::styled("█", Style::default().fg(self.theme.primary)));
+            }
+            Line::from(spans)
+        };
+
+        let schedule = Paragraph::new(line)
+            .block(self.field_block("Schedule (local time, optional)", ComposeField::Schedule));
+        frame.render_widget(schedule, area);
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let (title, text, style) = match (&self.preview, self.preview_kind) {
+            (Ok(newsletter), PreviewKind::Text) => (
+                "Preview: plain text (Ctrl+P for HTML)",
+                newsletter.text_content.clone(),
+                Style::default().fg(self.theme.text),
+            ),
+            (Ok(newsletter), PreviewKind::Html) => (
+                "Preview: HTML (Ctrl+P for plain text)",
+                newsletter.html_content.clone(),
+                Style::default().fg(self.theme.text_secondary),
+            ),
+            (Err(error), _) => (
+                "Preview",
+                error.clone(),
+                Style::default().fg(self.theme.danger),
+            ),
+        };
+
+        let preview = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(self.theme.border))
+                    .title_style(Style::default().fg(self.theme.accent)),
+            )
+            .style(style)
+            .wrap(Wrap { trim: false })
+            .scroll((self.preview_scroll, 0));
+        frame.render_widget(preview, area);
+    }
+
+    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let line = match &self.status_message {
+            Some((message, _)) => Line::from(message.clone()),
+            None => Line::from(vec![
+                Span::styled("Tab", Style::default().fg(self.theme.accent)),
+                Span::raw(" next field  "),
+                Span::styled("Ctrl+S", Style::default().fg(self.theme.success)),
+                Span::raw(" send  "),
+                Span::styled("Ctrl+T", Style::default().fg(self.theme.warning)),
+                Span::raw(" test email  "),
+                Span::styled("Ctrl+P", Style::default().fg(self.theme.accent)),
+                Span::raw(" toggle preview  "),
+                Span::styled("F1", Style::default().fg(self.theme.accent)),
+                Span::raw(" help  "),
+                Span::styled("Esc", Style::default().fg(self.theme.danger)),
+                Span::raw(" quit"),
+            ]),
+        };
+
+        let status = Paragraph::new(line)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Status")
+                    .border_style(Style::default().fg(self.theme.border))
+                    .title_style(Style::default().fg(self.theme.secondary)),
+            )
+            .style(Style::default().fg(self.theme.text));
+        frame.render_widget(status, area);
+    }
+
+    fn render_help(&self, frame: &mut Frame, area: Rect) {
+        let key = |key: &'static str, description: &'static str| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<14}", key),
+                    Style::default()
+                        .fg(self.theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(description),
+            ])
+        };
+
+        let help = Paragraph::new(vec![
+            Line::from(""),
+            key("Tab/Shift+Tab", "Move between fields"),
+            key("Space", "Toggle the highlighted segment"),
+            key("Ctrl+P", "Switch between plain text and HTML preview"),
+            key("Ctrl+↑/↓", "Scroll the preview"),
+            key("Ctrl+T", "Send a test email"),
+            key("Ctrl+S", "Send now, or schedule if a time is set"),
+            key("Esc/Ctrl+Q", "Quit without sending"),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press Esc or F1 to close",
+                Style::default().fg(self.theme.text_secondary),
+            )),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Help")
+                .title_alignment(Alignment::Center)
+                .border_style(Style::default().fg(self.theme.primary)),
+        )
+        .style(Style::default().fg(self.theme.text));
+
+        let popup_area = centered_rect(60, 50, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(help, popup_area);
+    }
+
+    fn render_test_email(&self, frame: &mut Frame, area: Rect) {
+        let input = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(
+                    self.test_email.clone(),
+                    Style::default().fg(self.theme.text),
+                ),
+                Span::styled("█", Style::default().fg(self.theme.primary)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Enter to send, Esc to cancel",
+                Style::default().fg(self.theme.text_secondary),
+            )),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Send test email to")
+                .title_alignment(Alignment::Center)
+                .border_style(Style::default().fg(self.theme.warning)),
+        )
+        .alignment(Alignment::Center);
+
+        let popup_area = centered_rect(50, 25, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(input, popup_area);
+    }
+
+    fn render_confirm_send(&self, frame: &mut Frame, area: Rect) {
+        let draft = self.draft();
+        let action = match &draft.schedule {
+            Some(at) => format!("Schedule for {}", at),
+            None => "Send now".to_string(),
+        };
+        let audience = if draft.segments.is_empty() {
+            format!("{} approved subscriber(s)", self.recipient_count())
+        } else {
+            format!(
+                "{} subscriber(s) in {}",
+                self.recipient_count(),
+                draft.segments.join(" + ")
+            )
+        };
+
+        self.render_confirm(
+            frame,
+            area,
+            vec![
+                Line::from(Span::styled(
+                    draft.subject,
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(format!("{} to {}?", action, audience)),
+            ],
+        );
+    }
+
+    fn render_confirm_quit(&self, frame: &mut Frame, area: Rect) {
+        self.render_confirm(
+            frame,
+            area,
+            vec![Line::from("Quit and discard this newsletter?")],
+        );
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect, message: Vec<Line<'static>>) {
+        let mut lines = vec![Line::from("")];
+        lines.extend(message);
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("Press ", Style::default().fg(self.theme.text_secondary)),
+            Span::styled(
+                "Y",
+                Style::default()
+                    .fg(self.theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " to confirm, ",
+                Style::default().fg(self.theme.text_secondary),
+            ),
+            Span::styled(
+                "N",
+                Style::default()
+                    .fg(self.theme.danger)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" to cancel", Style::default().fg(self.theme.text_secondary)),
+        ]));
+
+        let confirm = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Confirmation Required")
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(self.theme.warning)),
+            )
+            .style(Style::default().fg(self.theme.text))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        let popup_area = centered_rect(60, 30, area);
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(confirm, popup_area);
+    }
+}
+
+/// Apply a key press to a single-line text field; returns true if it changed
+fn edit_line(value: &mut String, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            value.clear();
+            true
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            value.push(c);
+            true
+        }
+        KeyCode::Backspace => value.pop().is_some(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn subscriber(email: &str, tags: &[&str]) -> Subscriber {
+        let mut subscriber = Subscriber::new(email.to_string(), None);
+        subscriber.status = SubscriberStatus::Approved;
+        subscriber.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        subscriber
+    }
+
+    fn app() -> ComposeApp {
+        let theme = blogr_themes::get_theme("minimal-retro").unwrap();
+        let composer = NewsletterComposer::new(theme, Config::default()).unwrap();
+        let mut pending = subscriber("pending@example.com", &["go"]);
+        pending.status = SubscriberStatus::Pending;
+        ComposeApp::new(
+            composer,
+            vec![
+                subscriber("ann@example.com", &["rust", "news"]),
+                subscriber("bob@example.com", &["rust"]),
+                pending,
+            ],
+        )
+    }
+
+    fn press(app: &mut ComposeApp, code: KeyCode) -> ComposeResult {
+        app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+            .unwrap()
+    }
+
+    fn ctrl(app: &mut ComposeApp, c: char) -> ComposeResult {
+        app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+            .unwrap()
+    }
+
+    fn type_text(app: &mut ComposeApp, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_field_cycling() {
+        let mut app = app();
+        assert_eq!(app.focus, ComposeField::Subject);
+        for expected in [
+            ComposeField::Body,
+            ComposeField::Segments,
+            ComposeField::Schedule,
+            ComposeField::Subject,
+        ] {
+            press(&mut app, KeyCode::Tab);
+            assert_eq!(app.focus, expected);
+        }
+        press(&mut app, KeyCode::BackTab);
+        assert_eq!(app.focus, ComposeField::Schedule);
+    }
+
+    #[test]
+    fn test_tag_selection() {
+        let mut app = app();
+        // Tags of approved subscribers only, sorted
+        assert_eq!(
+            app.tags,
+            [("news".to_string(), false), ("rust".to_string(), false)]
+        );
+
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.draft().segments, ["tag:rust"]);
+        assert_eq!(app.recipient_count(), 2);
+
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.draft().segments, ["tag:news", "tag:rust"]);
+        assert_eq!(app.recipient_count(), 1);
+
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.draft().segments, ["tag:rust"]);
+    }
+
+    #[test]
+    fn test_send() {
+        let mut app = app();
+        // Nothing to send yet
+        assert_eq!(ctrl(&mut app, 's'), ComposeResult::Continue);
+        assert_eq!(app.mode, ComposeMode::Edit);
+
+        type_text(&mut app, "Hello");
+        press(&mut app, KeyCode::Tab);
+        type_text(&mut app, "Some news");
+        let draft = app.draft();
+        assert_eq!(draft.subject, "Hello");
+        assert_eq!(draft.content, "Some news");
+        assert_eq!(draft.schedule, None);
+
+        assert_eq!(ctrl(&mut app, 's'), ComposeResult::Continue);
+        assert_eq!(app.mode, ComposeMode::ConfirmSend);
+        assert_eq!(press(&mut app, KeyCode::Char('n')), ComposeResult::Continue);
+        assert_eq!(app.mode, ComposeMode::Edit);
+
+        ctrl(&mut app, 's');
+        assert_eq!(press(&mut app, KeyCode::Enter), ComposeResult::Submit);
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_test_email() {
+        let mut app = app();
+        ctrl(&mut app, 't');
+        assert_eq!(app.mode, ComposeMode::TestEmail);
+
+        type_text(&mut app, "me");
+        assert_eq!(press(&mut app, KeyCode::Enter), ComposeResult::Continue);
+        assert_eq!(app.mode, ComposeMode::TestEmail);
+
+        type_text(&mut app, "@example.com");
+        assert_eq!(
+            press(&mut app, KeyCode::Enter),
+            ComposeResult::TestSend("me@example.com".to_string())
+        );
+        assert_eq!(app.mode, ComposeMode::Edit);
+        // Typing in the popup didn't touch the draft
+        assert_eq!(app.draft().subject, "");
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut app = app();
+        type_text(&mut app, "Unsaved");
+        assert_eq!(press(&mut app, KeyCode::Esc), ComposeResult::Continue);
+        assert_eq!(app.mode, ComposeMode::ConfirmQuit);
+        assert_eq!(press(&mut app, KeyCode::Char('y')), ComposeResult::Quit);
+        assert!(!app.running);
+
+        // An empty draft quits without asking
+        let mut app = self::app();
+        assert_eq!(press(&mut app, KeyCode::Esc), ComposeResult::Quit);
+    }
+}
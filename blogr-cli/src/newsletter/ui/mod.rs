@@ -1,3 +1,5 @@
 pub mod approval;
+pub mod compose;
 
 pub use approval::{ApprovalResult, ModernApprovalApp};
+pub use compose::{ComposeApp, ComposeResult};
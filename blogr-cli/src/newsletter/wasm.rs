@@ -0,0 +1,449 @@
+//! WebAssembly plugins loaded from the project's `plugins/` directory
+//!
+//! A plugin is a `.wasm` module that talks to blogr through JSON passed in its
+//! own linear memory. It must export:
+//!
+//! - `memory`
+//! - `blogr_api_version() -> i32`, returning [`HOST_API_VERSION`]
+//! - `blogr_alloc(len: i32) -> i32`, returning a buffer for host input
+//!
+//! and any of the hook functions `blogr_pre_build`, `blogr_post_build`,
+//! `blogr_post_deploy`, `blogr_pre_send` and `blogr_subscriber_event`, each
+//! `(ptr: i32, len: i32) -> i64`. The input is a
+//! JSON object `{"hook", "config", "data"}`; the return value packs the output
+//! location as `ptr << 32 | len`, or is 0 for no output. Output is JSON with
+//! optional `message`, `error` and `data` fields.
+//!
+//! An optional `blogr_metadata() -> i64` export returns JSON metadata in the same
+//! packed form, including an optional `config_schema` for the plugin's settings. Plugins may import `blogr.log(ptr: i32, len: i32)` to print a line.
+//! They get no filesystem or network access, and each call runs with a fuel and
+//! memory budget so a broken plugin can't hang or exhaust the host.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use wasmi::{
+    Caller, Config as WasmConfig, Engine, Extern, Instance, Linker, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+use super::plugin::{
+    NewsletterPlugin, PluginConfig, PluginContext, PluginHook, PluginMetadata, PluginResult,
+};
+use super::Newsletter;
+
+/// Version of the host API described above. Bumped on incompatible changes.
+pub const HOST_API_VERSION: i32 = 1;
+
+/// Instructions a single plugin call may execute
+const FUEL_PER_CALL: u64 = 500_000_000;
+/// Largest linear memory a plugin may grow to
+const MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+/// Largest JSON document a plugin may return
+const MAX_OUTPUT: usize = 16 * 1024 * 1024;
+
+struct HostState {
+    plugin: String,
+    limits: StoreLimits,
+}
+
+/// Optional metadata exported by a plugin
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct WasmMetadata {
+    name: Option<String>,
+    version: Option<String>,
+    author: Option<String>,
+    description: Option<String>,
+    homepage: Option<String>,
+    license: Option<String>,
+    config_schema: Option<serde_json::Value>,
+}
+
+/// What a hook returns
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct HookOutput {
+    message: Option<String>,
+    error: Option<String>,
+    data: HashMap<String, serde_json::Value>,
+}
+
+/// A plugin compiled from a `.wasm` file
+pub struct WasmPlugin {
+    metadata: PluginMetadata,
+    hooks: Vec<PluginHook>,
+    engine: Engine,
+    module: Module,
+    config: HashMap<String, serde_json::Value>,
+    config_schema: Option<serde_json::Value>,
+    /// Fuel given to each call
+    fuel: u64,
+}
+
+impl WasmPlugin {
+    /// Load a plugin; its name defaults to the file name without `.wasm`
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("Invalid plugin file name: {}", path.display()))?;
+
+        Self::from_bytes(name, &bytes)
+    }
+
+    /// Compile a plugin and check that it speaks this host's API version
+    pub fn from_bytes(name: &str, bytes: &[u8]) -> Result<Self> {
+        let mut wasm_config = WasmConfig::default();
+        wasm_config.consume_fuel(true);
+        let engine = Engine::new(&wasm_config);
+        let module = Module::new(&engine, bytes)
+            .map_err(|e| anyhow!("Invalid WebAssembly module: {}", e))?;
+
+        let hooks = [
+            PluginHook::PreBuild,
+            PluginHook::PostBuild,
+            PluginHook::PostDeploy,
+            PluginHook::PreSend,
+            PluginHook::SubscriberEvent,
+        ]
+        .into_iter()
+        .filter(|hook| {
+            hook.wasm_export()
+                .is_some_and(|export| module.get_export(export).is_some())
+        })
+        .collect();
+
+        let mut plugin = Self {
+            metadata: PluginMetadata {
+                name: name.to_string(),
+                version: "0.0.0".to_string(),
+                author: "Unknown".to_string(),
+                description: String::new(),
+                homepage: None,
+                repository: None,
+                license: None,
+                keywords: vec!["wasm".to_string()],
+                dependencies: Vec::new(),
+                min_blogr_version: None,
+            },
+            hooks,
+            engine,
+            module,
+            config: HashMap::new(),
+            config_schema: None,
+            fuel: FUEL_PER_CALL,
+        };
+
+        let (mut store, instance) = plugin.instantiate()?;
+        let version = instance
+            .get_typed_func::<(), i32>(&store, "blogr_api_version")
+            .map_err(|_| anyhow!("Plugin does not export blogr_api_version"))?
+            .call(&mut store, ())
+            .map_err(|e| anyhow!("blogr_api_version failed: {}", e))?;
+        if version != HOST_API_VERSION {
+            bail!(
+                "Plugin targets host API version {}, but this blogr provides version {}",
+                version,
+                HOST_API_VERSION
+            );
+        }
+
+        if instance.get_export(&store, "blogr_metadata").is_some() {
+            let packed = instance
+                .get_typed_func::<(), i64>(&store, "blogr_metadata")
+                .map_err(|e| anyhow!("blogr_metadata has the wrong signature: {}", e))?
+                .call(&mut store, ())
+                .map_err(|e| anyhow!("blogr_metadata failed: {}", e))?;
+            if let Some(output) = read_output(&store, &instance, packed)? {
+                let metadata: WasmMetadata =
+                    serde_json::from_slice(&output).context("Invalid plugin metadata")?;
+                plugin.apply_metadata(metadata);
+            }
+        }
+
+        Ok(plugin)
+    }
+
+    fn apply_metadata(&mut self, metadata: WasmMetadata) {
+        let target = &mut self.metadata;
+        if let Some(name) = metadata.name {
+            target.name = name;
+        }
+        if let Some(version) = metadata.version {
+            target.version = version;
+        }
+        if let Some(author) = metadata.author {
+            target.author = author;
+        }
+        if let Some(description) = metadata.description {
+            target.description = description;
+        }
+        target.homepage = metadata.homepage;
+        target.license = metadata.license;
+        self.config_schema = metadata.config_schema;
+    }
+
+    /// Fresh instance with its own store, so calls can't leak state into each other
+    fn instantiate(&self) -> Result<(Store<HostState>, Instance)> {
+        let state = HostState {
+            plugin: self.metadata.name.clone(),
+            limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(self.fuel)
+            .map_err(|e| anyhow!("Failed to set plugin fuel: {}", e))?;
+
+        let mut linker = Linker::new(&self.engine);
+        linker
+            .func_wrap(
+                "blogr",
+                "log",
+                |caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                    let memory = caller.get_export("memory").and_then(Extern::into_memory);
+                    if let Some(memory) = memory {
+                        let data = memory.data(&caller);
+                        let start = ptr as u32 as usize;
+                        if let Some(bytes) = data.get(start..start + len as u32 as usize) {
+                            println!(
+                                "[{}] {}",
+                                caller.data().plugin,
+                                String::from_utf8_lossy(bytes)
+                            );
+                        }
+                    }
+                },
+            )
+            .map_err(|e| anyhow!("Failed to define host functions: {}", e))?;
+
+        let instance = linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| anyhow!("Failed to instantiate plugin: {}", e))?;
+
+        Ok((store, instance))
+    }
+
+    /// Call a hook export with JSON input, returning its JSON output if any
+    fn call(&self, export: &str, input: &[u8]) -> Result<Option<Vec<u8>>> {
+        let (mut store, instance) = self.instantiate()?;
+
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "blogr_alloc")
+            .map_err(|_| anyhow!("Plugin does not export blogr_alloc"))?;
+        let len = i32::try_from(input.len()).context("Plugin input is too large")?;
+        let ptr = alloc
+            .call(&mut store, len)
+            .map_err(|e| anyhow!("blogr_alloc failed: {}", e))?;
+
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| anyhow!("Plugin does not export its memory"))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(|_| anyhow!("blogr_alloc returned an invalid buffer"))?;
+
+        let packed = instance
+            .get_typed_func::<(i32, i32), i64>(&store, export)
+            .map_err(|e| anyhow!("{} has the wrong signature: {}", export, e))?
+            .call(&mut store, (ptr, len))
+            .map_err(|e| anyhow!("{} failed: {}", export, e))?;
+
+        read_output(&store, &instance, packed)
+    }
+}
+
+/// Copy out the JSON a plugin returned as `ptr << 32 | len`
+fn read_output(
+    store: &Store<HostState>,
+    instance: &Instance,
+    packed: i64,
+) -> Result<Option<Vec<u8>>> {
+    if packed == 0 {
+        return Ok(None);
+    }
+
+    let ptr = (packed as u64 >> 32) as usize;
+    let len = (packed as u64 & 0xffff_ffff) as usize;
+    if len > MAX_OUTPUT {
+        bail!("Plugin output is larger than {} bytes", MAX_OUTPUT);
+    }
+
+    let memory = instance
+        .get_memory(store, "memory")
+        .ok_or_else(|| anyhow!("Plugin does not export its memory"))?;
+    memory
+        .data(store)
+        .get(ptr..ptr + len)
+        .map(|bytes| Some(bytes.to_vec()))
+        .ok_or_else(|| anyhow!("Plugin returned output outside its memory"))
+}
+
+impl NewsletterPlugin for WasmPlugin {
+    fn metadata(&self) -> &PluginMetadata {
+        &self.metadata
+    }
+
+    fn initialize(&mut self, config: &PluginConfig) -> Result<()> {
+        self.config = config.config.clone();
+        Ok(())
+    }
+
+    fn handles_hook(&self, hook: &PluginHook) -> bool {
+        self.hooks.contains(hook)
+    }
+
+    fn config_schema(&self) -> Option<serde_json::Value> {
+        self.config_schema.clone()
+    }
+
+    fn execute_hook(&self, context: &PluginContext) -> Result<PluginResult> {
+        let export = context
+            .hook
+            .wasm_export()
+            .ok_or_else(|| anyhow!("Hook {:?} is not available to WASM plugins", context.hook))?;
+
+        let input = serde_json::to_vec(&serde_json::json!({
+            "hook": context.hook.name(),
+            "config": self.config,
+            "data": context.data,
+        }))?;
+
+        let output = match self.call(export, &input)? {
+            Some(bytes) => serde_json::from_slice::<HookOutput>(&bytes)
+                .with_context(|| format!("Invalid JSON returned from {}", export))?,
+            None => HookOutput::default(),
+        };
+
+        // Pre-send plugins may rewrite any part of the newsletter
+        let modified_newsletter = if context.hook == PluginHook::PreSend {
+            let field = |name: &str| {
+                output
+                    .data
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            let subject = field("subject");
+            let html = field("html_content");
+            let text = field("text_content");
+            (subject.is_some() || html.is_some() || text.is_some()).then(|| {
+                let string = |name: &str| {
+                    context
+                        .data
+                        .get(name)
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                Newsletter::new(
+                    subject.unwrap_or_else(|| string("subject")),
+                    html.unwrap_or_else(|| string("html_content")),
+                    text.unwrap_or_else(|| string("text_content")),
+                )
+            })
+        } else {
+            None
+        };
+
+        Ok(PluginResult {
+            success: output.error.is_none(),
+            message: output.error.or(output.message),
+            data: output.data,
+            modified_newsletter,
+            modified_subscribers: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Echoes a fixed pre-send result and logs through the host
+    const PLUGIN: &str = r#"
+        (module
+          (import "blogr" "log" (func $log (param i32 i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{\"name\":\"shout\",\"version\":\"1.2.0\"}")
+          (data (i32.const 64) "{\"message\":\"done\",\"data\":{\"subject\":\"HELLO\"}}")
+          (data (i32.const 128) "called")
+          (func (export "blogr_api_version") (result i32) i32.const 1)
+          (func (export "blogr_alloc") (param i32) (result i32) i32.const 1024)
+          (func (export "blogr_metadata") (result i64) i64.const 34)
+          (func (export "blogr_pre_send") (param i32 i32) (result i64)
+            (call $log (i32.const 128) (i32.const 6))
+            i64.const 274877906989))
+    "#;
+
+    fn context(hook: PluginHook) -> PluginContext {
+        let mut data = HashMap::new();
+        data.insert("subject".to_string(), serde_json::json!("hello"));
+        data.insert("html_content".to_string(), serde_json::json!("<p>hi</p>"));
+        data.insert("text_content".to_string(), serde_json::json!("hi"));
+
+        PluginContext {
+            config: std::sync::Arc::new(crate::config::Config::default()),
+            database: None,
+            project_root: PathBuf::from("/tmp"),
+            hook,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_load_and_run() {
+        let plugin = WasmPlugin::from_bytes("file-name", &wat::parse_str(PLUGIN).unwrap()).unwrap();
+        assert_eq!(plugin.metadata().name, "shout");
+        assert_eq!(plugin.metadata().version, "1.2.0");
+        assert!(plugin.handles_hook(&PluginHook::PreSend));
+        assert!(!plugin.handles_hook(&PluginHook::PostBuild));
+
+        let result = plugin.execute_hook(&context(PluginHook::PreSend)).unwrap();
+        assert!(result.success);
+        assert_eq!(result.message.as_deref(), Some("done"));
+
+        let newsletter = result.modified_newsletter.unwrap();
+        assert_eq!(newsletter.subject, "HELLO");
+        assert_eq!(newsletter.text_content, "hi");
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        let module = wat::parse_str(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "blogr_api_version") (result i32) i32.const 99))"#,
+        )
+        .unwrap();
+
+        let error = WasmPlugin::from_bytes("old", &module).err().unwrap();
+        assert!(error.to_string().contains("API version 99"));
+    }
+
+    #[test]
+    fn test_runaway_plugin_is_stopped() {
+        let module = wat::parse_str(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "blogr_api_version") (result i32) i32.const 1)
+                 (func (export "blogr_alloc") (param i32) (result i32) i32.const 0)
+                 (func (export "blogr_post_build") (param i32 i32) (result i64)
+                   (loop $forever (br $forever))
+                   i64.const 0))"#,
+        )
+        .unwrap();
+
+        let mut plugin = WasmPlugin::from_bytes("loop", &module).unwrap();
+        plugin.fuel = 100_000;
+        assert!(plugin
+            .execute_hook(&context(PluginHook::PostBuild))
+            .is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Outbound webhooks for subscriber changes made through the API server
+//!
+//! Each event is POSTed as JSON to every configured endpoint that wants it. When a
+//! secret is set, the body is signed with HMAC-SHA256 and the hex digest sent as
+//! `X-Blogr-Signature: sha256=<digest>`, so receivers can verify the sender.
+
+use ring::hmac;
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+use super::Subscriber;
+use crate::config::WebhookConfig;
+use crate::utils::Utils;
+
+/// Attempts per delivery before giving up
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Subscriber change that triggers a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    Created,
+    Approved,
+    Removed,
+}
+
+impl WebhookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::Created => "subscriber.created",
+            WebhookEvent::Approved => "subscriber.approved",
+            WebhookEvent::Removed => "subscriber.removed",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// JSON body of a webhook delivery
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    /// Unique per delivery, so receivers can drop duplicates from retries
+    pub id: String,
+    pub event: &'static str,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub data: &'a Subscriber,
+}
+
+/// Sends webhook events in the background
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    hooks: Vec<WebhookConfig>,
+    client: reqwest::Client,
+}
+
+impl WebhookDispatcher {
+    pub fn new(hooks: Vec<WebhookConfig>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { hooks, client }
+    }
+
+    /// Queue an event for every endpoint subscribed to it. Delivery happens in a
+    /// background task and failures are logged, so API responses are not delayed.
+    pub fn dispatch(&self, event: WebhookEvent, subscriber: &Subscriber) {
+        let hooks: Vec<WebhookConfig> = self
+            .hooks
+            .iter()
+            .filter(|hook| {
+                hook.events.is_empty() || hook.events.iter().any(|e| e == event.as_str())
+            })
+            .cloned()
+            .collect();
+        if hooks.is_empty() {
+            return;
+        }
+
+        let payload = WebhookPayload {
+            id: uuid::Uuid::new_v4().to_string(),
+            event: event.as_str(),
+            timestamp: chrono::Utc::now(),
+            data: subscriber,
+        };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("Failed to serialize {} webhook: {}", event, e);
+                return;
+            }
+        };
+
+        for hook in hooks {
+            let client = self.client.clone();
+            let body = body.clone();
+            tokio::spawn(async move {
+                if let Err(e) = deliver(&client, &hook, event, body).await {
+                    tracing::error!("Webhook {} to {} failed: {}", event, hook.url, e);
+                }
+            });
+        }
+    }
+}
+
+/// POST one event, retrying with backoff on network errors and 5xx responses
+async fn deliver(
+    client: &reqwest::Client,
+    hook: &WebhookConfig,
+    event: WebhookEvent,
+    body: Vec<u8>,
+) -> anyhow::Result<()> {
+    let mut delay = Duration::from_secs(1);
+    let mut attempt = 1;
+
+    loop {
+        let mut request = client
+            .post(&hook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Blogr-Event", event.as_str())
+            .body(body.clone());
+        if let Some(secret) = hook.secret.as_deref().filter(|s| !s.is_empty()) {
+            request = request.header("X-Blogr-Signature", signature(secret, &body));
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) if !response.status().is_server_error() => {
+                return Err(anyhow::anyhow!("endpoint returned {}", response.status()));
+            }
+            Ok(response) => anyhow::anyhow!("endpoint returned {}", response.status()),
+            Err(e) => e.into(),
+        };
+
+        if attempt >= MAX_ATTEMPTS {
+            return Err(error);
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// `X-Blogr-Signature` header value for a body
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    format!("sha256={}", Utils::hex(hmac::sign(&key, body).as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_long_key() {
+        // Secrets longer than the 64-byte block are hashed first
+        assert_eq!(
+            signature(&"k".repeat(100), br#"{"event":"subscriber.created"}"#),
+            "sha256=7609de555708e5538984167b2dc2950ac85b9f1827feff7fd69287d177c0ddac"
+        );
+    }
+}
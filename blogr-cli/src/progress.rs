@@ -0,0 +1,72 @@
+//! Progress bars for long operations
+//!
+//! Bars draw on stderr, and only when it is a terminal, so CI logs and pipes
+//! stay clean; `--quiet` and `--json` hide them as well. Messages logged while
+//! a bar is showing are printed above it (see `logging`).
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// Operations with fewer steps than this finish too quickly to need a bar
+const MIN_STEPS: u64 = 3;
+
+static BARS: OnceLock<MultiProgress> = OnceLock::new();
+
+fn bars() -> &'static MultiProgress {
+    BARS.get_or_init(|| MultiProgress::with_draw_target(ProgressDrawTarget::stderr()))
+}
+
+/// Show progress bars, unless `enabled` is false
+pub fn init(enabled: bool) {
+    if !enabled {
+        bars().set_draw_target(ProgressDrawTarget::hidden());
+    }
+}
+
+/// Run `f` with the bars cleared, so it can print to the terminal
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    match BARS.get() {
+        Some(bars) => bars.suspend(f),
+        None => f(),
+    }
+}
+
+/// A bar counting `len` steps, cleared when dropped
+pub struct Progress {
+    bar: ProgressBar,
+}
+
+impl Progress {
+    pub fn new(len: u64, message: impl Into<Cow<'static, str>>) -> Self {
+        let bar = if len < MIN_STEPS {
+            ProgressBar::hidden()
+        } else {
+            bars().add(ProgressBar::new(len))
+        };
+        bar.set_style(
+            ProgressStyle::with_template(
+                "{spinner} {msg} [{bar:30}] {pos}/{len} ({elapsed}, {eta} left)",
+            )
+            .expect("valid progress template")
+            .progress_chars("=> "),
+        );
+        bar.set_message(message);
+        Self { bar }
+    }
+
+    pub fn inc(&self) {
+        self.bar.inc(1);
+    }
+
+    pub fn set_position(&self, position: u64) {
+        self.bar.set_position(position);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+        bars().remove(&self.bar);
+    }
+}
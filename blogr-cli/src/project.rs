@@ -1,8 +1,17 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::config::Config;
+use crate::content::{Post, PostStatus};
+use crate::starter::StarterKit;
+use crate::theme_package;
+use crate::workspace::Workspace;
+use blogr_themes::{SiteType, Theme};
 
 /// Project structure and utilities
 #[derive(Debug, Clone)]
@@ -62,7 +71,8 @@ impl Project {
             description,
             github_username,
             github_repo,
-            false,
+            SiteType::Blog,
+            None,
         )
     }
 
@@ -82,11 +92,60 @@ impl Project {
             description,
             github_username,
             github_repo,
-            true,
+            SiteType::Personal,
+            None,
+        )
+    }
+
+    /// Initialize a new hybrid site, a personal landing page with a blog, in the given directory
+    pub fn init_hybrid<P: AsRef<Path>>(
+        path: P,
+        name: String,
+        author: String,
+        description: String,
+        github_username: Option<String>,
+        github_repo: Option<String>,
+    ) -> Result<Self> {
+        Self::init_with_type(
+            path,
+            name,
+            author,
+            description,
+            github_username,
+            github_repo,
+            SiteType::Hybrid,
+            None,
+        )
+    }
+
+    /// Initialize a new project from a starter kit
+    pub fn init_from_starter<P: AsRef<Path>>(
+        path: P,
+        name: String,
+        author: String,
+        description: String,
+        github_username: Option<String>,
+        github_repo: Option<String>,
+        starter: StarterKit,
+    ) -> Result<Self> {
+        Self::init_with_type(
+            path,
+            name,
+            author,
+            description,
+            github_username,
+            github_repo,
+            if starter.is_personal() {
+                SiteType::Personal
+            } else {
+                SiteType::Blog
+            },
+            Some(starter),
         )
     }
 
     /// Internal initialization function
+    #[allow(clippy::too_many_arguments)]
     fn init_with_type<P: AsRef<Path>>(
         path: P,
         name: String,
@@ -94,9 +153,36 @@ impl Project {
         description: String,
         github_username: Option<String>,
         github_repo: Option<String>,
-        is_personal: bool,
+        site_type: SiteType,
+        starter: Option<StarterKit>,
     ) -> Result<Self> {
-        let project_path = path.as_ref().to_path_buf().join(&name);
+        let mut config = match site_type {
+            SiteType::Personal => {
+                Config::new_personal(name, author, description, github_username, github_repo)
+            }
+            SiteType::Hybrid => {
+                Config::new_hybrid(name, author, description, github_username, github_repo)
+            }
+            SiteType::Blog => {
+                Config::new_with_defaults(name, author, description, github_username, github_repo)
+            }
+        };
+        if let Some(starter) = starter {
+            starter.configure(&mut config);
+        }
+
+        Self::create(path.as_ref(), config, starter)
+    }
+
+    /// Initialize a new project from a configuration prepared by the caller,
+    /// such as the init wizard. The project directory is named after the title.
+    pub fn init_with_config<P: AsRef<Path>>(path: P, config: Config) -> Result<Self> {
+        Self::create(path.as_ref(), config, None)
+    }
+
+    fn create(path: &Path, config: Config, starter: Option<StarterKit>) -> Result<Self> {
+        let project_path = path.join(&config.blog.title);
+        let is_personal = config.site.site_type == "personal";
 
         // Create project directory if it doesn't exist
         if !project_path.exists() {
@@ -118,19 +204,12 @@ impl Project {
             anyhow::bail!("Directory is not empty. Please choose an empty directory or remove existing files.");
         }
 
-        // Create project structure
-        Self::create_directory_structure(&project_path, is_personal)?;
-
-        // Create configuration
-        let config = if is_personal {
-            Config::new_personal(name, author, description, github_username, github_repo)
-        } else {
-            Config::new_with_defaults(name, author, description, github_username, github_repo)
-        };
-
         // Validate configuration
         config.validate()?;
 
+        // Create project structure
+        Self::create_directory_structure(&project_path, is_personal)?;
+
         // Save configuration
         let config_path = project_path.join("blogr.toml");
         config.save_to_file(&config_path)?;
@@ -139,7 +218,12 @@ impl Project {
         if is_personal {
             Self::create_personal_files(&project_path, &config)?;
         } else {
-            Self::create_sample_files(&project_path, &config)?;
+            Self::create_sample_files(&project_path, &config, starter)?;
+        }
+
+        // Hybrid sites also get a landing page
+        if config.site.site_type == "hybrid" {
+            Self::create_content_md(&project_path, &config)?;
         }
 
         // Create GitHub Actions workflow if GitHub integration is enabled
@@ -178,7 +262,11 @@ impl Project {
     }
 
     /// Create sample files for a new project
-    fn create_sample_files(project_path: &Path, config: &Config) -> Result<()> {
+    fn create_sample_files(
+        project_path: &Path,
+        config: &Config,
+        starter: Option<StarterKit>,
+    ) -> Result<()> {
         // Create .gitignore
         let gitignore_content = include_str!("../templates/gitignore.template");
         fs::write(project_path.join(".gitignore"), gitignore_content)
@@ -193,6 +281,15 @@ impl Project {
         fs::write(project_path.join("README.md"), readme_content)
             .with_context(|| "Failed to create README.md file")?;
 
+        // Starter kits bring their own sample content
+        if let Some(starter) = starter {
+            for (file_name, content) in starter.sample_posts(config) {
+                fs::write(project_path.join("posts").join(file_name), content)
+                    .with_context(|| format!("Failed to create {}", file_name))?;
+            }
+            return Ok(());
+        }
+
         // Create sample post
         let sample_post_template = include_str!("../templates/sample_post.template");
         let sample_post = sample_post_template
@@ -215,26 +312,16 @@ impl Project {
         Ok(())
     }
 
-    /// Create sample files for a personal website
-    fn create_personal_files(project_path: &Path, config: &Config) -> Result<()> {
-        // Create .gitignore
-        let gitignore_content = include_str!("../templates/gitignore.template");
-        fs::write(project_path.join(".gitignore"), gitignore_content)
-            .with_context(|| "Failed to create .gitignore file")?;
-
-        // Create README.md
-        let readme_template = include_str!("../templates/readme.template");
-        let readme_content = readme_template
-            .replace("{title}", &config.blog.title)
-            .replace("{description}", &config.blog.description)
-            .replace("{author}", &config.blog.author);
-        fs::write(project_path.join("README.md"), readme_content)
-            .with_context(|| "Failed to create README.md file")?;
-
-        // Create content.md for personal info - theme-specific
-        let content_md = match config.theme.name.as_str() {
+    /// Sample `content.md` for a personal site, tailored to its theme
+    pub(crate) fn example_personal_content(config: &Config) -> String {
+        match config.theme.name.as_str() {
             "musashi" => blogr_themes::MusashiTheme::example_content(&config.blog.author),
             "dark-minimal" => blogr_themes::DarkMinimalTheme::example_content(&config.blog.author),
+            "slate-portfolio" => {
+                blogr_themes::SlatePortfolioTheme::example_content(&config.blog.author)
+            }
+            "typewriter" => blogr_themes::TypewriterTheme::example_content(&config.blog.author),
+            "handbook" => blogr_themes::HandbookTheme::example_content(&config.blog.author),
             _ => {
                 // Generic fallback for other themes
                 format!(
@@ -263,8 +350,31 @@ Feel free to reach out if you'd like to collaborate or just say hello!
                     config.blog.title, config.blog.author, config.blog.description
                 )
             }
-        };
+        }
+    }
+
+    /// Create sample files for a personal website
+    fn create_personal_files(project_path: &Path, config: &Config) -> Result<()> {
+        // Create .gitignore
+        let gitignore_content = include_str!("../templates/gitignore.template");
+        fs::write(project_path.join(".gitignore"), gitignore_content)
+            .with_context(|| "Failed to create .gitignore file")?;
+
+        // Create README.md
+        let readme_template = include_str!("../templates/readme.template");
+        let readme_content = readme_template
+            .replace("{title}", &config.blog.title)
+            .replace("{description}", &config.blog.description)
+            .replace("{author}", &config.blog.author);
+        fs::write(project_path.join("README.md"), readme_content)
+            .with_context(|| "Failed to create README.md file")?;
+
+        Self::create_content_md(project_path, config)
+    }
 
+    /// Create content.md for personal info - theme-specific
+    fn create_content_md(project_path: &Path, config: &Config) -> Result<()> {
+        let content_md = Self::example_personal_content(config);
         fs::write(project_path.join("content.md"), content_md)
             .with_context(|| "Failed to create content.md file")?;
 
@@ -297,11 +407,30 @@ Feel free to reach out if you'd like to collaborate or just say hello!
     }
 
     /// Get themes directory
-    #[allow(dead_code)]
     pub fn themes_dir(&self) -> PathBuf {
         self.config.themes_dir(&self.root)
     }
 
+    /// Theme packages shared by the projects of this project's workspace
+    pub fn shared_themes_dir(&self) -> Option<PathBuf> {
+        Workspace::find(&self.root)
+            .ok()
+            .flatten()
+            .and_then(|workspace| workspace.themes_dir())
+    }
+
+    /// Look up a theme by name, preferring one installed in `themes/`, then
+    /// one shared by the workspace
+    pub fn load_theme(&self, name: &str) -> Result<Option<Box<dyn Theme>>> {
+        let dirs = std::iter::once(self.themes_dir()).chain(self.shared_themes_dir());
+        for dir in dirs {
+            if let Some(theme) = theme_package::find_installed(&dir, name)? {
+                return Ok(Some(Box::new(theme)));
+            }
+        }
+        Ok(blogr_themes::get_theme(name))
+    }
+
     /// Get output directory
     #[allow(dead_code)]
     pub fn output_dir(&self) -> PathBuf {
@@ -467,6 +596,128 @@ impl ProjectStats {
     }
 }
 
+/// Per-post entry in a content statistics report
+#[derive(Debug, Clone, Serialize)]
+pub struct PostStats {
+    pub slug: String,
+    pub title: String,
+    pub status: PostStatus,
+    pub date: DateTime<Utc>,
+    pub words: usize,
+    pub reading_time: usize,
+}
+
+/// Number of posts using a tag
+#[derive(Debug, Clone, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Longest gap between two consecutive published posts
+#[derive(Debug, Clone, Serialize)]
+pub struct Drought {
+    pub days: i64,
+    pub from: String,
+    pub to: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Content analytics computed from loaded posts
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentStats {
+    pub total_posts: usize,
+    pub published_posts: usize,
+    pub draft_posts: usize,
+    pub total_words: usize,
+    pub average_words: usize,
+    pub total_reading_time: usize,
+    /// Posts sorted newest first
+    pub posts: Vec<PostStats>,
+    /// Published posts per month, keyed by `YYYY-MM`
+    pub posts_per_month: BTreeMap<String, usize>,
+    /// Tag usage sorted by count, most used first
+    pub tags: Vec<TagCount>,
+    pub longest_drought: Option<Drought>,
+}
+
+impl ContentStats {
+    /// Compute statistics for a set of posts
+    pub fn from_posts(posts: &[Post], config: &Config) -> Self {
+        let mut post_stats: Vec<PostStats> = posts
+            .iter()
+            .map(|post| {
+                let reading = post.reading_time(config);
+                PostStats {
+                    slug: post.metadata.slug.clone(),
+                    title: post.metadata.title.clone(),
+                    status: post.metadata.status,
+                    date: post.metadata.date,
+                    words: reading.words,
+                    reading_time: reading.minutes,
+                }
+            })
+            .collect();
+        post_stats.sort_by_key(|post| Reverse(post.date));
+
+        let total_words: usize = post_stats.iter().map(|p| p.words).sum();
+        let published: Vec<&PostStats> =
+            post_stats.iter().filter(|p| p.status.is_public()).collect();
+
+        let mut posts_per_month = BTreeMap::new();
+        for post in &published {
+            *posts_per_month
+                .entry(post.date.format("%Y-%m").to_string())
+                .or_insert(0) += 1;
+        }
+
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        for post in posts {
+            for tag in &post.metadata.tags {
+                *tag_counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        let mut tags: Vec<TagCount> = tag_counts
+            .into_iter()
+            .map(|(tag, count)| TagCount { tag, count })
+            .collect();
+        tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+
+        // `published` is newest first, so each window is (later, earlier)
+        let longest_drought = published
+            .windows(2)
+            .map(|pair| Drought {
+                days: (pair[0].date - pair[1].date).num_days(),
+                from: pair[1].slug.clone(),
+                to: pair[0].slug.clone(),
+                start: pair[1].date,
+                end: pair[0].date,
+            })
+            .max_by_key(|drought| drought.days);
+
+        Self {
+            total_posts: posts.len(),
+            published_posts: published.len(),
+            draft_posts: post_stats
+                .iter()
+                .filter(|p| p.status == PostStatus::Draft)
+                .count(),
+            total_words,
+            average_words: if posts.is_empty() {
+                0
+            } else {
+                total_words / posts.len()
+            },
+            total_reading_time: post_stats.iter().map(|p| p.reading_time).sum(),
+            posts: post_stats,
+            posts_per_month,
+            tags,
+            longest_drought,
+        }
+    }
+}
+
 /// Auto-initialization helper
 #[allow(dead_code)]
 pub struct AutoInit;
@@ -596,6 +847,25 @@ mod tests {
         assert!(project.root.join("README.md").exists());
     }
 
+    #[test]
+    fn test_hybrid_initialization() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = Project::init_hybrid(
+            temp_dir.path(),
+            "Test Site".to_string(),
+            "Test Author".to_string(),
+            "A test site".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(project.config.site.site_type, "hybrid");
+        assert!(project.root.join("content.md").exists());
+        assert!(fs::read_dir(project.posts_dir()).unwrap().count() > 0);
+        assert!(project.validate().unwrap().is_empty());
+    }
+
     #[test]
     fn test_project_validation() {
         let temp_dir = TempDir::new().unwrap();
@@ -612,4 +882,79 @@ mod tests {
         let issues = project.validate().unwrap();
         assert!(issues.is_empty());
     }
+
+    #[test]
+    fn test_content_stats() {
+        use crate::content::PostMetadata;
+        use chrono::TimeZone;
+
+        let post = |slug: &str, date: DateTime<Utc>, tags: &[&str], status: PostStatus| Post {
+            metadata: PostMetadata {
+                title: slug.to_string(),
+                date,
+                author: "Test Author".to_string(),
+                description: String::new(),
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                status,
+                slug: slug.to_string(),
+                featured: false,
+                aliases: Vec::new(),
+                updated_at: None,
+                contributors: Vec::new(),
+                crosspost: Default::default(),
+                video: None,
+                link: None,
+                cover: None,
+                cover_alt: None,
+                noindex: false,
+                private: false,
+                password: None,
+            },
+            content: "word ".repeat(450),
+            file_path: PathBuf::from(format!("{}.md", slug)),
+        };
+
+        let posts = vec![
+            post(
+                "first",
+                Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                &["rust"],
+                PostStatus::Published,
+            ),
+            post(
+                "second",
+                Utc.with_ymd_and_hms(2024, 1, 11, 0, 0, 0).unwrap(),
+                &["rust", "cli"],
+                PostStatus::Published,
+            ),
+            post(
+                "third",
+                Utc.with_ymd_and_hms(2024, 4, 10, 0, 0, 0).unwrap(),
+                &[],
+                PostStatus::Published,
+            ),
+            post(
+                "draft",
+                Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap(),
+                &[],
+                PostStatus::Draft,
+            ),
+        ];
+
+        let stats = ContentStats::from_posts(&posts, &Config::default());
+        assert_eq!(stats.total_posts, 4);
+        assert_eq!(stats.published_posts, 3);
+        assert_eq!(stats.draft_posts, 1);
+        assert_eq!(stats.total_words, 1800);
+        assert_eq!(stats.posts[0].slug, "draft");
+        assert_eq!(stats.posts_per_month.get("2024-01"), Some(&2));
+        assert_eq!(stats.posts_per_month.get("2024-05"), None);
+        assert_eq!(stats.tags[0].tag, "rust");
+        assert_eq!(stats.tags[0].count, 2);
+
+        let drought = stats.longest_drought.unwrap();
+        assert_eq!(drought.days, 90);
+        assert_eq!(drought.from, "second");
+        assert_eq!(drought.to, "third");
+    }
 }
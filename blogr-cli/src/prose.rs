@@ -0,0 +1,622 @@
+//! `blogr check prose`: spelling and style checks for posts
+//!
+//! The style rules are simple heuristics run on the text of each post, with
+//! code, link targets, HTML and shortcodes left out. Spelling is checked by an
+//! installed `aspell` or `hunspell`, with the project dictionary on top.
+//!
+//! Findings on a line can be silenced with `<!-- prose-ignore -->` on that
+//! line, or `<!-- prose-ignore passive-voice spelling -->` for some rules
+//! only. Longer passages go between `<!-- prose-ignore-start -->` and
+//! `<!-- prose-ignore-end -->`.
+
+use crate::config::ProseConfig;
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProseRule {
+    Spelling,
+    PassiveVoice,
+    LongSentence,
+    RepeatedWord,
+}
+
+impl ProseRule {
+    pub const ALL: [ProseRule; 4] = [
+        ProseRule::Spelling,
+        ProseRule::PassiveVoice,
+        ProseRule::LongSentence,
+        ProseRule::RepeatedWord,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProseRule::Spelling => "spelling",
+            ProseRule::PassiveVoice => "passive-voice",
+            ProseRule::LongSentence => "long-sentence",
+            ProseRule::RepeatedWord => "repeated-word",
+        }
+    }
+}
+
+impl fmt::Display for ProseRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ProseRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase().replace('_', "-");
+        ProseRule::ALL
+            .into_iter()
+            .find(|rule| rule.as_str() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = ProseRule::ALL.iter().map(|rule| rule.as_str()).collect();
+                anyhow!(
+                    "Unknown prose rule '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// A problem on one line of a post
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProseFinding {
+    /// Line in the file, counting from 1 and including the frontmatter
+    pub line: usize,
+    pub rule: ProseRule,
+    pub message: String,
+}
+
+/// Forms of "to be" that start a passive construction
+const BE: &[&str] = &["am", "are", "be", "been", "being", "is", "was", "were"];
+
+/// Past participles that don't end in -ed
+const IRREGULAR_PARTICIPLES: &[&str] = &[
+    "beaten",
+    "begun",
+    "bitten",
+    "blown",
+    "bought",
+    "broken",
+    "brought",
+    "built",
+    "caught",
+    "chosen",
+    "done",
+    "drawn",
+    "driven",
+    "eaten",
+    "fallen",
+    "forgotten",
+    "forgiven",
+    "found",
+    "given",
+    "gone",
+    "grown",
+    "held",
+    "hidden",
+    "kept",
+    "known",
+    "laid",
+    "left",
+    "lost",
+    "made",
+    "meant",
+    "paid",
+    "read",
+    "ridden",
+    "run",
+    "said",
+    "seen",
+    "sent",
+    "set",
+    "shown",
+    "shut",
+    "sold",
+    "spent",
+    "spoken",
+    "stolen",
+    "taken",
+    "taught",
+    "thought",
+    "thrown",
+    "told",
+    "torn",
+    "understood",
+    "won",
+    "worn",
+    "written",
+];
+
+/// Stands in for removed text such as code, so words on either side of it
+/// are not taken as repeated
+const GAP: char = '\u{1}';
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    /// Punctuation inside a sentence
+    Pause,
+    /// End of a sentence, paragraph, heading or list item
+    End,
+}
+
+/// Which rules are switched off on each line
+struct Ignored {
+    all: HashSet<usize>,
+    rules: HashMap<usize, Vec<ProseRule>>,
+}
+
+impl Ignored {
+    fn contains(&self, line: usize, rule: ProseRule) -> bool {
+        self.all.contains(&line)
+            || self
+                .rules
+                .get(&line)
+                .is_some_and(|rules| rules.contains(&rule))
+    }
+}
+
+/// The words of a post that the spell checker should see
+pub fn words(raw: &str) -> BTreeSet<String> {
+    let (tokens, _) = tokenize(raw);
+    tokens
+        .into_iter()
+        .filter_map(|(_, token)| match token {
+            Token::Word(word) if is_spellable(&word) => Some(word),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Run the style rules over a post, plus spelling against `misspelled`
+pub fn check(raw: &str, config: &ProseConfig, misspelled: &HashSet<String>) -> Vec<ProseFinding> {
+    let disabled: Vec<ProseRule> = config
+        .disable
+        .iter()
+        .filter_map(|rule| rule.parse().ok())
+        .collect();
+    let (tokens, ignored) = tokenize(raw);
+    let mut findings = Vec::new();
+    let mut report = |line: usize, rule: ProseRule, message: String| {
+        if !disabled.contains(&rule) && !ignored.contains(line, rule) {
+            findings.push(ProseFinding {
+                line,
+                rule,
+                message,
+            });
+        }
+    };
+
+    let word_at = |index: usize| match tokens.get(index) {
+        Some((line, Token::Word(word))) => Some((*line, word.as_str())),
+        _ => None,
+    };
+
+    let mut sentence_start = None;
+    let mut sentence_words = 0;
+    for (index, (line, token)) in tokens.iter().enumerate() {
+        let word = match token {
+            Token::Word(word) => word,
+            Token::Pause => continue,
+            Token::End => {
+                if let Some(start) = sentence_start.take() {
+                    if sentence_words > config.max_sentence_words {
+                        report(
+                            start,
+                            ProseRule::LongSentence,
+                            format!(
+                                "sentence has {} words (more than {})",
+                                sentence_words, config.max_sentence_words
+                            ),
+                        );
+                    }
+                }
+                sentence_words = 0;
+                continue;
+            }
+        };
+        sentence_start.get_or_insert(*line);
+        sentence_words += 1;
+
+        if misspelled.contains(word) {
+            report(
+                *line,
+                ProseRule::Spelling,
+                format!("unknown word \"{}\"", word),
+            );
+        }
+
+        let lower = word.to_lowercase();
+        if index > 0 {
+            if let Some((_, previous)) = word_at(index - 1) {
+                if previous.to_lowercase() == lower && lower.chars().all(char::is_alphabetic) {
+                    report(
+                        *line,
+                        ProseRule::RepeatedWord,
+                        format!("repeated word \"{} {}\"", previous, word),
+                    );
+                }
+            }
+        }
+
+        if BE.contains(&lower.as_str()) {
+            // "was quickly written" as well as "was written"
+            let mut next = index + 1;
+            if word_at(next).is_some_and(|(_, adverb)| adverb.ends_with("ly")) {
+                next += 1;
+            }
+            if let Some((_, participle)) = word_at(next) {
+                if is_participle(participle) {
+                    let phrase: Vec<_> =
+                        (index..=next).filter_map(word_at).map(|(_, w)| w).collect();
+                    report(
+                        *line,
+                        ProseRule::PassiveVoice,
+                        format!("possible passive voice \"{}\"", phrase.join(" ")),
+                    );
+                }
+            }
+        }
+    }
+
+    findings.sort();
+    findings.dedup();
+    findings
+}
+
+/// Words from the project dictionary, lowercased; `#` starts a comment
+pub fn load_dictionary(path: &Path) -> Result<HashSet<String>> {
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect())
+}
+
+/// The spell checker to run and its arguments, or None if none is installed
+pub fn spell_checker(config: &ProseConfig) -> Option<(String, Vec<String>)> {
+    let program = match &config.spell_checker {
+        Some(program) => program.clone(),
+        None => ["aspell", "hunspell"]
+            .into_iter()
+            .find(|program| {
+                Command::new(program)
+                    .arg("--version")
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_ok()
+            })?
+            .to_string(),
+    };
+
+    let name = Path::new(&program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let args = match name.as_str() {
+        "aspell" => vec![
+            "list".to_string(),
+            "--lang".to_string(),
+            config.language.clone(),
+        ],
+        "hunspell" => vec!["-l".to_string(), "-d".to_string(), config.language.clone()],
+        _ => Vec::new(),
+    };
+    Some((program, args))
+}
+
+/// The words the spell checker doesn't know; it gets one word per line and
+/// prints the misspelled ones
+pub fn misspelled_words(
+    program: &str,
+    args: &[String],
+    words: &BTreeSet<String>,
+) -> Result<HashSet<String>> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| {
+            format!(
+                "Failed to run {}. Install aspell or hunspell, or set [prose] spell_checker",
+                program
+            )
+        })?;
+
+    // Written from another thread so a full stdout pipe can't block us
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open the input of {}", program))?;
+    let input: String = words.iter().map(|word| format!("{}\n", word)).collect();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    writer
+        .join()
+        .map_err(|_| anyhow!("Failed to write to {}", program))?
+        .with_context(|| format!("Failed to write to {}", program))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn is_spellable(word: &str) -> bool {
+    word.chars().count() > 1 && word.chars().all(|c| c.is_alphabetic() || c == '\'')
+}
+
+fn is_participle(word: &str) -> bool {
+    let word = word.to_lowercase();
+    (word.len() > 4 && word.ends_with("ed")) || IRREGULAR_PARTICIPLES.contains(&word.as_str())
+}
+
+/// Line of the file where the body starts, after the frontmatter
+fn body_start(raw: &str) -> (usize, &str) {
+    if let Some(rest) = raw.strip_prefix("---\n") {
+        if let Some(end) = rest.find("\n---\n") {
+            let body = &rest[end + 5..];
+            let lines = raw[..raw.len() - body.len()].matches('\n').count();
+            return (lines + 1, body);
+        }
+    }
+    (1, raw)
+}
+
+/// Words and punctuation of a post with their lines, and the ignored lines
+fn tokenize(raw: &str) -> (Vec<(usize, Token)>, Ignored) {
+    let (first_line, body) = body_start(raw);
+    let mut tokens = Vec::new();
+    let mut ignored = Ignored {
+        all: HashSet::new(),
+        rules: HashMap::new(),
+    };
+    let mut fence: Option<&str> = None;
+    let mut ignoring = false;
+
+    for (offset, line) in body.lines().enumerate() {
+        let number = first_line + offset;
+        let trimmed = line.trim_start();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+            tokens.push((number, Token::End));
+            continue;
+        }
+
+        if trimmed.contains("<!-- prose-ignore-start -->") {
+            ignoring = true;
+        }
+        if trimmed.contains("<!-- prose-ignore-end -->") {
+            ignoring = false;
+            continue;
+        }
+        if ignoring {
+            continue;
+        }
+        if let Some(start) = trimmed.find("<!-- prose-ignore") {
+            let comment = &trimmed[start + "<!-- prose-ignore".len()..];
+            let names = comment.split("-->").next().unwrap_or("");
+            let rules: Vec<ProseRule> = names
+                .split_whitespace()
+                .filter_map(|name| name.parse().ok())
+                .collect();
+            if rules.is_empty() {
+                ignored.all.insert(number);
+            } else {
+                ignored.rules.insert(number, rules);
+            }
+        }
+
+        // Blank lines, headings, list items, quotes and tables start a new block
+        let text = trimmed.trim_start_matches(['#', '>', ' ']);
+        let list_marker = text
+            .strip_prefix(['-', '*', '+'])
+            .filter(|rest| rest.starts_with(' '))
+            .or_else(|| {
+                let digits = text.find(|c: char| !c.is_ascii_digit())?;
+                text[digits..]
+                    .strip_prefix(['.', ')'])
+                    .filter(|rest| digits > 0 && rest.starts_with(' '))
+            });
+        let starts_block = text.is_empty()
+            || text.len() != trimmed.len()
+            || list_marker.is_some()
+            || text.starts_with('|');
+        if starts_block {
+            tokens.push((number, Token::End));
+        }
+        if text.starts_with('|') {
+            continue;
+        }
+
+        let text = list_marker.unwrap_or(text);
+        tokenize_line(&strip_markup(text), number, &mut tokens);
+        if trimmed.starts_with('#') {
+            tokens.push((number, Token::End));
+        }
+    }
+    tokens.push((first_line, Token::End));
+
+    (tokens, ignored)
+}
+
+/// Remove inline code, HTML, shortcodes, link targets and URLs from a line
+fn strip_markup(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find(['`', '<', '{', ']', 'h']) {
+        output.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let skip = if after.starts_with('`') {
+            let ticks = after.len() - after.trim_start_matches('`').len();
+            after[ticks..]
+                .find(&after[..ticks])
+                .map(|end| end + ticks * 2)
+        } else if after.starts_with('<') {
+            after.find('>').map(|end| end + 1)
+        } else if after.starts_with("{{") {
+            after.find("}}").map(|end| end + 2)
+        } else if after.starts_with("](") {
+            after.find(')').map(|end| end + 1)
+        } else if after.starts_with("http://") || after.starts_with("https://") {
+            Some(after.find(char::is_whitespace).unwrap_or(after.len()))
+        } else {
+            None
+        };
+
+        match skip {
+            Some(length) => {
+                // A link keeps its text but not its target
+                if after.starts_with(']') {
+                    output.push(' ');
+                } else {
+                    output.push(GAP);
+                }
+                rest = &after[length..];
+            }
+            None => {
+                let c = after.chars().next().unwrap_or_default();
+                output.push(c);
+                rest = &after[c.len_utf8()..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn tokenize_line(text: &str, line: usize, tokens: &mut Vec<(usize, Token)>) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut word = String::new();
+
+    for (index, &c) in chars.iter().enumerate() {
+        let next = chars.get(index + 1).copied();
+        let apostrophe =
+            matches!(c, '\'' | '’') && !word.is_empty() && next.is_some_and(char::is_alphabetic);
+        if c.is_alphanumeric() || apostrophe {
+            word.push(if apostrophe { '\'' } else { c });
+            continue;
+        }
+        if !word.is_empty() {
+            tokens.push((line, Token::Word(std::mem::take(&mut word))));
+        }
+
+        match c {
+            '.' | '!' | '?' if next.is_none_or(char::is_whitespace) => {
+                tokens.push((line, Token::End))
+            }
+            // Emphasis markers and spaces don't separate words
+            '*' | '_' | '~' => {}
+            c if c.is_whitespace() => {}
+            _ => tokens.push((line, Token::Pause)),
+        }
+    }
+    if !word.is_empty() {
+        tokens.push((line, Token::Word(word)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(findings: &[ProseFinding]) -> Vec<(usize, ProseRule)> {
+        findings.iter().map(|f| (f.line, f.rule)).collect()
+    }
+
+    #[test]
+    fn test_prose_checks() {
+        let raw = "---\ntitle: Test\n---\n\
+# The the heading\n\
+\n\
+The report was written by the team and the the results\n\
+were carefully reviewed.\n\
+\n\
+```rust\n\
+let it be = the the;\n\
+```\n\
+Use `the` the way [a link](https://example.com/the) the docs say.\n\
+It is done. <!-- prose-ignore passive-voice -->\n\
+<!-- prose-ignore-start -->\n\
+This was fixed and and shipped.\n\
+<!-- prose-ignore-end -->\n\
+Teh end.\n";
+
+        let mut config = ProseConfig::default();
+        let misspelled: HashSet<String> = ["Teh".to_string()].into();
+        let findings = check(raw, &config, &misspelled);
+        assert_eq!(
+            rules(&findings),
+            [
+                (4, ProseRule::RepeatedWord),
+                (6, ProseRule::PassiveVoice),
+                (6, ProseRule::RepeatedWord),
+                (7, ProseRule::PassiveVoice),
+                (17, ProseRule::Spelling),
+            ]
+        );
+        assert_eq!(
+            findings[3].message,
+            "possible passive voice \"were carefully reviewed\""
+        );
+
+        config.max_sentence_words = 10;
+        config.disable = vec!["repeated-word".to_string()];
+        let findings = check(raw, &config, &HashSet::new());
+        assert_eq!(
+            rules(&findings),
+            [
+                (6, ProseRule::PassiveVoice),
+                (6, ProseRule::LongSentence),
+                (7, ProseRule::PassiveVoice),
+            ]
+        );
+
+        let words = words(raw);
+        assert!(words.contains("Teh") && words.contains("heading"));
+        assert!(!words.contains("let") && !words.contains("example"));
+        assert!("passive_voice".parse::<ProseRule>().is_ok());
+        assert!("grammar".parse::<ProseRule>().is_err());
+    }
+}
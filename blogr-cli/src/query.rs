@@ -0,0 +1,386 @@
+//! Queries for `blogr serve --query-api`
+//!
+//! Tooling posts a JSON document naming the collections it wants, in the
+//! spirit of GraphQL: `posts`, `tags` and `pages`, each with optional `where`
+//! filters, the `fields` to return, a `sort` field (`-` in front for
+//! descending order), `limit` and `offset`. The answer has one list per
+//! collection asked for:
+//!
+//! ```json
+//! { "posts": { "where": { "tag": "rust", "status": "draft" },
+//!              "fields": ["slug", "title", "date"], "limit": 5 },
+//!   "tags": { "sort": "-count" } }
+//! ```
+//!
+//! Every post is visible, drafts included, since this only runs on a local
+//! development server.
+
+use crate::config::Config;
+use crate::content::{Post, PostStatus};
+use crate::generator::pages::Page;
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const POST_FIELDS: &[&str] = &[
+    "slug",
+    "title",
+    "date",
+    "updated",
+    "author",
+    "description",
+    "tags",
+    "status",
+    "featured",
+    "private",
+    "noindex",
+    "aliases",
+    "word_count",
+    "reading_time",
+    "url",
+    "file",
+];
+const TAG_FIELDS: &[&str] = &["name", "count", "posts", "latest", "url"];
+const PAGE_FIELDS: &[&str] = &[
+    "slug",
+    "title",
+    "description",
+    "order",
+    "nav",
+    "noindex",
+    "word_count",
+    "url",
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Query {
+    pub posts: Option<Selection<PostFilter>>,
+    /// Tags of the posts matching `where`
+    pub tags: Option<Selection<PostFilter>>,
+    pub pages: Option<Selection<PageFilter>>,
+}
+
+/// What to return from one collection
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Selection<F> {
+    #[serde(default, rename = "where")]
+    pub filter: F,
+    /// Fields of each item; all of them when empty
+    #[serde(default)]
+    pub fields: Vec<String>,
+    pub sort: Option<String>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostFilter {
+    pub slug: Option<String>,
+    /// A tag, or a list of tags that must all be present
+    pub tag: Option<Tags>,
+    pub status: Option<String>,
+    pub author: Option<String>,
+    /// Posts dated on or after this day
+    pub since: Option<NaiveDate>,
+    /// Posts dated on or before this day
+    pub until: Option<NaiveDate>,
+    pub featured: Option<bool>,
+    pub private: Option<bool>,
+    /// Text in the title, description or body, ignoring case
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Tags {
+    One(String),
+    All(Vec<String>),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PageFilter {
+    pub slug: Option<String>,
+    pub nav: Option<bool>,
+    pub search: Option<String>,
+}
+
+impl PostFilter {
+    fn matches(&self, post: &Post) -> Result<bool> {
+        let metadata = &post.metadata;
+        if let Some(status) = &self.status {
+            if metadata.status != status.parse::<PostStatus>()? {
+                return Ok(false);
+            }
+        }
+        let tags: &[String] = match &self.tag {
+            Some(Tags::One(tag)) => std::slice::from_ref(tag),
+            Some(Tags::All(tags)) => tags,
+            None => &[],
+        };
+        let date = metadata.date.date_naive();
+        Ok(self.slug.as_ref().is_none_or(|slug| *slug == metadata.slug)
+            && tags.iter().all(|tag| metadata.tags.contains(tag))
+            && self
+                .author
+                .as_ref()
+                .is_none_or(|author| author.eq_ignore_ascii_case(&metadata.author))
+            && self.since.is_none_or(|since| date >= since)
+            && self.until.is_none_or(|until| date <= until)
+            && self
+                .featured
+                .is_none_or(|featured| featured == metadata.featured)
+            && self
+                .private
+                .is_none_or(|private| private == metadata.private)
+            && self.search.as_ref().is_none_or(|search| {
+                contains(
+                    &[&metadata.title, &metadata.description, &post.content],
+                    search,
+                )
+            }))
+    }
+}
+
+impl PageFilter {
+    fn matches(&self, page: &Page) -> bool {
+        self.slug.as_ref().is_none_or(|slug| *slug == page.slug)
+            && self.nav.is_none_or(|nav| nav == page.nav)
+            && self.search.as_ref().is_none_or(|search| {
+                let description = page.description.as_deref().unwrap_or_default();
+                contains(&[&page.title, description, &page.content], search)
+            })
+    }
+}
+
+fn contains(texts: &[&str], search: &str) -> bool {
+    let search = search.to_lowercase();
+    texts
+        .iter()
+        .any(|text| text.to_lowercase().contains(&search))
+}
+
+/// Answer `query` from the project's posts and pages
+pub fn run(
+    query: &Query,
+    posts: &[Post],
+    pages: &[Page],
+    config: &Config,
+    root: &Path,
+) -> Result<Value> {
+    let mut data = Map::new();
+    if let Some(selection) = &query.posts {
+        let mut rows = Vec::new();
+        for post in posts {
+            if selection.filter.matches(post)? {
+                rows.push(post_row(post, config, root));
+            }
+        }
+        data.insert(
+            "posts".into(),
+            select(rows, selection, "-date", POST_FIELDS)?,
+        );
+    }
+    if let Some(selection) = &query.tags {
+        let mut tags: BTreeMap<&str, Vec<&Post>> = BTreeMap::new();
+        for post in posts {
+            if selection.filter.matches(post)? {
+                for tag in &post.metadata.tags {
+                    tags.entry(tag).or_default().push(post);
+                }
+            }
+        }
+        let rows = tags
+            .into_iter()
+            .map(|(name, posts)| {
+                let latest = posts.iter().map(|post| post.metadata.date).max();
+                row(json!({
+                    "name": name,
+                    "count": posts.len(),
+                    "posts": posts.iter().map(|post| &post.metadata.slug).collect::<Vec<_>>(),
+                    "latest": latest.map(|date| date.to_rfc3339()),
+                    "url": format!("/{}", config.urls.page_path(&format!("tags/{}.html", name))),
+                }))
+            })
+            .collect();
+        data.insert("tags".into(), select(rows, selection, "name", TAG_FIELDS)?);
+    }
+    if let Some(selection) = &query.pages {
+        let rows = pages
+            .iter()
+            .filter(|page| selection.filter.matches(page))
+            .map(|page| {
+                row(json!({
+                    "slug": page.slug,
+                    "title": page.title,
+                    "description": page.description,
+                    "order": page.order,
+                    "nav": page.nav,
+                    "noindex": page.noindex,
+                    "word_count": page.content.split_whitespace().count(),
+                    "url": format!("/{}", config.urls.page_path(&page.output_path())),
+                }))
+            })
+            .collect();
+        data.insert(
+            "pages".into(),
+            select(rows, selection, "order", PAGE_FIELDS)?,
+        );
+    }
+    Ok(Value::Object(data))
+}
+
+fn post_row(post: &Post, config: &Config, root: &Path) -> Map<String, Value> {
+    let metadata = &post.metadata;
+    let reading = post.reading_time(config);
+    row(json!({
+        "slug": metadata.slug,
+        "title": post.display_title(),
+        "date": metadata.date.to_rfc3339(),
+        "updated": metadata.updated_at.map(|date| date.to_rfc3339()),
+        "author": metadata.author,
+        "description": metadata.description,
+        "tags": metadata.tags,
+        "status": metadata.status,
+        "featured": metadata.featured,
+        "private": metadata.private,
+        "noindex": metadata.noindex,
+        "aliases": metadata.aliases,
+        "word_count": reading.words,
+        "reading_time": reading.minutes,
+        "url": format!("/{}", config.urls.page_path(&format!("posts/{}.html", metadata.slug))),
+        "file": post.file_path.strip_prefix(root).unwrap_or(&post.file_path),
+    }))
+}
+
+fn row(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Sort, page and trim rows as a selection asks
+fn select<F>(
+    mut rows: Vec<Map<String, Value>>,
+    selection: &Selection<F>,
+    default_sort: &str,
+    available: &[&str],
+) -> Result<Value> {
+    let sort = selection.sort.as_deref().unwrap_or(default_sort);
+    let (field, descending) = match sort.strip_prefix('-') {
+        Some(field) => (field, true),
+        None => (sort, false),
+    };
+    for name in selection.fields.iter().map(String::as_str).chain([field]) {
+        if !available.contains(&name) {
+            bail!(
+                "Unknown field '{}' (available: {})",
+                name,
+                available.join(", ")
+            );
+        }
+    }
+
+    rows.sort_by(|a, b| {
+        let order = compare(a.get(field), b.get(field));
+        if descending {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+    let rows = rows
+        .into_iter()
+        .skip(selection.offset)
+        .take(selection.limit.unwrap_or(usize::MAX))
+        .map(|mut row| {
+            if !selection.fields.is_empty() {
+                row.retain(|name, _| selection.fields.contains(name));
+            }
+            Value::Object(row)
+        })
+        .collect();
+    Ok(Value::Array(rows))
+}
+
+/// Order of two field values; missing and null values come first
+fn compare(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        (Some(Value::Bool(a)), Some(Value::Bool(b))) => a.cmp(b),
+        (Some(a), Some(b)) => a.is_null().cmp(&b.is_null()).reverse(),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn post(slug: &str, day: u32, tags: &[&str], status: PostStatus) -> Post {
+        let mut post = Post::new(
+            slug.to_string(),
+            "Ann".to_string(),
+            None,
+            tags.iter().map(|tag| tag.to_string()).collect(),
+            Some(slug.to_string()),
+            status,
+        );
+        post.metadata.date = Utc.with_ymd_and_hms(2025, 3, day, 9, 0, 0).unwrap();
+        post.content = format!("Body of {}", slug);
+        post
+    }
+
+    #[test]
+    fn test_query() {
+        let posts = [
+            post("first", 1, &["rust"], PostStatus::Published),
+            post("second", 2, &["rust", "web"], PostStatus::Draft),
+            post("third", 3, &["web"], PostStatus::Published),
+        ];
+        let query: Query = serde_json::from_value(json!({
+            "posts": {
+                "where": { "tag": "rust", "since": "2025-03-02" },
+                "fields": ["slug", "status"],
+            },
+            "tags": { "where": { "status": "published" }, "sort": "-count", "fields": ["name", "count"] },
+        }))
+        .unwrap();
+
+        let result = run(&query, &posts, &[], &Config::default(), Path::new("/")).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "posts": [{ "slug": "second", "status": "draft" }],
+                "tags": [{ "name": "rust", "count": 1 }, { "name": "web", "count": 1 }],
+            })
+        );
+
+        let query: Query = serde_json::from_value(json!({
+            "posts": { "sort": "title", "offset": 1, "limit": 1, "fields": ["slug"] },
+        }))
+        .unwrap();
+        let result = run(&query, &posts, &[], &Config::default(), Path::new("/")).unwrap();
+        assert_eq!(result, json!({ "posts": [{ "slug": "second" }] }));
+
+        let query: Query =
+            serde_json::from_value(json!({ "posts": { "fields": ["colour"] } })).unwrap();
+        let error = run(&query, &posts, &[], &Config::default(), Path::new("/")).unwrap_err();
+        assert!(error.to_string().starts_with("Unknown field 'colour'"));
+        assert!(serde_json::from_value::<Query>(json!({ "drafts": {} })).is_err());
+    }
+}
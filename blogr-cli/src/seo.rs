@@ -0,0 +1,378 @@
+//! `blogr check seo`: audit the generated site
+//!
+//! Looks at every HTML page in the output directory for the basics search
+//! engines and link previews rely on, and at the links between pages to find
+//! ones nothing points to. Redirect pages and pages marked `noindex` are left
+//! out.
+
+use crate::webmention::{decode_entities, html_tags};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use url::Url;
+
+/// Longest meta description search engines show in full
+pub const MAX_DESCRIPTION_LENGTH: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SeoIssueKind {
+    MissingTitle,
+    DuplicateTitle,
+    MissingDescription,
+    LongDescription,
+    DuplicateDescription,
+    MissingCanonical,
+    MissingAlt,
+    Orphan,
+}
+
+impl SeoIssueKind {
+    /// Checks made on every page, for the score
+    const COUNT: usize = 8;
+}
+
+impl fmt::Display for SeoIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            SeoIssueKind::MissingTitle => "missing title",
+            SeoIssueKind::DuplicateTitle => "duplicate title",
+            SeoIssueKind::MissingDescription => "missing description",
+            SeoIssueKind::LongDescription => "long description",
+            SeoIssueKind::DuplicateDescription => "duplicate description",
+            SeoIssueKind::MissingCanonical => "missing canonical link",
+            SeoIssueKind::MissingAlt => "missing alt text",
+            SeoIssueKind::Orphan => "orphan page",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A problem with one page
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SeoIssue {
+    /// Page path relative to the output directory
+    pub file: String,
+    pub kind: SeoIssueKind,
+    pub message: String,
+}
+
+/// What the audit needs to know about a generated page
+#[derive(Debug, Clone, Default)]
+pub struct SeoPage {
+    /// Path relative to the output directory, with `/` separators
+    pub file: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub canonical: Option<String>,
+    /// `src` of each image without an `alt` attribute
+    pub images_without_alt: Vec<String>,
+    pub links: Vec<String>,
+    /// Redirects and `noindex` pages aren't audited
+    pub skip: bool,
+}
+
+impl SeoPage {
+    pub fn parse(file: &str, html: &str) -> Self {
+        let mut page = SeoPage {
+            file: file.to_string(),
+            title: title(html),
+            ..Default::default()
+        };
+
+        for tag in html_tags(html, &["meta", "link", "a", "img"]) {
+            let attribute = |name: &str| tag.attributes.get(name).map(String::as_str);
+            match tag.name.as_str() {
+                "meta" => {
+                    let name = attribute("name").unwrap_or("").to_ascii_lowercase();
+                    let content = attribute("content").unwrap_or("");
+                    if name == "description" {
+                        page.description = Some(collapse_whitespace(content));
+                    } else if name == "robots" && content.to_ascii_lowercase().contains("noindex") {
+                        page.skip = true;
+                    }
+                    if attribute("http-equiv").is_some_and(|v| v.eq_ignore_ascii_case("refresh")) {
+                        page.skip = true;
+                    }
+                }
+                "link" => {
+                    let canonical = attribute("rel").is_some_and(|rel| {
+                        rel.split_whitespace()
+                            .any(|value| value.eq_ignore_ascii_case("canonical"))
+                    });
+                    if canonical {
+                        page.canonical = attribute("href").map(str::to_string);
+                    }
+                }
+                "a" => page.links.extend(attribute("href").map(str::to_string)),
+                "img" if attribute("alt").is_none() => {
+                    page.images_without_alt
+                        .push(attribute("src").unwrap_or("").to_string());
+                }
+                _ => {}
+            }
+        }
+
+        page
+    }
+}
+
+/// Issues found across a site and its score out of 100
+#[derive(Debug, Clone, Default)]
+pub struct SeoReport {
+    pub pages: usize,
+    pub issues: Vec<SeoIssue>,
+    pub score: u32,
+}
+
+/// Check every page on its own, then titles, descriptions and links across pages
+pub fn audit(pages: &[SeoPage], base_url: &str) -> SeoReport {
+    let pages: Vec<&SeoPage> = pages.iter().filter(|page| !page.skip).collect();
+    let mut issues = Vec::new();
+    let mut issue = |page: &SeoPage, kind: SeoIssueKind, message: String| {
+        issues.push(SeoIssue {
+            file: page.file.clone(),
+            kind,
+            message,
+        })
+    };
+
+    for page in &pages {
+        if page.title.as_deref().is_none_or(str::is_empty) {
+            issue(page, SeoIssueKind::MissingTitle, "no <title>".to_string());
+        }
+        match page.description.as_deref() {
+            None | Some("") => issue(
+                page,
+                SeoIssueKind::MissingDescription,
+                "no meta description".to_string(),
+            ),
+            Some(description) if description.chars().count() > MAX_DESCRIPTION_LENGTH => issue(
+                page,
+                SeoIssueKind::LongDescription,
+                format!(
+                    "meta description has {} characters (search results show about {})",
+                    description.chars().count(),
+                    MAX_DESCRIPTION_LENGTH
+                ),
+            ),
+            Some(_) => {}
+        }
+        if page.canonical.as_deref().is_none_or(str::is_empty) {
+            issue(
+                page,
+                SeoIssueKind::MissingCanonical,
+                "no <link rel=\"canonical\">".to_string(),
+            );
+        }
+        if !page.images_without_alt.is_empty() {
+            issue(
+                page,
+                SeoIssueKind::MissingAlt,
+                format!(
+                    "{} image(s) without alt text: {}",
+                    page.images_without_alt.len(),
+                    page.images_without_alt.join(", ")
+                ),
+            );
+        }
+    }
+
+    for (kind, what) in [
+        (SeoIssueKind::DuplicateTitle, "title"),
+        (SeoIssueKind::DuplicateDescription, "description"),
+    ] {
+        let mut groups: BTreeMap<&str, Vec<&SeoPage>> = BTreeMap::new();
+        for page in &pages {
+            let value = match kind {
+                SeoIssueKind::DuplicateTitle => page.title.as_deref(),
+                _ => page.description.as_deref(),
+            };
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                groups.entry(value).or_default().push(page);
+            }
+        }
+        for group in groups.values().filter(|group| group.len() > 1) {
+            for page in group {
+                let others: Vec<&str> = group
+                    .iter()
+                    .filter(|other| other.file != page.file)
+                    .map(|other| other.file.as_str())
+                    .collect();
+                issue(
+                    page,
+                    kind,
+                    format!("same {} as {}", what, summarize(&others)),
+                );
+            }
+        }
+    }
+
+    let linked = linked_pages(&pages, base_url);
+    for page in &pages {
+        let is_entry = page.file == "index.html" || page.file == "404.html";
+        if !is_entry && !linked.contains(page.file.as_str()) {
+            issue(
+                page,
+                SeoIssueKind::Orphan,
+                "no other page links here".to_string(),
+            );
+        }
+    }
+
+    issues.sort();
+    let checks = pages.len() * SeoIssueKind::COUNT;
+    let failed: HashSet<(&str, SeoIssueKind)> = issues
+        .iter()
+        .map(|issue| (issue.file.as_str(), issue.kind))
+        .collect();
+    let score = (100 * (checks - failed.len()))
+        .checked_div(checks)
+        .unwrap_or(100) as u32;
+
+    SeoReport {
+        pages: pages.len(),
+        issues,
+        score,
+    }
+}
+
+/// Pages that at least one other page links to
+fn linked_pages<'a>(pages: &[&'a SeoPage], base_url: &str) -> HashSet<&'a str> {
+    let mut linked = HashSet::new();
+    let Ok(base) = Url::parse(&format!("{}/", base_url.trim_end_matches('/'))) else {
+        return linked;
+    };
+    let files: HashSet<&'a str> = pages.iter().map(|page| page.file.as_str()).collect();
+
+    for page in pages {
+        for href in &page.links {
+            if let Some(target) = resolve_link(&base, &page.file, href, &files) {
+                if target != page.file {
+                    linked.insert(target);
+                }
+            }
+        }
+    }
+    linked
+}
+
+/// The page a link points to, if it is one of `files`
+fn resolve_link<'a>(
+    base: &Url,
+    from: &str,
+    href: &str,
+    files: &HashSet<&'a str>,
+) -> Option<&'a str> {
+    let mut target = base.join(from).ok()?.join(href.trim()).ok()?;
+    if target.origin() != base.origin() {
+        return None;
+    }
+    target.set_query(None);
+    target.set_fragment(None);
+
+    let path = urlencoding::decode(target.path()).ok()?.into_owned();
+    let path = path
+        .strip_prefix(base.path())
+        .or_else(|| path.strip_prefix('/'))?;
+    let candidates = if path.is_empty() || path.ends_with('/') {
+        vec![format!("{}index.html", path)]
+    } else {
+        vec![
+            path.to_string(),
+            format!("{}.html", path),
+            format!("{}/index.html", path),
+        ]
+    };
+    candidates
+        .iter()
+        .find_map(|candidate| files.get(candidate.as_str()).copied())
+}
+
+fn title(html: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets the same
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title>")?;
+    Some(collapse_whitespace(&decode_entities(&html[start..end])))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn summarize(files: &[&str]) -> String {
+    match files {
+        [one] => one.to_string(),
+        [first, rest @ ..] => format!("{} and {} more", first, rest.len()),
+        [] => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(file: &str, head: &str, body: &str) -> SeoPage {
+        SeoPage::parse(
+            file,
+            &format!("<html><head>{}</head><body>{}</body></html>", head, body),
+        )
+    }
+
+    #[test]
+    fn test_audit() {
+        let good_head = |title: &str| {
+            format!(
+                r#"<title>{}</title><meta name="description" content="About {}">
+                <link rel="canonical" href="https://example.com/blog/">"#,
+                title, title
+            )
+        };
+        let pages = vec![
+            page(
+                "index.html",
+                &good_head("Home"),
+                r#"<a href="posts/hello.html#top">Hello</a> <a href="/blog/about/">About</a>
+                <a href="https://other.example/">Elsewhere</a>"#,
+            ),
+            page(
+                "posts/hello.html",
+                "<title>Home</title>",
+                r#"<img src="a.png"><img src="b.png" alt=""><a href="../index.html">Home</a>"#,
+            ),
+            page("about/index.html", &good_head("About"), ""),
+            page(
+                "lonely.html",
+                &good_head("Lonely"),
+                r#"<a href="lonely.html">Me</a>"#,
+            ),
+            page(
+                "old.html",
+                r#"<meta http-equiv="refresh" content="0; url=/new/">"#,
+                "",
+            ),
+        ];
+        assert!(pages[4].skip);
+
+        let report = audit(&pages, "https://example.com/blog");
+        let found: Vec<(&str, SeoIssueKind)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.file.as_str(), issue.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("index.html", SeoIssueKind::DuplicateTitle),
+                ("lonely.html", SeoIssueKind::Orphan),
+                ("posts/hello.html", SeoIssueKind::DuplicateTitle),
+                ("posts/hello.html", SeoIssueKind::MissingDescription),
+                ("posts/hello.html", SeoIssueKind::MissingCanonical),
+                ("posts/hello.html", SeoIssueKind::MissingAlt),
+            ]
+        );
+        assert!(report.issues[5].message.contains("a.png"));
+        assert_eq!(report.pages, 4);
+        assert_eq!(report.score, 81);
+    }
+}
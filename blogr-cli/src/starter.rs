@@ -0,0 +1,202 @@
+//! Starter kits for `blogr init --template`
+//!
+//! A kit picks a theme, turns on the features that kind of site needs, adds
+//! menu links and writes sample content in place of the generic welcome post.
+
+use crate::config::{Config, EnvConfig, MenuItem};
+use clap::ValueEnum;
+use std::collections::hash_map::Entry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StarterKit {
+    /// Project documentation with guide and reference sections
+    Docs,
+    /// Personal portfolio page built from content.md
+    Portfolio,
+    /// Digital garden of notes tagged by maturity
+    Garden,
+    /// Newsletter with a subscribe page and issues as posts
+    #[value(name = "newsletter-first")]
+    NewsletterFirst,
+}
+
+impl StarterKit {
+    pub fn name(self) -> &'static str {
+        match self {
+            StarterKit::Docs => "docs",
+            StarterKit::Portfolio => "portfolio",
+            StarterKit::Garden => "garden",
+            StarterKit::NewsletterFirst => "newsletter-first",
+        }
+    }
+
+    /// Whether the kit builds a personal website instead of a blog
+    pub fn is_personal(self) -> bool {
+        self == StarterKit::Portfolio
+    }
+
+    pub fn theme(self) -> &'static str {
+        match self {
+            StarterKit::Docs | StarterKit::NewsletterFirst => "minimal-retro",
+            StarterKit::Portfolio => "slate-portfolio",
+            StarterKit::Garden => "obsidian",
+        }
+    }
+
+    /// Apply the kit's theme, features and menu to a freshly created config
+    pub fn configure(self, config: &mut Config) {
+        if self.is_personal() {
+            config.site.site_type = "personal".to_string();
+        }
+
+        config.theme.name = self.theme().to_string();
+        if let Some(theme) = blogr_themes::get_theme(self.theme()) {
+            for (option_name, option) in theme.info().config_schema {
+                if let Entry::Vacant(e) = config.theme.config.entry(option_name) {
+                    e.insert(option.value);
+                }
+            }
+        }
+
+        match self {
+            StarterKit::Docs => {
+                config.menu = menu(&[
+                    ("Guides", "tags/guide.html"),
+                    ("Reference", "tags/reference.html"),
+                    ("Archive", "archive.html"),
+                ]);
+            }
+            StarterKit::Portfolio => {}
+            StarterKit::Garden => {
+                // Notes are tended over time, so show when each was last updated
+                config.build.git_metadata = true;
+                config.menu = menu(&[
+                    ("Seedlings", "tags/seedling.html"),
+                    ("Budding", "tags/budding.html"),
+                    ("Evergreen", "tags/evergreen.html"),
+                    ("All notes", "archive.html"),
+                ]);
+            }
+            StarterKit::NewsletterFirst => {
+                config.newsletter.enabled = true;
+                config.newsletter.subscribe_email = Some(
+                    EnvConfig::git_author_email()
+                        .filter(|email| email.contains('@'))
+                        .unwrap_or_else(|| "newsletter@example.com".to_string()),
+                );
+                config.newsletter.sender_name = Some(config.blog.author.clone());
+                config.menu = menu(&[
+                    ("Subscribe", "posts/subscribe.html"),
+                    ("Past issues", "archive.html"),
+                ]);
+            }
+        }
+    }
+
+    /// Sample posts as `(file name, content)` pairs, with placeholders filled in
+    pub fn sample_posts(self, config: &Config) -> Vec<(&'static str, String)> {
+        let posts: &[(&str, &str)] = match self {
+            StarterKit::Docs => &[
+                (
+                    "getting-started.md",
+                    include_str!("../templates/starters/docs_getting_started.md"),
+                ),
+                (
+                    "installation.md",
+                    include_str!("../templates/starters/docs_installation.md"),
+                ),
+                (
+                    "configuration.md",
+                    include_str!("../templates/starters/docs_configuration.md"),
+                ),
+            ],
+            StarterKit::Portfolio => &[],
+            StarterKit::Garden => &[
+                (
+                    "start-here.md",
+                    include_str!("../templates/starters/garden_start_here.md"),
+                ),
+                (
+                    "notes-should-be-atomic.md",
+                    include_str!("../templates/starters/garden_seedling.md"),
+                ),
+                (
+                    "gardens-versus-streams.md",
+                    include_str!("../templates/starters/garden_budding.md"),
+                ),
+            ],
+            StarterKit::NewsletterFirst => &[
+                (
+                    "subscribe.md",
+                    include_str!("../templates/starters/newsletter_subscribe.md"),
+                ),
+                (
+                    "issue-1.md",
+                    include_str!("../templates/starters/newsletter_issue.md"),
+                ),
+            ],
+        };
+
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let subscribe_email = config
+            .newsletter
+            .subscribe_email
+            .clone()
+            .unwrap_or_default();
+        posts
+            .iter()
+            .map(|(file_name, template)| {
+                let content = template
+                    .replace("{author}", &config.blog.author)
+                    .replace("{date}", &date)
+                    .replace("{blog_title}", &config.blog.title)
+                    .replace("{subscribe_email}", &subscribe_email);
+                (*file_name, content)
+            })
+            .collect()
+    }
+}
+
+fn menu(items: &[(&str, &str)]) -> Vec<MenuItem> {
+    items
+        .iter()
+        .map(|(name, url)| MenuItem {
+            name: name.to_string(),
+            url: url.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::Post;
+    use std::path::Path;
+
+    fn config() -> Config {
+        Config::new_with_defaults(
+            "Test Site".to_string(),
+            "Test Author".to_string(),
+            "A test site".to_string(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_starter_kits_produce_valid_projects() {
+        for kit in StarterKit::value_variants() {
+            let mut config = config();
+            kit.configure(&mut config);
+            config.validate().unwrap();
+            assert_eq!(config.theme.name, kit.theme());
+            assert_eq!(config.site.site_type == "personal", kit.is_personal());
+
+            for (file_name, content) in kit.sample_posts(&config) {
+                let post = Post::parse(&content, Path::new(file_name)).unwrap();
+                assert_eq!(format!("{}.md", post.metadata.slug), file_name);
+                assert!(!content.contains("{author}"));
+            }
+        }
+    }
+}
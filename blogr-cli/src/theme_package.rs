@@ -0,0 +1,503 @@
+//! Theme packages installed into a project's `themes/` directory
+//!
+//! A package is a directory with a `theme.toml` manifest, a `templates/`
+//! directory holding at least `base.html`, and an optional `assets/`
+//! directory whose files are copied to the site like a built-in theme's.
+//! `blogr theme install` copies a package from a local path or a git
+//! repository and records its source in `[theme.packages]`, pinned to the
+//! commit that was installed, so `blogr theme update` knows where to look.
+
+use crate::commands::deploy::remote_callbacks;
+use crate::config::{EnvConfig, ThemePackage};
+use crate::generator::assets::copy_dir_recursive;
+use crate::utils::Utils;
+use anyhow::{anyhow, bail, Context, Result};
+use blogr_themes::{ConfigOption, SiteType, Theme, ThemeInfo, ThemeTemplates};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Name of the manifest at the root of a package
+pub const MANIFEST: &str = "theme.toml";
+
+/// Templates a theme needs for each kind of site
+const BLOG_TEMPLATES: &[&str] = &[
+    "base.html",
+    "index.html",
+    "post.html",
+    "archive.html",
+    "tag.html",
+    "tags.html",
+];
+const PERSONAL_TEMPLATES: &[&str] = &["base.html", "index.html"];
+const HYBRID_TEMPLATES: &[&str] = &[
+    "base.html",
+    "index.html",
+    "post.html",
+    "archive.html",
+    "tag.html",
+    "tags.html",
+    "home.html",
+];
+
+/// Contents of `theme.toml`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThemeManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    /// "blog", "personal" or "hybrid"
+    #[serde(default = "default_site_type")]
+    pub site_type: String,
+    /// Options users can set in `[theme.config]`, with their defaults
+    #[serde(default)]
+    pub config: HashMap<String, ConfigOption>,
+}
+
+fn default_site_type() -> String {
+    "blog".to_string()
+}
+
+/// A theme loaded from a package directory
+pub struct PackagedTheme {
+    manifest: ThemeManifest,
+    site_type: SiteType,
+    templates: Vec<(String, String)>,
+    assets: HashMap<String, Vec<u8>>,
+}
+
+impl PackagedTheme {
+    /// Read and check a package directory
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest_path = dir.join(MANIFEST);
+        let manifest: ThemeManifest = toml::from_str(
+            &fs::read_to_string(&manifest_path)
+                .with_context(|| format!("Failed to read {}", manifest_path.display()))?,
+        )
+        .with_context(|| format!("Invalid theme manifest {}", manifest_path.display()))?;
+        if Utils::slugify(&manifest.name).is_empty() {
+            bail!(
+                "Theme name '{}' in {} needs at least one letter or digit",
+                manifest.name,
+                manifest_path.display()
+            );
+        }
+
+        let site_type: SiteType = manifest
+            .site_type
+            .parse()
+            .map_err(|e| anyhow!("Theme '{}': {}", manifest.name, e))?;
+        let required = match site_type {
+            SiteType::Blog => BLOG_TEMPLATES,
+            SiteType::Personal => PERSONAL_TEMPLATES,
+            SiteType::Hybrid => HYBRID_TEMPLATES,
+        };
+
+        let templates_dir = dir.join("templates");
+        let mut templates = Vec::new();
+        for entry in WalkDir::new(&templates_dir).sort_by_file_name() {
+            let entry = entry.with_context(|| {
+                format!("Theme '{}' has no templates/ directory", manifest.name)
+            })?;
+            if entry.file_type().is_file() {
+                let name = relative_name(&templates_dir, entry.path())?;
+                templates.push((name, fs::read_to_string(entry.path())?));
+            }
+        }
+        for name in required {
+            if !templates.iter().any(|(template, _)| template == name) {
+                bail!("Theme '{}' is missing templates/{}", manifest.name, name);
+            }
+        }
+        // Tera needs the base template registered first
+        templates.sort_by_key(|(name, _)| name != "base.html");
+
+        let assets_dir = dir.join("assets");
+        let mut assets = HashMap::new();
+        if assets_dir.is_dir() {
+            for entry in WalkDir::new(&assets_dir) {
+                let entry = entry?;
+                if entry.file_type().is_file() {
+                    assets.insert(
+                        relative_name(&assets_dir, entry.path())?,
+                        fs::read(entry.path())?,
+                    );
+                }
+            }
+        }
+
+        Ok(Self {
+            manifest,
+            site_type,
+            templates,
+            assets,
+        })
+    }
+
+    /// Directory name under `themes/` and the name used in `[theme] name`
+    pub fn slug(&self) -> String {
+        Utils::slugify(&self.manifest.name)
+    }
+}
+
+fn relative_name(root: &Path, path: &Path) -> Result<String> {
+    Ok(path
+        .strip_prefix(root)?
+        .to_string_lossy()
+        .replace('\\', "/"))
+}
+
+impl Theme for PackagedTheme {
+    fn info(&self) -> ThemeInfo {
+        ThemeInfo {
+            name: self.manifest.name.clone(),
+            version: self.manifest.version.clone(),
+            author: self.manifest.author.clone(),
+            description: self.manifest.description.clone(),
+            config_schema: self.manifest.config.clone(),
+            site_type: self.site_type.clone(),
+        }
+    }
+
+    fn templates(&self) -> ThemeTemplates {
+        ThemeTemplates::from_owned(self.templates.clone())
+    }
+
+    fn assets(&self) -> HashMap<String, Vec<u8>> {
+        self.assets.clone()
+    }
+
+    fn preview_tui_style(&self) -> ratatui::style::Style {
+        ratatui::style::Style::default()
+    }
+}
+
+/// Look up a theme installed in `themes_dir`
+pub fn find_installed(themes_dir: &Path, name: &str) -> Result<Option<PackagedTheme>> {
+    let dir = themes_dir.join(Utils::slugify(name));
+    if !dir.join(MANIFEST).is_file() {
+        return Ok(None);
+    }
+    PackagedTheme::load(&dir).map(Some)
+}
+
+/// Every package in `themes_dir`, with the directory of any that fail to load
+pub fn installed(themes_dir: &Path) -> Vec<(PathBuf, Result<PackagedTheme>)> {
+    let Ok(entries) = fs::read_dir(themes_dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join(MANIFEST).is_file())
+        .collect();
+    dirs.sort();
+    dirs.into_iter()
+        .map(|dir| {
+            let theme = PackagedTheme::load(&dir);
+            (dir, theme)
+        })
+        .collect()
+}
+
+/// Whether an install source is a git repository rather than a local path
+pub fn is_git_source(source: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "git@", "file://"]
+        .iter()
+        .any(|prefix| source.starts_with(prefix))
+        || source.ends_with(".git")
+}
+
+/// Fetch a package into `themes_dir`, replacing any installed copy, and
+/// return its slug with the pin to record. `rev` (a tag, branch or commit)
+/// is only allowed for git sources.
+pub fn install(
+    themes_dir: &Path,
+    source: &str,
+    rev: Option<&str>,
+) -> Result<(String, ThemePackage)> {
+    fs::create_dir_all(themes_dir)
+        .with_context(|| format!("Failed to create {}", themes_dir.display()))?;
+
+    let staging = themes_dir.join(format!(".install-{}", uuid::Uuid::new_v4()));
+    let result = fetch_into(themes_dir, &staging, source, rev);
+    if staging.exists() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    result
+}
+
+/// Refuse packages containing symlinks: copying follows them, so a link such
+/// as `assets/x -> ../../.env` would pull a local file into the published site
+fn reject_symlinks(package_dir: &Path) -> Result<()> {
+    for entry in WalkDir::new(package_dir)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() != 1 || entry.file_name() != ".git")
+    {
+        let entry = entry?;
+        if entry.path_is_symlink() {
+            let relative = entry
+                .path()
+                .strip_prefix(package_dir)
+                .unwrap_or(entry.path());
+            bail!(
+                "Theme package contains a symlink at {}; symlinks aren't allowed in themes",
+                relative.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn fetch_into(
+    themes_dir: &Path,
+    staging: &Path,
+    source: &str,
+    rev: Option<&str>,
+) -> Result<(String, ThemePackage)> {
+    let (package_dir, commit) = if is_git_source(source) {
+        let commit = clone_at(source, rev, staging)?;
+        (staging.to_path_buf(), Some(commit))
+    } else {
+        if rev.is_some() {
+            bail!(
+                "--rev only applies to git sources; '{}' is a local path",
+                source
+            );
+        }
+        let path = PathBuf::from(source);
+        if !path.join(MANIFEST).is_file() {
+            bail!("No {} found in {}", MANIFEST, path.display());
+        }
+        (path, None)
+    };
+
+    let theme = PackagedTheme::load(&package_dir)?;
+    let slug = theme.slug();
+    if blogr_themes::get_theme(&slug).is_some() {
+        bail!(
+            "Theme '{}' has the same name as a built-in theme. Rename it in {}.",
+            theme.manifest.name,
+            MANIFEST
+        );
+    }
+
+    // Copy only the package itself, leaving out .git and anything else
+    let target = themes_dir.join(&slug);
+    if slug.is_empty() || slug.starts_with('.') || target.parent() != Some(themes_dir) {
+        bail!(
+            "Theme '{}' can't be installed as '{}'",
+            theme.manifest.name,
+            slug
+        );
+    }
+    reject_symlinks(&package_dir)?;
+    if target.exists() {
+        fs::remove_dir_all(&target)
+            .with_context(|| format!("Failed to remove old copy at {}", target.display()))?;
+    }
+    fs::create_dir_all(&target)?;
+    fs::copy(package_dir.join(MANIFEST), target.join(MANIFEST))?;
+    copy_dir_recursive(&package_dir.join("templates"), &target.join("templates"))?;
+    if package_dir.join("assets").is_dir() {
+        copy_dir_recursive(&package_dir.join("assets"), &target.join("assets"))?;
+    }
+
+    Ok((
+        slug,
+        ThemePackage {
+            source: source.to_string(),
+            rev: rev.map(str::to_string),
+            commit,
+            version: theme.manifest.version.clone(),
+        },
+    ))
+}
+
+/// Clone `url` into `dest` and check out `rev` (default branch if `None`),
+/// returning the commit id
+fn clone_at(url: &str, rev: Option<&str>, dest: &Path) -> Result<String> {
+    // Public HTTPS repositories need no token
+    let callbacks = if url.starts_with("git@")
+        || url.starts_with("ssh://")
+        || (url.starts_with("https://") && EnvConfig::github_token().is_some())
+    {
+        remote_callbacks(url)?
+    } else {
+        git2::RemoteCallbacks::new()
+    };
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    let repo = git2::build::RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(url, dest)
+        .with_context(|| format!("Failed to clone {}", url))?;
+
+    let commit = match rev {
+        Some(rev) => {
+            let object = repo
+                .revparse_single(rev)
+                .or_else(|_| repo.revparse_single(&format!("origin/{}", rev)))
+                .map_err(|_| anyhow!("Revision '{}' not found in {}", rev, url))?;
+            let commit = object.peel_to_commit()?;
+            repo.checkout_tree(
+                commit.as_object(),
+                Some(git2::build::CheckoutBuilder::new().force()),
+            )?;
+            repo.set_head_detached(commit.id())?;
+            commit
+        }
+        None => repo.head()?.peel_to_commit()?,
+    };
+    Ok(commit.id().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_package(dir: &Path, version: &str) {
+        fs::create_dir_all(dir.join("templates")).unwrap();
+        fs::create_dir_all(dir.join("assets/css")).unwrap();
+        fs::write(
+            dir.join(MANIFEST),
+            format!(
+                "name = \"Paper Plane\"\nversion = \"{}\"\nsite_type = \"personal\"\n\n\
+                 [config.accent]\nvalue = \"#336699\"\ndescription = \"Accent colour\"\n",
+                version
+            ),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("templates/base.html"),
+            "<link href=\"/css/paper.css\">{% block content %}{% endblock %}",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("templates/index.html"),
+            "{% extends \"base.html\" %}{% block content %}{{ blog_title }}{% endblock %}",
+        )
+        .unwrap();
+        fs::write(dir.join("assets/css/paper.css"), "body {}").unwrap();
+    }
+
+    fn commit_all(repo: &git2::Repository, message: &str) -> git2::Oid {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_install_from_git_pins_revision() {
+        let upstream = TempDir::new().unwrap();
+        let repo = git2::Repository::init(upstream.path()).unwrap();
+        write_package(upstream.path(), "1.0.0");
+        let first = commit_all(&repo, "v1");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        write_package(upstream.path(), "2.0.0");
+        let second = commit_all(&repo, "v2");
+
+        let project = TempDir::new().unwrap();
+        let themes_dir = project.path().join("themes");
+        let url = format!("file://{}", upstream.path().display());
+
+        let (slug, pinned) = install(&themes_dir, &url, Some("v1.0.0")).unwrap();
+        assert_eq!(slug, "paper-plane");
+        assert_eq!(pinned.version, "1.0.0");
+        assert_eq!(pinned.commit, Some(first.to_string()));
+
+        let (_, latest) = install(&themes_dir, &url, None).unwrap();
+        assert_eq!(latest.version, "2.0.0");
+        assert_eq!(latest.commit, Some(second.to_string()));
+
+        let theme = find_installed(&themes_dir, "Paper Plane").unwrap().unwrap();
+        assert_eq!(theme.info().site_type, SiteType::Personal);
+        assert_eq!(theme.templates().into_iter().next().unwrap().0, "base.html");
+        assert!(theme.assets().contains_key("css/paper.css"));
+        assert!(!themes_dir.join("paper-plane/.git").exists());
+        assert_eq!(installed(&themes_dir).len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_rejects_symlinked_asset() {
+        let secrets = TempDir::new().unwrap();
+        fs::write(secrets.path().join(".env"), "TOKEN=hunter2").unwrap();
+
+        let upstream = TempDir::new().unwrap();
+        let repo = git2::Repository::init(upstream.path()).unwrap();
+        write_package(upstream.path(), "1.0.0");
+        std::os::unix::fs::symlink(
+            secrets.path().join(".env"),
+            upstream.path().join("assets/css/env.css"),
+        )
+        .unwrap();
+        commit_all(&repo, "v1");
+
+        let project = TempDir::new().unwrap();
+        let themes_dir = project.path().join("themes");
+        for source in [
+            format!("file://{}", upstream.path().display()),
+            upstream.path().display().to_string(),
+        ] {
+            let error = install(&themes_dir, &source, None).err().unwrap();
+            assert!(
+                format!("{:#}", error).contains("symlink at assets/css/env.css"),
+                "{:#}",
+                error
+            );
+        }
+        assert!(!themes_dir.join("paper-plane").exists());
+    }
+
+    #[test]
+    fn test_load_rejects_incomplete_package() {
+        let dir = TempDir::new().unwrap();
+        write_package(dir.path(), "1.0.0");
+        fs::remove_file(dir.path().join("templates/index.html")).unwrap();
+        let error = PackagedTheme::load(dir.path()).err().unwrap();
+        assert!(error.to_string().contains("missing templates/index.html"));
+    }
+
+    #[test]
+    fn test_install_rejects_name_without_slug() {
+        let project = TempDir::new().unwrap();
+        let themes_dir = project.path().join("themes");
+        write_package(&themes_dir.join("paper-plane"), "1.0.0");
+
+        let package = TempDir::new().unwrap();
+        write_package(package.path(), "1.0.0");
+        let manifest = package.path().join(MANIFEST);
+        let text = fs::read_to_string(&manifest)
+            .unwrap()
+            .replace("Paper Plane", "!!!");
+        fs::write(&manifest, text).unwrap();
+
+        let error = install(&themes_dir, package.path().to_str().unwrap(), None)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("at least one letter or digit"));
+        assert!(themes_dir.join("paper-plane").join(MANIFEST).is_file());
+    }
+}
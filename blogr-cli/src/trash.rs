@@ -0,0 +1,181 @@
+//! Trash for deleted posts
+//!
+//! `blogr delete` moves the post file to `.blogr/trash/<slug>-<timestamp>/`
+//! next to a `trash.json` describing where it came from, so `blogr restore`
+//! can put it back.
+
+use crate::content::Post;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INFO_FILE: &str = "trash.json";
+
+/// What `trash.json` records about a deleted post
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedPost {
+    pub slug: String,
+    pub title: String,
+    /// Original location, relative to the project root
+    pub path: PathBuf,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// A post in the trash
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    pub dir: PathBuf,
+    pub post: TrashedPost,
+}
+
+impl TrashEntry {
+    fn file(&self) -> PathBuf {
+        let name = self
+            .post
+            .path
+            .file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_else(|| format!("{}.md", self.post.slug).into());
+        self.dir.join(name)
+    }
+}
+
+/// The trash of one project
+pub struct Trash {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            root: project_root.to_path_buf(),
+            dir: project_root.join(".blogr").join("trash"),
+        }
+    }
+
+    /// Trashed posts, oldest first
+    pub fn entries(&self) -> Result<Vec<TrashEntry>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let dir = entry?.path();
+            let info = dir.join(INFO_FILE);
+            if !info.exists() {
+                continue;
+            }
+            let content = fs::read_to_string(&info)
+                .with_context(|| format!("Failed to read {}", info.display()))?;
+            let post = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid {}", info.display()))?;
+            entries.push(TrashEntry { dir, post });
+        }
+        entries.sort_by_key(|entry| entry.post.deleted_at);
+        Ok(entries)
+    }
+
+    /// The most recently trashed post with this slug
+    pub fn find(&self, slug: &str) -> Result<Option<TrashEntry>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .rev()
+            .find(|entry| entry.post.slug == slug))
+    }
+
+    /// Move a post file into the trash
+    pub fn trash(&self, post: &Post) -> Result<TrashEntry> {
+        let deleted_at = Utc::now();
+        let dir = self.dir.join(format!(
+            "{}-{}",
+            post.metadata.slug,
+            deleted_at.format("%Y%m%dT%H%M%S%3fZ")
+        ));
+        fs::create_dir_all(&dir)?;
+
+        let entry = TrashEntry {
+            dir,
+            post: TrashedPost {
+                slug: post.metadata.slug.clone(),
+                title: post.metadata.title.clone(),
+                path: post
+                    .file_path
+                    .strip_prefix(&self.root)
+                    .unwrap_or(&post.file_path)
+                    .to_path_buf(),
+                deleted_at,
+            },
+        };
+        fs::write(
+            entry.dir.join(INFO_FILE),
+            serde_json::to_string_pretty(&entry.post)?,
+        )?;
+        fs::rename(&post.file_path, entry.file())
+            .with_context(|| format!("Failed to move {} to the trash", post.file_path.display()))?;
+        Ok(entry)
+    }
+
+    /// Move a trashed post back to where it was. Returns its path.
+    pub fn restore(&self, entry: &TrashEntry) -> Result<PathBuf> {
+        let target = self.root.join(&entry.post.path);
+        if target.exists() {
+            return Err(anyhow!(
+                "{} already exists; move it out of the way first",
+                target.display()
+            ));
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(entry.file(), &target)
+            .with_context(|| format!("Failed to restore {}", target.display()))?;
+        fs::remove_dir_all(&entry.dir)?;
+        Ok(target)
+    }
+
+    /// Delete a trashed post permanently
+    pub fn purge(&self, entry: &TrashEntry) -> Result<()> {
+        fs::remove_dir_all(&entry.dir)
+            .with_context(|| format!("Failed to remove {}", entry.dir.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::PostStatus;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trash_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let posts_dir = temp_dir.path().join("posts");
+        fs::create_dir_all(&posts_dir).unwrap();
+
+        let mut post = Post::new(
+            "Hello".to_string(),
+            "Author".to_string(),
+            None,
+            vec![],
+            None,
+            PostStatus::Draft,
+        );
+        post.file_path = posts_dir.join(post.filename());
+        post.save_to_file(&post.file_path).unwrap();
+
+        let trash = Trash::new(temp_dir.path());
+        trash.trash(&post).unwrap();
+        assert!(!post.file_path.exists());
+
+        let entry = trash.find("hello").unwrap().unwrap();
+        assert_eq!(entry.post.path, Path::new("posts/hello.md"));
+        assert_eq!(trash.restore(&entry).unwrap(), post.file_path);
+        assert!(post.file_path.exists());
+        assert!(trash.entries().unwrap().is_empty());
+    }
+}
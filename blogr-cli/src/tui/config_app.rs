@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
-use crate::config::Config;
+use crate::config::{Config, ImapConfig, MenuItem, SmtpConfig};
+use crate::newsletter::fetcher::is_valid_email;
 use crate::project::Project;
+use crate::tui::text_area::TextArea;
 use crate::tui::theme::TuiTheme;
 use anyhow::Ok;
 use blogr_themes::{get_all_themes, ThemeInfo};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style, Stylize},
@@ -112,6 +114,7 @@ fn set_primary_domain(config: &mut Config, new_value: String) {
             subdomain: None,
             enforce_https: true,
             github_pages_domain: None,
+            www_redirect: true,
         });
     }
     if let Some(domains) = &mut config.blog.domains {
@@ -135,6 +138,7 @@ fn set_domain_enforce_https(config: &mut Config, new_value: String) -> AppResult
             subdomain: None,
             enforce_https,
             github_pages_domain: None,
+            www_redirect: true,
         });
     }
     if let Some(domains) = &mut config.blog.domains {
@@ -143,6 +147,141 @@ fn set_domain_enforce_https(config: &mut Config, new_value: String) -> AppResult
     Ok(())
 }
 
+fn set_domain_aliases(config: &mut Config, new_value: String) -> AppResult<()> {
+    let aliases = list_items(&new_value);
+    for alias in &aliases {
+        validate_host(alias)?;
+    }
+    config
+        .blog
+        .domains
+        .get_or_insert_with(|| crate::config::DomainConfig {
+            primary: None,
+            aliases: Vec::new(),
+            subdomain: None,
+            enforce_https: true,
+            github_pages_domain: None,
+            www_redirect: true,
+        })
+        .aliases = aliases;
+    Ok(())
+}
+
+/// Menu entries are edited one per line as `Name | url`
+fn set_menu(config: &mut Config, new_value: String) -> AppResult<()> {
+    config.menu = list_items(&new_value)
+        .iter()
+        .map(|line| match line.split_once('|') {
+            Some((name, url)) if !name.trim().is_empty() && !url.trim().is_empty() => {
+                Ok(MenuItem {
+                    name: name.trim().to_string(),
+                    url: url.trim().to_string(),
+                })
+            }
+            _ => Err(anyhow::anyhow!(
+                "'{}' needs a name and a link, e.g. About | about.html",
+                line
+            )),
+        })
+        .collect::<AppResult<_>>()?;
+    Ok(())
+}
+
+/// The non-empty lines of a list field
+fn list_items(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn set_subscribe_email(config: &mut Config, new_value: String) -> AppResult<()> {
+    if !is_valid_email(&new_value) {
+        anyhow::bail!("'{}' is not a valid email address", new_value);
+    }
+    config.newsletter.subscribe_email = Some(new_value);
+    Ok(())
+}
+
+/// Host names for IMAP and SMTP: no scheme, port or spaces
+fn validate_host(host: &str) -> AppResult<()> {
+    let valid = host
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        && !host.starts_with(['.', '-'])
+        && !host.ends_with(['.', '-']);
+    if !valid {
+        anyhow::bail!(
+            "'{}' is not a host name; enter it without a scheme or port, e.g. imap.example.com",
+            host
+        );
+    }
+    Ok(())
+}
+
+fn set_imap_server(config: &mut Config, new_value: String) -> AppResult<()> {
+    validate_host(&new_value)?;
+    let username = config
+        .newsletter
+        .subscribe_email
+        .clone()
+        .unwrap_or_default();
+    config
+        .newsletter
+        .imap
+        .get_or_insert_with(|| ImapConfig {
+            server: String::new(),
+            port: 993,
+            username,
+            use_tls: Some(true),
+            oauth: None,
+        })
+        .server = new_value;
+    Ok(())
+}
+
+fn set_smtp_server(config: &mut Config, new_value: String) -> AppResult<()> {
+    validate_host(&new_value)?;
+    let username = config
+        .newsletter
+        .subscribe_email
+        .clone()
+        .unwrap_or_default();
+    config
+        .newsletter
+        .smtp
+        .get_or_insert_with(|| SmtpConfig {
+            server: String::new(),
+            port: 587,
+            username,
+            use_tls: Some(true),
+            oauth: None,
+        })
+        .server = new_value;
+    Ok(())
+}
+
+fn set_field_boost(config: &mut Config, field: &str, new_value: String) -> AppResult<()> {
+    let boost: f32 = new_value.parse()?;
+    if !boost.is_finite() || boost <= 0.0 {
+        anyhow::bail!("A boost must be a number greater than 0");
+    }
+    config.search.field_boosts.insert(field.to_string(), boost);
+    Ok(())
+}
+
+/// The input a field is edited with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldEditor {
+    /// One line; Enter saves
+    Line,
+    /// Wrapped text over several lines
+    Text,
+    /// One item per line
+    List,
+}
+
 /// Configuration field types
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConfigField {
@@ -152,15 +291,29 @@ pub enum ConfigField {
     BlogBaseUrl,
     BlogLanguage,
     BlogTimezone,
+    Menu,
     ThemeName,
     ThemeOption { name: String, value: toml::Value },
     DomainPrimary,
     DomainEnforceHttps,
+    DomainAliases,
     BuildOutputDir,
     BuildDrafts,
     BuildFuturePosts,
     DevPort,
     DevAutoReload,
+    NewsletterEnabled,
+    NewsletterSubscribeEmail,
+    NewsletterImapServer,
+    NewsletterSmtpServer,
+    SearchEnabled,
+    SearchLazyLoad,
+    SearchFields,
+    SearchExclude,
+    SearchFieldBoost { field: String },
+    SeoStructuredData,
+    SeoRobots,
+    SeoCanonicalUrls,
 }
 
 impl std::fmt::Display for ConfigField {
@@ -172,15 +325,29 @@ impl std::fmt::Display for ConfigField {
             Self::BlogBaseUrl => "Base URL",
             Self::BlogLanguage => "Language",
             Self::BlogTimezone => "Timezone",
+            Self::Menu => "Menu",
             Self::ThemeName => "Theme Name",
             Self::ThemeOption { name, .. } => name,
             Self::DomainPrimary => "Primary Domain",
             Self::DomainEnforceHttps => "Enforce HTTPS",
+            Self::DomainAliases => "Domain Aliases",
             Self::BuildOutputDir => "Output Directory",
             Self::BuildDrafts => "Include Drafts",
             Self::BuildFuturePosts => "Include Future Posts",
             Self::DevPort => "Development Port",
             Self::DevAutoReload => "Auto Reload",
+            Self::NewsletterEnabled => "Newsletter Enabled",
+            Self::NewsletterSubscribeEmail => "Subscribe Email",
+            Self::NewsletterImapServer => "IMAP Server",
+            Self::NewsletterSmtpServer => "SMTP Server",
+            Self::SearchEnabled => "Search Enabled",
+            Self::SearchLazyLoad => "Lazy Load Index",
+            Self::SearchFields => "Indexed Fields",
+            Self::SearchExclude => "Excluded Paths",
+            Self::SearchFieldBoost { field } => return write!(f, "Boost: {field}"),
+            Self::SeoStructuredData => "Structured Data",
+            Self::SeoRobots => "robots.txt",
+            Self::SeoCanonicalUrls => "Canonical URLs",
         };
         write!(f, "{name}")
     }
@@ -195,6 +362,12 @@ impl ConfigField {
             Self::BlogBaseUrl => config.blog.base_url.clone(),
             Self::BlogLanguage => config.blog.language.as_deref().unwrap_or("").to_string(),
             Self::BlogTimezone => config.blog.timezone.as_deref().unwrap_or("").to_string(),
+            Self::Menu => config
+                .menu
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
             Self::ThemeName => config.theme.name.clone(),
             // don't render toml strings with added quotes
             Self::ThemeOption { value, .. } => match value {
@@ -215,6 +388,12 @@ impl ConfigField {
                     "true".to_string()
                 }
             }
+            Self::DomainAliases => config
+                .blog
+                .domains
+                .as_ref()
+                .map(|domains| domains.aliases.join(", "))
+                .unwrap_or_default(),
             Self::BuildOutputDir => config
                 .build
                 .output_dir
@@ -225,6 +404,38 @@ impl ConfigField {
             Self::BuildFuturePosts => config.build.future_posts.to_string(),
             Self::DevPort => config.dev.port.to_string(),
             Self::DevAutoReload => config.dev.auto_reload.to_string(),
+            Self::NewsletterEnabled => config.newsletter.enabled.to_string(),
+            Self::NewsletterSubscribeEmail => config
+                .newsletter
+                .subscribe_email
+                .clone()
+                .unwrap_or_default(),
+            Self::NewsletterImapServer => config
+                .newsletter
+                .imap
+                .as_ref()
+                .map(|imap| imap.server.clone())
+                .unwrap_or_default(),
+            Self::NewsletterSmtpServer => config
+                .newsletter
+                .smtp
+                .as_ref()
+                .map(|smtp| smtp.server.clone())
+                .unwrap_or_default(),
+            Self::SearchEnabled => config.search.enabled.to_string(),
+            Self::SearchLazyLoad => config.search.lazy_load.to_string(),
+            Self::SearchFields => config.search.fields.join(", "),
+            Self::SearchExclude => config.search.exclude.join(", "),
+            Self::SearchFieldBoost { field } => config
+                .search
+                .field_boosts
+                .get(field)
+                .copied()
+                .unwrap_or(1.0)
+                .to_string(),
+            Self::SeoStructuredData => config.structured_data.enabled.to_string(),
+            Self::SeoRobots => config.robots.enabled.to_string(),
+            Self::SeoCanonicalUrls => config.urls.canonical.to_string(),
         }
     }
 
@@ -240,12 +451,14 @@ impl ConfigField {
             Self::BlogTimezone => {
                 config.blog.timezone = (!new_value.is_empty()).then_some(new_value)
             }
+            Self::Menu => set_menu(config, new_value)?,
             Self::ThemeName => config.theme.name = new_value,
             Self::ThemeOption { name, value } => {
                 set_theme_option(config, name.clone(), value, new_value)?
             }
             Self::DomainPrimary => set_primary_domain(config, new_value),
             Self::DomainEnforceHttps => set_domain_enforce_https(config, new_value)?,
+            Self::DomainAliases => set_domain_aliases(config, new_value)?,
             Self::BuildOutputDir => {
                 config.build.output_dir = (!new_value.is_empty()).then_some(new_value)
             }
@@ -253,6 +466,24 @@ impl ConfigField {
             Self::BuildFuturePosts => config.build.future_posts = new_value.parse()?,
             Self::DevPort => config.dev.port = new_value.parse()?,
             Self::DevAutoReload => config.dev.auto_reload = new_value.parse()?,
+            Self::NewsletterEnabled => config.newsletter.enabled = new_value.parse()?,
+            Self::NewsletterSubscribeEmail => set_subscribe_email(config, new_value)?,
+            Self::NewsletterImapServer => set_imap_server(config, new_value)?,
+            Self::NewsletterSmtpServer => set_smtp_server(config, new_value)?,
+            Self::SearchEnabled => config.search.enabled = new_value.parse()?,
+            Self::SearchLazyLoad => config.search.lazy_load = new_value.parse()?,
+            Self::SearchFields => {
+                let fields = list_items(&new_value);
+                if fields.is_empty() {
+                    anyhow::bail!("Search needs at least one field to index");
+                }
+                config.search.fields = fields;
+            }
+            Self::SearchExclude => config.search.exclude = list_items(&new_value),
+            Self::SearchFieldBoost { field } => set_field_boost(config, field, new_value)?,
+            Self::SeoStructuredData => config.structured_data.enabled = new_value.parse()?,
+            Self::SeoRobots => config.robots.enabled = new_value.parse()?,
+            Self::SeoCanonicalUrls => config.urls.canonical = new_value.parse()?,
         }
         Ok(())
     }
@@ -264,6 +495,12 @@ impl ConfigField {
                 | Self::BuildDrafts
                 | Self::BuildFuturePosts
                 | Self::DevAutoReload
+                | Self::NewsletterEnabled
+                | Self::SearchEnabled
+                | Self::SearchLazyLoad
+                | Self::SeoStructuredData
+                | Self::SeoRobots
+                | Self::SeoCanonicalUrls
                 | Self::ThemeOption {
                     value: toml::Value::Boolean(_),
                     ..
@@ -271,10 +508,62 @@ impl ConfigField {
         )
     }
 
+    /// How the field is edited
+    pub fn editor(&self) -> FieldEditor {
+        match self {
+            Self::BlogDescription => FieldEditor::Text,
+            Self::Menu | Self::DomainAliases | Self::SearchFields | Self::SearchExclude => {
+                FieldEditor::List
+            }
+            _ => FieldEditor::Line,
+        }
+    }
+
+    /// The value as it is edited: list fields one item per line
+    fn edit_text(&self, config: &Config) -> String {
+        match self {
+            Self::Menu => config
+                .menu
+                .iter()
+                .map(|item| format!("{} | {}", item.name, item.url))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ if self.editor() == FieldEditor::List => self
+                .get_value(config)
+                .split(", ")
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => self.get_value(config),
+        }
+    }
+
+    /// What to enter, for fields that need more than "the new value"
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::NewsletterSubscribeEmail => Some("Enter an email address, e.g. newsletter@example.com"),
+            Self::NewsletterImapServer => Some(
+                "Enter the IMAP host, e.g. imap.gmail.com (port 993 with TLS; change it in blogr.toml)",
+            ),
+            Self::NewsletterSmtpServer => Some(
+                "Enter the SMTP host, e.g. smtp.gmail.com (port 587 with TLS; change it in blogr.toml)",
+            ),
+            Self::Menu => Some("One link per line, as: Name | url (e.g. About | about.html)"),
+            Self::DomainAliases => Some("One domain per line, e.g. www.example.com"),
+            Self::SearchFields => Some("One field per line, e.g. title, tags and content"),
+            Self::SearchExclude => Some("One path prefix per line, e.g. drafts/"),
+            Self::SearchFieldBoost { .. } => {
+                Some("Enter a number greater than 0; matches in fields with higher boosts rank first")
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_numeric(&self) -> bool {
         matches!(
             self,
             Self::DevPort
+                | Self::SearchFieldBoost { .. }
                 | Self::ThemeOption {
                     value: toml::Value::Integer(_),
                     ..
@@ -299,6 +588,21 @@ fn get_all_theme_fields(config: &Config) -> Vec<ConfigField> {
     fields
 }
 
+fn get_search_fields(config: &Config) -> Vec<ConfigField> {
+    let mut fields = vec![
+        ConfigField::SearchEnabled,
+        ConfigField::SearchLazyLoad,
+        ConfigField::SearchFields,
+        ConfigField::SearchExclude,
+    ];
+    for field in &config.search.fields {
+        fields.push(ConfigField::SearchFieldBoost {
+            field: field.clone(),
+        });
+    }
+    fields
+}
+
 #[derive(Debug, Clone, Copy, EnumIter, PartialEq, Eq, Hash)]
 pub enum ConfigSection {
     Blog,
@@ -306,6 +610,9 @@ pub enum ConfigSection {
     Domain,
     Build,
     Development,
+    Newsletter,
+    Search,
+    Seo,
 }
 
 impl std::fmt::Display for ConfigSection {
@@ -316,6 +623,9 @@ impl std::fmt::Display for ConfigSection {
             Self::Domain => "Domain Settings",
             Self::Build => "Build Settings",
             Self::Development => "Development Settings",
+            Self::Newsletter => "Newsletter Settings",
+            Self::Search => "Search Settings",
+            Self::Seo => "SEO Settings",
         };
         write!(f, "{name}")
     }
@@ -331,15 +641,32 @@ impl ConfigSection {
                 ConfigField::BlogBaseUrl,
                 ConfigField::BlogLanguage,
                 ConfigField::BlogTimezone,
+                ConfigField::Menu,
             ],
             Self::Theme => get_all_theme_fields(config),
-            Self::Domain => vec![ConfigField::DomainPrimary, ConfigField::DomainEnforceHttps],
+            Self::Domain => vec![
+                ConfigField::DomainPrimary,
+                ConfigField::DomainEnforceHttps,
+                ConfigField::DomainAliases,
+            ],
             Self::Build => vec![
                 ConfigField::BuildOutputDir,
                 ConfigField::BuildDrafts,
                 ConfigField::BuildFuturePosts,
             ],
             Self::Development => vec![ConfigField::DevPort, ConfigField::DevAutoReload],
+            Self::Newsletter => vec![
+                ConfigField::NewsletterEnabled,
+                ConfigField::NewsletterSubscribeEmail,
+                ConfigField::NewsletterImapServer,
+                ConfigField::NewsletterSmtpServer,
+            ],
+            Self::Search => get_search_fields(config),
+            Self::Seo => vec![
+                ConfigField::SeoStructuredData,
+                ConfigField::SeoRobots,
+                ConfigField::SeoCanonicalUrls,
+            ],
         }
     }
 }
@@ -392,6 +719,7 @@ enum ConfigAppState {
     Edit(Box<Edit>),
     EditTheme(Box<EditTheme>),
     Help(Box<Help>),
+    ConfirmQuit(Box<ConfirmQuit>),
     Shutdown(Shutdown),
 }
 
@@ -406,6 +734,10 @@ impl ConfigAppState {
             Self::Edit(app) => app.render_edit_mode(frame, area, theme),
             Self::EditTheme(app) => app.render_table(frame, area, theme),
             Self::Help(app) => app.render_help_overlay(frame, theme), // Help is rendered as overlay
+            Self::ConfirmQuit(app) => {
+                app.browse_data.render_browse_mode(frame, area, theme);
+                app.render_prompt(frame, theme);
+            }
             Self::Shutdown(_) => {}
         }
     }
@@ -427,12 +759,19 @@ impl ConfigAppState {
     }
 
     fn render_status_bar(&self, frame: &mut Frame, area: Rect, theme: &TuiTheme) {
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border_style());
+        if self
+            .browse_data()
+            .is_some_and(|browse| browse.history.is_dirty())
+        {
+            block = block
+                .title("● Unsaved changes: s to save, u to undo")
+                .title_style(theme.title_style());
+        }
         let status = Paragraph::new(self.get_status())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(theme.border_style()),
-            )
+            .block(block)
             .style(theme.text_style());
 
         frame.render_widget(status, area);
@@ -444,17 +783,26 @@ impl ConfigAppState {
             Self::Edit(app) => app.handle_key_event(key),
             Self::EditTheme(app) => app.handle_key_event(key),
             Self::Help(app) => Ok(app.handle_key_event(key)),
+            Self::ConfirmQuit(app) => Ok(app.handle_key_event(key)),
             Self::Shutdown(app) => Ok(app.into()),
         }
     }
 
-    fn get_status(&self) -> String {
+    fn browse_data(&self) -> Option<&Browse> {
         match self {
-            Self::Browse(app) => app.status_message.clone(),
-            Self::Edit(app) => app.browse_data.status_message.clone(),
-            Self::EditTheme(app) => app.browse_data.status_message.clone(),
-            Self::Help(app) => app.browse_data.status_message.clone(),
-            Self::Shutdown(_) => "Shutting down".to_string(),
+            Self::Browse(app) => Some(app),
+            Self::Edit(app) => Some(&app.browse_data),
+            Self::EditTheme(app) => Some(&app.browse_data),
+            Self::Help(app) => Some(&app.browse_data),
+            Self::ConfirmQuit(app) => Some(&app.browse_data),
+            Self::Shutdown(_) => None,
+        }
+    }
+
+    fn get_status(&self) -> String {
+        match self.browse_data() {
+            Some(app) => app.status_message.clone(),
+            None => "Shutting down".to_string(),
         }
     }
 }
@@ -483,6 +831,12 @@ impl From<Help> for ConfigAppState {
     }
 }
 
+impl From<ConfirmQuit> for ConfigAppState {
+    fn from(value: ConfirmQuit) -> Self {
+        ConfigAppState::ConfirmQuit(Box::new(value))
+    }
+}
+
 impl From<Shutdown> for ConfigAppState {
     fn from(value: Shutdown) -> Self {
         ConfigAppState::Shutdown(value)
@@ -502,6 +856,62 @@ struct Browse {
     /// List state for field selection
     list_state: ListState,
     status_message: String,
+    /// Edits since the editor opened, for undo and redo
+    history: History,
+}
+
+/// Changes are staged in `Browse::config` until saved. Undo and redo swap
+/// it with earlier or later versions.
+#[derive(Default)]
+struct History {
+    undo: Vec<Config>,
+    redo: Vec<Config>,
+    /// The length of `undo` when the config on disk was current; `None` once
+    /// that version can't be reached
+    saved_at: Option<usize>,
+}
+
+impl History {
+    fn new() -> Self {
+        Self {
+            saved_at: Some(0),
+            ..Self::default()
+        }
+    }
+
+    /// Note a change, given the config from before it
+    fn record(&mut self, before: Config) {
+        if self
+            .saved_at
+            .is_some_and(|saved_at| saved_at > self.undo.len())
+        {
+            self.saved_at = None;
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// The config before the last change, if there is one
+    fn undo(&mut self, current: &Config) -> Option<Config> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current.clone());
+        Some(previous)
+    }
+
+    /// The config from the last undone change, if there is one
+    fn redo(&mut self, current: &Config) -> Option<Config> {
+        let next = self.redo.pop()?;
+        self.undo.push(current.clone());
+        Some(next)
+    }
+
+    fn mark_saved(&mut self) {
+        self.saved_at = Some(self.undo.len());
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.saved_at != Some(self.undo.len())
+    }
 }
 
 impl Browse {
@@ -518,14 +928,28 @@ impl Browse {
             config_index,
             list_layout,
             list_state,
-            status_message: "Navigate with ↑/↓, Enter to edit, 'q' to quit".to_string(),
+            status_message: "Navigate with ↑/↓, Enter to edit, 's' to save, 'q' to quit"
+                .to_string(),
+            history: History::new(),
         }
     }
 
     pub fn handle_key_event(self, key: KeyEvent) -> ConfigAppState {
         match key.code {
+            KeyCode::Char('q') | KeyCode::Char('Q') if self.history.is_dirty() => {
+                self.enter_confirm_quit_mode().into()
+            }
             KeyCode::Char('q') | KeyCode::Char('Q') => self.enter_shutdown_mode().into(),
             KeyCode::Char('h') | KeyCode::F(1) => self.enter_help_mode().into(),
+            KeyCode::Char('s') => self.save().into(),
+            KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.undo().into()
+            }
+            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.redo().into()
+            }
+            KeyCode::Char('u') => self.undo().into(),
+            KeyCode::Char('r') => self.redo().into(),
             KeyCode::Up => self.key_up().into(),
             KeyCode::Down => self.key_down().into(),
             KeyCode::Enter => match self.selected_field {
@@ -648,16 +1072,21 @@ impl Browse {
     }
 
     fn enter_edit_mode(mut self) -> Edit {
-        let edit_buffer = self.selected_field.get_value(&self.config);
+        let editor = self.selected_field.editor();
+        let text_area = TextArea::new(
+            &self.selected_field.edit_text(&self.config),
+            editor != FieldEditor::Line,
+        );
         self.status_message = format!(
-            "Editing {}: Press Enter to save, Esc to cancel",
-            self.selected_field
+            "Editing {}: Press {} to save, Esc to cancel",
+            self.selected_field,
+            save_key(editor)
         );
         Edit {
             new_config: self.config.clone(),
             target_field: self.selected_field.clone(),
             browse_data: self,
-            edit_buffer,
+            text_area,
         }
     }
 
@@ -672,21 +1101,153 @@ impl Browse {
     fn enter_shutdown_mode(self) -> Shutdown {
         Shutdown
     }
+
+    fn enter_confirm_quit_mode(mut self) -> ConfirmQuit {
+        self.status_message = "Unsaved changes".to_string();
+        ConfirmQuit { browse_data: self }
+    }
+
+    /// Stage a change to the config, to be saved with `s`
+    fn stage(mut self, new_config: Config, change: String) -> Self {
+        let before = std::mem::replace(&mut self.config, new_config);
+        self.history.record(before);
+        self.status_message = format!("{} (press 's' to save, 'u' to undo)", change);
+        self.refresh_layout()
+    }
+
+    fn undo(mut self) -> Self {
+        match self.history.undo(&self.config) {
+            Some(previous) => {
+                self.config = previous;
+                self.status_message = "Undid the last change".to_string();
+                self.refresh_layout()
+            }
+            None => {
+                self.status_message = "Nothing to undo".to_string();
+                self
+            }
+        }
+    }
+
+    fn redo(mut self) -> Self {
+        match self.history.redo(&self.config) {
+            Some(next) => {
+                self.config = next;
+                self.status_message = "Redid the change".to_string();
+                self.refresh_layout()
+            }
+            None => {
+                self.status_message = "Nothing to redo".to_string();
+                self
+            }
+        }
+    }
+
+    fn save(mut self) -> Self {
+        if !self.history.is_dirty() {
+            self.status_message = "No changes to save".to_string();
+            return self;
+        }
+        let config_path = self.project.root.join("blogr.toml");
+        if let Err(e) = self.config.save_to_file(&config_path) {
+            self.status_message = format!("Failed to save: {:#}", e);
+            return self;
+        }
+        self.history.mark_saved();
+        self.status_message = "Configuration saved successfully!".to_string();
+        self
+    }
+
+    /// Lay the fields out again, as sections such as search boosts depend on
+    /// the config, keeping the selected field where it still exists
+    fn refresh_layout(mut self) -> Self {
+        self.list_layout = HighLevelConfigList::new(&self.config);
+        match self.list_layout.index_of(&self.selected_field) {
+            Some(index) => self.config_index = index,
+            None => {
+                self.selected_field = ConfigField::BlogTitle;
+                self.config_index = self.list_layout.index_of(&self.selected_field).unwrap_or(2);
+            }
+        }
+        self.list_state.select(Some(self.config_index));
+        self
+    }
 }
 
 struct Edit {
     browse_data: Browse,
     target_field: ConfigField,
-    edit_buffer: String,
+    text_area: TextArea,
     new_config: Config,
 }
 
+/// Enter saves single-line fields; in multi-line ones it starts a new line
+fn save_key(editor: FieldEditor) -> &'static str {
+    match editor {
+        FieldEditor::Line => "Enter",
+        FieldEditor::Text | FieldEditor::List => "Ctrl+S",
+    }
+}
+
 struct Help {
     browse_data: Browse,
 }
 
 struct Shutdown;
 
+/// Asks what to do with unsaved changes on quit
+struct ConfirmQuit {
+    browse_data: Browse,
+}
+
+impl ConfirmQuit {
+    fn handle_key_event(self, key: KeyEvent) -> ConfigAppState {
+        match key.code {
+            KeyCode::Char('s') | KeyCode::Char('y') => {
+                let browse_data = self.browse_data.save();
+                match browse_data.history.is_dirty() {
+                    // Saving failed; stay so the error can be read
+                    true => browse_data.into(),
+                    false => Shutdown.into(),
+                }
+            }
+            KeyCode::Char('d') => Shutdown.into(),
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('c') => {
+                let mut browse_data = self.browse_data;
+                browse_data.status_message = "Quit cancelled".to_string();
+                browse_data.into()
+            }
+            _ => self.into(),
+        }
+    }
+
+    fn render_prompt(&self, frame: &mut Frame, theme: &TuiTheme) {
+        let area = frame.area();
+        let width = 52.min(area.width);
+        let height = 7.min(area.height);
+        let popup_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, popup_area);
+
+        let prompt = Paragraph::new(
+            "You have unsaved changes.\n\ns: save and quit   d: discard and quit\nEsc: keep editing",
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Quit")
+                .border_style(theme.focused_border_style()),
+        )
+        .wrap(Wrap { trim: true })
+        .style(theme.text_style());
+        frame.render_widget(prompt, popup_area);
+    }
+}
+
 impl Edit {
     pub fn handle_key_event(mut self, key: KeyEvent) -> AppResult<ConfigAppState> {
         match key.code {
@@ -695,71 +1256,75 @@ impl Edit {
                 browse_data.status_message = "Edit cancelled".to_string();
                 Ok(browse_data.into())
             }
-            KeyCode::Enter => self.apply(),
-            KeyCode::Backspace => {
-                self.edit_buffer.pop();
-                Ok(self.into())
-            }
-            KeyCode::Char(c) => {
-                self.edit_buffer.push(c);
+            KeyCode::Enter if self.target_field.editor() == FieldEditor::Line => self.apply(),
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => self.apply(),
+            _ => {
+                self.text_area.handle_key_event(key);
                 Ok(self.into())
             }
-            _ => Ok(self.into()),
         }
     }
 
     fn render_edit_mode(&self, frame: &mut Frame, area: Rect, theme: &TuiTheme) {
+        let editor = self.target_field.editor();
+        let constraints = match editor {
+            FieldEditor::Line => [Constraint::Length(5), Constraint::Min(0)],
+            FieldEditor::Text | FieldEditor::List => [Constraint::Min(8), Constraint::Length(7)],
+        };
         let edit_area = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(5), Constraint::Min(0)])
+            .constraints(constraints)
             .split(area);
 
-        let input_text = if self.browse_data.selected_field.is_boolean() {
-            format!("{} (true/false)", self.edit_buffer)
-        } else if self.browse_data.selected_field.is_numeric() {
-            format!("{} (number)", self.edit_buffer)
+        let kind = if self.target_field.is_boolean() {
+            " (true/false)"
+        } else if self.target_field.is_numeric() {
+            " (number)"
+        } else if editor == FieldEditor::List {
+            " (one per line)"
         } else {
-            self.edit_buffer.clone()
+            ""
         };
-
-        let input = Paragraph::new(input_text)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("Editing: {}", self.browse_data.selected_field))
-                    .border_style(theme.focused_border_style()),
-            )
-            .style(theme.text_style());
-
-        frame.render_widget(input, edit_area[0]);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Editing: {}{}", self.target_field, kind))
+            .border_style(theme.focused_border_style());
+        self.text_area.render(frame, edit_area[0], block, theme);
 
         let help_text = if self.browse_data.selected_field.is_boolean() {
             "Enter 'true' or 'false'"
         } else if self.browse_data.selected_field.is_numeric() {
             "Enter a valid number"
         } else {
-            "Enter the new value"
+            self.browse_data
+                .selected_field
+                .hint()
+                .unwrap_or("Enter the new value")
         };
 
-        let help = Paragraph::new(format!(
-            "{}\n\nPress Enter to save, Esc to cancel",
-            help_text
-        ))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Help")
-                .border_style(theme.border_style()),
-        )
-        .wrap(Wrap { trim: true })
-        .style(theme.text_style());
+        let keys = match editor {
+            FieldEditor::Line => "Press Enter to save, Esc to cancel",
+            FieldEditor::Text | FieldEditor::List => {
+                "Enter starts a new line, arrows move the cursor.\nPress Ctrl+S to save, Esc to cancel"
+            }
+        };
+        let help = Paragraph::new(format!("{}\n\n{}", help_text, keys))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Help")
+                    .border_style(theme.border_style()),
+            )
+            .wrap(Wrap { trim: true })
+            .style(theme.text_style());
 
         frame.render_widget(help, edit_area[1]);
     }
 
     fn apply(mut self) -> AppResult<ConfigAppState> {
-        let new_value = self.edit_buffer.trim().to_string();
-        if new_value.is_empty() {
+        let new_value = self.text_area.text().trim().to_string();
+        // An empty list is a value; other empty fields discard the edit
+        if new_value.is_empty() && self.target_field.editor() != FieldEditor::List {
             self.browse_data.status_message = "Edit discarded".to_string();
             return Ok(self.browse_data.into());
         }
@@ -769,7 +1334,8 @@ impl Edit {
             return Ok(self.into());
         };
 
-        self.browse_data = save_and_refresh(self.browse_data, self.new_config.clone())?;
+        let change = format!("Changed {}", self.target_field);
+        self.browse_data = self.browse_data.stage(self.new_config.clone(), change);
         Ok(self.enter_browse_mode().into())
     }
 
@@ -817,8 +1383,10 @@ impl Help {
             "  Esc       - Cancel edit",
             "",
             "Actions:",
-            "  s         - Save configuration",
-            "  q         - Quit",
+            "  s         - Save changes to blogr.toml",
+            "  u/Ctrl+Z  - Undo the last change",
+            "  r/Ctrl+Y  - Redo",
+            "  q         - Quit (asks first if there are unsaved changes)",
             "  h/F1      - Toggle this help",
             "",
             "Field Types:",
@@ -988,9 +1556,9 @@ impl EditTheme {
             .into_iter()
             .map(|(field_name, config)| (field_name, config.value))
             .collect::<HashMap<String, toml::Value>>();
+        let change = format!("Switched to the {} theme", theme.name);
         self.new_config.set_theme(theme.name, default_theme_config);
-        //save
-        self.browse_data = save_and_refresh(self.browse_data, self.new_config.clone())?;
+        self.browse_data = self.browse_data.stage(self.new_config.clone(), change);
         Ok(self.enter_browse_mode())
     }
 
@@ -999,11 +1567,241 @@ impl EditTheme {
     }
 }
 
-fn save_and_refresh(mut browse_data: Browse, new_config: Config) -> AppResult<Browse> {
-    let config_path = browse_data.project.root.join("blogr.toml");
-    browse_data.config = new_config;
-    browse_data.config.save_to_file(&config_path)?;
-    browse_data.list_layout = HighLevelConfigList::new(&browse_data.config);
-    browse_data.status_message = "Configuration saved successfully!".to_string();
-    Ok(browse_data)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn browse(temp_dir: &TempDir) -> Browse {
+        let config = Config::default();
+        let project = Project::new(temp_dir.path().to_path_buf(), config.clone());
+        Browse::new(config, project)
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    /// Open the editor for a field
+    fn edit(mut browse: Browse, field: ConfigField) -> ConfigAppState {
+        browse.selected_field = field;
+        browse.enter_edit_mode().into()
+    }
+
+    fn send(state: ConfigAppState, keys: &[KeyEvent]) -> ConfigAppState {
+        keys.iter()
+            .fold(state, |state, key| state.handle_key_event(*key).unwrap())
+    }
+
+    fn type_text(state: ConfigAppState, text: &str) -> ConfigAppState {
+        let keys: Vec<KeyEvent> = text
+            .chars()
+            .map(|c| match c {
+                '\n' => key(KeyCode::Enter),
+                c => key(KeyCode::Char(c)),
+            })
+            .collect();
+        send(state, &keys)
+    }
+
+    fn into_browse(state: ConfigAppState) -> Box<Browse> {
+        match state {
+            ConfigAppState::Browse(browse) => browse,
+            _ => panic!("expected the field list"),
+        }
+    }
+
+    #[test]
+    fn test_edit_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut browse = browse(&temp_dir);
+        browse.config.menu = vec![MenuItem {
+            name: "Home".to_string(),
+            url: "index.html".to_string(),
+        }];
+        assert_eq!(ConfigField::Menu.editor(), FieldEditor::List);
+        assert_eq!(
+            ConfigField::Menu.edit_text(&browse.config),
+            "Home | index.html"
+        );
+
+        // Enter adds a line rather than saving
+        let state = type_text(edit(browse, ConfigField::Menu), "\nAbout|about.html\n\n");
+        assert!(matches!(state, ConfigAppState::Edit(_)));
+        let browse = into_browse(send(state, &[ctrl('s')]));
+        let menu: Vec<_> = browse
+            .config
+            .menu
+            .iter()
+            .map(|item| (item.name.as_str(), item.url.as_str()))
+            .collect();
+        assert_eq!(menu, [("Home", "index.html"), ("About", "about.html")]);
+
+        // A line without a link keeps the editor open with the error
+        let state = type_text(edit(*browse, ConfigField::Menu), "\nBlog");
+        let state = send(state, &[ctrl('s')]);
+        let ConfigAppState::Edit(edit_state) = &state else {
+            panic!("the bad entry was accepted");
+        };
+        assert!(edit_state
+            .browse_data
+            .status_message
+            .contains("needs a name and a link"));
+        let browse = into_browse(send(state, &[key(KeyCode::Esc)]));
+        assert_eq!(browse.config.menu.len(), 2);
+    }
+
+    #[test]
+    fn test_edit_domain_aliases() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = type_text(
+            edit(browse(&temp_dir), ConfigField::DomainAliases),
+            "www.example.com\n  blog.example.com  ",
+        );
+        let browse = into_browse(send(state, &[ctrl('s')]));
+        assert_eq!(
+            browse.config.blog.domains.as_ref().unwrap().aliases,
+            ["www.example.com", "blog.example.com"]
+        );
+        assert_eq!(
+            ConfigField::DomainAliases.edit_text(&browse.config),
+            "www.example.com\nblog.example.com"
+        );
+
+        // Clearing every line removes the aliases rather than discarding
+        // the edit
+        let state = send(
+            edit(*browse, ConfigField::DomainAliases),
+            &[ctrl('u'), key(KeyCode::Backspace), ctrl('u'), ctrl('s')],
+        );
+        let browse = into_browse(state);
+        assert!(browse
+            .config
+            .blog
+            .domains
+            .as_ref()
+            .unwrap()
+            .aliases
+            .is_empty());
+    }
+
+    #[test]
+    fn test_edit_description() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut browse = browse(&temp_dir);
+        browse.config.blog.description = String::new();
+        let state = type_text(
+            edit(browse, ConfigField::BlogDescription),
+            "Notes on Rust.\nAnd other things.",
+        );
+        let browse = into_browse(send(state, &[ctrl('s')]));
+        assert_eq!(
+            browse.config.blog.description,
+            "Notes on Rust.\nAnd other things."
+        );
+
+        // Single-line fields save on Enter
+        let state = type_text(edit(*browse, ConfigField::BlogAuthor), "!\n");
+        assert_eq!(into_browse(state).config.blog.author, "Anonymous!");
+    }
+
+    fn titled(title: &str) -> Config {
+        let mut config = Config::default();
+        config.blog.title = title.to_string();
+        config
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = History::new();
+        assert!(!history.is_dirty());
+        assert!(history.undo(&titled("A")).is_none());
+
+        history.record(titled("A"));
+        history.record(titled("B"));
+        assert!(history.is_dirty());
+
+        // Undoing back to the saved version is clean again
+        assert_eq!(history.undo(&titled("C")).unwrap().blog.title, "B");
+        assert_eq!(history.undo(&titled("B")).unwrap().blog.title, "A");
+        assert!(!history.is_dirty());
+        assert_eq!(history.redo(&titled("A")).unwrap().blog.title, "B");
+        assert_eq!(history.redo(&titled("B")).unwrap().blog.title, "C");
+        assert!(history.redo(&titled("C")).is_none());
+        assert!(history.is_dirty());
+
+        history.mark_saved();
+        assert!(!history.is_dirty());
+        history.undo(&titled("C"));
+        assert!(history.is_dirty());
+
+        // A new change drops the redo stack. The saved version can no
+        // longer be reached, so every state from here on is unsaved.
+        history.record(titled("B"));
+        assert!(history.redo(&titled("D")).is_none());
+        assert!(history.is_dirty());
+        history.undo(&titled("D"));
+        assert!(history.is_dirty());
+    }
+
+    #[test]
+    fn test_undo_redo_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("blogr.toml");
+        let state = type_text(edit(browse(&temp_dir), ConfigField::BlogAuthor), "!\n");
+        let browse = into_browse(state);
+        assert_eq!(browse.config.blog.author, "Anonymous!");
+        assert!(browse.history.is_dirty());
+        // Changes are only staged
+        assert!(!config_path.exists());
+
+        let browse = into_browse(send((*browse).into(), &[key(KeyCode::Char('u'))]));
+        assert_eq!(browse.config.blog.author, "Anonymous");
+        assert!(!browse.history.is_dirty());
+        let browse = into_browse(send((*browse).into(), &[ctrl('y')]));
+        assert_eq!(browse.config.blog.author, "Anonymous!");
+        let browse = into_browse(send(
+            (*browse).into(),
+            &[ctrl('z'), key(KeyCode::Char('r'))],
+        ));
+        assert_eq!(browse.config.blog.author, "Anonymous!");
+
+        let browse = into_browse(send((*browse).into(), &[key(KeyCode::Char('s'))]));
+        assert!(!browse.history.is_dirty());
+        let saved = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(saved.blog.author, "Anonymous!");
+
+        // Nothing to save, so quitting doesn't ask
+        let state = send((*browse).into(), &[key(KeyCode::Char('q'))]);
+        assert!(matches!(state, ConfigAppState::Shutdown(_)));
+    }
+
+    #[test]
+    fn test_quit_with_unsaved_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("blogr.toml");
+        let state = type_text(edit(browse(&temp_dir), ConfigField::BlogAuthor), "!\n");
+
+        let state = send(state, &[key(KeyCode::Char('q'))]);
+        assert!(matches!(state, ConfigAppState::ConfirmQuit(_)));
+        let state = send(state, &[key(KeyCode::Esc)]);
+        let kept = into_browse(state);
+        assert!(kept.history.is_dirty());
+
+        // Discarding leaves the file alone
+        let state = send((*kept).into(), &[key(KeyCode::Char('q'))]);
+        let discarded = send(state, &[key(KeyCode::Char('d'))]);
+        assert!(matches!(discarded, ConfigAppState::Shutdown(_)));
+        assert!(!config_path.exists());
+
+        let state = type_text(edit(browse(&temp_dir), ConfigField::BlogAuthor), "?\n");
+        let state = send(state, &[key(KeyCode::Char('q')), key(KeyCode::Char('s'))]);
+        assert!(matches!(state, ConfigAppState::Shutdown(_)));
+        let saved = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(saved.blog.author, "Anonymous?");
+    }
 }
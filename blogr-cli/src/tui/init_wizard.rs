@@ -0,0 +1,932 @@
+//! Step-by-step setup for `blogr init`
+//!
+//! Collects the answers the plain prompts ask for, plus theme, domain and
+//! newsletter settings, and turns them into a [`Config`] for the new project.
+
+use crate::config::{Config, EnvConfig};
+use crate::tui::theme::TuiTheme;
+use crate::utils::Utils;
+use blogr_themes::{get_all_themes, SiteType, ThemeInfo};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Title,
+    Author,
+    Description,
+    SiteType,
+    Theme,
+    GitHubUsername,
+    GitHubRepo,
+    Domain,
+    Newsletter,
+    NewsletterEmail,
+    Summary,
+}
+
+impl Step {
+    fn label(self) -> &'static str {
+        match self {
+            Step::Title => "Title",
+            Step::Author => "Author",
+            Step::Description => "Description",
+            Step::SiteType => "Site type",
+            Step::Theme => "Theme",
+            Step::GitHubUsername => "GitHub username",
+            Step::GitHubRepo => "GitHub repository",
+            Step::Domain => "Custom domain",
+            Step::Newsletter => "Newsletter",
+            Step::NewsletterEmail => "Subscribe address",
+            Step::Summary => "Create project",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Step::Title => "The name of your site. It is also used for the project directory.",
+            Step::Author => "Shown on posts and in feeds.",
+            Step::Description => "A one-line summary used by themes and search engines. Leave empty for a default.",
+            Step::SiteType => "A blog publishes posts. A personal website is built from content.md. A hybrid site has both, with the blog under /blog/.",
+            Step::Theme => "Pick a theme; the preview shows its colours. You can switch later with 'blogr theme set'.",
+            Step::GitHubUsername => "Used to deploy to GitHub Pages. Leave empty to skip GitHub.",
+            Step::GitHubRepo => "Repository to create and deploy from.",
+            Step::Domain => "A custom domain such as blog.example.com. Leave empty to use GitHub Pages.",
+            Step::Newsletter => "Let readers subscribe by email and send them new posts.",
+            Step::NewsletterEmail => "Address readers email to subscribe. Blogr fetches subscriptions from its inbox.",
+            Step::Summary => "Press Enter to create the project, or Esc to go back and change something.",
+        }
+    }
+
+    fn is_text(self) -> bool {
+        !matches!(
+            self,
+            Step::SiteType | Step::Theme | Step::Newsletter | Step::Summary
+        )
+    }
+}
+
+/// Outcome of the wizard once it stops
+pub enum WizardResult {
+    Create(Box<Config>),
+    Cancelled,
+}
+
+/// Site types offered by the wizard, with their labels
+const SITE_TYPES: [(SiteType, &str); 3] = [
+    (SiteType::Blog, "Blog"),
+    (SiteType::Personal, "Personal website"),
+    (SiteType::Hybrid, "Personal website with a blog"),
+];
+
+pub struct InitWizard {
+    step: Step,
+    title: String,
+    author: String,
+    description: String,
+    site_type: SiteType,
+    themes: Vec<(ThemeInfo, Style)>,
+    theme_state: ListState,
+    github_username: String,
+    github_repo: String,
+    domain: String,
+    newsletter: bool,
+    newsletter_email: String,
+    error: Option<String>,
+    result: Option<WizardResult>,
+    theme: TuiTheme,
+}
+
+impl InitWizard {
+    pub fn new(theme: TuiTheme) -> Self {
+        let themes = get_all_themes()
+            .iter()
+            .map(|theme| (theme.info(), theme.preview_tui_style()))
+            .collect();
+        let mut wizard = Self {
+            step: Step::Title,
+            title: String::new(),
+            author: EnvConfig::git_author_name()
+                .filter(|name| !name.is_empty())
+                .unwrap_or_default(),
+            description: String::new(),
+            site_type: SiteType::Blog,
+            themes,
+            theme_state: ListState::default(),
+            github_username: EnvConfig::github_username().unwrap_or_default(),
+            github_repo: String::new(),
+            domain: String::new(),
+            newsletter: false,
+            newsletter_email: EnvConfig::git_author_email()
+                .filter(|email| email.contains('@'))
+                .unwrap_or_default(),
+            error: None,
+            result: None,
+            theme,
+        };
+        wizard.select_default_theme();
+        wizard
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+
+    pub fn into_result(self) -> WizardResult {
+        self.result.unwrap_or(WizardResult::Cancelled)
+    }
+
+    /// Steps that apply given the answers so far
+    fn steps(&self) -> Vec<Step> {
+        let mut steps = vec![
+            Step::Title,
+            Step::Author,
+            Step::Description,
+            Step::SiteType,
+            Step::Theme,
+            Step::GitHubUsername,
+        ];
+        if !self.github_username.trim().is_empty() {
+            steps.push(Step::GitHubRepo);
+        }
+        steps.push(Step::Domain);
+        if self.site_type != SiteType::Personal {
+            steps.push(Step::Newsletter);
+            if self.newsletter {
+                steps.push(Step::NewsletterEmail);
+            }
+        }
+        steps.push(Step::Summary);
+        steps
+    }
+
+    /// Themes for the chosen site type
+    fn theme_choices(&self) -> Vec<&(ThemeInfo, Style)> {
+        self.themes
+            .iter()
+            .filter(|(info, _)| info.supports(&self.site_type))
+            .collect()
+    }
+
+    fn selected_theme(&self) -> Option<&(ThemeInfo, Style)> {
+        self.theme_choices()
+            .get(self.theme_state.selected().unwrap_or(0))
+            .copied()
+    }
+
+    /// Select the theme `blogr init` would use for the chosen site type
+    fn select_default_theme(&mut self) {
+        let default = match self.site_type {
+            SiteType::Personal => "Dark Minimal",
+            SiteType::Blog | SiteType::Hybrid => "Minimal Retro",
+        };
+        let index = self
+            .theme_choices()
+            .iter()
+            .position(|(info, _)| info.name == default)
+            .unwrap_or(0);
+        self.theme_state.select(Some(index));
+    }
+
+    fn text_mut(&mut self) -> Option<&mut String> {
+        match self.step {
+            Step::Title => Some(&mut self.title),
+            Step::Author => Some(&mut self.author),
+            Step::Description => Some(&mut self.description),
+            Step::GitHubUsername => Some(&mut self.github_username),
+            Step::GitHubRepo => Some(&mut self.github_repo),
+            Step::Domain => Some(&mut self.domain),
+            Step::NewsletterEmail => Some(&mut self.newsletter_email),
+            _ => None,
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.result = Some(WizardResult::Cancelled);
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.back(),
+            KeyCode::Enter => self.advance(),
+            KeyCode::Up | KeyCode::Down => self.move_selection(key.code == KeyCode::Up),
+            KeyCode::Backspace => {
+                if let Some(text) = self.text_mut() {
+                    text.pop();
+                }
+            }
+            KeyCode::Char(c) if self.step.is_text() => {
+                if let Some(text) = self.text_mut() {
+                    text.push(c);
+                }
+            }
+            KeyCode::Char('y') if self.step == Step::Newsletter => self.newsletter = true,
+            KeyCode::Char('n') if self.step == Step::Newsletter => self.newsletter = false,
+            _ => {}
+        }
+    }
+
+    fn move_selection(&mut self, up: bool) {
+        match self.step {
+            Step::SiteType => {
+                let index = SITE_TYPES
+                    .iter()
+                    .position(|(site_type, _)| *site_type == self.site_type)
+                    .unwrap_or(0);
+                let index = if up {
+                    index.saturating_sub(1)
+                } else {
+                    (index + 1).min(SITE_TYPES.len() - 1)
+                };
+                self.site_type = SITE_TYPES[index].0.clone();
+                self.select_default_theme();
+            }
+            Step::Theme => {
+                let count = self.theme_choices().len();
+                let selected = self.theme_state.selected().unwrap_or(0);
+                let selected = if up {
+                    selected.saturating_sub(1)
+                } else {
+                    (selected + 1).min(count.saturating_sub(1))
+                };
+                self.theme_state.select(Some(selected));
+            }
+            Step::Newsletter => self.newsletter = !self.newsletter,
+            _ => {}
+        }
+    }
+
+    fn back(&mut self) {
+        self.error = None;
+        let steps = self.steps();
+        match steps.iter().position(|step| *step == self.step) {
+            Some(0) | None => self.result = Some(WizardResult::Cancelled),
+            Some(index) => self.step = steps[index - 1],
+        }
+    }
+
+    fn advance(&mut self) {
+        if let Err(message) = self.check_step() {
+            self.error = Some(message);
+            return;
+        }
+        self.error = None;
+
+        if self.step == Step::Summary {
+            self.result = Some(WizardResult::Create(Box::new(self.config())));
+            return;
+        }
+
+        let steps = self.steps();
+        if let Some(index) = steps.iter().position(|step| *step == self.step) {
+            self.step = steps[index + 1];
+        }
+        if self.step == Step::GitHubRepo && self.github_repo.is_empty() {
+            self.github_repo = Utils::slugify(&self.title);
+        }
+    }
+
+    /// Validate the answer to the current step
+    fn check_step(&self) -> Result<(), String> {
+        match self.step {
+            Step::Title if self.title.trim().is_empty() => Err("The title cannot be empty".into()),
+            Step::Author if self.author.trim().is_empty() => {
+                Err("The author cannot be empty".into())
+            }
+            Step::GitHubUsername
+                if !self.github_username.trim().is_empty()
+                    && !Utils::is_valid_github_username(self.github_username.trim()) =>
+            {
+                Err(format!(
+                    "'{}' is not a valid GitHub username",
+                    self.github_username.trim()
+                ))
+            }
+            Step::GitHubRepo if !Utils::is_valid_github_repo_name(self.github_repo.trim()) => {
+                Err(format!(
+                    "'{}' is not a valid repository name",
+                    self.github_repo.trim()
+                ))
+            }
+            Step::Domain
+                if !self.domain.trim().is_empty()
+                    && (!self.domain.contains('.') || self.domain.contains("://")) =>
+            {
+                Err("Enter a bare domain such as blog.example.com".into())
+            }
+            Step::NewsletterEmail if !self.newsletter_email.contains('@') => {
+                Err("Enter the email address readers subscribe with".into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Configuration built from the answers
+    fn config(&self) -> Config {
+        let title = self.title.trim().to_string();
+        let author = self.author.trim().to_string();
+        let description = match self.description.trim() {
+            "" if self.site_type != SiteType::Blog => format!("Personal website of {author}"),
+            "" => format!("A blog by {author}"),
+            description => description.to_string(),
+        };
+        let (github_username, github_repo) = match self.github_username.trim() {
+            "" => (None, None),
+            username => (
+                Some(username.to_string()),
+                Some(self.github_repo.trim().to_string()),
+            ),
+        };
+
+        let mut config = match self.site_type {
+            SiteType::Personal => {
+                Config::new_personal(title, author, description, github_username, github_repo)
+            }
+            SiteType::Hybrid => {
+                Config::new_hybrid(title, author, description, github_username, github_repo)
+            }
+            SiteType::Blog => {
+                Config::new_with_defaults(title, author, description, github_username, github_repo)
+            }
+        };
+
+        if let Some((info, _)) = self.selected_theme() {
+            let theme_config = info
+                .config_schema
+                .iter()
+                .map(|(name, option)| (name.clone(), option.value.clone()))
+                .collect();
+            config.set_theme(info.name.to_lowercase().replace(' ', "-"), theme_config);
+        }
+
+        if !self.domain.trim().is_empty() {
+            config.set_primary_domain(self.domain.trim().to_string(), true);
+            config.sync_base_url_with_domains();
+        }
+
+        if self.site_type != SiteType::Personal && self.newsletter {
+            config.newsletter.enabled = true;
+            config.newsletter.subscribe_email = Some(self.newsletter_email.trim().to_string());
+            config.newsletter.sender_name = Some(config.blog.author.clone());
+        }
+
+        config
+    }
+
+    pub fn render(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Status bar
+            ])
+            .split(frame.area());
+
+        let steps = self.steps();
+        let position = steps
+            .iter()
+            .position(|step| *step == self.step)
+            .unwrap_or(0);
+        let header = Paragraph::new(format!(
+            "Step {} of {}: {}",
+            position + 1,
+            steps.len(),
+            self.step.label()
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.border_style())
+                .title("Blogr Setup")
+                .title_style(self.theme.title_style()),
+        )
+        .style(self.theme.text_style());
+        frame.render_widget(header, chunks[0]);
+
+        let main = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(26), Constraint::Min(0)])
+            .split(chunks[1]);
+        self.render_steps(frame, main[0], &steps, position);
+        match self.step {
+            Step::SiteType => self.render_site_type(frame, main[1]),
+            Step::Theme => self.render_themes(frame, main[1]),
+            Step::Newsletter => self.render_newsletter(frame, main[1]),
+            Step::Summary => self.render_summary(frame, main[1]),
+            _ => self.render_text_input(frame, main[1]),
+        }
+
+        let status = match &self.error {
+            Some(error) => {
+                Paragraph::new(format!("⚠ {error}")).style(Style::default().fg(Color::Red))
+            }
+            None => Paragraph::new(self.key_hints()).style(self.theme.text_style()),
+        };
+        frame.render_widget(
+            status.block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style()),
+            ),
+            chunks[2],
+        );
+    }
+
+    fn key_hints(&self) -> &'static str {
+        match self.step {
+            Step::SiteType | Step::Theme => "↑/↓ choose • Enter next • Esc back • Ctrl+C quit",
+            Step::Newsletter => "y/n or ↑/↓ toggle • Enter next • Esc back • Ctrl+C quit",
+            Step::Summary => "Enter create project • Esc back • Ctrl+C quit",
+            _ => "Type your answer • Enter next • Esc back • Ctrl+C quit",
+        }
+    }
+
+    fn render_steps(&self, frame: &mut Frame, area: Rect, steps: &[Step], position: usize) {
+        let items: Vec<ListItem> = steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let (marker, style) = if index < position {
+                    ("✓", Style::default().fg(self.theme.secondary_color))
+                } else if index == position {
+                    (
+                        "▶",
+                        Style::default()
+                            .fg(self.theme.primary_color)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    (" ", Style::default().fg(self.theme.border_color))
+                };
+                ListItem::new(Line::from(Span::styled(
+                    format!(" {marker} {}", step.label()),
+                    style,
+                )))
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Steps")
+                .border_style(self.theme.border_style()),
+        );
+        frame.render_widget(list, area);
+    }
+
+    /// Question text above the answer area
+    fn question(&self, frame: &mut Frame, area: Rect) -> Rect {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
+            .split(area);
+        let help = Paragraph::new(self.step.help())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.step.label())
+                    .border_style(self.theme.border_style()),
+            )
+            .wrap(Wrap { trim: true })
+            .style(self.theme.text_style());
+        frame.render_widget(help, chunks[0]);
+        chunks[1]
+    }
+
+    fn render_text_input(&mut self, frame: &mut Frame, area: Rect) {
+        let area = self.question(frame, area);
+        let value = self.text_mut().map(|text| text.clone()).unwrap_or_default();
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled(value, self.theme.text_style()),
+            Span::styled("█", Style::default().fg(self.theme.cursor_color())),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.focused_border_style()),
+        );
+        frame.render_widget(input, Rect { height: 3, ..area });
+    }
+
+    fn render_choice(&self, frame: &mut Frame, area: Rect, options: &[(&str, bool)]) {
+        let area = self.question(frame, area);
+        let lines: Vec<Line> = options
+            .iter()
+            .map(|(label, selected)| {
+                if *selected {
+                    Line::from(Span::styled(
+                        format!(" ● {label}"),
+                        Style::default()
+                            .fg(self.theme.primary_color)
+                            .add_modifier(Modifier::BOLD),
+                    ))
+                } else {
+                    Line::from(Span::styled(format!(" ○ {label}"), self.theme.text_style()))
+                }
+            })
+            .collect();
+        let choice = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.focused_border_style()),
+        );
+        frame.render_widget(
+            choice,
+            Rect {
+                height: options.len() as u16 + 2,
+                ..area
+            },
+        );
+    }
+
+    fn render_site_type(&self, frame: &mut Frame, area: Rect) {
+        let options: Vec<(&str, bool)> = SITE_TYPES
+            .iter()
+            .map(|(site_type, label)| (*label, *site_type == self.site_type))
+            .collect();
+        self.render_choice(frame, area, &options);
+    }
+
+    fn render_newsletter(&self, frame: &mut Frame, area: Rect) {
+        self.render_choice(
+            frame,
+            area,
+            &[
+                ("Enable the newsletter", self.newsletter),
+                ("Not now", !self.newsletter),
+            ],
+        );
+    }
+
+    fn render_themes(&mut self, frame: &mut Frame, area: Rect) {
+        let area = self.question(frame, area);
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(24), Constraint::Min(0)])
+            .split(area);
+
+        let items: Vec<ListItem> = self
+            .theme_choices()
+            .iter()
+            .map(|(info, _)| ListItem::new(format!(" {}", info.name)))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Themes")
+                    .border_style(self.theme.focused_border_style()),
+            )
+            .style(self.theme.text_style())
+            .highlight_style(
+                Style::default()
+                    .bg(self.theme.primary_color)
+                    .fg(self.theme.background_color)
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_stateful_widget(list, chunks[0], &mut self.theme_state);
+
+        if let Some((info, style)) = self.selected_theme() {
+            self.render_theme_preview(frame, chunks[1], info, *style);
+        }
+    }
+
+    /// A mock page drawn in the theme's colours, followed by its details
+    fn render_theme_preview(&self, frame: &mut Frame, area: Rect, info: &ThemeInfo, style: Style) {
+        let title = if self.title.trim().is_empty() {
+            "My Site"
+        } else {
+            self.title.trim()
+        };
+        let accent = style.add_modifier(Modifier::BOLD);
+        let muted = style.add_modifier(Modifier::DIM);
+
+        let mut lines = vec![
+            Line::from(Span::styled(format!(" {title}"), accent)),
+            Line::from(Span::styled(" Home · Archive · Tags", muted)),
+            Line::from(""),
+        ];
+        if self.site_type == SiteType::Personal {
+            lines.push(Line::from(Span::styled(" About me", accent)));
+            lines.push(Line::from(Span::styled(
+                " Developer, writer and maker of things.",
+                style,
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(" Hello, world", accent)));
+            lines.push(Line::from(Span::styled(" 3 min read · #welcome", muted)));
+            lines.push(Line::from(Span::styled(
+                " The first post on the new blog.",
+                style,
+            )));
+        }
+        lines.push(Line::from(""));
+
+        let mut colours: Vec<(&String, Color)> = info
+            .config_schema
+            .iter()
+            .filter_map(|(name, option)| {
+                let value = option.value.as_str()?;
+                Some((name, TuiTheme::parse_color(value)?))
+            })
+            .collect();
+        colours.sort_by_key(|(name, _)| name.as_str());
+
+        let mut details = vec![
+            Line::from(Span::styled(
+                format!("{} v{} by {}", info.name, info.version, info.author),
+                self.theme.title_style(),
+            )),
+            Line::from(Span::styled(
+                info.description.clone(),
+                self.theme.text_style(),
+            )),
+            Line::from(""),
+        ];
+        details.extend(colours.into_iter().map(|(name, colour)| {
+            Line::from(vec![
+                Span::styled("██ ", Style::default().fg(colour)),
+                Span::styled(name.clone(), self.theme.text_style()),
+            ])
+        }));
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(lines.len() as u16 + 2),
+                Constraint::Min(0),
+            ])
+            .split(area);
+        let page = Paragraph::new(lines).style(style).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Preview")
+                .border_style(style),
+        );
+        frame.render_widget(page, chunks[0]);
+
+        let details = Paragraph::new(details)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.border_style()),
+            )
+            .wrap(Wrap { trim: true });
+        frame.render_widget(details, chunks[1]);
+    }
+
+    fn render_summary(&self, frame: &mut Frame, area: Rect) {
+        let area = self.question(frame, area);
+        let config = self.config();
+        let or_none = |value: Option<&str>| value.unwrap_or("(none)").to_string();
+
+        let rows = [
+            ("Title", config.blog.title.clone()),
+            ("Author", config.blog.author.clone()),
+            ("Description", config.blog.description.clone()),
+            (
+                "Site type",
+                SITE_TYPES
+                    .iter()
+                    .find(|(site_type, _)| *site_type == self.site_type)
+                    .map(|(_, label)| label.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("Theme", config.theme.name.clone()),
+            (
+                "GitHub",
+                or_none(
+                    config
+                        .github
+                        .as_ref()
+                        .map(|github| format!("{}/{}", github.username, github.repository))
+                        .as_deref(),
+                ),
+            ),
+            ("URL", config.get_effective_base_url()),
+            (
+                "Newsletter",
+                or_none(config.newsletter.subscribe_email.as_deref()),
+            ),
+        ];
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {label:<12}"),
+                        Style::default()
+                            .fg(self.theme.primary_color)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(value, self.theme.text_style()),
+                ])
+            })
+            .collect();
+
+        let summary = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Summary")
+                    .border_style(self.theme.focused_border_style()),
+            )
+            .wrap(Wrap { trim: true });
+        frame.render_widget(summary, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A wizard that doesn't depend on the git or GitHub settings of the
+    /// machine running the tests
+    fn new_wizard() -> InitWizard {
+        let mut wizard = InitWizard::new(TuiTheme::minimal_retro());
+        wizard.author.clear();
+        wizard.github_username.clear();
+        wizard.newsletter_email.clear();
+        wizard
+    }
+
+    fn press(wizard: &mut InitWizard, code: KeyCode) {
+        wizard.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn answer(wizard: &mut InitWizard, text: &str) {
+        for c in text.chars() {
+            press(wizard, KeyCode::Char(c));
+        }
+        press(wizard, KeyCode::Enter);
+    }
+
+    #[test]
+    fn test_steps() {
+        let mut wizard = new_wizard();
+        assert_eq!(
+            wizard.steps(),
+            vec![
+                Step::Title,
+                Step::Author,
+                Step::Description,
+                Step::SiteType,
+                Step::Theme,
+                Step::GitHubUsername,
+                Step::Domain,
+                Step::Newsletter,
+                Step::Summary,
+            ]
+        );
+
+        wizard.github_username = "octocat".to_string();
+        wizard.newsletter = true;
+        let steps = wizard.steps();
+        assert!(steps.contains(&Step::GitHubRepo));
+        assert!(steps.contains(&Step::NewsletterEmail));
+
+        // Personal sites have no posts to mail out
+        wizard.site_type = SiteType::Personal;
+        let steps = wizard.steps();
+        assert!(!steps.contains(&Step::Newsletter));
+        assert!(!steps.contains(&Step::NewsletterEmail));
+    }
+
+    #[test]
+    fn test_walk_through() {
+        let mut wizard = new_wizard();
+        answer(&mut wizard, "My Blog");
+        answer(&mut wizard, "Ann");
+        answer(&mut wizard, "");
+        assert_eq!(wizard.step, Step::SiteType);
+        press(&mut wizard, KeyCode::Enter);
+        press(&mut wizard, KeyCode::Enter);
+        answer(&mut wizard, "octocat");
+        // The repository is suggested from the title
+        assert_eq!(wizard.step, Step::GitHubRepo);
+        assert_eq!(wizard.github_repo, "my-blog");
+        press(&mut wizard, KeyCode::Enter);
+        answer(&mut wizard, "");
+        press(&mut wizard, KeyCode::Char('y'));
+        press(&mut wizard, KeyCode::Enter);
+        answer(&mut wizard, "news@example.com");
+        assert_eq!(wizard.step, Step::Summary);
+
+        press(&mut wizard, KeyCode::Esc);
+        assert_eq!(wizard.step, Step::NewsletterEmail);
+        press(&mut wizard, KeyCode::Enter);
+        press(&mut wizard, KeyCode::Enter);
+        assert!(wizard.is_finished());
+        let WizardResult::Create(config) = wizard.into_result() else {
+            panic!("the wizard was cancelled");
+        };
+        assert_eq!(config.blog.title, "My Blog");
+        assert!(config.newsletter.enabled);
+    }
+
+    #[test]
+    fn test_check_step() {
+        let mut wizard = new_wizard();
+        press(&mut wizard, KeyCode::Enter);
+        assert_eq!(wizard.step, Step::Title);
+        assert_eq!(wizard.error.as_deref(), Some("The title cannot be empty"));
+        answer(&mut wizard, "  ");
+        assert_eq!(wizard.step, Step::Title);
+        press(&mut wizard, KeyCode::Backspace);
+        press(&mut wizard, KeyCode::Backspace);
+        answer(&mut wizard, "My Blog");
+        assert_eq!(wizard.step, Step::Author);
+        assert!(wizard.error.is_none());
+
+        let cases = [
+            (Step::Author, ""),
+            (Step::GitHubUsername, "not--valid"),
+            (Step::GitHubUsername, "-octocat"),
+            (Step::GitHubRepo, ""),
+            (Step::GitHubRepo, "my blog"),
+            (Step::Domain, "localhost"),
+            (Step::Domain, "https://blog.example.com"),
+            (Step::NewsletterEmail, "news.example.com"),
+        ];
+        for (step, value) in cases {
+            let mut wizard = new_wizard();
+            wizard.step = step;
+            *wizard.text_mut().unwrap() = value.to_string();
+            assert!(wizard.check_step().is_err(), "{step:?} accepted {value:?}");
+        }
+
+        let cases = [
+            (Step::Author, "Ann"),
+            (Step::GitHubUsername, ""),
+            (Step::GitHubUsername, "octo-cat"),
+            (Step::GitHubRepo, "my_blog.io"),
+            (Step::Domain, ""),
+            (Step::Domain, "blog.example.com"),
+            (Step::NewsletterEmail, "news@example.com"),
+        ];
+        for (step, value) in cases {
+            let mut wizard = new_wizard();
+            wizard.step = step;
+            *wizard.text_mut().unwrap() = value.to_string();
+            assert!(wizard.check_step().is_ok(), "{step:?} rejected {value:?}");
+        }
+    }
+
+    #[test]
+    fn test_config() {
+        let mut wizard = new_wizard();
+        wizard.title = " My Blog ".to_string();
+        wizard.author = "Ann".to_string();
+        wizard.github_username = "octocat".to_string();
+        wizard.github_repo = "my-blog".to_string();
+        wizard.domain = "blog.example.com".to_string();
+        wizard.newsletter = true;
+        wizard.newsletter_email = "news@example.com".to_string();
+
+        let config = wizard.config();
+        assert_eq!(config.blog.title, "My Blog");
+        assert_eq!(config.blog.description, "A blog by Ann");
+        assert_eq!(config.site.site_type, "blog");
+        assert_eq!(config.theme.name, "minimal-retro");
+        let github = config.github.as_ref().unwrap();
+        assert_eq!(github.username, "octocat");
+        assert_eq!(github.repository, "my-blog");
+        assert!(config
+            .get_effective_base_url()
+            .starts_with("https://blog.example.com"));
+        assert!(config.newsletter.enabled);
+        assert_eq!(
+            config.newsletter.subscribe_email.as_deref(),
+            Some("news@example.com")
+        );
+        assert_eq!(config.newsletter.sender_name.as_deref(), Some("Ann"));
+
+        // Switching to a personal site picks its default theme and drops
+        // the newsletter even if it was turned on earlier
+        wizard.step = Step::SiteType;
+        press(&mut wizard, KeyCode::Down);
+        assert_eq!(wizard.site_type, SiteType::Personal);
+        wizard.github_username.clear();
+        wizard.domain.clear();
+        wizard.description = "Notes and projects".to_string();
+
+        let config = wizard.config();
+        assert_eq!(config.site.site_type, "personal");
+        assert_eq!(config.theme.name, "dark-minimal");
+        assert_eq!(config.blog.description, "Notes and projects");
+        assert!(config.github.is_none());
+        assert!(config.blog.domains.is_none());
+        assert!(!config.newsletter.enabled);
+
+        wizard.description.clear();
+        assert_eq!(wizard.config().blog.description, "Personal website of Ann");
+    }
+}
@@ -2,8 +2,10 @@ pub mod app;
 pub mod config_app;
 pub mod editor;
 pub mod events;
+pub mod init_wizard;
 pub mod markdown;
 pub mod preview;
+pub mod text_area;
 pub mod theme;
 
 pub use app::{App, AppResult};
@@ -21,6 +23,7 @@ use ratatui::{
 use std::io;
 
 use crate::tui::config_app::ConfigApp;
+use crate::tui::init_wizard::InitWizard;
 
 /// Representation of a terminal user interface.
 ///
@@ -74,6 +77,12 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    /// Draw the init wizard
+    pub fn draw_init_wizard(&mut self, wizard: &mut InitWizard) -> AppResult<()> {
+        self.terminal.draw(|frame| wizard.render(frame))?;
+        Ok(())
+    }
+
     /// Draw the newsletter approval app
     pub fn draw_approval(
         &mut self,
@@ -83,6 +92,22 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 
+    /// Draw the newsletter composer app
+    pub fn draw_compose(
+        &mut self,
+        compose_app: &mut crate::newsletter::ComposeApp,
+    ) -> AppResult<()> {
+        self.terminal.draw(|frame| compose_app.render(frame))?;
+        Ok(())
+    }
+
+    /// Clears the screen so the next draw repaints everything,
+    /// e.g. after other output was written to the terminal.
+    pub fn clear(&mut self) -> AppResult<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     /// Resets the terminal interface.
     ///
     /// This function is also used for the panic hook to revert
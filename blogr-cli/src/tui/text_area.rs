@@ -0,0 +1,284 @@
+use crate::tui::theme::TuiTheme;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+/// Text input for form fields: one line, or several with wrapping.
+/// Unlike `Editor` it counts the cursor in characters, so accented and
+/// other non-ASCII text edits safely.
+pub struct TextArea {
+    lines: Vec<Vec<char>>,
+    /// Cursor position (line, character)
+    cursor: (usize, usize),
+    multi_line: bool,
+}
+
+impl TextArea {
+    pub fn new(text: &str, multi_line: bool) -> Self {
+        let mut lines: Vec<Vec<char>> = if multi_line {
+            text.lines().map(|line| line.chars().collect()).collect()
+        } else {
+            vec![text.chars().collect()]
+        };
+        if lines.is_empty() {
+            lines.push(Vec::new());
+        }
+        // Start at the end, ready to append
+        let last = lines.len() - 1;
+        let cursor = (last, lines[last].len());
+        Self {
+            lines,
+            cursor,
+            multi_line,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Handle an editing or movement key. Enter adds a line in multi-line
+    /// areas; single-line areas leave it to the caller.
+    pub fn handle_key_event(&mut self, key: KeyEvent) {
+        let (line, col) = self.cursor;
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('a') => self.cursor.1 = 0,
+                KeyCode::Char('e') => self.cursor.1 = self.lines[line].len(),
+                KeyCode::Char('u') => {
+                    self.lines[line].drain(..col);
+                    self.cursor.1 = 0;
+                }
+                KeyCode::Char('k') => self.lines[line].truncate(col),
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char(c) => {
+                self.lines[line].insert(col, c);
+                self.cursor.1 += 1;
+            }
+            KeyCode::Enter if self.multi_line => {
+                let rest = self.lines[line].split_off(col);
+                self.lines.insert(line + 1, rest);
+                self.cursor = (line + 1, 0);
+            }
+            KeyCode::Backspace => {
+                if col > 0 {
+                    self.lines[line].remove(col - 1);
+                    self.cursor.1 -= 1;
+                } else if line > 0 {
+                    let current = self.lines.remove(line);
+                    self.cursor = (line - 1, self.lines[line - 1].len());
+                    self.lines[line - 1].extend(current);
+                }
+            }
+            KeyCode::Delete => {
+                if col < self.lines[line].len() {
+                    self.lines[line].remove(col);
+                } else if line + 1 < self.lines.len() {
+                    let next = self.lines.remove(line + 1);
+                    self.lines[line].extend(next);
+                }
+            }
+            KeyCode::Left => {
+                if col > 0 {
+                    self.cursor.1 -= 1;
+                } else if line > 0 {
+                    self.cursor = (line - 1, self.lines[line - 1].len());
+                }
+            }
+            KeyCode::Right => {
+                if col < self.lines[line].len() {
+                    self.cursor.1 += 1;
+                } else if line + 1 < self.lines.len() {
+                    self.cursor = (line + 1, 0);
+                }
+            }
+            KeyCode::Up if line > 0 => {
+                self.cursor = (line - 1, col.min(self.lines[line - 1].len()));
+            }
+            KeyCode::Down if line + 1 < self.lines.len() => {
+                self.cursor = (line + 1, col.min(self.lines[line + 1].len()));
+            }
+            KeyCode::Home => self.cursor.1 = 0,
+            KeyCode::End => self.cursor.1 = self.lines[line].len(),
+            _ => {}
+        }
+    }
+
+    /// Render the text wrapped to the area, scrolled to keep the cursor in view
+    pub fn render(&self, frame: &mut Frame, area: Rect, block: Block, theme: &TuiTheme) {
+        let inner = block.inner(area);
+        let width = (inner.width as usize).max(1);
+        let cursor_style = Style::default()
+            .bg(theme.cursor_color())
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD);
+
+        let mut rows: Vec<Line> = Vec::new();
+        let mut cursor_row = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            // The cursor after a line that fills its last row starts a new one
+            let cursor_at_end = index == self.cursor.0 && self.cursor.1 == line.len();
+            let row_count = line.len().div_ceil(width).max(1)
+                + usize::from(cursor_at_end && !line.is_empty() && line.len() % width == 0);
+            for row in 0..row_count {
+                let start = row * width;
+                let end = (start + width).min(line.len());
+                let on_cursor_row = index == self.cursor.0
+                    && self.cursor.1 >= start
+                    && (self.cursor.1 < end || row == row_count - 1);
+                if !on_cursor_row {
+                    rows.push(Line::from(line[start..end].iter().collect::<String>()));
+                    continue;
+                }
+
+                cursor_row = rows.len();
+                let at = self.cursor.1 - start;
+                let before: String = line[start..start + at].iter().collect();
+                let under = line.get(self.cursor.1).map_or(' ', |c| *c);
+                let after: String = line[(start + at + 1).min(end)..end].iter().collect();
+                rows.push(Line::from(vec![
+                    Span::raw(before),
+                    Span::styled(under.to_string(), cursor_style),
+                    Span::raw(after),
+                ]));
+            }
+        }
+
+        let height = (inner.height as usize).max(1);
+        let scroll = cursor_row.saturating_sub(height - 1);
+        let paragraph = Paragraph::new(rows)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .style(theme.text_style());
+        frame.render_widget(paragraph, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(text_area: &mut TextArea, code: KeyCode) {
+        text_area.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn ctrl(text_area: &mut TextArea, c: char) {
+        text_area.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+    }
+
+    fn type_text(text_area: &mut TextArea, text: &str) {
+        for c in text.chars() {
+            press(text_area, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_single_line() {
+        let mut text_area = TextArea::new("café", false);
+        assert_eq!(text_area.cursor, (0, 4));
+        // Enter is left to the caller
+        press(&mut text_area, KeyCode::Enter);
+        assert_eq!(text_area.text(), "café");
+
+        press(&mut text_area, KeyCode::Left);
+        press(&mut text_area, KeyCode::Backspace);
+        assert_eq!(text_area.text(), "caé");
+        type_text(&mut text_area, "ff");
+        assert_eq!(text_area.text(), "caffé");
+        assert_eq!(text_area.cursor, (0, 4));
+
+        press(&mut text_area, KeyCode::Home);
+        press(&mut text_area, KeyCode::Delete);
+        assert_eq!(text_area.text(), "affé");
+        press(&mut text_area, KeyCode::End);
+        assert_eq!(text_area.cursor, (0, 4));
+        press(&mut text_area, KeyCode::Right);
+        assert_eq!(text_area.cursor, (0, 4));
+
+        // Multi-line text pasted into a single-line field stays one line
+        let text_area = TextArea::new("one\ntwo", false);
+        assert_eq!(text_area.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_multi_line() {
+        let mut text_area = TextArea::new("", true);
+        assert_eq!(text_area.lines, vec![Vec::<char>::new()]);
+        type_text(&mut text_area, "first");
+        press(&mut text_area, KeyCode::Enter);
+        type_text(&mut text_area, "second line");
+        assert_eq!(text_area.text(), "first\nsecond line");
+        assert_eq!(text_area.cursor, (1, 11));
+
+        // Enter in the middle of a line splits it
+        for _ in 0..5 {
+            press(&mut text_area, KeyCode::Left);
+        }
+        press(&mut text_area, KeyCode::Enter);
+        assert_eq!(text_area.text(), "first\nsecond\n line");
+        assert_eq!(text_area.cursor, (2, 0));
+
+        // Backspace at the start of a line joins it to the one above
+        press(&mut text_area, KeyCode::Backspace);
+        assert_eq!(text_area.text(), "first\nsecond line");
+        assert_eq!(text_area.cursor, (1, 6));
+
+        // Up and Down keep the column where the line is long enough
+        press(&mut text_area, KeyCode::End);
+        press(&mut text_area, KeyCode::Up);
+        assert_eq!(text_area.cursor, (0, 5));
+        press(&mut text_area, KeyCode::Down);
+        assert_eq!(text_area.cursor, (1, 5));
+        press(&mut text_area, KeyCode::Down);
+        assert_eq!(text_area.cursor, (1, 5));
+
+        // Left and Right cross line ends
+        press(&mut text_area, KeyCode::Home);
+        press(&mut text_area, KeyCode::Left);
+        assert_eq!(text_area.cursor, (0, 5));
+        press(&mut text_area, KeyCode::Right);
+        assert_eq!(text_area.cursor, (1, 0));
+
+        // Delete at the end of a line pulls up the next one
+        press(&mut text_area, KeyCode::Up);
+        press(&mut text_area, KeyCode::End);
+        press(&mut text_area, KeyCode::Delete);
+        assert_eq!(text_area.text(), "firstsecond line");
+        assert_eq!(text_area.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_control_keys() {
+        let mut text_area = TextArea::new("one\nhello world", true);
+        for _ in 0..6 {
+            press(&mut text_area, KeyCode::Left);
+        }
+        ctrl(&mut text_area, 'k');
+        assert_eq!(text_area.text(), "one\nhello");
+        ctrl(&mut text_area, 'a');
+        assert_eq!(text_area.cursor, (1, 0));
+        ctrl(&mut text_area, 'e');
+        assert_eq!(text_area.cursor, (1, 5));
+        press(&mut text_area, KeyCode::Left);
+        ctrl(&mut text_area, 'u');
+        assert_eq!(text_area.text(), "one\no");
+        assert_eq!(text_area.cursor, (1, 0));
+        // Other control keys don't insert text
+        ctrl(&mut text_area, 'x');
+        assert_eq!(text_area.text(), "one\no");
+    }
+}
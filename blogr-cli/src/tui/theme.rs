@@ -53,7 +53,7 @@ impl TuiTheme {
     }
 
     /// Parse a color string (hex format)
-    fn parse_color(color_str: &str) -> Option<Color> {
+    pub fn parse_color(color_str: &str) -> Option<Color> {
         if let Some(hex) = color_str.strip_prefix('#') {
             if hex.len() == 6 {
                 if let (Ok(r), Ok(g), Ok(b)) = (
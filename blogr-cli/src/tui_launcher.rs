@@ -1,6 +1,7 @@
 use crate::content::{Post, PostManager};
 use crate::project::Project;
 use crate::tui::config_app::ConfigApp;
+use crate::tui::init_wizard::{InitWizard, WizardResult};
 use crate::tui::theme::TuiTheme;
 use crate::tui::{self, App, Event};
 use anyhow::Result;
@@ -36,7 +37,7 @@ pub async fn launch_editor(post: Post, project: &Project) -> Result<Post> {
     tui.init()?;
 
     // Create post manager
-    let post_manager = PostManager::new(project.posts_dir());
+    let post_manager = PostManager::new(project.posts_dir()).with_history(&project.root);
 
     // Create app
     let mut app = App::new(post, tui_theme, post_manager);
@@ -133,3 +134,36 @@ pub async fn launch_config_editor(project: &Project) -> Result<()> {
 
     result
 }
+
+/// Launch the `blogr init` wizard
+pub async fn launch_init_wizard() -> Result<WizardResult> {
+    // Initialize TUI
+    let mut tui = tui::init()?;
+    tui.init()?;
+
+    let mut wizard = InitWizard::new(TuiTheme::minimal_retro());
+
+    // Main event loop
+    let result = loop {
+        // Draw the interface
+        if let Err(e) = tui.draw_init_wizard(&mut wizard) {
+            break Err(e);
+        }
+
+        // Handle events
+        match tui.events.next() {
+            Ok(Event::Key(key_event)) => wizard.handle_key_event(key_event),
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+
+        if wizard.is_finished() {
+            break Ok(wizard.into_result());
+        }
+    };
+
+    // Cleanup
+    tui.exit()?;
+
+    result
+}
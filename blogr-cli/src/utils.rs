@@ -2,11 +2,40 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+/// Set by the global `--json` flag: the command prints one JSON report to
+/// stdout, and progress messages go to stderr
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+/// Whether the command has printed its JSON report
+static JSON_REPORTED: AtomicBool = AtomicBool::new(false);
+static STARTED: OnceLock<Instant> = OnceLock::new();
+/// Warnings printed so far, for the JSON report
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// `println!` for progress messages: an info event, hidden by `--quiet` and
+/// sent to stderr with `--json` (see `logging`)
+#[macro_export]
+macro_rules! status {
+    () => {
+        ::tracing::info!("")
+    };
+    ($($arg:tt)*) => {
+        ::tracing::info!($($arg)*)
+    };
+}
 
 /// Utility functions for the CLI
 pub struct Utils;
 
 impl Utils {
+    /// Lowercase hexadecimal, as used for hashes and signatures
+    pub fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
     /// Convert a title to a URL-friendly slug
     pub fn slugify(text: &str) -> String {
         text.to_lowercase()
@@ -22,10 +51,12 @@ impl Utils {
     /// Calculate estimated reading time based on word count
     #[allow(dead_code)]
     pub fn calculate_reading_time(content: &str) -> usize {
-        const WORDS_PER_MINUTE: usize = 200;
-        let word_count = content.split_whitespace().count();
-        let minutes = word_count.div_ceil(WORDS_PER_MINUTE); // Ceiling division
-        minutes.max(1) // Minimum 1 minute
+        crate::content::ReadingTime::estimate(
+            content,
+            &crate::config::ReadingTimeConfig::default(),
+            None,
+        )
+        .minutes
     }
 
     /// Extract excerpt from content
@@ -128,6 +159,37 @@ impl Utils {
         timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string()
     }
 
+    /// Parse a time given in local time ("2024-06-01 09:00") or RFC 3339
+    pub fn parse_local_time(input: &str) -> Result<DateTime<Utc>> {
+        use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+
+        let input = input.trim();
+        if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+            return Ok(time.with_timezone(&Utc));
+        }
+
+        let naive = ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid time '{}'. Use \"YYYY-MM-DD HH:MM\" (local time) or RFC 3339",
+                    input
+                )
+            })?;
+
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+            .ok_or_else(|| anyhow::anyhow!("'{}' does not exist in the local time zone", input))
+    }
+
     /// Validate GitHub repository name
     pub fn is_valid_github_repo_name(name: &str) -> bool {
         if name.is_empty() || name.len() > 100 {
@@ -313,30 +375,71 @@ impl Utils {
 pub struct Console;
 
 impl Console {
+    /// Switch to JSON output for the rest of the command
+    pub fn set_json(enabled: bool) {
+        JSON_OUTPUT.store(enabled, Ordering::Relaxed);
+        STARTED.get_or_init(Instant::now);
+    }
+
+    pub fn is_json() -> bool {
+        JSON_OUTPUT.load(Ordering::Relaxed)
+    }
+
+    /// Whether a JSON report has been printed
+    pub fn json_reported() -> bool {
+        JSON_REPORTED.load(Ordering::Relaxed)
+    }
+
+    /// Print a command's JSON report to stdout, adding the command name,
+    /// `status` (`ok` unless the report sets it), the warnings printed along
+    /// the way and how long the command took
+    pub fn report(command: &str, report: serde_json::Value) -> Result<()> {
+        let report = Self::complete_report(command, report);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        JSON_REPORTED.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn complete_report(command: &str, mut report: serde_json::Value) -> serde_json::Value {
+        let warnings = std::mem::take(&mut *WARNINGS.lock().unwrap());
+        let duration = STARTED.get_or_init(Instant::now).elapsed();
+        if let Some(fields) = report.as_object_mut() {
+            fields.insert("command".to_string(), command.into());
+            fields.entry("status").or_insert_with(|| "ok".into());
+            fields.insert("warnings".to_string(), warnings.into());
+            fields.insert(
+                "duration_ms".to_string(),
+                (duration.as_millis() as u64).into(),
+            );
+        }
+        report
+    }
+
     /// Print success message
     pub fn success(message: &str) {
-        println!("✅ {}", message);
+        status!("✅ {}", message);
     }
 
     /// Print error message
     #[allow(dead_code)]
     pub fn error(message: &str) {
-        eprintln!("❌ {}", message);
+        tracing::error!("{}", message);
     }
 
     /// Print warning message
     pub fn warn(message: &str) {
-        println!("⚠️  {}", message);
+        WARNINGS.lock().unwrap().push(message.to_string());
+        tracing::warn!("{}", message);
     }
 
     /// Print info message
     pub fn info(message: &str) {
-        println!("ℹ️  {}", message);
+        status!("ℹ️  {}", message);
     }
 
     /// Print step message
     pub fn step(step: u8, total: u8, message: &str) {
-        println!("[{}/{}] {}", step, total, message);
+        status!("[{}/{}] {}", step, total, message);
     }
 }
 
@@ -344,6 +447,12 @@ impl Console {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hex() {
+        assert_eq!(Utils::hex(&[0x00, 0x0f, 0xa5, 0xff]), "000fa5ff");
+        assert_eq!(Utils::hex(&[]), "");
+    }
+
     #[test]
     fn test_slugify() {
         assert_eq!(Utils::slugify("Hello World"), "hello-world");
@@ -371,6 +480,28 @@ mod tests {
         assert_eq!(excerpt, "This is a long text...");
     }
 
+    #[test]
+    fn test_complete_report() {
+        Console::warn("Theme has no tag template");
+        let report = Console::complete_report("build", serde_json::json!({ "pages": 3 }));
+        assert_eq!(report["command"], "build");
+        assert_eq!(report["status"], "ok");
+        assert_eq!(report["pages"], 3);
+        assert!(report["duration_ms"].is_u64());
+        assert!(report["warnings"]
+            .as_array()
+            .unwrap()
+            .contains(&"Theme has no tag template".into()));
+
+        // Warnings are reported once, and a report's own status is kept
+        let report = Console::complete_report("deploy", serde_json::json!({ "status": "partial" }));
+        assert_eq!(report["status"], "partial");
+        assert!(!report["warnings"]
+            .as_array()
+            .unwrap()
+            .contains(&"Theme has no tag template".into()));
+    }
+
     #[test]
     fn test_github_validation() {
         assert!(Utils::is_valid_github_username("valid-username"));
@@ -0,0 +1,220 @@
+//! File watching for `blogr serve`
+//!
+//! Everything under the project root is watched, so posts, pages, themes,
+//! data files, `content.md` and `blogr.toml` all trigger a rebuild, along with
+//! the workspace's shared themes. The output directory, `.git`, `.blogr` and
+//! files matching the ignore globs (editor swap and backup files by default,
+//! plus `dev.ignore`) are skipped. Changes are collected until the tree has
+//! been quiet for `dev.debounce_ms`, so an editor's save-via-temp-file or a
+//! `git checkout` leads to one rebuild rather than a storm of them.
+//!
+//! The watcher polls modification times rather than relying on OS events,
+//! which behave differently across platforms, editors and network drives.
+
+use crate::config::Config;
+use crate::project::Project;
+use anyhow::{anyhow, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use walkdir::WalkDir;
+
+/// How often the tree is scanned
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Files editors and tools write next to the ones being edited
+const DEFAULT_IGNORES: &[&str] = &[
+    "**/*~",
+    "**/.#*",
+    "**/#*#",
+    "**/*.swp",
+    "**/*.swo",
+    "**/*.swx",
+    "**/*.tmp",
+    "**/*.bak",
+    "**/4913",
+    "**/.DS_Store",
+    "**/Thumbs.db",
+];
+
+/// Directories under the project root that are never inputs of a build
+const SKIPPED_DIRS: &[&str] = &[".git", ".blogr", "node_modules", "target"];
+
+/// Modification time and size of a file when last scanned
+type Stamp = (Option<SystemTime>, u64);
+
+pub struct Watcher {
+    roots: Vec<PathBuf>,
+    project_root: PathBuf,
+    output_dir: PathBuf,
+    ignore: GlobSet,
+    debounce: Duration,
+    snapshot: HashMap<PathBuf, Stamp>,
+}
+
+impl Watcher {
+    pub fn new(project: &Project, config: &Config) -> Result<Self> {
+        let mut roots = vec![project.root.clone()];
+        if let Some(shared) = project.shared_themes_dir() {
+            if !shared.starts_with(&project.root) {
+                roots.push(shared);
+            }
+        }
+        let mut watcher = Self {
+            roots,
+            project_root: project.root.clone(),
+            output_dir: config.output_dir(&project.root),
+            ignore: ignore_set(&config.dev.ignore)?,
+            debounce: Duration::from_millis(config.dev.debounce_ms),
+            snapshot: HashMap::new(),
+        };
+        watcher.snapshot = watcher.scan();
+        Ok(watcher)
+    }
+
+    /// Whether changes to `path` are left alone
+    fn ignored(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.project_root).unwrap_or(path);
+        self.ignore.is_match(relative)
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, Stamp> {
+        let mut files = HashMap::new();
+        for root in &self.roots {
+            let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+                let path = entry.path();
+                if path == root {
+                    return true;
+                }
+                let skipped_dir = entry.file_type().is_dir()
+                    && (path == self.output_dir
+                        || entry
+                            .file_name()
+                            .to_str()
+                            .is_some_and(|name| SKIPPED_DIRS.contains(&name)));
+                !skipped_dir && !self.ignored(path)
+            });
+            for entry in walker.filter_map(|entry| entry.ok()) {
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                if let Ok(metadata) = entry.metadata() {
+                    files.insert(
+                        entry.into_path(),
+                        (metadata.modified().ok(), metadata.len()),
+                    );
+                }
+            }
+        }
+        files
+    }
+
+    /// Files added, changed or removed since the last scan
+    pub fn changes(&mut self) -> Vec<PathBuf> {
+        let current = self.scan();
+        let mut changed: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, stamp)| self.snapshot.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.snapshot
+                    .keys()
+                    .filter(|path| !current.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        changed.sort();
+        self.snapshot = current;
+        changed
+    }
+
+    /// Block until something changes, then until nothing has changed for the
+    /// debounce time, and return everything that changed
+    pub fn wait(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        let mut last_change = Instant::now();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let batch = self.changes();
+            if !batch.is_empty() {
+                last_change = Instant::now();
+                changed.extend(batch);
+            } else if !changed.is_empty() && last_change.elapsed() >= self.debounce {
+                changed.sort();
+                changed.dedup();
+                return changed;
+            }
+        }
+    }
+
+    /// `path` relative to the project root, for messages
+    pub fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.project_root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+fn ignore_set(extra: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in DEFAULT_IGNORES
+        .iter()
+        .copied()
+        .chain(extra.iter().map(String::as_str))
+    {
+        let glob = Glob::new(pattern)
+            .map_err(|e| anyhow!("Invalid pattern '{}' in dev.ignore: {}", pattern, e))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("posts")).unwrap();
+        fs::create_dir_all(root.join("themes/mine")).unwrap();
+        fs::write(root.join("posts/hello.md"), "hello").unwrap();
+
+        let mut config = Config::default();
+        config.dev.ignore = vec!["drafts-notes/**".to_string()];
+        let project = Project::new(root.to_path_buf(), config.clone());
+        let mut watcher = Watcher::new(&project, &config).unwrap();
+        assert!(watcher.changes().is_empty());
+
+        fs::write(root.join("posts/hello.md"), "hello, world").unwrap();
+        fs::write(root.join("themes/mine/base.html"), "<html>").unwrap();
+        fs::write(root.join("content.md"), "# Home").unwrap();
+        fs::write(root.join("posts/.hello.md.swp"), "swap").unwrap();
+        fs::write(root.join("posts/hello.md~"), "backup").unwrap();
+        fs::create_dir_all(root.join("drafts-notes")).unwrap();
+        fs::write(root.join("drafts-notes/idea.md"), "idea").unwrap();
+        fs::create_dir_all(config.output_dir(root)).unwrap();
+        fs::write(config.output_dir(root).join("index.html"), "built").unwrap();
+
+        let changed: Vec<String> = watcher
+            .changes()
+            .iter()
+            .map(|path| watcher.display(path))
+            .collect();
+        assert_eq!(
+            changed,
+            ["content.md", "posts/hello.md", "themes/mine/base.html"]
+        );
+
+        fs::remove_file(root.join("content.md")).unwrap();
+        assert_eq!(watcher.changes(), [root.join("content.md")]);
+
+        config.dev.ignore = vec!["[".to_string()];
+        assert!(Watcher::new(&project, &config).is_err());
+    }
+}
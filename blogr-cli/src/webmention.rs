@@ -0,0 +1,367 @@
+//! Webmention sending
+//!
+//! Finds links to other sites in published posts, discovers each target's
+//! [Webmention](https://www.w3.org/TR/webmention/) endpoint and notifies it.
+//! Results are recorded in `.blogr/webmentions.json`, so every link is only
+//! processed once.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use url::Url;
+
+/// Largest page read while looking for an endpoint
+const MAX_DISCOVERY_BYTES: usize = 2 * 1024 * 1024;
+
+/// What happened to one source → target pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentMention {
+    pub at: DateTime<Utc>,
+    /// Endpoint notified, or `None` when the target doesn't accept webmentions
+    pub endpoint: Option<String>,
+}
+
+/// Mentions already processed, by source URL and then target URL
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SentLog {
+    sources: BTreeMap<String, BTreeMap<String, SentMention>>,
+}
+
+impl SentLog {
+    /// Load the log, or start an empty one if the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn contains(&self, source: &str, target: &str) -> bool {
+        self.sources
+            .get(source)
+            .is_some_and(|targets| targets.contains_key(target))
+    }
+
+    pub fn record(&mut self, source: &str, target: &str, endpoint: Option<&Url>) {
+        self.sources.entry(source.to_string()).or_default().insert(
+            target.to_string(),
+            SentMention {
+                at: Utc::now(),
+                endpoint: endpoint.map(Url::to_string),
+            },
+        );
+    }
+}
+
+/// Discovers endpoints and sends webmentions
+pub struct WebmentionClient {
+    client: reqwest::Client,
+}
+
+impl Default for WebmentionClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebmentionClient {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent(concat!(
+                "blogr/",
+                env!("CARGO_PKG_VERSION"),
+                " (webmention)"
+            ))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Whether a published page is reachable and links to every target. Receivers
+    /// verify this, so sending before the page is live would be wasted.
+    pub async fn source_links_to(&self, source: &str, targets: &[String]) -> Result<bool> {
+        let response = self.client.get(source).send().await?;
+        if !response.status().is_success() {
+            return Ok(false);
+        }
+        let live = external_links(&response.text().await?, "");
+        Ok(targets.iter().all(|target| live.contains(target)))
+    }
+
+    /// Find the webmention endpoint advertised by a page, from its `Link` header
+    /// or the first `<link>`/`<a>` with `rel="webmention"`
+    pub async fn discover(&self, target: &str) -> Result<Option<Url>> {
+        let response = self.client.get(target).send().await?;
+        let page = response.url().clone();
+
+        for value in response.headers().get_all(reqwest::header::LINK) {
+            if let Some(endpoint) = value.to_str().ok().and_then(endpoint_from_link_header) {
+                return Ok(page.join(&endpoint).ok());
+            }
+        }
+
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("html"));
+        if !response.status().is_success() || !is_html {
+            return Ok(None);
+        }
+
+        let bytes = response.bytes().await?;
+        let html = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_DISCOVERY_BYTES)]);
+        Ok(endpoint_from_html(&html).and_then(|endpoint| page.join(&endpoint).ok()))
+    }
+
+    /// Notify an endpoint that `source` links to `target`
+    pub async fn send(&self, endpoint: &Url, source: &str, target: &str) -> Result<()> {
+        let response = self
+            .client
+            .post(endpoint.clone())
+            .form(&[("source", source), ("target", target)])
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(anyhow!("endpoint returned {}", response.status()))
+        }
+    }
+}
+
+/// Links in rendered HTML that point to other sites, without duplicates
+pub fn external_links(html: &str, base_url: &str) -> Vec<String> {
+    let own_host = Url::parse(base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string));
+
+    let mut links: Vec<String> = Vec::new();
+    for tag in html_tags(html, &["a", "link"]) {
+        if tag.name != "a" {
+            continue;
+        }
+        let Some(href) = tag.attributes.get("href") else {
+            continue;
+        };
+        let Ok(mut url) = Url::parse(href) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") || url.host_str() == own_host.as_deref() {
+            continue;
+        }
+        url.set_fragment(None);
+        let url = url.to_string();
+        if !links.contains(&url) {
+            links.push(url);
+        }
+    }
+    links
+}
+
+/// The `webmention` URL from an HTTP `Link` header value
+pub fn endpoint_from_link_header(value: &str) -> Option<String> {
+    value.split(',').find_map(|link| {
+        let (target, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+        params
+            .split(';')
+            .filter_map(|param| param.trim().split_once('='))
+            .any(|(name, rel)| name.trim().eq_ignore_ascii_case("rel") && has_webmention_rel(rel))
+            .then(|| target.to_string())
+    })
+}
+
+/// The `href` of the first `<link>` or `<a>` with `rel="webmention"`
+pub fn endpoint_from_html(html: &str) -> Option<String> {
+    html_tags(html, &["a", "link"]).into_iter().find_map(|tag| {
+        let rel = tag.attributes.get("rel")?;
+        if !has_webmention_rel(rel) {
+            return None;
+        }
+        tag.attributes.get("href").cloned()
+    })
+}
+
+fn has_webmention_rel(rel: &str) -> bool {
+    rel.trim_matches('"')
+        .split_whitespace()
+        .any(|value| value.eq_ignore_ascii_case("webmention"))
+}
+
+/// An HTML start tag with its attributes, entities decoded
+pub(crate) struct Tag {
+    pub name: String,
+    pub attributes: HashMap<String, String>,
+}
+
+/// Every tag named in `names` (lowercase) in a document, or every start tag
+/// if `names` is empty, skipping comments
+pub(crate) fn html_tags(html: &str, names: &[&str]) -> Vec<Tag> {
+    let mut tags = Vec::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if let Some(comment) = rest.strip_prefix("!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        let name = rest[..name_len].to_ascii_lowercase();
+        rest = &rest[name_len..];
+        if name.is_empty() || !(names.is_empty() || names.contains(&name.as_str())) {
+            continue;
+        }
+
+        let (attributes, remaining) = parse_attributes(rest);
+        tags.push(Tag { name, attributes });
+        rest = remaining;
+    }
+
+    tags
+}
+
+/// Parse attributes up to the end of a tag, returning them and the text after it
+pub(crate) fn parse_attributes(input: &str) -> (HashMap<String, String>, &str) {
+    let mut attributes = HashMap::new();
+    let mut chars = input.char_indices().peekable();
+
+    loop {
+        while chars
+            .next_if(|&(_, c)| c.is_whitespace() || c == '/')
+            .is_some()
+        {}
+
+        let Some(&(start, c)) = chars.peek() else {
+            return (attributes, "");
+        };
+        if c == '>' {
+            return (attributes, &input[start + 1..]);
+        }
+
+        let mut end = start;
+        while let Some((i, c)) =
+            chars.next_if(|&(_, c)| !c.is_whitespace() && !matches!(c, '=' | '>' | '/'))
+        {
+            end = i + c.len_utf8();
+        }
+        if end == start {
+            // A stray `=`; skip it so parsing always moves forward
+            chars.next();
+            continue;
+        }
+        let name = input[start..end].to_ascii_lowercase();
+
+        while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if(|&(_, c)| c == '=').is_some() {
+            while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+            match chars.next_if(|&(_, c)| c == '"' || c == '\'') {
+                Some((_, quote)) => {
+                    for (_, c) in chars.by_ref() {
+                        if c == quote {
+                            break;
+                        }
+                        value.push(c);
+                    }
+                }
+                None => {
+                    while let Some((_, c)) = chars.next_if(|&(_, c)| !c.is_whitespace() && c != '>')
+                    {
+                        value.push(c);
+                    }
+                }
+            }
+        }
+
+        attributes
+            .entry(name)
+            .or_insert_with(|| decode_entities(&value));
+    }
+}
+
+pub(crate) fn decode_entities(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_links() {
+        let html = r#"<p>See <a href="https://other.example/post?a=1&amp;b=2#c">this</a>,
+            <a href="/posts/mine.html">mine</a>, <a href="https://blog.example.com/x">self</a>,
+            <!-- <a href="https://hidden.example/">hidden</a> -->
+            <a title="x" href='https://other.example/post?a=1&b=2'>again</a>
+            <a href="mailto:me@example.com">mail</a></p>"#;
+
+        assert_eq!(
+            external_links(html, "https://blog.example.com"),
+            vec!["https://other.example/post?a=1&b=2"]
+        );
+    }
+
+    #[test]
+    fn test_endpoint_from_link_header() {
+        assert_eq!(
+            endpoint_from_link_header(
+                r#"<https://example.com/style.css>; rel="preload", <https://webmention.io/example.com/webmention>; rel="webmention""#
+            ),
+            Some("https://webmention.io/example.com/webmention".to_string())
+        );
+        assert_eq!(
+            endpoint_from_link_header(r#"</wm>; rel="other webmention""#),
+            Some("/wm".to_string())
+        );
+        assert_eq!(
+            endpoint_from_link_header(r#"</x>; rel="webmentions""#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_endpoint_from_html() {
+        let html = r#"<html><head>
+            <!-- <link rel="webmention" href="/commented"> -->
+            <link rel="stylesheet" href="/style.css">
+            <link href="/endpoint?a=1&amp;b=2" rel="webmention" />
+            </head><body><a rel="webmention" href="/later">x</a></body></html>"#;
+        assert_eq!(
+            endpoint_from_html(html),
+            Some("/endpoint?a=1&b=2".to_string())
+        );
+
+        // An empty href means the page is its own endpoint
+        assert_eq!(
+            endpoint_from_html(r#"<link rel=webmention href="">"#),
+            Some(String::new())
+        );
+        assert_eq!(endpoint_from_html("<p>nothing</p>"), None);
+    }
+}
@@ -0,0 +1,224 @@
+//! Several projects in one checkout
+//!
+//! A `blogr-workspace.toml` above the projects lists them and names
+//! directories they share: theme packages installed once for every project,
+//! and WebAssembly plugins loaded after each project's own `plugins/`.
+//! Without the file, projects are found by looking for `blogr.toml` files
+//! next to the current project, or in the git checkout holding it.
+//!
+//! ```toml
+//! projects = ["personal", "project-blog"]
+//! themes_dir = "shared/themes"
+//! plugins_dir = "shared/plugins"
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const WORKSPACE_FILE: &str = "blogr-workspace.toml";
+
+/// How deep below the workspace root projects are looked for
+const DISCOVERY_DEPTH: usize = 3;
+
+/// Directories never searched for projects
+const SKIPPED_DIRS: [&str; 4] = ["node_modules", "target", "dist", "themes"];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// Project directories, relative to the workspace root; found
+    /// automatically when empty
+    #[serde(default)]
+    pub projects: Vec<String>,
+    /// Theme packages shared by every project
+    pub themes_dir: Option<String>,
+    /// WebAssembly newsletter plugins shared by every project
+    pub plugins_dir: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub config: WorkspaceConfig,
+}
+
+impl Workspace {
+    /// The workspace containing `start`, from the nearest `blogr-workspace.toml`
+    pub fn find(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let path = dir.join(WORKSPACE_FILE);
+            if path.is_file() {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let config = toml::from_str(&content)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                return Ok(Some(Self {
+                    root: dir.to_path_buf(),
+                    config,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn themes_dir(&self) -> Option<PathBuf> {
+        self.config
+            .themes_dir
+            .as_ref()
+            .map(|dir| self.root.join(dir))
+    }
+
+    pub fn plugins_dir(&self) -> Option<PathBuf> {
+        self.config
+            .plugins_dir
+            .as_ref()
+            .map(|dir| self.root.join(dir))
+    }
+
+    /// Project roots, as listed or found below the workspace root
+    pub fn projects(&self) -> Vec<PathBuf> {
+        if self.config.projects.is_empty() {
+            discover_projects(&self.root)
+        } else {
+            self.config
+                .projects
+                .iter()
+                .map(|project| self.root.join(project))
+                .collect()
+        }
+    }
+}
+
+/// Directories below `root` (or `root` itself) holding a `blogr.toml`, sorted.
+/// Projects are not searched for nested projects.
+pub fn discover_projects(root: &Path) -> Vec<PathBuf> {
+    let mut projects = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = pending.pop() {
+        if dir.join("blogr.toml").is_file() {
+            projects.push(dir);
+            continue;
+        }
+        if depth == DISCOVERY_DEPTH {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                pending.push((entry.path(), depth + 1));
+            }
+        }
+    }
+    projects.sort();
+    projects
+}
+
+/// Where projects are looked for: the workspace root; else, from inside a
+/// project, the git checkout holding it or its parent directory; else the
+/// git checkout or the current directory
+pub fn search_root(cwd: &Path) -> Result<PathBuf> {
+    if let Some(workspace) = Workspace::find(cwd)? {
+        return Ok(workspace.root);
+    }
+    let checkout = |dir: &Path| {
+        dir.ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_path_buf)
+    };
+    // Projects are usually their own git repositories, so look above them
+    if let Some(project) = cwd.ancestors().find(|dir| dir.join("blogr.toml").is_file()) {
+        if let Some(parent) = project.parent() {
+            return Ok(checkout(parent).unwrap_or_else(|| parent.to_path_buf()));
+        }
+    }
+    Ok(checkout(cwd).unwrap_or_else(|| cwd.to_path_buf()))
+}
+
+/// Resolve `--project`: a path to a project directory, or the directory name
+/// of a project in the workspace
+pub fn resolve_project(project: &str, cwd: &Path) -> Result<PathBuf> {
+    let path = cwd.join(project);
+    if path.join("blogr.toml").is_file() {
+        return Ok(path);
+    }
+
+    let root = search_root(cwd)?;
+    let projects = match Workspace::find(cwd)? {
+        Some(workspace) => workspace.projects(),
+        None => discover_projects(&root),
+    };
+    let matches: Vec<&PathBuf> = projects
+        .iter()
+        .filter(|dir| dir.file_name().is_some_and(|name| name == project))
+        .collect();
+    match matches.as_slice() {
+        [dir] => Ok(dir.to_path_buf()),
+        [] => Err(anyhow!(
+            "No blogr project '{}' (no blogr.toml there, and no project of that name under {}). Run 'blogr projects list' to see them.",
+            project,
+            root.display()
+        )),
+        _ => Err(anyhow!(
+            "Several projects are named '{}'; pass its path instead",
+            project
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_workspace_projects() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        for project in ["sites/personal", "sites/work", "docs", "node_modules/pkg"] {
+            fs::create_dir_all(root.join(project)).unwrap();
+            fs::write(root.join(project).join("blogr.toml"), "").unwrap();
+        }
+        fs::create_dir_all(root.join("docs/themes/nested")).unwrap();
+        fs::write(root.join("docs/themes/nested/blogr.toml"), "").unwrap();
+
+        assert_eq!(
+            discover_projects(root),
+            [
+                root.join("docs"),
+                root.join("sites/personal"),
+                root.join("sites/work")
+            ]
+        );
+        // Siblings of the current project, then the whole checkout
+        let cwd = root.join("sites/work");
+        assert!(resolve_project("docs", &cwd).is_err());
+        fs::create_dir(root.join(".git")).unwrap();
+        assert_eq!(resolve_project("docs", &cwd).unwrap(), root.join("docs"));
+        assert_eq!(resolve_project(".", &cwd).unwrap(), cwd.join("."));
+        assert_eq!(
+            resolve_project("personal", &cwd).unwrap(),
+            root.join("sites/personal")
+        );
+        assert!(resolve_project("missing", root).is_err());
+
+        fs::write(
+            root.join(WORKSPACE_FILE),
+            "projects = [\"sites/work\"]\nthemes_dir = \"shared/themes\"\n",
+        )
+        .unwrap();
+        let workspace = Workspace::find(&cwd).unwrap().unwrap();
+        assert_eq!(workspace.root, root);
+        assert_eq!(workspace.projects(), [root.join("sites/work")]);
+        assert_eq!(workspace.themes_dir(), Some(root.join("shared/themes")));
+        assert_eq!(workspace.plugins_dir(), None);
+        assert!(resolve_project("personal", &cwd).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+/**
+ * Blogr Gallery - grid layout and lightbox for photo galleries
+ *
+ * Enhances every [data-blogr-gallery] element, on gallery pages and where the
+ * gallery shortcode embeds one in a post. Each link in a gallery points at the
+ * full-size image; its data-caption is shown under the image.
+ *
+ * Keys: Escape closes, arrow keys move between images.
+ */
+
+(function () {
+    if (window.BlogrGallery) {
+        return;
+    }
+    window.BlogrGallery = true;
+
+    // :where() keeps these rules easy for theme stylesheets to override
+    const style = document.createElement('style');
+    style.textContent = `
+        :where(.blogr-gallery) { display: grid; grid-template-columns: repeat(auto-fill, minmax(10rem, 1fr)); gap: 0.5rem; margin: 1.5rem 0; }
+        :where(.blogr-gallery) figure { margin: 0; }
+        :where(.blogr-gallery) img { display: block; width: 100%; height: auto; aspect-ratio: 1; object-fit: cover; }
+        :where(.blogr-lightbox) { position: fixed; inset: 0; z-index: 1000; display: flex; flex-direction: column; align-items: center; justify-content: center; gap: 0.75rem; background: rgba(0, 0, 0, 0.9); color: #fff; }
+        :where(.blogr-lightbox) img { max-width: 92vw; max-height: 82vh; object-fit: contain; }
+        :where(.blogr-lightbox) button { position: absolute; background: none; border: 0; color: inherit; font-size: 2.5rem; cursor: pointer; padding: 0.5rem 1rem; }
+        :where(.blogr-lightbox-close) { top: 0.5rem; right: 0.5rem; }
+        :where(.blogr-lightbox-prev) { left: 0.5rem; }
+        :where(.blogr-lightbox-next) { right: 0.5rem; }
+        :where(.blogr-lightbox[hidden]) { display: none; }
+    `;
+    document.head.prepend(style);
+
+    class BlogrLightbox {
+        constructor() {
+            this.links = [];
+            this.index = 0;
+
+            this.root = document.createElement('div');
+            this.root.className = 'blogr-lightbox';
+            this.root.hidden = true;
+            this.root.setAttribute('role', 'dialog');
+            this.root.setAttribute('aria-modal', 'true');
+            this.root.innerHTML = `
+                <button class="blogr-lightbox-close" aria-label="Close">×</button>
+                <button class="blogr-lightbox-prev" aria-label="Previous image">‹</button>
+                <img alt="">
+                <p class="blogr-lightbox-caption"></p>
+                <button class="blogr-lightbox-next" aria-label="Next image">›</button>
+            `;
+            this.image = this.root.querySelector('img');
+            this.caption = this.root.querySelector('.blogr-lightbox-caption');
+            document.body.appendChild(this.root);
+
+            this.root.querySelector('.blogr-lightbox-close').addEventListener('click', () => this.close());
+            this.root.querySelector('.blogr-lightbox-prev').addEventListener('click', () => this.show(this.index - 1));
+            this.root.querySelector('.blogr-lightbox-next').addEventListener('click', () => this.show(this.index + 1));
+            this.root.addEventListener('click', (event) => {
+                if (event.target === this.root) {
+                    this.close();
+                }
+            });
+            document.addEventListener('keydown', (event) => {
+                if (this.root.hidden) {
+                    return;
+                }
+                if (event.key === 'Escape') {
+                    this.close();
+                } else if (event.key === 'ArrowLeft') {
+                    this.show(this.index - 1);
+                } else if (event.key === 'ArrowRight') {
+                    this.show(this.index + 1);
+                }
+            });
+        }
+
+        open(links, index) {
+            this.links = links;
+            this.opener = document.activeElement;
+            this.root.hidden = false;
+            this.show(index);
+            this.root.querySelector('.blogr-lightbox-close').focus();
+        }
+
+        show(index) {
+            const count = this.links.length;
+            this.index = (index + count) % count;
+            const link = this.links[this.index];
+            const thumbnail = link.querySelector('img');
+            this.image.src = link.href;
+            this.image.alt = thumbnail ? thumbnail.alt : '';
+            this.caption.textContent = link.dataset.caption || '';
+        }
+
+        close() {
+            this.root.hidden = true;
+            this.image.removeAttribute('src');
+            if (this.opener) {
+                this.opener.focus();
+            }
+        }
+    }
+
+    function init() {
+        const lightbox = new BlogrLightbox();
+        document.querySelectorAll('[data-blogr-gallery]').forEach((gallery) => {
+            const links = Array.from(gallery.querySelectorAll('a[href]'));
+            links.forEach((link, index) => {
+                link.addEventListener('click', (event) => {
+                    event.preventDefault();
+                    lightbox.open(links, index);
+                });
+            });
+        });
+    }
+
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', init);
+    } else {
+        init();
+    }
+})();
@@ -0,0 +1,90 @@
+/**
+ * Blogr Newsletter - submits the newsletter signup form
+ *
+ * Forms with data-blogr-newsletter="endpoint" post to the newsletter API
+ * server and show its reply in place. Forms with data-blogr-newsletter="email"
+ * open a subscription email to data-subscribe-email in the reader's mail
+ * client. Without JavaScript, both still work: the first as a plain form
+ * post, the second through the mailto link in its <noscript>.
+ */
+
+(function () {
+    if (window.BlogrNewsletter) {
+        return;
+    }
+    window.BlogrNewsletter = true;
+
+    async function submitToEndpoint(form) {
+        const status = form.querySelector('.newsletter-status');
+        const button = form.querySelector('.newsletter-submit-btn');
+        button.disabled = true;
+
+        try {
+            const response = await fetch(form.action, {
+                method: 'POST',
+                headers: { 'Accept': 'application/json' },
+                body: new URLSearchParams(new FormData(form)),
+            });
+            const result = await response.json();
+            status.textContent = result.success ? result.data : result.error;
+            if (result.success) {
+                form.reset();
+            }
+        } catch (error) {
+            status.textContent = 'Could not reach the newsletter server. Please try again later.';
+        } finally {
+            button.disabled = false;
+        }
+    }
+
+    function openSubscriptionEmail(form) {
+        const userEmail = form.querySelector('input[type="email"]').value;
+        if (!userEmail) {
+            alert('Please enter your email address');
+            return;
+        }
+
+        const subject = encodeURIComponent(form.dataset.subject);
+        const body = encodeURIComponent(`Hello,
+
+I would like to subscribe to your newsletter.
+
+My email address is: ${userEmail}
+
+Thank you!`);
+
+        // Try to open the email client
+        window.location.href = `mailto:${form.dataset.subscribeEmail}?subject=${subject}&body=${body}`;
+
+        // Show confirmation message
+        const originalContent = form.innerHTML;
+        form.innerHTML = `
+    <div style="text-align: center; padding: 20px;">
+      <div style="font-size: 24px; margin-bottom: 10px;">✅</div>
+      <h4 style="margin: 0 0 10px 0; color: var(--color-primary, #007acc);">Email Client Opened!</h4>
+      <p style="margin: 0; color: var(--color-text-muted, #666);">Please send the email to complete your subscription.</p>
+      <button type="button" style="margin-top: 15px; padding: 8px 16px; background: var(--color-primary, #007acc); color: white; border: none; border-radius: 6px; cursor: pointer;">Try Again</button>
+    </div>
+  `;
+        const restore = () => {
+            form.innerHTML = originalContent;
+        };
+        form.querySelector('button').addEventListener('click', restore);
+
+        // Reset form after 10 seconds
+        setTimeout(restore, 10000);
+    }
+
+    document.addEventListener('submit', (event) => {
+        const form = event.target.closest('form[data-blogr-newsletter]');
+        if (!form) {
+            return;
+        }
+        event.preventDefault();
+        if (form.dataset.blogrNewsletter === 'endpoint') {
+            submitToEndpoint(form);
+        } else {
+            openSubscriptionEmail(form);
+        }
+    });
+})();
@@ -0,0 +1,80 @@
+/**
+ * Blogr Private - unlocks password-protected posts
+ *
+ * A private post's text is in data-blogr-private, encrypted with AES-GCM
+ * under a key derived from the password with PBKDF2-SHA256 (data-salt and
+ * data-iterations). The password is kept for the browser session, so other
+ * private posts with the same password open without asking again.
+ */
+
+(function () {
+    if (window.BlogrPrivate) {
+        return;
+    }
+    window.BlogrPrivate = true;
+
+    const STORAGE_KEY = 'blogr-private-password';
+
+    function bytes(base64) {
+        return Uint8Array.from(atob(base64), (c) => c.charCodeAt(0));
+    }
+
+    async function decrypt(container, password) {
+        const material = await crypto.subtle.importKey(
+            'raw', new TextEncoder().encode(password), 'PBKDF2', false, ['deriveKey']
+        );
+        const key = await crypto.subtle.deriveKey(
+            {
+                name: 'PBKDF2',
+                salt: bytes(container.dataset.salt),
+                iterations: Number(container.dataset.iterations),
+                hash: 'SHA-256',
+            },
+            material,
+            { name: 'AES-GCM', length: 256 },
+            false,
+            ['decrypt']
+        );
+        const plain = await crypto.subtle.decrypt(
+            { name: 'AES-GCM', iv: bytes(container.dataset.iv) },
+            key,
+            bytes(container.dataset.blogrPrivate)
+        );
+        return new TextDecoder().decode(plain);
+    }
+
+    async function unlock(container, password) {
+        try {
+            container.innerHTML = await decrypt(container, password);
+            container.classList.add('blogr-private-open');
+            sessionStorage.setItem(STORAGE_KEY, password);
+            return true;
+        } catch (error) {
+            return false;
+        }
+    }
+
+    function setUp(container) {
+        const form = container.querySelector('.blogr-private-form');
+        const error = container.querySelector('.blogr-private-error');
+        form.addEventListener('submit', async (event) => {
+            event.preventDefault();
+            error.hidden = await unlock(container, form.elements.password.value);
+        });
+
+        const remembered = sessionStorage.getItem(STORAGE_KEY);
+        if (remembered) {
+            unlock(container, remembered);
+        }
+    }
+
+    function init() {
+        document.querySelectorAll('[data-blogr-private]').forEach(setUp);
+    }
+
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', init);
+    } else {
+        init();
+    }
+})();
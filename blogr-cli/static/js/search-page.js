@@ -0,0 +1,277 @@
+/**
+ * Blogr Search Page - full results page at /search/
+ *
+ * Drives the elements rendered by the search page template:
+ *   [data-blogr-search-page]  page root
+ *   #search-page-input        query input (reads/writes ?q=)
+ *   #search-page-results      result list
+ *   #search-page-recent       recent queries (stored in localStorage)
+ *   #search-page-status       result count / status messages
+ *
+ * Uses BlogrSearch from search.js for index loading and term processing, so
+ * stop words, stemming and boosts match the header search.
+ */
+
+class BlogrSearchPage {
+    constructor(root) {
+        this.root = root;
+        this.input = root.querySelector('#search-page-input');
+        this.results = root.querySelector('#search-page-results');
+        this.recent = root.querySelector('#search-page-recent');
+        this.status = root.querySelector('#search-page-status');
+        this.engine = new window.BlogrSearch({
+            autoAttach: false,
+            // Chunks of a split index arrive after the first results
+            onIndexUpdate: () => {
+                if (this.input.value.trim()) {
+                    this.search(this.input.value);
+                }
+            },
+        });
+        this.activeIndex = -1;
+        this.debounceTimer = null;
+        this.storageKey = 'blogr-recent-searches';
+        this.maxRecent = 8;
+
+        this.setupEventListeners();
+
+        const initial = new URLSearchParams(window.location.search).get('q') || '';
+        this.input.value = initial;
+        if (initial.trim()) {
+            this.search(initial);
+        } else {
+            this.renderRecent();
+        }
+    }
+
+    setupEventListeners() {
+        this.input.addEventListener('input', () => {
+            clearTimeout(this.debounceTimer);
+            this.debounceTimer = setTimeout(() => this.search(this.input.value), 200);
+        });
+
+        const form = this.input.closest('form');
+        if (form) {
+            form.addEventListener('submit', (e) => {
+                e.preventDefault();
+                this.search(this.input.value);
+                this.rememberQuery(this.input.value);
+            });
+        }
+
+        document.addEventListener('keydown', (e) => {
+            const typing = document.activeElement === this.input;
+            if (e.key === '/' && !typing) {
+                e.preventDefault();
+                this.input.focus();
+                this.input.select();
+            } else if (e.key === 'ArrowDown') {
+                e.preventDefault();
+                this.moveSelection(1);
+            } else if (e.key === 'ArrowUp') {
+                e.preventDefault();
+                this.moveSelection(-1);
+            } else if (e.key === 'Enter' && this.activeIndex >= 0) {
+                const link = this.activeLink();
+                if (link) {
+                    e.preventDefault();
+                    this.rememberQuery(this.input.value);
+                    window.location.href = link.getAttribute('href');
+                }
+            } else if (e.key === 'Escape') {
+                this.input.value = '';
+                this.search('');
+                this.input.focus();
+            }
+        });
+
+        this.results.addEventListener('click', (e) => {
+            if (e.target.closest('a')) {
+                this.rememberQuery(this.input.value);
+            }
+        });
+
+        if (this.recent) {
+            this.recent.addEventListener('click', (e) => {
+                const clear = e.target.closest('[data-clear-recent]');
+                if (clear) {
+                    e.preventDefault();
+                    localStorage.removeItem(this.storageKey);
+                    this.renderRecent();
+                    return;
+                }
+                const item = e.target.closest('[data-query]');
+                if (item) {
+                    e.preventDefault();
+                    this.input.value = item.dataset.query;
+                    this.search(item.dataset.query);
+                }
+            });
+        }
+    }
+
+    async search(query) {
+        const trimmed = query.trim();
+        this.updateUrl(trimmed);
+        this.activeIndex = -1;
+
+        if (trimmed.length < this.engine.options.minQueryLength) {
+            this.results.innerHTML = '';
+            this.setStatus('');
+            this.renderRecent();
+            return;
+        }
+
+        if (this.recent) {
+            this.recent.hidden = true;
+        }
+
+        try {
+            if (!this.engine.isInitialized) {
+                this.setStatus('Loading search index…');
+                await this.engine.loadSearchIndex();
+            }
+        } catch (error) {
+            console.error('Failed to load search index:', error);
+            this.setStatus('Search is unavailable right now.');
+            return;
+        }
+
+        const results = this.engine.miniSearch.search(trimmed);
+        if (results.length === 0) {
+            this.results.innerHTML = '';
+            this.setStatus(`No results for “${trimmed}”`);
+            return;
+        }
+
+        this.setStatus(`${results.length} result${results.length === 1 ? '' : 's'} for “${trimmed}”`);
+        this.results.innerHTML = results.map((result) => this.renderResult(result, trimmed)).join('');
+    }
+
+    renderResult(result, query) {
+        const terms = this.highlightTerms(result, query);
+        const href = this.engine.joinUrl(this.engine.baseHref, result.url);
+        const date = result.date ? `<time class="search-page-date">${this.engine.escapeHtml(result.date)}</time>` : '';
+        const tags = (result.tags || [])
+            .map((tag) => `<span class="search-page-tag">${this.highlight(tag, terms)}</span>`)
+            .join(' ');
+        const summary = result.excerpt || result.description || '';
+
+        return `
+            <li class="search-page-result">
+                <a class="search-page-link" href="${this.engine.escapeHtml(href)}">${this.highlight(result.title, terms)}</a>
+                <div class="search-page-meta">${date} ${tags}</div>
+                <p class="search-page-excerpt">${this.highlight(summary, terms)}</p>
+            </li>`;
+    }
+
+    highlightTerms(result, query) {
+        // Matched index terms may be stemmed, so also highlight the raw query words
+        const words = query.toLowerCase().split(/\s+/).filter(Boolean);
+        const matched = (result.terms || []).map((t) => t.toLowerCase());
+        return [...new Set([...words, ...matched])]
+            .filter((t) => t.length > 1)
+            .sort((a, b) => b.length - a.length);
+    }
+
+    highlight(text, terms) {
+        if (terms.length === 0) {
+            return this.engine.escapeHtml(text || '');
+        }
+        // Match the raw text and escape each piece, so terms like "amp" can't
+        // match inside the entities escaping adds
+        const pattern = terms.map((t) => this.engine.escapeRegex(t)).join('|');
+        return (text || '')
+            .split(new RegExp(`(${pattern})`, 'gi'))
+            .map((part, i) => {
+                const escaped = this.engine.escapeHtml(part);
+                return i % 2 === 1 ? `<mark class="search-highlight">${escaped}</mark>` : escaped;
+            })
+            .join('');
+    }
+
+    moveSelection(delta) {
+        const items = Array.from(this.results.querySelectorAll('.search-page-result'));
+        if (items.length === 0) return;
+
+        items.forEach((item) => item.classList.remove('is-active'));
+        this.activeIndex = (this.activeIndex + delta + items.length) % items.length;
+        const active = items[this.activeIndex];
+        active.classList.add('is-active');
+        active.scrollIntoView({ block: 'nearest' });
+    }
+
+    activeLink() {
+        const items = this.results.querySelectorAll('.search-page-result');
+        const item = items[this.activeIndex];
+        return item ? item.querySelector('a.search-page-link') : null;
+    }
+
+    setStatus(message) {
+        if (this.status) {
+            this.status.textContent = message;
+        }
+    }
+
+    updateUrl(query) {
+        const url = new URL(window.location.href);
+        if (query) {
+            url.searchParams.set('q', query);
+        } else {
+            url.searchParams.delete('q');
+        }
+        window.history.replaceState(null, '', url);
+    }
+
+    recentQueries() {
+        try {
+            return JSON.parse(localStorage.getItem(this.storageKey)) || [];
+        } catch (error) {
+            return [];
+        }
+    }
+
+    rememberQuery(query) {
+        const trimmed = query.trim();
+        if (trimmed.length < this.engine.options.minQueryLength) return;
+
+        const queries = [trimmed, ...this.recentQueries().filter((q) => q !== trimmed)];
+        try {
+            localStorage.setItem(this.storageKey, JSON.stringify(queries.slice(0, this.maxRecent)));
+        } catch (error) {
+            // Storage may be unavailable (private mode); recent queries are optional
+        }
+    }
+
+    renderRecent() {
+        if (!this.recent) return;
+
+        const queries = this.recentQueries();
+        if (queries.length === 0) {
+            this.recent.hidden = true;
+            this.recent.innerHTML = '';
+            return;
+        }
+
+        const items = queries
+            .map((q) => `<li><a href="?q=${encodeURIComponent(q)}" data-query="${this.engine.escapeHtml(q)}">${this.engine.escapeHtml(q)}</a></li>`)
+            .join('');
+        this.recent.innerHTML = `
+            <h2 class="search-page-recent-title">Recent searches</h2>
+            <ul class="search-page-recent-list">${items}</ul>
+            <button type="button" class="search-page-recent-clear" data-clear-recent>Clear</button>`;
+        this.recent.hidden = false;
+    }
+}
+
+document.addEventListener('DOMContentLoaded', function() {
+    const root = document.querySelector('[data-blogr-search-page]');
+    if (!root) return;
+
+    if (typeof window.BlogrSearch === 'undefined') {
+        console.error('search.js must be loaded before search-page.js');
+        return;
+    }
+
+    new BlogrSearchPage(root);
+});
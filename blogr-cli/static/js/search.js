@@ -5,6 +5,17 @@
  * It loads the search index and provides real-time search functionality.
  */
 
+// Common inflectional suffixes per language, longest first
+const STEM_SUFFIXES = {
+    en: ['ational', 'ization', 'fulness', 'iveness', 'ments', 'ment', 'ingly', 'ings', 'ing', 'edly', 'ers', 'er', 'ed', 'ly', 'es', 's'],
+    de: ['ungen', 'heit', 'keit', 'ung', 'isch', 'ern', 'em', 'en', 'er', 'es', 'e', 's'],
+    fr: ['issements', 'issement', 'ements', 'ement', 'ations', 'ation', 'euses', 'euse', 'eux', 'ées', 'és', 'ée', 'es', 'er', 'é', 'e', 's'],
+    es: ['aciones', 'amientos', 'amiento', 'ación', 'mente', 'idades', 'idad', 'ando', 'iendo', 'ados', 'adas', 'ado', 'ada', 'es', 'os', 'as', 'o', 'a', 's'],
+    it: ['azioni', 'azione', 'mente', 'ità', 'ando', 'endo', 'ati', 'ate', 'ato', 'ata', 'i', 'e', 'o', 'a'],
+    pt: ['ações', 'ação', 'mente', 'idades', 'idade', 'ando', 'endo', 'ados', 'adas', 'ado', 'ada', 'os', 'as', 'es', 'o', 'a', 's'],
+    nl: ['heden', 'heid', 'ingen', 'ing', 'lijk', 'en', 'er', 'e', 's'],
+};
+
 class BlogrSearch {
     constructor(options = {}) {
         this.options = {
@@ -16,6 +27,12 @@ class BlogrSearch {
             minQueryLength: 2,
             debounceMs: 300,
             lazyLoad: true,
+            autoAttach: true,
+            language: 'en',
+            stemming: false,
+            stopWords: [],
+            prefix: true,
+            onIndexUpdate: null,
             miniSearchUrl: 'js/vendor/minisearch.min.js',
             ...options
         };
@@ -28,12 +45,17 @@ class BlogrSearch {
         this.baseHref = '/';
         this.currentResults = [];
         this.currentLimit = this.options.maxResults;
+        this.stopWords = new Set(this.options.stopWords);
         
         this.init();
     }
     
     async init() {
         try {
+            if (!this.options.autoAttach) {
+                // Used by the search page, which drives the index itself
+                return;
+            }
             if (this.options.lazyLoad) {
                 // For lazy loading, just set up event listeners
                 // The search index will be loaded on first search
@@ -65,21 +87,20 @@ class BlogrSearch {
             
             const baseEl = document.querySelector('meta[name="blogr-base"]');
             this.baseHref = baseEl ? baseEl.getAttribute('content') || '/' : '/';
-            const indexPath = this.joinUrl(this.baseHref, this.options.indexUrl);
-            const response = await fetch(indexPath);
-            if (!response.ok) {
-                throw new Error(`Failed to load search index: ${response.status}`);
-            }
-            
-            this.indexData = await response.json();
+            const index = await this.fetchIndexFile(this.options.indexUrl);
+            // A split index lists its chunks; search starts with the first
+            const chunks = Array.isArray(index) ? [] : index.chunks;
+            this.indexData = chunks.length > 0 ? await this.fetchIndexFile(chunks[0]) : index;
             
             // Initialize MiniSearch with the loaded data
             this.miniSearch = new MiniSearch({
                 fields: ['title', 'tags', 'content'],
                 storeFields: ['title', 'url', 'date', 'tags', 'excerpt', 'description'],
+                tokenize: (text) => this.tokenize(text),
+                processTerm: (term) => this.processTerm(term),
                 searchOptions: {
                     boost: { title: 5, tags: 3, content: 1 },
-                    prefix: true,
+                    prefix: this.options.prefix,
                     fuzzy: 0.2
                 }
             });
@@ -87,6 +108,9 @@ class BlogrSearch {
             this.miniSearch.addAll(this.indexData);
             this.isInitialized = true;
             console.log(`📚 Loaded ${this.indexData.length} documents for search`);
+            if (chunks.length > 1) {
+                this.loadChunks(chunks.slice(1));
+            }
         } catch (error) {
             console.error('Error loading search index:', error);
             throw error;
@@ -94,6 +118,37 @@ class BlogrSearch {
             this.isLoading = false;
         }
     }
+
+    async fetchIndexFile(path) {
+        const response = await fetch(this.joinUrl(this.baseHref, path));
+        if (!response.ok) {
+            throw new Error(`Failed to load search index: ${response.status}`);
+        }
+        return response.json();
+    }
+
+    async loadChunks(chunks) {
+        // Add the rest of a split index in the background, refreshing results as it grows
+        for (const chunk of chunks) {
+            try {
+                const documents = await this.fetchIndexFile(chunk);
+                this.miniSearch.addAll(documents);
+                this.indexData.push(...documents);
+            } catch (error) {
+                console.error(`Failed to load search index chunk ${chunk}:`, error);
+                return;
+            }
+            if (this.options.onIndexUpdate) {
+                this.options.onIndexUpdate();
+            } else if (this.options.autoAttach) {
+                const searchInput = document.querySelector(this.options.searchInput);
+                if (searchInput && searchInput.value.trim()) {
+                    this.performSearch(searchInput.value);
+                }
+            }
+        }
+        console.log(`📚 Loaded ${this.indexData.length} documents for search`);
+    }
     
     async loadMiniSearch() {
         return new Promise((resolve, reject) => {
@@ -278,13 +333,13 @@ class BlogrSearch {
         
         try {
             const results = this.miniSearch.search(trimmedQuery, {
-                prefix: true,
+                prefix: this.options.prefix,
                 fuzzy: 0.2,
                 boost: { title: 5, tags: 3, content: 1 }
             }).slice(0, this.options.maxResults);
             // Keep full set for pagination; we will manage limits during render
             this.currentResults = this.miniSearch.search(trimmedQuery, {
-                prefix: true,
+                prefix: this.options.prefix,
                 fuzzy: 0.2,
                 boost: { title: 5, tags: 3, content: 1 }
             });
@@ -356,6 +411,54 @@ class BlogrSearch {
         items[next].scrollIntoView({ block: 'nearest' });
     }
 
+    tokenize(text) {
+        const words = String(text).split(/[\n\r\p{Z}\p{P}]+/u);
+        if (!['zh', 'ja', 'ko'].includes(this.options.language)) {
+            return words;
+        }
+
+        // CJK text has no spaces between words, so index overlapping character pairs
+        const tokens = [];
+        for (const word of words) {
+            const chars = Array.from(word);
+            if (chars.length < 2 || !/[\u3040-\u30ff\u3400-\u9fff\uac00-\ud7af]/.test(word)) {
+                tokens.push(word);
+                continue;
+            }
+            for (let i = 0; i + 1 < chars.length; i++) {
+                tokens.push(chars[i] + chars[i + 1]);
+            }
+        }
+        return tokens;
+    }
+
+    processTerm(term) {
+        const word = term.toLowerCase();
+        if (!word || this.stopWords.has(word)) {
+            return null;
+        }
+        return this.options.stemming ? this.stem(word) : word;
+    }
+
+    stem(word) {
+        // Light suffix stripping; applied to both indexed and query terms so they line up
+        const suffixes = STEM_SUFFIXES[this.options.language];
+        if (!suffixes) {
+            return word;
+        }
+        let stem = word;
+        for (const suffix of suffixes) {
+            if (stem.endsWith(suffix) && stem.length - suffix.length >= 3) {
+                stem = stem.slice(0, -suffix.length);
+                break;
+            }
+        }
+        if (this.options.language === 'en' && stem.endsWith('y')) {
+            stem = stem.slice(0, -1) + 'i';
+        }
+        return stem;
+    }
+
     joinUrl(base, path) {
         // Return absolute URLs as-is
         if (/^https?:\/\//i.test(path)) return path;
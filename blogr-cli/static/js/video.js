@@ -0,0 +1,99 @@
+/**
+ * Blogr Video - click-to-load players for YouTube and Vimeo embeds
+ *
+ * With [markdown.video] click_to_load, embeds are placeholders carrying the
+ * player URL in data-blogr-video. Nothing is requested from the video host
+ * until the reader presses the play button, which swaps in the player.
+ *
+ * [privacy.embeds] also turns tweets into data-blogr-tweet placeholders,
+ * whose button loads Twitter's script for that tweet only.
+ */
+
+(function () {
+    if (window.BlogrVideo) {
+        return;
+    }
+    window.BlogrVideo = true;
+
+    // :where() keeps these rules easy for theme stylesheets to override
+    const style = document.createElement('style');
+    style.textContent = `
+        :where([data-blogr-video]) { display: flex; flex-direction: column; align-items: center; justify-content: center; gap: 0.5rem; margin: 1.5rem 0; padding: 1rem; background: #111; color: #eee; text-align: center; }
+        :where([data-blogr-video]) button { font: inherit; font-size: 1.1rem; padding: 0.6rem 1.2rem; border: 0; border-radius: 999px; background: #fff; color: #111; cursor: pointer; }
+        :where([data-blogr-video]) p { margin: 0; font-size: 0.85rem; opacity: 0.8; }
+        :where([data-blogr-video]) a { color: inherit; }
+        :where(.blogr-video-loaded) iframe { position: absolute; inset: 0; width: 100%; height: 100%; border: 0; }
+        :where([data-blogr-tweet]) { margin: 1.5rem 0; }
+        :where([data-blogr-tweet]) button { font: inherit; padding: 0.4rem 1rem; cursor: pointer; }
+        :where([data-blogr-tweet]) p { margin: 0.5rem 0 0; font-size: 0.85rem; opacity: 0.8; }
+    `;
+    document.head.appendChild(style);
+
+    function load(container) {
+        const iframe = document.createElement('iframe');
+        const src = new URL(container.dataset.blogrVideo);
+        src.searchParams.set('autoplay', '1');
+        iframe.src = src.toString();
+        iframe.title = container.dataset.title || 'Video';
+        iframe.allow = 'autoplay; encrypted-media; fullscreen; picture-in-picture';
+        iframe.referrerPolicy = 'strict-origin-when-cross-origin';
+        iframe.allowFullscreen = true;
+
+        container.replaceChildren(iframe);
+        container.removeAttribute('data-blogr-video');
+        container.classList.add('blogr-video-loaded');
+        container.style.padding = '0';
+    }
+
+    let twitter = null;
+
+    function loadTweet(container) {
+        const quote = container.querySelector('blockquote');
+        quote.classList.replace('blogr-twitter-tweet', 'twitter-tweet');
+        container.querySelectorAll(':scope > button, :scope > p').forEach(function (element) {
+            element.remove();
+        });
+        container.removeAttribute('data-blogr-tweet');
+
+        // The script renders every twitter-tweet on the page when it first
+        // loads, and later ones on request
+        if (twitter) {
+            twitter.then(function () {
+                window.twttr.widgets.load(container);
+            });
+            return;
+        }
+        twitter = new Promise(function (resolve) {
+            const script = document.createElement('script');
+            script.src = 'https://platform.twitter.com/widgets.js';
+            script.async = true;
+            script.onload = resolve;
+            document.head.appendChild(script);
+        });
+    }
+
+    function init() {
+        document.querySelectorAll('[data-blogr-tweet]').forEach(function (container) {
+            const button = container.querySelector('button');
+            if (button) {
+                button.addEventListener('click', function () {
+                    loadTweet(container);
+                });
+            }
+        });
+        document.querySelectorAll('[data-blogr-video]').forEach(function (container) {
+            const button = container.querySelector('button');
+            if (button) {
+                button.addEventListener('click', function () {
+                    load(container);
+                });
+            }
+        });
+    }
+
+    if (document.readyState === 'loading') {
+        document.addEventListener('DOMContentLoaded', init);
+    } else {
+        init();
+    }
+})();
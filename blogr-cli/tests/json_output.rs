@@ -0,0 +1,70 @@
+//! `--json` prints exactly one JSON document on stdout, with status output
+//! kept off it
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn blogr(dir: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_blogr"))
+        .args(args)
+        .current_dir(dir)
+        .env_remove("GITHUB_TOKEN")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Parse stdout as a single JSON document
+fn report(output: &Output) -> serde_json::Value {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    let mut documents = serde_json::Deserializer::from_str(&stdout).into_iter();
+    let document = documents.next().unwrap().unwrap();
+    assert!(
+        documents.next().is_none(),
+        "more than one document: {stdout}"
+    );
+    document
+}
+
+#[test]
+fn test_json_reports() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let init = blogr(
+        temp_dir.path(),
+        &["init", "myblog", "--no-github"],
+        "Ann\nA test blog\n",
+    );
+    assert!(
+        init.status.success(),
+        "{}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+    let project = temp_dir.path().join("myblog");
+
+    let list = report(&blogr(&project, &["--json", "list"], ""));
+    assert_eq!(list["command"], "list");
+    assert_eq!(list["status"], "ok");
+    assert!(list["warnings"].is_array());
+    assert!(list["duration_ms"].is_u64());
+    assert!(!list["posts"].as_array().unwrap().is_empty());
+
+    let stats = report(&blogr(&project, &["--json", "project", "stats"], ""));
+    assert_eq!(stats["command"], "project stats");
+    assert_eq!(stats["status"], "ok");
+    assert!(stats["files"].is_object());
+}
@@ -0,0 +1,125 @@
+use crate::{ConfigOption, SiteType, Theme, ThemeInfo, ThemeTemplates};
+use ratatui::style::{Color, Style};
+use std::collections::HashMap;
+
+/// A documentation theme: a sidebar built from `[[docs.nav]]`, previous and
+/// next links, a version selector and search in the top bar
+pub struct HandbookTheme;
+
+impl HandbookTheme {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Theme for HandbookTheme {
+    fn info(&self) -> ThemeInfo {
+        let mut config_schema = HashMap::new();
+
+        config_schema.insert(
+            "accent_color".to_string(),
+            ConfigOption {
+                value: toml::Value::String("#2563eb".to_string()),
+                description: "Color of links and the current page in the sidebar".to_string(),
+            },
+        );
+
+        config_schema.insert(
+            "font_family".to_string(),
+            ConfigOption {
+                value: toml::Value::String(
+                    "system-ui, -apple-system, \"Segoe UI\", sans-serif".to_string(),
+                ),
+                description: "Font family for body text".to_string(),
+            },
+        );
+
+        config_schema.insert(
+            "sidebar_width".to_string(),
+            ConfigOption {
+                value: toml::Value::String("17rem".to_string()),
+                description: "Width of the navigation sidebar".to_string(),
+            },
+        );
+
+        config_schema.insert(
+            "footer_text".to_string(),
+            ConfigOption {
+                value: toml::Value::String(String::new()),
+                description: "Extra text in the footer, e.g. a license".to_string(),
+            },
+        );
+
+        ThemeInfo {
+            name: "Handbook".to_string(),
+            version: "1.0.0".to_string(),
+            author: "Blogr Team".to_string(),
+            description: "A documentation theme with sidebar navigation, previous/next links, a version selector and built-in search.".to_string(),
+            config_schema,
+            site_type: SiteType::Personal,
+        }
+    }
+
+    fn templates(&self) -> ThemeTemplates {
+        ThemeTemplates::new("base.html", include_str!("templates/base.html"))
+            .with_template("pager.html", include_str!("templates/pager.html"))
+            .with_template("index.html", include_str!("templates/index.html"))
+            .with_template("page.html", include_str!("templates/page.html"))
+    }
+
+    fn assets(&self) -> HashMap<String, Vec<u8>> {
+        let mut assets = HashMap::new();
+
+        assets.insert(
+            "css/style.css".to_string(),
+            include_bytes!("assets/style.css").to_vec(),
+        );
+
+        assets
+    }
+
+    fn preview_tui_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Rgb(31, 35, 40))
+            .bg(Color::Rgb(246, 247, 249))
+    }
+}
+
+impl HandbookTheme {
+    /// Get example content.md for this theme: the docs' home page
+    pub fn example_content(author: &str) -> String {
+        format!(
+            r##"---
+title: "Introduction"
+description: "Documentation for the project"
+author: "{}"
+---
+
+# Welcome
+
+This is the home page of your documentation. Write each page as a Markdown
+file in `content/`, e.g. `content/installation.md`, and list them in
+`blogr.toml` to build the sidebar:
+
+```toml
+[[docs.nav]]
+page = "installation"
+
+[[docs.nav]]
+title = "Guides"
+children = [{{ page = "configuration" }}, {{ page = "deployment" }}]
+```
+
+Without `[[docs.nav]]`, the sidebar lists the pages in their `order`.
+"##,
+            author
+        )
+    }
+}
+
+impl Default for HandbookTheme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
 pub mod brutja;
 pub mod dark_minimal;
+pub mod handbook;
+pub mod linklog;
+pub mod magazine;
 pub mod minimal_retro;
 pub mod musashi;
 pub mod obsidian;
@@ -13,6 +18,9 @@ pub mod typewriter;
 
 pub use brutja::BrutjaTheme;
 pub use dark_minimal::DarkMinimalTheme;
+pub use handbook::HandbookTheme;
+pub use linklog::LinklogTheme;
+pub use magazine::MagazineTheme;
 pub use minimal_retro::MinimalRetroTheme;
 pub use musashi::MusashiTheme;
 pub use obsidian::ObsidianTheme;
@@ -24,6 +32,8 @@ pub use typewriter::TypewriterTheme;
 pub enum SiteType {
     Blog,
     Personal,
+    /// A personal landing page with the blog under `/blog/`
+    Hybrid,
 }
 
 impl fmt::Display for SiteType {
@@ -31,6 +41,22 @@ impl fmt::Display for SiteType {
         match self {
             SiteType::Blog => write!(f, "blog"),
             SiteType::Personal => write!(f, "personal"),
+            SiteType::Hybrid => write!(f, "hybrid"),
+        }
+    }
+}
+
+impl FromStr for SiteType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blog" => Ok(SiteType::Blog),
+            "personal" => Ok(SiteType::Personal),
+            "hybrid" => Ok(SiteType::Hybrid),
+            other => Err(format!(
+                "Unknown site type '{other}'. Expected 'blog', 'personal' or 'hybrid'."
+            )),
         }
     }
 }
@@ -50,6 +76,13 @@ impl ThemeInfo {
     pub fn as_data_row(&self) -> [&String; 4] {
         [&self.name, &self.version, &self.author, &self.description]
     }
+
+    /// Whether the theme can build a site of the given type. Hybrid themes are
+    /// blog themes with an extra `home.html` landing page, so they build blogs too.
+    pub fn supports(&self, site_type: &SiteType) -> bool {
+        self.site_type == *site_type
+            || (self.site_type == SiteType::Hybrid && *site_type == SiteType::Blog)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,25 +99,36 @@ pub trait Theme: Send + Sync {
 }
 
 pub struct ThemeTemplates {
-    templates: Vec<(&'static str, &'static str)>,
+    templates: Vec<(Cow<'static, str>, Cow<'static, str>)>,
 }
 
 impl ThemeTemplates {
     // Base template must be first. This ensure it's registered first with Tera when we iterate through the templates.
     pub fn new(base_template_name: &'static str, base_template: &'static str) -> Self {
         Self {
-            templates: vec![(base_template_name, base_template)],
+            templates: vec![(base_template_name.into(), base_template.into())],
         }
     }
 
     pub fn with_template(mut self, name: &'static str, template: &'static str) -> Self {
-        self.templates.push((name, template));
+        self.templates.push((name.into(), template.into()));
         self
     }
+
+    /// Templates read at runtime, e.g. from an installed theme package.
+    /// As with `new`, the base template must come first.
+    pub fn from_owned(templates: Vec<(String, String)>) -> Self {
+        Self {
+            templates: templates
+                .into_iter()
+                .map(|(name, template)| (name.into(), template.into()))
+                .collect(),
+        }
+    }
 }
 
 impl IntoIterator for ThemeTemplates {
-    type Item = (&'static str, &'static str);
+    type Item = (Cow<'static, str>, Cow<'static, str>);
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -103,14 +147,23 @@ pub fn get_all_themes() -> Vec<Box<dyn Theme>> {
         Box::new(SlatePortfolioTheme::new()),
         Box::new(TypewriterTheme::new()),
         Box::new(BrutjaTheme::new()),
+        Box::new(MagazineTheme::new()),
+        Box::new(LinklogTheme::new()),
+        Box::new(HandbookTheme::new()),
     ]
 }
 
+/// Look up a theme by display name ("Minimal Retro") or slug ("minimal-retro")
 #[must_use]
 pub fn get_theme(name: &str) -> Option<Box<dyn Theme>> {
+    let wanted = normalize_theme_name(name);
     get_all_themes()
         .into_iter()
-        .find(|theme| theme.info().name.to_lowercase() == name.to_lowercase())
+        .find(|theme| normalize_theme_name(&theme.info().name) == wanted)
+}
+
+fn normalize_theme_name(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '_'], "-")
 }
 
 #[must_use]
@@ -122,7 +175,7 @@ pub fn get_theme_by_name(name: &str) -> Option<Box<dyn Theme>> {
 mod test {
     use std::collections::{HashMap, HashSet};
 
-    use crate::get_all_themes;
+    use crate::{get_all_themes, get_theme, SiteType};
 
     #[test]
     fn themes_have_unique_names() {
@@ -158,4 +211,24 @@ mod test {
             panic!("Test working incorrectly. Unreachable statement reached.");
         }
     }
+
+    #[test]
+    fn themes_can_be_found_by_slug() {
+        assert!(get_theme("minimal-retro").is_some());
+        assert!(get_theme("Minimal Retro").is_some());
+        assert!(get_theme("dark_minimal").is_some());
+        assert!(get_theme("no-such-theme").is_none());
+    }
+
+    #[test]
+    fn hybrid_themes_also_build_blogs() {
+        let theme = get_theme("minimal-retro").unwrap().info();
+        assert!(theme.supports(&SiteType::Hybrid));
+        assert!(theme.supports(&SiteType::Blog));
+        assert!(!theme.supports(&SiteType::Personal));
+
+        let personal = get_theme("dark-minimal").unwrap().info();
+        assert!(!personal.supports(&SiteType::Hybrid));
+        assert_eq!("hybrid".parse::<SiteType>(), Ok(SiteType::Hybrid));
+    }
 }
@@ -0,0 +1,80 @@
+use crate::{ConfigOption, SiteType, Theme, ThemeInfo, ThemeTemplates};
+use ratatui::style::{Color, Style};
+use std::collections::HashMap;
+
+/// A link blog and microblog theme: posts are shown in full on the home
+/// page, titles are optional, and posts with a `link` point at the page they
+/// are about
+pub struct LinklogTheme;
+
+impl LinklogTheme {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Theme for LinklogTheme {
+    fn info(&self) -> ThemeInfo {
+        let mut config_schema = HashMap::new();
+
+        config_schema.insert(
+            "accent_color".to_string(),
+            ConfigOption {
+                value: toml::Value::String("#c2410c".to_string()),
+                description: "Color of links".to_string(),
+            },
+        );
+
+        config_schema.insert(
+            "external_marker".to_string(),
+            ConfigOption {
+                value: toml::Value::String("↗".to_string()),
+                description: "Marker after links to other sites".to_string(),
+            },
+        );
+
+        ThemeInfo {
+            name: "Linklog".to_string(),
+            version: "1.0.0".to_string(),
+            author: "Blogr Team".to_string(),
+            description: "A link blog and microblog theme: full posts on the home page, optional titles and link posts.".to_string(),
+            config_schema,
+            site_type: SiteType::Blog,
+        }
+    }
+
+    fn templates(&self) -> ThemeTemplates {
+        ThemeTemplates::new("base.html", include_str!("templates/base.html"))
+            .with_template("entry.html", include_str!("templates/entry.html"))
+            .with_template("index.html", include_str!("templates/index.html"))
+            .with_template("post.html", include_str!("templates/post.html"))
+            .with_template("archive.html", include_str!("templates/archive.html"))
+            .with_template("tag.html", include_str!("templates/tag.html"))
+            .with_template("tags.html", include_str!("templates/tags.html"))
+            .with_template("404.html", include_str!("templates/404.html"))
+    }
+
+    fn assets(&self) -> HashMap<String, Vec<u8>> {
+        let mut assets = HashMap::new();
+
+        assets.insert(
+            "css/style.css".to_string(),
+            include_bytes!("assets/style.css").to_vec(),
+        );
+
+        assets
+    }
+
+    fn preview_tui_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Rgb(34, 34, 34))
+            .bg(Color::Rgb(255, 253, 249))
+    }
+}
+
+impl Default for LinklogTheme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
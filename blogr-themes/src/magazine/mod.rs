@@ -0,0 +1,97 @@
+use crate::{ConfigOption, SiteType, Theme, ThemeInfo, ThemeTemplates};
+use ratatui::style::{Color, Style};
+use std::collections::HashMap;
+
+/// A photo-led blog theme: a featured-post hero, card grids built from the
+/// `cover` images in post frontmatter, and section ribbons from post tags
+pub struct MagazineTheme;
+
+impl MagazineTheme {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Theme for MagazineTheme {
+    fn info(&self) -> ThemeInfo {
+        let mut config_schema = HashMap::new();
+
+        config_schema.insert(
+            "accent_color".to_string(),
+            ConfigOption {
+                value: toml::Value::String("#e4572e".to_string()),
+                description: "Color of section ribbons, links and covers without an image"
+                    .to_string(),
+            },
+        );
+
+        config_schema.insert(
+            "card_aspect_ratio".to_string(),
+            ConfigOption {
+                value: toml::Value::String("4 / 3".to_string()),
+                description: "Shape of the cover images on cards, as a CSS aspect ratio"
+                    .to_string(),
+            },
+        );
+
+        config_schema.insert(
+            "hero_label".to_string(),
+            ConfigOption {
+                value: toml::Value::String("Featured".to_string()),
+                description: "Label above the featured post on the front page".to_string(),
+            },
+        );
+
+        config_schema.insert(
+            "show_category_bar".to_string(),
+            ConfigOption {
+                value: toml::Value::Boolean(true),
+                description: "Show a bar of section ribbons above the front page".to_string(),
+            },
+        );
+
+        ThemeInfo {
+            name: "Magazine".to_string(),
+            version: "1.0.0".to_string(),
+            author: "Blogr Team".to_string(),
+            description: "A photo-heavy magazine layout with a featured-post hero, card grids and section ribbons.".to_string(),
+            config_schema,
+            site_type: SiteType::Blog,
+        }
+    }
+
+    fn templates(&self) -> ThemeTemplates {
+        ThemeTemplates::new("base.html", include_str!("templates/base.html"))
+            .with_template("card.html", include_str!("templates/card.html"))
+            .with_template("index.html", include_str!("templates/index.html"))
+            .with_template("post.html", include_str!("templates/post.html"))
+            .with_template("archive.html", include_str!("templates/archive.html"))
+            .with_template("tag.html", include_str!("templates/tag.html"))
+            .with_template("tags.html", include_str!("templates/tags.html"))
+            .with_template("404.html", include_str!("templates/404.html"))
+    }
+
+    fn assets(&self) -> HashMap<String, Vec<u8>> {
+        let mut assets = HashMap::new();
+
+        assets.insert(
+            "css/style.css".to_string(),
+            include_bytes!("assets/style.css").to_vec(),
+        );
+
+        assets
+    }
+
+    fn preview_tui_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Rgb(228, 87, 46))
+            .bg(Color::Rgb(250, 248, 245))
+    }
+}
+
+impl Default for MagazineTheme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
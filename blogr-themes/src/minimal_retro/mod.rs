@@ -87,7 +87,7 @@ impl Theme for MinimalRetroTheme {
             author: "Blogr Team".to_string(),
             description: "An artistic, minimal theme focused on content with expandable posts and beautiful typography".to_string(),
             config_schema,
-            site_type: SiteType::Blog,
+            site_type: SiteType::Hybrid,
         }
     }
 
@@ -98,6 +98,8 @@ impl Theme for MinimalRetroTheme {
             .with_template("archive.html", include_str!("templates/archive.html"))
             .with_template("tag.html", include_str!("templates/tag.html"))
             .with_template("tags.html", include_str!("templates/tags.html"))
+            .with_template("404.html", include_str!("templates/404.html"))
+            .with_template("home.html", include_str!("templates/home.html"))
     }
 
     fn assets(&self) -> HashMap<String, Vec<u8>> {
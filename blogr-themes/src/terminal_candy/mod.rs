@@ -106,6 +106,7 @@ impl Theme for TerminalCandyTheme {
             .with_template("archive.html", include_str!("templates/archive.html"))
             .with_template("tag.html", include_str!("templates/tag.html"))
             .with_template("tags.html", include_str!("templates/tags.html"))
+            .with_template("404.html", include_str!("templates/404.html"))
     }
 
     fn assets(&self) -> HashMap<String, Vec<u8>> {